@@ -240,15 +240,9 @@ pub fn emulate(
 
                 let view = emulator.finalize();
                 exit_code_bytes = view
-                    .get_exit_code()
-                    .iter()
-                    .map(|public_output_entry| public_output_entry.value)
-                    .collect();
-                output_bytes = view
-                    .get_public_output()
-                    .iter()
-                    .map(|public_output_entry| public_output_entry.value)
-                    .collect();
+                    .exit_code_u32()
+                    .map_or_else(Vec::new, |code| code.to_le_bytes().to_vec());
+                output_bytes = view.public_output_bytes();
 
                 // Run a second pass with a linear emulator constructed from the harvard emulator.
                 if matches!(emulator_type, EmulatorType::TwoPass) {
@@ -262,15 +256,9 @@ pub fn emulate(
                     // Get output bytes.
                     let view = linear_emulator.finalize();
                     exit_code_bytes = view
-                        .get_exit_code()
-                        .iter()
-                        .map(|public_output_entry| public_output_entry.value)
-                        .collect();
-                    output_bytes = view
-                        .get_public_output()
-                        .iter()
-                        .map(|public_output_entry| public_output_entry.value)
-                        .collect();
+                        .exit_code_u32()
+                        .map_or_else(Vec::new, |code| code.to_le_bytes().to_vec());
+                    output_bytes = view.public_output_bytes();
                 }
                 cycles.push(cur_cycles);
             }
@@ -300,15 +288,9 @@ pub fn emulate(
 
                 let view = emulator.finalize();
                 exit_code_bytes = view
-                    .get_exit_code()
-                    .iter()
-                    .map(|public_output_entry| public_output_entry.value)
-                    .collect();
-                output_bytes = view
-                    .get_public_output()
-                    .iter()
-                    .map(|public_output_entry| public_output_entry.value)
-                    .collect();
+                    .exit_code_u32()
+                    .map_or_else(Vec::new, |code| code.to_le_bytes().to_vec());
+                output_bytes = view.public_output_bytes();
             }
         }
     }
@@ -0,0 +1,178 @@
+//! Constants the emulator and the prover must agree on.
+//!
+//! [`SHARED_CONSTANTS`] is the source of truth. The emulator and the prover each describe the constants they
+//! assume in [`AssumedConstants`] of their own, check them against [`SHARED_CONSTANTS`] with a const assertion, and
+//! again at runtime when an emulator is constructed or a proof starts, so that a constant changed on one side only is
+//! caught before it produces proofs about the wrong execution. Constants a side doesn't depend on are left out.
+
+use crate::constants::{
+    ELF_TEXT_START, MEMORY_TOP, NUM_REGISTERS, PUBLIC_INPUT_ADDRESS_LOCATION,
+    PUBLIC_OUTPUT_ADDRESS_LOCATION, WORD_SIZE,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SharedConstants {
+    pub word_size: usize,
+    pub num_registers: u32,
+    /// Clock of the first executed instruction, clock zero stands for the initial memory.
+    pub first_clk: u32,
+    pub public_input_address_location: u32,
+    pub public_output_address_location: u32,
+    pub elf_text_start: u32,
+    pub memory_top: u32,
+}
+
+pub const SHARED_CONSTANTS: SharedConstants = SharedConstants {
+    word_size: WORD_SIZE,
+    num_registers: NUM_REGISTERS,
+    first_clk: 1,
+    public_input_address_location: PUBLIC_INPUT_ADDRESS_LOCATION,
+    public_output_address_location: PUBLIC_OUTPUT_ADDRESS_LOCATION,
+    elf_text_start: ELF_TEXT_START,
+    memory_top: MEMORY_TOP,
+};
+
+/// The constants assumed by one side, `None` for those it doesn't depend on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AssumedConstants {
+    pub word_size: Option<usize>,
+    pub num_registers: Option<u32>,
+    pub first_clk: Option<u32>,
+    pub public_input_address_location: Option<u32>,
+    pub public_output_address_location: Option<u32>,
+    pub elf_text_start: Option<u32>,
+    pub memory_top: Option<u32>,
+}
+
+impl AssumedConstants {
+    /// Assumes none of the constants.
+    pub const NONE: Self = Self {
+        word_size: None,
+        num_registers: None,
+        first_clk: None,
+        public_input_address_location: None,
+        public_output_address_location: None,
+        elf_text_start: None,
+        memory_top: None,
+    };
+
+    const fn fields(&self) -> [Option<u64>; 7] {
+        const fn widen(value: Option<u32>) -> Option<u64> {
+            match value {
+                Some(value) => Some(value as u64),
+                None => None,
+            }
+        }
+        [
+            match self.word_size {
+                Some(word_size) => Some(word_size as u64),
+                None => None,
+            },
+            widen(self.num_registers),
+            widen(self.first_clk),
+            widen(self.public_input_address_location),
+            widen(self.public_output_address_location),
+            widen(self.elf_text_start),
+            widen(self.memory_top),
+        ]
+    }
+}
+
+/// A constant whose value assumed by `side` differs from [`SHARED_CONSTANTS`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{side} assumes {name} = {assumed:#x}, but it is {shared:#x} in nexus_common")]
+pub struct SharedConstantMismatch {
+    pub side: &'static str,
+    pub name: &'static str,
+    pub shared: u64,
+    pub assumed: u64,
+}
+
+impl SharedConstants {
+    const fn fields(&self) -> [(&'static str, u64); 7] {
+        [
+            ("word_size", self.word_size as u64),
+            ("num_registers", self.num_registers as u64),
+            ("first_clk", self.first_clk as u64),
+            (
+                "public_input_address_location",
+                self.public_input_address_location as u64,
+            ),
+            (
+                "public_output_address_location",
+                self.public_output_address_location as u64,
+            ),
+            ("elf_text_start", self.elf_text_start as u64),
+            ("memory_top", self.memory_top as u64),
+        ]
+    }
+
+    /// Returns the index of the first assumed constant that differs from `self`.
+    const fn mismatch_index(&self, assumed: &AssumedConstants) -> Option<usize> {
+        let shared = self.fields();
+        let assumed = assumed.fields();
+        let mut idx = 0;
+        while idx < shared.len() {
+            if let Some(value) = assumed[idx] {
+                if value != shared[idx].1 {
+                    return Some(idx);
+                }
+            }
+            idx += 1;
+        }
+        None
+    }
+
+    /// Returns the name of the first assumed constant that differs from `self`, usable in const assertions.
+    pub const fn first_mismatch(&self, assumed: &AssumedConstants) -> Option<&'static str> {
+        match self.mismatch_index(assumed) {
+            Some(idx) => Some(self.fields()[idx].0),
+            None => None,
+        }
+    }
+
+    /// Checks that the constants assumed by `side` are the same as in `self`.
+    pub fn check(
+        &self,
+        assumed: &AssumedConstants,
+        side: &'static str,
+    ) -> Result<(), SharedConstantMismatch> {
+        let Some(idx) = self.mismatch_index(assumed) else {
+            return Ok(());
+        };
+        let (name, shared) = self.fields()[idx];
+        Err(SharedConstantMismatch {
+            side,
+            name,
+            shared,
+            assumed: assumed.fields()[idx].expect("only assumed constants mismatch"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_names_mismatch() {
+        assert_eq!(
+            SHARED_CONSTANTS.check(&AssumedConstants::NONE, "test"),
+            Ok(())
+        );
+        let word_size = AssumedConstants {
+            word_size: Some(WORD_SIZE),
+            ..AssumedConstants::NONE
+        };
+        assert_eq!(SHARED_CONSTANTS.check(&word_size, "test"), Ok(()));
+
+        let moved_output = AssumedConstants {
+            public_output_address_location: Some(PUBLIC_OUTPUT_ADDRESS_LOCATION + 4),
+            ..AssumedConstants::NONE
+        };
+        let err = SHARED_CONSTANTS.check(&moved_output, "test").unwrap_err();
+        assert_eq!(err.name, "public_output_address_location");
+        assert_eq!(err.assumed, err.shared + 4);
+        assert!(err.to_string().contains("public_output_address_location"));
+    }
+}
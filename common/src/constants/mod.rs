@@ -11,5 +11,8 @@ pub const ELF_TEXT_START: u32 = PUBLIC_OUTPUT_ADDRESS_LOCATION + WORD_SIZE as u3
 
 pub const PRECOMPILE_SYMBOL_PREFIX: &str = "PRECOMPILE_";
 
+/// Encoding of the canonical NOP, `addi x0, x0, 0`.
+pub const NOP_INSTRUCTION_WORD: u32 = 0x0000_0013;
+
 // TODO: handle built-in custom instructions.
 pub const KECCAKF_OPCODE: u8 = 0x5A;
@@ -0,0 +1,105 @@
+//! Cost model of instructions, shared by the emulator and the prover.
+//!
+//! The global clock of the emulator advances by the cost of every executed instruction, and memory records are
+//! timestamped with it. Costs are measured in clock cycles.
+//!
+//! An instruction taking `k` cycles owns the timestamps `clock..clock + k`, which lets it make up to `k` memory
+//! accesses at distinct sub-timestamps, see [`crate::memory::timestamp_accesses`].
+
+use crate::{
+    constants::WORD_SIZE,
+    ecall::Ecall,
+    riscv::{opcode::BuiltinOpcode, Opcode},
+};
+
+/// Number of clock cycles taken by each opcode.
+pub struct OpcodeCost;
+
+impl OpcodeCost {
+    /// Cost of every instruction that isn't listed in [`Self::cycles`].
+    pub const DEFAULT_CYCLES: u32 = 1;
+
+    /// Builtin opcodes that don't take [`Self::DEFAULT_CYCLES`].
+    const BUILTIN_CYCLES: &'static [(BuiltinOpcode, u32)] = &[];
+
+    /// Syscalls that don't take the cycles of ECALL, one cycle per byte they write to memory.
+    const SYSCALL_CYCLES: &'static [(Ecall, u32)] =
+        &[(Ecall::ReadPrivateInputWord, WORD_SIZE as u32)];
+
+    /// Returns the number of clock cycles taken by an instruction executed with `syscall_number` in `a7`, which
+    /// only matters for ECALL.
+    pub fn instruction_cycles(opcode: &Opcode, syscall_number: u32) -> u32 {
+        match opcode.builtin() {
+            Some(BuiltinOpcode::ECALL) => Self::syscall_cycles(syscall_number),
+            _ => Self::cycles(opcode),
+        }
+    }
+
+    /// Returns the number of clock cycles taken by ECALL with `syscall_number` in `a7`.
+    pub fn syscall_cycles(syscall_number: u32) -> u32 {
+        Self::SYSCALL_CYCLES
+            .iter()
+            .find(|(ecall, _)| *ecall as u32 == syscall_number)
+            .map_or(
+                Self::builtin_cycles(BuiltinOpcode::ECALL),
+                |&(_, cycles)| cycles,
+            )
+    }
+
+    /// Returns the number of clock cycles taken by `opcode` regardless of the syscall number, always at least one.
+    /// Use [`Self::instruction_cycles`] for executed instructions.
+    pub fn cycles(opcode: &Opcode) -> u32 {
+        match opcode.builtin() {
+            Some(builtin) => Self::builtin_cycles(builtin),
+            // Custom instructions (precompiles) are proved by their own components, one row per instruction.
+            None => Self::DEFAULT_CYCLES,
+        }
+    }
+
+    /// Returns the number of clock cycles taken by a builtin opcode.
+    pub fn builtin_cycles(opcode: BuiltinOpcode) -> u32 {
+        Self::BUILTIN_CYCLES
+            .iter()
+            .find(|(builtin, _)| *builtin == opcode)
+            .map_or(Self::DEFAULT_CYCLES, |&(_, cycles)| cycles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_costs_are_positive() {
+        for spec in crate::riscv::opcode::OPCODE_TABLE {
+            assert!(
+                OpcodeCost::builtin_cycles(spec.opcode) >= 1,
+                "{}",
+                spec.mnemonic
+            );
+        }
+        assert!(OpcodeCost::cycles(&Opcode::new(0b1011011, Some(0), None, "custom")) >= 1);
+        for ecall in Ecall::ALL {
+            assert!(OpcodeCost::syscall_cycles(ecall as u32) >= 1, "{ecall:?}");
+        }
+    }
+
+    #[test]
+    fn test_syscall_cycles() {
+        let ecall = Opcode::from(BuiltinOpcode::ECALL);
+        assert_eq!(
+            OpcodeCost::instruction_cycles(&ecall, Ecall::ReadPrivateInputWord as u32),
+            WORD_SIZE as u32
+        );
+        assert_eq!(
+            OpcodeCost::instruction_cycles(&ecall, Ecall::ReadFromPrivateInput as u32),
+            OpcodeCost::cycles(&ecall)
+        );
+        // The syscall number only matters for ECALL.
+        let add = Opcode::from(BuiltinOpcode::ADD);
+        assert_eq!(
+            OpcodeCost::instruction_cycles(&add, Ecall::ReadPrivateInputWord as u32),
+            OpcodeCost::cycles(&add)
+        );
+    }
+}
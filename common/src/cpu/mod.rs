@@ -1,7 +1,9 @@
+mod cost;
 mod pc;
 mod registers;
 mod traits;
 
+pub use cost::OpcodeCost;
 pub use pc::PC;
 pub use registers::Registers;
 pub use traits::{InstructionExecutor, InstructionResult, InstructionState, Processor};
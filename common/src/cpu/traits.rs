@@ -70,6 +70,17 @@ pub trait InstructionState {
     /// This result is intended to simplify the vm <-> prover interface, by not requiring
     /// the prover to find or reconstruct it from the registers or memory operations in
     /// order to incorporate it into the witness.
+    ///
+    /// # Writes to `x0`
+    ///
+    /// When the destination register is `x0` the write is discarded, but the instruction otherwise executes
+    /// normally and its result is still returned:
+    /// * loads perform, and record, the memory read,
+    /// * jumps update the pc without recording the link address,
+    /// * arithmetic and upper-immediate instructions compute their result and drop it.
+    ///
+    /// The prover relies on this: it constrains the computed value, and only zeroes the value written back to the
+    /// register file (`ValueAEffective`).
     fn write_back(&self, cpu: &mut impl Processor) -> InstructionResult;
 }
 
@@ -0,0 +1,144 @@
+//! Numbering of the environment calls of the guest ABI.
+//!
+//! A guest requests an environment call by placing its number in `a7` and executing `ecall`. [`Ecall`] is the single
+//! registry of these numbers for the emulator and the prover, the guest runtime mirrors them in its own constants as
+//! it can't depend on this crate. Changing the meaning of a number, or removing one, breaks guests built against the
+//! previous numbering: such changes must bump [`ECALL_ABI_VERSION`], which the runtime embeds in every guest at the
+//! symbol [`ECALL_ABI_VERSION_SYMBOL`] so that the emulator can reject incompatible guests.
+
+use thiserror::Error;
+use variant_count::VariantCount;
+
+/// Version of the ecall ABI described by [`Ecall`].
+pub const ECALL_ABI_VERSION: u32 = 1;
+
+/// Symbol of the `u32` holding the ecall ABI version a guest was built against.
+pub const ECALL_ABI_VERSION_SYMBOL: &str = "__nexus_ecall_abi_version";
+
+/// Environment calls, the discriminant is the number the guest passes in `a7`.
+///
+/// Standard calls are numbered from 0x200, zkVM specific ones from 0x400.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, VariantCount)]
+#[repr(u32)]
+pub enum Ecall {
+    Write = 0x200, // Is converted to NOP for tracing
+    Exit = 0x201,
+    ReadFromPrivateInput = 0x400,
+    CycleCount = 0x401, // Is converted to NOP for tracing
+    OverwriteStackPointer = 0x402,
+    OverwriteHeapPointer = 0x403,
+    /// Reserved, not supported by the emulator.
+    ReadFromAuxiliaryInput = 0x404,
+    MemoryAdvise = 0x405, // Is converted to NOP for tracing
+    Commit = 0x406,       // Is converted to NOP for tracing
+    Hint = 0x407,
+    ReadHint = 0x408,
+    ReadCycleCounter = 0x409,
+    ReadPrivateInputWord = 0x40a,
+}
+
+/// The value of `a7` is not the number of an [`Ecall`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("unknown ecall 0x{0:x}")]
+pub struct UnknownEcall(pub u32);
+
+impl Ecall {
+    /// All environment calls, in increasing order of their numbers.
+    pub const ALL: [Ecall; Ecall::VARIANT_COUNT] = [
+        Ecall::Write,
+        Ecall::Exit,
+        Ecall::ReadFromPrivateInput,
+        Ecall::CycleCount,
+        Ecall::OverwriteStackPointer,
+        Ecall::OverwriteHeapPointer,
+        Ecall::ReadFromAuxiliaryInput,
+        Ecall::MemoryAdvise,
+        Ecall::Commit,
+        Ecall::Hint,
+        Ecall::ReadHint,
+        Ecall::ReadCycleCounter,
+        Ecall::ReadPrivateInputWord,
+    ];
+}
+
+impl TryFrom<u32> for Ecall {
+    type Error = UnknownEcall;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        Ecall::ALL
+            .into_iter()
+            .find(|&ecall| ecall as u32 == value)
+            .ok_or(UnknownEcall(value))
+    }
+}
+
+impl From<Ecall> for u32 {
+    fn from(ecall: Ecall) -> Self {
+        ecall as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbers_are_unique_and_sorted() {
+        // Strictly increasing numbers also rule out two calls sharing a number.
+        assert!(Ecall::ALL
+            .windows(2)
+            .all(|pair| (pair[0] as u32) < (pair[1] as u32)));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for ecall in Ecall::ALL {
+            assert_eq!(Ecall::try_from(u32::from(ecall)), Ok(ecall));
+        }
+        assert_eq!(u32::from(Ecall::Exit), 0x201);
+    }
+
+    /// Returns the value of a `u32` constant of the guest runtime, which mirrors the registry.
+    fn runtime_constant(name: &str) -> u32 {
+        let source = include_str!("../../runtime/src/lib.rs");
+        let prefix = format!("const {name}: u32 = ");
+        let value = source
+            .lines()
+            .find_map(|line| line.split_once(&prefix).map(|(_, value)| value))
+            .unwrap_or_else(|| panic!("runtime doesn't define {name}"))
+            .trim_end_matches(';');
+        match value.strip_prefix("0x") {
+            Some(hex) => u32::from_str_radix(hex, 16).unwrap(),
+            None => value.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_runtime_mirrors_registry() {
+        assert_eq!(runtime_constant("ECALL_ABI_VERSION"), ECALL_ABI_VERSION);
+        for (name, ecall) in [
+            ("SYS_LOG", Ecall::Write),
+            ("SYS_EXIT", Ecall::Exit),
+            ("SYS_READ_PRIVATE_INPUT", Ecall::ReadFromPrivateInput),
+            ("SYS_CYCLE_COUNT", Ecall::CycleCount),
+            ("SYS_OVERWRITE_SP", Ecall::OverwriteStackPointer),
+            ("SYS_ALLOC_ALIGNED", Ecall::OverwriteHeapPointer),
+            ("SYS_PERFORM_HEAP_ALLOCATION", Ecall::MemoryAdvise),
+            ("SYS_COMMIT", Ecall::Commit),
+            ("SYS_HINT", Ecall::Hint),
+            ("SYS_READ_HINT", Ecall::ReadHint),
+            ("SYS_READ_CYCLE_COUNTER", Ecall::ReadCycleCounter),
+            ("SYS_READ_PRIVATE_INPUT_WORD", Ecall::ReadPrivateInputWord),
+        ] {
+            assert_eq!(runtime_constant(name), u32::from(ecall), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_unknown_ecall() {
+        for value in [0, 93, 0x1ff, 0x202, 0x40b, u32::MAX] {
+            assert_eq!(Ecall::try_from(value), Err(UnknownEcall(value)));
+        }
+        assert_eq!(UnknownEcall(0x40b).to_string(), "unknown ecall 0x40b");
+    }
+}
@@ -1,5 +1,30 @@
+use std::fmt::Display;
+
 use thiserror::Error;
 
+use crate::memory::MemAccessSize;
+
+/// Why a load or a store failed, see [`MemoryError::AccessFault`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryFaultKind {
+    /// The address is outside of every memory region.
+    Unmapped,
+    /// The address is not a multiple of the access size.
+    Misaligned,
+    /// The memory region doesn't allow the access, e.g. a store into read-only memory.
+    PermissionDenied,
+}
+
+impl Display for MemoryFaultKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unmapped => write!(f, "unmapped"),
+            Self::Misaligned => write!(f, "misaligned"),
+            Self::PermissionDenied => write!(f, "permission denied"),
+        }
+    }
+}
+
 #[derive(Error, Debug, PartialEq)]
 pub enum MemoryError {
     // Cannot write unaligned memory
@@ -47,4 +72,42 @@ pub enum MemoryError {
     // Invalid memory segment
     #[error("Invalid memory segment")]
     InvalidMemorySegment,
+
+    // Load or store failing at its effective address
+    #[error("Memory access fault ({kind}): {size:?} access at 0x{addr:08X}")]
+    AccessFault {
+        kind: MemoryFaultKind,
+        addr: u32,
+        size: MemAccessSize,
+    },
+}
+
+impl MemoryError {
+    /// Returns the kind of access fault the error reports, or `None` for errors that are not caused by the address
+    /// of an access, such as overlapping memory regions.
+    pub fn fault_kind(&self) -> Option<MemoryFaultKind> {
+        match self {
+            Self::InvalidMemoryAccess(..)
+            | Self::AddressCalculationOverflow
+            | Self::AddressCalculationUnderflow
+            | Self::UndefinedMemoryRegion => Some(MemoryFaultKind::Unmapped),
+            Self::UnalignedMemoryRead(_) | Self::UnalignedMemoryWrite(_) => {
+                Some(MemoryFaultKind::Misaligned)
+            }
+            Self::UnauthorizedRead(_) | Self::UnauthorizedWrite(_) => {
+                Some(MemoryFaultKind::PermissionDenied)
+            }
+            Self::AccessFault { kind, .. } => Some(*kind),
+            Self::MemoryOverlap | Self::InvalidMemorySegment => None,
+        }
+    }
+
+    /// Turns the error of a load or store of `size` at `addr` into an [`AccessFault`](Self::AccessFault), keeping
+    /// errors that are not access faults as they are.
+    pub fn into_access_fault(self, addr: u32, size: MemAccessSize) -> Self {
+        match self.fault_kind() {
+            Some(kind) => Self::AccessFault { kind, addr, size },
+            None => self,
+        }
+    }
 }
@@ -1,5 +1,8 @@
+pub mod consistency;
 pub mod constants;
 pub mod cpu;
+pub mod ecall;
 pub mod error;
 pub mod memory;
 pub mod riscv;
+pub mod trace_size;
@@ -1,8 +1,8 @@
-use crate::error::MemoryError;
+use crate::{constants::WORD_SIZE, error::MemoryError};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
 /// Represents the size of memory access operations.
 pub enum MemAccessSize {
     Byte = 1,
@@ -30,7 +30,7 @@ impl MemAccessSize {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
 pub enum MemoryRecord {
     // (size, address, value), timestamp
     // TODO: add byte-wise previous timestamps
@@ -39,7 +39,8 @@ pub enum MemoryRecord {
     // TODO: add byte-wise previous timestamps
     StoreRecord((MemAccessSize, u32, u32, u32), u32),
 }
-pub type MemoryRecords = HashSet<MemoryRecord>;
+/// Memory records of a step, ordered so that iterating or serializing them doesn't depend on the hasher.
+pub type MemoryRecords = BTreeSet<MemoryRecord>;
 
 impl MemoryRecord {
     pub fn get_timestamp(&self) -> u32 {
@@ -49,6 +50,14 @@ impl MemoryRecord {
         }
     }
 
+    /// Returns the same access at another timestamp.
+    pub fn with_timestamp(self, timestamp: u32) -> Self {
+        match self {
+            MemoryRecord::LoadRecord(access, _) => MemoryRecord::LoadRecord(access, timestamp),
+            MemoryRecord::StoreRecord(access, _) => MemoryRecord::StoreRecord(access, timestamp),
+        }
+    }
+
     pub fn get_address(&self) -> u32 {
         match self {
             MemoryRecord::LoadRecord((_, address, _), _) => *address,
@@ -170,6 +179,42 @@ impl StoreOp {
     }
 }
 
+/// Timestamps the memory accesses of an instruction executed at `clock` and taking `cycles` clock cycles.
+///
+/// The accesses of a single-cycle instruction all happen at `clock`. Those of a multi-cycle instruction get the
+/// distinct sub-timestamps `clock + i` for its `i`-th access, loads before stores and each in increasing address
+/// order, so that the prover can order them like accesses of different instructions.
+pub fn timestamp_accesses(
+    load_ops: &LoadOps,
+    store_ops: &StoreOps,
+    clock: usize,
+    cycles: u32,
+) -> MemoryRecords {
+    if cycles <= 1 {
+        return load_ops
+            .iter()
+            .map(|op| op.as_record(clock))
+            .chain(store_ops.iter().map(|op| op.as_record(clock)))
+            .collect();
+    }
+    let mut loads: Vec<&LoadOp> = load_ops.iter().collect();
+    loads.sort_by_key(|op| op.get_address());
+    let mut stores: Vec<&StoreOp> = store_ops.iter().collect();
+    stores.sort_by_key(|op| op.get_address());
+
+    let records = loads
+        .into_iter()
+        .map(|op| op.as_record(0))
+        .chain(stores.into_iter().map(|op| op.as_record(0)));
+    records
+        .enumerate()
+        .map(|(i, record)| {
+            debug_assert!(i < cycles as usize, "more accesses than cycles");
+            record.with_timestamp((clock + i) as u32)
+        })
+        .collect()
+}
+
 /// A trait for permissions modes for memories.
 pub trait Mode {}
 
@@ -241,32 +286,69 @@ pub trait MemoryProcessor: Default {
         value: u32,
     ) -> Result<StoreOp, MemoryError>;
 
-    /// Reads multiple bytes from memory at the specified address, built on top of `read`.
+    /// Reads `size` bytes from memory starting at the specified address, built on top of `read`.
+    ///
+    /// The result is the one of reading each byte in turn: the first byte that can't be read fails the whole read
+    /// with its error. Aligned words are read at once, a word that can't be read is retried byte by byte to find
+    /// the failing byte, which assumes that a word access succeeds exactly when the accesses to its four bytes do, as
+    /// for memory mapped in whole words. A range past the end of the address space fails with
+    /// [`MemoryError::AddressCalculationOverflow`] before anything is read.
     ///
     /// Only used for (unproven) ecalls, so does not return an operation record.
     fn read_bytes(&self, address: u32, size: usize) -> Result<Vec<u8>, MemoryError> {
-        let mut data = vec![0; size];
-        for (i, byte) in data.iter_mut().enumerate().take(size) {
-            let addr = address
-                .checked_add(i as u32)
-                .ok_or(MemoryError::AddressCalculationOverflow)?;
-            match self.read(addr, MemAccessSize::Byte)? {
-                LoadOp::Op(_, _, v) => *byte = v as u8,
+        let end = byte_range_end(address, size)?;
+        let mut data = Vec::with_capacity(size);
+        let mut addr = u64::from(address);
+        while addr < end {
+            if addr % WORD_SIZE as u64 == 0 && addr + WORD_SIZE as u64 <= end {
+                if let Ok(LoadOp::Op(_, _, v)) = self.read(addr as u32, MemAccessSize::Word) {
+                    data.extend_from_slice(&v.to_le_bytes());
+                    addr += WORD_SIZE as u64;
+                    continue;
+                }
+            }
+            match self.read(addr as u32, MemAccessSize::Byte)? {
+                LoadOp::Op(_, _, v) => data.push(v as u8),
             };
+            addr += 1;
         }
         Ok(data)
     }
 
-    /// Writes multiple bytes to memory at the specified address, built on top of `write`.
+    /// Writes multiple bytes to memory starting at the specified address, built on top of `write`.
+    ///
+    /// The result is the one of writing each byte in turn: bytes before the first one that can't be written are
+    /// written, the write fails with the error of that byte. Aligned words are written at once, as with
+    /// [`Self::read_bytes`], and a range past the end of the address space fails before anything is written.
     ///
     /// Only used for (unproven) ecalls, so does not return an operation record.
     fn write_bytes(&mut self, address: u32, data: &[u8]) -> Result<(), MemoryError> {
-        for (i, &byte) in data.iter().enumerate() {
-            let addr = address
-                .checked_add(i as u32)
-                .ok_or(MemoryError::AddressCalculationOverflow)?;
-            self.write(addr, MemAccessSize::Byte, byte as u32)?;
+        let end = byte_range_end(address, data.len())?;
+        let mut addr = u64::from(address);
+        let mut remaining = data;
+        while addr < end {
+            if addr % WORD_SIZE as u64 == 0 && remaining.len() >= WORD_SIZE {
+                let word = u32::from_le_bytes(remaining[..WORD_SIZE].try_into().unwrap());
+                if self.write(addr as u32, MemAccessSize::Word, word).is_ok() {
+                    remaining = &remaining[WORD_SIZE..];
+                    addr += WORD_SIZE as u64;
+                    continue;
+                }
+            }
+            self.write(addr as u32, MemAccessSize::Byte, remaining[0] as u32)?;
+            remaining = &remaining[1..];
+            addr += 1;
         }
         Ok(())
     }
 }
+
+/// Returns the address one past the last byte of the `size` bytes starting at `address`, checking that they fit in
+/// the address space.
+pub fn byte_range_end(address: u32, size: usize) -> Result<u64, MemoryError> {
+    let end = u64::from(address) + size as u64;
+    if end > 1 << 32 {
+        return Err(MemoryError::AddressCalculationOverflow);
+    }
+    Ok(end)
+}
@@ -38,7 +38,11 @@ fn encode_i_type(instruction: &Instruction) -> u32 {
     let rd = (instruction.op_a as u32 & 0x1F) << 7;
     let funct3 = (instruction.opcode.fn3.value() as u32) << 12;
     let rs1 = (instruction.op_b as u32 & 0x1F) << 15;
-    let imm = (instruction.op_c & 0xFFF) << 20;
+    // System instructions are told apart by their immediate, which isn't an operand.
+    let imm = match instruction.opcode.builtin().and_then(|op| op.spec().fn12) {
+        Some(fn12) => (fn12 as u32 & 0xFFF) << 20,
+        None => (instruction.op_c & 0xFFF) << 20,
+    };
 
     opcode | rd | funct3 | rs1 | imm
 }
@@ -105,6 +105,14 @@ impl Instruction {
         }
     }
 
+    /// Returns true if the instruction is the canonical NOP, `addi x0, x0, 0`.
+    pub fn is_nop(&self) -> bool {
+        self.opcode.builtin() == Some(BuiltinOpcode::ADDI)
+            && self.op_a == Register::X0
+            && self.op_b == Register::X0
+            && self.op_c == 0
+    }
+
     // Returns true if the instruction is a system instruction
     pub fn is_system_instruction(&self) -> bool {
         if let Some(opcode) = self.opcode.builtin() {
@@ -89,126 +89,10 @@ impl Opcode {
                 0b1011011 => InstructionType::SType,
                 _ => InstructionType::Unimpl,
             },
-            _ => {
-                if self.is_r_type() {
-                    InstructionType::RType
-                } else if self.is_i_type() {
-                    InstructionType::IType
-                } else if self.is_i_shamt_type() {
-                    InstructionType::ITypeShamt
-                } else if self.is_s_type() {
-                    InstructionType::SType
-                } else if self.is_b_type() {
-                    InstructionType::BType
-                } else if self.is_u_type() {
-                    InstructionType::UType
-                } else if self.is_j_type() {
-                    InstructionType::JType
-                } else if self.is_unimpl_type() {
-                    InstructionType::Unimpl
-                } else {
-                    unreachable!("Opcodes should be one of the above types")
-                }
-            }
+            OpcodeIdentifier::Builtin(opcode) => opcode.spec().format,
+            OpcodeIdentifier::None => unreachable!("Opcodes should be either builtin or custom"),
         }
     }
-
-    fn is_r_type(&self) -> bool {
-        matches!(
-            self.identifier,
-            OpcodeIdentifier::Builtin(BuiltinOpcode::ADD)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::SUB)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::SLL)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::SLT)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::SLTU)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::XOR)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::SRL)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::SRA)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::OR)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::AND)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::MUL)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::MULH)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::MULHSU)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::MULHU)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::DIV)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::DIVU)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::REM)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::REMU)
-        )
-    }
-
-    fn is_i_type(&self) -> bool {
-        matches!(
-            self.identifier,
-            OpcodeIdentifier::Builtin(BuiltinOpcode::ADDI)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::SLTI)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::SLTIU)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::XORI)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::ORI)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::ANDI)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::LB)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::LH)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::LW)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::LBU)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::LHU)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::JALR)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::ECALL)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::EBREAK)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::FENCE)
-        )
-    }
-
-    fn is_i_shamt_type(&self) -> bool {
-        matches!(
-            self.identifier,
-            OpcodeIdentifier::Builtin(BuiltinOpcode::SLLI)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::SRLI)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::SRAI)
-        )
-    }
-
-    fn is_s_type(&self) -> bool {
-        matches!(
-            self.identifier,
-            OpcodeIdentifier::Builtin(BuiltinOpcode::SB)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::SH)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::SW)
-        )
-    }
-
-    fn is_b_type(&self) -> bool {
-        matches!(
-            self.identifier,
-            OpcodeIdentifier::Builtin(BuiltinOpcode::BEQ)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::BNE)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::BLT)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::BGE)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::BLTU)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::BGEU)
-        )
-    }
-
-    fn is_u_type(&self) -> bool {
-        matches!(
-            self.identifier,
-            OpcodeIdentifier::Builtin(BuiltinOpcode::LUI)
-                | OpcodeIdentifier::Builtin(BuiltinOpcode::AUIPC)
-        )
-    }
-
-    fn is_j_type(&self) -> bool {
-        matches!(
-            self.identifier,
-            OpcodeIdentifier::Builtin(BuiltinOpcode::JAL)
-        )
-    }
-
-    fn is_unimpl_type(&self) -> bool {
-        matches!(
-            self.identifier,
-            OpcodeIdentifier::Builtin(BuiltinOpcode::UNIMPL)
-        )
-    }
 }
 
 impl From<BuiltinOpcode> for Opcode {
@@ -349,216 +233,197 @@ pub enum BuiltinOpcode {
     UNIMPL,
 }
 
+/// Kind of value held by an instruction operand.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum OperandKind {
+    Register,
+    Immediate,
+    Unused,
+}
+
+/// Encoding of a builtin opcode: its instruction format, the fixed bits identifying it and the kinds of its
+/// operands.
+///
+/// Every component that needs to know how an opcode is encoded (the encoder, the decoder, the disassembler and
+/// the prover's decoding checks) reads it from [`OPCODE_TABLE`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct OpcodeSpec {
+    pub opcode: BuiltinOpcode,
+    pub mnemonic: &'static str,
+    pub format: InstructionType,
+    /// The 7 least significant bits of the instruction.
+    pub raw: u8,
+    pub fn3: Option<u8>,
+    /// For shifts by an immediate these are the upper bits of the immediate, imm[11:5], which play the same
+    /// role as funct7.
+    pub fn7: Option<u8>,
+    /// For system instructions, the funct12 field held in imm[11:0].
+    pub fn12: Option<u16>,
+    /// Kinds of `op_a`, `op_b` and `op_c`, in this order.
+    pub operands: [OperandKind; 3],
+}
+
+impl OpcodeSpec {
+    const fn new(
+        opcode: BuiltinOpcode,
+        mnemonic: &'static str,
+        format: InstructionType,
+        raw: u8,
+        fn3: Option<u8>,
+        fn7: Option<u8>,
+    ) -> Self {
+        use OperandKind::{Immediate, Register, Unused};
+
+        let operands = match format {
+            InstructionType::RType => [Register, Register, Register],
+            InstructionType::IType
+            | InstructionType::ITypeShamt
+            | InstructionType::SType
+            | InstructionType::BType => [Register, Register, Immediate],
+            InstructionType::UType | InstructionType::JType => [Register, Unused, Immediate],
+            InstructionType::Unimpl => [Unused, Unused, Unused],
+        };
+        Self {
+            opcode,
+            mnemonic,
+            format,
+            raw,
+            fn3,
+            fn7,
+            fn12: None,
+            operands,
+        }
+    }
+
+    /// System instructions are encoded as I-type, their immediate is fixed and their registers are zero.
+    const fn system(self, fn12: u16) -> Self {
+        Self {
+            fn12: Some(fn12),
+            operands: [OperandKind::Unused; 3],
+            ..self
+        }
+    }
+}
+
+macro_rules! spec {
+    ($opcode:ident, $mnemonic:literal, $format:ident, $raw:literal, $fn3:expr, $fn7:expr) => {
+        OpcodeSpec::new(
+            BuiltinOpcode::$opcode,
+            $mnemonic,
+            InstructionType::$format,
+            $raw,
+            $fn3,
+            $fn7,
+        )
+    };
+}
+
+/// Encodings of all builtin opcodes, in the order of declaration of [`BuiltinOpcode`].
+#[rustfmt::skip]
+pub const OPCODE_TABLE: [OpcodeSpec; BuiltinOpcode::VARIANT_COUNT] = [
+    // R-type instructions
+    spec!(ADD,    "add",    RType,      0b0110011, Some(0b000), Some(0b0000000)),
+    spec!(SUB,    "sub",    RType,      0b0110011, Some(0b000), Some(0b0100000)),
+    spec!(SLL,    "sll",    RType,      0b0110011, Some(0b001), Some(0b0000000)),
+    spec!(SLT,    "slt",    RType,      0b0110011, Some(0b010), Some(0b0000000)),
+    spec!(SLTU,   "sltu",   RType,      0b0110011, Some(0b011), Some(0b0000000)),
+    spec!(XOR,    "xor",    RType,      0b0110011, Some(0b100), Some(0b0000000)),
+    spec!(SRL,    "srl",    RType,      0b0110011, Some(0b101), Some(0b0000000)),
+    spec!(SRA,    "sra",    RType,      0b0110011, Some(0b101), Some(0b0100000)),
+    spec!(OR,     "or",     RType,      0b0110011, Some(0b110), Some(0b0000000)),
+    spec!(AND,    "and",    RType,      0b0110011, Some(0b111), Some(0b0000000)),
+    // RISC-V M extension
+    spec!(MUL,    "mul",    RType,      0b0110011, Some(0b000), Some(0b0000001)),
+    spec!(MULH,   "mulh",   RType,      0b0110011, Some(0b001), Some(0b0000001)),
+    spec!(MULHSU, "mulhsu", RType,      0b0110011, Some(0b010), Some(0b0000001)),
+    spec!(MULHU,  "mulhu",  RType,      0b0110011, Some(0b011), Some(0b0000001)),
+    spec!(DIV,    "div",    RType,      0b0110011, Some(0b100), Some(0b0000001)),
+    spec!(DIVU,   "divu",   RType,      0b0110011, Some(0b101), Some(0b0000001)),
+    spec!(REM,    "rem",    RType,      0b0110011, Some(0b110), Some(0b0000001)),
+    spec!(REMU,   "remu",   RType,      0b0110011, Some(0b111), Some(0b0000001)),
+    // I-type instructions, n.b. nop is implemented as addi x0, x0, 0
+    spec!(ADDI,   "addi",   IType,      0b0010011, Some(0b000), None),
+    spec!(SLLI,   "slli",   ITypeShamt, 0b0010011, Some(0b001), Some(0b0000000)),
+    spec!(SLTI,   "slti",   IType,      0b0010011, Some(0b010), None),
+    spec!(SLTIU,  "sltiu",  IType,      0b0010011, Some(0b011), None),
+    spec!(XORI,   "xori",   IType,      0b0010011, Some(0b100), None),
+    spec!(SRLI,   "srli",   ITypeShamt, 0b0010011, Some(0b101), Some(0b0000000)),
+    spec!(SRAI,   "srai",   ITypeShamt, 0b0010011, Some(0b101), Some(0b0100000)),
+    spec!(ORI,    "ori",    IType,      0b0010011, Some(0b110), None),
+    spec!(ANDI,   "andi",   IType,      0b0010011, Some(0b111), None),
+    spec!(LB,     "lb",     IType,      0b0000011, Some(0b000), None),
+    spec!(LH,     "lh",     IType,      0b0000011, Some(0b001), None),
+    spec!(LW,     "lw",     IType,      0b0000011, Some(0b010), None),
+    spec!(LBU,    "lbu",    IType,      0b0000011, Some(0b100), None),
+    spec!(LHU,    "lhu",    IType,      0b0000011, Some(0b101), None),
+    spec!(JALR,   "jalr",   IType,      0b1100111, Some(0b000), None),
+    spec!(ECALL,  "ecall",  IType,      0b1110011, Some(0b000), None).system(0),
+    spec!(EBREAK, "ebreak", IType,      0b1110011, Some(0b000), None).system(1),
+    spec!(FENCE,  "fence",  IType,      0b0001111, Some(0b000), None),
+    // S-type instructions
+    spec!(SB,     "sb",     SType,      0b0100011, Some(0b000), None),
+    spec!(SH,     "sh",     SType,      0b0100011, Some(0b001), None),
+    spec!(SW,     "sw",     SType,      0b0100011, Some(0b010), None),
+    // B-type instructions
+    spec!(BEQ,    "beq",    BType,      0b1100011, Some(0b000), None),
+    spec!(BNE,    "bne",    BType,      0b1100011, Some(0b001), None),
+    spec!(BLT,    "blt",    BType,      0b1100011, Some(0b100), None),
+    spec!(BGE,    "bge",    BType,      0b1100011, Some(0b101), None),
+    spec!(BLTU,   "bltu",   BType,      0b1100011, Some(0b110), None),
+    spec!(BGEU,   "bgeu",   BType,      0b1100011, Some(0b111), None),
+    // U-type instructions
+    spec!(LUI,    "lui",    UType,      0b0110111, None,        None),
+    spec!(AUIPC,  "auipc",  UType,      0b0010111, None,        None),
+    // J-type instructions
+    spec!(JAL,    "jal",    JType,      0b1101111, None,        None),
+    // Placeholder for unimplemented instructions should not have a known encoding
+    spec!(UNIMPL, "unimpl", Unimpl,     0b0000000, None,        None),
+];
+
+// Lookups index the table by discriminant, reject a table that doesn't follow the enum.
+const _: () = {
+    let mut i = 0;
+    while i < OPCODE_TABLE.len() {
+        assert!(
+            OPCODE_TABLE[i].opcode as usize == i,
+            "OPCODE_TABLE must follow the declaration order of BuiltinOpcode"
+        );
+        i += 1;
+    }
+};
+
 impl BuiltinOpcode {
-    const BUILTIN_NAMES: [&'static str; BuiltinOpcode::VARIANT_COUNT] = [
-        "add", "sub", "sll", "slt", "sltu", "xor", "srl", "sra", "or", "and", "mul", "mulh",
-        "mulhsu", "mulhu", "div", "divu", "rem", "remu", "addi", "slli", "slti", "sltiu", "xori",
-        "srli", "srai", "ori", "andi", "lb", "lh", "lw", "lbu", "lhu", "jalr", "ecall", "ebreak",
-        "fence", "sb", "sh", "sw", "beq", "bne", "blt", "bge", "bltu", "bgeu", "lui", "auipc",
-        "jal", "unimpl",
-    ];
+    /// Returns the encoding of the opcode.
+    pub const fn spec(&self) -> OpcodeSpec {
+        OPCODE_TABLE[*self as usize]
+    }
+
+    /// Returns the opcode with the given mnemonic, as printed by the disassembler.
+    pub fn from_mnemonic(mnemonic: &str) -> Option<Self> {
+        OPCODE_TABLE
+            .iter()
+            .find(|spec| spec.mnemonic == mnemonic)
+            .map(|spec| spec.opcode)
+    }
 
     fn mnemonic(&self) -> &'static str {
-        // Safety: BUILTIN_NAMES is statically guaranteed to have the same size as the number of
-        // variants for BuiltinOpcode.
-        Self::BUILTIN_NAMES[*self as usize]
+        self.spec().mnemonic
     }
 
     pub const fn raw(&self) -> u8 {
-        match self {
-            BuiltinOpcode::ADD => 0b0110011,
-            BuiltinOpcode::SUB => 0b0110011,
-            BuiltinOpcode::SLL => 0b0110011,
-            BuiltinOpcode::SLT => 0b0110011,
-            BuiltinOpcode::SLTU => 0b0110011,
-            BuiltinOpcode::XOR => 0b0110011,
-            BuiltinOpcode::SRL => 0b0110011,
-            BuiltinOpcode::SRA => 0b0110011,
-            BuiltinOpcode::OR => 0b0110011,
-            BuiltinOpcode::AND => 0b0110011,
-
-            BuiltinOpcode::MUL => 0b0110011,
-            BuiltinOpcode::MULH => 0b0110011,
-            BuiltinOpcode::MULHSU => 0b0110011,
-            BuiltinOpcode::MULHU => 0b0110011,
-            BuiltinOpcode::DIV => 0b0110011,
-            BuiltinOpcode::DIVU => 0b0110011,
-            BuiltinOpcode::REM => 0b0110011,
-            BuiltinOpcode::REMU => 0b0110011,
-
-            BuiltinOpcode::ADDI => 0b0010011,
-            BuiltinOpcode::SLTI => 0b0010011,
-            BuiltinOpcode::SLTIU => 0b0010011,
-            BuiltinOpcode::XORI => 0b0010011,
-            BuiltinOpcode::ORI => 0b0010011,
-            BuiltinOpcode::ANDI => 0b0010011,
-            BuiltinOpcode::SLLI => 0b0010011,
-            BuiltinOpcode::SRLI => 0b0010011,
-            BuiltinOpcode::SRAI => 0b0010011,
-            BuiltinOpcode::LB => 0b0000011,
-            BuiltinOpcode::LH => 0b0000011,
-            BuiltinOpcode::LW => 0b0000011,
-            BuiltinOpcode::LBU => 0b0000011,
-            BuiltinOpcode::LHU => 0b0000011,
-            BuiltinOpcode::JALR => 0b1100111,
-            BuiltinOpcode::ECALL => 0b1110011,
-            BuiltinOpcode::EBREAK => 0b1110011,
-            BuiltinOpcode::FENCE => 0b0001111,
-
-            BuiltinOpcode::SB => 0b0100011,
-            BuiltinOpcode::SH => 0b0100011,
-            BuiltinOpcode::SW => 0b0100011,
-
-            BuiltinOpcode::BEQ => 0b1100011,
-            BuiltinOpcode::BNE => 0b1100011,
-            BuiltinOpcode::BLT => 0b1100011,
-            BuiltinOpcode::BGE => 0b1100011,
-            BuiltinOpcode::BLTU => 0b1100011,
-            BuiltinOpcode::BGEU => 0b1100011,
-
-            BuiltinOpcode::LUI => 0b0110111,
-            BuiltinOpcode::AUIPC => 0b0010111,
-
-            BuiltinOpcode::JAL => 0b1101111,
-
-            BuiltinOpcode::UNIMPL => 0b000000,
-        }
+        self.spec().raw
     }
 
     pub fn fn3(&self) -> SubByte<3> {
-        match self {
-            BuiltinOpcode::ADD | BuiltinOpcode::SUB => SubByte::<3>::new_set(0b000),
-            BuiltinOpcode::SLL => SubByte::<3>::new_set(0b001),
-            BuiltinOpcode::SLT => SubByte::<3>::new_set(0b010),
-            BuiltinOpcode::SLTU => SubByte::<3>::new_set(0b011),
-            BuiltinOpcode::XOR => SubByte::<3>::new_set(0b100),
-            BuiltinOpcode::SRL | BuiltinOpcode::SRA => SubByte::<3>::new_set(0b101),
-            BuiltinOpcode::OR => SubByte::<3>::new_set(0b110),
-            BuiltinOpcode::AND => SubByte::<3>::new_set(0b111),
-
-            BuiltinOpcode::MUL => SubByte::<3>::new_set(0b000),
-            BuiltinOpcode::MULH => SubByte::<3>::new_set(0b001),
-            BuiltinOpcode::MULHSU => SubByte::<3>::new_set(0b010),
-            BuiltinOpcode::MULHU => SubByte::<3>::new_set(0b011),
-            BuiltinOpcode::DIV => SubByte::<3>::new_set(0b100),
-            BuiltinOpcode::DIVU => SubByte::<3>::new_set(0b101),
-            BuiltinOpcode::REM => SubByte::<3>::new_set(0b110),
-            BuiltinOpcode::REMU => SubByte::<3>::new_set(0b111),
-
-            // n.b. nop is implemented as addi x0, x0, 0
-            BuiltinOpcode::ADDI => SubByte::<3>::new_set(0b000),
-            BuiltinOpcode::SLTI => SubByte::<3>::new_set(0b010),
-            BuiltinOpcode::SLTIU => SubByte::<3>::new_set(0b011),
-            BuiltinOpcode::XORI => SubByte::<3>::new_set(0b100),
-            BuiltinOpcode::ORI => SubByte::<3>::new_set(0b110),
-            BuiltinOpcode::ANDI => SubByte::<3>::new_set(0b111),
-            BuiltinOpcode::SLLI => SubByte::<3>::new_set(0b001),
-            BuiltinOpcode::SRLI => SubByte::<3>::new_set(0b101),
-            BuiltinOpcode::SRAI => SubByte::<3>::new_set(0b101),
-
-            BuiltinOpcode::LB => SubByte::<3>::new_set(0b000),
-            BuiltinOpcode::LH => SubByte::<3>::new_set(0b001),
-            BuiltinOpcode::LW => SubByte::<3>::new_set(0b010),
-            BuiltinOpcode::LBU => SubByte::<3>::new_set(0b100),
-            BuiltinOpcode::LHU => SubByte::<3>::new_set(0b101),
-            BuiltinOpcode::SB => SubByte::<3>::new_set(0b000),
-            BuiltinOpcode::SH => SubByte::<3>::new_set(0b001),
-            BuiltinOpcode::SW => SubByte::<3>::new_set(0b010),
-
-            BuiltinOpcode::BEQ => SubByte::<3>::new_set(0b000),
-            BuiltinOpcode::BNE => SubByte::<3>::new_set(0b001),
-            BuiltinOpcode::BLT => SubByte::<3>::new_set(0b100),
-            BuiltinOpcode::BGE => SubByte::<3>::new_set(0b101),
-            BuiltinOpcode::BLTU => SubByte::<3>::new_set(0b110),
-            BuiltinOpcode::BGEU => SubByte::<3>::new_set(0b111),
-
-            BuiltinOpcode::JALR => SubByte::<3>::new_set(0b000),
-            BuiltinOpcode::JAL => SubByte::<3>::new_unset(),
-
-            BuiltinOpcode::LUI => SubByte::<3>::new_unset(),
-            BuiltinOpcode::AUIPC => SubByte::<3>::new_unset(),
-
-            BuiltinOpcode::ECALL => SubByte::<3>::new_set(0b000),
-            BuiltinOpcode::EBREAK => SubByte::<3>::new_set(0b000),
-
-            BuiltinOpcode::FENCE => SubByte::<3>::new_set(0b000),
-
-            // Placeholder for unimplemented instructions should not have a known funct3
-            BuiltinOpcode::UNIMPL => SubByte::<3>::new_unset(),
-        }
+        self.spec()
+            .fn3
+            .map_or_else(SubByte::<3>::new_unset, SubByte::<3>::new_set)
     }
 
     pub fn fn7(&self) -> SubByte<7> {
-        match self {
-            BuiltinOpcode::ADD => SubByte::<7>::new_set(0b0000000),
-            BuiltinOpcode::SUB => SubByte::<7>::new_set(0b0100000),
-            BuiltinOpcode::SLL => SubByte::<7>::new_set(0b0000000),
-            BuiltinOpcode::SLT => SubByte::<7>::new_set(0b0000000),
-            BuiltinOpcode::SLTU => SubByte::<7>::new_set(0b0000000),
-            BuiltinOpcode::XOR => SubByte::<7>::new_set(0b0000000),
-            BuiltinOpcode::SRL => SubByte::<7>::new_set(0b0000000),
-            BuiltinOpcode::SRA => SubByte::<7>::new_set(0b0100000),
-            BuiltinOpcode::OR => SubByte::<7>::new_set(0b0000000),
-            BuiltinOpcode::AND => SubByte::<7>::new_set(0b0000000),
-
-            BuiltinOpcode::MUL => SubByte::<7>::new_set(0b0000001),
-            BuiltinOpcode::MULH => SubByte::<7>::new_set(0b0000001),
-            BuiltinOpcode::MULHSU => SubByte::<7>::new_set(0b0000001),
-            BuiltinOpcode::MULHU => SubByte::<7>::new_set(0b0000001),
-            BuiltinOpcode::DIV => SubByte::<7>::new_set(0b0000001),
-            BuiltinOpcode::DIVU => SubByte::<7>::new_set(0b0000001),
-            BuiltinOpcode::REM => SubByte::<7>::new_set(0b0000001),
-            BuiltinOpcode::REMU => SubByte::<7>::new_set(0b0000001),
-
-            // I-type instructions have no funct7.
-            BuiltinOpcode::ADDI => SubByte::<7>::new_unset(),
-            BuiltinOpcode::SLTI => SubByte::<7>::new_unset(),
-            BuiltinOpcode::SLTIU => SubByte::<7>::new_unset(),
-            BuiltinOpcode::XORI => SubByte::<7>::new_unset(),
-            BuiltinOpcode::ORI => SubByte::<7>::new_unset(),
-            BuiltinOpcode::ANDI => SubByte::<7>::new_unset(),
-
-            // These are technically specified as imm[11:5] due to these instructions being I-type,
-            // but they use the same bits as funct7, and for the same purpose, so it's most correct
-            // to treat them as funct7 values here.
-            BuiltinOpcode::SLLI => SubByte::<7>::new_set(0b0000000),
-            BuiltinOpcode::SRLI => SubByte::<7>::new_set(0b0000000),
-            BuiltinOpcode::SRAI => SubByte::<7>::new_set(0b0100000),
-
-            // Memory operations are also I-type
-            BuiltinOpcode::LB => SubByte::<7>::new_unset(),
-            BuiltinOpcode::LH => SubByte::<7>::new_unset(),
-            BuiltinOpcode::LW => SubByte::<7>::new_unset(),
-            BuiltinOpcode::LBU => SubByte::<7>::new_unset(),
-            BuiltinOpcode::LHU => SubByte::<7>::new_unset(),
-
-            // S-type instructions have no funct7 either.
-            BuiltinOpcode::SB => SubByte::<7>::new_unset(),
-            BuiltinOpcode::SH => SubByte::<7>::new_unset(),
-            BuiltinOpcode::SW => SubByte::<7>::new_unset(),
-
-            // Same story for B-type, J-type, and U-type instructions, which all have larger
-            // immediates instead of funct7 fields.
-            BuiltinOpcode::BEQ => SubByte::<7>::new_unset(),
-            BuiltinOpcode::BNE => SubByte::<7>::new_unset(),
-            BuiltinOpcode::BLT => SubByte::<7>::new_unset(),
-            BuiltinOpcode::BGE => SubByte::<7>::new_unset(),
-            BuiltinOpcode::BLTU => SubByte::<7>::new_unset(),
-            BuiltinOpcode::BGEU => SubByte::<7>::new_unset(),
-
-            BuiltinOpcode::JALR => SubByte::<7>::new_unset(),
-            BuiltinOpcode::JAL => SubByte::<7>::new_unset(),
-
-            BuiltinOpcode::LUI => SubByte::<7>::new_unset(),
-            BuiltinOpcode::AUIPC => SubByte::<7>::new_unset(),
-
-            BuiltinOpcode::ECALL => SubByte::<7>::new_unset(),
-            BuiltinOpcode::EBREAK => SubByte::<7>::new_unset(),
-
-            BuiltinOpcode::FENCE => SubByte::<7>::new_unset(),
-
-            BuiltinOpcode::UNIMPL => SubByte::<7>::new_unset(),
-        }
+        self.spec()
+            .fn7
+            .map_or_else(SubByte::<7>::new_unset, SubByte::<7>::new_set)
     }
 }
 
@@ -648,4 +513,28 @@ mod tests {
         assert_eq!(format!("{}", BuiltinOpcode::JAL), "jal");
         assert_eq!(format!("{}", BuiltinOpcode::UNIMPL), "unimpl");
     }
+
+    #[test]
+    fn test_opcode_table_consistency() {
+        for spec in OPCODE_TABLE {
+            let opcode = Opcode::from(spec.opcode);
+            assert_eq!(opcode.ins_type(), spec.format, "{}", spec.mnemonic);
+            assert_eq!(
+                BuiltinOpcode::from_mnemonic(spec.mnemonic),
+                Some(spec.opcode)
+            );
+
+            let has_fn3 = !matches!(
+                spec.format,
+                InstructionType::UType | InstructionType::JType | InstructionType::Unimpl
+            );
+            let has_fn7 = matches!(
+                spec.format,
+                InstructionType::RType | InstructionType::ITypeShamt
+            );
+            assert_eq!(opcode.fn3().is_set(), has_fn3, "{}", spec.mnemonic);
+            assert_eq!(opcode.fn7().is_set(), has_fn7, "{}", spec.mnemonic);
+        }
+        assert_eq!(BuiltinOpcode::from_mnemonic("csrrw"), None);
+    }
 }
@@ -82,6 +82,69 @@ impl From<u8> for Register {
 }
 
 impl Register {
+    /// Registers passing the arguments of a call, `a0` to `a7`, in order.
+    pub const ARG_REGISTERS: [Register; 8] = [
+        Register::X10,
+        Register::X11,
+        Register::X12,
+        Register::X13,
+        Register::X14,
+        Register::X15,
+        Register::X16,
+        Register::X17,
+    ];
+
+    /// Registers preserved across calls by the psABI: the stack pointer and `s0` to `s11`.
+    pub const CALLEE_SAVED: [Register; 13] = [
+        Register::X2,
+        Register::X8,
+        Register::X9,
+        Register::X18,
+        Register::X19,
+        Register::X20,
+        Register::X21,
+        Register::X22,
+        Register::X23,
+        Register::X24,
+        Register::X25,
+        Register::X26,
+        Register::X27,
+    ];
+
+    /// Returns the register with the given ABI name, `fp` being accepted for `s0`.
+    pub fn from_abi_name(name: &str) -> Option<Self> {
+        if name == "fp" {
+            return Some(Register::X8);
+        }
+        (0..NUM_REGISTERS as u8)
+            .map(Register::from)
+            .find(|reg| reg.abi_name() == name)
+    }
+
+    /// Returns whether the register passes arguments of a call, `a0` to `a7`.
+    pub fn is_argument(&self) -> bool {
+        Self::ARG_REGISTERS.contains(self)
+    }
+
+    /// Returns whether the register is preserved across calls, see [`Self::CALLEE_SAVED`].
+    pub fn is_callee_saved(&self) -> bool {
+        Self::CALLEE_SAVED.contains(self)
+    }
+
+    /// Returns whether the register is a temporary, `t0` to `t6`.
+    pub fn is_temporary(&self) -> bool {
+        matches!(
+            self,
+            Register::X5
+                | Register::X6
+                | Register::X7
+                | Register::X28
+                | Register::X29
+                | Register::X30
+                | Register::X31
+        )
+    }
+
     pub fn abi_name(&self) -> &'static str {
         match self {
             Register::X0 => "zero", // Hardwired zero
@@ -194,6 +257,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_register_from_abi_name() {
+        for i in 0..32 {
+            let reg = Register::from(i);
+            assert_eq!(Register::from_abi_name(reg.abi_name()), Some(reg));
+        }
+        assert_eq!(Register::from_abi_name("fp"), Some(Register::X8));
+        assert_eq!(Register::from_abi_name("x10"), None);
+        assert_eq!(Register::from_abi_name("a8"), None);
+    }
+
+    #[test]
+    fn test_register_classification() {
+        // (argument, callee-saved, temporary) per register, from the register table of the RISC-V psABI.
+        const NONE: (bool, bool, bool) = (false, false, false);
+        const ARG: (bool, bool, bool) = (true, false, false);
+        const SAVED: (bool, bool, bool) = (false, true, false);
+        const TEMP: (bool, bool, bool) = (false, false, true);
+        let classes = [
+            NONE, NONE, SAVED, NONE, NONE, TEMP, TEMP, TEMP, SAVED, SAVED, ARG, ARG, ARG, ARG, ARG,
+            ARG, ARG, ARG, SAVED, SAVED, SAVED, SAVED, SAVED, SAVED, SAVED, SAVED, SAVED, SAVED,
+            TEMP, TEMP, TEMP, TEMP,
+        ];
+
+        for i in 0..32 {
+            let reg = Register::from(i);
+            assert_eq!(
+                (reg.is_argument(), reg.is_callee_saved(), reg.is_temporary()),
+                classes[i as usize],
+                "Classification mismatch for register {}",
+                reg
+            );
+        }
+        for (i, reg) in Register::ARG_REGISTERS.iter().enumerate() {
+            assert_eq!(reg.abi_name(), format!("a{i}"));
+        }
+    }
+
     #[test]
     fn test_register_display() {
         for i in 0..32 {
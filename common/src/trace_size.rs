@@ -0,0 +1,36 @@
+//! Size of the main trace of the prover, shared with the emulator so that it can be estimated without proving.
+
+/// Smallest log size of the main trace supported by the prover.
+pub const MIN_LOG_SIZE: u32 = 8;
+
+/// Largest log size of a trace accepted by the verifier.
+///
+/// The verifier builds the preprocessed and program traces at the log sizes claimed by a proof, so they bound its
+/// allocations. The main trace must also have the size of [`main_log_size`] for the claimed number of steps.
+pub const MAX_LOG_SIZE: u32 = 24;
+
+/// Returns the log size of the main trace of an execution taking `num_steps` steps of a program of `program_len`
+/// instructions.
+///
+/// The main trace has a row per step and the program trace a row per instruction, both are padded to the same
+/// power of two.
+pub fn main_log_size(num_steps: usize, program_len: usize) -> u32 {
+    num_steps
+        .max(program_len)
+        .next_power_of_two()
+        .trailing_zeros()
+        .max(MIN_LOG_SIZE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_main_log_size() {
+        assert_eq!(main_log_size(0, 0), MIN_LOG_SIZE);
+        assert_eq!(main_log_size(1 << MIN_LOG_SIZE, 10), MIN_LOG_SIZE);
+        assert_eq!(main_log_size((1 << MIN_LOG_SIZE) + 1, 10), MIN_LOG_SIZE + 1);
+        assert_eq!(main_log_size(10, 1 << 12), 12);
+    }
+}
@@ -5,7 +5,7 @@
 pub mod nvm {
     pub use nexus_vm::{
         elf::{ElfError, ElfFile},
-        emulator::View,
+        emulator::{PanicInfo, View},
         error::VMError,
         trace::{bb_trace, k_trace, BBTrace, UniformTrace},
     };
@@ -20,5 +20,7 @@ pub mod nvm {
 
 /// Stwo proving
 pub mod stwo {
-    pub use nexus_vm_prover::{prove, verify, Proof, ProvingError, VerificationError};
+    #[allow(deprecated)]
+    pub use nexus_vm_prover::ProvingError;
+    pub use nexus_vm_prover::{prove, verify, NexusProvingError, Proof, VerificationError};
 }
@@ -0,0 +1,25 @@
+//! Proves the seed executions and writes their serialized proofs to `$OUT_DIR/seed_proofs.bin`.
+
+use std::{env, fs, path::PathBuf};
+
+#[path = "src/seed.rs"]
+mod seed;
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/seed.rs");
+
+    let proofs: Vec<Vec<u8>> = seed::seed_traces()
+        .iter()
+        .map(|(view, trace)| {
+            let proof = nexus_vm_prover::prove(trace, view).expect("failed to prove seed");
+            postcard::to_stdvec(&proof).expect("failed to serialize seed proof")
+        })
+        .collect();
+
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("OUT_DIR is set by cargo"));
+    fs::write(
+        out_dir.join("seed_proofs.bin"),
+        postcard::to_stdvec(&proofs).expect("failed to serialize seeds"),
+    )
+    .expect("failed to write seeds");
+}
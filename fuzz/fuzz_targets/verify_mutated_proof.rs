@@ -0,0 +1,47 @@
+//! Mutates valid proofs and checks that the verifier rejects them without panicking.
+
+#![no_main]
+
+use std::{
+    panic::{self, AssertUnwindSafe},
+    process,
+    sync::OnceLock,
+};
+
+use libfuzzer_sys::fuzz_target;
+use nexus_vm_prover::{deser::DeserLimits, Proof};
+use nexus_vm_prover_fuzz::{seeds, FuzzInput, Seed};
+
+static SEEDS: OnceLock<Vec<Seed>> = OnceLock::new();
+
+/// Runs `f`, aborting with the input if it panics.
+fn no_panic<T>(stage: &str, input: &FuzzInput, f: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|_| {
+        eprintln!("{stage} panicked on {input:?}");
+        process::abort()
+    })
+}
+
+fuzz_target!(|input: FuzzInput| {
+    let seeds = SEEDS.get_or_init(seeds);
+    let seed = &seeds[input.seed as usize % seeds.len()];
+
+    let bytes = input.mutate(seed.proof.clone());
+    let Ok(proof) = no_panic("deserialization", &input, || {
+        Proof::from_bytes_bounded(&bytes, DeserLimits::default())
+    }) else {
+        return;
+    };
+    // Mutations may cancel out, e.g. swapping a commitment with itself.
+    if postcard::to_stdvec(&proof).expect("failed to serialize proof") == seed.bytes {
+        return;
+    }
+
+    let result = no_panic("verification", &input, || {
+        nexus_vm_prover::verify(proof, &seed.view)
+    });
+    if result.is_ok() {
+        eprintln!("mutated proof was accepted: {input:?}");
+        process::abort();
+    }
+});
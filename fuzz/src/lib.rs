@@ -0,0 +1,163 @@
+//! Structured mutations of proofs for fuzzing the verifier, see the `verify_mutated_proof` target.
+
+use libfuzzer_sys::arbitrary::{self, Arbitrary};
+use nexus_vm::emulator::View;
+use nexus_vm_prover::{deser::DeserLimits, Proof};
+use stwo::core::fields::{
+    m31::{BaseField, P},
+    qm31::SecureField,
+};
+
+mod seed;
+
+pub use seed::seed_traces;
+
+/// Serialized seed proofs, in the order of [`seed_traces`].
+const SEED_PROOFS: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/seed_proofs.bin"));
+
+/// A valid proof together with the view it verifies against.
+pub struct Seed {
+    pub view: View,
+    pub proof: Proof,
+    /// Canonical serialization of the proof.
+    pub bytes: Vec<u8>,
+}
+
+/// Returns the seed proofs generated by the build script, with the views of their executions.
+pub fn seeds() -> Vec<Seed> {
+    let proofs: Vec<Vec<u8>> = postcard::from_bytes(SEED_PROOFS).expect("invalid seeds");
+    seed_traces()
+        .into_iter()
+        .zip(proofs)
+        .map(|((view, _), bytes)| Seed {
+            view,
+            proof: Proof::from_bytes_bounded(&bytes, DeserLimits::default())
+                .expect("invalid seed proof"),
+            bytes,
+        })
+        .collect()
+}
+
+/// Mutations of a proof. Indices wrap around the length of what they index, mutations of empty vectors do nothing.
+#[derive(Arbitrary, Debug)]
+pub enum Mutation {
+    TruncateLogSizes(u8),
+    TruncateClaimedSums(u8),
+    TruncateCommitments(u8),
+    /// Truncates the columns of a tree of sampled values.
+    TruncateSampledValues {
+        tree: u8,
+        len: u16,
+    },
+    TruncateFriLayers(u8),
+    SetLogSize {
+        index: u8,
+        log_size: u32,
+    },
+    SetNumSteps(u32),
+    SwapCommitments(u8, u8),
+    /// Adds a non-zero base field element to a claimed sum.
+    PerturbClaimedSum {
+        index: u8,
+        delta: u32,
+    },
+    /// Adds a non-zero base field element to a sampled value.
+    PerturbSampledValue {
+        tree: u8,
+        column: u16,
+        sample: u8,
+        delta: u32,
+    },
+    /// Flips bits of a byte of the serialized proof, applied after all other mutations.
+    FlipByte {
+        offset: u32,
+        mask: u8,
+    },
+}
+
+/// Input of the `verify_mutated_proof` target: the seed to start from and the mutations to apply to it.
+#[derive(Arbitrary, Debug)]
+pub struct FuzzInput {
+    pub seed: u8,
+    pub mutations: Vec<Mutation>,
+}
+
+impl FuzzInput {
+    /// Applies the mutations to `proof` and returns its serialization.
+    pub fn mutate(&self, mut proof: Proof) -> Vec<u8> {
+        for mutation in &self.mutations {
+            mutation.apply(&mut proof);
+        }
+        let mut bytes = postcard::to_stdvec(&proof).expect("failed to serialize proof");
+        for mutation in &self.mutations {
+            if let &Mutation::FlipByte { offset, mask } = mutation {
+                if !bytes.is_empty() {
+                    let len = bytes.len();
+                    bytes[offset as usize % len] ^= mask;
+                }
+            }
+        }
+        bytes
+    }
+}
+
+impl Mutation {
+    fn apply(&self, proof: &mut Proof) {
+        let stark_proof = &mut proof.stark_proof.0;
+        match *self {
+            Self::TruncateLogSizes(len) => proof.log_size.truncate(len.into()),
+            Self::TruncateClaimedSums(len) => proof.claimed_sums.truncate(len.into()),
+            Self::TruncateCommitments(len) => stark_proof.commitments.truncate(len.into()),
+            Self::TruncateSampledValues { tree, len } => {
+                if let Some(columns) = nth_mut(&mut stark_proof.sampled_values, tree.into()) {
+                    columns.truncate(len.into());
+                }
+            }
+            Self::TruncateFriLayers(len) => stark_proof.fri_proof.inner_layers.truncate(len.into()),
+            Self::SetLogSize { index, log_size } => {
+                if let Some(value) = nth_mut(&mut proof.log_size, index.into()) {
+                    *value = log_size;
+                }
+            }
+            Self::SetNumSteps(num_steps) => proof.num_steps = num_steps,
+            Self::SwapCommitments(a, b) => {
+                let len = stark_proof.commitments.len();
+                if len > 0 {
+                    stark_proof
+                        .commitments
+                        .swap(a as usize % len, b as usize % len);
+                }
+            }
+            Self::PerturbClaimedSum { index, delta } => {
+                if let Some(value) = nth_mut(&mut proof.claimed_sums, index.into()) {
+                    *value += non_zero(delta);
+                }
+            }
+            Self::PerturbSampledValue {
+                tree,
+                column,
+                sample,
+                delta,
+            } => {
+                let value = nth_mut(&mut stark_proof.sampled_values, tree.into())
+                    .and_then(|columns| nth_mut(columns, column.into()))
+                    .and_then(|samples| nth_mut(samples, sample.into()));
+                if let Some(value) = value {
+                    *value += non_zero(delta);
+                }
+            }
+            Self::FlipByte { .. } => {}
+        }
+    }
+}
+
+/// Element `index` of `items`, wrapping around their length.
+fn nth_mut<T>(items: &mut [T], index: usize) -> Option<&mut T> {
+    let len = items.len();
+    items.get_mut(index.checked_rem(len)?)
+}
+
+/// A non-zero base field element derived from `delta`.
+fn non_zero(delta: u32) -> SecureField {
+    BaseField::from(delta % (P - 1) + 1).into()
+}
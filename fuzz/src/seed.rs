@@ -0,0 +1,26 @@
+//! Statements of the seed proofs, shared by the build script proving them and the fuzz targets verifying them.
+
+use nexus_common::trace_size::MIN_LOG_SIZE;
+use nexus_vm::{
+    emulator::View,
+    riscv::builder::ProgramBuilder,
+    trace::{k_trace_direct, UniformTrace},
+};
+use nexus_vm_prover::testing::synthesize_worst_case_trace;
+
+/// Returns the executions the seed proofs are generated for, in the order of the seeds.
+///
+/// The executions are deterministic, so that the fuzz targets rebuild the views the proofs were generated for.
+pub fn seed_traces() -> Vec<(View, UniformTrace)> {
+    let arithmetic = ProgramBuilder::new()
+        .addi(1, 0, 1)
+        .add(2, 1, 1)
+        .sub(3, 2, 1)
+        .build()
+        .expect("seed program is valid");
+    vec![
+        k_trace_direct(&arithmetic, 1).expect("error generating trace"),
+        // Every instruction chip and lookup table is used.
+        synthesize_worst_case_trace(MIN_LOG_SIZE),
+    ]
+}
@@ -0,0 +1,52 @@
+use std::time::Duration;
+
+use nexus_vm::{
+    emulator::{Emulator, HarvardEmulator},
+    error::VMErrorKind,
+    riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+criterion_group! {
+    name = dispatch;
+    config = Criterion::default().warm_up_time(Duration::from_millis(3000));
+    targets = bench_dispatch,
+}
+
+criterion_main!(dispatch);
+
+/// x1 = 2^14 iterations of a loop of 16 additions, every instruction after the first run is dispatched through the
+/// emulator's dispatch table.
+fn hot_loop() -> Vec<BasicBlock> {
+    let mut instructions = vec![Instruction::new_ir(
+        Opcode::from(BuiltinOpcode::LUI),
+        1,
+        0,
+        4,
+    )];
+    instructions.extend(
+        (2..18).map(|rd| Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), rd, rd - 1, 1)),
+    );
+    instructions.extend([
+        Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 1, 0xFFF), // -1
+        Instruction::new_ir(Opcode::from(BuiltinOpcode::BNE), 1, 0, 0x1FB8), // -72
+    ]);
+    vec![BasicBlock::new(instructions)]
+}
+
+fn bench_dispatch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Dispatch-HotLoop");
+    group.sample_size(10);
+
+    let basic_blocks = hot_loop();
+    group.bench_function("Harvard", |b| {
+        b.iter(|| {
+            let mut emulator = HarvardEmulator::from_basic_blocks(black_box(&basic_blocks));
+            let err = emulator.execute(false).unwrap_err();
+            assert_eq!(err.source, VMErrorKind::VMOutOfInstructions);
+        })
+    });
+
+    group.finish();
+}
@@ -0,0 +1,52 @@
+use std::time::Duration;
+
+use nexus_vm::{
+    elf::ElfFile,
+    emulator::View,
+    trace::{k_trace_with_options, Trace, TraceOptions, UniformTrace},
+};
+use nexus_vm_prover::trace::PreprocessedTraces;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const K: usize = 1;
+
+const ELF_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../vm/test/fib_10.elf");
+
+criterion_group! {
+    name = fusion;
+    config = Criterion::default().warm_up_time(Duration::from_millis(3000));
+    targets = bench_fusion,
+}
+
+criterion_main!(fusion);
+
+fn bench_fusion(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Fusion-Fib10");
+    group.sample_size(10);
+
+    for fuse in [false, true] {
+        let (view, program_trace) = program_trace(fuse);
+        let num_steps = program_trace.get_num_steps();
+        let log_size = num_steps
+            .next_power_of_two()
+            .trailing_zeros()
+            .max(PreprocessedTraces::MIN_LOG_SIZE);
+        let name = if fuse { "Fused" } else { "Unfused" };
+
+        group.bench_function(format!("{name}-Rows-{num_steps}-LogSize-{log_size}"), |b| {
+            b.iter(|| nexus_vm_prover::prove(black_box(&program_trace), black_box(&view)).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+fn program_trace(fuse: bool) -> (View, UniformTrace) {
+    let elf = ElfFile::from_path(ELF_PATH).expect("error loading elf");
+    let options = TraceOptions {
+        fuse_address_materialization: fuse,
+        ..Default::default()
+    };
+    k_trace_with_options(elf, &[], &[], &[], K, options).expect("error generating trace")
+}
@@ -3,12 +3,12 @@ use std::time::Duration;
 use nexus_vm::{
     emulator::{InternalView, View},
     riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
-    trace::{k_trace_direct, UniformTrace},
+    trace::{k_trace_direct, Trace, UniformTrace},
 };
 use nexus_vm_prover::{
     components::AllLookupElements,
     extensions::ExtensionsConfig,
-    machine::BaseComponent,
+    machine::{BaseComponent, CANCELLATION_CHECK_ROWS},
     trace::{
         program::iter_program_steps,
         program_trace::{ProgramTraceRef, ProgramTracesBuilder},
@@ -45,7 +45,7 @@ const _: () = {
 criterion_group! {
     name = trace_gen;
     config = Criterion::default().warm_up_time(Duration::from_millis(3000));
-    targets = bench_trace_gen,
+    targets = bench_trace_gen, bench_main_trace_chunks,
 }
 
 criterion_main!(trace_gen);
@@ -75,6 +75,8 @@ fn bench_trace_gen(c: &mut Criterion) {
             .concat(),
             exit_code: view.get_exit_code(),
             public_output: view.get_public_output(),
+            num_steps: execution_trace.get_num_steps(),
+            committed_output_len: 0,
         };
         let mut program_traces = ProgramTracesBuilder::new(log_size, program_trace_ref);
 
@@ -127,6 +129,77 @@ fn bench_trace_gen(c: &mut Criterion) {
     }
 }
 
+fn bench_main_trace_chunks(c: &mut Criterion) {
+    for &log_size in LOG_SIZES {
+        let mut group = c.benchmark_group(format!("MainTraceChunks-LogSize-{log_size}"));
+        group.sample_size(20);
+
+        for (name, blocks) in [
+            ("Add", program_trace(log_size)),
+            ("LoadStore", load_store_program_trace(log_size)),
+        ] {
+            let (view, execution_trace) =
+                k_trace_direct(&blocks, K).expect("error generating trace");
+            let init_memory = [
+                view.get_ro_initial_memory(),
+                view.get_rw_initial_memory(),
+                view.get_public_input(),
+            ]
+            .concat();
+            let program_traces = ProgramTracesBuilder::new(
+                log_size,
+                ProgramTraceRef {
+                    program_memory: view.get_program_memory(),
+                    init_memory: &init_memory,
+                    exit_code: view.get_exit_code(),
+                    public_output: view.get_public_output(),
+                    num_steps: execution_trace.get_num_steps(),
+                    committed_output_len: 0,
+                },
+            );
+
+            for chunk_rows in [1, CANCELLATION_CHECK_ROWS] {
+                group.bench_function(format!("{name}-Chunk-{chunk_rows}"), |b| {
+                    b.iter(|| {
+                        let mut prover_traces = TracesBuilder::new(black_box(log_size));
+                        fill_main_trace_chunks(
+                            &mut prover_traces,
+                            &execution_trace,
+                            &program_traces,
+                            black_box(&view),
+                            chunk_rows,
+                        );
+                    })
+                });
+            }
+        }
+        group.finish();
+    }
+}
+
+fn fill_main_trace_chunks(
+    prover_traces: &mut TracesBuilder,
+    execution_trace: &UniformTrace,
+    program_memory: &ProgramTracesBuilder,
+    view: &View,
+    chunk_rows: usize,
+) {
+    let mut prover_side_note = SideNote::new(program_memory, view);
+    let program_steps: Vec<_> =
+        iter_program_steps(execution_trace, prover_traces.num_rows()).collect();
+    let ext_config = ExtensionsConfig::default();
+    for (i, steps) in program_steps.chunks(chunk_rows).enumerate() {
+        let rows = i * chunk_rows..i * chunk_rows + steps.len();
+        BaseComponent::fill_main_trace_chunk(
+            black_box(prover_traces),
+            black_box(rows),
+            black_box(steps),
+            black_box(&mut prover_side_note),
+            black_box(&ext_config),
+        )
+    }
+}
+
 fn fill_main_trace(
     prover_traces: &mut TracesBuilder,
     execution_trace: &UniformTrace,
@@ -173,3 +246,24 @@ fn program_trace(log_size: u32) -> Vec<BasicBlock> {
     .collect();
     vec![BasicBlock::new(insts)]
 }
+
+/// Alternately stores a register to memory and loads it back into the next register.
+fn load_store_program_trace(log_size: u32) -> Vec<BasicBlock> {
+    let insts = std::iter::once(Instruction::new_ir(
+        Opcode::from(BuiltinOpcode::ADDI),
+        1,
+        0,
+        1,
+    ))
+    .chain((0..).map(|i: u32| {
+        let offset = 4 * (i % 64);
+        if i % 2 == 0 {
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 0, 1, offset)
+        } else {
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 1, 0, offset - 4)
+        }
+    }))
+    .take(1 << log_size)
+    .collect();
+    vec![BasicBlock::new(insts)]
+}
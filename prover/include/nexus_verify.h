@@ -0,0 +1,40 @@
+/*
+ * C declarations of the verifier ABI of nexus-vm-prover, built with the `capi` feature.
+ * See the documentation of the `capi` module for the encoding of the inputs and memory ownership.
+ */
+#ifndef NEXUS_VERIFY_H
+#define NEXUS_VERIFY_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define NEXUS_OK 0
+#define NEXUS_ERR_NULL_POINTER -1
+#define NEXUS_ERR_MALFORMED_PROOF -2
+#define NEXUS_ERR_MALFORMED_PUBLIC_DATA -3
+#define NEXUS_ERR_COMPONENTS_MISMATCH -4
+#define NEXUS_ERR_VERIFICATION_FAILED -5
+#define NEXUS_ERR_PANIC -6
+
+/*
+ * Verifies a serialized proof against the serialized public data of the execution.
+ * The buffers are borrowed for the duration of the call. Returns NEXUS_OK or a negative error code.
+ */
+int32_t nexus_verify(const uint8_t *proof_ptr, size_t proof_len, const uint8_t *public_data_ptr,
+                     size_t public_data_len);
+
+/*
+ * Message of the error of the last nexus_verify call on this thread, or NULL. Owned by the library and valid
+ * until the next nexus_verify call on the same thread, must not be freed.
+ */
+const char *nexus_last_error_message(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* NEXUS_VERIFY_H */
@@ -58,6 +58,16 @@ pub fn generate_impls(input: TokenStream) -> syn::Result<TokenStream> {
         }
     };
 
+    let name_iter = ident_iter.clone().map(|ident| ident.to_string());
+    let name_impl = quote! {
+        #[doc = "Returns the name of the variant, independent of its `Debug` output."]
+        pub const fn name(self) -> &'static str {
+            match self {
+                #( Self::#ident_iter => #name_iter, )*
+            }
+        }
+    };
+
     let ident_iter = _ident_iter;
     let mut offset = 0usize;
     let offset_iter = variants.iter().map(|v| {
@@ -85,6 +95,8 @@ pub fn generate_impls(input: TokenStream) -> syn::Result<TokenStream> {
 
             #offset_impl
 
+            #name_impl
+
             #all_variants_impl
 
             #string_id_impl
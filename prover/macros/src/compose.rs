@@ -0,0 +1,154 @@
+use std::collections::HashSet;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    braced,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Token,
+};
+
+/// `#[attrs] pub enum Name { GroupA, path::GroupB, ... }`
+struct ComposeInput {
+    attrs: Vec<syn::Attribute>,
+    vis: syn::Visibility,
+    ident: syn::Ident,
+    groups: Punctuated<syn::Path, Token![,]>,
+}
+
+impl Parse for ComposeInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        input.parse::<Token![enum]>()?;
+        let ident = input.parse()?;
+        let content;
+        braced!(content in input);
+        let groups = content.parse_terminated(syn::Path::parse)?;
+        Ok(Self {
+            attrs,
+            vis,
+            ident,
+            groups,
+        })
+    }
+}
+
+pub fn generate_composition(input: TokenStream) -> syn::Result<TokenStream> {
+    let ComposeInput {
+        attrs,
+        vis,
+        ident,
+        groups,
+    } = syn::parse2(input)?;
+
+    if groups.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &ident,
+            "at least one group expected",
+        ));
+    }
+    let mut variants = Vec::with_capacity(groups.len());
+    let mut seen = HashSet::new();
+    for group in &groups {
+        let variant = group
+            .segments
+            .last()
+            .map(|segment| segment.ident.clone())
+            .ok_or_else(|| syn::Error::new_spanned(group, "group type expected"))?;
+        if !seen.insert(variant.to_string()) {
+            return Err(syn::Error::new_spanned(group, "repeating column group"));
+        }
+        variants.push(variant);
+    }
+    let groups: Vec<_> = groups.into_iter().collect();
+    let num_groups = groups.len();
+
+    // Group `i` starts right after groups `0..i`.
+    let group_offsets = (0..num_groups).map(|i| {
+        let preceding = &groups[..i];
+        quote! { 0 #( + #preceding::COLUMNS_NUM )* }
+    });
+    let group_idx = 0..num_groups;
+    let group_names = variants.iter().map(|variant| variant.to_string());
+
+    let from_impls = groups.iter().zip(&variants).map(|(group, variant)| {
+        quote! {
+            impl ::core::convert::From<#group> for #ident {
+                fn from(col: #group) -> Self {
+                    Self::#variant(col)
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis enum #ident {
+            #( #variants(#groups), )*
+        }
+
+        impl #ident {
+            #[doc = "Constant sum of all groups sizes."]
+            pub const COLUMNS_NUM: usize = 0 #( + #groups::COLUMNS_NUM )*;
+
+            #[doc = "Starting offset of each group, in composition order."]
+            pub const GROUP_OFFSETS: [usize; #num_groups] = [#( #group_offsets, )*];
+
+            #[doc = "Returns the number of actual columns used by this variant."]
+            pub const fn size(self) -> usize {
+                match self {
+                    #( Self::#variants(col) => col.size(), )*
+                }
+            }
+
+            #[doc = "Returns the starting offset index for a variant."]
+            pub const fn offset(self) -> usize {
+                match self {
+                    #( Self::#variants(col) => Self::GROUP_OFFSETS[#group_idx] + col.offset(), )*
+                }
+            }
+
+            #[doc = "Returns the name of the group of the variant."]
+            pub const fn group_name(self) -> &'static str {
+                match self {
+                    #( Self::#variants(_) => #group_names, )*
+                }
+            }
+
+            #[doc = "Returns the name of the variant within its group."]
+            pub const fn name(self) -> &'static str {
+                match self {
+                    #( Self::#variants(col) => col.name(), )*
+                }
+            }
+
+            #[doc = "Returns all variants of all groups, in layout order."]
+            pub fn all_variants() -> impl ::core::iter::Iterator<Item = Self> {
+                ::core::iter::empty::<Self>()
+                    #( .chain(#groups::ALL_VARIANTS.iter().map(|&col| Self::#variants(col))) )*
+            }
+        }
+
+        #(#from_impls)*
+
+        const _: () = {
+            // Groups are laid out back to back: each one ends where the next one starts and the last one ends
+            // at the total number of columns, so no two groups share a column.
+            let offsets = #ident::GROUP_OFFSETS;
+            let sizes = [#( #groups::COLUMNS_NUM, )*];
+            let mut i = 0;
+            while i < #num_groups {
+                assert!(sizes[i] > 0, "empty column group");
+                let end = offsets[i] + sizes[i];
+                if i + 1 < #num_groups {
+                    assert!(end == offsets[i + 1], "overlapping column groups");
+                } else {
+                    assert!(end == #ident::COLUMNS_NUM, "overlapping column groups");
+                }
+                i += 1;
+            }
+        };
+    })
+}
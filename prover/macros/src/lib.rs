@@ -1,9 +1,10 @@
 use proc_macro::TokenStream;
 
 mod column_enum;
+mod compose;
 
-/// Implements public `size` and `offset` **const** methods on a unit-variant
-/// enum, and defines `COLUMNS_NUM` constant.
+/// Implements public `size`, `offset` and `name` **const** methods on a
+/// unit-variant enum, and defines `COLUMNS_NUM` constant.
 ///
 /// These are usual enum methods and not part of any traits, because
 /// traits only allow associated constants, not constant functions.
@@ -25,6 +26,7 @@ mod column_enum;
 ///     C,
 /// }
 /// assert_eq!(Column::COLUMNS_NUM, 1 + 4 + 5);
+/// assert_eq!(Column::B.name(), "B");
 /// ```
 #[proc_macro_derive(ColumnsEnum, attributes(size, column_derive))]
 pub fn derive_columns_enum(input: TokenStream) -> TokenStream {
@@ -32,3 +34,49 @@ pub fn derive_columns_enum(input: TokenStream) -> TokenStream {
         .map(Into::into)
         .unwrap_or_else(|err| err.into_compile_error().into())
 }
+
+/// Composes a global column layout from enums deriving [`ColumnsEnum`](macro@ColumnsEnum).
+///
+/// Generates an enum with one tuple variant per group, named after the group type, together with
+/// `From<Group>` conversions, the same `COLUMNS_NUM`, `size`, `offset` and `name` items as the derive macro and
+/// `group_name`.
+/// Groups are laid out back to back in the listed order, `GROUP_OFFSETS` holds their starting offsets.
+/// Listing a group twice is rejected, and a compile-time assertion checks that no two groups share a column.
+///
+/// Attributes are copied to the generated enum, which must derive at least `Copy` and `Clone`.
+///
+/// ```
+/// use nexus_vm_prover_macros::{compose_columns, ColumnsEnum};
+/// #[derive(Copy, Clone, ColumnsEnum)]
+/// enum CpuColumns {
+///     #[size = 4]
+///     Pc,
+/// }
+/// #[derive(Copy, Clone, ColumnsEnum)]
+/// enum AddColumns {
+///     #[size = 1]
+///     IsAdd,
+///     #[size = 4]
+///     Carry,
+/// }
+/// compose_columns! {
+///     #[derive(Copy, Clone, Debug, PartialEq)]
+///     enum GlobalColumn {
+///         CpuColumns,
+///         AddColumns,
+///     }
+/// }
+/// // Columns layout:
+/// // Pc0 Pc1 Pc2 Pc3 IsAdd0 Carry0 Carry1 Carry2 Carry3
+/// assert_eq!(GlobalColumn::COLUMNS_NUM, 4 + 1 + 4);
+/// assert_eq!(GlobalColumn::from(AddColumns::Carry).offset(), 5);
+/// assert_eq!(GlobalColumn::all_variants().count(), 3);
+/// assert_eq!(GlobalColumn::from(AddColumns::Carry).group_name(), "AddColumns");
+/// assert_eq!(GlobalColumn::from(AddColumns::Carry).name(), "Carry");
+/// ```
+#[proc_macro]
+pub fn compose_columns(input: TokenStream) -> TokenStream {
+    compose::generate_composition(input.into())
+        .map(Into::into)
+        .unwrap_or_else(|err| err.into_compile_error().into())
+}
@@ -0,0 +1,44 @@
+//! Cooperative cancellation of in-flight proving.
+//!
+//! [`Machine::prove_cancellable`](crate::machine::Machine::prove_cancellable) checks the token at phase boundaries
+//! and periodically while filling the main trace. Once cancelled, proving returns [`ProvingAborted`] and drops all
+//! traces and commitments, instead of leaking them as killing the proving thread would.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use crate::error::ProvingAborted;
+
+/// Shared flag requesting cancellation of proving, cheap to clone and send to other threads.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests cancellation, proving stops at the next check of the token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn check(&self) -> Result<(), ProvingAborted> {
+        if self.is_cancelled() {
+            Err(ProvingAborted)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Checks an optional token, proving without a token can't be cancelled.
+pub(crate) fn check(token: Option<&CancellationToken>) -> Result<(), ProvingAborted> {
+    token.map_or(Ok(()), CancellationToken::check)
+}
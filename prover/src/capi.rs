@@ -0,0 +1,398 @@
+//! C ABI for verifying proofs, enabled with the `capi` feature.
+//!
+//! Hosts written in C or C++ verify a proof with [`nexus_verify`], passing two byte buffers:
+//!
+//! - the proof, in its canonical (postcard) serialization, the payload of the compressed container of the
+//!   `compression` feature;
+//! - the public data of the execution, a [`PublicData`] in its postcard serialization, see
+//!   [`PublicData::to_bytes`].
+//!
+//! Together they form the proof bundle: everything the verifier needs, without a [`View`]. The function returns
+//! [`NEXUS_OK`] if the proof is valid for the public data, and one of the negative `NEXUS_ERR_*` codes otherwise.
+//! The message of the last error is returned by [`nexus_last_error_message`].
+//!
+//! Both buffers are untrusted. Malformed encodings are rejected with an error code, and a panic while decoding or
+//! verifying is caught at the boundary and reported as [`NEXUS_ERR_PANIC`], so no unwinding crosses into the host.
+//! This relies on the default `panic = "unwind"` strategy, with `panic = "abort"` a panic aborts the host process.
+//!
+//! # Memory ownership
+//!
+//! The input buffers are owned by the caller and only borrowed for the duration of the call, the library doesn't
+//! keep pointers to them. The error message is owned by the library: it is valid until the next call to
+//! [`nexus_verify`] on the same thread, and must not be freed or modified by the caller.
+//!
+//! # Building
+//!
+//! The crate builds as an `rlib`, a static library for linking into a host is built with
+//!
+//! ```sh
+//! cargo rustc -p nexus-vm-prover --release --features capi --crate-type staticlib
+//! ```
+//!
+//! The matching declarations are in `prover/include/nexus_verify.h`.
+
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CString},
+    panic::{self, AssertUnwindSafe},
+    ptr, slice,
+};
+
+use nexus_vm::emulator::{
+    InternalView, MemoryInitializationEntry, ProgramInfo, ProgramMemoryEntry, PublicOutputEntry,
+    View,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    deser::DeserLimits,
+    machine::{BaseComponent, Machine},
+    Proof, VerificationError,
+};
+
+/// The proof is valid for the public data.
+pub const NEXUS_OK: i32 = 0;
+/// A pointer is null while its length is not zero.
+pub const NEXUS_ERR_NULL_POINTER: i32 = -1;
+/// The proof bytes are not a serialized proof.
+pub const NEXUS_ERR_MALFORMED_PROOF: i32 = -2;
+/// The public data bytes are not serialized [`PublicData`], or the public data doesn't fit the proof.
+pub const NEXUS_ERR_MALFORMED_PUBLIC_DATA: i32 = -3;
+/// The proof was generated for a different set of components than the verifier expects.
+pub const NEXUS_ERR_COMPONENTS_MISMATCH: i32 = -4;
+/// The proof doesn't verify against the public data.
+pub const NEXUS_ERR_VERIFICATION_FAILED: i32 = -5;
+/// Decoding or verification panicked, which is a bug in the verifier.
+pub const NEXUS_ERR_PANIC: i32 = -6;
+
+/// Public data of an execution, which the proof is verified against.
+///
+/// Memory entries are `(address, byte)` pairs, as in the [`View`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicData {
+    /// Program counter where the execution starts.
+    pub initial_pc: u32,
+    /// `(pc, instruction word)` of every instruction of the program.
+    pub program: Vec<(u32, u32)>,
+    /// Arguments passed to the entry point in registers a0 to a7.
+    pub initial_args: Vec<u32>,
+    pub associated_data: Vec<u8>,
+    /// Read-only and read-write initial memory, followed by the public input.
+    pub init_memory: Vec<(u32, u8)>,
+    pub exit_code: Vec<(u32, u8)>,
+    pub public_output: Vec<(u32, u8)>,
+}
+
+impl PublicData {
+    /// Extracts the public data from the view of an execution.
+    pub fn from_view(view: &View) -> Self {
+        let program_info = view.get_program_memory();
+        let init_memory = [
+            view.get_ro_initial_memory(),
+            view.get_rw_initial_memory(),
+            view.get_public_input(),
+        ]
+        .concat();
+        Self {
+            initial_pc: program_info.initial_pc,
+            program: program_info
+                .program
+                .iter()
+                .map(|entry| (entry.pc, entry.instruction_word))
+                .collect(),
+            initial_args: program_info.initial_args.clone(),
+            associated_data: view.view_associated_data().unwrap_or_default(),
+            init_memory: init_memory
+                .iter()
+                .map(|entry| (entry.address, entry.value))
+                .collect(),
+            exit_code: public_entries(view.get_exit_code()),
+            public_output: public_entries(view.get_public_output()),
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        postcard::to_stdvec(self).expect("public data is serializable")
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, postcard::Error> {
+        postcard::from_bytes(bytes)
+    }
+
+    /// Verifies `proof` against the public data.
+    pub fn verify(&self, proof: Proof) -> Result<(), VerificationError> {
+        let program_info = ProgramInfo {
+            initial_pc: self.initial_pc,
+            program: self
+                .program
+                .iter()
+                .map(|&(pc, instruction_word)| ProgramMemoryEntry {
+                    pc,
+                    instruction_word,
+                })
+                .collect(),
+            initial_args: self.initial_args.clone(),
+        };
+        let init_memory: Vec<_> = self
+            .init_memory
+            .iter()
+            .map(|&(address, value)| MemoryInitializationEntry::new(address, value))
+            .collect();
+        let to_entries = |entries: &[(u32, u8)]| -> Vec<_> {
+            entries
+                .iter()
+                .map(|&(address, value)| PublicOutputEntry::new(address, value))
+                .collect()
+        };
+        Machine::<BaseComponent>::verify(
+            proof,
+            &program_info,
+            &self.associated_data,
+            &init_memory,
+            &to_entries(&self.exit_code),
+            &to_entries(&self.public_output),
+        )
+    }
+}
+
+fn public_entries(entries: &[PublicOutputEntry]) -> Vec<(u32, u8)> {
+    entries
+        .iter()
+        .map(|entry| (entry.address, entry.value))
+        .collect()
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    // Messages don't contain NUL bytes, other than through a panic payload, which is cut at the first one.
+    let message = CString::new(message).unwrap_or_else(|err| {
+        let nul_position = err.nul_position();
+        let mut bytes = err.into_vec();
+        bytes.truncate(nul_position);
+        CString::new(bytes).expect("bytes are cut before the first NUL")
+    });
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some(message));
+}
+
+/// Returns the bytes of a buffer passed by the host, rejecting a null pointer unless the buffer is empty.
+///
+/// # Safety
+///
+/// A non-null `ptr` must be valid for reads of `len` bytes for the lifetime `'a`.
+unsafe fn input_slice<'a>(
+    ptr: *const u8,
+    len: usize,
+    name: &str,
+) -> Result<&'a [u8], (i32, String)> {
+    if len == 0 {
+        return Ok(&[]);
+    }
+    if ptr.is_null() {
+        return Err((
+            NEXUS_ERR_NULL_POINTER,
+            format!("{name} pointer is null with length {len}"),
+        ));
+    }
+    Ok(slice::from_raw_parts(ptr, len))
+}
+
+fn verify_bytes(proof: &[u8], public_data: &[u8]) -> Result<(), (i32, String)> {
+    let proof = Proof::from_bytes_bounded(proof, DeserLimits::default())
+        .map_err(|err| (NEXUS_ERR_MALFORMED_PROOF, format!("malformed proof: {err}")))?;
+    let public_data = PublicData::from_bytes(public_data).map_err(|err| {
+        (
+            NEXUS_ERR_MALFORMED_PUBLIC_DATA,
+            format!("malformed public data: {err}"),
+        )
+    })?;
+    if public_data.initial_args.len() > ProgramInfo::MAX_INITIAL_ARGS {
+        return Err((
+            NEXUS_ERR_MALFORMED_PUBLIC_DATA,
+            format!(
+                "malformed public data: {} initial arguments, at most {} are supported",
+                public_data.initial_args.len(),
+                ProgramInfo::MAX_INITIAL_ARGS
+            ),
+        ));
+    }
+
+    public_data.verify(proof).map_err(|err| {
+        let code = match err {
+            VerificationError::Stwo(_) => NEXUS_ERR_VERIFICATION_FAILED,
+            VerificationError::ComponentsMismatch { .. } => NEXUS_ERR_COMPONENTS_MISMATCH,
+            VerificationError::UnsortedPublicOutput { .. }
+            | VerificationError::PublicMemoryOverflow
+            | VerificationError::ProgramTemplate(_) => NEXUS_ERR_MALFORMED_PUBLIC_DATA,
+        };
+        (code, err.to_string())
+    })
+}
+
+/// Verifies a proof against the public data of the execution, see the [module documentation](self).
+///
+/// Returns [`NEXUS_OK`] if the proof is valid, and a negative `NEXUS_ERR_*` code otherwise, with the message
+/// available from [`nexus_last_error_message`].
+///
+/// # Safety
+///
+/// Unless its length is zero, each pointer must be valid for reads of its length in bytes, and the memory must not
+/// be written to during the call.
+#[no_mangle]
+pub unsafe extern "C" fn nexus_verify(
+    proof_ptr: *const u8,
+    proof_len: usize,
+    public_data_ptr: *const u8,
+    public_data_len: usize,
+) -> i32 {
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = None);
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let proof = input_slice(proof_ptr, proof_len, "proof")?;
+        let public_data = input_slice(public_data_ptr, public_data_len, "public data")?;
+        verify_bytes(proof, public_data)
+    }));
+    match result {
+        Ok(Ok(())) => NEXUS_OK,
+        Ok(Err((code, message))) => {
+            set_last_error(message);
+            code
+        }
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic payload".to_string());
+            set_last_error(format!("verifier panicked: {message}"));
+            NEXUS_ERR_PANIC
+        }
+    }
+}
+
+/// Returns the message of the error reported by the last call to [`nexus_verify`] on this thread, as a
+/// NUL-terminated UTF-8 string, or null if the call succeeded or there was none.
+///
+/// The string is owned by the library and valid until the next call to [`nexus_verify`] on the same thread, the
+/// caller must not free it.
+#[no_mangle]
+pub extern "C" fn nexus_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last_error| {
+        last_error
+            .borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+
+    use nexus_vm::{
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+        trace::k_trace_direct,
+    };
+
+    use super::*;
+
+    fn bundle() -> (Vec<u8>, Vec<u8>) {
+        let basic_block = BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 3, 2, 1),
+        ]);
+        let (view, trace) = k_trace_direct(&vec![basic_block], 1).expect("error generating trace");
+        let proof = Machine::<BaseComponent>::prove(&trace, &view).unwrap();
+        (
+            postcard::to_stdvec(&proof).unwrap(),
+            PublicData::from_view(&view).to_bytes(),
+        )
+    }
+
+    fn verify(proof: &[u8], public_data: &[u8]) -> i32 {
+        unsafe {
+            nexus_verify(
+                proof.as_ptr(),
+                proof.len(),
+                public_data.as_ptr(),
+                public_data.len(),
+            )
+        }
+    }
+
+    fn last_error_message() -> String {
+        let message = nexus_last_error_message();
+        assert!(!message.is_null());
+        unsafe { CStr::from_ptr(message) }
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn verify_valid_bundle() {
+        let (proof, public_data) = bundle();
+        assert_eq!(verify(&proof, &public_data), NEXUS_OK);
+        assert!(nexus_last_error_message().is_null());
+    }
+
+    #[test]
+    fn reject_corrupted_bundle() {
+        let (proof, public_data) = bundle();
+
+        let null_proof = unsafe {
+            nexus_verify(
+                ptr::null(),
+                proof.len(),
+                public_data.as_ptr(),
+                public_data.len(),
+            )
+        };
+        assert_eq!(null_proof, NEXUS_ERR_NULL_POINTER);
+        assert!(last_error_message().contains("proof pointer is null"));
+
+        assert_eq!(verify(&[], &public_data), NEXUS_ERR_MALFORMED_PROOF);
+        assert!(!last_error_message().is_empty());
+        assert_eq!(
+            verify(&proof[..proof.len() / 2], &public_data),
+            NEXUS_ERR_MALFORMED_PROOF
+        );
+
+        assert_eq!(
+            verify(&proof, &public_data[..public_data.len() - 1]),
+            NEXUS_ERR_MALFORMED_PUBLIC_DATA
+        );
+        assert!(last_error_message().starts_with("malformed public data"));
+
+        // A different program is a different statement.
+        let mut other = PublicData::from_bytes(&public_data).unwrap();
+        other.program[1].1 ^= 1 << 20;
+        let code = verify(&proof, &other.to_bytes());
+        assert!(code < 0);
+        assert!(!last_error_message().is_empty());
+
+        let mut tampered: Proof = postcard::from_bytes(&proof).unwrap();
+        tampered.num_steps += 1;
+        assert_eq!(
+            verify(&postcard::to_stdvec(&tampered).unwrap(), &public_data),
+            NEXUS_ERR_VERIFICATION_FAILED
+        );
+        assert!(!last_error_message().is_empty());
+
+        // Some bytes are informational, e.g. the crate version, but no flipped byte unwinds across the call.
+        for idx in (0..proof.len()).step_by(proof.len() / 16 + 1) {
+            let mut corrupted = proof.clone();
+            corrupted[idx] ^= 0x55;
+            let code = verify(&corrupted, &public_data);
+            assert!(code <= NEXUS_OK);
+            assert_eq!(code == NEXUS_OK, nexus_last_error_message().is_null());
+        }
+
+        // A failed call doesn't leave its error behind for a successful one.
+        assert_eq!(verify(&proof, &public_data), NEXUS_OK);
+        assert!(nexus_last_error_message().is_null());
+    }
+}
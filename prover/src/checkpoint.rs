@@ -0,0 +1,165 @@
+//! Checkpointing of long-running proving jobs.
+//!
+//! A [`ProverCheckpoint`] is taken once all traces are generated, right before the interaction trace is committed
+//! to. It stores the evaluations of every committed tree together with log sizes, the number of steps and claimed
+//! sums, so that a job resumed with [`Machine::prove_resumable`](crate::machine::Machine::prove_resumable) skips
+//! trace generation.
+//!
+//! The stwo commitment scheme and channel are not serializable, instead they are rebuilt on resume by committing
+//! to the stored evaluations again. Commitments are deterministic, therefore the channel ends up in the same state
+//! and the resulting proof is identical to the one produced by an uninterrupted run.
+//!
+//! Checkpointing right after main trace finalization is not supported: generating the interaction trace also
+//! requires the side note and extension traces, which aren't serializable.
+//!
+//! A checkpoint records the hashes of the program and of the public input it was created for, and the digest of
+//! its stored trees. Resuming with another program or input, or from trees that don't match their digest, fails
+//! with [`NexusProvingError::InvalidCheckpoint`].
+
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+use stwo::{
+    core::{
+        fields::{m31::BaseField, qm31::SecureField},
+        poly::circle::CanonicCoset,
+    },
+    prover::{
+        backend::simd::{column::BaseColumn, SimdBackend},
+        poly::{circle::CircleEvaluation, BitReversedOrder},
+    },
+};
+
+use nexus_vm::emulator::{InternalView, View};
+use tiny_keccak::{Hasher, Keccak};
+
+use crate::{error::NexusProvingError, trace::program_trace::ProgramTraceTemplate};
+
+const CHECKPOINT_FILE: &str = "checkpoint.bin";
+
+/// Serialized state of a proving job, see the [module documentation](self).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProverCheckpoint {
+    pub(crate) components_id: [u8; 32],
+    /// See [`ProgramTraceTemplate::program_hash`].
+    pub(crate) program_hash: [u8; 32],
+    /// See [`public_input_hash`].
+    pub(crate) public_input_hash: [u8; 32],
+    /// Digest of the main and program trees, as in [`TraceBundle::trace_digest`](crate::machine::TraceBundle::trace_digest).
+    pub(crate) trace_digest: [u8; 32],
+    pub(crate) log_size: Vec<u32>,
+    pub(crate) num_steps: u32,
+    pub(crate) committed_output_len: Option<u32>,
+    pub(crate) claimed_sum: Vec<SecureField>,
+    /// Main, interaction and program trees, in tree order. The preprocessed tree only depends on the log sizes and
+    /// is committed to again on resume.
+    pub(crate) trees: [Vec<CheckpointColumn>; 3],
+}
+
+/// A single column of a committed tree, evaluated over the canonic coset of the given size.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct CheckpointColumn {
+    log_size: u32,
+    values: Vec<BaseField>,
+}
+
+impl ProverCheckpoint {
+    /// Returns the identifier of the components the checkpoint was created for.
+    pub fn components_id(&self) -> [u8; 32] {
+        self.components_id
+    }
+
+    /// Checks that the checkpoint was created for the program and public input of `view`.
+    pub(crate) fn check_execution(&self, view: &View) -> Result<(), NexusProvingError> {
+        if self.program_hash != ProgramTraceTemplate::program_hash(view.get_program_memory()) {
+            return Err(NexusProvingError::InvalidCheckpoint(
+                "checkpoint was created for a different program".to_string(),
+            ));
+        }
+        if self.public_input_hash != public_input_hash(view) {
+            return Err(NexusProvingError::InvalidCheckpoint(
+                "checkpoint was created for a different public input".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Writes the checkpoint to `dir`, replacing any previous checkpoint stored there.
+    ///
+    /// The file is written under a temporary name first, so that an interrupted save never leaves a truncated
+    /// checkpoint behind.
+    pub fn save(&self, dir: impl AsRef<Path>) -> Result<(), NexusProvingError> {
+        let dir = dir.as_ref();
+        let bytes = postcard::to_stdvec(self)
+            .map_err(|err| NexusProvingError::InvalidCheckpoint(err.to_string()))?;
+
+        fs::create_dir_all(dir)?;
+        let tmp_path = dir.join(format!("{CHECKPOINT_FILE}.tmp"));
+        fs::write(&tmp_path, bytes)?;
+        fs::rename(tmp_path, dir.join(CHECKPOINT_FILE))?;
+        Ok(())
+    }
+
+    /// Reads the checkpoint stored in `dir`, returns `None` if there is none.
+    pub fn load(dir: impl AsRef<Path>) -> Result<Option<Self>, NexusProvingError> {
+        let bytes = match fs::read(dir.as_ref().join(CHECKPOINT_FILE)) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let checkpoint: Self = postcard::from_bytes(&bytes)
+            .map_err(|err| NexusProvingError::InvalidCheckpoint(err.to_string()))?;
+
+        let malformed = checkpoint
+            .trees
+            .iter()
+            .flatten()
+            .any(|col| col.values.len() != 1 << col.log_size);
+        if malformed || checkpoint.log_size.len() != checkpoint.claimed_sum.len() {
+            return Err(NexusProvingError::InvalidCheckpoint(
+                "malformed checkpoint data".to_string(),
+            ));
+        }
+        Ok(Some(checkpoint))
+    }
+}
+
+/// Returns the Keccak-256 hash of the associated data followed by the read-only, read-write and public input
+/// entries of `view`, each as its address and byte.
+pub(crate) fn public_input_hash(view: &View) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    let associated_data = view.view_associated_data().unwrap_or_default();
+    hasher.update(&(associated_data.len() as u64).to_le_bytes());
+    hasher.update(&associated_data);
+    for entry in [
+        view.get_ro_initial_memory(),
+        view.get_rw_initial_memory(),
+        view.get_public_input(),
+    ]
+    .concat()
+    {
+        hasher.update(&entry.address.to_le_bytes());
+        hasher.update(&[entry.value]);
+    }
+    let mut hash = [0u8; 32];
+    hasher.finalize(&mut hash);
+    hash
+}
+
+impl CheckpointColumn {
+    pub(crate) fn from_circle_evaluation(
+        eval: &CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>,
+    ) -> Self {
+        Self {
+            log_size: eval.domain.log_size(),
+            values: eval.values.to_cpu(),
+        }
+    }
+
+    pub(crate) fn into_circle_evaluation(
+        self,
+    ) -> CircleEvaluation<SimdBackend, BaseField, BitReversedOrder> {
+        let domain = CanonicCoset::new(self.log_size).circle_domain();
+        CircleEvaluation::new(domain, BaseColumn::from_iter(self.values))
+    }
+}
@@ -0,0 +1,417 @@
+//! Type-level composition of the component tuple of a [`Machine`](crate::machine::Machine).
+//!
+//! Chips are filled and evaluated in tuple order, and some of them consume values filled by others: the CPU and
+//! decoding chips come first, memory and range checks come last, and the chips whose lookup relations are used by
+//! base extensions must always be present. [`components!`](crate::components) keeps these chips at fixed positions
+//! and only accepts [`InstructionComponent`]s in between, so that a misordered set fails to compile.
+
+use std::{any::TypeId, marker::PhantomData, ops::Range};
+
+use impl_trait_for_tuples::impl_for_tuples;
+use nexus_vm::riscv::BuiltinOpcode;
+use stwo::core::channel::Channel;
+use stwo_constraint_framework::EvalAtRow;
+
+use super::{
+    AddChip, AuipcChip, BeqChip, BgeChip, BgeuChip, BltChip, BltuChip, BneChip,
+    CustomInstructionChip, JalChip, JalrChip, LuiChip, MExtensionChips, SllChip, SltChip, SltuChip,
+    SraChip, SrlChip, SubChip, SyscallChip,
+};
+use crate::{
+    components::AllLookupElements,
+    description::ChipDescription,
+    extensions::ExtensionsConfig,
+    trace::{
+        eval::TraceEval, logup::LogupTraceBuilder, preprocessed::PreprocessedTraces,
+        program_trace::ProgramTraces, sidenote::SideNote, FinalizedTraces, ProgramStep,
+        TracesBuilder,
+    },
+    traits::{MachineChip, SideNoteState},
+};
+
+/// Chip constraining a subset of instructions, which can be combined with other such chips in any order.
+pub trait InstructionComponent: MachineChip + 'static {
+    /// Always `Self`, projecting through it enforces the trait bound in type position.
+    type Chip: MachineChip;
+}
+
+macro_rules! impl_instruction_component {
+    ($($chip:ty),* $(,)?) => {
+        $(
+            impl InstructionComponent for $chip {
+                type Chip = Self;
+            }
+        )*
+    };
+}
+
+impl_instruction_component!(
+    AddChip,
+    SubChip,
+    SltuChip,
+    SltChip,
+    BneChip,
+    BeqChip,
+    BltuChip,
+    BltChip,
+    BgeuChip,
+    BgeChip,
+    JalChip,
+    LuiChip,
+    AuipcChip,
+    JalrChip,
+    SllChip,
+    SrlChip,
+    SraChip,
+    SyscallChip,
+    MExtensionChips,
+    CustomInstructionChip,
+);
+
+/// Tuple of [`InstructionComponent`]s excluded from the base component set, see [`components!`](crate::components).
+pub trait ExcludedChips: 'static {
+    /// Returns whether the chip with `type_id` is one of the tuple.
+    fn contains(type_id: TypeId) -> bool;
+}
+
+#[impl_for_tuples(1, 28)]
+impl ExcludedChips for Tuple {
+    for_tuples!( where #( Tuple: InstructionComponent )* );
+
+    fn contains(type_id: TypeId) -> bool {
+        for_tuples!( #( if TypeId::of::<Tuple>() == type_id { return true; } )* );
+        false
+    }
+}
+
+/// Chip `C` of the base component set, which does nothing if it is one of the chips `X`.
+///
+/// An excluded chip keeps its position in the tuple but fills no columns, adds no constraints and draws no lookup
+/// elements, the same as if it wasn't listed.
+pub struct Excluding<C, X>(PhantomData<(C, X)>);
+
+impl<C: InstructionComponent, X: ExcludedChips> Excluding<C, X> {
+    fn excluded() -> bool {
+        X::contains(TypeId::of::<C>())
+    }
+}
+
+impl<C: InstructionComponent, X: ExcludedChips> MachineChip for Excluding<C, X> {
+    const NAME: &'static str = C::NAME;
+
+    fn collect_names(names: &mut Vec<&'static str>) {
+        if !Self::excluded() {
+            C::collect_names(names);
+        }
+    }
+
+    fn collect_supported_opcodes(opcodes: &mut Vec<BuiltinOpcode>) {
+        if !Self::excluded() {
+            C::collect_supported_opcodes(opcodes);
+        }
+    }
+
+    fn collect_descriptions(descriptions: &mut Vec<ChipDescription>) {
+        if !Self::excluded() {
+            C::collect_descriptions(descriptions);
+        }
+    }
+
+    fn fill_main_trace(
+        traces: &mut TracesBuilder,
+        row_idx: usize,
+        vm_step: &Option<ProgramStep>,
+        side_note: &mut SideNote,
+        config: &ExtensionsConfig,
+    ) {
+        if !Self::excluded() {
+            C::fill_main_trace(traces, row_idx, vm_step, side_note, config);
+        }
+    }
+
+    fn fill_main_trace_chunk(
+        traces: &mut TracesBuilder,
+        rows: Range<usize>,
+        steps: &[Option<ProgramStep>],
+        side_note: &mut SideNote,
+        config: &ExtensionsConfig,
+    ) {
+        if !Self::excluded() {
+            C::fill_main_trace_chunk(traces, rows, steps, side_note, config);
+        }
+    }
+
+    fn collect_side_note_state(states: &mut Vec<SideNoteState>, config: &ExtensionsConfig) {
+        if !Self::excluded() {
+            C::collect_side_note_state(states, config);
+        }
+    }
+
+    fn add_constraints<E: EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &TraceEval<E>,
+        lookup_elements: &AllLookupElements,
+        config: &ExtensionsConfig,
+    ) {
+        if !Self::excluded() {
+            C::add_constraints(eval, trace_eval, lookup_elements, config);
+        }
+    }
+
+    fn add_constraints_by_chip<E: EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &TraceEval<E>,
+        lookup_elements: &AllLookupElements,
+        config: &ExtensionsConfig,
+        on_chip: &mut dyn FnMut(&mut E, &'static str),
+    ) {
+        if !Self::excluded() {
+            C::add_constraints_by_chip(eval, trace_eval, lookup_elements, config, on_chip);
+        }
+    }
+
+    fn fill_interaction_trace(
+        logup_trace_gen: &mut LogupTraceBuilder,
+        original_traces: &FinalizedTraces,
+        preprocessed_traces: &PreprocessedTraces,
+        program_traces: &ProgramTraces,
+        lookup_elements: &AllLookupElements,
+    ) {
+        if !Self::excluded() {
+            C::fill_interaction_trace(
+                logup_trace_gen,
+                original_traces,
+                preprocessed_traces,
+                program_traces,
+                lookup_elements,
+            );
+        }
+    }
+
+    fn draw_lookup_elements(
+        all_elements: &mut AllLookupElements,
+        channel: &mut impl Channel,
+        config: &ExtensionsConfig,
+    ) {
+        if !Self::excluded() {
+            C::draw_lookup_elements(all_elements, channel, config);
+        }
+    }
+}
+
+/// Builds a component tuple for [`Machine`](crate::machine::Machine).
+///
+/// `components!(Base)` is the base component set for the RV32I ISA with the M extension, in the order the chips
+/// are filled and evaluated. `exclude(..)` drops instruction chips from it and `append(..)` adds
+/// [`InstructionComponent`]s after the base instruction chips, in the order they are listed:
+///
+/// ```
+/// use nexus_vm_prover::{
+///     chips::{MExtensionChips, SraChip},
+///     components,
+///     machine::Machine,
+/// };
+///
+/// type NoMulMachine = Machine<components!(Base, exclude(MExtensionChips))>;
+/// type NoSraMachine = Machine<components!(Base, exclude(SraChip), append(MExtensionChips))>;
+/// ```
+///
+/// A list of [`InstructionComponent`]s builds a set from scratch. The listed chips are placed between the CPU and
+/// decoding chips on one side, and the fused LUI/AUIPC chip, the bitwise and load-store chips, memory checks, the
+/// step count and range checks on the other side.
+///
+/// ```
+/// use nexus_vm_prover::{
+///     chips::{AddChip, SubChip},
+///     components,
+///     machine::Machine,
+/// };
+///
+/// type AddSubMachine = Machine<components!(AddChip, SubChip)>;
+/// ```
+///
+/// Only [`InstructionComponent`]s can be listed, excluded or appended, chips with a fixed position are rejected at
+/// compile time. Up to six chips can be appended to the base set.
+#[macro_export]
+macro_rules! components {
+    (@slot [] $chip:ty) => {
+        $chip
+    };
+    (@slot [$($excluded:ty,)+] $chip:ty) => {
+        $crate::chips::Excluding<$chip, ($($excluded,)+)>
+    };
+    (@base [$($excluded:ty,)*] [$($appended:ty,)*]) => {
+        (
+            $crate::chips::CpuChip,
+            $crate::chips::DecodingCheckChip,
+            (
+                $crate::components!(@slot [$($excluded,)*] $crate::chips::AddChip),
+                $crate::components!(@slot [$($excluded,)*] $crate::chips::SubChip),
+                $crate::components!(@slot [$($excluded,)*] $crate::chips::SltuChip),
+                $crate::chips::BitOpChip,
+                $crate::components!(@slot [$($excluded,)*] $crate::chips::SltChip),
+                $crate::components!(@slot [$($excluded,)*] $crate::chips::BneChip),
+                $crate::components!(@slot [$($excluded,)*] $crate::chips::BeqChip),
+                $crate::components!(@slot [$($excluded,)*] $crate::chips::BltuChip),
+                $crate::components!(@slot [$($excluded,)*] $crate::chips::BltChip),
+                $crate::components!(@slot [$($excluded,)*] $crate::chips::BgeuChip),
+                $crate::components!(@slot [$($excluded,)*] $crate::chips::BgeChip),
+                $crate::components!(@slot [$($excluded,)*] $crate::chips::JalChip),
+                $crate::components!(@slot [$($excluded,)*] $crate::chips::LuiChip),
+                $crate::components!(@slot [$($excluded,)*] $crate::chips::AuipcChip),
+                $crate::components!(@slot [$($excluded,)*] $crate::chips::JalrChip),
+                $crate::components!(@slot [$($excluded,)*] $crate::chips::SllChip),
+                $crate::components!(@slot [$($excluded,)*] $crate::chips::SrlChip),
+                $crate::components!(@slot [$($excluded,)*] $crate::chips::SraChip),
+                $crate::chips::LoadStoreChip,
+                $crate::components!(@slot [$($excluded,)*] $crate::chips::SyscallChip),
+                $crate::components!(@slot [$($excluded,)*] $crate::chips::MExtensionChips),
+                $crate::components!(@slot [$($excluded,)*] $crate::chips::CustomInstructionChip),
+                $(<$appended as $crate::chips::InstructionComponent>::Chip,)*
+            ),
+            // Fused rows constrain LUI and AUIPC results into `FusedBase`, and are rejected if neither is listed.
+            $crate::chips::FusedLiChip,
+            $crate::chips::ProgramMemCheckChip,
+            $crate::chips::RegisterMemCheckChip,
+            $crate::chips::TimestampChip,
+            $crate::chips::StepCountChip,
+            // Range checks must be positioned at the end. They use values filled by instruction chips.
+            $crate::chips::RangeCheckChip,
+        )
+    };
+    (
+        Base
+        $(, exclude($($excluded:ty),+ $(,)?))?
+        $(, append($($appended:ty),+ $(,)?))?
+        $(,)?
+    ) => {
+        $crate::components!(@base [$($($excluded,)+)?] [$($($appended,)+)?])
+    };
+    ($($chip:ty),+ $(,)?) => {
+        (
+            $crate::chips::CpuChip,
+            $crate::chips::DecodingCheckChip,
+            ($(<$chip as $crate::chips::InstructionComponent>::Chip,)+),
+            // Fused rows constrain LUI and AUIPC results into `FusedBase`, and are rejected if neither is listed.
+            $crate::chips::FusedLiChip,
+            // Lookup relations of these chips are used by base extensions.
+            $crate::chips::BitOpChip,
+            $crate::chips::LoadStoreChip,
+            $crate::chips::ProgramMemCheckChip,
+            $crate::chips::RegisterMemCheckChip,
+            $crate::chips::TimestampChip,
+            $crate::chips::StepCountChip,
+            // Range checks must be positioned at the end. They use values filled by instruction chips.
+            $crate::chips::RangeCheckChip,
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chips::{
+        BitOpChip, CpuChip, DecodingCheckChip, FusedLiChip, LoadStoreChip, ProgramMemCheckChip,
+        RangeCheckChip, RegisterMemCheckChip, StepCountChip, TimestampChip,
+    };
+
+    fn names<C: MachineChip>() -> Vec<&'static str> {
+        let mut names = Vec::new();
+        C::collect_names(&mut names);
+        names
+    }
+
+    #[test]
+    fn base_keeps_chip_order() {
+        type Flat = (
+            CpuChip,
+            DecodingCheckChip,
+            AddChip,
+            SubChip,
+            SltuChip,
+            BitOpChip,
+            SltChip,
+            BneChip,
+            BeqChip,
+            BltuChip,
+            BltChip,
+            BgeuChip,
+            BgeChip,
+            JalChip,
+            LuiChip,
+            AuipcChip,
+            JalrChip,
+            SllChip,
+            SrlChip,
+            SraChip,
+            LoadStoreChip,
+            SyscallChip,
+            MExtensionChips,
+            CustomInstructionChip,
+            FusedLiChip,
+            ProgramMemCheckChip,
+            RegisterMemCheckChip,
+            (TimestampChip, StepCountChip, RangeCheckChip),
+        );
+        assert_eq!(names::<crate::components!(Base)>(), names::<Flat>());
+    }
+
+    #[test]
+    fn exclude_drops_chips_in_place() {
+        type Excluded = crate::components!(Base, exclude(SraChip, MExtensionChips));
+
+        let mut expected = names::<crate::components!(Base)>();
+        let mut dropped = vec![SraChip::NAME];
+        MExtensionChips::collect_names(&mut dropped);
+        expected.retain(|name| !dropped.contains(name));
+        assert_eq!(names::<Excluded>(), expected);
+
+        let mut opcodes = Vec::new();
+        Excluded::collect_supported_opcodes(&mut opcodes);
+        for opcode in SraChip::supported_opcodes() {
+            assert!(!opcodes.contains(opcode));
+        }
+    }
+
+    #[test]
+    fn append_follows_base_instruction_chips() {
+        type Appended = crate::components!(Base, exclude(MExtensionChips), append(MExtensionChips));
+
+        let base = names::<crate::components!(Base)>();
+        let appended = names::<Appended>();
+        assert_eq!(appended.len(), base.len());
+
+        let mut m_names = Vec::new();
+        MExtensionChips::collect_names(&mut m_names);
+        let position = |names: &[&str], name| names.iter().position(|n| *n == name).unwrap();
+        // The M extension chips move after the custom instruction chip, before the fixed chips.
+        let custom = position(&appended, CustomInstructionChip::NAME);
+        assert_eq!(
+            &appended[custom + 1..custom + 1 + m_names.len()],
+            m_names.as_slice()
+        );
+        assert_eq!(appended[custom + 1 + m_names.len()], FusedLiChip::NAME);
+        assert!(position(&base, m_names[0]) < position(&base, CustomInstructionChip::NAME));
+    }
+
+    #[test]
+    fn list_places_chips_between_fixed_chips() {
+        assert_eq!(
+            names::<crate::components!(AddChip, SubChip)>(),
+            names::<(
+                CpuChip,
+                DecodingCheckChip,
+                AddChip,
+                SubChip,
+                FusedLiChip,
+                BitOpChip,
+                LoadStoreChip,
+                ProgramMemCheckChip,
+                RegisterMemCheckChip,
+                TimestampChip,
+                StepCountChip,
+                RangeCheckChip,
+            )>()
+        );
+    }
+}
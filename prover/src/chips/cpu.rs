@@ -1,15 +1,18 @@
-use num_traits::One;
+use std::ops::Range;
+
+use num_traits::{One, Zero};
 use stwo::core::fields::m31::BaseField;
 use stwo_constraint_framework::EvalAtRow;
 
 use super::utils;
 use crate::{
-    chips::add_with_carries,
+    chips::{add_with_carries, SyscallColumns, SYSCALL_ARG0_REG, SYSCALL_NUMBER_REG},
     column::{
         Column::{self, *},
         PreprocessedColumn,
     },
     components::AllLookupElements,
+    description::{ChipDescription, ConstraintDescription},
     extensions::ExtensionsConfig,
     trace::{
         eval::{preprocessed_trace_eval, trace_eval, trace_eval_next_row, TraceEval},
@@ -20,86 +23,214 @@ use crate::{
     virtual_column::{self, VirtualColumn},
 };
 
-use nexus_common::constants::KECCAKF_OPCODE;
+use nexus_common::{constants::KECCAKF_OPCODE, cpu::OpcodeCost, ecall::Ecall};
 use nexus_vm::{
     riscv::{
         BuiltinOpcode,
         InstructionType::{BType, IType, ITypeShamt, JType, RType, SType, UType, Unimpl},
         Register,
     },
+    trace::Step,
     WORD_SIZE,
 };
+use nexus_vm_prover_macros::ColumnsEnum;
 
+/// Fills and constrains columns shared by all instructions.
+///
+/// Padding rows have `IsPadding` set, which excludes every opcode flag, so instruction chips and register memory
+/// checking treat them as no-ops. Once set, `IsPadding` stays set up to the last row.
 pub struct CpuChip;
 
-impl MachineChip for CpuChip {
-    fn fill_main_trace(
+/// Columns used only by [`CpuChip`].
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, ColumnsEnum)]
+pub enum CpuColumns {
+    /// Auxiliary variable for computing ValueAEffectiveFlag
+    #[size = 1]
+    ValueAEffectiveFlagAux,
+    /// Another auxiliary variable for computing ValueAEffectiveFlag
+    #[size = 1]
+    ValueAEffectiveFlagAuxInv,
+    /// Clock cycles taken by the instruction of the row, see [`OpcodeCost`], zero on padding rows.
+    #[size = 1]
+    Cycles,
+}
+
+/// Opcode flags with the builtin opcodes they stand for. Every opcode of a flag must take the same number of cycles,
+/// rows with the flag set take that many. The keccak flag stands for a custom instruction.
+const FLAG_OPCODES: &[(Column, &[BuiltinOpcode])] = {
+    use BuiltinOpcode::*;
+    &[
+        (IsAdd, &[ADD, ADDI]),
+        (IsSub, &[SUB]),
+        (IsAnd, &[AND, ANDI]),
+        (IsOr, &[OR, ORI]),
+        (IsXor, &[XOR, XORI]),
+        (IsSlt, &[SLT, SLTI]),
+        (IsSltu, &[SLTU, SLTIU]),
+        (IsBne, &[BNE]),
+        (IsBeq, &[BEQ]),
+        (IsBltu, &[BLTU]),
+        (IsBlt, &[BLT]),
+        (IsBgeu, &[BGEU]),
+        (IsBge, &[BGE]),
+        (IsJal, &[JAL]),
+        (IsSb, &[SB]),
+        (IsSh, &[SH]),
+        (IsSw, &[SW]),
+        (IsLui, &[LUI]),
+        (IsAuipc, &[AUIPC]),
+        (IsJalr, &[JALR]),
+        (IsLb, &[LB]),
+        (IsLbu, &[LBU]),
+        (IsLh, &[LH]),
+        (IsLhu, &[LHU]),
+        (IsLw, &[LW]),
+        (IsSll, &[SLL, SLLI]),
+        (IsSrl, &[SRL, SRLI]),
+        (IsSra, &[SRA, SRAI]),
+        (IsMul, &[MUL]),
+        (IsMulh, &[MULH]),
+        (IsMulhsu, &[MULHSU]),
+        (IsMulhu, &[MULHU]),
+        (IsDiv, &[DIV]),
+        (IsDivu, &[DIVU]),
+        (IsRem, &[REM]),
+        (IsRemu, &[REMU]),
+        (IsEcall, &[ECALL]),
+        (IsEbreak, &[EBREAK]),
+        (IsNop, &[ADDI]),
+        (IsCustomKeccak, &[]),
+    ]
+};
+
+/// Syscall flags with the syscalls they stand for, the ones taking a different number of cycles than ECALL. Rows
+/// with the flag set take the cycles of the syscall instead of the cycles of ECALL.
+const SYSCALL_FLAGS: &[(SyscallColumns, Ecall)] = &[(
+    SyscallColumns::IsSysPrivInputWord,
+    Ecall::ReadPrivateInputWord,
+)];
+
+/// Returns the number of cycles taken by rows with a flag of [`FLAG_OPCODES`] set.
+fn flag_cycles(opcodes: &[BuiltinOpcode]) -> u32 {
+    opcodes
+        .first()
+        .map_or(OpcodeCost::DEFAULT_CYCLES, |&opcode| {
+            OpcodeCost::builtin_cycles(opcode)
+        })
+}
+
+/// Pc increment of a step that doesn't jump, fused steps also execute the instruction that follows.
+fn pc_increment(step: &Step) -> u32 {
+    if step.fused.is_some() {
+        2 * WORD_SIZE as u32
+    } else {
+        WORD_SIZE as u32
+    }
+}
+
+impl CpuChip {
+    /// Fills the effective flag of `ValueA` and `IsPadding` on every row, as well as the program counter and the
+    /// instruction word on non-padding rows.
+    fn fill_common_columns(
         traces: &mut TracesBuilder,
-        row_idx: usize,
-        vm_step: &Option<ProgramStep>,
-        _side_note: &mut SideNote,
-        _config: &ExtensionsConfig,
+        rows: Range<usize>,
+        steps: &[Option<ProgramStep>],
     ) {
+        assert_eq!(rows.len(), steps.len(), "chunk size mismatch");
+        let chunk = || rows.clone().zip(steps);
+
         // Fill ValueAEffectiveFlag to the main trace
-        let value_a_effective_flag = match vm_step {
-            Some(vm_step) => vm_step.value_a_effectitve_flag(),
-            None => false,
-        };
-        traces.fill_columns(row_idx, value_a_effective_flag, ValueAEffectiveFlag);
+        traces.fill_columns_batch(
+            chunk().map(|(row_idx, vm_step)| {
+                let flag = vm_step
+                    .as_ref()
+                    .is_some_and(|vm_step| vm_step.value_a_effectitve_flag());
+                (row_idx, flag)
+            }),
+            ValueAEffectiveFlag,
+        );
 
-        // Fill ValueAEffectiveFlagAux to the main trace
+        // Fill ValueAEffectiveFlagAux and ValueAEffectiveFlagAuxInv to the main trace
         // Note op_a is u8 so it is always smaller than M31.
-        let (value_a_effective_flag_aux, value_a_effective_flag_aux_inv) =
-            if let Some(vm_step) = vm_step {
-                let op_a = vm_step.get_op_a();
-                if op_a == Register::X0 {
-                    (BaseField::one(), BaseField::one())
-                } else {
-                    let op_a_element = BaseField::from(op_a as u32);
-                    (BaseField::inverse(&op_a_element), op_a_element)
+        let aux: Vec<_> = chunk()
+            .map(|(row_idx, vm_step)| match vm_step {
+                Some(vm_step) if vm_step.get_op_a() != Register::X0 => {
+                    let op_a_element = BaseField::from(vm_step.get_op_a() as u32);
+                    (row_idx, BaseField::inverse(&op_a_element), op_a_element)
                 }
-            } else {
-                (BaseField::one(), BaseField::one())
-            };
-
-        traces.fill_columns_base_field(
-            row_idx,
-            &[value_a_effective_flag_aux],
-            ValueAEffectiveFlagAux,
+                _ => (row_idx, BaseField::one(), BaseField::one()),
+            })
+            .collect();
+        traces.fill_columns_batch(
+            aux.iter().map(|&(row_idx, aux, _)| (row_idx, [aux])),
+            CpuColumns::ValueAEffectiveFlagAux,
         );
-
-        // Fill ValueAEffectiveFlagAuxInv to the main trace
-        traces.fill_columns_base_field(
-            row_idx,
-            &[value_a_effective_flag_aux_inv],
-            ValueAEffectiveFlagAuxInv,
+        traces.fill_columns_batch(
+            aux.iter()
+                .map(|&(row_idx, _, aux_inv)| (row_idx, [aux_inv])),
+            CpuColumns::ValueAEffectiveFlagAuxInv,
         );
 
-        let vm_step = match vm_step {
-            Some(vm_step) => vm_step,
-            None => {
-                // padding
-                traces.fill_columns(row_idx, true, IsPadding);
-                return;
-            }
-        };
+        // padding
+        traces.fill_columns_batch(
+            chunk()
+                .filter(|(_, vm_step)| vm_step.is_none())
+                .map(|(row_idx, _)| (row_idx, true)),
+            IsPadding,
+        );
 
-        let step = &vm_step.step;
-        let pc = step.pc;
-        // Sanity check: preprocessed column `Clk` contains `row_idx + 1`
-        assert!(step.timestamp as usize == row_idx + 1);
-        traces.fill_columns(row_idx, pc, Pc);
+        let executed =
+            || chunk().filter_map(|(row_idx, vm_step)| Some((row_idx, &vm_step.as_ref()?.step)));
+        for (row_idx, _) in executed() {
+            traces.record_real_row(row_idx);
+        }
+        traces.fill_columns_batch(executed().map(|(row_idx, step)| (row_idx, step.pc)), Pc);
+        traces.fill_columns_batch(
+            chunk().filter_map(|(row_idx, vm_step)| {
+                let vm_step = vm_step.as_ref()?;
+                let cycles = OpcodeCost::instruction_cycles(
+                    &vm_step.step.instruction.opcode,
+                    vm_step.regs.read(SYSCALL_NUMBER_REG),
+                );
+                Some((row_idx, [BaseField::from(cycles)]))
+            }),
+            CpuColumns::Cycles,
+        );
         // Fill PcCarry
         // PcCarry isn't used in jump or branch instructions, but we fill it anyway.
-        let (_, pc_carry) = add_with_carries(pc.to_le_bytes(), 4u32.to_le_bytes());
         // PcCarry only needs two flags for carries for 16-bit chunks because the constraints treat the addition 16 bits at a time.
-        traces.fill_columns(row_idx, [pc_carry[1], pc_carry[3]], PcCarry);
-        traces.fill_columns(row_idx, pc.wrapping_add(WORD_SIZE as u32), PcNext); // default expectation of the next Pc; might be overwritten by Branch or Jump chips
-                                                                                 // Fill InstructionWord to the main trace for the program memory checking
-        traces.fill_columns(row_idx, step.raw_instruction, InstrVal);
+        traces.fill_columns_batch(
+            executed().map(|(row_idx, step)| {
+                let (_, pc_carry) =
+                    add_with_carries(step.pc.to_le_bytes(), pc_increment(step).to_le_bytes());
+                (row_idx, [pc_carry[1], pc_carry[3]])
+            }),
+            PcCarry,
+        );
+        // Default expectation of the next Pc; might be overwritten by Branch or Jump chips
+        traces.fill_columns_batch(
+            executed().map(|(row_idx, step)| (row_idx, step.pc.wrapping_add(pc_increment(step)))),
+            PcNext,
+        );
+        // Fill InstructionWord to the main trace for the program memory checking
+        traces.fill_columns_batch(
+            executed().map(|(row_idx, step)| (row_idx, step.raw_instruction)),
+            InstrVal,
+        );
+    }
+
+    /// Fills the opcode flags, operands and register addresses of an executed row.
+    fn fill_instruction_columns(traces: &mut TracesBuilder, row_idx: usize, vm_step: &ProgramStep) {
+        let step = &vm_step.step;
 
         // Add opcode to the main trace
 
+        // NOPs only advance the pc, they have no operands and don't access registers.
+        if step.instruction.is_nop() {
+            traces.fill_columns(row_idx, true, IsNop);
+            return;
+        }
+
         // Set is_opcode to 1, e.g If this is ADD opcode, set IsAdd to 1.
         match step.instruction.opcode.builtin() {
             Some(BuiltinOpcode::ADD) | Some(BuiltinOpcode::ADDI) => {
@@ -155,9 +286,11 @@ impl MachineChip for CpuChip {
             }
             Some(BuiltinOpcode::LUI) => {
                 traces.fill_columns(row_idx, true, IsLui);
+                traces.fill_columns(row_idx, step.fused.is_some(), IsFusedLi);
             }
             Some(BuiltinOpcode::AUIPC) => {
                 traces.fill_columns(row_idx, true, IsAuipc);
+                traces.fill_columns(row_idx, step.fused.is_some(), IsFusedLi);
             }
             Some(BuiltinOpcode::JALR) => {
                 traces.fill_columns(row_idx, true, IsJalr);
@@ -218,9 +351,12 @@ impl MachineChip for CpuChip {
                 traces.fill_columns(row_idx, true, IsEbreak);
             }
             _ => {
-                if step.instruction.opcode.raw != KECCAKF_OPCODE {
-                    panic!("Unsupported opcode: {:?}", step.instruction.opcode);
-                }
+                // Machine::check_opcodes rejects traces with instructions no chip or extension constrains.
+                assert_eq!(
+                    step.instruction.opcode.raw, KECCAKF_OPCODE,
+                    "unsupported opcode {} must be rejected before filling traces",
+                    step.instruction.opcode,
+                );
                 return;
             }
         }
@@ -313,6 +449,46 @@ impl MachineChip for CpuChip {
                 );
             }
         }
+
+        // System calls read their first argument through the second register access.
+        if vm_step.get_syscall_code().is_some() {
+            traces.fill_columns(row_idx, SYSCALL_ARG0_REG as u8, OpC);
+            traces.fill_columns(row_idx, SYSCALL_ARG0_REG as u8, Reg2Address);
+            traces.fill_columns(row_idx, vm_step.get_syscall_arg0().to_le_bytes(), ValueC);
+        }
+    }
+}
+
+impl MachineChip for CpuChip {
+    const NAME: &'static str = "CpuChip";
+
+    fn fill_main_trace(
+        traces: &mut TracesBuilder,
+        row_idx: usize,
+        vm_step: &Option<ProgramStep>,
+        _side_note: &mut SideNote,
+        _config: &ExtensionsConfig,
+    ) {
+        Self::fill_common_columns(traces, row_idx..row_idx + 1, std::slice::from_ref(vm_step));
+        if let Some(vm_step) = vm_step {
+            Self::fill_instruction_columns(traces, row_idx, vm_step);
+        }
+    }
+
+    /// Fills the columns written on every row one column at a time, then the columns depending on the instruction.
+    fn fill_main_trace_chunk(
+        traces: &mut TracesBuilder,
+        rows: Range<usize>,
+        steps: &[Option<ProgramStep>],
+        _side_note: &mut SideNote,
+        _config: &ExtensionsConfig,
+    ) {
+        Self::fill_common_columns(traces, rows.clone(), steps);
+        for (row_idx, vm_step) in rows.zip(steps) {
+            if let Some(vm_step) = vm_step {
+                Self::fill_instruction_columns(traces, row_idx, vm_step);
+            }
+        }
     }
 
     fn add_constraints<E: EvalAtRow>(
@@ -337,8 +513,10 @@ impl MachineChip for CpuChip {
         // Constrain ValueAEffectiveFlag's range
         let [value_a_effective_flag] = trace_eval!(trace_eval, ValueAEffectiveFlag);
 
-        let [value_a_effective_flag_aux] = trace_eval!(trace_eval, ValueAEffectiveFlagAux);
-        let [value_a_effective_flag_aux_inv] = trace_eval!(trace_eval, ValueAEffectiveFlagAuxInv);
+        let [value_a_effective_flag_aux] =
+            trace_eval!(trace_eval, CpuColumns::ValueAEffectiveFlagAux);
+        let [value_a_effective_flag_aux_inv] =
+            trace_eval!(trace_eval, CpuColumns::ValueAEffectiveFlagAuxInv);
         // Below is just for making sure value_a_effective_flag_aux is not zero.
         eval.add_constraint(
             value_a_effective_flag_aux.clone() * value_a_effective_flag_aux_inv - E::F::one(),
@@ -390,6 +568,7 @@ impl MachineChip for CpuChip {
         let [is_ecall] = trace_eval!(trace_eval, IsEcall);
         let [is_ebreak] = trace_eval!(trace_eval, IsEbreak);
         let [is_keccak] = trace_eval!(trace_eval, IsCustomKeccak);
+        let [is_nop] = trace_eval!(trace_eval, IsNop);
         eval.add_constraint(
             is_add.clone()
                 + is_sub.clone()
@@ -431,9 +610,30 @@ impl MachineChip for CpuChip {
                 + is_ebreak.clone()
                 + is_padding
                 + is_keccak
+                + is_nop
                 - E::F::one(),
         );
 
+        // Cycles is the cost of the opcode of the row, zero on padding, and the cost of the syscall on ECALL rows.
+        // The clock of the circuit, `Clk`, advances by the cycles of every row, see `TimestampChip`, the same as
+        // the emulator advancing its clock by the cost of the instruction.
+        let [cycles] = trace_eval!(trace_eval, CpuColumns::Cycles);
+        let opcode_cycles = FLAG_OPCODES
+            .iter()
+            .fold(E::F::zero(), |acc, &(flag, opcodes)| {
+                let [flag] = trace_eval.column_eval(flag);
+                acc + flag * BaseField::from(flag_cycles(opcodes))
+            });
+        let ecall_cycles = BaseField::from(OpcodeCost::builtin_cycles(BuiltinOpcode::ECALL));
+        let syscall_cycles = SYSCALL_FLAGS
+            .iter()
+            .fold(E::F::zero(), |acc, &(flag, ecall)| {
+                let [flag] = trace_eval.column_eval(flag);
+                acc + flag
+                    * (BaseField::from(OpcodeCost::syscall_cycles(ecall as u32)) - ecall_cycles)
+            });
+        eval.add_constraint(cycles - opcode_cycles - syscall_cycles);
+
         // is_type_r = (1-imm_c) ・(is_add + is_sub + is_slt + is_sltu + is_xor + is_or + is_and + is_sll + is_srl + is_sra)
         // is_type_r += (1 - imm_c) ・(is_mul + is_mulhu + is_div + is_divu + is_rem + is_remu + is_mulh + is_mulhsu)
         let [is_type_r] = virtual_column::IsTypeR::eval(trace_eval);
@@ -455,13 +655,15 @@ impl MachineChip for CpuChip {
             (is_type_r.clone() + is_type_i.clone()) * (op_a.clone() - reg3_address.clone()),
         );
 
-        // Constrain read access doesn't change register values for type R and type I instructions
+        // Constrain read access doesn't change register values for type R and type I instructions, and for the
+        // first argument of system calls
         // The comparison is batched by two limbs at a time
         let reg1_val_prev = trace_eval!(trace_eval, Column::Reg1ValPrev);
         let reg2_val_prev = trace_eval!(trace_eval, Column::Reg2ValPrev);
         let value_b = trace_eval!(trace_eval, Column::ValueB);
         let value_c = trace_eval!(trace_eval, Column::ValueC);
         let [op_b_flag] = virtual_column::OpBFlag::eval(trace_eval);
+        let [reg2_accessed] = virtual_column::Reg2Accessed::eval(trace_eval);
         for limb_idx in (0..WORD_SIZE).step_by(2) {
             eval.add_constraint(
                 op_b_flag.clone()
@@ -471,7 +673,7 @@ impl MachineChip for CpuChip {
                             + value_b[limb_idx + 1].clone() * BaseField::from(1 << 8))),
             );
             eval.add_constraint(
-                is_type_r.clone()
+                reg2_accessed.clone()
                     * (reg2_val_prev[limb_idx].clone()
                         + reg2_val_prev[limb_idx + 1].clone() * BaseField::from(1 << 8)
                         - (value_c[limb_idx].clone()
@@ -509,11 +711,11 @@ impl MachineChip for CpuChip {
         }
 
         let is_type_sys = is_ebreak + is_ecall;
-        let [is_sys_halt] = trace_eval!(trace_eval, Column::IsSysHalt);
+        let [is_sys_halt] = trace_eval!(trace_eval, SyscallColumns::IsSysHalt);
 
         // Constraint reg{1,2,3}_address uniquely for type SYS instructions
         eval.add_constraint(is_type_sys.clone() * (op_b - reg1_address));
-        eval.add_constraint(is_type_sys.clone() * (op_c - reg2_address)); // not currently used; a future syscall might use it
+        eval.add_constraint(is_type_sys.clone() * (op_c - reg2_address));
         eval.add_constraint(is_type_sys.clone() * (op_a - reg3_address));
 
         // PcNext should be Pc on the next row, unless the next row is the first row or padding.
@@ -531,9 +733,10 @@ impl MachineChip for CpuChip {
             );
         }
 
-        // Increment PC by four
-        // (is_pc_incremented)・(pc_next_1 + pc_next_2·2^8 + pc_carry_1·2^16 - (pc_1 + pc_2·2^8) - 4) = 0
+        // Increment PC by four, or by eight on fused rows
+        // (is_pc_incremented)・(pc_next_1 + pc_next_2·2^8 + pc_carry_1·2^16 - (pc_1 + pc_2·2^8) - 4 - is_fused_li·4) = 0
         let [is_pc_incremented] = virtual_column::IsPcIncremented::eval(trace_eval);
+        let [is_fused_li] = trace_eval!(trace_eval, Column::IsFusedLi);
         let pc_carry = trace_eval!(trace_eval, Column::PcCarry);
         let pc = trace_eval!(trace_eval, Column::Pc);
         eval.add_constraint(
@@ -542,7 +745,8 @@ impl MachineChip for CpuChip {
                     + pc_next[1].clone() * BaseField::from(1 << 8)
                     + pc_carry[0].clone() * BaseField::from(1 << 16)
                     - (pc[0].clone() + pc[1].clone() * BaseField::from(1 << 8))
-                    - BaseField::from(4).into()),
+                    - BaseField::from(4).into()
+                    - is_fused_li * BaseField::from(4)),
         );
         // (is_pc_incremented)・(pc_next_3 + pc_next_4·2^8 + pc_carry_2·2^16 - (pc_3 + pc_4·2^8) - pc_carry_1) = 0
         eval.add_constraint(
@@ -566,4 +770,274 @@ impl MachineChip for CpuChip {
             );
         }
     }
+
+    fn describe() -> Option<ChipDescription> {
+        Some(ChipDescription {
+            chip: "CpuChip",
+            constraints: &[
+                ConstraintDescription {
+                    id: "padding_monotone",
+                    description: "IsPadding can't go from one to zero, except when the next row is the first row.",
+                    columns: &["IsLast", "IsPadding"],
+                    degree: 3,
+                    count: 1,
+                },
+                ConstraintDescription {
+                    id: "value_a_effective_flag_aux_nonzero",
+                    description: "ValueAEffectiveFlagAux is non-zero, witnessed by its inverse.",
+                    columns: &["ValueAEffectiveFlagAux", "ValueAEffectiveFlagAuxInv"],
+                    degree: 2,
+                    count: 1,
+                },
+                ConstraintDescription {
+                    id: "value_a_effective_flag",
+                    description: "ValueAEffectiveFlag is zero if and only if OpA is zero, so writes to x0 are discarded.",
+                    columns: &["OpA", "ValueAEffectiveFlagAux", "ValueAEffectiveFlag"],
+                    degree: 2,
+                    count: 1,
+                },
+                ConstraintDescription {
+                    id: "one_opcode_flag",
+                    description: "Exactly one opcode flag or IsPadding is set, given the flags are range checked as booleans.",
+                    columns: &["IsAdd", "IsSub", "IsPadding", "IsNop"],
+                    degree: 1,
+                    count: 1,
+                },
+                ConstraintDescription {
+                    id: "opcode_cycles",
+                    description: "Cycles is the cost of the opcode flagged on the row, or of the syscall flagged on ECALL rows, from the cost model shared with the emulator.",
+                    columns: &["Cycles", "IsAdd", "IsSub", "IsNop", "IsSysPrivInputWord"],
+                    degree: 1,
+                    count: 1,
+                },
+                ConstraintDescription {
+                    id: "type_r_i_reg1_address",
+                    description: "Type R and type I instructions read the first register from OpB.",
+                    columns: &["IsTypeR", "IsTypeI", "OpB", "Reg1Address"],
+                    degree: 3,
+                    count: 1,
+                },
+                ConstraintDescription {
+                    id: "type_r_reg2_address",
+                    description: "Type R instructions read the second register from OpC.",
+                    columns: &["IsTypeR", "OpC", "Reg2Address"],
+                    degree: 3,
+                    count: 1,
+                },
+                ConstraintDescription {
+                    id: "type_r_i_reg3_address",
+                    description: "Type R and type I instructions write the register in OpA.",
+                    columns: &["IsTypeR", "IsTypeI", "OpA", "Reg3Address"],
+                    degree: 3,
+                    count: 1,
+                },
+                ConstraintDescription {
+                    id: "reg1_value",
+                    description: "Reading the first register doesn't change it, and ValueB is its value. Batched by limb pairs.",
+                    columns: &["OpBFlag", "Reg1ValPrev", "ValueB"],
+                    degree: 2,
+                    count: 2,
+                },
+                ConstraintDescription {
+                    id: "reg2_value",
+                    description: "Reading the second register of a type R instruction or a system call doesn't change it, and ValueC is its value. Batched by limb pairs.",
+                    columns: &["IsTypeR", "IsEcall", "IsEbreak", "Reg2ValPrev", "ValueC"],
+                    degree: 3,
+                    count: 2,
+                },
+                ConstraintDescription {
+                    id: "type_b_s_reg1_address",
+                    description: "Type B and type S instructions read the first register from OpB.",
+                    columns: &["IsTypeB", "IsTypeS", "OpB", "Reg1Address"],
+                    degree: 2,
+                    count: 1,
+                },
+                ConstraintDescription {
+                    id: "type_b_s_reg3_address",
+                    description: "Type B and type S instructions access the register in OpA through Reg3, which simplifies register memory checking.",
+                    columns: &["IsTypeB", "IsTypeS", "OpA", "Reg3Address"],
+                    degree: 2,
+                    count: 1,
+                },
+                ConstraintDescription {
+                    id: "type_b_s_reg3_value",
+                    description: "Type B and type S instructions only read the register in OpA, and ValueA is its value. Batched by limb pairs.",
+                    columns: &["IsTypeB", "IsTypeS", "Reg3ValPrev", "ValueA"],
+                    degree: 2,
+                    count: 2,
+                },
+                ConstraintDescription {
+                    id: "sys_reg1_address",
+                    description: "System calls read the first register from OpB.",
+                    columns: &["IsEcall", "IsEbreak", "OpB", "Reg1Address"],
+                    degree: 2,
+                    count: 1,
+                },
+                ConstraintDescription {
+                    id: "sys_reg2_address",
+                    description: "System calls read the second register from OpC, the register of their first argument.",
+                    columns: &["IsEcall", "IsEbreak", "OpC", "Reg2Address"],
+                    degree: 2,
+                    count: 1,
+                },
+                ConstraintDescription {
+                    id: "sys_reg3_address",
+                    description: "System calls access the register in OpA through Reg3.",
+                    columns: &["IsEcall", "IsEbreak", "OpA", "Reg3Address"],
+                    degree: 2,
+                    count: 1,
+                },
+                ConstraintDescription {
+                    id: "pc_next_continuity",
+                    description: "PcNext is the Pc of the next row, unless the next row is the first row or padding. Batched by limb pairs.",
+                    columns: &["IsLast", "IsPadding", "PcNext", "Pc"],
+                    degree: 3,
+                    count: 2,
+                },
+                ConstraintDescription {
+                    id: "pc_increment_low",
+                    description: "On rows that increment the pc, the low 16 bits of PcNext are Pc plus four, or plus eight on fused rows.",
+                    columns: &["IsPcIncremented", "IsFusedLi", "PcNext", "PcCarry", "Pc"],
+                    degree: 3,
+                    count: 1,
+                },
+                ConstraintDescription {
+                    id: "pc_increment_high",
+                    description: "On rows that increment the pc, the high 16 bits of PcNext are Pc plus the carry from the low half.",
+                    columns: &["IsPcIncremented", "PcNext", "PcCarry", "Pc"],
+                    degree: 3,
+                    count: 1,
+                },
+                ConstraintDescription {
+                    id: "halt_pc",
+                    description: "The halt syscall leaves the pc unchanged. Batched by limb pairs.",
+                    columns: &["IsEcall", "IsEbreak", "IsSysHalt", "Pc", "PcNext"],
+                    degree: 3,
+                    count: 2,
+                },
+            ],
+            lookups: &[],
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        chips::{AddChip, DecodingCheckChip, ProgramMemCheckChip, RegisterMemCheckChip},
+        error::TraceCheckError,
+        test_utils::{assert_chip, check_chip},
+        trace::{
+            program::iter_program_steps,
+            program_trace::{ProgramTraces, ProgramTracesBuilder},
+            PreprocessedTraces,
+        },
+    };
+    use nexus_vm::{
+        emulator::InternalView,
+        riscv::{BasicBlock, Instruction, Opcode},
+        trace::k_trace_direct,
+    };
+
+    const LOG_SIZE: u32 = PreprocessedTraces::MIN_LOG_SIZE;
+
+    fn fill_add_traces() -> (TracesBuilder, ProgramTraces) {
+        type Chips = (
+            CpuChip,
+            DecodingCheckChip,
+            AddChip,
+            RegisterMemCheckChip,
+            ProgramMemCheckChip,
+        );
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+        ])];
+        let (view, vm_traces) = k_trace_direct(&basic_block, 1).expect("Failed to create trace");
+
+        let mut traces = TracesBuilder::new(LOG_SIZE);
+        let program_steps = iter_program_steps(&vm_traces, traces.num_rows());
+        let program_trace =
+            ProgramTracesBuilder::new_with_empty_memory(LOG_SIZE, view.get_program_memory());
+        let mut side_note = SideNote::new(&program_trace, &view);
+        for (row_idx, program_step) in program_steps.enumerate() {
+            Chips::fill_main_trace(
+                &mut traces,
+                row_idx,
+                &program_step,
+                &mut side_note,
+                &ExtensionsConfig::default(),
+            );
+        }
+        (traces, program_trace.finalize())
+    }
+
+    #[test]
+    fn test_selectors_of_executed_rows() {
+        let (traces, program_trace) = fill_add_traces();
+        assert_chip::<CpuChip>(traces, Some(program_trace));
+    }
+
+    #[test]
+    fn test_flags_share_opcode_cost() {
+        for (flag, opcodes) in FLAG_OPCODES {
+            for &opcode in *opcodes {
+                assert_eq!(
+                    OpcodeCost::builtin_cycles(opcode),
+                    flag_cycles(opcodes),
+                    "{flag:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_syscalls_with_own_cost_have_flags() {
+        let ecall_cycles = OpcodeCost::builtin_cycles(BuiltinOpcode::ECALL);
+        for ecall in Ecall::ALL {
+            let has_flag = SYSCALL_FLAGS.iter().any(|&(_, e)| e == ecall);
+            assert_eq!(
+                OpcodeCost::syscall_cycles(ecall as u32) != ecall_cycles,
+                has_flag,
+                "{ecall:?}"
+            );
+        }
+    }
+
+    /// Asserts that the first constraint of `CpuChip` violated by `traces` is on `row`.
+    fn assert_rejected_at(traces: TracesBuilder, program_trace: ProgramTraces, row: usize) {
+        match check_chip::<CpuChip>(traces, Some(program_trace)) {
+            Err(TraceCheckError::ConstraintNotSatisfied {
+                chip: "CpuChip",
+                row: failed_row,
+                ..
+            }) if failed_row == row => {}
+            result => panic!("unexpected result: {result:?}"),
+        }
+    }
+
+    #[test]
+    fn test_executed_row_with_wrong_cycles() {
+        // The second row executes ADD, which takes one cycle.
+        let (mut traces, program_trace) = fill_add_traces();
+        traces.fill_columns(1, 2u8, CpuColumns::Cycles);
+        assert_rejected_at(traces, program_trace, 1);
+    }
+
+    #[test]
+    fn test_padding_row_with_cycles() {
+        // Rows after the two instructions are padding.
+        let (mut traces, program_trace) = fill_add_traces();
+        traces.fill_columns(2, 1u8, CpuColumns::Cycles);
+        assert_rejected_at(traces, program_trace, 2);
+    }
+
+    #[test]
+    fn test_executed_row_without_selector() {
+        // A row that is neither padding nor any instruction would be left unconstrained by instruction chips.
+        let (mut traces, program_trace) = fill_add_traces();
+        traces.fill_columns(1, false, IsAdd);
+        assert_rejected_at(traces, program_trace, 1);
+    }
 }
@@ -20,7 +20,7 @@ use crate::{
         sidenote::SideNote,
         ProgramStep, TracesBuilder,
     },
-    traits::MachineChip,
+    traits::{MachineChip, SideNoteState},
 };
 
 /// The custom instruction chip works as an (optional) bridge between main component and custom extensions.
@@ -124,6 +124,8 @@ impl KeccakChip {
 }
 
 impl MachineChip for KeccakChip {
+    const NAME: &'static str = "KeccakChip";
+
     fn draw_lookup_elements(
         lookup_elements: &mut AllLookupElements,
         channel: &mut impl Channel,
@@ -175,6 +177,12 @@ impl MachineChip for KeccakChip {
         traces.fill_columns(row_idx, true, Column::IsCustomKeccak);
     }
 
+    fn collect_side_note_state(states: &mut Vec<SideNoteState>, config: &ExtensionsConfig) {
+        if config.is_keccak_enabled() {
+            states.push(SideNoteState::ReadWriteMemory);
+        }
+    }
+
     fn add_constraints<E: stwo_constraint_framework::EvalAtRow>(
         eval: &mut E,
         trace_eval: &TraceEval<E>,
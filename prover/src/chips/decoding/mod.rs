@@ -1,8 +1,12 @@
 // This module contains chips about instruction decoding.
 
+use nexus_vm::riscv::BuiltinOpcode;
+use stwo::core::fields::m31::BaseField;
+
 mod type_b;
 mod type_i;
 mod type_j;
+mod type_nop;
 mod type_r;
 mod type_s;
 mod type_sys;
@@ -16,4 +20,30 @@ pub type DecodingCheckChip = (
     type_u::TypeUChip,
     type_j::TypeJChip,
     type_sys::TypeSysChip,
+    type_nop::TypeNopChip,
 );
+
+// Encodings checked by the decoding chips are read from the opcode table shared with the encoder and the decoder.
+
+/// The major opcode of `opcode`, in the lower 7 bits of the first instruction byte.
+pub(crate) fn opcode_bits(opcode: BuiltinOpcode) -> BaseField {
+    BaseField::from(opcode.raw() as u32)
+}
+
+/// The funct3 field of `opcode`, in bits 4 to 6 of the second instruction byte.
+pub(crate) fn funct3_bits(opcode: BuiltinOpcode) -> BaseField {
+    let fn3 = opcode.spec().fn3.expect("opcode has no funct3");
+    BaseField::from((fn3 as u32) << 4)
+}
+
+/// The funct7 field of `opcode`, in the upper 7 bits of the last instruction byte.
+fn funct7_bits(opcode: BuiltinOpcode) -> BaseField {
+    let fn7 = opcode.spec().fn7.expect("opcode has no funct7");
+    BaseField::from((fn7 as u32) << 1)
+}
+
+/// The lower 4 bits of the funct12 field of a system instruction, in the upper half of the third instruction byte.
+fn funct12_low_bits(opcode: BuiltinOpcode) -> BaseField {
+    let fn12 = opcode.spec().fn12.expect("opcode has no funct12");
+    BaseField::from(((fn12 & 0xF) as u32) << 4)
+}
@@ -9,7 +9,9 @@ use crate::{
     virtual_column::{self, VirtualColumn},
 };
 
-use nexus_vm::riscv::InstructionType::BType;
+use nexus_vm::riscv::{BuiltinOpcode, InstructionType::BType};
+
+use super::{funct3_bits, opcode_bits};
 
 use crate::column::Column;
 
@@ -18,6 +20,8 @@ use crate::trace::eval::trace_eval;
 pub struct TypeBChip;
 
 impl MachineChip for TypeBChip {
+    const NAME: &'static str = "TypeBChip";
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
@@ -147,7 +151,7 @@ impl MachineChip for TypeBChip {
         // (is_type_b) ・ (b1100011 + op_c11・2^7 - instr_val_1) = 0			// limb 1
         eval.add_constraint(
             is_type_b.clone()
-                * (E::F::from(BaseField::from(0b1100011))
+                * (E::F::from(opcode_bits(BuiltinOpcode::BEQ))
                     + op_c11.clone() * BaseField::from(1 << 7)
                     - value_instr[0].clone()),
         );
@@ -170,7 +174,7 @@ impl MachineChip for TypeBChip {
         eval.add_constraint(
             is_beq
                 * (op_c1_4.clone()
-                    + E::F::from(BaseField::from(0b000) * BaseField::from(1 << 4))
+                    + E::F::from(funct3_bits(BuiltinOpcode::BEQ))
                     + op_a0.clone() * BaseField::from(1 << 7)
                     - value_instr[1].clone()),
         );
@@ -178,35 +182,35 @@ impl MachineChip for TypeBChip {
         eval.add_constraint(
             is_bne
                 * (op_c1_4.clone()
-                    + E::F::from(BaseField::from(0b001) * BaseField::from(1 << 4))
+                    + E::F::from(funct3_bits(BuiltinOpcode::BNE))
                     + op_a0.clone() * BaseField::from(1 << 7)
                     - value_instr[1].clone()),
         );
         eval.add_constraint(
             is_blt
                 * (op_c1_4.clone()
-                    + E::F::from(BaseField::from(0b100) * BaseField::from(1 << 4))
+                    + E::F::from(funct3_bits(BuiltinOpcode::BLT))
                     + op_a0.clone() * BaseField::from(1 << 7)
                     - value_instr[1].clone()),
         );
         eval.add_constraint(
             is_bge
                 * (op_c1_4.clone()
-                    + E::F::from(BaseField::from(0b101) * BaseField::from(1 << 4))
+                    + E::F::from(funct3_bits(BuiltinOpcode::BGE))
                     + op_a0.clone() * BaseField::from(1 << 7)
                     - value_instr[1].clone()),
         );
         eval.add_constraint(
             is_bltu
                 * (op_c1_4.clone()
-                    + E::F::from(BaseField::from(0b110) * BaseField::from(1 << 4))
+                    + E::F::from(funct3_bits(BuiltinOpcode::BLTU))
                     + op_a0.clone() * BaseField::from(1 << 7)
                     - value_instr[1].clone()),
         );
         eval.add_constraint(
             is_bgeu
                 * (op_c1_4.clone()
-                    + E::F::from(BaseField::from(0b111) * BaseField::from(1 << 4))
+                    + E::F::from(funct3_bits(BuiltinOpcode::BGEU))
                     + op_a0.clone() * BaseField::from(1 << 7)
                     - value_instr[1].clone()),
         );
@@ -11,7 +11,12 @@ use crate::{
     virtual_column::{self, VirtualColumn},
 };
 
-use nexus_vm::riscv::InstructionType::{IType, ITypeShamt};
+use nexus_vm::riscv::{
+    BuiltinOpcode,
+    InstructionType::{IType, ITypeShamt},
+};
+
+use super::{funct3_bits, funct7_bits, opcode_bits};
 
 use crate::column::Column::{
     self, ImmC, InstrVal, IsAdd, IsAnd, IsJalr, IsLb, IsLbu, IsLh, IsLhu, IsLw, IsOr, IsSll, IsSlt,
@@ -26,6 +31,8 @@ pub type TypeIChip = (TypeINoShiftChip, TypeIShiftChip);
 pub struct TypeINoShiftChip;
 
 impl MachineChip for TypeINoShiftChip {
+    const NAME: &'static str = "TypeINoShiftChip";
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
@@ -150,33 +157,36 @@ impl MachineChip for TypeINoShiftChip {
         // (is_load)・(b0000011 + op_a0・2^7 - instr_val_1) = 0
         eval.add_constraint(
             is_load
-                * (E::F::from(BaseField::from(0b0000011))
+                * (E::F::from(opcode_bits(BuiltinOpcode::LB))
                     + op_a0.clone() * BaseField::from(1 << 7)
                     - instr_val_1.clone()),
         );
         // (is_alu_imm_no_shift)・(b0010011 + op_a0・2^7 - instr_val_1) = 0
         eval.add_constraint(
             is_alu_imm_no_shift
-                * (E::F::from(BaseField::from(0b0010011))
+                * (E::F::from(opcode_bits(BuiltinOpcode::ADDI))
                     + op_a0.clone() * BaseField::from(1 << 7)
                     - instr_val_1.clone()),
         );
         // (is_jalr) ・(b1100111 + op_a0・2^7 - instr_val_1) = 0
         eval.add_constraint(
             is_jalr.clone()
-                * (E::F::from(BaseField::from(0b1100111)) + op_a0 * BaseField::from(1 << 7)
+                * (E::F::from(opcode_bits(BuiltinOpcode::JALR)) + op_a0 * BaseField::from(1 << 7)
                     - instr_val_1),
         );
         // (is_lb)・(op_a1_4 + b000・2^4 + op_b0・2^7 - instr_val_2) = 0
         eval.add_constraint(
             is_lb
-                * (op_a1_4.clone() + op_b0.clone() * BaseField::from(1 << 7) - instr_val_2.clone()),
+                * (op_a1_4.clone()
+                    + E::F::from(funct3_bits(BuiltinOpcode::LB))
+                    + op_b0.clone() * BaseField::from(1 << 7)
+                    - instr_val_2.clone()),
         );
         // (is_lh)・(op_a1_4 + b001・2^4 + op_b0・2^7 - instr_val_2) = 0
         eval.add_constraint(
             is_lh
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b001 * 2u32.pow(4)))
+                    + E::F::from(funct3_bits(BuiltinOpcode::LH))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val_2.clone()),
         );
@@ -184,7 +194,7 @@ impl MachineChip for TypeINoShiftChip {
         eval.add_constraint(
             is_lw
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b010 * 2u32.pow(4)))
+                    + E::F::from(funct3_bits(BuiltinOpcode::LW))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val_2.clone()),
         );
@@ -192,7 +202,7 @@ impl MachineChip for TypeINoShiftChip {
         eval.add_constraint(
             is_lbu
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b100 * 2u32.pow(4)))
+                    + E::F::from(funct3_bits(BuiltinOpcode::LBU))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val_2.clone()),
         );
@@ -200,7 +210,7 @@ impl MachineChip for TypeINoShiftChip {
         eval.add_constraint(
             is_lhu
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b101 * 2u32.pow(4)))
+                    + E::F::from(funct3_bits(BuiltinOpcode::LHU))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val_2.clone()),
         );
@@ -217,14 +227,17 @@ impl MachineChip for TypeINoShiftChip {
         eval.add_constraint(
             is_add
                 * imm_c.clone()
-                * (op_a1_4.clone() + op_b0.clone() * BaseField::from(1 << 7) - instr_val_2.clone()),
+                * (op_a1_4.clone()
+                    + E::F::from(funct3_bits(BuiltinOpcode::ADDI))
+                    + op_b0.clone() * BaseField::from(1 << 7)
+                    - instr_val_2.clone()),
         );
         // (is_slt)・imm_c・(op_a1_4 + b010・2^4 + op_b0・2^7 - instr_val_2) = 0
         eval.add_constraint(
             is_slt
                 * imm_c.clone()
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b010 * 2u32.pow(4)))
+                    + E::F::from(funct3_bits(BuiltinOpcode::SLTI))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val_2.clone()),
         );
@@ -233,7 +246,7 @@ impl MachineChip for TypeINoShiftChip {
             is_sltu
                 * imm_c.clone()
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b011 * 2u32.pow(4)))
+                    + E::F::from(funct3_bits(BuiltinOpcode::SLTIU))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val_2.clone()),
         );
@@ -242,7 +255,7 @@ impl MachineChip for TypeINoShiftChip {
             is_xor
                 * imm_c.clone()
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b100 * 2u32.pow(4)))
+                    + E::F::from(funct3_bits(BuiltinOpcode::XORI))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val_2.clone()),
         );
@@ -251,7 +264,7 @@ impl MachineChip for TypeINoShiftChip {
             is_or
                 * imm_c.clone()
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b110 * 2u32.pow(4)))
+                    + E::F::from(funct3_bits(BuiltinOpcode::ORI))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val_2.clone()),
         );
@@ -260,14 +273,17 @@ impl MachineChip for TypeINoShiftChip {
             is_and
                 * imm_c.clone()
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b111 * 2u32.pow(4)))
+                    + E::F::from(funct3_bits(BuiltinOpcode::ANDI))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val_2.clone()),
         );
         // (is_jalr)・(op_a1_4 + b000・2^4 + op_b0・2^7 - instr_val_2) = 0
         eval.add_constraint(
             is_jalr
-                * (op_a1_4.clone() + op_b0.clone() * BaseField::from(1 << 7) - instr_val_2.clone()),
+                * (op_a1_4.clone()
+                    + E::F::from(funct3_bits(BuiltinOpcode::JALR))
+                    + op_b0.clone() * BaseField::from(1 << 7)
+                    - instr_val_2.clone()),
         );
 
         // (is_type_i_no_shift)・(op_b1_4 + op_c0_3・2^4 - instr_val_3) = 0
@@ -287,6 +303,8 @@ impl MachineChip for TypeINoShiftChip {
 pub struct TypeIShiftChip;
 
 impl MachineChip for TypeIShiftChip {
+    const NAME: &'static str = "TypeIShiftChip";
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
@@ -390,7 +408,7 @@ impl MachineChip for TypeIShiftChip {
         // (is_alu_imm_shift) ・(b0010011 + op_a0・2^7 - instr_val_1) = 0
         eval.add_constraint(
             is_alu_imm_shift.clone()
-                * (E::F::from(BaseField::from(0b0010011))
+                * (E::F::from(opcode_bits(BuiltinOpcode::SLLI))
                     + op_a0.clone() * BaseField::from(1 << 7)
                     - instr_val_1.clone()),
         );
@@ -399,7 +417,7 @@ impl MachineChip for TypeIShiftChip {
             is_sll.clone()
                 * imm_c.clone()
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b001 * 2u32.pow(4)))
+                    + E::F::from(funct3_bits(BuiltinOpcode::SLLI))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val_2.clone()),
         );
@@ -408,7 +426,7 @@ impl MachineChip for TypeIShiftChip {
             is_srl.clone()
                 * imm_c.clone()
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b101 * 2u32.pow(4)))
+                    + E::F::from(funct3_bits(BuiltinOpcode::SRLI))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val_2.clone()),
         );
@@ -417,7 +435,7 @@ impl MachineChip for TypeIShiftChip {
             is_sra.clone()
                 * imm_c.clone()
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b101 * 2u32.pow(4)))
+                    + E::F::from(funct3_bits(BuiltinOpcode::SRAI))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val_2.clone()),
         );
@@ -429,14 +447,24 @@ impl MachineChip for TypeIShiftChip {
                     - instr_val_3.clone()),
         );
         // (is_sll)・imm_c・ (op_c4 + b0000000・2 - instr_val_4) = 0
-        eval.add_constraint(is_sll * imm_c.clone() * (op_c4.clone() - instr_val_4.clone()));
+        eval.add_constraint(
+            is_sll
+                * imm_c.clone()
+                * (op_c4.clone() + E::F::from(funct7_bits(BuiltinOpcode::SLLI))
+                    - instr_val_4.clone()),
+        );
         // (is_srl)・imm_c・(op_c4 + b0000000・2 - instr_val_4) = 0
-        eval.add_constraint(is_srl * imm_c.clone() * (op_c4.clone() - instr_val_4.clone()));
+        eval.add_constraint(
+            is_srl
+                * imm_c.clone()
+                * (op_c4.clone() + E::F::from(funct7_bits(BuiltinOpcode::SRLI))
+                    - instr_val_4.clone()),
+        );
         // (is_sra)・imm_c・(op_c4 + b0100000・2 - instr_val_4) = 0
         eval.add_constraint(
             is_sra
                 * imm_c.clone()
-                * (op_c4.clone() + E::F::from(BaseField::from(0b0100000 * 2))
+                * (op_c4.clone() + E::F::from(funct7_bits(BuiltinOpcode::SRAI))
                     - instr_val_4.clone()),
         );
     }
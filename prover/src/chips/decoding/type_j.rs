@@ -9,7 +9,9 @@ use crate::{
     virtual_column::{self, VirtualColumn},
 };
 
-use nexus_vm::riscv::InstructionType::JType;
+use nexus_vm::riscv::{BuiltinOpcode, InstructionType::JType};
+
+use super::opcode_bits;
 
 use crate::column::Column;
 
@@ -18,6 +20,8 @@ use crate::trace::eval::trace_eval;
 pub struct TypeJChip;
 
 impl MachineChip for TypeJChip {
+    const NAME: &'static str = "TypeJChip";
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
@@ -164,7 +168,7 @@ impl MachineChip for TypeJChip {
 
         eval.add_constraint(
             is_jal.clone()
-                * (E::F::from(BaseField::from(0b1101111))
+                * (E::F::from(opcode_bits(BuiltinOpcode::JAL))
                     + op_a0.clone() * BaseField::from(1 << 7)
                     - value_instr[0].clone()),
         );
@@ -0,0 +1,162 @@
+use num_traits::Zero;
+use stwo_constraint_framework::EvalAtRow;
+
+use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
+
+use super::{funct3_bits, opcode_bits};
+
+use crate::trace::eval::trace_eval;
+use crate::{
+    column::Column::{InstrVal, IsNop},
+    components::AllLookupElements,
+    extensions::ExtensionsConfig,
+    traits::MachineChip,
+};
+
+/// Ties `IsNop` to the instruction word `addi x0, x0, 0`.
+///
+/// NOP rows have no operands and access no registers, program memory checking binds the word to the program.
+pub struct TypeNopChip;
+
+impl MachineChip for TypeNopChip {
+    const NAME: &'static str = "TypeNopChip";
+
+    fn fill_main_trace(
+        _traces: &mut crate::trace::TracesBuilder,
+        _row_idx: usize,
+        _vm_step: &Option<crate::trace::ProgramStep>,
+        _side_note: &mut crate::trace::sidenote::SideNote,
+        _config: &ExtensionsConfig,
+    ) {
+    }
+
+    fn add_constraints<E: EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &crate::trace::eval::TraceEval<E>,
+        _lookup_elements: &AllLookupElements,
+        _config: &ExtensionsConfig,
+    ) {
+        let [is_nop] = trace_eval!(trace_eval, IsNop);
+        let instr_val = trace_eval.column_eval::<WORD_SIZE>(InstrVal);
+        // The word is opcode | rd << 7 | funct3 << 12 | rs1 << 15 | imm << 20, with rd = rs1 = imm = 0.
+        // (is_nop)・(b0010011 - instr_val_1) = 0
+        // (is_nop)・(funct3 - instr_val_2) = 0
+        // (is_nop)・(instr_val_3) = 0
+        // (is_nop)・(instr_val_4) = 0
+        let expected = [
+            E::F::from(opcode_bits(BuiltinOpcode::ADDI)),
+            E::F::from(funct3_bits(BuiltinOpcode::ADDI)),
+            E::F::zero(),
+            E::F::zero(),
+        ];
+        for (expected, limb) in expected.into_iter().zip(instr_val) {
+            eval.add_constraint(is_nop.clone() * (expected - limb));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::panic::{self, AssertUnwindSafe};
+
+    use num_traits::One;
+    use stwo::core::fields::m31::BaseField;
+
+    use crate::{
+        chips::{
+            AddChip, CpuChip, DecodingCheckChip, ProgramMemCheckChip, RangeCheckChip,
+            RegisterMemCheckChip, TimestampChip,
+        },
+        test_utils::assert_chip,
+        trace::{
+            program::iter_program_steps,
+            program_trace::{ProgramTraces, ProgramTracesBuilder},
+            sidenote::SideNote,
+            TracesBuilder,
+        },
+        virtual_column::{OpBFlag, Reg2Accessed, Reg3Accessed, VirtualColumn},
+    };
+
+    use super::*;
+    use nexus_common::constants::NOP_INSTRUCTION_WORD;
+    use nexus_vm::{
+        emulator::InternalView,
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+        trace::k_trace_direct,
+    };
+
+    const LOG_SIZE: u32 = 10;
+    const NUM_STEPS: usize = 1000;
+
+    type Chips = (
+        CpuChip,
+        DecodingCheckChip,
+        AddChip,
+        RegisterMemCheckChip,
+        ProgramMemCheckChip,
+        TimestampChip,
+        RangeCheckChip,
+    );
+
+    fn fill_traces(instruction: Instruction) -> (TracesBuilder, ProgramTraces) {
+        let basic_block = vec![BasicBlock::new(vec![instruction; NUM_STEPS])];
+        let (view, vm_traces) = k_trace_direct(&basic_block, 1).expect("Failed to create trace");
+
+        let mut traces = TracesBuilder::new(LOG_SIZE);
+        let program_steps = iter_program_steps(&vm_traces, traces.num_rows());
+        let program_trace =
+            ProgramTracesBuilder::new_with_empty_memory(LOG_SIZE, view.get_program_memory());
+        let mut side_note = SideNote::new(&program_trace, &view);
+        for (row_idx, program_step) in program_steps.enumerate() {
+            Chips::fill_main_trace(
+                &mut traces,
+                row_idx,
+                &program_step,
+                &mut side_note,
+                &ExtensionsConfig::default(),
+            );
+        }
+        (traces, program_trace.finalize())
+    }
+
+    /// Number of register accesses, each of which adds two fractions to the interaction trace.
+    fn register_accesses(traces: &TracesBuilder) -> u32 {
+        (0..traces.num_rows())
+            .map(|row_idx| {
+                let [reg1] = OpBFlag::read_from_traces_builder(traces, row_idx);
+                let [reg2] = Reg2Accessed::read_from_traces_builder(traces, row_idx);
+                let [reg3] = Reg3Accessed::read_from_traces_builder(traces, row_idx);
+                (reg1 + reg2 + reg3).0
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_k_trace_constrained_nops() {
+        let (traces, program_trace) = fill_traces(Instruction::nop());
+        assert_eq!(register_accesses(&traces), 0);
+        assert_chip::<Chips>(traces, Some(program_trace));
+
+        // The same instruction with a non-zero immediate is proven as an ADDI.
+        let addi = Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 0, 0, 1);
+        let (traces, program_trace) = fill_traces(addi);
+        assert_eq!(register_accesses(&traces), 2 * NUM_STEPS as u32);
+        assert_chip::<Chips>(traces, Some(program_trace));
+    }
+
+    #[test]
+    fn test_nop_selector_on_other_word_rejected() {
+        let (mut traces, program_trace) = fill_traces(Instruction::nop());
+        assert_eq!(Instruction::nop().encode(), NOP_INSTRUCTION_WORD);
+
+        // Claim that `addi x0, x0, 1` is a NOP, skipping its register accesses.
+        let addi = Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 0, 0, 1);
+        traces.fill_columns(0, addi.encode(), InstrVal);
+        assert_eq!(traces.column(0, IsNop), [BaseField::one()]);
+
+        let rejected = panic::catch_unwind(AssertUnwindSafe(|| {
+            assert_chip::<(CpuChip, DecodingCheckChip)>(traces, Some(program_trace))
+        }));
+        assert!(rejected.is_err());
+    }
+}
@@ -2,7 +2,9 @@ use num_traits::One;
 use stwo::core::fields::m31::BaseField;
 use stwo_constraint_framework::EvalAtRow;
 
-use nexus_vm::riscv::InstructionType::RType;
+use nexus_vm::riscv::{BuiltinOpcode, InstructionType::RType};
+
+use super::{funct3_bits, funct7_bits, opcode_bits};
 
 use crate::column::Column::{
     self, ImmC, InstrVal, IsAdd, IsSub, OpA, OpA0, OpA1_4, OpB, OpB0, OpB1_4, OpC, OpC0_3, OpC4,
@@ -22,6 +24,8 @@ use crate::{
 pub struct TypeRChip;
 
 impl MachineChip for TypeRChip {
+    const NAME: &'static str = "TypeRChip";
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
@@ -93,7 +97,7 @@ impl MachineChip for TypeRChip {
         let instr_val = trace_eval!(trace_eval, InstrVal);
         eval.add_constraint(
             is_type_r.clone()
-                * (E::F::from(BaseField::from(0b0110011))
+                * (E::F::from(opcode_bits(BuiltinOpcode::ADD))
                     + op_a0.clone() * BaseField::from(1 << 7)
                     - instr_val[0].clone()),
         );
@@ -105,7 +109,9 @@ impl MachineChip for TypeRChip {
         eval.add_constraint(
             is_add.clone()
                 * (one.clone() - imm_c.clone())
-                * (op_a1_4.clone() + op_b0.clone() * BaseField::from(1 << 7)
+                * (op_a1_4.clone()
+                    + E::F::from(funct3_bits(BuiltinOpcode::ADD))
+                    + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val[1].clone()),
         );
 
@@ -114,7 +120,9 @@ impl MachineChip for TypeRChip {
         eval.add_constraint(
             is_sub.clone()
                 * (one.clone() - imm_c.clone())
-                * (op_a1_4.clone() + op_b0.clone() * BaseField::from(1 << 7)
+                * (op_a1_4.clone()
+                    + E::F::from(funct3_bits(BuiltinOpcode::SUB))
+                    + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val[1].clone()),
         );
 
@@ -124,7 +132,7 @@ impl MachineChip for TypeRChip {
             is_sll.clone()
                 * (one.clone() - imm_c.clone())
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b001)) * BaseField::from(1 << 4)
+                    + E::F::from(funct3_bits(BuiltinOpcode::SLL))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val[1].clone()),
         );
@@ -135,7 +143,7 @@ impl MachineChip for TypeRChip {
             is_slt.clone()
                 * (one.clone() - imm_c.clone())
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b010)) * BaseField::from(1 << 4)
+                    + E::F::from(funct3_bits(BuiltinOpcode::SLT))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val[1].clone()),
         );
@@ -146,7 +154,7 @@ impl MachineChip for TypeRChip {
             is_sltu.clone()
                 * (one.clone() - imm_c.clone())
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b011)) * BaseField::from(1 << 4)
+                    + E::F::from(funct3_bits(BuiltinOpcode::SLTU))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val[1].clone()),
         );
@@ -157,7 +165,7 @@ impl MachineChip for TypeRChip {
             is_xor.clone()
                 * (one.clone() - imm_c.clone())
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b100)) * BaseField::from(1 << 4)
+                    + E::F::from(funct3_bits(BuiltinOpcode::XOR))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val[1].clone()),
         );
@@ -168,7 +176,7 @@ impl MachineChip for TypeRChip {
             is_srl.clone()
                 * (one.clone() - imm_c.clone())
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b101)) * BaseField::from(1 << 4)
+                    + E::F::from(funct3_bits(BuiltinOpcode::SRL))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val[1].clone()),
         );
@@ -179,7 +187,7 @@ impl MachineChip for TypeRChip {
             is_sra.clone()
                 * (one.clone() - imm_c.clone())
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b101)) * BaseField::from(1 << 4)
+                    + E::F::from(funct3_bits(BuiltinOpcode::SRA))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val[1].clone()),
         );
@@ -190,7 +198,7 @@ impl MachineChip for TypeRChip {
             is_or.clone()
                 * (one.clone() - imm_c.clone())
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b110)) * BaseField::from(1 << 4)
+                    + E::F::from(funct3_bits(BuiltinOpcode::OR))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val[1].clone()),
         );
@@ -201,7 +209,7 @@ impl MachineChip for TypeRChip {
             is_and.clone()
                 * (one.clone() - imm_c.clone())
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b111)) * BaseField::from(1 << 4)
+                    + E::F::from(funct3_bits(BuiltinOpcode::AND))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val[1].clone()),
         );
@@ -212,7 +220,7 @@ impl MachineChip for TypeRChip {
             is_mul.clone()
                 * (one.clone() - imm_c.clone())
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b000)) * BaseField::from(1 << 4)
+                    + E::F::from(funct3_bits(BuiltinOpcode::MUL))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val[1].clone()),
         );
@@ -223,7 +231,7 @@ impl MachineChip for TypeRChip {
             is_mulhu.clone()
                 * (one.clone() - imm_c.clone())
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b011)) * BaseField::from(1 << 4)
+                    + E::F::from(funct3_bits(BuiltinOpcode::MULHU))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val[1].clone()),
         );
@@ -234,7 +242,7 @@ impl MachineChip for TypeRChip {
             is_divu.clone()
                 * (one.clone() - imm_c.clone())
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b101)) * BaseField::from(1 << 4)
+                    + E::F::from(funct3_bits(BuiltinOpcode::DIVU))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val[1].clone()),
         );
@@ -245,7 +253,7 @@ impl MachineChip for TypeRChip {
             is_remu.clone()
                 * (one.clone() - imm_c.clone())
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b111)) * BaseField::from(1 << 4)
+                    + E::F::from(funct3_bits(BuiltinOpcode::REMU))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val[1].clone()),
         );
@@ -256,7 +264,7 @@ impl MachineChip for TypeRChip {
             is_div.clone()
                 * (one.clone() - imm_c.clone())
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b100)) * BaseField::from(1 << 4)
+                    + E::F::from(funct3_bits(BuiltinOpcode::DIV))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val[1].clone()),
         );
@@ -267,7 +275,7 @@ impl MachineChip for TypeRChip {
             is_rem.clone()
                 * (one.clone() - imm_c.clone())
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b110)) * BaseField::from(1 << 4)
+                    + E::F::from(funct3_bits(BuiltinOpcode::REM))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val[1].clone()),
         );
@@ -278,7 +286,7 @@ impl MachineChip for TypeRChip {
             is_mulhsu.clone()
                 * (one.clone() - imm_c.clone())
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b010)) * BaseField::from(1 << 4)
+                    + E::F::from(funct3_bits(BuiltinOpcode::MULHSU))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val[1].clone()),
         );
@@ -289,7 +297,7 @@ impl MachineChip for TypeRChip {
             is_mulh.clone()
                 * (one.clone() - imm_c.clone())
                 * (op_a1_4.clone()
-                    + E::F::from(BaseField::from(0b001)) * BaseField::from(1 << 4)
+                    + E::F::from(funct3_bits(BuiltinOpcode::MULH))
                     + op_b0.clone() * BaseField::from(1 << 7)
                     - instr_val[1].clone()),
         );
@@ -305,8 +313,7 @@ impl MachineChip for TypeRChip {
         eval.add_constraint(
             is_add.clone()
                 * (one.clone() - imm_c.clone())
-                * (op_c4.clone()
-                    + E::F::from(BaseField::from(0b0000000)) * BaseField::from(1 << 1)
+                * (op_c4.clone() + E::F::from(funct7_bits(BuiltinOpcode::ADD))
                     - instr_val[3].clone()),
         );
 
@@ -314,8 +321,7 @@ impl MachineChip for TypeRChip {
         eval.add_constraint(
             is_sub.clone()
                 * (one.clone() - imm_c.clone())
-                * (op_c4.clone()
-                    + E::F::from(BaseField::from(0b0100000)) * BaseField::from(1 << 1)
+                * (op_c4.clone() + E::F::from(funct7_bits(BuiltinOpcode::SUB))
                     - instr_val[3].clone()),
         );
 
@@ -323,8 +329,7 @@ impl MachineChip for TypeRChip {
         eval.add_constraint(
             is_sll.clone()
                 * (one.clone() - imm_c.clone())
-                * (op_c4.clone()
-                    + E::F::from(BaseField::from(0b0000000)) * BaseField::from(1 << 1)
+                * (op_c4.clone() + E::F::from(funct7_bits(BuiltinOpcode::SLL))
                     - instr_val[3].clone()),
         );
 
@@ -332,8 +337,7 @@ impl MachineChip for TypeRChip {
         eval.add_constraint(
             is_slt.clone()
                 * (one.clone() - imm_c.clone())
-                * (op_c4.clone()
-                    + E::F::from(BaseField::from(0b0000000)) * BaseField::from(1 << 1)
+                * (op_c4.clone() + E::F::from(funct7_bits(BuiltinOpcode::SLT))
                     - instr_val[3].clone()),
         );
 
@@ -341,8 +345,7 @@ impl MachineChip for TypeRChip {
         eval.add_constraint(
             is_sltu.clone()
                 * (one.clone() - imm_c.clone())
-                * (op_c4.clone()
-                    + E::F::from(BaseField::from(0b0000000)) * BaseField::from(1 << 1)
+                * (op_c4.clone() + E::F::from(funct7_bits(BuiltinOpcode::SLTU))
                     - instr_val[3].clone()),
         );
 
@@ -350,8 +353,7 @@ impl MachineChip for TypeRChip {
         eval.add_constraint(
             is_xor.clone()
                 * (one.clone() - imm_c.clone())
-                * (op_c4.clone()
-                    + E::F::from(BaseField::from(0b0000000)) * BaseField::from(1 << 1)
+                * (op_c4.clone() + E::F::from(funct7_bits(BuiltinOpcode::XOR))
                     - instr_val[3].clone()),
         );
 
@@ -359,8 +361,7 @@ impl MachineChip for TypeRChip {
         eval.add_constraint(
             is_srl.clone()
                 * (one.clone() - imm_c.clone())
-                * (op_c4.clone()
-                    + E::F::from(BaseField::from(0b0000000)) * BaseField::from(1 << 1)
+                * (op_c4.clone() + E::F::from(funct7_bits(BuiltinOpcode::SRL))
                     - instr_val[3].clone()),
         );
 
@@ -368,8 +369,7 @@ impl MachineChip for TypeRChip {
         eval.add_constraint(
             is_sra.clone()
                 * (one.clone() - imm_c.clone())
-                * (op_c4.clone()
-                    + E::F::from(BaseField::from(0b0100000)) * BaseField::from(1 << 1)
+                * (op_c4.clone() + E::F::from(funct7_bits(BuiltinOpcode::SRA))
                     - instr_val[3].clone()),
         );
 
@@ -377,8 +377,7 @@ impl MachineChip for TypeRChip {
         eval.add_constraint(
             is_or.clone()
                 * (one.clone() - imm_c.clone())
-                * (op_c4.clone()
-                    + E::F::from(BaseField::from(0b0000000)) * BaseField::from(1 << 1)
+                * (op_c4.clone() + E::F::from(funct7_bits(BuiltinOpcode::OR))
                     - instr_val[3].clone()),
         );
 
@@ -386,8 +385,7 @@ impl MachineChip for TypeRChip {
         eval.add_constraint(
             is_and.clone()
                 * (one.clone() - imm_c.clone())
-                * (op_c4.clone()
-                    + E::F::from(BaseField::from(0b0000000)) * BaseField::from(1 << 1)
+                * (op_c4.clone() + E::F::from(funct7_bits(BuiltinOpcode::AND))
                     - instr_val[3].clone()),
         );
 
@@ -402,8 +400,7 @@ impl MachineChip for TypeRChip {
                 + is_mulhsu.clone()
                 + is_mulh.clone())
                 * (one.clone() - imm_c.clone())
-                * (op_c4.clone()
-                    + E::F::from(BaseField::from(0b0000001)) * BaseField::from(1 << 1)
+                * (op_c4.clone() + E::F::from(funct7_bits(BuiltinOpcode::MUL))
                     - instr_val[3].clone()),
         );
     }
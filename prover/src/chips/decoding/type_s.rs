@@ -9,7 +9,9 @@ use crate::{
     virtual_column::{self, VirtualColumn},
 };
 
-use nexus_vm::riscv::InstructionType::SType;
+use nexus_vm::riscv::{BuiltinOpcode, InstructionType::SType};
+
+use super::{funct3_bits, opcode_bits};
 
 use crate::column::Column;
 
@@ -18,6 +20,8 @@ use crate::trace::eval::trace_eval;
 pub struct TypeSChip;
 
 impl MachineChip for TypeSChip {
+    const NAME: &'static str = "TypeSChip";
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
@@ -154,7 +158,7 @@ impl MachineChip for TypeSChip {
 
         eval.add_constraint(
             is_type_s.clone()
-                * (E::F::from(BaseField::from(0b0100011))
+                * (E::F::from(opcode_bits(BuiltinOpcode::SB))
                     + op_c0.clone() * BaseField::from(1 << 7)
                     - value_instr[0].clone()),
         );
@@ -162,21 +166,21 @@ impl MachineChip for TypeSChip {
         eval.add_constraint(
             is_sb
                 * (op_c1_4.clone()
-                    + E::F::from(BaseField::from(0b000)) * BaseField::from(1 << 4)
+                    + E::F::from(funct3_bits(BuiltinOpcode::SB))
                     + op_a0.clone() * BaseField::from(1 << 7)
                     - value_instr[1].clone()),
         );
         eval.add_constraint(
             is_sh
                 * (op_c1_4.clone()
-                    + E::F::from(BaseField::from(0b001)) * BaseField::from(1 << 4)
+                    + E::F::from(funct3_bits(BuiltinOpcode::SH))
                     + op_a0.clone() * BaseField::from(1 << 7)
                     - value_instr[1].clone()),
         );
         eval.add_constraint(
             is_sw
                 * (op_c1_4.clone()
-                    + E::F::from(BaseField::from(0b010)) * BaseField::from(1 << 4)
+                    + E::F::from(funct3_bits(BuiltinOpcode::SW))
                     + op_a0.clone() * BaseField::from(1 << 7)
                     - value_instr[1].clone()),
         );
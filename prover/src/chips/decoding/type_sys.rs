@@ -3,9 +3,12 @@ use stwo_constraint_framework::EvalAtRow;
 
 use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
 
+use super::{funct12_low_bits, funct3_bits, opcode_bits};
+
 use crate::trace::eval::trace_eval;
 use crate::{
-    column::Column::{self, IsEbreak, IsEcall, OpB},
+    chips::{SYSCALL_ARG0_REG, SYSCALL_NUMBER_REG},
+    column::Column::{self, IsEbreak, IsEcall, OpB, OpC},
     components::AllLookupElements,
     extensions::ExtensionsConfig,
     traits::MachineChip,
@@ -15,6 +18,8 @@ use crate::{
 pub struct TypeSysChip;
 
 impl MachineChip for TypeSysChip {
+    const NAME: &'static str = "TypeSysChip";
+
     fn fill_main_trace(
         traces: &mut crate::trace::TracesBuilder,
         row_idx: usize,
@@ -33,8 +38,9 @@ impl MachineChip for TypeSysChip {
             return;
         }
 
-        // Set OpB to be 17
-        traces.fill_columns(row_idx, 17u8, OpB);
+        // Set OpB and OpC to the registers of the syscall number and of the first argument
+        traces.fill_columns(row_idx, SYSCALL_NUMBER_REG as u8, OpB);
+        traces.fill_columns(row_idx, SYSCALL_ARG0_REG as u8, OpC);
     }
     fn add_constraints<E: EvalAtRow>(
         eval: &mut E,
@@ -43,45 +49,43 @@ impl MachineChip for TypeSysChip {
         _config: &ExtensionsConfig,
     ) {
         let [is_type_sys] = IsTypeSys::eval(trace_eval);
-        // Making sure that op_b=x17
+        // Making sure that op_b=a7, the register of the syscall number
         // (is_type_sys)・ (17 - op_b) = 0
         let [op_b] = trace_eval!(trace_eval, OpB);
-        eval.add_constraint(is_type_sys.clone() * (E::F::from(BaseField::from(17)) - op_b));
-        // Making sure that op_c=0
-        // (is_type_sys)・ (op_c) = 0
-        let [op_c] = trace_eval!(trace_eval, crate::column::Column::OpC);
-        eval.add_constraint(is_type_sys.clone() * op_c);
-        // Computing c_val limbs
-        // (is_type_sys)・ (c_val_1) = 0
-        // (is_type_sys)・ (c_val_2) = 0
-        // (is_type_sys)・ (c_val_3) = 0
-        // (is_type_sys)・ (c_val_4) = 0
-        let c_val = trace_eval.column_eval::<WORD_SIZE>(crate::column::Column::ValueC);
-        for limb in c_val.into_iter() {
-            eval.add_constraint(is_type_sys.clone() * limb);
-        }
+        eval.add_constraint(
+            is_type_sys.clone() * (E::F::from(BaseField::from(SYSCALL_NUMBER_REG as u32)) - op_b),
+        );
+        // Making sure that op_c=a0, the register of the first argument, whose value is c_val
+        // (is_type_sys)・ (10 - op_c) = 0
+        let [op_c] = trace_eval!(trace_eval, OpC);
+        eval.add_constraint(
+            is_type_sys.clone() * (E::F::from(BaseField::from(SYSCALL_ARG0_REG as u32)) - op_c),
+        );
         let instr_val = trace_eval.column_eval::<WORD_SIZE>(crate::column::Column::InstrVal);
         // checking format of instructions - limb 1
         // (is_type_sys) ・ (b01110011 - instr_val_1) = 0
         eval.add_constraint(
-            is_type_sys.clone() * (E::F::from(BaseField::from(0b01110011)) - instr_val[0].clone()),
+            is_type_sys.clone()
+                * (E::F::from(opcode_bits(BuiltinOpcode::ECALL)) - instr_val[0].clone()),
         );
         // checking format of instructions - limb 2
         // (is_type_sys) ・ (b00000000 - instr_val_2) = 0
         eval.add_constraint(
-            is_type_sys.clone() * (E::F::from(BaseField::from(0b00000000)) - instr_val[1].clone()),
+            is_type_sys.clone()
+                * (E::F::from(funct3_bits(BuiltinOpcode::ECALL)) - instr_val[1].clone()),
         );
         // checking format of instructions - limb 3
         // (is_ecall) ・ (b0000 + b0000・2^4 - instr_val_3) = 0
         let [is_ecall] = trace_eval!(trace_eval, IsEcall);
         eval.add_constraint(
-            is_ecall.clone() * (E::F::from(BaseField::from(0b0000)) - instr_val[2].clone()),
+            is_ecall.clone()
+                * (E::F::from(funct12_low_bits(BuiltinOpcode::ECALL)) - instr_val[2].clone()),
         );
-        // (is_ebreak)・ (b0000 + b1000・2^4 - instr_val_3) = 0
+        // (is_ebreak)・ (b0000 + b0001・2^4 - instr_val_3) = 0
         let [is_ebreak] = trace_eval!(trace_eval, IsEbreak);
         eval.add_constraint(
             is_ebreak.clone()
-                * (E::F::from(BaseField::from(0b1000 * (1 << 4))) - instr_val[2].clone()),
+                * (E::F::from(funct12_low_bits(BuiltinOpcode::EBREAK)) - instr_val[2].clone()),
         );
         // checking format of instructions - limb 4
         // (is_type_sys) ・ (b00000000 - instr_val_4) = 0
@@ -9,7 +9,9 @@ use crate::{
     virtual_column::{self, VirtualColumn},
 };
 
-use nexus_vm::riscv::InstructionType::UType;
+use nexus_vm::riscv::{BuiltinOpcode, InstructionType::UType};
+
+use super::opcode_bits;
 
 use crate::column::Column::{self, OpA, OpA0, OpA1_4, OpC, OpC12_15, OpC16_23, OpC24_31, ValueC};
 
@@ -18,6 +20,8 @@ use crate::trace::eval::trace_eval;
 pub struct TypeUChip;
 
 impl MachineChip for TypeUChip {
+    const NAME: &'static str = "TypeUChip";
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
@@ -109,7 +113,7 @@ impl MachineChip for TypeUChip {
         // is_lui ・ (b0110111 + op_a0・2^7 - instr_val_1) = 0
         let instr_val = trace_eval!(trace_eval, Column::InstrVal);
         let [is_lui] = trace_eval!(trace_eval, Column::IsLui);
-        let lui_opcode: E::F = BaseField::from(0b0110111).into();
+        let lui_opcode: E::F = opcode_bits(BuiltinOpcode::LUI).into();
         eval.add_constraint(
             is_lui.clone()
                 * (lui_opcode + op_a0.clone() * BaseField::from(1 << 7) - instr_val[0].clone()),
@@ -117,7 +121,7 @@ impl MachineChip for TypeUChip {
 
         // is_auipc ・ (b0010111 + op_a0・2^7 - instr_val_1) = 0
         let [is_auipc] = trace_eval!(trace_eval, Column::IsAuipc);
-        let auipc_opcode: E::F = BaseField::from(0b0010111).into();
+        let auipc_opcode: E::F = opcode_bits(BuiltinOpcode::AUIPC).into();
         eval.add_constraint(
             is_auipc.clone()
                 * (auipc_opcode + op_a0.clone() * BaseField::from(1 << 7) - instr_val[0].clone()),
@@ -0,0 +1,329 @@
+//! Fused address materialization, a LUI or an AUIPC followed by an ADDI on the same register.
+//!
+//! [`fuse_address_materialization`](nexus_vm::trace::fuse_address_materialization) merges such pairs into a single
+//! step, proven on a single row. `LuiChip` and `AuipcChip` constrain the upper part of the address into `FusedBase`
+//! instead of `ValueA`, and [`FusedLiChip`] adds the sign-extended immediate of the ADDI on top of it. The ADDI word
+//! is read from the program memory at Pc + 4 by `ProgramMemCheckChip`, and is decoded here byte by byte against the
+//! destination register of the row, which is both the source and the destination of the ADDI.
+//!
+//! The chip fills and constrains the following columns, all of them are zero on rows that aren't fused:
+//!
+//! - `FusedInstrVal`: the ADDI word, range-checked in `Range256Chip`.
+//! - `FusedBase`: the result of the LUI or AUIPC, filled by their chips and range-checked in `Range256Chip`.
+//! - `FusedImmLow`, `FusedImmBits`: the lowest byte of the immediate, followed by its bits 8 to 11, the last one
+//!   being the sign. They are range-checked in `Range256Chip` and `RangeBoolChip`.
+//! - `FusedCarry`: carries of `FusedBase + imm` at 16-bit boundaries.
+
+use num_traits::One;
+use stwo::core::fields::m31::BaseField;
+use stwo_constraint_framework::EvalAtRow;
+
+use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
+
+use super::add_with_carries;
+use crate::{
+    chips::{
+        decoding::{funct3_bits, opcode_bits},
+        word_decomp::fill_word_limbs,
+    },
+    column::Column::{self, *},
+    components::AllLookupElements,
+    extensions::ExtensionsConfig,
+    trace::{
+        eval::{trace_eval, TraceEval},
+        sidenote::SideNote,
+        ProgramStep, TracesBuilder, Word,
+    },
+    traits::{ExecuteChip, MachineChip},
+};
+
+/// Column holding the result of a LUI or an AUIPC: `FusedBase` on fused rows, `ValueA` otherwise.
+pub(crate) fn upper_result_column(vm_step: &ProgramStep) -> Column {
+    if vm_step.step.fused.is_some() {
+        FusedBase
+    } else {
+        ValueA
+    }
+}
+
+/// Evaluates the result of a LUI or an AUIPC, see [`upper_result_column`].
+///
+/// Selecting the column adds one to the degree of the constraints using it.
+pub(crate) fn upper_result<E: EvalAtRow>(trace_eval: &TraceEval<E>) -> [E::F; WORD_SIZE] {
+    let value_a = trace_eval!(trace_eval, ValueA);
+    let fused_base = trace_eval!(trace_eval, FusedBase);
+    let [is_fused_li] = trace_eval!(trace_eval, IsFusedLi);
+    std::array::from_fn(|i| {
+        value_a[i].clone() + is_fused_li.clone() * (fused_base[i].clone() - value_a[i].clone())
+    })
+}
+
+pub struct ExecutionResult {
+    imm_low: u8,
+    imm_bits: [bool; WORD_SIZE], // Bits 8 to 11 of the immediate
+    value_a: Word,
+    carry_bits: [bool; 2], // At 16-bit boundaries
+}
+
+pub struct FusedLiChip;
+
+impl ExecuteChip for FusedLiChip {
+    type ExecutionResult = ExecutionResult;
+
+    fn execute(program_step: &ProgramStep) -> Self::ExecutionResult {
+        let step = &program_step.step;
+        let fused = step.fused.as_ref().expect("step must be fused");
+        let upper = step.instruction.op_c << 12;
+        let base = match step.instruction.opcode.builtin() {
+            Some(BuiltinOpcode::AUIPC) => step.pc.wrapping_add(upper),
+            _ => upper,
+        };
+        // The immediate of the ADDI, sign-extended from the top 12 bits of its word.
+        let imm = ((fused.raw_instruction as i32) >> 20) as u32;
+        let (value_a, carry_bits) = add_with_carries(base.to_le_bytes(), imm.to_le_bytes());
+
+        ExecutionResult {
+            imm_low: imm as u8,
+            imm_bits: std::array::from_fn(|i| (imm >> (8 + i)) & 1 == 1),
+            value_a,
+            carry_bits: [carry_bits[1], carry_bits[3]],
+        }
+    }
+}
+
+impl MachineChip for FusedLiChip {
+    const NAME: &'static str = "FusedLiChip";
+
+    fn fill_main_trace(
+        traces: &mut TracesBuilder,
+        row_idx: usize,
+        vm_step: &Option<ProgramStep>,
+        _side_note: &mut SideNote,
+        _config: &ExtensionsConfig,
+    ) {
+        let vm_step = match vm_step {
+            Some(vm_step) => vm_step,
+            None => return, // padding
+        };
+        let fused = match vm_step.step.fused.as_ref() {
+            Some(fused) => fused,
+            None => return,
+        };
+
+        let ExecutionResult {
+            imm_low,
+            imm_bits,
+            value_a,
+            carry_bits,
+        } = Self::execute(vm_step);
+
+        fill_word_limbs(traces, row_idx, fused.raw_instruction, FusedInstrVal);
+        traces.fill_columns(row_idx, imm_low, FusedImmLow);
+        traces.fill_columns(row_idx, imm_bits, FusedImmBits);
+        fill_word_limbs(traces, row_idx, value_a, ValueA);
+        traces.fill_columns(row_idx, carry_bits, FusedCarry);
+    }
+
+    fn add_constraints<E: EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &TraceEval<E>,
+        _lookup_elements: &AllLookupElements,
+        _config: &ExtensionsConfig,
+    ) {
+        let [is_fused_li] = trace_eval!(trace_eval, IsFusedLi);
+        let [is_lui] = trace_eval!(trace_eval, IsLui);
+        let [is_auipc] = trace_eval!(trace_eval, IsAuipc);
+
+        // Only LUI and AUIPC are fused
+        // is_fused_li・(1 - is_lui - is_auipc) = 0
+        eval.add_constraint(is_fused_li.clone() * (E::F::one() - is_lui - is_auipc));
+
+        let instr_val = trace_eval!(trace_eval, FusedInstrVal);
+        let [op_a0] = trace_eval!(trace_eval, OpA0);
+        let [op_a1_4] = trace_eval!(trace_eval, OpA1_4);
+        let modulus_7 = E::F::from(BaseField::from(1 << 7));
+        let modulus_8 = E::F::from(BaseField::from(1 << 8));
+
+        // The ADDI word is opcode | rd << 7 | funct3 << 12 | rs1 << 15 | imm << 20, with rs1 = rd = op_a.
+        // is_fused_li・(b0010011 + op_a0・2^7 - instr_val_1) = 0
+        eval.add_constraint(
+            is_fused_li.clone()
+                * (E::F::from(opcode_bits(BuiltinOpcode::ADDI))
+                    + op_a0.clone() * modulus_7.clone()
+                    - instr_val[0].clone()),
+        );
+        // is_fused_li・(op_a1_4 + funct3 + op_a0・2^7 - instr_val_2) = 0
+        eval.add_constraint(
+            is_fused_li.clone()
+                * (op_a1_4.clone()
+                    + E::F::from(funct3_bits(BuiltinOpcode::ADDI))
+                    + op_a0 * modulus_7
+                    - instr_val[1].clone()),
+        );
+
+        let [imm_low] = trace_eval!(trace_eval, FusedImmLow);
+        let imm_bits = trace_eval!(trace_eval, FusedImmBits);
+        let imm_high = imm_bits[0].clone()
+            + imm_bits[1].clone() * BaseField::from(2)
+            + imm_bits[2].clone() * BaseField::from(4);
+        let sign = imm_bits[3].clone();
+        let imm = imm_low.clone()
+            + (imm_high.clone() + sign.clone() * BaseField::from(8)) * modulus_8.clone();
+        // Bounded by range checks of the immediate and the ADDI word, therefore no wrap-around.
+        // is_fused_li・(imm・2^4 - (instr_val_3 - op_a1_4) - instr_val_4・2^8) = 0
+        eval.add_constraint(
+            is_fused_li.clone()
+                * (imm * BaseField::from(1 << 4)
+                    - (instr_val[2].clone() - op_a1_4)
+                    - instr_val[3].clone() * modulus_8.clone()),
+        );
+
+        let base = trace_eval!(trace_eval, FusedBase);
+        let value_a = trace_eval!(trace_eval, ValueA);
+        let carry = trace_eval!(trace_eval, FusedCarry);
+        let modulus_16 = E::F::from(BaseField::from(1 << 16));
+        // The sign-extended immediate in two halfwords
+        let imm_lo =
+            imm_low + (imm_high + sign.clone() * BaseField::from(0xF8)) * modulus_8.clone();
+        let imm_hi = sign * BaseField::from(0xFFFF);
+
+        // a_val = base + imm, in halfwords
+        // is_fused_li・(base_1 + base_2・2^8 + imm_lo - carry_1・2^16 - a_val_1 - a_val_2・2^8) = 0
+        eval.add_constraint(
+            is_fused_li.clone()
+                * (base[0].clone() + base[1].clone() * modulus_8.clone() + imm_lo
+                    - carry[0].clone() * modulus_16.clone()
+                    - value_a[0].clone()
+                    - value_a[1].clone() * modulus_8.clone()),
+        );
+        // is_fused_li・(base_3 + base_4・2^8 + imm_hi + carry_1 - carry_2・2^16 - a_val_3 - a_val_4・2^8) = 0
+        eval.add_constraint(
+            is_fused_li
+                * (base[2].clone()
+                    + base[3].clone() * modulus_8.clone()
+                    + imm_hi
+                    + carry[0].clone()
+                    - carry[1].clone() * modulus_16
+                    - value_a[2].clone()
+                    - value_a[3].clone() * modulus_8),
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::panic::{self, AssertUnwindSafe};
+
+    use super::*;
+    use crate::{
+        chips::{
+            AddChip, AuipcChip, CpuChip, DecodingCheckChip, LuiChip, ProgramMemCheckChip,
+            RegisterMemCheckChip,
+        },
+        test_utils::assert_chip,
+        trace::{
+            program::iter_program_steps, program_trace::ProgramTracesBuilder,
+            utils::FromBaseFields, PreprocessedTraces,
+        },
+    };
+    use nexus_common::constants::ELF_TEXT_START;
+    use nexus_vm::{
+        emulator::InternalView,
+        riscv::{BasicBlock, Instruction, Opcode},
+        trace::{fuse_address_materialization, k_trace_direct},
+    };
+
+    const LOG_SIZE: u32 = PreprocessedTraces::MIN_LOG_SIZE;
+
+    type Chips = (
+        CpuChip,
+        DecodingCheckChip,
+        LuiChip,
+        AuipcChip,
+        AddChip,
+        FusedLiChip,
+        ProgramMemCheckChip,
+        RegisterMemCheckChip,
+    );
+
+    fn setup_basic_block_ir() -> Vec<BasicBlock> {
+        let basic_block = BasicBlock::new(vec![
+            // li x1, 0x12345678
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LUI), 1, 0, 0x12345),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 1, 0x678),
+            // Negative immediate, borrowing from the upper part
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LUI), 2, 0, 0x1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 2, 0xFFF),
+            // Pc-relative address
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::AUIPC), 3, 0, 0x10),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 3, 3, 0x800),
+            // Different registers aren't fused
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LUI), 4, 0, 0x1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 5, 4, 0x1),
+            // Wrapping around
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LUI), 6, 0, 0x0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 6, 6, 0xFFF),
+        ]);
+        vec![basic_block]
+    }
+
+    fn fill_traces() -> (TracesBuilder, ProgramTracesBuilder) {
+        let (view, vm_traces) =
+            k_trace_direct(&setup_basic_block_ir(), 1).expect("Failed to create trace");
+        let vm_traces = fuse_address_materialization(&vm_traces);
+
+        let mut traces = TracesBuilder::new(LOG_SIZE);
+        let program_traces =
+            ProgramTracesBuilder::new_with_empty_memory(LOG_SIZE, view.get_program_memory());
+        let mut side_note = SideNote::new(&program_traces, &view);
+        for (row_idx, program_step) in iter_program_steps(&vm_traces, traces.num_rows()).enumerate()
+        {
+            Chips::fill_main_trace(
+                &mut traces,
+                row_idx,
+                &program_step,
+                &mut side_note,
+                &ExtensionsConfig::default(),
+            );
+        }
+        (traces, program_traces)
+    }
+
+    #[test]
+    fn test_k_trace_constrained_fused_li() {
+        let (traces, program_traces) = fill_traces();
+        // Fused pairs take a single row each, the unfused pair takes rows 3 and 4
+        let values: Vec<u32> = [0, 1, 2, 3, 5]
+            .map(|row_idx| u32::from_base_fields(traces.column(row_idx, ValueA)))
+            .into();
+        let auipc_pc = ELF_TEXT_START + 4 * WORD_SIZE as u32;
+        assert_eq!(
+            values,
+            [
+                0x12345678,
+                0xFFF,
+                auipc_pc + 0x10000 - 0x800,
+                0x1000,
+                0xFFFF_FFFF
+            ]
+        );
+
+        assert_chip::<Chips>(traces, Some(program_traces.finalize()));
+    }
+
+    #[test]
+    fn test_fused_li_wrong_immediate_rejected() {
+        let (mut traces, program_traces) = fill_traces();
+        // Claim x1 = 0x12345679, the ADDI word no longer matches the immediate
+        let [imm_low] = traces.column_mut(0, FusedImmLow);
+        *imm_low += BaseField::one();
+        let [value_a, ..] = traces.column_mut::<WORD_SIZE>(0, ValueA);
+        *value_a += BaseField::one();
+
+        let program_trace = program_traces.finalize();
+        let rejected = panic::catch_unwind(AssertUnwindSafe(|| {
+            assert_chip::<Chips>(traces, Some(program_trace))
+        }));
+        assert!(rejected.is_err());
+    }
+}
@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use stwo::core::fields::FieldExpOps;
 use stwo_constraint_framework::EvalAtRow;
 
@@ -6,6 +8,7 @@ use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
 use crate::{
     column::Column::{self, *},
     components::AllLookupElements,
+    description::{ChipDescription, ConstraintDescription},
     extensions::ExtensionsConfig,
     trace::{
         eval::{trace_eval, TraceEval},
@@ -61,6 +64,38 @@ impl ExecuteChip for AddChip {
 }
 
 impl MachineChip for AddChip {
+    const NAME: &'static str = "AddChip";
+
+    fn supported_opcodes() -> &'static [BuiltinOpcode] {
+        &[BuiltinOpcode::ADD, BuiltinOpcode::ADDI]
+    }
+
+    fn describe() -> Option<ChipDescription> {
+        Some(ChipDescription {
+            chip: "AddChip",
+            constraints: &[
+                ConstraintDescription {
+                    id: "sum_low",
+                    description: "On ADD and ADDI rows, the low 16 bits of ValueA are the sum of the low 16 bits of \
+                        ValueB and ValueC, with the carry out in CarryFlag[0].",
+                    columns: &["IsAdd", "ValueA", "ValueB", "ValueC", "CarryFlag"],
+                    degree: 2,
+                    count: 1,
+                },
+                ConstraintDescription {
+                    id: "sum_high",
+                    description: "On ADD and ADDI rows, the high 16 bits of ValueA are the sum of the high 16 bits \
+                        of ValueB and ValueC and CarryFlag[0], with the carry out in CarryFlag[1]. The carries are \
+                        range checked as booleans by the range check chips.",
+                    columns: &["IsAdd", "ValueA", "ValueB", "ValueC", "CarryFlag"],
+                    degree: 2,
+                    count: 1,
+                },
+            ],
+            lookups: &[],
+        })
+    }
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
@@ -95,6 +130,49 @@ impl MachineChip for AddChip {
         traces.fill_columns(row_idx, carry_bits, CarryFlag);
     }
 
+    fn fill_main_trace_chunk(
+        traces: &mut TracesBuilder,
+        rows: Range<usize>,
+        steps: &[Option<ProgramStep>],
+        _side_note: &mut SideNote,
+        _config: &ExtensionsConfig,
+    ) {
+        assert_eq!(rows.len(), steps.len(), "chunk size mismatch");
+        let results: Vec<(usize, ExecutionResult)> = rows
+            .zip(steps)
+            .filter_map(|(row_idx, vm_step)| {
+                let vm_step = vm_step.as_ref()?;
+                if !matches!(
+                    vm_step.step.instruction.opcode.builtin(),
+                    Some(BuiltinOpcode::ADD) | Some(BuiltinOpcode::ADDI)
+                ) {
+                    return None;
+                }
+                let result = Self::execute(vm_step);
+                assert_eq!(
+                    result.sum_bytes,
+                    vm_step
+                        .get_result()
+                        .expect("ADD/ADDI instruction must have a result")
+                );
+                Some((row_idx, result))
+            })
+            .collect();
+
+        traces.fill_columns_batch(
+            results
+                .iter()
+                .map(|(row_idx, result)| (*row_idx, result.sum_bytes)),
+            ValueA,
+        );
+        traces.fill_columns_batch(
+            results
+                .iter()
+                .map(|(row_idx, result)| (*row_idx, result.carry_bits)),
+            CarryFlag,
+        );
+    }
+
     fn add_constraints<E: EvalAtRow>(
         eval: &mut E,
         trace_eval: &TraceEval<E>,
@@ -143,10 +221,10 @@ mod test {
 
     use crate::{
         chips::{
-            CpuChip, DecodingCheckChip, ProgramMemCheckChip, RangeCheckChip, RegisterMemCheckChip,
-            TimestampChip,
+            CpuChip, DecodingCheckChip, LuiChip, ProgramMemCheckChip, RangeCheckChip,
+            RegisterMemCheckChip, TimestampChip,
         },
-        test_utils::assert_chip,
+        test_utils::{assert_chip, exhaustive_chip_test, SMALL_DOMAIN},
         trace::{
             program::iter_program_steps, program_trace::ProgramTracesBuilder, PreprocessedTraces,
         },
@@ -246,4 +324,9 @@ mod test {
         }
         assert_chip::<Chips>(traces, Some(program_trace.finalize()));
     }
+
+    #[test]
+    fn test_exhaustive_add() {
+        exhaustive_chip_test::<(AddChip, LuiChip)>(BuiltinOpcode::ADD, SMALL_DOMAIN);
+    }
 }
@@ -4,6 +4,10 @@ use stwo_constraint_framework::EvalAtRow;
 use nexus_vm::riscv::BuiltinOpcode;
 
 use crate::{
+    chips::{
+        instructions::fused_li::{upper_result, upper_result_column},
+        word_decomp::fill_word_limbs,
+    },
     column::Column::{self, *},
     components::AllLookupElements,
     extensions::ExtensionsConfig,
@@ -43,6 +47,12 @@ impl ExecuteChip for AuipcChip {
 }
 
 impl MachineChip for AuipcChip {
+    const NAME: &'static str = "AuipcChip";
+
+    fn supported_opcodes() -> &'static [BuiltinOpcode] {
+        &[BuiltinOpcode::AUIPC]
+    }
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
@@ -68,7 +78,7 @@ impl MachineChip for AuipcChip {
 
         // Fill valueA and its carry flag.
         // ValueC is filled in CPUChip.
-        traces.fill_columns(row_idx, value_a, Column::ValueA);
+        fill_word_limbs(traces, row_idx, value_a, upper_result_column(vm_step));
         traces.fill_columns(row_idx, carry_bits, Column::CarryFlag);
     }
 
@@ -79,13 +89,13 @@ impl MachineChip for AuipcChip {
         _config: &ExtensionsConfig,
     ) {
         let modulus = E::F::from(256u32.into());
-        let value_a = trace_eval!(trace_eval, ValueA);
+        let value_a = upper_result(trace_eval);
         let carry_bits = trace_eval!(trace_eval, CarryFlag);
         let value_c = trace_eval!(trace_eval, ValueC);
         let pc = trace_eval!(trace_eval, Pc);
         let [is_auipc] = trace_eval!(trace_eval, Column::IsAuipc);
 
-        // Setting a_val = pc + c_val
+        // Setting a_val = pc + c_val, a_val is FusedBase on fused rows
         // is_auipc・(pc_1 + pc_2 * 256 + c_val_1 + c_val_2 * 256 - carry_1·2^{16} - a_val_1 - a_val_2 * 256) = 0
         eval.add_constraint(
             is_auipc.clone()
@@ -5,6 +5,7 @@ use stwo_constraint_framework::EvalAtRow;
 use nexus_vm::riscv::BuiltinOpcode;
 
 use crate::{
+    chips::word_decomp::fill_word_limbs,
     column::Column::{self, *},
     components::AllLookupElements,
     extensions::ExtensionsConfig,
@@ -106,6 +107,12 @@ impl ExecuteChip for BeqChip {
 }
 
 impl MachineChip for BeqChip {
+    const NAME: &'static str = "BeqChip";
+
+    fn supported_opcodes() -> &'static [BuiltinOpcode] {
+        &[BuiltinOpcode::BEQ]
+    }
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
@@ -139,7 +146,7 @@ impl MachineChip for BeqChip {
         traces.fill_columns(row_idx, neq_34_flag, Column::Neq34);
 
         // Fill valueA
-        traces.fill_columns(row_idx, vm_step.get_value_a(), Column::ValueA);
+        fill_word_limbs(traces, row_idx, vm_step.get_value_a(), Column::ValueA);
 
         // TODO: it's possible to pack neq_{12,34}_flag into diff and store in Helper
         // NeqAux = 1 / (valueA - valueB); If valueA == valueB, NeqAux is random non-zero value.
@@ -277,42 +284,41 @@ mod test {
     use super::*;
     use nexus_vm::{
         emulator::InternalView,
-        riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+        riscv::{builder::ProgramBuilder, BasicBlock},
         trace::k_trace_direct,
     };
 
     const LOG_SIZE: u32 = PreprocessedBuilder::MIN_LOG_SIZE;
 
     fn setup_basic_block_ir() -> Vec<BasicBlock> {
-        let basic_block = BasicBlock::new(vec![
+        ProgramBuilder::new()
             // Set x1 = 10
-            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 10),
+            .addi(1, 0, 10)
             // Set x2 = 20
-            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 20),
+            .addi(2, 0, 20)
             // Set x3 = 10 (same as x1)
-            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 3, 0, 10),
+            .addi(3, 0, 10)
             // Set x4 = -10
-            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 4, 0, 1),
+            .sub(4, 0, 1)
             // Case 1: BEQ with different values (should not branch)
-            // BEQ x1, x2, 0xff (branch to PC + 0xff if x1 == x2)
-            Instruction::new_ir(Opcode::from(BuiltinOpcode::BEQ), 1, 2, 0xff),
+            .beq(1, 2, "fail")
             // Case 2: BEQ with equal values (should branch)
-            // BEQ x1, x3, 12 (should branch as x1 == x3)
-            Instruction::new_ir(Opcode::from(BuiltinOpcode::BEQ), 1, 3, 12),
+            .beq(1, 3, "case3")
             // Unimpl instructions to fill the gap (trigger error when executed)
-            Instruction::unimpl(),
-            Instruction::unimpl(),
+            .label("fail")
+            .unimpl()
+            .unimpl()
             // Case 3: BEQ with zero and non-zero (should not branch)
-            // BEQ x0, x1, 0xff (branch to PC + 0xff if x0 == x1)
-            Instruction::new_ir(Opcode::from(BuiltinOpcode::BEQ), 0, 1, 0xff),
+            .label("case3")
+            .beq(0, 1, "fail")
             // Case 4: BEQ with zero and zero (should branch)
-            // BEQ x0, x0, 8 (should branch as x0 == x0)
-            Instruction::new_ir(Opcode::from(BuiltinOpcode::BEQ), 0, 0, 8),
+            .beq(0, 0, "end")
             // Unimpl instructions to fill the gap (trigger error when executed)
-            Instruction::unimpl(),
-            Instruction::nop(),
-        ]);
-        vec![basic_block]
+            .unimpl()
+            .label("end")
+            .nop()
+            .build()
+            .unwrap()
     }
 
     #[test]
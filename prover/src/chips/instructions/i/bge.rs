@@ -2,9 +2,13 @@ use num_traits::One;
 use stwo::core::fields::FieldExpOps;
 use stwo_constraint_framework::EvalAtRow;
 
-use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
+use nexus_vm::riscv::BuiltinOpcode;
 
 use crate::{
+    chips::{
+        instructions::word_cmp::{self, Comparison},
+        word_decomp::fill_word_limbs,
+    },
     column::Column::{self, *},
     components::AllLookupElements,
     extensions::ExtensionsConfig,
@@ -19,13 +23,9 @@ use crate::{
 use super::add::{self};
 
 pub struct ExecutionResult {
-    pub diff_bytes: Word,
-    pub borrow_bits: [bool; 2], // at 16-bit boundaries
+    pub cmp: Comparison,
     pub pc_next: Word,
     pub carry_bits: [bool; 2],
-    pub lt_flag: bool,
-    pub h2: Word,
-    pub h3: Word,
 }
 
 pub struct BgeChip;
@@ -36,51 +36,36 @@ impl ExecuteChip for BgeChip {
     fn execute(program_step: &ProgramStep) -> Self::ExecutionResult {
         let value_a = program_step.get_value_a();
         let value_b = program_step.get_value_b();
-        let sgn_a = program_step.get_sgn_a();
-        let sgn_b = program_step.get_sgn_b();
         let imm = program_step.get_value_c().0;
         let pc = program_step.step.pc.to_le_bytes();
 
-        let (diff_bytes, borrow_bits) = super::sub::subtract_with_borrow(value_a, value_b);
-
-        let result = match (sgn_a, sgn_b) {
-            (false, false) | (true, true) => borrow_bits[3],
-            (false, true) => false,
-            (true, false) => true,
-        };
-
-        let borrow_bits = [borrow_bits[1], borrow_bits[3]];
+        // The last borrow of the sign-flipped subtraction is the signed a < b.
+        let cmp = word_cmp::compare(value_a, value_b, true);
 
-        // lt_flag is equal to result
-        let (pc_next, carry_bits) = if result {
+        let (pc_next, carry_bits) = if cmp.lt() {
             // a < b is true: pc_next = pc + 4
             add::add_with_carries(pc, 4u32.to_le_bytes())
         } else {
             // a >= b is true: pc_next = pc + imm
             add::add_with_carries(pc, imm)
         };
-
-        let mut h2 = value_a;
-        let mut h3 = value_b;
-        // h2 and h3 are value_a and value_b with the sign bit cleared
-        h2[WORD_SIZE - 1] &= 0x7f;
-        h3[WORD_SIZE - 1] &= 0x7f;
-
         let carry_bits = [carry_bits[1], carry_bits[3]];
 
         ExecutionResult {
-            diff_bytes,
-            borrow_bits,
+            cmp,
             pc_next,
             carry_bits,
-            lt_flag: result,
-            h2,
-            h3,
         }
     }
 }
 
 impl MachineChip for BgeChip {
+    const NAME: &'static str = "BgeChip";
+
+    fn supported_opcodes() -> &'static [BuiltinOpcode] {
+        &[BuiltinOpcode::BGE]
+    }
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
@@ -100,25 +85,15 @@ impl MachineChip for BgeChip {
         }
 
         let ExecutionResult {
-            diff_bytes,
-            borrow_bits,
+            cmp,
             pc_next,
             carry_bits,
-            lt_flag,
-            h2,
-            h3,
         } = Self::execute(vm_step);
 
-        traces.fill_columns(row_idx, diff_bytes, Column::Helper1);
-        traces.fill_columns(row_idx, borrow_bits, Column::BorrowFlag);
-        traces.fill_columns(row_idx, vm_step.get_sgn_a(), Column::SgnA);
-        traces.fill_columns(row_idx, vm_step.get_sgn_b(), Column::SgnB);
-        traces.fill_columns(row_idx, h2, Column::Helper2);
-        traces.fill_columns(row_idx, h3, Column::Helper3);
-        traces.fill_columns(row_idx, lt_flag, Column::LtFlag);
+        word_cmp::fill_cmp_columns(traces, row_idx, &cmp);
 
         // Fill valueA
-        traces.fill_columns(row_idx, vm_step.get_value_a(), Column::ValueA);
+        fill_word_limbs(traces, row_idx, vm_step.get_value_a(), Column::ValueA);
 
         // Fill PcNext and CarryFlag, since Pc and Immediate are filled to the main trace in CPU.
         traces.fill_columns(row_idx, pc_next, Column::PcNext);
@@ -132,68 +107,17 @@ impl MachineChip for BgeChip {
         _config: &ExtensionsConfig,
     ) {
         let modulus = E::F::from(256u32.into());
-        let modulus_7 = E::F::from(128u32.into());
         let value_a = trace_eval!(trace_eval, ValueA);
         let value_b = trace_eval!(trace_eval, ValueB);
         let value_c = trace_eval!(trace_eval, ValueC);
         let pc = trace_eval!(trace_eval, Column::Pc);
         let carry_bits = trace_eval!(trace_eval, Column::CarryFlag);
-        let borrow_bits = trace_eval!(trace_eval, Column::BorrowFlag);
-        let diff_bytes = trace_eval!(trace_eval, Column::Helper1);
         let pc_next = trace_eval!(trace_eval, Column::PcNext);
         let [is_bge] = trace_eval!(trace_eval, Column::IsBge);
-        let ltu_flag = borrow_bits[1].clone();
-        let [lt_flag] = trace_eval!(trace_eval, Column::LtFlag);
-        let h2 = trace_eval!(trace_eval, Column::Helper2);
-        let h3 = trace_eval!(trace_eval, Column::Helper3);
-        let [sgn_a] = trace_eval!(trace_eval, Column::SgnA);
-        let [sgn_b] = trace_eval!(trace_eval, Column::SgnB);
 
-        // is_bge・(a_val_1 + a_val_2 * 256 - b_val_1 - b_val_2 * 256 - h1_1 - h1_2 * 256 + borrow_1・2^{16}) = 0
-        eval.add_constraint(
-            is_bge.clone()
-                * (value_a[0].clone() + value_a[1].clone() * modulus.clone()
-                    - value_b[0].clone()
-                    - value_b[1].clone() * modulus.clone()
-                    - diff_bytes[0].clone()
-                    - diff_bytes[1].clone() * modulus.clone()
-                    + borrow_bits[0].clone() * modulus.clone().pow(2)),
-        );
-
-        // is_bge・(a_val_3 + a_val_4 * 256 - b_val_3 - b_val_4 * 256 - h1_3 - h1_4 * 256 + borrow_2・2^{16} - borrow_1) = 0
-        eval.add_constraint(
-            is_bge.clone()
-                * (value_a[2].clone() + value_a[3].clone() * modulus.clone()
-                    - value_b[2].clone()
-                    - value_b[3].clone() * modulus.clone()
-                    - diff_bytes[2].clone()
-                    - diff_bytes[3].clone() * modulus.clone()
-                    + borrow_bits[1].clone() * modulus.clone().pow(2)
-                    - borrow_bits[0].clone()),
-        );
-
-        // is_bge・ (h2 + sgna・2^7 - a_val_4) = 0
-        // is_bge・ (h3 + sgnb・2^7 - b_val_4) = 0
-        eval.add_constraint(
-            is_bge.clone()
-                * (h2[WORD_SIZE - 1].clone() + sgn_a.clone() * modulus_7.clone()
-                    - value_a[WORD_SIZE - 1].clone()),
-        );
-        eval.add_constraint(
-            is_bge.clone()
-                * (h3[WORD_SIZE - 1].clone() + sgn_b.clone() * modulus_7.clone()
-                    - value_b[WORD_SIZE - 1].clone()),
-        );
-
-        // is_bge・ (sgna・(1-sgnb) + ltu_flag・(sgna・sgnb+(1-sgna)・(1-sgnb)) - lt_flag) =0
-        eval.add_constraint(
-            is_bge.clone()
-                * (sgn_a.clone() * (E::F::one() - sgn_b.clone())
-                    + ltu_flag.clone()
-                        * (sgn_a.clone() * sgn_b.clone()
-                            + (E::F::one() - sgn_a.clone()) * (E::F::one() - sgn_b.clone()))
-                    - lt_flag.clone()),
-        );
+        // The borrow of the sign-flipped subtraction is the signed comparison result.
+        let lt_flag =
+            word_cmp::constrain_cmp(eval, trace_eval, is_bge.clone(), &value_a, &value_b, true);
 
         // Setting pc_next based on comparison result
         // pc_next=pc+c_val if lt_flag = 0
@@ -213,8 +137,8 @@ impl MachineChip for BgeChip {
 
         // is_bge・((1 - lt_flag)・(c_val_3 + c_val_4 * 256) + pc_3 + pc_4 * 256 + carry_2 - carry_4·2^{16} - pc_next_3 - pc_next_4 * 256) = 0
         eval.add_constraint(
-            is_bge.clone()
-                * ((E::F::one() - lt_flag.clone())
+            is_bge
+                * ((E::F::one() - lt_flag)
                     * (value_c[2].clone() + value_c[3].clone() * modulus.clone())
                     + pc[2].clone()
                     + pc[3].clone() * modulus.clone()
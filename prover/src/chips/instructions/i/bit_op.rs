@@ -5,11 +5,12 @@ use stwo::{
     core::fields::m31::BaseField,
     prover::backend::simd::m31::{PackedBaseField, LOG_N_LANES},
 };
-use stwo_constraint_framework::{EvalAtRow, LogupTraceGenerator, Relation, RelationEntry};
+use stwo_constraint_framework::{EvalAtRow, Relation, RelationEntry};
 
 use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
 
 use crate::{
+    chips::word_decomp::fill_word_limbs,
     column::Column::{
         self, IsAnd, IsOr, IsXor, ValueA, ValueA4_7, ValueB, ValueB4_7, ValueC, ValueC4_7,
     },
@@ -17,6 +18,7 @@ use crate::{
     extensions::ExtensionsConfig,
     trace::{
         eval::{trace_eval, TraceEval},
+        logup::LogupTraceBuilder,
         program_trace::ProgramTraces,
         sidenote::SideNote,
         FinalizedTraces, PreprocessedTraces, ProgramStep, TracesBuilder, Word,
@@ -248,6 +250,19 @@ impl VirtualColumnForSum for IsBitop {
 }
 
 impl MachineChip for BitOpChip {
+    const NAME: &'static str = "BitOpChip";
+
+    fn supported_opcodes() -> &'static [BuiltinOpcode] {
+        &[
+            BuiltinOpcode::AND,
+            BuiltinOpcode::ANDI,
+            BuiltinOpcode::OR,
+            BuiltinOpcode::ORI,
+            BuiltinOpcode::XOR,
+            BuiltinOpcode::XORI,
+        ]
+    }
+
     fn draw_lookup_elements(
         all_elements: &mut AllLookupElements,
         channel: &mut impl stwo::core::channel::Channel,
@@ -317,14 +332,14 @@ impl MachineChip for BitOpChip {
             *multiplicity_counter.entry(looked_up_row).or_default() += 1;
         }
 
-        traces.fill_columns(row_idx, out_bytes, ValueA);
+        fill_word_limbs(traces, row_idx, out_bytes, ValueA);
     }
 
     /// Fills the whole interaction trace in one-go using SIMD in the stwo-usual way
     ///
     /// data[vec_row] contains sixteen rows. A single write_frac() adds sixteen rows.
     fn fill_interaction_trace(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         _preprocessed_trace: &PreprocessedTraces,
         _program_traces: &ProgramTraces,
@@ -434,9 +449,11 @@ impl MachineChip for BitOpChip {
 #[cfg(test)]
 mod test {
     use crate::{
-        chips::{AddChip, CpuChip, DecodingCheckChip, ProgramMemCheckChip, RegisterMemCheckChip},
+        chips::{
+            AddChip, CpuChip, DecodingCheckChip, LuiChip, ProgramMemCheckChip, RegisterMemCheckChip,
+        },
         extensions::{bit_op::BitOpMultiplicityEval, final_reg::FinalRegEval, ExtensionComponent},
-        test_utils::assert_chip,
+        test_utils::{assert_chip, exhaustive_chip_test, SMALL_DOMAIN},
         trace::{
             preprocessed::PreprocessedBuilder,
             program::iter_program_steps,
@@ -555,4 +572,19 @@ mod test {
             SecureField::zero()
         );
     }
+
+    #[test]
+    fn test_exhaustive_and() {
+        exhaustive_chip_test::<(AddChip, LuiChip, BitOpChip)>(BuiltinOpcode::AND, SMALL_DOMAIN);
+    }
+
+    #[test]
+    fn test_exhaustive_or() {
+        exhaustive_chip_test::<(AddChip, LuiChip, BitOpChip)>(BuiltinOpcode::OR, SMALL_DOMAIN);
+    }
+
+    #[test]
+    fn test_exhaustive_xor() {
+        exhaustive_chip_test::<(AddChip, LuiChip, BitOpChip)>(BuiltinOpcode::XOR, SMALL_DOMAIN);
+    }
 }
@@ -5,6 +5,10 @@ use stwo_constraint_framework::EvalAtRow;
 use nexus_vm::riscv::BuiltinOpcode;
 
 use crate::{
+    chips::{
+        instructions::word_cmp::{self, Comparison},
+        word_decomp::fill_word_limbs,
+    },
     column::Column::{self, *},
     components::AllLookupElements,
     extensions::ExtensionsConfig,
@@ -19,8 +23,7 @@ use crate::{
 use super::add::{self};
 
 pub struct ExecutionResult {
-    pub diff_bytes: Word,
-    pub borrow_bits: [bool; 2], // At 16-bit boundaries
+    pub cmp: Comparison,
     pub pc_next: Word,
     pub carry_bits: [bool; 2], // At 16-bit boundaries
 }
@@ -36,23 +39,19 @@ impl ExecuteChip for BltuChip {
         let imm = program_step.get_value_c().0;
         let pc = program_step.step.pc.to_le_bytes();
 
-        let (diff_bytes, borrow_bits) = super::sub::subtract_with_borrow(value_a, value_b);
+        let cmp = word_cmp::compare(value_a, value_b, false);
 
-        // ltu_flag is equal to borrow_bit[3]
-        let (pc_next, carry_bits) = if borrow_bits[3] {
+        let (pc_next, carry_bits) = if cmp.lt() {
             // a < b is true: pc_next = pc + imm
             add::add_with_carries(pc, imm)
         } else {
             // a >= b is true: pc_next = pc + 4
             add::add_with_carries(pc, 4u32.to_le_bytes())
         };
-
-        let borrow_bits = [borrow_bits[1], borrow_bits[3]];
         let carry_bits = [carry_bits[1], carry_bits[3]];
 
         ExecutionResult {
-            diff_bytes,
-            borrow_bits,
+            cmp,
             pc_next,
             carry_bits,
         }
@@ -60,6 +59,12 @@ impl ExecuteChip for BltuChip {
 }
 
 impl MachineChip for BltuChip {
+    const NAME: &'static str = "BltuChip";
+
+    fn supported_opcodes() -> &'static [BuiltinOpcode] {
+        &[BuiltinOpcode::BLTU]
+    }
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
@@ -79,17 +84,15 @@ impl MachineChip for BltuChip {
         }
 
         let ExecutionResult {
-            diff_bytes,
-            borrow_bits,
+            cmp,
             pc_next,
             carry_bits,
         } = Self::execute(vm_step);
 
-        traces.fill_columns(row_idx, diff_bytes, Column::Helper1);
-        traces.fill_columns(row_idx, borrow_bits, Column::BorrowFlag);
+        word_cmp::fill_cmp_columns(traces, row_idx, &cmp);
 
         // Fill valueA
-        traces.fill_columns(row_idx, vm_step.get_value_a(), Column::ValueA);
+        fill_word_limbs(traces, row_idx, vm_step.get_value_a(), Column::ValueA);
 
         // Fill PcNext and CarryFlag, since Pc and Immediate are filled to the main trace in CPU.
         traces.fill_columns(row_idx, pc_next, Column::PcNext);
@@ -108,33 +111,11 @@ impl MachineChip for BltuChip {
         let value_c = trace_eval!(trace_eval, ValueC);
         let pc = trace_eval!(trace_eval, Column::Pc);
         let carry_bits = trace_eval!(trace_eval, Column::CarryFlag);
-        let borrow_bits = trace_eval!(trace_eval, Column::BorrowFlag);
-        let diff_bytes = trace_eval!(trace_eval, Column::Helper1);
         let pc_next = trace_eval!(trace_eval, Column::PcNext);
         let [is_bltu] = trace_eval!(trace_eval, Column::IsBltu);
-        let ltu_flag = borrow_bits[1].clone();
 
-        // is_bltu・(a_val_1 + a_val_2 * 256 - b_val_1 - b_val_2 * 256 - h1_1 - h1_2 * 256 + borrow_1・2^{16}) = 0
-        eval.add_constraint(
-            is_bltu.clone()
-                * (value_a[0].clone() + value_a[1].clone() * modulus.clone()
-                    - value_b[0].clone()
-                    - value_b[1].clone() * modulus.clone()
-                    - diff_bytes[0].clone()
-                    - diff_bytes[1].clone() * modulus.clone()
-                    + borrow_bits[0].clone() * modulus.clone().pow(2)),
-        );
-        // is_bltu・(a_val_3 + a_val_4 * 256 - b_val_3 - b_val_4 * 256 - h1_3 - h1_4 * 256 + borrow_2・2^{16} - borrow_1) = 0
-        eval.add_constraint(
-            is_bltu.clone()
-                * (value_a[2].clone() + value_a[3].clone() * modulus.clone()
-                    - value_b[2].clone()
-                    - value_b[3].clone() * modulus.clone()
-                    - diff_bytes[2].clone()
-                    - diff_bytes[3].clone() * modulus.clone()
-                    + borrow_bits[1].clone() * modulus.clone().pow(2)
-                    - borrow_bits[0].clone()),
-        );
+        let ltu_flag =
+            word_cmp::constrain_cmp(eval, trace_eval, is_bltu.clone(), &value_a, &value_b, false);
 
         // is_bltu・(ltu_flag・(c_val_1 + c_val_2 * 256) + (1-ltu_flag)・4 + pc_1 + pc_2 * 256 - carry_1·2^{16} - pc_next_1 - pc_next_2 * 256) =0
         eval.add_constraint(
@@ -149,8 +130,8 @@ impl MachineChip for BltuChip {
         );
         // is_bltu・(ltu_flag・(c_val_3 + c_val_4 * 256) + pc_3 + pc_4 * 256 + carry_1 - carry_2·2^{16} - pc_next_3 - pc_next_4 * 256) = 0
         eval.add_constraint(
-            is_bltu.clone()
-                * (ltu_flag.clone() * (value_c[2].clone() + value_c[3].clone() * modulus.clone())
+            is_bltu
+                * (ltu_flag * (value_c[2].clone() + value_c[3].clone() * modulus.clone())
                     + pc[2].clone()
                     + pc[3].clone() * modulus.clone()
                     + carry_bits[0].clone()
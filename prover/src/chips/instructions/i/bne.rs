@@ -5,6 +5,7 @@ use stwo_constraint_framework::EvalAtRow;
 use nexus_vm::riscv::BuiltinOpcode;
 
 use crate::{
+    chips::word_decomp::fill_word_limbs,
     column::Column::{self, *},
     components::AllLookupElements,
     extensions::ExtensionsConfig,
@@ -105,6 +106,12 @@ impl ExecuteChip for BneChip {
 }
 
 impl MachineChip for BneChip {
+    const NAME: &'static str = "BneChip";
+
+    fn supported_opcodes() -> &'static [BuiltinOpcode] {
+        &[BuiltinOpcode::BNE]
+    }
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
@@ -138,7 +145,7 @@ impl MachineChip for BneChip {
         traces.fill_columns(row_idx, neq_34_flag, Column::Neq34);
 
         // Fill valueA
-        traces.fill_columns(row_idx, vm_step.get_value_a(), Column::ValueA);
+        fill_word_limbs(traces, row_idx, vm_step.get_value_a(), Column::ValueA);
 
         // TODO: it's possible to pack neq_{12,34}_flag into diff and store in Helper
         // NeqAux = 1 / (valueA - valueB); If valueA == valueB, NeqAux is random non-zero value.
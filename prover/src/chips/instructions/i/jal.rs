@@ -4,6 +4,7 @@ use stwo_constraint_framework::EvalAtRow;
 use nexus_vm::riscv::BuiltinOpcode;
 
 use crate::{
+    chips::word_decomp::fill_word_limbs,
     column::Column::{self, *},
     components::AllLookupElements,
     extensions::ExtensionsConfig,
@@ -30,8 +31,8 @@ impl ExecuteChip for JalChip {
     type ExecutionResult = ExecutionResult;
 
     fn execute(program_step: &ProgramStep) -> Self::ExecutionResult {
-        let imm = program_step.get_value_c().0;
-        let pc = program_step.step.pc.to_le_bytes();
+        let imm = program_step.instruction().op_c.to_le_bytes();
+        let pc = program_step.pc().to_le_bytes();
 
         // 1. Compute pc_next = pc + imm
         // 2. value_a = pc + 4
@@ -51,6 +52,12 @@ impl ExecuteChip for JalChip {
 }
 
 impl MachineChip for JalChip {
+    const NAME: &'static str = "JalChip";
+
+    fn supported_opcodes() -> &'static [BuiltinOpcode] {
+        &[BuiltinOpcode::JAL]
+    }
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
@@ -63,7 +70,7 @@ impl MachineChip for JalChip {
             None => return, // padding
         };
         if !matches!(
-            vm_step.step.instruction.opcode.builtin(),
+            vm_step.instruction().opcode.builtin(),
             Some(BuiltinOpcode::JAL)
         ) {
             return;
@@ -81,7 +88,7 @@ impl MachineChip for JalChip {
         traces.fill_columns(row_idx, pc_carry_bits, Column::BorrowFlag);
 
         // Fill valueA and its carry flag.
-        traces.fill_columns(row_idx, value_a, Column::ValueA);
+        fill_word_limbs(traces, row_idx, value_a, Column::ValueA);
         traces.fill_columns(row_idx, carry_bits, Column::CarryFlag);
     }
 
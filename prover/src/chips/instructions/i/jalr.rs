@@ -4,6 +4,7 @@ use stwo_constraint_framework::EvalAtRow;
 use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
 
 use crate::{
+    chips::word_decomp::{fill_word_limbs, word_halves},
     column::Column::{self, *},
     components::AllLookupElements,
     extensions::ExtensionsConfig,
@@ -71,6 +72,12 @@ impl ExecuteChip for JalrChip {
 }
 
 impl MachineChip for JalrChip {
+    const NAME: &'static str = "JalrChip";
+
+    fn supported_opcodes() -> &'static [BuiltinOpcode] {
+        &[BuiltinOpcode::JALR]
+    }
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
@@ -106,11 +113,11 @@ impl MachineChip for JalrChip {
         traces.fill_columns(row_idx, pc_next, Column::PcNext);
 
         // Fill PcNextAux and CarryFlag, since Pc and Immediate are filled to the main trace in CPU.
-        traces.fill_columns(row_idx, pc_next_aux, Column::PcNextAux);
+        fill_word_limbs(traces, row_idx, pc_next_aux, Column::PcNextAux);
         traces.fill_columns(row_idx, pc_carry_bits, Column::BorrowFlag);
 
         // Fill valueA and its carry flag.
-        traces.fill_columns(row_idx, value_a, Column::ValueA);
+        fill_word_limbs(traces, row_idx, value_a, Column::ValueA);
         traces.fill_columns(row_idx, carry_bits, Column::CarryFlag);
     }
 
@@ -138,19 +145,19 @@ impl MachineChip for JalrChip {
         // is_jalr・(4 + pc_1 + pc_2 * 256 - carry1_1·2^{16} - a_val_1 - a_val_2 * 256) = 0
         // is_jalr・(pc_3 + pc_3 * 256 + carry1_1 - carry1_2·2^{16} - a_val_3 - a_val_4 * 256) = 0
 
+        let [pc_low, pc_high] = word_halves::<E>(&pc);
+        let [value_a_low, value_a_high] = word_halves::<E>(&value_a);
         eval.add_constraint(
             is_jalr.clone()
-                * (E::F::from(4.into()) + pc[0].clone() + pc[1].clone() * modulus.clone()
+                * (E::F::from(4.into()) + pc_low
                     - carry_bits[0].clone() * modulus.clone().pow(2)
-                    - value_a[0].clone()
-                    - value_a[1].clone() * modulus.clone()),
+                    - value_a_low),
         );
         eval.add_constraint(
             is_jalr.clone()
-                * (pc[2].clone() + pc[3].clone() * modulus.clone() + carry_bits[0].clone()
+                * (pc_high + carry_bits[0].clone()
                     - carry_bits[1].clone() * modulus.clone().pow(2)
-                    - value_a[2].clone()
-                    - value_a[3].clone() * modulus.clone()),
+                    - value_a_high),
         );
 
         // Setting pc_next
@@ -158,26 +165,20 @@ impl MachineChip for JalrChip {
         // pc_carry_{1,2,3,4} used for carry handling
         // is_jalr・(c_val_1 + c_val_2 * 256 + b_val_1 + b_val_2 * 256 - pc_carry_1·2^{16} - pc_next_aux_1 - pc_next_aux_2 * 256) = 0
         // is_jalr・(c_val_3 + c_val_4 * 256 + b_val_3 + b_val_4 * 256 + pc_carry_1 - pc_carry_2·2^{16} - pc_next_aux_3 - pc_next_aux_4 * 256) = 0
+        let [value_b_low, value_b_high] = word_halves::<E>(&value_b);
+        let [value_c_low, value_c_high] = word_halves::<E>(&value_c);
+        let [pc_next_aux_low, pc_next_aux_high] = word_halves::<E>(&pc_next_aux);
         eval.add_constraint(
             is_jalr.clone()
-                * (value_c[0].clone()
-                    + value_c[1].clone() * modulus.clone()
-                    + value_b[0].clone()
-                    + value_b[1].clone() * modulus.clone()
+                * (value_c_low + value_b_low
                     - pc_carry_bits[0].clone() * modulus.clone().pow(2)
-                    - pc_next_aux[0].clone()
-                    - pc_next_aux[1].clone() * modulus.clone()),
+                    - pc_next_aux_low),
         );
         eval.add_constraint(
             is_jalr.clone()
-                * (value_c[2].clone()
-                    + value_c[3].clone() * modulus.clone()
-                    + value_b[2].clone()
-                    + value_b[3].clone() * modulus.clone()
-                    + pc_carry_bits[0].clone()
-                    - pc_carry_bits[1].clone() * modulus.clone().pow(2)
-                    - pc_next_aux[2].clone()
-                    - pc_next_aux[3].clone() * modulus.clone()),
+                * (value_c_high + value_b_high + pc_carry_bits[0].clone()
+                    - pc_carry_bits[1].clone() * modulus.pow(2)
+                    - pc_next_aux_high),
         );
 
         // Setting pc_next
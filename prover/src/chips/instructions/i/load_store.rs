@@ -1,65 +1,109 @@
+use std::{array, marker::PhantomData, ops::Range};
+
 use num_traits::One;
 use stwo::{
     core::fields::m31::BaseField,
     prover::backend::simd::m31::{PackedBaseField, LOG_N_LANES},
 };
-use stwo_constraint_framework::{EvalAtRow, LogupTraceGenerator, Relation, RelationEntry};
+use stwo_constraint_framework::{EvalAtRow, Relation, RelationEntry};
 
 use nexus_common::constants::WORD_SIZE_HALVED;
-use nexus_vm::{memory::MemAccessSize, riscv::BuiltinOpcode, WORD_SIZE};
+use nexus_vm::{memory::MemAccessSize, riscv::BuiltinOpcode, SyscallCode, WORD_SIZE};
 
 use crate::{
-    chips::memory_check::decr_subtract_with_borrow,
-    column::{
-        Column::{
-            self, Helper1, Helper2, Helper3, Helper4, IsLb, IsLbu, IsLh, IsLhu, IsLw, IsSb, IsSh,
-            IsSw, Ram1TsPrev, Ram1TsPrevAux, Ram1ValCur, Ram1ValPrev, Ram2TsPrev, Ram2TsPrevAux,
-            Ram2ValCur, Ram2ValPrev, Ram3TsPrev, Ram3TsPrevAux, Ram3ValCur, Ram3ValPrev,
-            Ram4TsPrev, Ram4TsPrevAux, Ram4ValCur, Ram4ValPrev,
-        },
-        PreprocessedColumn,
+    chips::{
+        memory_check::decr_subtract_with_borrow, range_check::constraints::constrain_bool_if,
+        word_decomp::fill_word_limbs_batch, SyscallColumns,
+    },
+    column::Column::{
+        self, Helper1, Helper2, Helper3, Helper4, IsLb, IsLbu, IsLh, IsLhu, IsLw, IsSb, IsSh, IsSw,
+        Ram1TsPrev, Ram1TsPrevAux, Ram1ValCur, Ram1ValPrev, Ram2TsPrev, Ram2TsPrevAux, Ram2ValCur,
+        Ram2ValPrev, Ram3TsPrev, Ram3TsPrevAux, Ram3ValCur, Ram3ValPrev, Ram4TsPrev, Ram4TsPrevAux,
+        Ram4ValCur, Ram4ValPrev,
     },
     components::AllLookupElements,
     extensions::ExtensionsConfig,
     trace::{
-        eval::{preprocessed_trace_eval, trace_eval},
+        eval::{trace_eval, TraceEval},
+        logup::LogupTraceBuilder,
         program_trace::ProgramTraces,
         sidenote::SideNote,
-        FinalizedTraces, PreprocessedTraces, ProgramStep, TracesBuilder, Word,
+        BoolWord, FinalizedTraces, PreprocessedTraces, ProgramStep, TracesBuilder, Word,
     },
-    traits::MachineChip,
+    traits::{MachineChip, SideNoteState},
     virtual_column::{IsLoad, IsTypeS, VirtualColumn, VirtualColumnForSum},
 };
 
 use super::add::add_with_carries;
 
-/// A virtual column that indicates the first byte RAM access
-struct Ram1Accessed;
+/// A virtual column that indicates the first byte access of load and store instructions
+struct Ram1Opcodes;
 
-impl VirtualColumnForSum for Ram1Accessed {
+impl VirtualColumnForSum for Ram1Opcodes {
     fn columns() -> &'static [Column] {
         &[IsSb, IsSh, IsSw, IsLb, IsLh, IsLbu, IsLhu, IsLw]
     }
 }
 
-/// A virtual column that indicates the second byte RAM access
-struct Ram2Accessed;
+/// A virtual column that indicates the second byte access of load and store instructions
+struct Ram2Opcodes;
 
-impl VirtualColumnForSum for Ram2Accessed {
+impl VirtualColumnForSum for Ram2Opcodes {
     fn columns() -> &'static [Column] {
         &[IsSh, IsSw, IsLh, IsLhu, IsLw]
     }
 }
 
-/// A virtual column that indicates the third and the fourth byte RAM access
-struct Ram3_4Accessed;
+/// A virtual column that indicates the third and the fourth byte access of load and store instructions
+struct Ram3_4Opcodes;
 
-impl VirtualColumnForSum for Ram3_4Accessed {
+impl VirtualColumnForSum for Ram3_4Opcodes {
     fn columns() -> &'static [Column] {
         &[IsSw, IsLw]
     }
 }
 
+/// A virtual column that indicates a byte RAM access, made by the `Opcodes` or by the read private input word
+/// syscall, which writes all four bytes.
+struct RamAccessed<Opcodes>(PhantomData<Opcodes>);
+
+impl<Opcodes: VirtualColumnForSum> VirtualColumn<1> for RamAccessed<Opcodes> {
+    fn read_from_traces_builder(traces: &TracesBuilder, row_idx: usize) -> [BaseField; 1] {
+        let [opcodes] = Opcodes::read_from_traces_builder(traces, row_idx);
+        let [is_sys_priv_input_word] = traces.column(row_idx, SyscallColumns::IsSysPrivInputWord);
+        [opcodes + is_sys_priv_input_word]
+    }
+    fn read_from_finalized_traces(
+        traces: &FinalizedTraces,
+        vec_idx: usize,
+    ) -> [PackedBaseField; 1] {
+        let [opcodes] = Opcodes::read_from_finalized_traces(traces, vec_idx);
+        let is_sys_priv_input_word =
+            traces.get_base_column::<1>(SyscallColumns::IsSysPrivInputWord)[0].data[vec_idx];
+        [opcodes + is_sys_priv_input_word]
+    }
+    fn eval<E: EvalAtRow>(trace_eval: &TraceEval<E>) -> [E::F; 1] {
+        let [opcodes] = Opcodes::eval(trace_eval);
+        let [is_sys_priv_input_word] = trace_eval!(trace_eval, SyscallColumns::IsSysPrivInputWord);
+        [opcodes + is_sys_priv_input_word]
+    }
+}
+
+/// A virtual column that indicates the first byte RAM access
+type Ram1Accessed = RamAccessed<Ram1Opcodes>;
+/// A virtual column that indicates the second byte RAM access
+type Ram2Accessed = RamAccessed<Ram2Opcodes>;
+/// A virtual column that indicates the third and the fourth byte RAM access
+type Ram3_4Accessed = RamAccessed<Ram3_4Opcodes>;
+
+/// Value, previous value, previous timestamp, timestamp comparison and its borrows of each accessed byte.
+const RAM_BYTES: [(Column, Column, Column, Column, Column); WORD_SIZE] = [
+    (Ram1ValCur, Ram1ValPrev, Ram1TsPrev, Ram1TsPrevAux, Helper1),
+    (Ram2ValCur, Ram2ValPrev, Ram2TsPrev, Ram2TsPrevAux, Helper2),
+    (Ram3ValCur, Ram3ValPrev, Ram3TsPrev, Ram3TsPrevAux, Helper3),
+    (Ram4ValCur, Ram4ValPrev, Ram4TsPrev, Ram4TsPrevAux, Helper4),
+];
+
 // Support SB, SH, SW, LB, LH and LW opcodes
 pub struct LoadStoreChip;
 
@@ -67,6 +111,21 @@ const LOOKUP_TUPLE_SIZE: usize = 2 * WORD_SIZE_HALVED + 1;
 stwo_constraint_framework::relation!(LoadStoreLookupElements, LOOKUP_TUPLE_SIZE);
 
 impl MachineChip for LoadStoreChip {
+    const NAME: &'static str = "LoadStoreChip";
+
+    fn supported_opcodes() -> &'static [BuiltinOpcode] {
+        &[
+            BuiltinOpcode::LB,
+            BuiltinOpcode::LH,
+            BuiltinOpcode::LW,
+            BuiltinOpcode::LBU,
+            BuiltinOpcode::LHU,
+            BuiltinOpcode::SB,
+            BuiltinOpcode::SH,
+            BuiltinOpcode::SW,
+        ]
+    }
+
     fn draw_lookup_elements(
         all_elements: &mut AllLookupElements,
         channel: &mut impl stwo::core::channel::Channel,
@@ -86,153 +145,38 @@ impl MachineChip for LoadStoreChip {
             Some(vm_step) => vm_step,
             None => return,
         };
-        if !matches!(
-            vm_step.step.instruction.opcode.builtin(),
-            Some(BuiltinOpcode::SB)
-                | Some(BuiltinOpcode::SH)
-                | Some(BuiltinOpcode::SW)
-                | Some(BuiltinOpcode::LB)
-                | Some(BuiltinOpcode::LH)
-                | Some(BuiltinOpcode::LBU)
-                | Some(BuiltinOpcode::LHU)
-                | Some(BuiltinOpcode::LW)
-        ) {
-            return;
+        if let Some(row) = Self::memory_row(vm_step, side_note) {
+            Self::fill_rows(traces, &[(row_idx, row)]);
         }
+    }
 
-        let is_load = matches!(
-            vm_step.step.instruction.opcode.builtin(),
-            Some(BuiltinOpcode::LB)
-                | Some(BuiltinOpcode::LH)
-                | Some(BuiltinOpcode::LW)
-                | Some(BuiltinOpcode::LBU)
-                | Some(BuiltinOpcode::LHU)
-        );
-
-        let value_a = vm_step.get_value_a();
-        traces.fill_columns(row_idx, value_a, Column::ValueA);
-        let value_b = vm_step.get_value_b();
-        let (offset, effective_bits) = vm_step.get_value_c();
-        assert_eq!(effective_bits, 12);
-        let (ram_base_address, carry_bits) = if is_load {
-            add_with_carries(value_b, offset)
-        } else {
-            add_with_carries(value_a, offset)
-        };
-        traces.fill_columns(row_idx, ram_base_address, Column::RamBaseAddr);
-        let carry_bits = [carry_bits[1], carry_bits[3]];
-        traces.fill_columns(row_idx, carry_bits, Column::CarryFlag);
-        let clk = row_idx as u32 + 1;
-        for memory_record in vm_step.step.memory_records.iter() {
-            assert_eq!(
-                memory_record.get_timestamp(),
-                (row_idx as u32 + 1),
-                "timestamp mismatch"
-            );
-            assert_eq!(memory_record.get_timestamp(), clk, "timestamp mismatch");
-            let byte_address = memory_record.get_address();
-            assert_eq!(
-                byte_address,
-                u32::from_le_bytes(ram_base_address),
-                "address mismatch"
-            );
-
-            let size = memory_record.get_size() as usize;
-
-            if !is_load {
-                assert!(
-                    (memory_record.get_prev_value().unwrap() as u64) < { 1u64 } << (size * 8),
-                    "a memory operation contains a too big prev value"
-                );
-            }
-            assert!(
-                (memory_record.get_value() as u64) < { 1u64 } << (size * 8),
-                "a memory operation contains a too big value"
-            );
-
-            if is_load {
-                let cur_value_extended = vm_step
-                    .step
-                    .result
-                    .expect("load operation should have a result");
-                match memory_record.get_size() {
-                    MemAccessSize::Byte => {
-                        assert_eq!(cur_value_extended & 0xff, memory_record.get_value() & 0xff);
-                        traces.fill_columns(
-                            row_idx,
-                            (cur_value_extended & 0x7f) as u8,
-                            Column::QtAux,
-                        );
-                    }
-                    MemAccessSize::HalfWord => {
-                        assert_eq!(
-                            cur_value_extended & 0xffff,
-                            memory_record.get_value() & 0xffff
-                        );
-                        traces.fill_columns(
-                            row_idx,
-                            ((cur_value_extended >> 8) & 0x7f) as u8,
-                            Column::QtAux,
-                        );
-                    }
-                    MemAccessSize::Word => {
-                        assert_eq!(cur_value_extended, memory_record.get_value());
-                    }
-                }
-                traces.fill_columns(row_idx, cur_value_extended, Column::ValueA);
-            }
-            let cur_value: Word = memory_record.get_value().to_le_bytes();
-            let prev_value: Word = if is_load {
-                cur_value
-            } else {
-                memory_record
-                    .get_prev_value()
-                    .expect("Store operation should carry a previous value")
-                    .to_le_bytes()
-            };
+    /// Computes the rows of the chunk in order, as each access updates the last access of its bytes in the side
+    /// note, then fills them column by column.
+    fn fill_main_trace_chunk(
+        traces: &mut TracesBuilder,
+        rows: Range<usize>,
+        steps: &[Option<ProgramStep>],
+        side_note: &mut SideNote,
+        _config: &ExtensionsConfig,
+    ) {
+        assert_eq!(rows.len(), steps.len(), "chunk size mismatch");
+        let memory_rows: Vec<(usize, LoadStoreRow)> = rows
+            .zip(steps)
+            .filter_map(|(row_idx, vm_step)| {
+                Some((row_idx, Self::memory_row(vm_step.as_ref()?, side_note)?))
+            })
+            .collect();
+        Self::fill_rows(traces, &memory_rows);
+    }
 
-            for (i, (val_cur, val_prev, ts_prev, ram_ts_prev_aux, helper)) in [
-                (Ram1ValCur, Ram1ValPrev, Ram1TsPrev, Ram1TsPrevAux, Helper1),
-                (Ram2ValCur, Ram2ValPrev, Ram2TsPrev, Ram2TsPrevAux, Helper2),
-                (Ram3ValCur, Ram3ValPrev, Ram3TsPrev, Ram3TsPrevAux, Helper3),
-                (Ram4ValCur, Ram4ValPrev, Ram4TsPrev, Ram4TsPrevAux, Helper4),
-            ]
-            .into_iter()
-            .take(size)
-            .enumerate()
-            {
-                let prev_access = side_note.rw_mem_check.last_access.insert(
-                    byte_address
-                        .checked_add(i as u32)
-                        .expect("memory access range overflowed back to address zero"),
-                    (clk, cur_value[i]),
-                );
-                let (prev_timestamp, prev_val) = prev_access.unwrap_or((0, 0));
-                // If it's LOAD, the vm and the prover need to agree on the previous value
-                if is_load {
-                    assert_eq!(
-                        prev_val,
-                        prev_value[i],
-                        "memory access value mismatch at address 0x{:x}, prev_timestamp = {}",
-                        byte_address.checked_add(i as u32).unwrap(),
-                        prev_timestamp,
-                    );
-                }
-                traces.fill_columns(row_idx, cur_value[i], val_cur);
-                traces.fill_columns(row_idx, prev_val, val_prev);
-                traces.fill_columns(row_idx, prev_timestamp, ts_prev);
-                let (ram_ts_prev_aux_word, helper_word) =
-                    decr_subtract_with_borrow(clk.to_le_bytes(), prev_timestamp.to_le_bytes());
-                traces.fill_columns(row_idx, ram_ts_prev_aux_word, ram_ts_prev_aux);
-                traces.fill_columns(row_idx, helper_word, helper);
-            }
-        }
+    fn collect_side_note_state(states: &mut Vec<SideNoteState>, _config: &ExtensionsConfig) {
+        states.push(SideNoteState::ReadWriteMemory);
     }
 
     fn fill_interaction_trace(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
-        preprocessed_trace: &PreprocessedTraces,
+        _preprocessed_trace: &PreprocessedTraces,
         _program_traces: &ProgramTraces,
         lookup_element: &AllLookupElements,
     ) {
@@ -251,7 +195,6 @@ impl MachineChip for LoadStoreChip {
         // TODO: make it a loop or four calls
         Self::subtract_add_access::<Ram1Accessed>(
             original_traces,
-            preprocessed_trace,
             lookup_element,
             logup_trace_gen,
             Ram1ValPrev,
@@ -261,7 +204,6 @@ impl MachineChip for LoadStoreChip {
         );
         Self::subtract_add_access::<Ram2Accessed>(
             original_traces,
-            preprocessed_trace,
             lookup_element,
             logup_trace_gen,
             Ram2ValPrev,
@@ -271,7 +213,6 @@ impl MachineChip for LoadStoreChip {
         );
         Self::subtract_add_access::<Ram3_4Accessed>(
             original_traces,
-            preprocessed_trace,
             lookup_element,
             logup_trace_gen,
             Ram3ValPrev,
@@ -281,7 +222,6 @@ impl MachineChip for LoadStoreChip {
         );
         Self::subtract_add_access::<Ram3_4Accessed>(
             original_traces,
-            preprocessed_trace,
             lookup_element,
             logup_trace_gen,
             Ram4ValPrev,
@@ -297,14 +237,22 @@ impl MachineChip for LoadStoreChip {
         lookup_elements: &AllLookupElements,
         _config: &ExtensionsConfig,
     ) {
-        // Computing ram1_ts_prev_aux = clk - 1 - ram1_ts_prev
+        // The sub-timestamps of the accesses only differ from the clock on syscall rows.
+        // (1 - is_sys_priv_input_word)・ram_sub_ts_carry_i = 0 for i = 1..3
+        let [is_sys_priv_input_word] = trace_eval!(trace_eval, SyscallColumns::IsSysPrivInputWord);
+        for carry in trace_eval!(trace_eval, Column::RamSubTsCarry) {
+            eval.add_constraint((E::F::one() - is_sys_priv_input_word.clone()) * carry);
+        }
+        let [ram1_ts, ram2_ts, ram3_ts, ram4_ts]: [[E::F; 2]; WORD_SIZE] =
+            array::from_fn(|i| Self::sub_timestamp_eval(trace_eval, i as u8));
+
+        // Computing ram1_ts_prev_aux = ram1_ts - 1 - ram1_ts_prev
         // Helper1 used for borrow handling
-        let clk = preprocessed_trace_eval!(trace_eval, PreprocessedColumn::Clk);
         let ram1_ts_prev = trace_eval!(trace_eval, Ram1TsPrev);
         let ram1_ts_prev_aux = trace_eval!(trace_eval, Ram1TsPrevAux);
         let helper1 = trace_eval!(trace_eval, Column::Helper1);
         let [ram1_accessed] = Ram1Accessed::eval(trace_eval);
-        // ram1_ts_prev_aux_1 + ram1_ts_prev_aux_2 * 256 + 1    + ram1_ts_prev_1 + ram1_ts_prev_2 * 256 = clk_1 + clk_2 * 256 + h1_2・2^16
+        // ram1_ts_prev_aux_1 + ram1_ts_prev_aux_2 * 256 + 1    + ram1_ts_prev_1 + ram1_ts_prev_2 * 256 = ram1_ts_low + h1_2・2^16
         // (conditioned on ram1_accessed != 0)
         eval.add_constraint(
             ram1_accessed.clone()
@@ -313,11 +261,10 @@ impl MachineChip for LoadStoreChip {
                     + E::F::one()
                     + ram1_ts_prev[0].clone()
                     + ram1_ts_prev[1].clone() * BaseField::from(1 << 8)
-                    - clk[0].clone()
-                    - clk[1].clone() * BaseField::from(1 << 8)
+                    - ram1_ts[0].clone()
                     - helper1[1].clone() * BaseField::from(1 << 16)),
         );
-        // ram1_ts_prev_aux_3 + ram1_ts_prev_aux_4 * 256 + h1_2 + ram1_ts_prev_3 + ram1_ts_prev_4 * 256 = clk_3 + clk_4 * 256 + h1_4・2^16
+        // ram1_ts_prev_aux_3 + ram1_ts_prev_aux_4 * 256 + h1_2 + ram1_ts_prev_3 + ram1_ts_prev_4 * 256 = ram1_ts_high + h1_4・2^16
         // (conditioned on ram1_accessed != 0)
         eval.add_constraint(
             ram1_accessed.clone()
@@ -326,25 +273,22 @@ impl MachineChip for LoadStoreChip {
                     + helper1[1].clone()
                     + ram1_ts_prev[2].clone()
                     + ram1_ts_prev[3].clone() * BaseField::from(1 << 8)
-                    - clk[2].clone()
-                    - clk[3].clone() * BaseField::from(1 << 8)
+                    - ram1_ts[1].clone()
                     - helper1[3].clone() * BaseField::from(1 << 16)),
         );
 
         // h1_2・(h1_2 - 1) = 0 (conditioned on ram1_accessed != 0)
-        eval.add_constraint(
-            helper1[1].clone() * (helper1[1].clone() - E::F::one()) * ram1_accessed.clone(),
-        );
+        constrain_bool_if(eval, ram1_accessed.clone(), helper1[1].clone());
         // h1_4 = 0 (conditioned on ram1_accessed != 0)
         eval.add_constraint(helper1[WORD_SIZE - 1].clone() * ram1_accessed.clone());
 
-        // Computing ram2_ts_prev_aux = clk - 1 - ram2_ts_prev
+        // Computing ram2_ts_prev_aux = ram2_ts - 1 - ram2_ts_prev
         // Helper2 used for borrow handling
         let ram2_ts_prev = trace_eval!(trace_eval, Ram2TsPrev);
         let ram2_ts_prev_aux = trace_eval!(trace_eval, Ram2TsPrevAux);
         let helper2 = trace_eval!(trace_eval, Column::Helper2);
         let [ram2_accessed] = Ram2Accessed::eval(trace_eval);
-        // ram2_ts_prev_aux_1 + ram2_ts_prev_aux_2 * 256 + 1    + ram2_ts_prev_1 + ram2_ts_prev_2 * 256 = clk_1 + clk_2 * 256 + h2_2・2^{16}
+        // ram2_ts_prev_aux_1 + ram2_ts_prev_aux_2 * 256 + 1    + ram2_ts_prev_1 + ram2_ts_prev_2 * 256 = ram2_ts_low + h2_2・2^{16}
         // (conditioned on ram2_accessed != 0)
         eval.add_constraint(
             ram2_accessed.clone()
@@ -353,11 +297,10 @@ impl MachineChip for LoadStoreChip {
                     + E::F::one()
                     + ram2_ts_prev[0].clone()
                     + ram2_ts_prev[1].clone() * BaseField::from(1 << 8)
-                    - clk[0].clone()
-                    - clk[1].clone() * BaseField::from(1 << 8)
+                    - ram2_ts[0].clone()
                     - helper2[1].clone() * BaseField::from(1 << 16)),
         );
-        // ram2_ts_prev_aux_3 + ram2_ts_prev_aux_4 * 256 + h2_2 + ram2_ts_prev_3 + ram2_ts_prev_4 * 256 = clk_3 + clk_4 * 256 + h2_4・2^{16}
+        // ram2_ts_prev_aux_3 + ram2_ts_prev_aux_4 * 256 + h2_2 + ram2_ts_prev_3 + ram2_ts_prev_4 * 256 = ram2_ts_high + h2_4・2^{16}
         // (conditioned on ram2_accessed != 0)
         eval.add_constraint(
             ram2_accessed.clone()
@@ -366,25 +309,22 @@ impl MachineChip for LoadStoreChip {
                     + helper2[1].clone()
                     + ram2_ts_prev[2].clone()
                     + ram2_ts_prev[3].clone() * BaseField::from(1 << 8)
-                    - clk[2].clone()
-                    - clk[3].clone() * BaseField::from(1 << 8)
+                    - ram2_ts[1].clone()
                     - helper2[3].clone() * BaseField::from(1 << 16)),
         );
 
         // h2_2・(h2_2 - 1) = 0 (conditioned on ram2_accessed != 0)
-        eval.add_constraint(
-            helper2[1].clone() * (helper2[1].clone() - E::F::one()) * ram2_accessed.clone(),
-        );
+        constrain_bool_if(eval, ram2_accessed.clone(), helper2[1].clone());
         // h2_4 = 0 (conditioned on ram2_accessed != 0)
         eval.add_constraint(helper2[WORD_SIZE - 1].clone() * ram2_accessed.clone());
 
-        // Computing ram3_ts_prev_aux = clk - 1 - ram3_ts_prev
+        // Computing ram3_ts_prev_aux = ram3_ts - 1 - ram3_ts_prev
         // Helper3 used for borrow handling
         let ram3_ts_prev = trace_eval!(trace_eval, Ram3TsPrev);
         let ram3_ts_prev_aux = trace_eval!(trace_eval, Ram3TsPrevAux);
         let helper3 = trace_eval!(trace_eval, Column::Helper3);
         let [ram3_4_accessed] = Ram3_4Accessed::eval(trace_eval);
-        // ram3_ts_prev_aux_1 + ram3_ts_prev_aux_2 * 256 + 1    + ram3_ts_prev_1 + ram3_ts_prev_2 * 256 = clk_1 + clk_2 * 256 + h3_2・2^{16}
+        // ram3_ts_prev_aux_1 + ram3_ts_prev_aux_2 * 256 + 1    + ram3_ts_prev_1 + ram3_ts_prev_2 * 256 = ram3_ts_low + h3_2・2^{16}
         // (conditioned on ram3_accessed != 0)
         eval.add_constraint(
             ram3_4_accessed.clone()
@@ -393,11 +333,10 @@ impl MachineChip for LoadStoreChip {
                     + E::F::one()
                     + ram3_ts_prev[0].clone()
                     + ram3_ts_prev[1].clone() * BaseField::from(1 << 8)
-                    - clk[0].clone()
-                    - clk[1].clone() * BaseField::from(1 << 8)
+                    - ram3_ts[0].clone()
                     - helper3[1].clone() * BaseField::from(1 << 16)),
         );
-        // ram3_ts_prev_aux_3 + ram3_ts_prev_aux_4 * 256 + h3_2 + ram3_ts_prev_3 + ram3_ts_prev_4 * 256 = clk_3 + clk_4 * 256 + h3_4・2^{16}
+        // ram3_ts_prev_aux_3 + ram3_ts_prev_aux_4 * 256 + h3_2 + ram3_ts_prev_3 + ram3_ts_prev_4 * 256 = ram3_ts_high + h3_4・2^{16}
         // (conditioned on ram3_accessed != 0)
         eval.add_constraint(
             ram3_4_accessed.clone()
@@ -406,18 +345,15 @@ impl MachineChip for LoadStoreChip {
                     + helper3[1].clone()
                     + ram3_ts_prev[2].clone()
                     + ram3_ts_prev[3].clone() * BaseField::from(1 << 8)
-                    - clk[2].clone()
-                    - clk[3].clone() * BaseField::from(1 << 8)
+                    - ram3_ts[1].clone()
                     - helper3[3].clone() * BaseField::from(1 << 16)),
         );
         // h3_2・(h3_2 - 1) = 0 (conditioned on ram3_accessed != 0)
-        eval.add_constraint(
-            helper3[1].clone() * (helper3[1].clone() - E::F::one()) * ram3_4_accessed.clone(),
-        );
+        constrain_bool_if(eval, ram3_4_accessed.clone(), helper3[1].clone());
         // h3_4 = 0 (conditioned on ram3_accessed != 0)
         eval.add_constraint(helper3[WORD_SIZE - 1].clone() * ram3_4_accessed.clone());
 
-        // Computing ram4_ts_prev_aux = clk - 1 - ram4_ts_prev
+        // Computing ram4_ts_prev_aux = ram4_ts - 1 - ram4_ts_prev
         // Helper4 used for borrow handling
         let ram4_ts_prev = trace_eval!(trace_eval, Ram4TsPrev);
         let ram4_ts_prev_aux = trace_eval!(trace_eval, Ram4TsPrevAux);
@@ -429,8 +365,7 @@ impl MachineChip for LoadStoreChip {
                     + E::F::one()
                     + ram4_ts_prev[0].clone()
                     + ram4_ts_prev[1].clone() * BaseField::from(1 << 8)
-                    - clk[0].clone()
-                    - clk[1].clone() * BaseField::from(1 << 8)
+                    - ram4_ts[0].clone()
                     - helper4[1].clone() * BaseField::from(1 << 16)),
         );
         eval.add_constraint(
@@ -440,14 +375,11 @@ impl MachineChip for LoadStoreChip {
                     + helper4[1].clone()
                     + ram4_ts_prev[2].clone()
                     + ram4_ts_prev[3].clone() * BaseField::from(1 << 8)
-                    - clk[2].clone()
-                    - clk[3].clone() * BaseField::from(1 << 8)
+                    - ram4_ts[1].clone()
                     - helper4[3].clone() * BaseField::from(1 << 16)),
         );
         // h4_2・(h4_2 - 1) = 0 (conditioned on ram4_accessed != 0)
-        eval.add_constraint(
-            helper4[1].clone() * (helper4[1].clone() - E::F::one()) * ram3_4_accessed.clone(),
-        );
+        constrain_bool_if(eval, ram3_4_accessed.clone(), helper4[1].clone());
         // h4_4 = 0 (conditioned on ram4_accessed != 0)
         eval.add_constraint(helper4[WORD_SIZE - 1].clone() * ram3_4_accessed.clone());
 
@@ -567,7 +499,7 @@ impl MachineChip for LoadStoreChip {
         let sign_bit = (ram2_val_prev.clone() - sign_removed.clone()) * inv_128;
         // The sign bit should be zero or one.
         // is_lh * sign_bit * (sign_bit - 1) = 0
-        eval.add_constraint(is_lh.clone() * sign_bit.clone() * (sign_bit.clone() - E::F::one()));
+        constrain_bool_if(eval, is_lh.clone(), sign_bit.clone());
         // is_lh * (value_a_1 + value_a_2 * 256 - ram1_val_prev + ram2_val_prev * 256) = 0
         eval.add_constraint(
             is_lh.clone()
@@ -602,7 +534,7 @@ impl MachineChip for LoadStoreChip {
         let sign_bit = (ram1_val_prev.clone() - sign_removed.clone()) * inv_128;
         // The sign bit should be zero or one.
         // is_lb * sign_bit * (sign_bit - 1) = 0
-        eval.add_constraint(is_lb.clone() * sign_bit.clone() * (sign_bit.clone() - E::F::one()));
+        constrain_bool_if(eval, is_lb.clone(), sign_bit.clone());
         // is_lb * (value_a_1 + value_a_2 * 256 - ram1_val_prev - sign_bit * 127 * 128) = 0
         eval.add_constraint(
             is_lb.clone()
@@ -691,12 +623,315 @@ impl MachineChip for LoadStoreChip {
     }
 }
 
+/// A byte access of a row of the [`LoadStoreChip`], filled into the columns of [`RAM_BYTES`].
+struct RamByteAccess {
+    cur: u8,
+    prev: u8,
+    ts_prev: u32,
+    ts_prev_aux: Word,
+    helper: BoolWord,
+}
+
+/// The columns of a row of the [`LoadStoreChip`] that accesses memory.
+struct LoadStoreRow {
+    ram_base_address: Word,
+    value_a: Option<Word>,
+    carry_flag: Option<[bool; 2]>,
+    qt_aux: Option<u8>,
+    sub_ts_carry: Option<[bool; WORD_SIZE - 1]>,
+    // The `i`-th access goes to `RAM_BYTES[i]`
+    bytes: Vec<RamByteAccess>,
+}
+
 impl LoadStoreChip {
+    /// Computes the columns of a load, a store or a read private input word syscall, and records its accesses as
+    /// the last access of their bytes. Other steps access no memory through this chip.
+    fn memory_row(vm_step: &ProgramStep, side_note: &mut SideNote) -> Option<LoadStoreRow> {
+        if vm_step.get_syscall_code() == Some(SyscallCode::ReadPrivateInputWord as u32) {
+            return Some(Self::priv_input_word_row(vm_step, side_note));
+        }
+        let is_load = match vm_step.step.instruction.opcode.builtin() {
+            Some(
+                BuiltinOpcode::LB
+                | BuiltinOpcode::LH
+                | BuiltinOpcode::LW
+                | BuiltinOpcode::LBU
+                | BuiltinOpcode::LHU,
+            ) => true,
+            Some(BuiltinOpcode::SB | BuiltinOpcode::SH | BuiltinOpcode::SW) => false,
+            _ => return None,
+        };
+
+        let mut value_a = vm_step.get_value_a();
+        let value_b = vm_step.get_value_b();
+        let (offset, effective_bits) = vm_step.get_value_c();
+        assert_eq!(effective_bits, 12);
+        let (ram_base_address, carry_bits) = if is_load {
+            add_with_carries(value_b, offset)
+        } else {
+            add_with_carries(value_a, offset)
+        };
+        let mut qt_aux = None;
+        let mut bytes = Vec::with_capacity(WORD_SIZE);
+        let clk = vm_step.step.timestamp;
+        for memory_record in vm_step.step.memory_records.iter() {
+            assert_eq!(memory_record.get_timestamp(), clk, "timestamp mismatch");
+            let byte_address = memory_record.get_address();
+            assert_eq!(
+                byte_address,
+                u32::from_le_bytes(ram_base_address),
+                "address mismatch"
+            );
+
+            let size = memory_record.get_size() as usize;
+
+            if !is_load {
+                assert!(
+                    (memory_record.get_prev_value().unwrap() as u64) < { 1u64 } << (size * 8),
+                    "a memory operation contains a too big prev value"
+                );
+            }
+            assert!(
+                (memory_record.get_value() as u64) < { 1u64 } << (size * 8),
+                "a memory operation contains a too big value"
+            );
+
+            if is_load {
+                let cur_value_extended = vm_step
+                    .step
+                    .result
+                    .expect("load operation should have a result");
+                match memory_record.get_size() {
+                    MemAccessSize::Byte => {
+                        assert_eq!(cur_value_extended & 0xff, memory_record.get_value() & 0xff);
+                        qt_aux = Some((cur_value_extended & 0x7f) as u8);
+                    }
+                    MemAccessSize::HalfWord => {
+                        assert_eq!(
+                            cur_value_extended & 0xffff,
+                            memory_record.get_value() & 0xffff
+                        );
+                        qt_aux = Some(((cur_value_extended >> 8) & 0x7f) as u8);
+                    }
+                    MemAccessSize::Word => {
+                        assert_eq!(cur_value_extended, memory_record.get_value());
+                    }
+                }
+                value_a = cur_value_extended.to_le_bytes();
+            }
+            let cur_value: Word = memory_record.get_value().to_le_bytes();
+            let prev_value: Word = if is_load {
+                cur_value
+            } else {
+                memory_record
+                    .get_prev_value()
+                    .expect("Store operation should carry a previous value")
+                    .to_le_bytes()
+            };
+
+            for (i, &cur) in cur_value.iter().enumerate().take(size) {
+                let prev_access = side_note.rw_mem_check.last_access.insert(
+                    byte_address
+                        .checked_add(i as u32)
+                        .expect("memory access range overflowed back to address zero"),
+                    (clk, cur),
+                );
+                let (prev_timestamp, prev_val) = prev_access.unwrap_or((0, 0));
+                // If it's LOAD, the vm and the prover need to agree on the previous value
+                if is_load {
+                    assert_eq!(
+                        prev_val,
+                        prev_value[i],
+                        "memory access value mismatch at address 0x{:x}, prev_timestamp = {}",
+                        byte_address.checked_add(i as u32).unwrap(),
+                        prev_timestamp,
+                    );
+                }
+                let (ts_prev_aux, helper) =
+                    decr_subtract_with_borrow(clk.to_le_bytes(), prev_timestamp.to_le_bytes());
+                bytes.push(RamByteAccess {
+                    cur,
+                    prev: prev_val,
+                    ts_prev: prev_timestamp,
+                    ts_prev_aux,
+                    helper,
+                });
+            }
+        }
+
+        Some(LoadStoreRow {
+            ram_base_address,
+            value_a: Some(value_a),
+            carry_flag: Some([carry_bits[1], carry_bits[3]]),
+            qt_aux,
+            sub_ts_carry: None,
+            bytes,
+        })
+    }
+
+    /// Computes the four byte writes of the read private input word syscall, the `i`-th at sub-timestamp `clk + i`.
+    fn priv_input_word_row(vm_step: &ProgramStep, side_note: &mut SideNote) -> LoadStoreRow {
+        let clk = vm_step.step.timestamp;
+        let buf = vm_step.get_syscall_arg0();
+
+        let mut memory_records: Vec<_> = vm_step.step.memory_records.iter().collect();
+        memory_records.sort_by_key(|memory_record| memory_record.get_address());
+        assert_eq!(memory_records.len(), WORD_SIZE, "syscall writes a word");
+        let bytes = memory_records
+            .into_iter()
+            .enumerate()
+            .map(|(i, memory_record)| {
+                let sub_ts = clk + i as u32;
+                assert_eq!(memory_record.get_timestamp(), sub_ts, "timestamp mismatch");
+                assert_eq!(memory_record.get_size(), MemAccessSize::Byte);
+                let byte_address = memory_record.get_address();
+                assert_eq!(byte_address, buf + i as u32, "address mismatch");
+
+                let value = memory_record.get_value() as u8;
+                let (prev_timestamp, prev_val) = side_note
+                    .rw_mem_check
+                    .last_access
+                    .insert(byte_address, (sub_ts, value))
+                    .unwrap_or((0, 0));
+                let (ts_prev_aux, helper) =
+                    decr_subtract_with_borrow(sub_ts.to_le_bytes(), prev_timestamp.to_le_bytes());
+                RamByteAccess {
+                    cur: value,
+                    prev: prev_val,
+                    ts_prev: prev_timestamp,
+                    ts_prev_aux,
+                    helper,
+                }
+            })
+            .collect();
+
+        LoadStoreRow {
+            ram_base_address: buf.to_le_bytes(),
+            value_a: None,
+            carry_flag: None,
+            qt_aux: None,
+            sub_ts_carry: Some(array::from_fn(|i| (clk & 0xFFFF) + i as u32 + 1 > 0xFFFF)),
+            bytes,
+        }
+    }
+
+    /// Fills the computed rows into the trace, one column at a time.
+    fn fill_rows(traces: &mut TracesBuilder, rows: &[(usize, LoadStoreRow)]) {
+        fill_word_limbs_batch(
+            traces,
+            rows.iter()
+                .map(|(row_idx, row)| (*row_idx, row.ram_base_address)),
+            Column::RamBaseAddr,
+        );
+        fill_word_limbs_batch(
+            traces,
+            rows.iter()
+                .filter_map(|(row_idx, row)| Some((*row_idx, row.value_a?))),
+            Column::ValueA,
+        );
+        traces.fill_columns_batch(
+            rows.iter()
+                .filter_map(|(row_idx, row)| Some((*row_idx, row.carry_flag?))),
+            Column::CarryFlag,
+        );
+        traces.fill_columns_batch(
+            rows.iter()
+                .filter_map(|(row_idx, row)| Some((*row_idx, row.qt_aux?))),
+            Column::QtAux,
+        );
+        traces.fill_columns_batch(
+            rows.iter()
+                .filter_map(|(row_idx, row)| Some((*row_idx, row.sub_ts_carry?))),
+            Column::RamSubTsCarry,
+        );
+
+        for (i, (val_cur, val_prev, ts_prev, ram_ts_prev_aux, helper)) in
+            RAM_BYTES.into_iter().enumerate()
+        {
+            let accesses = || {
+                rows.iter()
+                    .filter_map(move |(row_idx, row)| Some((*row_idx, row.bytes.get(i)?)))
+            };
+            traces.fill_columns_batch(
+                accesses().map(|(row_idx, access)| (row_idx, access.cur)),
+                val_cur,
+            );
+            traces.fill_columns_batch(
+                accesses().map(|(row_idx, access)| (row_idx, access.prev)),
+                val_prev,
+            );
+            traces.fill_columns_batch(
+                accesses().map(|(row_idx, access)| (row_idx, access.ts_prev)),
+                ts_prev,
+            );
+            traces.fill_columns_batch(
+                accesses().map(|(row_idx, access)| (row_idx, access.ts_prev_aux)),
+                ram_ts_prev_aux,
+            );
+            traces.fill_columns_batch(
+                accesses().map(|(row_idx, access)| (row_idx, access.helper)),
+                helper,
+            );
+        }
+    }
+
+    /// Returns the 16-bit halves of the timestamp of the byte access at `address_offset`.
+    ///
+    /// Loads and stores access every byte at the clock of the row. The read private input word syscall takes a
+    /// cycle per byte and writes the byte at `address_offset` at the sub-timestamp `clk + address_offset`, carrying
+    /// into the high half with `RamSubTsCarry`. A wrong carry leaves a low half out of the 16-bit range, which no
+    /// range-checked previous timestamp can cancel in the logup sum.
+    fn sub_timestamp_eval<E: EvalAtRow>(
+        trace_eval: &TraceEval<E>,
+        address_offset: u8,
+    ) -> [E::F; 2] {
+        let clk = trace_eval!(trace_eval, Column::Clk);
+        let clk_low = clk[0].clone() + clk[1].clone() * E::F::from((1 << 8).into());
+        let clk_high = clk[2].clone() + clk[3].clone() * E::F::from((1 << 8).into());
+        if address_offset == 0 {
+            return [clk_low, clk_high];
+        }
+        let [is_sys_priv_input_word] = trace_eval!(trace_eval, SyscallColumns::IsSysPrivInputWord);
+        let carry =
+            trace_eval!(trace_eval, Column::RamSubTsCarry)[address_offset as usize - 1].clone();
+        [
+            clk_low + is_sys_priv_input_word * BaseField::from(address_offset as u32)
+                - carry.clone() * BaseField::from(1 << 16),
+            clk_high + carry,
+        ]
+    }
+
+    /// Same as [`Self::sub_timestamp_eval`], for the interaction trace.
+    fn sub_timestamp_packed(
+        original_traces: &FinalizedTraces,
+        address_offset: u8,
+        vec_row: usize,
+    ) -> [PackedBaseField; 2] {
+        let clk = original_traces.get_base_column::<WORD_SIZE>(Column::Clk);
+        let byte = PackedBaseField::broadcast((1 << 8).into());
+        let clk_low = clk[0].data[vec_row] + clk[1].data[vec_row] * byte;
+        let clk_high = clk[2].data[vec_row] + clk[3].data[vec_row] * byte;
+        if address_offset == 0 {
+            return [clk_low, clk_high];
+        }
+        let [is_sys_priv_input_word] =
+            original_traces.get_base_column(SyscallColumns::IsSysPrivInputWord);
+        let carry = original_traces.get_base_column::<{ WORD_SIZE - 1 }>(Column::RamSubTsCarry)
+            [address_offset as usize - 1]
+            .data[vec_row];
+        [
+            clk_low
+                + is_sys_priv_input_word.data[vec_row]
+                    * PackedBaseField::broadcast(BaseField::from(address_offset as u32))
+                - carry * PackedBaseField::broadcast(BaseField::from(1 << 16)),
+            clk_high + carry,
+        ]
+    }
+
     fn subtract_add_access<Accessed: VirtualColumn<1>>(
         original_traces: &FinalizedTraces,
-        preprocessed_traces: &PreprocessedTraces,
         lookup_elements: &LoadStoreLookupElements,
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         val_prev: Column,
         ts_prev: Column,
         val_cur: Column,
@@ -712,7 +947,6 @@ impl LoadStoreChip {
         );
         Self::add_access::<Accessed>(
             original_traces,
-            preprocessed_traces,
             lookup_elements,
             logup_trace_gen,
             val_cur,
@@ -749,7 +983,7 @@ impl LoadStoreChip {
     fn subtract_access<Accessed: VirtualColumn<1>>(
         original_traces: &FinalizedTraces,
         lookup_elements: &LoadStoreLookupElements,
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         val_prev: Column,
         ts_prev: Column,
         address_offset: u8,
@@ -831,17 +1065,14 @@ impl LoadStoreChip {
 
     fn add_access<Accessed: VirtualColumn<1>>(
         original_traces: &FinalizedTraces,
-        preprocessed_traces: &PreprocessedTraces,
         lookup_elements: &LoadStoreLookupElements,
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         val_cur: Column,
         address_offset: u8,
     ) {
         let [val_cur] = original_traces.get_base_column(val_cur);
         let base_address = original_traces.get_base_column::<WORD_SIZE>(Column::RamBaseAddr);
         // Add current tuple
-        let clk =
-            preprocessed_traces.get_preprocessed_base_column::<WORD_SIZE>(PreprocessedColumn::Clk);
         let mut logup_col_gen = logup_trace_gen.new_col();
         for vec_row in 0..(1 << (original_traces.log_size() - LOG_N_LANES)) {
             let mut tuple = vec![];
@@ -857,12 +1088,10 @@ impl LoadStoreChip {
 
             tuple.push(val_cur.data[vec_row]);
 
-            let clk_low = clk[0].data[vec_row]
-                + clk[1].data[vec_row] * PackedBaseField::broadcast((1 << 8).into());
-            let clk_high = clk[2].data[vec_row]
-                + clk[3].data[vec_row] * PackedBaseField::broadcast((1 << 8).into());
-            tuple.push(clk_low);
-            tuple.push(clk_high);
+            let [ts_low, ts_high] =
+                Self::sub_timestamp_packed(original_traces, address_offset, vec_row);
+            tuple.push(ts_low);
+            tuple.push(ts_high);
 
             assert_eq!(tuple.len(), 2 * WORD_SIZE_HALVED + 1);
             let [accessed] = Accessed::read_from_finalized_traces(original_traces, vec_row);
@@ -885,7 +1114,6 @@ impl LoadStoreChip {
         let [val_cur] = trace_eval.column_eval(val_cur);
         let [accessed] = Accessed::eval(trace_eval);
         let base_address = trace_eval!(trace_eval, Column::RamBaseAddr);
-        let clk = preprocessed_trace_eval!(trace_eval, PreprocessedColumn::Clk);
         let mut tuple = vec![];
         // The least significant byte of the address is base_address[0] + address_offset
         // Adding an offset without carry is correct because of memory alignment.
@@ -899,10 +1127,9 @@ impl LoadStoreChip {
 
         tuple.push(val_cur);
 
-        let clk_low = clk[0].clone() + clk[1].clone() * E::F::from((1 << 8).into());
-        let clk_high = clk[2].clone() + clk[3].clone() * E::F::from((1 << 8).into());
-        tuple.push(clk_low);
-        tuple.push(clk_high);
+        let [ts_low, ts_high] = Self::sub_timestamp_eval(trace_eval, address_offset);
+        tuple.push(ts_low);
+        tuple.push(ts_high);
         assert_eq!(tuple.len(), 2 * WORD_SIZE_HALVED + 1);
 
         eval.add_to_relation(RelationEntry::new(lookup_elements, accessed.into(), &tuple));
@@ -918,22 +1145,23 @@ mod test {
                 range32::Range32Chip, range8::Range8Chip,
             },
             AddChip, BeqChip, BitOpChip, CpuChip, DecodingCheckChip, RegisterMemCheckChip, SllChip,
+            TimestampChip,
         },
         machine::Machine,
-        test_utils::assert_chip,
+        test_utils::{assert_chip, check_chip},
         trace::{
             program::iter_program_steps, program_trace::ProgramTracesBuilder, PreprocessedTraces,
         },
     };
 
     use super::*;
+    use crate::error::{NexusProvingError, TraceCheckError};
     use nexus_vm::{
         emulator::InternalView,
         memory::MemoryRecords,
         riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
         trace::k_trace_direct,
     };
-    use stwo::prover::ProvingError;
 
     const LOG_SIZE: u32 = PreprocessedTraces::MIN_LOG_SIZE;
 
@@ -1001,6 +1229,8 @@ mod test {
         LoadStoreChip,
         // `prove` call includes default extensions that require lookup elements.
         RegisterMemCheckChip,
+        // Fills the clock of the rows, which timestamps memory accesses.
+        TimestampChip,
         Range8Chip,
         Range16Chip,
         Range32Chip,
@@ -1115,6 +1345,166 @@ mod test {
         store_step.memory_records = MemoryRecords::from_iter([memory_record]);
 
         let result = Machine::<Chips>::prove(&vm_traces, &view);
-        assert!(matches!(result, Err(ProvingError::ConstraintsNotSatisfied)));
+        assert!(matches!(
+            result,
+            Err(NexusProvingError::Stwo(
+                stwo::prover::ProvingError::ConstraintsNotSatisfied
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_wrapping_address_calculation() {
+        let basic_block = BasicBlock::new(vec![
+            // x1 = 0 - 8 = 0xfffffff8
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 0xFFFFFFF8),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 3, 0, 0x7f),
+            // Store to and load from 0xfffffff8 + 16, which wraps around to 0x8
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 1, 3, 16),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 6, 1, 16),
+            // x2 = 0x10, load from 0x10 - 8 = 0x8 without wrapping
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 0x10),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LBU), 7, 2, 0xFFFFFFF8),
+        ]);
+
+        let (view, vm_traces) =
+            k_trace_direct(&vec![basic_block], 1).expect("Failed to create trace");
+        assert_eq!(vm_traces.blocks[3].steps[0].result, Some(0x7f));
+        assert_eq!(vm_traces.blocks[5].steps[0].result, Some(0x7f));
+
+        let proof = Machine::<Chips>::prove(&vm_traces, &view).unwrap();
+        Machine::<Chips>::verify(
+            proof,
+            view.get_program_memory(),
+            view.view_associated_data().as_deref().unwrap_or_default(),
+            &[
+                view.get_ro_initial_memory(),
+                view.get_rw_initial_memory(),
+                view.get_public_input(),
+            ]
+            .concat(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap();
+    }
+
+    /// Loads a word with the given sign of its lowest byte and halfword using `opcode`, then replaces the upper
+    /// limbs of the loaded value in the trace with their wrong extension and returns the result of proving.
+    fn prove_tampered_load(opcode: BuiltinOpcode, negative: bool) -> Result<(), NexusProvingError> {
+        let set_value = if negative {
+            // x3 = 0 - 128 = 0xffffff80
+            vec![
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 4, 0, 128),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 3, 0, 4),
+            ]
+        } else {
+            // x3 = 0x7f
+            vec![Instruction::new_ir(
+                Opcode::from(BuiltinOpcode::ADDI),
+                3,
+                0,
+                0x7f,
+            )]
+        };
+        let basic_block = BasicBlock::new(
+            [
+                vec![Instruction::new_ir(
+                    Opcode::from(BuiltinOpcode::ADDI),
+                    1,
+                    0,
+                    8,
+                )],
+                set_value,
+                vec![
+                    Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 1, 3, 0),
+                    Instruction::new_ir(Opcode::from(opcode), 6, 1, 0),
+                ],
+            ]
+            .concat(),
+        );
+
+        let (view, mut vm_traces) =
+            k_trace_direct(&vec![basic_block], 1).expect("Failed to create trace");
+        let load_step = &mut vm_traces.blocks.last_mut().unwrap().steps[0];
+        let result = load_step
+            .result
+            .as_mut()
+            .expect("load should have a result");
+        // Keep the loaded bytes, flip the extension.
+        *result ^= match opcode {
+            BuiltinOpcode::LB | BuiltinOpcode::LBU => 0xffffff00,
+            BuiltinOpcode::LH | BuiltinOpcode::LHU => 0xffff0000,
+            _ => unreachable!(),
+        };
+
+        Machine::<Chips>::prove(&vm_traces, &view).map(|_| ())
+    }
+
+    #[test]
+    fn test_invalid_load_extension() {
+        for opcode in [
+            BuiltinOpcode::LB,
+            BuiltinOpcode::LH,
+            BuiltinOpcode::LBU,
+            BuiltinOpcode::LHU,
+        ] {
+            for negative in [false, true] {
+                let result = prove_tampered_load(opcode, negative);
+                assert!(
+                    matches!(
+                        result,
+                        Err(NexusProvingError::Stwo(
+                            stwo::prover::ProvingError::ConstraintsNotSatisfied
+                        ))
+                    ),
+                    "tampered {opcode:?} (negative: {negative}) was not rejected"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_non_boolean_timestamp_borrow() {
+        // The SB at row 4 of the block accesses a single byte of memory.
+        const STORE_ROW: usize = 4;
+        let (view, vm_traces) =
+            k_trace_direct(&setup_basic_block_ir(), 1).expect("Failed to create trace");
+        let fill = || {
+            let mut traces = TracesBuilder::new(LOG_SIZE);
+            let program_trace = ProgramTracesBuilder::dummy(LOG_SIZE);
+            let mut side_note = SideNote::new(&program_trace, &view);
+            for (row_idx, program_step) in
+                iter_program_steps(&vm_traces, traces.num_rows()).enumerate()
+            {
+                Chips::fill_main_trace(
+                    &mut traces,
+                    row_idx,
+                    &program_step,
+                    &mut side_note,
+                    &ExtensionsConfig::default(),
+                );
+            }
+            traces
+        };
+        check_chip::<Chips>(fill(), None).unwrap();
+
+        // Set the borrow out of the low half of the timestamp difference to 2, and move it between the limbs of the
+        // difference so that both subtraction constraints still hold.
+        let mut traces = fill();
+        let [_, h1_2, _, _] = traces.column_mut::<WORD_SIZE>(STORE_ROW, Helper1);
+        *h1_2 += BaseField::from(2u32);
+        let [aux_1, _, aux_3, _] = traces.column_mut::<WORD_SIZE>(STORE_ROW, Ram1TsPrevAux);
+        *aux_1 += BaseField::from(2u32 << 16);
+        *aux_3 -= BaseField::from(2u32);
+
+        match check_chip::<Chips>(traces, None) {
+            Err(TraceCheckError::ConstraintNotSatisfied {
+                chip: "LoadStoreChip",
+                row: STORE_ROW,
+                ..
+            }) => {}
+            result => panic!("unexpected result: {result:?}"),
+        }
     }
 }
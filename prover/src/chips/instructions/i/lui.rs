@@ -3,6 +3,10 @@ use stwo_constraint_framework::EvalAtRow;
 use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
 
 use crate::{
+    chips::{
+        instructions::fused_li::{upper_result, upper_result_column},
+        word_decomp::fill_word_limbs,
+    },
     column::Column::{self, *},
     components::AllLookupElements,
     extensions::ExtensionsConfig,
@@ -33,6 +37,12 @@ impl ExecuteChip for LuiChip {
 
 pub struct LuiChip;
 impl MachineChip for LuiChip {
+    const NAME: &'static str = "LuiChip";
+
+    fn supported_opcodes() -> &'static [BuiltinOpcode] {
+        &[BuiltinOpcode::LUI]
+    }
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
@@ -53,7 +63,7 @@ impl MachineChip for LuiChip {
 
         let ExecutionResult { value_a } = Self::execute(vm_step);
 
-        traces.fill_columns(row_idx, value_a, Column::ValueA);
+        fill_word_limbs(traces, row_idx, value_a, upper_result_column(vm_step));
     }
 
     fn add_constraints<E: EvalAtRow>(
@@ -62,11 +72,11 @@ impl MachineChip for LuiChip {
         _lookup_elements: &AllLookupElements,
         _config: &ExtensionsConfig,
     ) {
-        let value_a = trace_eval!(trace_eval, ValueA);
+        let value_a = upper_result(trace_eval);
         let value_c = trace_eval!(trace_eval, ValueC);
         let [is_lui] = trace_eval!(trace_eval, Column::IsLui);
 
-        // Setting a_val = c_val
+        // Setting a_val = c_val, a_val is FusedBase on fused rows
         // is_lui・(c_val_1 - a_val_1) = 0
         // is_lui・(c_val_2 - a_val_2) = 0
         // is_lui・(c_val_3 - a_val_3) = 0
@@ -35,16 +35,17 @@ mod sll;
 pub use sll::SllChip;
 
 mod sra;
-pub use sra::SraChip;
+pub use sra::{SraChip, SraColumns};
 
 mod srl;
 pub use srl::SrlChip;
 
 mod sub;
-pub use sub::{subtract_with_borrow, SubChip};
+pub use sub::{subtract_signed_with_borrow, subtract_with_borrow, SubChip};
 
 mod syscall;
-pub use syscall::SyscallChip;
+pub use syscall::{CommitLookupElements, SyscallChip, SyscallColumns, SyscallOperandColumns};
+pub(crate) use syscall::{SYSCALL_ARG0_REG, SYSCALL_ARG1_REG, SYSCALL_NUMBER_REG};
 
 mod lui;
 pub use lui::LuiChip;
@@ -1,11 +1,12 @@
-use num_traits::One;
-use stwo::core::fields::FieldExpOps;
 use stwo_constraint_framework::EvalAtRow;
 
-use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
+use nexus_vm::riscv::BuiltinOpcode;
 
 use crate::{
-    chips::SubChip,
+    chips::{
+        instructions::word_cmp::{self, Comparison},
+        word_decomp::fill_word_limbs,
+    },
     column::Column::{self, *},
     components::AllLookupElements,
     extensions::ExtensionsConfig,
@@ -18,8 +19,7 @@ use crate::{
 };
 
 pub struct ExecutionResult {
-    pub borrow_bits: [bool; 2], // for 16-bit boundaries
-    pub diff_bytes: Word,
+    pub cmp: Comparison,
     pub result: Word,
 }
 
@@ -29,30 +29,22 @@ pub struct SltChip;
 impl ExecuteChip for SltChip {
     type ExecutionResult = ExecutionResult;
     fn execute(program_step: &ProgramStep) -> Self::ExecutionResult {
-        let super::sub::ExecutionResult {
-            borrow_bits,
-            diff_bytes,
-        } = SubChip::execute(program_step);
-
-        // Extract signed bits of b and c
-        let sgn_b = program_step.get_sgn_b();
-        let sgn_c = program_step.get_sgn_c();
-
-        let result = match (sgn_b, sgn_c) {
-            (false, false) | (true, true) => [borrow_bits[1] as u8, 0, 0, 0],
-            (false, true) => [0, 0, 0, 0],
-            (true, false) => [1, 0, 0, 0],
-        };
-
-        ExecutionResult {
-            borrow_bits,
-            diff_bytes,
-            result,
-        }
+        let value_b = program_step.get_value_b();
+        let (value_c, _) = program_step.get_value_c();
+        // The last borrow of the sign-flipped subtraction is the signed b < c.
+        let cmp = word_cmp::compare(value_b, value_c, true);
+        let result = [cmp.lt() as u8, 0, 0, 0];
+        ExecutionResult { cmp, result }
     }
 }
 
 impl MachineChip for SltChip {
+    const NAME: &'static str = "SltChip";
+
+    fn supported_opcodes() -> &'static [BuiltinOpcode] {
+        &[BuiltinOpcode::SLT, BuiltinOpcode::SLTI]
+    }
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
@@ -71,35 +63,13 @@ impl MachineChip for SltChip {
             return;
         }
 
-        let ExecutionResult {
-            borrow_bits,
-            diff_bytes,
-            result,
-        } = Self::execute(vm_step);
-
-        // Fill Helper2 and Helper3 to the main trace
-        let mut helper_b = vm_step.get_value_b();
-        helper_b[WORD_SIZE - 1] &= 0x7f;
-
-        let (mut helper_c, _) = vm_step.get_value_c();
-        helper_c[WORD_SIZE - 1] &= 0x7f;
-
-        traces.fill_columns(row_idx, helper_b, Helper2);
-        traces.fill_columns(row_idx, helper_c, Helper3);
-
-        // Fill SgnB and SgnC to the main trace
-        let sgn_b = vm_step.get_sgn_b();
-        traces.fill_columns(row_idx, sgn_b, SgnB);
+        let ExecutionResult { cmp, result } = Self::execute(vm_step);
 
-        let sgn_c = vm_step.get_sgn_c();
-        traces.fill_columns(row_idx, sgn_c, SgnC);
-
-        traces.fill_columns(row_idx, diff_bytes, Helper1);
-        traces.fill_columns(row_idx, borrow_bits, CarryFlag);
+        word_cmp::fill_cmp_columns(traces, row_idx, &cmp);
 
         assert_eq!(result, vm_step.get_result().expect("STL must have result"));
 
-        traces.fill_columns(row_idx, result, ValueA);
+        fill_word_limbs(traces, row_idx, result, ValueA);
     }
 
     fn add_constraints<E: EvalAtRow>(
@@ -108,79 +78,21 @@ impl MachineChip for SltChip {
         _lookup_elements: &AllLookupElements,
         _config: &ExtensionsConfig,
     ) {
-        let is_slt = trace_eval!(trace_eval, IsSlt);
-        let is_slt = is_slt[0].clone();
-
-        // modulus for 8-bit limbs
-        let modulus = E::F::from(256u32.into());
-        // modulues for 7-bit
-        let modulus_7 = E::F::from(128u32.into());
-
-        // Reusing the CarryFlag as borrow flag.
-        let borrow_flag = trace_eval!(trace_eval, CarryFlag);
+        let [is_slt] = trace_eval!(trace_eval, IsSlt);
         let value_b = trace_eval!(trace_eval, ValueB);
         let value_c = trace_eval!(trace_eval, ValueC);
         let value_a = trace_eval!(trace_eval, ValueA);
-        let sgn_b = trace_eval!(trace_eval, SgnB);
-        let sgn_c = trace_eval!(trace_eval, SgnC);
-        let helper1_val = trace_eval!(trace_eval, Helper1);
-        let helper2_val = trace_eval!(trace_eval, Helper2);
-        let helper3_val = trace_eval!(trace_eval, Helper3);
 
-        // h_1[0] + h_1[1] * 256 - borrow[0] * 2^{16} = rs1val[0] + rs1val[1] * 256 - rs2val[i] - rs2val[1] * 256
-        eval.add_constraint(
-            is_slt.clone()
-                * (helper1_val[0].clone() + helper1_val[1].clone() * modulus.clone()
-                    - borrow_flag[0].clone() * modulus.clone().pow(2)
-                    - (value_b[0].clone() + value_b[1].clone() * modulus.clone()
-                        - value_c[0].clone()
-                        - value_c[1].clone() * modulus.clone())),
-        );
-
-        // h_1[2] + h_1[3] * 256 - borrow[1] * 2^{16} = rs1val[2] + rs1val[3] * 256 - rs2val[2] - rs2val[3] * 256 - borrow[0]
-        eval.add_constraint(
-            is_slt.clone()
-                * (helper1_val[2].clone() + helper1_val[3].clone() * modulus.clone()
-                    - borrow_flag[1].clone() * modulus.clone().pow(2)
-                    - (value_b[2].clone() + value_b[3].clone() * modulus.clone()
-                        - value_c[2].clone()
-                        - value_c[3].clone() * modulus.clone()
-                        - borrow_flag[0].clone())),
-        );
+        // The borrow of the sign-flipped subtraction is the signed comparison result.
+        let lt_flag =
+            word_cmp::constrain_cmp(eval, trace_eval, is_slt.clone(), &value_b, &value_c, true);
 
-        // Computing a_val from sltu_flag (borrow_flag[3]) and sign bits sgnb and sgnc
-        // is_slt・ (sgnb・(1-sgnc) + ltu_flag・(sgnb・sgnc+(1-sgnb)・(1-sgnc)) - a_val_1) =0
-        // is_slt・(a_val_2) = 0
-        // is_slt・(a_val_3) = 0
-        // is_slt・(a_val_4) = 0
-        for i in 0..WORD_SIZE {
-            if i == 0 {
-                eval.add_constraint(
-                    is_slt.clone()
-                        * (sgn_b[0].clone() * (E::F::one() - sgn_c[0].clone())
-                            + borrow_flag[1].clone()
-                                * (sgn_b[0].clone() * sgn_c[0].clone()
-                                    + (E::F::one() - sgn_b[0].clone())
-                                        * (E::F::one() - sgn_c[0].clone()))
-                            - value_a[0].clone()),
-                );
-            } else {
-                eval.add_constraint(is_slt.clone() * value_a[i].clone())
-            }
+        // is_slt・(lt_flag - a_val_1) = 0
+        eval.add_constraint(is_slt.clone() * (lt_flag - value_a[0].clone()));
+        // is_slt・a_val_i = 0 for i = 2, 3, 4
+        for value_a_byte in &value_a[1..] {
+            eval.add_constraint(is_slt.clone() * value_a_byte.clone());
         }
-
-        // is_slt * (h2[3] + sgn_b * 2^7 - b_val[3]) = 0
-        eval.add_constraint(
-            is_slt.clone()
-                * (modulus_7.clone() * sgn_b[0].clone() + helper2_val[3].clone()
-                    - value_b[3].clone()),
-        );
-        // is_slt * (h3[3] + sgn_c * 2^7 - c_val[3]) = 0
-        eval.add_constraint(
-            is_slt.clone()
-                * (modulus_7.clone() * sgn_c[0].clone() + helper3_val[3].clone()
-                    - value_c[3].clone()),
-        );
     }
 }
 
@@ -188,9 +100,10 @@ impl MachineChip for SltChip {
 mod test {
     use crate::{
         chips::{
-            AddChip, CpuChip, DecodingCheckChip, ProgramMemCheckChip, RegisterMemCheckChip, SubChip,
+            AddChip, CpuChip, DecodingCheckChip, LuiChip, ProgramMemCheckChip,
+            RegisterMemCheckChip, SubChip,
         },
-        test_utils::assert_chip,
+        test_utils::{assert_chip, exhaustive_chip_test, SMALL_DOMAIN},
         trace::{
             preprocessed::PreprocessedBuilder, program::iter_program_steps,
             program_trace::ProgramTracesBuilder,
@@ -325,4 +238,9 @@ mod test {
         }
         assert_chip::<Chips>(traces, Some(program_traces.finalize()));
     }
+
+    #[test]
+    fn test_exhaustive_slt() {
+        exhaustive_chip_test::<(AddChip, LuiChip, SltChip)>(BuiltinOpcode::SLT, SMALL_DOMAIN);
+    }
 }
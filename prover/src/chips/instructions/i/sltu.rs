@@ -1,10 +1,9 @@
-use stwo::core::fields::FieldExpOps;
 use stwo_constraint_framework::EvalAtRow;
 
 use nexus_vm::riscv::BuiltinOpcode;
 
 use crate::{
-    chips::SubChip,
+    chips::instructions::word_cmp::{self, Comparison},
     column::Column::{self, *},
     components::AllLookupElements,
     extensions::ExtensionsConfig,
@@ -17,8 +16,7 @@ use crate::{
 };
 
 pub struct ExecutionResult {
-    pub borrow_bits: [bool; 2], // for 16-bit boundaries
-    pub diff_bytes: Word,
+    pub cmp: Comparison,
     pub result: Word,
 }
 
@@ -28,20 +26,21 @@ pub struct SltuChip;
 impl ExecuteChip for SltuChip {
     type ExecutionResult = ExecutionResult;
     fn execute(program_step: &ProgramStep) -> Self::ExecutionResult {
-        let super::sub::ExecutionResult {
-            borrow_bits,
-            diff_bytes,
-        } = SubChip::execute(program_step);
-        let result = [borrow_bits[1] as u8, 0, 0, 0];
-        ExecutionResult {
-            borrow_bits,
-            diff_bytes,
-            result,
-        }
+        let value_b = program_step.get_value_b();
+        let (value_c, _) = program_step.get_value_c();
+        let cmp = word_cmp::compare(value_b, value_c, false);
+        let result = [cmp.lt() as u8, 0, 0, 0];
+        ExecutionResult { cmp, result }
     }
 }
 
 impl MachineChip for SltuChip {
+    const NAME: &'static str = "SltuChip";
+
+    fn supported_opcodes() -> &'static [BuiltinOpcode] {
+        &[BuiltinOpcode::SLTU, BuiltinOpcode::SLTIU]
+    }
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
@@ -60,14 +59,9 @@ impl MachineChip for SltuChip {
             return;
         }
 
-        let ExecutionResult {
-            borrow_bits,
-            diff_bytes,
-            result,
-        } = Self::execute(vm_step);
+        let ExecutionResult { cmp, result } = Self::execute(vm_step);
 
-        traces.fill_columns_bytes(row_idx, &diff_bytes, Helper1);
-        traces.fill_columns(row_idx, borrow_bits, CarryFlag);
+        word_cmp::fill_cmp_columns(traces, row_idx, &cmp);
 
         assert_eq!(result, vm_step.get_result().expect("STLU must have result"));
 
@@ -80,49 +74,19 @@ impl MachineChip for SltuChip {
         _lookup_elements: &AllLookupElements,
         _config: &ExtensionsConfig,
     ) {
-        let is_sltu = trace_eval!(trace_eval, IsSltu);
-        let is_sltu = is_sltu[0].clone();
-        // modulus for 8-bit limbs
-        let modulus = E::F::from(256u32.into());
-
-        // Reusing the CarryFlag as borrow flag.
-        let borrow_flag = trace_eval!(trace_eval, CarryFlag);
+        let [is_sltu] = trace_eval!(trace_eval, IsSltu);
         let value_b = trace_eval!(trace_eval, ValueB);
         let value_c = trace_eval!(trace_eval, ValueC);
         let value_a = trace_eval!(trace_eval, ValueA);
-        let helper1_val = trace_eval!(trace_eval, Helper1);
-
-        // Assert boorrow_flag[1] is equal to value_a[0].
-        // So the last iteration of the loop below match
-        // is_sltu・(b_val_4 - c_val_4 - h1_4 + a_val_1・2^8 - borrow_1) = 0
-        eval.add_constraint(is_sltu.clone() * (borrow_flag[1].clone() - value_a[0].clone()));
-
-        // h_1[0] + h_1[1] * 256 - borrow[0] * 2^{16} = rs1val[0] + rs1val[1] * 256 - rs2val[0] - rs2val[1] * 256
-        eval.add_constraint(
-            is_sltu.clone()
-                * (helper1_val[0].clone() + helper1_val[1].clone() * modulus.clone()
-                    - borrow_flag[0].clone() * modulus.clone().pow(2)
-                    - (value_b[0].clone() + value_b[1].clone() * modulus.clone()
-                        - value_c[0].clone()
-                        - value_c[1].clone() * modulus.clone())),
-        );
-        // h_1[2] + h_1[3] * 256 - borrow[1] * 2^{16} = rs1val[2] + rs1val[3] * 256 - rs2val[2] - rs2val[3] * 256 - borrow[0]
-        eval.add_constraint(
-            is_sltu.clone()
-                * (helper1_val[2].clone() + helper1_val[3].clone() * modulus.clone()
-                    - borrow_flag[1].clone() * modulus.clone().pow(2)
-                    - (value_b[2].clone() + value_b[3].clone() * modulus.clone()
-                        - value_c[2].clone()
-                        - value_c[3].clone() * modulus.clone()
-                        - borrow_flag[0].clone())),
-        );
 
-        for (i, value_a_byte) in value_a.iter().enumerate() {
-            // value_a[0] is constrained to be equal to the borrow flag, which is in {0, 1}.
-            // Constraint value_a[1..=3] to equal 0.
-            if i != 0 {
-                eval.add_constraint(is_sltu.clone() * value_a_byte.clone());
-            }
+        let lt_flag =
+            word_cmp::constrain_cmp(eval, trace_eval, is_sltu.clone(), &value_b, &value_c, false);
+
+        // is_sltu・(lt_flag - a_val_1) = 0
+        eval.add_constraint(is_sltu.clone() * (lt_flag - value_a[0].clone()));
+        // is_sltu・a_val_i = 0 for i = 2, 3, 4
+        for value_a_byte in &value_a[1..] {
+            eval.add_constraint(is_sltu.clone() * value_a_byte.clone());
         }
     }
 }
@@ -130,8 +94,10 @@ impl MachineChip for SltuChip {
 #[cfg(test)]
 mod test {
     use crate::{
-        chips::{AddChip, CpuChip, DecodingCheckChip, ProgramMemCheckChip, RegisterMemCheckChip},
-        test_utils::assert_chip,
+        chips::{
+            AddChip, CpuChip, DecodingCheckChip, LuiChip, ProgramMemCheckChip, RegisterMemCheckChip,
+        },
+        test_utils::{assert_chip, exhaustive_chip_test, SMALL_DOMAIN},
         trace::{
             preprocessed::PreprocessedBuilder, program::iter_program_steps,
             program_trace::ProgramTracesBuilder,
@@ -214,4 +180,9 @@ mod test {
         }
         assert_chip::<Chips>(traces, Some(program_traces.finalize()));
     }
+
+    #[test]
+    fn test_exhaustive_sltu() {
+        exhaustive_chip_test::<(AddChip, LuiChip, SltuChip)>(BuiltinOpcode::SLTU, SMALL_DOMAIN);
+    }
 }
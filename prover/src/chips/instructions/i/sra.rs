@@ -3,8 +3,10 @@ use stwo::core::fields::m31::BaseField;
 use stwo_constraint_framework::EvalAtRow;
 
 use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
+use nexus_vm_prover_macros::ColumnsEnum;
 
 use crate::{
+    chips::word_decomp::fill_word_limbs,
     column::Column::{self},
     components::AllLookupElements,
     extensions::ExtensionsConfig,
@@ -32,6 +34,17 @@ pub struct ExecutionResult {
 
 pub struct SraChip;
 
+/// Columns used only by [`SraChip`].
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, ColumnsEnum)]
+pub enum SraColumns {
+    /// Exp. Called exp in document.
+    #[size = 1]
+    Exp,
+    /// Auxiliary column for SRA chip, equals sgn_b・(exp1_3-1)・exp in the doc, to keep the constraint degree low.
+    #[size = 1]
+    SraDegreeAux,
+}
+
 impl ExecuteChip for SraChip {
     type ExecutionResult = ExecutionResult;
 
@@ -88,6 +101,12 @@ impl ExecuteChip for SraChip {
 }
 
 impl MachineChip for SraChip {
+    const NAME: &'static str = "SraChip";
+
+    fn supported_opcodes() -> &'static [BuiltinOpcode] {
+        &[BuiltinOpcode::SRA, BuiltinOpcode::SRAI]
+    }
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
@@ -124,11 +143,11 @@ impl MachineChip for SraChip {
         let sra_degree_aux =
             BaseField::from(sgn_b as u32) * BaseField::from(exp1_3 as u32 - 1) * exp;
 
-        traces.fill_columns(row_idx, result, Column::ValueA);
-        traces.fill_columns(row_idx, rem, Column::Rem);
-        traces.fill_columns(row_idx, rem_diff, Column::RemDiff);
-        traces.fill_columns(row_idx, qt, Column::Qt);
-        traces.fill_columns(row_idx, [h1, 0u8, 0u8, 0u8], Column::Helper1);
+        fill_word_limbs(traces, row_idx, result, Column::ValueA);
+        fill_word_limbs(traces, row_idx, rem, Column::Rem);
+        fill_word_limbs(traces, row_idx, rem_diff, Column::RemDiff);
+        fill_word_limbs(traces, row_idx, qt, Column::Qt);
+        fill_word_limbs(traces, row_idx, [h1, 0u8, 0u8, 0u8], Column::Helper1);
         traces.fill_columns(row_idx, [h2, 0u8, 0u8, 0u8], Column::Helper2);
         traces.fill_columns(row_idx, srl, Column::Helper3);
         traces.fill_columns(row_idx, shift_bits[0], Column::ShiftBit1);
@@ -137,9 +156,9 @@ impl MachineChip for SraChip {
         traces.fill_columns(row_idx, shift_bits[3], Column::ShiftBit4);
         traces.fill_columns(row_idx, shift_bits[4], Column::ShiftBit5);
         traces.fill_columns(row_idx, exp1_3, Column::Exp1_3);
-        traces.fill_columns(row_idx, exp, Column::Exp);
+        traces.fill_columns(row_idx, exp, SraColumns::Exp);
         traces.fill_columns(row_idx, sgn_b, Column::SgnB);
-        traces.fill_columns(row_idx, sra_degree_aux, Column::SraDegreeAux);
+        traces.fill_columns(row_idx, sra_degree_aux, SraColumns::SraDegreeAux);
     }
 
     fn add_constraints<E: EvalAtRow>(
@@ -161,13 +180,13 @@ impl MachineChip for SraChip {
         let [h2, _, _, _] = trace_eval!(trace_eval, Column::Helper2);
         let srl = trace_eval!(trace_eval, Column::Helper3);
         let [exp1_3] = trace_eval!(trace_eval, Column::Exp1_3);
-        let [exp] = trace_eval!(trace_eval, Column::Exp);
+        let [exp] = trace_eval!(trace_eval, SraColumns::Exp);
         let rem = trace_eval!(trace_eval, Column::Rem);
         let qt = trace_eval!(trace_eval, Column::Qt);
         let [is_sra] = trace_eval!(trace_eval, Column::IsSra);
         let [sgn_b] = trace_eval!(trace_eval, Column::SgnB);
         let rem_diff = trace_eval!(trace_eval, Column::RemDiff);
-        let [sra_degree_aux] = trace_eval!(trace_eval, Column::SraDegreeAux);
+        let [sra_degree_aux] = trace_eval!(trace_eval, SraColumns::SraDegreeAux);
 
         // is_sra・(sh1 + sh2・2 + sh3・4 + sh4・8 + sh5・16 + h1・32 - c_val_1) = 0
         eval.add_constraint(
@@ -325,10 +344,10 @@ impl MachineChip for SraChip {
 mod test {
     use crate::{
         chips::{
-            AddChip, CpuChip, DecodingCheckChip, ProgramMemCheckChip, RangeCheckChip,
+            AddChip, CpuChip, DecodingCheckChip, LuiChip, ProgramMemCheckChip, RangeCheckChip,
             RegisterMemCheckChip, SllChip, SubChip,
         },
-        test_utils::assert_chip,
+        test_utils::{assert_chip, exhaustive_chip_test, LARGE_SHIFT_DOMAIN, SMALL_DOMAIN},
         trace::{
             program::iter_program_steps, program_trace::ProgramTracesBuilder, PreprocessedTraces,
         },
@@ -418,4 +437,14 @@ mod test {
         }
         assert_chip::<Chips>(traces, Some(program_traces.finalize()));
     }
+
+    #[test]
+    fn test_exhaustive_sra() {
+        exhaustive_chip_test::<(AddChip, LuiChip, SraChip)>(BuiltinOpcode::SRA, SMALL_DOMAIN);
+    }
+
+    #[test]
+    fn test_sra_large_shift_amounts() {
+        exhaustive_chip_test::<(AddChip, LuiChip, SraChip)>(BuiltinOpcode::SRA, LARGE_SHIFT_DOMAIN);
+    }
 }
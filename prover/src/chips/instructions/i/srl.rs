@@ -4,6 +4,7 @@ use stwo_constraint_framework::EvalAtRow;
 use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
 
 use crate::{
+    chips::word_decomp::fill_word_limbs,
     column::Column::{self},
     components::AllLookupElements,
     extensions::ExtensionsConfig,
@@ -77,6 +78,12 @@ impl ExecuteChip for SrlChip {
 }
 
 impl MachineChip for SrlChip {
+    const NAME: &'static str = "SrlChip";
+
+    fn supported_opcodes() -> &'static [BuiltinOpcode] {
+        &[BuiltinOpcode::SRL, BuiltinOpcode::SRLI]
+    }
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
@@ -105,11 +112,11 @@ impl MachineChip for SrlChip {
             qt,
         } = Self::execute(vm_step);
 
-        traces.fill_columns(row_idx, result, Column::ValueA);
-        traces.fill_columns(row_idx, rem, Column::Rem);
-        traces.fill_columns(row_idx, rem_diff, Column::RemDiff);
-        traces.fill_columns(row_idx, qt, Column::Qt);
-        traces.fill_columns(row_idx, [h1, 0u8, 0u8, 0u8], Column::Helper1);
+        fill_word_limbs(traces, row_idx, result, Column::ValueA);
+        fill_word_limbs(traces, row_idx, rem, Column::Rem);
+        fill_word_limbs(traces, row_idx, rem_diff, Column::RemDiff);
+        fill_word_limbs(traces, row_idx, qt, Column::Qt);
+        fill_word_limbs(traces, row_idx, [h1, 0u8, 0u8, 0u8], Column::Helper1);
         traces.fill_columns(row_idx, shift_bits[0], Column::ShiftBit1);
         traces.fill_columns(row_idx, shift_bits[1], Column::ShiftBit2);
         traces.fill_columns(row_idx, shift_bits[2], Column::ShiftBit3);
@@ -229,10 +236,10 @@ impl MachineChip for SrlChip {
 mod test {
     use crate::{
         chips::{
-            AddChip, CpuChip, DecodingCheckChip, ProgramMemCheckChip, RangeCheckChip,
+            AddChip, CpuChip, DecodingCheckChip, LuiChip, ProgramMemCheckChip, RangeCheckChip,
             RegisterMemCheckChip, SllChip, SubChip,
         },
-        test_utils::assert_chip,
+        test_utils::{assert_chip, exhaustive_chip_test, LARGE_SHIFT_DOMAIN, SMALL_DOMAIN},
         trace::{
             program::iter_program_steps, program_trace::ProgramTracesBuilder, PreprocessedTraces,
         },
@@ -328,4 +335,14 @@ mod test {
         }
         assert_chip::<Chips>(traces, Some(program_traces.finalize()));
     }
+
+    #[test]
+    fn test_exhaustive_srl() {
+        exhaustive_chip_test::<(AddChip, LuiChip, SrlChip)>(BuiltinOpcode::SRL, SMALL_DOMAIN);
+    }
+
+    #[test]
+    fn test_srl_large_shift_amounts() {
+        exhaustive_chip_test::<(AddChip, LuiChip, SrlChip)>(BuiltinOpcode::SRL, LARGE_SHIFT_DOMAIN);
+    }
 }
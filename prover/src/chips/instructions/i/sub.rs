@@ -45,6 +45,16 @@ pub fn subtract_with_borrow(x: Word, y: Word) -> (Word, BoolWord) {
     (diff_bytes, borrow_bits)
 }
 
+/// Subtracts `y` from `x` as signed words, by subtracting their sign-flipped values as unsigned words.
+///
+/// The difference bytes are the same as for [`subtract_with_borrow`], but the last borrow bit is set iff `x < y`
+/// as signed integers. Equal operands have a zero difference and never borrow, regardless of their sign.
+pub fn subtract_signed_with_borrow(mut x: Word, mut y: Word) -> (Word, BoolWord) {
+    x[WORD_SIZE - 1] ^= 0x80;
+    y[WORD_SIZE - 1] ^= 0x80;
+    subtract_with_borrow(x, y)
+}
+
 impl ExecuteChip for SubChip {
     type ExecutionResult = ExecutionResult;
     fn execute(program_step: &ProgramStep) -> ExecutionResult {
@@ -66,6 +76,12 @@ impl ExecuteChip for SubChip {
 }
 
 impl MachineChip for SubChip {
+    const NAME: &'static str = "SubChip";
+
+    fn supported_opcodes() -> &'static [BuiltinOpcode] {
+        &[BuiltinOpcode::SUB]
+    }
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
@@ -146,9 +162,10 @@ mod test {
     use super::*;
     use crate::{
         chips::{
-            cpu::CpuChip, AddChip, DecodingCheckChip, ProgramMemCheckChip, RegisterMemCheckChip,
+            cpu::CpuChip, AddChip, DecodingCheckChip, LuiChip, ProgramMemCheckChip,
+            RegisterMemCheckChip,
         },
-        test_utils::assert_chip,
+        test_utils::{assert_chip, exhaustive_chip_test, SMALL_DOMAIN},
         trace::{
             preprocessed::PreprocessedBuilder, program::iter_program_steps,
             program_trace::ProgramTracesBuilder,
@@ -236,4 +253,9 @@ mod test {
         }
         assert_chip::<Chips>(traces, Some(program_traces.finalize()));
     }
+
+    #[test]
+    fn test_exhaustive_sub() {
+        exhaustive_chip_test::<(AddChip, LuiChip, SubChip)>(BuiltinOpcode::SUB, SMALL_DOMAIN);
+    }
 }
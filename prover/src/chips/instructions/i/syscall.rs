@@ -1,17 +1,33 @@
-use num_traits::One;
-use stwo::core::fields::m31::BaseField;
-use stwo_constraint_framework::EvalAtRow;
+use num_traits::{One, Zero};
+use stwo::{
+    core::{channel::Channel, fields::m31::BaseField},
+    prover::backend::simd::{
+        column::BaseColumn,
+        m31::{PackedBaseField, LOG_N_LANES},
+    },
+};
+use stwo_constraint_framework::{EvalAtRow, Relation, RelationEntry};
 
-use nexus_vm::{riscv::BuiltinOpcode, SyscallCode};
+use nexus_vm::{
+    riscv::{BuiltinOpcode, Register},
+    SyscallCode, WORD_SIZE,
+};
+use nexus_vm_prover_macros::ColumnsEnum;
 
 use crate::{
-    column::Column::{self},
+    chips::word_decomp::{fill_word_limbs, word_halves},
+    column::{
+        Column::{self},
+        PreprocessedColumn, ProgramColumn,
+    },
     components::AllLookupElements,
     extensions::ExtensionsConfig,
     trace::{
-        eval::{trace_eval, TraceEval},
+        eval::{preprocessed_trace_eval, program_trace_eval, trace_eval, TraceEval},
+        logup::LogupTraceBuilder,
+        program_trace::ProgramTraces,
         sidenote::SideNote,
-        ProgramStep, TracesBuilder,
+        FinalizedTraces, PreprocessedTraces, ProgramStep, TracesBuilder,
     },
     traits::MachineChip,
     virtual_column::IsTypeSys,
@@ -21,7 +37,88 @@ use crate::virtual_column::VirtualColumn;
 
 pub struct SyscallChip;
 
+/// Register holding the syscall number, `a7`.
+pub(crate) const SYSCALL_NUMBER_REG: Register = Register::X17;
+/// Register holding the first argument of a syscall, `a0`, which is also where syscalls return their result.
+pub(crate) const SYSCALL_ARG0_REG: Register = Register::X10;
+/// Register holding the second argument of a syscall, `a1`. Only the commit syscall reads it, through the third
+/// register access, which writes its value back unchanged.
+pub(crate) const SYSCALL_ARG1_REG: Register = Register::X11;
+
+const LOOKUP_TUPLE_SIZE: usize = 2;
+stwo_constraint_framework::relation!(CommitLookupElements, LOOKUP_TUPLE_SIZE);
+
+/// Operands of a syscall, read from registers through the register memory check, see [`SYSCALL_NUMBER_REG`] and
+/// [`SYSCALL_ARG0_REG`].
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, ColumnsEnum)]
+pub enum SyscallOperandColumns {
+    /// The syscall number, read from the register of the first access.
+    #[size = 4]
+    SyscallNumber,
+    /// The first argument, read from the register of the second access.
+    #[size = 4]
+    SyscallArg0,
+}
+
+/// Columns used only by [`SyscallChip`], one flag per supported syscall.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, ColumnsEnum)]
+pub enum SyscallColumns {
+    /// Boolean flag on whether the row is an ECALL_DEBUG (Write).
+    #[size = 1]
+    IsSysDebug,
+    /// Boolean flag on whether the row is an ECALL_MADVISE (Heap Allocation).
+    #[size = 1]
+    IsSysMemoryAdvise,
+    /// Boolean flag on whether the row is an ECALL_HALT (Exit).
+    #[size = 1]
+    IsSysHalt,
+    /// Boolean flag on whether the row is an ECALL_PRIVATE_INPUT (ReadFromPrivateInput).
+    #[size = 1]
+    IsSysPrivInput,
+    /// Boolean flag on whether the row is an ECALL_CYCLECOUNT (CycleCount).
+    #[size = 1]
+    IsSysCycleCount,
+    /// Boolean flag on whether the row is an ECALL_STACK_RESET (OverwriteStackPointer).
+    #[size = 1]
+    IsSysStackReset,
+    /// Boolean flag on whether the row is an ECALL_HEAP_RESET (OverwriteHeapPointer).
+    #[size = 1]
+    IsSysHeapReset,
+    /// Boolean flag on whether the row is an ECALL_COMMIT (Commit).
+    #[size = 1]
+    IsSysCommit,
+    /// Boolean flag on whether the row is an ECALL_HINT (Hint).
+    #[size = 1]
+    IsSysHint,
+    /// Boolean flag on whether the row is an ECALL_READ_HINT (ReadHint).
+    #[size = 1]
+    IsSysReadHint,
+    /// Boolean flag on whether the row is an ECALL_RDCYCLE (ReadCycleCounter).
+    #[size = 1]
+    IsSysReadCycleCounter,
+    /// Boolean flag on whether the row is an ECALL_PRIVATE_INPUT_WORD (ReadPrivateInputWord).
+    #[size = 1]
+    IsSysPrivInputWord,
+    /// Carry of the low 16-bit halves of `offset + len` on ECALL_COMMIT rows, zero elsewhere.
+    #[size = 1]
+    CommitCarry,
+}
+
 impl MachineChip for SyscallChip {
+    const NAME: &'static str = "SyscallChip";
+
+    fn supported_opcodes() -> &'static [BuiltinOpcode] {
+        &[BuiltinOpcode::ECALL, BuiltinOpcode::EBREAK]
+    }
+
+    fn draw_lookup_elements(
+        all_elements: &mut AllLookupElements,
+        channel: &mut impl Channel,
+        _config: &ExtensionsConfig,
+    ) {
+        all_elements.insert(CommitLookupElements::draw(channel));
+    }
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
@@ -42,33 +139,75 @@ impl MachineChip for SyscallChip {
 
         let syscall_number = vm_step
             .get_syscall_code()
-            .expect("ECALL must have syscall number at Register::X17");
+            .expect("ECALL must have syscall number at SYSCALL_NUMBER_REG");
+        traces.fill_columns(
+            row_idx,
+            syscall_number.to_le_bytes(),
+            SyscallOperandColumns::SyscallNumber,
+        );
+        traces.fill_columns(
+            row_idx,
+            vm_step.get_syscall_arg0().to_le_bytes(),
+            SyscallOperandColumns::SyscallArg0,
+        );
 
         let result = vm_step.step.result;
-        match (syscall_number, result) {
-            (0x200, None) => traces.fill_columns(row_idx, true, Column::IsSysDebug),
-            (0x201, result) => {
+        match (SyscallCode::try_from(syscall_number), result) {
+            (Ok(SyscallCode::Write), None) => {
+                traces.fill_columns(row_idx, true, SyscallColumns::IsSysDebug)
+            }
+            (Ok(SyscallCode::Exit), result) => {
                 // the result may be present or not depending on a pass, has no effect
                 let _ = result;
 
-                traces.fill_columns(row_idx, true, Column::IsSysHalt);
+                traces.fill_columns(row_idx, true, SyscallColumns::IsSysHalt);
                 // PcNext should be the current Pc
                 traces.fill_columns(row_idx, vm_step.step.pc, Column::PcNext);
             }
-            (0x400, Some(result)) => {
-                traces.fill_columns(row_idx, true, Column::IsSysPrivInput);
-                traces.fill_columns(row_idx, result, Column::ValueA);
+            (Ok(SyscallCode::ReadFromPrivateInput), Some(result)) => {
+                traces.fill_columns(row_idx, true, SyscallColumns::IsSysPrivInput);
+                fill_word_limbs(traces, row_idx, result, Column::ValueA);
+            }
+            (Ok(SyscallCode::ReadPrivateInputWord), Some(result)) => {
+                // The bytes written to memory are filled by LoadStoreChip.
+                traces.fill_columns(row_idx, true, SyscallColumns::IsSysPrivInputWord);
+                fill_word_limbs(traces, row_idx, result, Column::ValueA);
+            }
+            (Ok(SyscallCode::CycleCount), None) => {
+                traces.fill_columns(row_idx, true, SyscallColumns::IsSysCycleCount)
+            }
+            (Ok(SyscallCode::OverwriteStackPointer), Some(result)) => {
+                traces.fill_columns(row_idx, true, SyscallColumns::IsSysStackReset);
+                fill_word_limbs(traces, row_idx, result, Column::ValueA);
             }
-            (0x401, None) => traces.fill_columns(row_idx, true, Column::IsSysCycleCount),
-            (0x402, Some(result)) => {
-                traces.fill_columns(row_idx, true, Column::IsSysStackReset);
-                traces.fill_columns(row_idx, result, Column::ValueA);
+            (Ok(SyscallCode::OverwriteHeapPointer), Some(result)) => {
+                traces.fill_columns(row_idx, true, SyscallColumns::IsSysHeapReset);
+                fill_word_limbs(traces, row_idx, result, Column::ValueA);
             }
-            (0x403, Some(result)) => {
-                traces.fill_columns(row_idx, true, Column::IsSysHeapReset);
-                traces.fill_columns(row_idx, result, Column::ValueA);
+            (Ok(SyscallCode::MemoryAdvise), None) => {
+                traces.fill_columns(row_idx, true, SyscallColumns::IsSysMemoryAdvise)
+            }
+            (Ok(SyscallCode::Commit), None) => {
+                traces.fill_columns(row_idx, true, SyscallColumns::IsSysCommit);
+                // The length is read through the third register access, which writes it back unchanged.
+                let offset = vm_step.get_syscall_arg0();
+                let len = vm_step.regs.read(SYSCALL_ARG1_REG);
+                fill_word_limbs(traces, row_idx, len, Column::ValueA);
+                let carry = (offset & 0xFFFF) + (len & 0xFFFF) > 0xFFFF;
+                traces.fill_columns(row_idx, carry, SyscallColumns::CommitCarry);
+            }
+            (Ok(SyscallCode::Hint), Some(result)) => {
+                traces.fill_columns(row_idx, true, SyscallColumns::IsSysHint);
+                fill_word_limbs(traces, row_idx, result, Column::ValueA);
+            }
+            (Ok(SyscallCode::ReadHint), Some(result)) => {
+                traces.fill_columns(row_idx, true, SyscallColumns::IsSysReadHint);
+                fill_word_limbs(traces, row_idx, result, Column::ValueA);
+            }
+            (Ok(SyscallCode::ReadCycleCounter), Some(result)) => {
+                traces.fill_columns(row_idx, true, SyscallColumns::IsSysReadCycleCounter);
+                fill_word_limbs(traces, row_idx, result, Column::ValueA);
             }
-            (0x405, None) => traces.fill_columns(row_idx, true, Column::IsSysMemoryAdvise),
             _ => {
                 panic!(
                     "Unknown syscall number: 0x{:x} and result: {:?}, on row {}",
@@ -81,33 +220,66 @@ impl MachineChip for SyscallChip {
     fn add_constraints<E: EvalAtRow>(
         eval: &mut E,
         trace_eval: &TraceEval<E>,
-        _lookup_elements: &AllLookupElements,
+        lookup_elements: &AllLookupElements,
         _config: &ExtensionsConfig,
     ) {
         let [is_type_sys] = IsTypeSys::eval(trace_eval);
-        let [is_sys_debug] = trace_eval!(trace_eval, Column::IsSysDebug);
-        let [is_sys_halt] = trace_eval!(trace_eval, Column::IsSysHalt);
-        let [is_sys_priv_input] = trace_eval!(trace_eval, Column::IsSysPrivInput);
-        let [is_sys_cycle_count] = trace_eval!(trace_eval, Column::IsSysCycleCount);
-        let [is_sys_stack_reset] = trace_eval!(trace_eval, Column::IsSysStackReset);
-        let [is_sys_heap_reset] = trace_eval!(trace_eval, Column::IsSysHeapReset);
-        let [is_sys_madvise] = trace_eval!(trace_eval, Column::IsSysMemoryAdvise);
-        let value_b = trace_eval!(trace_eval, Column::ValueB);
-
-        // is_type_sys・				(b_val_3) = 0
-        // is_type_sys・				(b_val_4) = 0
-        // is_type_sys・is_sys_debug・		(b_val_1 - 0x00) = 0  // b_val=0x200
-        // is_type_sys・is_sys_debug・		(b_val_2 - 0x02) = 0  // b_val=0x200
-        // is_type_sys・is_sys_halt・		(b_val_1 - 0x01) = 0  // b_val=0x201
-        // is_type_sys・is_sys_halt・		(b_val_2 - 0x02) = 0  // b_val=0x201
-        // is_type_sys・is_sys_priv_input・	(b_val_1 - 0x00) = 0  // b_val=0x400
-        // is_type_sys・is_sys_priv_input・	(b_val_2 - 0x04) = 0  // b_val=0x400
-        // is_type_sys・is_sys_cycle_count・	(b_val_1 - 0x01) = 0  // b_val=0x401
-        // is_type_sys・is_sys_cycle_count・	(b_val_2 - 0x04) = 0  // b_val=0x401
-        // is_type_sys・is_sys_stack_reset・	(b_val_1 - 0x02) = 0  // b_val=0x402
-        // is_type_sys・is_sys_stack_reset・	(b_val_2 - 0x04) = 0  // b_val=0x402
-        // is_type_sys・is_sys_heap_reset・	(b_val_1 - 0x03) = 0  // b_val=0x403
-        // is_type_sys・is_sys_heap_reset・	(b_val_2 - 0x04) = 0  // b_val=0x403
+        let [is_sys_debug] = trace_eval!(trace_eval, SyscallColumns::IsSysDebug);
+        let [is_sys_halt] = trace_eval!(trace_eval, SyscallColumns::IsSysHalt);
+        let [is_sys_priv_input] = trace_eval!(trace_eval, SyscallColumns::IsSysPrivInput);
+        let [is_sys_cycle_count] = trace_eval!(trace_eval, SyscallColumns::IsSysCycleCount);
+        let [is_sys_stack_reset] = trace_eval!(trace_eval, SyscallColumns::IsSysStackReset);
+        let [is_sys_heap_reset] = trace_eval!(trace_eval, SyscallColumns::IsSysHeapReset);
+        let [is_sys_madvise] = trace_eval!(trace_eval, SyscallColumns::IsSysMemoryAdvise);
+        let [is_sys_commit] = trace_eval!(trace_eval, SyscallColumns::IsSysCommit);
+        let [is_sys_hint] = trace_eval!(trace_eval, SyscallColumns::IsSysHint);
+        let [is_sys_read_hint] = trace_eval!(trace_eval, SyscallColumns::IsSysReadHint);
+        let [is_sys_read_cycle_counter] =
+            trace_eval!(trace_eval, SyscallColumns::IsSysReadCycleCounter);
+        let [is_sys_priv_input_word] = trace_eval!(trace_eval, SyscallColumns::IsSysPrivInputWord);
+        let syscall_number = trace_eval!(trace_eval, SyscallOperandColumns::SyscallNumber);
+        let syscall_arg0 = trace_eval!(trace_eval, SyscallOperandColumns::SyscallArg0);
+
+        // The operands are the values read by the first two register accesses, whose addresses are constrained
+        // to the calling convention in the decoding of system calls. Comparing the previous values rather than
+        // ValueB and ValueC keeps this chip independent of how the accesses are laid out in the shared columns.
+        // is_type_sys・(syscall_number_i - reg1_val_prev_i) = 0 for i = 1..4
+        // is_type_sys・(syscall_arg0_i - reg2_val_prev_i) = 0 for i = 1..4
+        let reg1_val_prev = trace_eval!(trace_eval, Column::Reg1ValPrev);
+        let reg2_val_prev = trace_eval!(trace_eval, Column::Reg2ValPrev);
+        for (operand, reg_val_prev) in [
+            (syscall_number.clone(), reg1_val_prev),
+            (syscall_arg0.clone(), reg2_val_prev),
+        ] {
+            for (limb, prev) in operand.into_iter().zip(reg_val_prev) {
+                eval.add_constraint(is_type_sys.clone() * (limb - prev));
+            }
+        }
+
+        // is_type_sys・				(number_3) = 0
+        // is_type_sys・				(number_4) = 0
+        // is_type_sys・is_sys_debug・		(number_1 - 0x00) = 0  // number=0x200
+        // is_type_sys・is_sys_debug・		(number_2 - 0x02) = 0  // number=0x200
+        // is_type_sys・is_sys_halt・		(number_1 - 0x01) = 0  // number=0x201
+        // is_type_sys・is_sys_halt・		(number_2 - 0x02) = 0  // number=0x201
+        // is_type_sys・is_sys_priv_input・	(number_1 - 0x00) = 0  // number=0x400
+        // is_type_sys・is_sys_priv_input・	(number_2 - 0x04) = 0  // number=0x400
+        // is_type_sys・is_sys_cycle_count・	(number_1 - 0x01) = 0  // number=0x401
+        // is_type_sys・is_sys_cycle_count・	(number_2 - 0x04) = 0  // number=0x401
+        // is_type_sys・is_sys_stack_reset・	(number_1 - 0x02) = 0  // number=0x402
+        // is_type_sys・is_sys_stack_reset・	(number_2 - 0x04) = 0  // number=0x402
+        // is_type_sys・is_sys_heap_reset・	(number_1 - 0x03) = 0  // number=0x403
+        // is_type_sys・is_sys_heap_reset・	(number_2 - 0x04) = 0  // number=0x403
+        // is_type_sys・is_sys_commit・		(number_1 - 0x06) = 0  // number=0x406
+        // is_type_sys・is_sys_commit・		(number_2 - 0x04) = 0  // number=0x406
+        // is_type_sys・is_sys_hint・		(number_1 - 0x07) = 0  // number=0x407
+        // is_type_sys・is_sys_hint・		(number_2 - 0x04) = 0  // number=0x407
+        // is_type_sys・is_sys_read_hint・	(number_1 - 0x08) = 0  // number=0x408
+        // is_type_sys・is_sys_read_hint・	(number_2 - 0x04) = 0  // number=0x408
+        // is_type_sys・is_sys_read_cycle_counter・	(number_1 - 0x09) = 0  // number=0x409
+        // is_type_sys・is_sys_read_cycle_counter・	(number_2 - 0x04) = 0  // number=0x409
+        // is_type_sys・is_sys_priv_input_word・	(number_1 - 0x0a) = 0  // number=0x40a
+        // is_type_sys・is_sys_priv_input_word・	(number_2 - 0x04) = 0  // number=0x40a
 
         let syscall_table = [
             (SyscallCode::Write as u32, &is_sys_debug),
@@ -120,26 +292,39 @@ impl MachineChip for SyscallChip {
             ),
             (SyscallCode::OverwriteHeapPointer as u32, &is_sys_heap_reset),
             (SyscallCode::MemoryAdvise as u32, &is_sys_madvise),
+            (SyscallCode::Commit as u32, &is_sys_commit),
+            (SyscallCode::Hint as u32, &is_sys_hint),
+            (SyscallCode::ReadHint as u32, &is_sys_read_hint),
+            (
+                SyscallCode::ReadCycleCounter as u32,
+                &is_sys_read_cycle_counter,
+            ),
+            (
+                SyscallCode::ReadPrivateInputWord as u32,
+                &is_sys_priv_input_word,
+            ),
         ];
 
-        eval.add_constraint(is_type_sys.clone() * value_b[2].clone());
-        eval.add_constraint(is_type_sys.clone() * value_b[3].clone());
+        eval.add_constraint(is_type_sys.clone() * syscall_number[2].clone());
+        eval.add_constraint(is_type_sys.clone() * syscall_number[3].clone());
 
         for (code, is_sys) in syscall_table {
             let value_codes = u16::try_from(code)
                 .expect("Syscall code must be in u16 range")
                 .to_le_bytes();
-            for (vc, vb) in value_codes.iter().zip(value_b.clone()) {
+            for (vc, number) in value_codes.iter().zip(syscall_number.clone()) {
                 eval.add_constraint(
                     is_type_sys.clone()
                         * is_sys.clone()
-                        * (vb - E::F::from(BaseField::from(*vc as u32))),
+                        * (number - E::F::from(BaseField::from(*vc as u32))),
                 );
             }
         }
 
         // Enforce that one flag is set
-        // is_type_sys・(is_sys_debug + is_sys_halt + is_sys_priv_input + is_sys_cycle_count + is_sys_stack_reset + is_sys_heap_reset - 1) = 0
+        // is_type_sys・(is_sys_debug + is_sys_halt + is_sys_priv_input + is_sys_cycle_count + is_sys_stack_reset + is_sys_heap_reset
+        //   + is_sys_madvise + is_sys_commit + is_sys_hint + is_sys_read_hint + is_sys_read_cycle_counter
+        //   + is_sys_priv_input_word - 1) = 0
         eval.add_constraint(
             is_type_sys.clone()
                 * (is_sys_debug.clone()
@@ -149,13 +334,20 @@ impl MachineChip for SyscallChip {
                     + is_sys_stack_reset.clone()
                     + is_sys_heap_reset.clone()
                     + is_sys_madvise.clone()
+                    + is_sys_commit.clone()
+                    + is_sys_hint.clone()
+                    + is_sys_read_hint.clone()
+                    + is_sys_read_cycle_counter.clone()
+                    + is_sys_priv_input_word.clone()
                     - E::F::one()),
         );
 
         // Enforcing values for op_a
         // is_type_sys・(is_sys_debug + is_sys_halt + is_sys_cycle_count + is_sys_madvise)・(op_a) = 0
-        // is_type_sys・(is_sys_priv_input + is_sys_heap_reset)・(10 - op_a) = 0
+        // is_type_sys・(is_sys_priv_input + is_sys_heap_reset + is_sys_hint + is_sys_read_hint + is_sys_read_cycle_counter
+        //   + is_sys_priv_input_word)・(a0 - op_a) = 0
         // is_type_sys・(is_sys_stack_reset)・(2 - op_a) = 0
+        // is_type_sys・(is_sys_commit)・(a1 - op_a) = 0
         let [op_a] = trace_eval!(trace_eval, Column::OpA);
 
         eval.add_constraint(
@@ -168,18 +360,28 @@ impl MachineChip for SyscallChip {
         );
         eval.add_constraint(
             is_type_sys.clone()
-                * (is_sys_priv_input.clone() + is_sys_heap_reset.clone())
-                * (E::F::from(BaseField::from(10)) - op_a.clone()),
+                * (is_sys_priv_input.clone()
+                    + is_sys_heap_reset.clone()
+                    + is_sys_hint.clone()
+                    + is_sys_read_hint.clone()
+                    + is_sys_read_cycle_counter.clone()
+                    + is_sys_priv_input_word.clone())
+                * (E::F::from(BaseField::from(SYSCALL_ARG0_REG as u32)) - op_a.clone()),
         );
         eval.add_constraint(
             is_type_sys.clone()
                 * is_sys_stack_reset.clone()
                 * (E::F::from(BaseField::from(2)) - op_a.clone()),
         );
+        eval.add_constraint(
+            is_type_sys.clone()
+                * is_sys_commit.clone()
+                * (E::F::from(BaseField::from(SYSCALL_ARG1_REG as u32)) - op_a.clone()),
+        );
 
         // Enforcing ranges for a_val
-        // is_type_sys・(is_sys_debug + is_sys_halt + is_sys_cycle_count)・(a_val_1 + a_val_2 * 256) = 0
-        // is_type_sys・(is_sys_debug + is_sys_halt + is_sys_cycle_count)・(a_val_3 + a_val_3 * 256) = 0
+        // is_type_sys・(is_sys_debug + is_sys_halt + is_sys_cycle_count + is_sys_madvise)・(a_val_1 + a_val_2 * 256) = 0
+        // is_type_sys・(is_sys_debug + is_sys_halt + is_sys_cycle_count + is_sys_madvise)・(a_val_3 + a_val_3 * 256) = 0
         let value_a = trace_eval!(trace_eval, Column::ValueA);
         for a in value_a.chunks(2) {
             eval.add_constraint(
@@ -191,21 +393,226 @@ impl MachineChip for SyscallChip {
                     * (a[0].clone() + a[1].clone() * E::F::from(BaseField::from(256))),
             );
         }
+
+        // The cycle counter is the clock of the row, otherwise the prover could lie to the guest about time.
+        // is_type_sys・is_sys_read_cycle_counter・(a_val_i - clk_i) = 0 for i = 1..4
+        let clk = trace_eval!(trace_eval, Column::Clk);
+        for (a, clk) in value_a.clone().into_iter().zip(clk) {
+            eval.add_constraint(
+                is_type_sys.clone() * is_sys_read_cycle_counter.clone() * (a - clk),
+            );
+        }
+
+        Self::constrain_priv_input_word(
+            eval,
+            trace_eval,
+            is_type_sys.clone(),
+            is_sys_priv_input_word,
+        );
+
+        Self::constrain_commit(
+            eval,
+            trace_eval,
+            lookup_elements.as_ref(),
+            is_type_sys,
+            is_sys_commit,
+            &syscall_arg0,
+            &value_a,
+        );
+    }
+
+    fn fill_interaction_trace(
+        logup_trace_gen: &mut LogupTraceBuilder,
+        original_traces: &FinalizedTraces,
+        preprocessed_trace: &PreprocessedTraces,
+        program_traces: &ProgramTraces,
+        lookup_elements: &AllLookupElements,
+    ) {
+        let lookup_elements: &CommitLookupElements = lookup_elements.as_ref();
+        let [is_sys_commit] = original_traces.get_base_column(SyscallColumns::IsSysCommit);
+        let [carry] = original_traces.get_base_column(SyscallColumns::CommitCarry);
+        let offset: [_; WORD_SIZE] =
+            original_traces.get_base_column(SyscallOperandColumns::SyscallArg0);
+        let len: [_; WORD_SIZE] = original_traces.get_base_column(Column::ValueA);
+        let [is_first] =
+            preprocessed_trace.get_preprocessed_base_column(PreprocessedColumn::IsFirst);
+        let committed_len: [_; 2] =
+            program_traces.get_base_column(ProgramColumn::PrgCommittedOutputLen);
+
+        let halves = |limbs: &[&BaseColumn; WORD_SIZE], vec_row: usize| {
+            let byte = PackedBaseField::broadcast(BaseField::from(1 << 8));
+            [
+                limbs[0].data[vec_row] + limbs[1].data[vec_row] * byte,
+                limbs[2].data[vec_row] + limbs[3].data[vec_row] * byte,
+            ]
+        };
+        let num_vec_rows = 1 << (original_traces.log_size() - LOG_N_LANES);
+
+        // Subtract (offset) and add (offset + len) on every commit row.
+        let mut logup_col_gen = logup_trace_gen.new_col();
+        for vec_row in 0..num_vec_rows {
+            let offset = halves(&offset, vec_row);
+            let numerator = is_sys_commit.data[vec_row];
+            logup_col_gen.write_frac(
+                vec_row,
+                (-numerator).into(),
+                lookup_elements.combine(&offset),
+            );
+        }
+        logup_col_gen.finalize_col();
+
+        let mut logup_col_gen = logup_trace_gen.new_col();
+        for vec_row in 0..num_vec_rows {
+            let [offset_low, offset_high] = halves(&offset, vec_row);
+            let [len_low, len_high] = halves(&len, vec_row);
+            let carry = carry.data[vec_row];
+            let end = [
+                offset_low + len_low - carry * PackedBaseField::broadcast(BaseField::from(1 << 16)),
+                offset_high + len_high + carry,
+            ];
+            let numerator = is_sys_commit.data[vec_row];
+            logup_col_gen.write_frac(vec_row, numerator.into(), lookup_elements.combine(&end));
+        }
+        logup_col_gen.finalize_col();
+
+        // Add (0) and subtract (committed length) on the first row, so that the commits must form a chain from the
+        // start of the public output to the committed length.
+        let mut logup_col_gen = logup_trace_gen.new_col();
+        for vec_row in 0..num_vec_rows {
+            let start = [PackedBaseField::zero(); 2];
+            logup_col_gen.write_frac(
+                vec_row,
+                is_first.data[vec_row].into(),
+                lookup_elements.combine(&start),
+            );
+        }
+        logup_col_gen.finalize_col();
+
+        let mut logup_col_gen = logup_trace_gen.new_col();
+        for vec_row in 0..num_vec_rows {
+            let end = [
+                committed_len[0].data[vec_row],
+                committed_len[1].data[vec_row],
+            ];
+            let numerator = is_first.data[vec_row];
+            logup_col_gen.write_frac(vec_row, (-numerator).into(), lookup_elements.combine(&end));
+        }
+        logup_col_gen.finalize_col();
+    }
+}
+
+impl SyscallChip {
+    /// Constrains the read private input word syscall to write the four bytes at `a0`.
+    ///
+    /// LoadStoreChip makes the four byte accesses of the row, one per cycle of the syscall, at the base address
+    /// `RamBaseAddr`. The bytes themselves come from the private input tape and are not constrained.
+    fn constrain_priv_input_word<E: EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &TraceEval<E>,
+        is_type_sys: E::F,
+        is_sys_priv_input_word: E::F,
+    ) {
+        // Only ECALL rows write memory.
+        // (1 - is_type_sys)・is_sys_priv_input_word = 0
+        eval.add_constraint((E::F::one() - is_type_sys) * is_sys_priv_input_word.clone());
+
+        // is_sys_priv_input_word・(ram_base_addr_i - syscall_arg0_i) = 0 for i = 1..4
+        let ram_base_addr = trace_eval!(trace_eval, Column::RamBaseAddr);
+        let syscall_arg0 = trace_eval!(trace_eval, SyscallOperandColumns::SyscallArg0);
+        for (addr, arg0) in ram_base_addr.into_iter().zip(syscall_arg0) {
+            eval.add_constraint(is_sys_priv_input_word.clone() * (addr - arg0));
+        }
+    }
+
+    /// Constrains the arguments of the commit syscall.
+    ///
+    /// The guest commits the public output in consecutive chunks, `offset` being the number of bytes committed so
+    /// far, see `execute_commit` in the emulator. Each commit row subtracts `offset` and adds `offset + len` to
+    /// [`CommitLookupElements`], and the first row adds zero and subtracts the committed length of the program
+    /// trace, which the verifier checks against the public output. The logup sum only cancels out when the commits
+    /// chain from zero to the committed length, in any order since lengths are non-negative.
+    ///
+    /// The sum `offset + len` is split into 16-bit halves with a boolean carry. A wrong carry or an overflowing
+    /// sum produces a half out of the 16-bit range, which can't be cancelled by any offset read from a register nor
+    /// by the committed length.
+    fn constrain_commit<E: EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &TraceEval<E>,
+        lookup_elements: &CommitLookupElements,
+        is_type_sys: E::F,
+        is_sys_commit: E::F,
+        offset: &[E::F; WORD_SIZE],
+        len: &[E::F; WORD_SIZE],
+    ) {
+        // Only ECALL rows commit.
+        // (1 - is_type_sys)・is_sys_commit = 0
+        eval.add_constraint((E::F::one() - is_type_sys.clone()) * is_sys_commit.clone());
+
+        // The length is a1 as read by the third register access, which writes it back unchanged.
+        // is_type_sys・is_sys_commit・(a_val_1 + a_val_2 * 256 - reg3_val_prev_1 - reg3_val_prev_2 * 256) = 0
+        // is_type_sys・is_sys_commit・(a_val_3 + a_val_4 * 256 - reg3_val_prev_3 - reg3_val_prev_4 * 256) = 0
+        let reg3_val_prev = trace_eval!(trace_eval, Column::Reg3ValPrev);
+        for (len, prev) in word_halves::<E>(len)
+            .into_iter()
+            .zip(word_halves::<E>(&reg3_val_prev))
+        {
+            eval.add_constraint(is_type_sys.clone() * is_sys_commit.clone() * (len - prev));
+        }
+
+        // The carry is only set on commit rows.
+        // (1 - is_sys_commit)・carry = 0
+        let [carry] = trace_eval!(trace_eval, SyscallColumns::CommitCarry);
+        eval.add_constraint((E::F::one() - is_sys_commit.clone()) * carry.clone());
+
+        let [offset_low, offset_high] = word_halves::<E>(offset);
+        let [len_low, len_high] = word_halves::<E>(len);
+        let end = [
+            offset_low.clone() + len_low - carry.clone() * E::F::from(BaseField::from(1 << 16)),
+            offset_high.clone() + len_high + carry,
+        ];
+        eval.add_to_relation(RelationEntry::new(
+            lookup_elements,
+            (-is_sys_commit.clone()).into(),
+            &[offset_low, offset_high],
+        ));
+        eval.add_to_relation(RelationEntry::new(
+            lookup_elements,
+            is_sys_commit.into(),
+            &end,
+        ));
+
+        let [is_first] = preprocessed_trace_eval!(trace_eval, PreprocessedColumn::IsFirst);
+        let committed_len = program_trace_eval!(trace_eval, ProgramColumn::PrgCommittedOutputLen);
+        eval.add_to_relation(RelationEntry::new(
+            lookup_elements,
+            is_first.clone().into(),
+            &[E::F::zero(), E::F::zero()],
+        ));
+        eval.add_to_relation(RelationEntry::new(
+            lookup_elements,
+            (-is_first).into(),
+            &committed_len,
+        ));
     }
 }
 
 #[cfg(test)]
 mod test {
+    use std::panic::{self, AssertUnwindSafe};
+
     use crate::{
         chips::{
             AddChip, CpuChip, DecodingCheckChip, ProgramMemCheckChip, RangeCheckChip,
-            RegisterMemCheckChip,
+            RegisterMemCheckChip, TimestampChip,
         },
         test_utils::assert_chip,
         trace::{
-            program::iter_program_steps, program_trace::ProgramTracesBuilder, PreprocessedTraces,
+            program::iter_program_steps,
+            program_trace::{ProgramTraceRef, ProgramTracesBuilder},
+            PreprocessedTraces,
         },
     };
+    use stwo::core::fields::qm31::SecureField;
 
     use super::*;
     use nexus_vm::{
@@ -232,6 +639,13 @@ mod test {
             // Heap reset syscall (0x403)
             Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, SyscallCode::OverwriteHeapPointer as u32),
             Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+            // Commit syscall (0x406), commits zero bytes at offset zero
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 11, 0, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, SyscallCode::Commit as u32),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+            // Cycle counter syscall (0x409)
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, SyscallCode::ReadCycleCounter as u32),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
             // End with Halt syscall (0x201)
             Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, SyscallCode::Exit as u32),
             Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
@@ -280,4 +694,265 @@ mod test {
         }
         assert_chip::<Chips>(traces, Some(program_traces.finalize()));
     }
+
+    #[test]
+    fn test_syscall_operands_bound_to_registers() {
+        type Chips = (
+            CpuChip,
+            DecodingCheckChip,
+            SyscallChip,
+            AddChip,
+            RegisterMemCheckChip,
+            ProgramMemCheckChip,
+            RangeCheckChip,
+        );
+        let basic_block = setup_basic_block_ir();
+        let (view, vm_traces) = k_trace_direct(&basic_block, 1).expect("Failed to create trace");
+
+        let mut traces = TracesBuilder::new(LOG_SIZE);
+        let program_traces =
+            ProgramTracesBuilder::new_with_empty_memory(LOG_SIZE, view.get_program_memory());
+        let mut side_note = SideNote::new(&program_traces, &view);
+        let program_steps: Vec<_> = iter_program_steps(&vm_traces, traces.num_rows()).collect();
+        for (row_idx, program_step) in program_steps.iter().enumerate() {
+            Chips::fill_main_trace(
+                &mut traces,
+                row_idx,
+                program_step,
+                &mut side_note,
+                &ExtensionsConfig::default(),
+            );
+        }
+        let program_trace = program_traces.finalize();
+
+        let row_idx = program_steps
+            .iter()
+            .position(|step| {
+                step.as_ref().and_then(|step| step.get_syscall_code())
+                    == Some(SyscallCode::Write as u32)
+            })
+            .expect("program writes");
+        let step = program_steps[row_idx].as_ref().unwrap();
+        let [reg2_address] = traces.column(row_idx, Column::Reg2Address);
+        assert_eq!(reg2_address, BaseField::from(SYSCALL_ARG0_REG as u32));
+        let arg0: [BaseField; 4] = traces.column(row_idx, SyscallOperandColumns::SyscallArg0);
+        assert_eq!(
+            arg0,
+            step.get_syscall_arg0()
+                .to_le_bytes()
+                .map(|b| BaseField::from(b as u32))
+        );
+
+        let rejected = |traces: TracesBuilder| {
+            let program_trace = program_trace.clone();
+            panic::catch_unwind(AssertUnwindSafe(|| {
+                assert_chip::<Chips>(traces, Some(program_trace))
+            }))
+            .is_err()
+        };
+
+        // Claim a commit instead of the write held by a7, both are no-ops for the rest of the circuit.
+        let mut claimed = traces.clone();
+        claimed.fill_columns(
+            row_idx,
+            (SyscallCode::Commit as u32).to_le_bytes(),
+            SyscallOperandColumns::SyscallNumber,
+        );
+        claimed.fill_columns(row_idx, false, SyscallColumns::IsSysDebug);
+        claimed.fill_columns(row_idx, true, SyscallColumns::IsSysCommit);
+        assert!(rejected(claimed));
+
+        // Claim a first argument other than the value of a0.
+        let mut claimed = traces;
+        claimed.fill_columns(
+            row_idx,
+            (step.get_syscall_arg0() + 1).to_le_bytes(),
+            SyscallOperandColumns::SyscallArg0,
+        );
+        assert!(rejected(claimed));
+    }
+
+    #[test]
+    fn commits_chain_to_committed_length() {
+        type Chips = (
+            CpuChip,
+            DecodingCheckChip,
+            SyscallChip,
+            AddChip,
+            RegisterMemCheckChip,
+            ProgramMemCheckChip,
+            RangeCheckChip,
+        );
+        let commit = |offset, len| {
+            [
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, offset),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 11, 0, len),
+                Instruction::new_ir(
+                    Opcode::from(BuiltinOpcode::ADDI),
+                    17,
+                    0,
+                    SyscallCode::Commit as u32,
+                ),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+            ]
+        };
+        let basic_block = vec![BasicBlock::new([commit(0, 4), commit(4, 8)].concat())];
+        let (view, vm_traces) = k_trace_direct(&basic_block, 1).expect("Failed to create trace");
+        assert_eq!(view.view_committed_output_len(), Some(12));
+
+        let mut traces = TracesBuilder::new(LOG_SIZE);
+        let program_traces =
+            ProgramTracesBuilder::new_with_empty_memory(LOG_SIZE, view.get_program_memory());
+        let mut side_note = SideNote::new(&program_traces, &view);
+        let program_steps: Vec<_> = iter_program_steps(&vm_traces, traces.num_rows()).collect();
+        for (row_idx, program_step) in program_steps.iter().enumerate() {
+            Chips::fill_main_trace(
+                &mut traces,
+                row_idx,
+                program_step,
+                &mut side_note,
+                &ExtensionsConfig::default(),
+            );
+        }
+        let commit_rows: Vec<usize> = program_steps
+            .iter()
+            .enumerate()
+            .filter(|(_, step)| {
+                step.as_ref().and_then(|step| step.get_syscall_code())
+                    == Some(SyscallCode::Commit as u32)
+            })
+            .map(|(row_idx, _)| row_idx)
+            .collect();
+        assert_eq!(commit_rows.len(), 2);
+
+        // The commit relation is only used by this chip, its sum alone cancels out.
+        let claimed_sum = |traces: TracesBuilder, committed_output_len| {
+            let program_trace = ProgramTracesBuilder::new(
+                LOG_SIZE,
+                ProgramTraceRef {
+                    committed_output_len,
+                    ..ProgramTraceRef::new_with_empty_memory(view.get_program_memory())
+                },
+            )
+            .finalize();
+            assert_chip::<SyscallChip>(traces, Some(program_trace)).1
+        };
+        assert_eq!(claimed_sum(traces.clone(), 12), SecureField::zero());
+        for committed_output_len in [0, 4, 8, 16] {
+            assert_ne!(
+                claimed_sum(traces.clone(), committed_output_len),
+                SecureField::zero(),
+                "commits accepted for {committed_output_len} bytes"
+            );
+        }
+
+        // A wrong carry moves the end of the first commit out of the 16-bit range of its low half.
+        let mut tampered = traces.clone();
+        tampered.fill_columns(commit_rows[0], true, SyscallColumns::CommitCarry);
+        assert_ne!(claimed_sum(tampered, 12), SecureField::zero());
+
+        // The length of a commit is the value of a1.
+        let mut tampered = traces;
+        tampered.fill_columns(commit_rows[1], 4u32.to_le_bytes(), Column::ValueA);
+        let rejected = panic::catch_unwind(AssertUnwindSafe(|| claimed_sum(tampered, 8)));
+        assert!(rejected.is_err());
+    }
+
+    #[test]
+    fn syscall_flags_are_one_hot_in_own_group() {
+        let basic_block = setup_basic_block_ir();
+        let (view, vm_traces) = k_trace_direct(&basic_block, 1).expect("Failed to create trace");
+
+        let mut traces = TracesBuilder::new(LOG_SIZE);
+        let program_traces =
+            ProgramTracesBuilder::new_with_empty_memory(LOG_SIZE, view.get_program_memory());
+        let mut side_note = SideNote::new(&program_traces, &view);
+        let program_steps: Vec<_> = iter_program_steps(&vm_traces, traces.num_rows()).collect();
+        for (row_idx, program_step) in program_steps.iter().enumerate() {
+            <(CpuChip, SyscallChip, AddChip)>::fill_main_trace(
+                &mut traces,
+                row_idx,
+                program_step,
+                &mut side_note,
+                &ExtensionsConfig::default(),
+            );
+        }
+
+        for (row_idx, program_step) in program_steps.iter().enumerate() {
+            let expected = program_step
+                .as_ref()
+                .and_then(|step| step.get_syscall_code())
+                .map(|code| match SyscallCode::try_from(code).unwrap() {
+                    SyscallCode::Write => SyscallColumns::IsSysDebug,
+                    SyscallCode::Exit => SyscallColumns::IsSysHalt,
+                    SyscallCode::ReadFromPrivateInput => SyscallColumns::IsSysPrivInput,
+                    SyscallCode::OverwriteStackPointer => SyscallColumns::IsSysStackReset,
+                    SyscallCode::OverwriteHeapPointer => SyscallColumns::IsSysHeapReset,
+                    SyscallCode::Commit => SyscallColumns::IsSysCommit,
+                    SyscallCode::ReadCycleCounter => SyscallColumns::IsSysReadCycleCounter,
+                    _ => panic!("unexpected syscall {code:#x}"),
+                });
+            for &col in SyscallColumns::ALL_VARIANTS {
+                let [value] = traces.column(row_idx, col);
+                assert_eq!(
+                    value,
+                    BaseField::from(u32::from(Some(col) == expected)),
+                    "row {row_idx}, {col:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_cycle_counter_bound_to_clock() {
+        let basic_block = setup_basic_block_ir();
+        let (view, vm_traces) = k_trace_direct(&basic_block, 1).expect("Failed to create trace");
+
+        let mut traces = TracesBuilder::new(LOG_SIZE);
+        let program_traces =
+            ProgramTracesBuilder::new_with_empty_memory(LOG_SIZE, view.get_program_memory());
+        let mut side_note = SideNote::new(&program_traces, &view);
+        let program_steps: Vec<_> = iter_program_steps(&vm_traces, traces.num_rows()).collect();
+        for (row_idx, program_step) in program_steps.iter().enumerate() {
+            <(CpuChip, DecodingCheckChip, SyscallChip, TimestampChip)>::fill_main_trace(
+                &mut traces,
+                row_idx,
+                program_step,
+                &mut side_note,
+                &ExtensionsConfig::default(),
+            );
+        }
+        let program_trace = program_traces.finalize();
+
+        let row_idx = program_steps
+            .iter()
+            .position(|step| {
+                step.as_ref().and_then(|step| step.get_syscall_code())
+                    == Some(SyscallCode::ReadCycleCounter as u32)
+            })
+            .expect("program reads the cycle counter");
+        assert_eq!(
+            program_steps[row_idx].as_ref().unwrap().get_result(),
+            Some((row_idx as u32 + 1).to_le_bytes())
+        );
+        assert_chip::<(CpuChip, DecodingCheckChip, SyscallChip, TimestampChip)>(
+            traces.clone(),
+            Some(program_trace.clone()),
+        );
+
+        // Report one cycle less to the guest.
+        fill_word_limbs(
+            &mut traces,
+            row_idx,
+            (row_idx as u32).to_le_bytes(),
+            Column::ValueA,
+        );
+        let rejected = panic::catch_unwind(AssertUnwindSafe(|| {
+            assert_chip::<(CpuChip, DecodingCheckChip, SyscallChip, TimestampChip)>(
+                traces,
+                Some(program_trace),
+            )
+        }));
+        assert!(rejected.is_err());
+    }
 }
@@ -22,6 +22,12 @@ use super::{
 pub struct DivRemChip;
 
 impl MachineChip for DivRemChip {
+    const NAME: &'static str = "DivRemChip";
+
+    fn supported_opcodes() -> &'static [BuiltinOpcode] {
+        &[BuiltinOpcode::DIV, BuiltinOpcode::REM]
+    }
+
     fn fill_main_trace(
         traces: &mut crate::trace::TracesBuilder,
         row_idx: usize,
@@ -19,6 +19,12 @@ use super::{
 pub struct DivuRemuChip;
 
 impl MachineChip for DivuRemuChip {
+    const NAME: &'static str = "DivuRemuChip";
+
+    fn supported_opcodes() -> &'static [BuiltinOpcode] {
+        &[BuiltinOpcode::DIVU, BuiltinOpcode::REMU]
+    }
+
     fn fill_main_trace(
         traces: &mut crate::trace::TracesBuilder,
         row_idx: usize,
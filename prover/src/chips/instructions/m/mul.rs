@@ -16,6 +16,12 @@ use super::{gadget::constrain_mul_partial_product, nexani::mull_limb};
 pub struct MulChip;
 
 impl MachineChip for MulChip {
+    const NAME: &'static str = "MulChip";
+
+    fn supported_opcodes() -> &'static [BuiltinOpcode] {
+        &[BuiltinOpcode::MUL]
+    }
+
     fn fill_main_trace(
         traces: &mut crate::trace::TracesBuilder,
         row_idx: usize,
@@ -18,6 +18,12 @@ use crate::{
 pub struct MulhMulhsuChip;
 
 impl MachineChip for MulhMulhsuChip {
+    const NAME: &'static str = "MulhMulhsuChip";
+
+    fn supported_opcodes() -> &'static [BuiltinOpcode] {
+        &[BuiltinOpcode::MULH, BuiltinOpcode::MULHSU]
+    }
+
     fn fill_main_trace(
         traces: &mut crate::trace::TracesBuilder,
         row_idx: usize,
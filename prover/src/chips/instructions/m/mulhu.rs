@@ -15,6 +15,12 @@ use crate::{
 pub struct MulhuChip;
 
 impl MachineChip for MulhuChip {
+    const NAME: &'static str = "MulhuChip";
+
+    fn supported_opcodes() -> &'static [BuiltinOpcode] {
+        &[BuiltinOpcode::MULHU]
+    }
+
     fn fill_main_trace(
         traces: &mut crate::trace::TracesBuilder,
         row_idx: usize,
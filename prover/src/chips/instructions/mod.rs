@@ -2,11 +2,17 @@ pub(crate) mod i;
 
 pub use i::{
     add_with_carries, subtract_with_borrow, AddChip, AuipcChip, BeqChip, BgeChip, BgeuChip, BitOp,
-    BitOpChip, BitOpLookupElements, BltChip, BltuChip, BneChip, JalChip, JalrChip, LoadStoreChip,
-    LoadStoreLookupElements, LuiChip, SllChip, SltChip, SltuChip, SraChip, SrlChip, SubChip,
-    SyscallChip,
+    BitOpChip, BitOpLookupElements, BltChip, BltuChip, BneChip, CommitLookupElements, JalChip,
+    JalrChip, LoadStoreChip, LoadStoreLookupElements, LuiChip, SllChip, SltChip, SltuChip, SraChip,
+    SraColumns, SrlChip, SubChip, SyscallChip, SyscallColumns, SyscallOperandColumns,
 };
+pub(crate) use i::{SYSCALL_ARG0_REG, SYSCALL_ARG1_REG, SYSCALL_NUMBER_REG};
+
+pub(crate) mod fused_li;
+pub use fused_li::FusedLiChip;
 
 pub(crate) mod m;
 pub use m::{DivRemChip, DivuRemuChip, MulChip, MulhMulhsuChip, MulhuChip};
 pub type MExtensionChips = (DivRemChip, DivuRemuChip, MulChip, MulhMulhsuChip, MulhuChip);
+
+mod word_cmp;
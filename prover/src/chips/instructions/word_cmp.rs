@@ -0,0 +1,264 @@
+//! Comparison of two words, shared by the branch and set-less-than chips.
+//!
+//! `lhs < rhs` is the last borrow of the subtraction `lhs - rhs` over 16-bit limbs. Signed words are compared by
+//! subtracting their sign-flipped values, see [`subtract_signed_with_borrow`]. The gadget fills and constrains the
+//! following columns, comparison chips are mutually exclusive per row so they all share them:
+//!
+//! - `Helper1`: bytes of the difference, range-checked in `Range256Chip`.
+//! - `BorrowFlag`: borrows at 16-bit boundaries, the second one is the comparison result.
+//! - `SgnA`, `SgnB`: sign bits of `lhs` and `rhs`, for signed comparisons only. These are the signs of the
+//!   compared operands, which are not necessarily `ValueA` and `ValueB`.
+//! - `Helper2`, `Helper3`: `lhs` and `rhs` with the sign bit cleared, for signed comparisons only. Their top bytes
+//!   are range-checked in `Range128Chip`, which pins down the sign bits.
+
+use stwo::core::fields::FieldExpOps;
+use stwo_constraint_framework::EvalAtRow;
+
+use nexus_vm::WORD_SIZE;
+
+use super::i::{subtract_signed_with_borrow, subtract_with_borrow};
+use crate::{
+    chips::word_decomp::fill_word_limbs,
+    column::Column::{self, BorrowFlag, Helper1, Helper2, Helper3, SgnA, SgnB},
+    trace::{
+        eval::{trace_eval, TraceEval},
+        TracesBuilder, Word,
+    },
+};
+
+/// Result of comparing two words, together with the helper values filled into the trace.
+pub struct Comparison {
+    pub diff_bytes: Word,
+    pub borrow_bits: [bool; 2], // At 16-bit boundaries.
+    /// Operands with the sign bit cleared, set for signed comparisons.
+    unsigned_operands: Option<[Word; 2]>,
+    sign_bits: [bool; 2],
+}
+
+impl Comparison {
+    /// Whether `lhs < rhs`.
+    pub fn lt(&self) -> bool {
+        self.borrow_bits[1]
+    }
+}
+
+/// Compares `lhs` with `rhs`, as signed integers if `signed` is set.
+pub(super) fn compare(lhs: Word, rhs: Word, signed: bool) -> Comparison {
+    let (diff_bytes, borrow_bits) = if signed {
+        subtract_signed_with_borrow(lhs, rhs)
+    } else {
+        subtract_with_borrow(lhs, rhs)
+    };
+    let sign_bits = [lhs, rhs].map(|word| word[WORD_SIZE - 1] >> 7 == 1);
+    let unsigned_operands = signed.then(|| {
+        [lhs, rhs].map(|mut word| {
+            word[WORD_SIZE - 1] &= 0x7f;
+            word
+        })
+    });
+
+    Comparison {
+        diff_bytes,
+        borrow_bits: [borrow_bits[1], borrow_bits[3]],
+        unsigned_operands,
+        sign_bits,
+    }
+}
+
+/// Fills the helper columns of the comparison at `row_idx`.
+pub(super) fn fill_cmp_columns(traces: &mut TracesBuilder, row_idx: usize, cmp: &Comparison) {
+    fill_word_limbs(traces, row_idx, cmp.diff_bytes, Helper1);
+    traces.fill_columns(row_idx, cmp.borrow_bits, BorrowFlag);
+
+    if let Some([h2, h3]) = cmp.unsigned_operands {
+        traces.fill_columns(row_idx, h2, Helper2);
+        traces.fill_columns(row_idx, h3, Helper3);
+        traces.fill_columns(row_idx, cmp.sign_bits[0], SgnA);
+        traces.fill_columns(row_idx, cmp.sign_bits[1], SgnB);
+    }
+}
+
+/// Constrains the helper columns to compare `lhs` with `rhs` on rows where `selector` is set, returning the flag
+/// for `lhs < rhs`.
+///
+/// The flag is boolean as long as `BorrowFlag` is range-checked in `RangeBoolChip`.
+pub(super) fn constrain_cmp<E: EvalAtRow>(
+    eval: &mut E,
+    trace_eval: &TraceEval<E>,
+    selector: E::F,
+    lhs: &[E::F; WORD_SIZE],
+    rhs: &[E::F; WORD_SIZE],
+    signed: bool,
+) -> E::F {
+    let modulus = E::F::from(256u32.into());
+    let diff_bytes = trace_eval!(trace_eval, Helper1);
+    let borrow_bits = trace_eval!(trace_eval, BorrowFlag);
+    let lt_flag = borrow_bits[1].clone();
+
+    // Borrow out of the top limb. For sign-flipped operands, the flipped top byte is
+    // val_4 + 2^7 - sgn・2^8, so the top limb additionally borrows sgn_lhs - sgn_rhs.
+    let mut top_borrow = lt_flag.clone();
+    if signed {
+        let modulus_7 = E::F::from(128u32.into());
+        let [sgn_lhs] = trace_eval!(trace_eval, SgnA);
+        let [sgn_rhs] = trace_eval!(trace_eval, SgnB);
+        let h2 = trace_eval!(trace_eval, Helper2);
+        let h3 = trace_eval!(trace_eval, Helper3);
+
+        // selector・(h2_4 + sgn_lhs・2^7 - lhs_4) = 0
+        eval.add_constraint(
+            selector.clone()
+                * (h2[WORD_SIZE - 1].clone() + sgn_lhs.clone() * modulus_7.clone()
+                    - lhs[WORD_SIZE - 1].clone()),
+        );
+        // selector・(h3_4 + sgn_rhs・2^7 - rhs_4) = 0
+        eval.add_constraint(
+            selector.clone()
+                * (h3[WORD_SIZE - 1].clone() + sgn_rhs.clone() * modulus_7
+                    - rhs[WORD_SIZE - 1].clone()),
+        );
+        top_borrow = top_borrow + sgn_rhs - sgn_lhs;
+    }
+
+    // selector・(lhs_1 + lhs_2 * 256 - rhs_1 - rhs_2 * 256 - h1_1 - h1_2 * 256 + borrow_1・2^{16}) = 0
+    eval.add_constraint(
+        selector.clone()
+            * (lhs[0].clone() + lhs[1].clone() * modulus.clone()
+                - rhs[0].clone()
+                - rhs[1].clone() * modulus.clone()
+                - diff_bytes[0].clone()
+                - diff_bytes[1].clone() * modulus.clone()
+                + borrow_bits[0].clone() * modulus.clone().pow(2)),
+    );
+    // selector・(lhs_3 + lhs_4 * 256 - rhs_3 - rhs_4 * 256 - h1_3 - h1_4 * 256 + top_borrow・2^{16} - borrow_1) = 0
+    eval.add_constraint(
+        selector
+            * (lhs[2].clone() + lhs[3].clone() * modulus.clone()
+                - rhs[2].clone()
+                - rhs[3].clone() * modulus.clone()
+                - diff_bytes[2].clone()
+                - diff_bytes[3].clone() * modulus.clone()
+                + top_borrow * modulus.pow(2)
+                - borrow_bits[0].clone()),
+    );
+
+    lt_flag
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{self, AssertUnwindSafe};
+
+    use num_traits::One;
+    use stwo::core::fields::m31::BaseField;
+
+    use super::*;
+    use crate::{
+        chips::{AddChip, BgeChip, BgeuChip, BltChip, BltuChip, CpuChip, SltChip, SltuChip},
+        extensions::ExtensionsConfig,
+        test_utils::assert_chip,
+        trace::{
+            program::iter_program_steps, program_trace::ProgramTracesBuilder, sidenote::SideNote,
+            PreprocessedTraces,
+        },
+        traits::MachineChip,
+    };
+    use nexus_vm::{
+        emulator::InternalView,
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+        trace::k_trace_direct,
+    };
+
+    const LOG_SIZE: u32 = PreprocessedTraces::MIN_LOG_SIZE;
+
+    #[test]
+    fn compare_words() {
+        let cases: [(u32, u32); 8] = [
+            (0, 0),
+            (1, 2),
+            (2, 1),
+            (0xFFFF_FFFF, 0),
+            (0, 0xFFFF_FFFF),
+            (0x8000_0000, 0x7FFF_FFFF),
+            (0x7FFF_FFFF, 0x8000_0000),
+            (0xFFFF_FFF6, 0xFFFF_FFF6),
+        ];
+        for (lhs, rhs) in cases {
+            let unsigned = compare(lhs.to_le_bytes(), rhs.to_le_bytes(), false);
+            assert_eq!(unsigned.lt(), lhs < rhs, "{lhs:#x} <u {rhs:#x}");
+            let signed = compare(lhs.to_le_bytes(), rhs.to_le_bytes(), true);
+            assert_eq!(
+                signed.lt(),
+                (lhs as i32) < (rhs as i32),
+                "{lhs:#x} <s {rhs:#x}"
+            );
+            assert_eq!(signed.diff_bytes, unsigned.diff_bytes);
+        }
+    }
+
+    /// Fills the main trace for x1 = -1, x2 = 1 followed by `instruction` at row 2.
+    fn fill_traces<C: MachineChip>(instruction: Instruction) -> TracesBuilder {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 0xFFF),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 1),
+            instruction,
+        ])];
+        let (view, vm_traces) = k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        let mut traces = TracesBuilder::new(LOG_SIZE);
+        let program_traces =
+            ProgramTracesBuilder::new_with_empty_memory(LOG_SIZE, view.get_program_memory());
+        let mut side_note = SideNote::new(&program_traces, &view);
+        for (row_idx, program_step) in iter_program_steps(&vm_traces, traces.num_rows()).enumerate()
+        {
+            C::fill_main_trace(
+                &mut traces,
+                row_idx,
+                &program_step,
+                &mut side_note,
+                &ExtensionsConfig::default(),
+            );
+        }
+        traces
+    }
+
+    /// Flips the comparison result of `instruction`, together with `result` that depends on it, and checks that
+    /// chip `C` rejects the trace.
+    fn assert_flipped_comparison_rejected<C: MachineChip>(
+        instruction: Instruction,
+        result: Option<Column>,
+    ) {
+        let mut traces = fill_traces::<(CpuChip, AddChip, C)>(instruction);
+        assert_chip::<C>(traces.clone(), None);
+
+        let [_, lt_flag] = traces.column_mut::<2>(2, BorrowFlag);
+        *lt_flag = BaseField::one() - *lt_flag;
+        if let Some(result) = result {
+            let [result, ..] = traces.column_mut::<WORD_SIZE>(2, result);
+            *result = BaseField::one() - *result;
+        }
+
+        let rejected = panic::catch_unwind(AssertUnwindSafe(|| assert_chip::<C>(traces, None)));
+        assert!(rejected.is_err());
+    }
+
+    #[test]
+    fn flipped_comparison_rejected() {
+        // Branching by 4 leads to the same pc either way, only the comparison constrains the flag.
+        let branch = |opcode| Instruction::new_ir(Opcode::from(opcode), 1, 2, 4);
+        assert_flipped_comparison_rejected::<BltuChip>(branch(BuiltinOpcode::BLTU), None);
+        assert_flipped_comparison_rejected::<BgeuChip>(branch(BuiltinOpcode::BGEU), None);
+        assert_flipped_comparison_rejected::<BltChip>(branch(BuiltinOpcode::BLT), None);
+        assert_flipped_comparison_rejected::<BgeChip>(branch(BuiltinOpcode::BGE), None);
+
+        let set = |opcode| Instruction::new_ir(Opcode::from(opcode), 3, 1, 2);
+        assert_flipped_comparison_rejected::<SltuChip>(
+            set(BuiltinOpcode::SLTU),
+            Some(Column::ValueA),
+        );
+        assert_flipped_comparison_rejected::<SltChip>(
+            set(BuiltinOpcode::SLT),
+            Some(Column::ValueA),
+        );
+    }
+}
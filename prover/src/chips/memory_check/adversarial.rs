@@ -0,0 +1,306 @@
+//! Adversarial tests of register and read-write memory checking.
+//!
+//! Each attack rewrites the previous value or timestamp of some accesses in an honestly filled main trace, the way a
+//! malicious prover could, and recomputes the timestamp comparison helpers so that the attack isn't caught by stale
+//! auxiliary columns alone. The interaction trace is then generated from the tampered trace. Every attack must either
+//! break a constraint of [`RegisterMemCheckChip`](super::RegisterMemCheckChip),
+//! [`LoadStoreChip`](crate::chips::LoadStoreChip) or [`TimestampChip`](super::TimestampChip), or leave logup sums
+//! that don't cancel out. An attack that passes both is a soundness bug.
+//!
+//! Clock attacks rewrite the clock of the rows around a syscall taking several cycles, whose memory accesses have
+//! distinct sub-timestamps, so that two accesses share a timestamp or the clock doesn't account for the cycles.
+//!
+//! Proving a tampered trace must fail as well. The prover refuses logup sums that don't cancel out before producing
+//! a proof, which the verifier would reject for the same reason.
+
+use nexus_vm::{
+    emulator::{Emulator, HarvardEmulator, View},
+    riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+    trace::{k_trace_direct, k_trace_harvard, UniformTrace},
+    SyscallCode, WORD_SIZE,
+};
+
+use super::decr_subtract_with_borrow;
+use crate::{
+    chips::{CpuColumns, SyscallColumns},
+    column::Column::{
+        self, CH1Minus, CH2Minus, CH3Minus, CReg1TsPrev, CReg2TsPrev, CReg3TsPrev, Clk, Helper1,
+        Helper2, Helper3, Helper4, Ram1TsPrev, Ram1TsPrevAux, Ram1ValPrev, Ram2TsPrev,
+        Ram2TsPrevAux, Ram3TsPrev, Ram3TsPrevAux, Ram4TsPrev, Ram4TsPrevAux, RamSubTsCarry,
+        Reg1TsPrev, Reg2TsPrev, Reg3TsPrev, Reg3ValPrev,
+    },
+    error::TraceCheckError,
+    machine::{BaseComponent, Machine, ProverContext},
+    trace::{utils::FromBaseFields, TracesBuilder},
+};
+
+/// `x1 = 8`, `x3 = 0x55`, stores `x3` at `x1`, loads it twice into `x4` and `x5` and adds them.
+fn program() -> Vec<BasicBlock> {
+    vec![BasicBlock::new(vec![
+        Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 8),
+        Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 3, 0, 0x55),
+        Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 1, 3, 0),
+        Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 4, 1, 0),
+        Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 5, 1, 0),
+        Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 6, 4, 5),
+    ])]
+}
+
+const WRITE_X1_ROW: usize = 0;
+const STORE_ROW: usize = 2;
+/// Both loads read `x1` in register access slot 1, and the stored word.
+const FIRST_LOAD_ROW: usize = 3;
+const SECOND_LOAD_ROW: usize = 4;
+const LAST_ROW: usize = 5;
+
+/// `a0 = 8`, reads a word of the private input at `a0` in four cycles, loads it into `x11` and doubles it.
+fn syscall_program() -> Vec<BasicBlock> {
+    vec![BasicBlock::new(vec![
+        Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 8),
+        Instruction::new_ir(
+            Opcode::from(BuiltinOpcode::ADDI),
+            17,
+            0,
+            SyscallCode::ReadPrivateInputWord as u32,
+        ),
+        Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+        Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 11, 0, 8),
+        Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 12, 11, 11),
+    ])]
+}
+
+/// Writes the four bytes at sub-timestamps `clk..clk + 4`.
+const SYSCALL_ROW: usize = 2;
+/// Reads the four bytes written by the syscall.
+const SYSCALL_LOAD_ROW: usize = 3;
+
+/// Previous timestamp, comparison result and borrows of each register access slot, see
+/// [`TimestampChip`](super::TimestampChip).
+const REG_TS_PREV: [(Column, Column, Column); 3] = [
+    (Reg1TsPrev, CReg1TsPrev, CH1Minus),
+    (Reg2TsPrev, CReg2TsPrev, CH2Minus),
+    (Reg3TsPrev, CReg3TsPrev, CH3Minus),
+];
+
+/// Previous timestamp, comparison result and borrows of each accessed byte of read-write memory.
+const RAM_TS_PREV: [(Column, Column, Column); 4] = [
+    (Ram1TsPrev, Ram1TsPrevAux, Helper1),
+    (Ram2TsPrev, Ram2TsPrevAux, Helper2),
+    (Ram3TsPrev, Ram3TsPrevAux, Helper3),
+    (Ram4TsPrev, Ram4TsPrevAux, Helper4),
+];
+
+fn ts_prev(traces: &TracesBuilder, row: usize, col: Column) -> u32 {
+    u32::from_base_fields(traces.column(row, col))
+}
+
+/// Sets the previous timestamp of register access `slot`, filling the comparison with the current timestamp like
+/// the prover would, except that the order of the timestamps isn't asserted.
+fn set_reg_ts_prev(traces: &mut TracesBuilder, row: usize, slot: usize, ts_prev: u32) {
+    let (ts_col, diff_col, borrow_col) = REG_TS_PREV[slot];
+    let ts_cur = (row as u32 + 1) * 3 + slot as u32 + 1;
+    let (diff, borrow) = decr_subtract_with_borrow(ts_cur.to_le_bytes(), ts_prev.to_le_bytes());
+    traces.fill_columns(row, ts_prev, ts_col);
+    traces.fill_columns(row, diff, diff_col);
+    traces.fill_columns(row, [borrow[1], borrow[3]], borrow_col);
+}
+
+/// Sets the previous timestamp of every accessed byte of read-write memory, see [`set_reg_ts_prev`].
+fn set_ram_ts_prev(traces: &mut TracesBuilder, row: usize, ts_prev: u32) {
+    set_ram_ts_prevs(traces, row, [ts_prev; WORD_SIZE]);
+}
+
+/// Sets the previous timestamps of the accessed bytes of read-write memory, compared with the sub-timestamp of each
+/// byte, see [`set_reg_ts_prev`].
+fn set_ram_ts_prevs(traces: &mut TracesBuilder, row: usize, ts_prevs: [u32; WORD_SIZE]) {
+    let clk = clk(traces, row);
+    let [is_sys_priv_input_word] = traces.column(row, SyscallColumns::IsSysPrivInputWord);
+    for (i, ((ts_col, diff_col, borrow_col), ts_prev)) in
+        RAM_TS_PREV.into_iter().zip(ts_prevs).enumerate()
+    {
+        let sub_ts = clk + i as u32 * is_sys_priv_input_word.0;
+        let (diff, borrow) = decr_subtract_with_borrow(sub_ts.to_le_bytes(), ts_prev.to_le_bytes());
+        traces.fill_columns(row, ts_prev, ts_col);
+        traces.fill_columns(row, diff, diff_col);
+        traces.fill_columns(row, borrow, borrow_col);
+    }
+}
+
+fn clk(traces: &TracesBuilder, row: usize) -> u32 {
+    u32::from_base_fields(traces.column(row, Clk))
+}
+
+/// Sets the clock of `row` and of every row after it as if the row took `cycles` cycles, and links the load after the
+/// syscall to the accesses of the syscall again.
+fn set_cycles(traces: &mut TracesBuilder, row: usize, cycles: u32) {
+    let clk_after = clk(traces, row) + cycles;
+    let shift = clk(traces, row + 1) - clk_after;
+    for later in row + 1..traces.num_rows() {
+        let clk = clk(traces, later) - shift;
+        traces.fill_columns(later, clk, Clk);
+    }
+    let ts_prevs = RAM_TS_PREV.map(|(ts_col, _, _)| ts_prev(traces, SYSCALL_LOAD_ROW, ts_col));
+    set_ram_ts_prevs(traces, SYSCALL_LOAD_ROW, ts_prevs);
+}
+
+struct Attack {
+    name: &'static str,
+    tamper: fn(&mut TracesBuilder),
+}
+
+const ATTACKS: &[Attack] = &[
+    Attack {
+        name: "second register read replays the access of the first",
+        tamper: |traces| {
+            let replayed = ts_prev(traces, FIRST_LOAD_ROW, Reg1TsPrev);
+            set_reg_ts_prev(traces, SECOND_LOAD_ROW, 0, replayed);
+        },
+    },
+    Attack {
+        name: "register reads swap their previous accesses",
+        tamper: |traces| {
+            let first = ts_prev(traces, FIRST_LOAD_ROW, Reg1TsPrev);
+            let second = ts_prev(traces, SECOND_LOAD_ROW, Reg1TsPrev);
+            set_reg_ts_prev(traces, FIRST_LOAD_ROW, 0, second);
+            set_reg_ts_prev(traces, SECOND_LOAD_ROW, 0, first);
+        },
+    },
+    Attack {
+        name: "register read links to a future access",
+        tamper: |traces| {
+            let future = (LAST_ROW as u32 + 1) * 3 + 1;
+            set_reg_ts_prev(traces, FIRST_LOAD_ROW, 0, future);
+        },
+    },
+    Attack {
+        name: "register write claims a wrong previous value",
+        tamper: |traces| {
+            traces.fill_columns(WRITE_X1_ROW, 7u32, Reg3ValPrev);
+        },
+    },
+    Attack {
+        name: "second load replays the access of the first",
+        tamper: |traces| {
+            let replayed = ts_prev(traces, FIRST_LOAD_ROW, Ram1TsPrev);
+            set_ram_ts_prev(traces, SECOND_LOAD_ROW, replayed);
+        },
+    },
+    Attack {
+        name: "loads swap their previous accesses",
+        tamper: |traces| {
+            let first = ts_prev(traces, FIRST_LOAD_ROW, Ram1TsPrev);
+            let second = ts_prev(traces, SECOND_LOAD_ROW, Ram1TsPrev);
+            set_ram_ts_prev(traces, FIRST_LOAD_ROW, second);
+            set_ram_ts_prev(traces, SECOND_LOAD_ROW, first);
+        },
+    },
+    Attack {
+        name: "load links to a future access",
+        tamper: |traces| set_ram_ts_prev(traces, FIRST_LOAD_ROW, LAST_ROW as u32 + 1),
+    },
+    Attack {
+        name: "store claims a wrong previous value",
+        tamper: |traces| traces.fill_columns(STORE_ROW, 1u8, Ram1ValPrev),
+    },
+];
+
+const CLOCK_ATTACKS: &[Attack] = &[
+    Attack {
+        name: "clock skips the cycles of the syscall",
+        tamper: |traces| set_cycles(traces, SYSCALL_ROW, 1),
+    },
+    Attack {
+        name: "syscall claims a single cycle",
+        tamper: |traces| {
+            set_cycles(traces, SYSCALL_ROW, 1);
+            traces.fill_columns(SYSCALL_ROW, 1u8, CpuColumns::Cycles);
+        },
+    },
+    Attack {
+        name: "load reuses the last sub-timestamp of the syscall",
+        tamper: |traces| set_cycles(traces, SYSCALL_ROW, WORD_SIZE as u32 - 1),
+    },
+    Attack {
+        name: "syscall byte carries into the high half of its sub-timestamp",
+        tamper: |traces| traces.fill_columns(SYSCALL_ROW, [true, false, false], RamSubTsCarry),
+    },
+    Attack {
+        name: "load after the syscall claims the previous sub-timestamps",
+        tamper: |traces| set_ram_ts_prev(traces, SYSCALL_LOAD_ROW, clk(traces, SYSCALL_ROW)),
+    },
+];
+
+fn execute() -> (View, UniformTrace) {
+    k_trace_direct(&program(), 1).expect("Failed to create trace")
+}
+
+fn execute_syscall() -> (View, UniformTrace) {
+    let mut harvard = HarvardEmulator::from_basic_blocks(&syscall_program());
+    harvard.set_private_input(&[1, 2, 3, 4]);
+    k_trace_harvard(&mut harvard, 1).expect("Failed to create trace")
+}
+
+/// Checks the constraints and logup sums of the tampered traces.
+fn check(
+    execute: fn() -> (View, UniformTrace),
+    tamper: impl FnOnce(&mut TracesBuilder),
+) -> Result<(), TraceCheckError> {
+    let (view, vm_traces) = execute();
+    let mut bundle = Machine::<BaseComponent>::build_traces(&[], &vm_traces, &view).unwrap();
+    bundle.tamper_main_trace(tamper);
+    Machine::<BaseComponent>::check_bundle(bundle)
+}
+
+/// Proves the tampered traces and verifies the proof, if the prover produces one.
+fn prove_and_verify(
+    execute: fn() -> (View, UniformTrace),
+    tamper: impl FnOnce(&mut TracesBuilder),
+) -> bool {
+    let (view, vm_traces) = execute();
+    let mut bundle = Machine::<BaseComponent>::build_traces(&[], &vm_traces, &view).unwrap();
+    bundle.tamper_main_trace(tamper);
+    let context = ProverContext::new(bundle.max_log_size());
+    let Ok(proof) = Machine::<BaseComponent>::commit_and_interact(bundle, &context)
+        .and_then(Machine::<BaseComponent>::finish_proof)
+    else {
+        return false;
+    };
+    crate::verify(proof, &view).is_ok()
+}
+
+fn assert_rejected(execute: fn() -> (View, UniformTrace), attacks: &[Attack]) {
+    for attack in attacks {
+        match check(execute, attack.tamper) {
+            Err(
+                TraceCheckError::ConstraintNotSatisfied { .. }
+                | TraceCheckError::LogupNotSatisfied { .. }
+                | TraceCheckError::LogupSumNotZero,
+            ) => {}
+            Err(err) => panic!("{}: unexpected error {err}", attack.name),
+            Ok(()) => panic!("{}: passes memory checking", attack.name),
+        }
+        assert!(
+            !prove_and_verify(execute, attack.tamper),
+            "{}: proof is accepted",
+            attack.name
+        );
+    }
+}
+
+#[test]
+fn untampered_trace_is_accepted() {
+    for execute in [execute as fn() -> _, execute_syscall] {
+        check(execute, |_| {}).unwrap();
+        assert!(prove_and_verify(execute, |_| {}));
+    }
+}
+
+#[test]
+fn memory_checking_attacks_are_rejected() {
+    assert_rejected(execute, ATTACKS);
+}
+
+#[test]
+fn clock_attacks_are_rejected() {
+    assert_rejected(execute_syscall, CLOCK_ATTACKS);
+}
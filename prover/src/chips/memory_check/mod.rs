@@ -1,3 +1,5 @@
+#[cfg(test)]
+mod adversarial;
 pub(crate) mod program_mem_check;
 pub(crate) mod register_mem_check;
 mod timestamp;
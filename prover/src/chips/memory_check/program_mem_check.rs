@@ -6,14 +6,16 @@ use stwo::{
     core::fields::m31::BaseField,
     prover::backend::simd::m31::{PackedBaseField, LOG_N_LANES},
 };
-use stwo_constraint_framework::{EvalAtRow, LogupTraceGenerator, Relation, RelationEntry};
+use stwo_constraint_framework::{EvalAtRow, Relation, RelationEntry};
 
 use crate::{
+    chips::instructions::add_with_carries,
     column::{Column, PreprocessedColumn, ProgramColumn},
     components::AllLookupElements,
     extensions::ExtensionsConfig,
     trace::{
         eval::{preprocessed_trace_eval, program_trace_eval, trace_eval, TraceEval},
+        logup::LogupTraceBuilder,
         program_trace::ProgramTraces,
         sidenote::SideNote,
         utils::FromBaseFields,
@@ -31,6 +33,8 @@ const LOOKUP_TUPLE_SIZE: usize = 2 * WORD_SIZE_HALVED + WORD_SIZE;
 stwo_constraint_framework::relation!(ProgramCheckLookupElements, LOOKUP_TUPLE_SIZE);
 
 impl MachineChip for ProgramMemCheckChip {
+    const NAME: &'static str = "ProgramMemCheckChip";
+
     fn draw_lookup_elements(
         all_elements: &mut AllLookupElements,
         channel: &mut impl stwo::core::channel::Channel,
@@ -39,7 +43,7 @@ impl MachineChip for ProgramMemCheckChip {
         all_elements.insert(ProgramCheckLookupElements::draw(channel));
     }
 
-    /// Fills `ProgPrevCtr` columns
+    /// Fills `ProgPrevCtr` columns, and their `Fused` counterparts for the ADDI of fused rows
     ///
     /// Assumes other chips have written to `Pc` on the current row
     fn fill_main_trace(
@@ -49,41 +53,38 @@ impl MachineChip for ProgramMemCheckChip {
         side_note: &mut SideNote,
         _config: &ExtensionsConfig,
     ) {
-        if let Some(_vm_step) = vm_step {
+        if let Some(vm_step) = vm_step {
             // not padding
             let pc = traces.column(row_idx, Column::Pc);
             let pc = u32::from_base_fields(pc);
-            let last_access_counter = side_note
-                .program_mem_check
-                .last_access_counter
-                .get(&pc)
-                .unwrap_or(&0u32);
-            traces.fill_columns(row_idx, *last_access_counter, Column::ProgCtrPrev);
-            let new_access_counter = last_access_counter
-                .checked_add(1)
-                .expect("access counter overflow");
-            traces.fill_columns(row_idx, new_access_counter, Column::ProgCtrCur);
-            // Compute and fill carry flags
-            let last_counter_bytes = last_access_counter.to_le_bytes();
-            let mut carry_bits = [false; WORD_SIZE];
-            let mut incremented_bytes = [0u8; WORD_SIZE];
-            (incremented_bytes[0], carry_bits[0]) = last_counter_bytes[0].overflowing_add(1);
-            for i in 1..WORD_SIZE {
-                // Add the bytes and the previous carry
-                (incremented_bytes[i], carry_bits[i]) =
-                    last_counter_bytes[i].overflowing_add(carry_bits[i - 1] as u8);
-            }
-            assert!(!carry_bits[WORD_SIZE - 1]); // Check against overflow
-            assert_eq!(u32::from_le_bytes(incremented_bytes), new_access_counter);
-            traces.fill_columns(
+            Self::fill_access(
+                traces,
                 row_idx,
-                [carry_bits[1], carry_bits[3]],
-                Column::ProgCtrCarry,
+                pc,
+                side_note,
+                [
+                    Column::ProgCtrPrev,
+                    Column::ProgCtrCur,
+                    Column::ProgCtrCarry,
+                ],
             );
-            side_note
-                .program_mem_check
-                .last_access_counter
-                .insert(pc, new_access_counter);
+            if vm_step.step.fused.is_some() {
+                // The ADDI of a fused row is read at Pc + 4
+                let (_, pc_carry) =
+                    add_with_carries(pc.to_le_bytes(), (WORD_SIZE as u32).to_le_bytes());
+                traces.fill_columns(row_idx, pc_carry[1], Column::FusedPcCarry);
+                Self::fill_access(
+                    traces,
+                    row_idx,
+                    pc + WORD_SIZE as u32,
+                    side_note,
+                    [
+                        Column::FusedProgCtrPrev,
+                        Column::FusedProgCtrCur,
+                        Column::FusedProgCtrCarry,
+                    ],
+                );
+            }
         }
         // Use accessed_program_memory sidenote to fill in the final program memory contents
         if row_idx == traces.num_rows() - 1 {
@@ -114,8 +115,10 @@ impl MachineChip for ProgramMemCheckChip {
     /// For the final content of the program memory:
     /// * 1 / lookup_element.combine(tuple) is subtracted for each instruction
     /// where tuples contain (the address, the whole word of the instruction, final counter value).
+    ///
+    /// Fused rows access the program memory twice, the second access is at Pc + 4.
     fn fill_interaction_trace(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         _preprocessed_trace: &PreprocessedTraces,
         program_trace: &ProgramTraces,
@@ -147,6 +150,22 @@ impl MachineChip for ProgramMemCheckChip {
         // add program memory access, new counter write backs
         // For each access, a tuple of the form (address, instruction_as_word, new_counter) is added.
         Self::add_access(logup_trace_gen, original_traces, lookup_element);
+
+        // same for the ADDI of fused rows, at Pc + 4
+        Self::fused_access(
+            logup_trace_gen,
+            original_traces,
+            lookup_element,
+            Column::FusedProgCtrPrev,
+            true,
+        );
+        Self::fused_access(
+            logup_trace_gen,
+            original_traces,
+            lookup_element,
+            Column::FusedProgCtrCur,
+            false,
+        );
     }
 
     fn add_constraints<E: EvalAtRow>(
@@ -168,14 +187,118 @@ impl MachineChip for ProgramMemCheckChip {
 
         // Constrain PrgCurCtr = PrgPrevCtr + 1
         let [is_padding] = trace_eval.column_eval(Column::IsPadding);
-        let prg_prev_ctr = trace_eval.column_eval::<WORD_SIZE>(Column::ProgCtrPrev);
-        let prg_cur_ctr = trace_eval.column_eval::<WORD_SIZE>(Column::ProgCtrCur);
-        let prg_ctr_carry = trace_eval.column_eval::<WORD_SIZE_HALVED>(Column::ProgCtrCarry);
+        Self::constrain_increment(
+            eval,
+            trace_eval,
+            E::F::one() - is_padding,
+            [
+                Column::ProgCtrPrev,
+                Column::ProgCtrCur,
+                Column::ProgCtrCarry,
+            ],
+        );
+        // Same for the ADDI of fused rows
+        let [is_fused_li] = trace_eval!(trace_eval, Column::IsFusedLi);
+        Self::constrain_increment(
+            eval,
+            trace_eval,
+            is_fused_li,
+            [
+                Column::FusedProgCtrPrev,
+                Column::FusedProgCtrCur,
+                Column::FusedProgCtrCarry,
+            ],
+        );
+
+        // Logup constraints
+
+        // add initial digest
+        // For each used Pc, one tuple (address, instruction_as_word, 0u32) is added.
+        Self::constrain_add_initial_digest(eval, trace_eval, lookup_elements);
+
+        // subtract final digest
+        // For each used Pc, one tuple (address, instruction_as_word, final_counter) is subtracted.
+        Self::constrain_subtract_final_digest(eval, trace_eval, lookup_elements);
+
+        // subtract program memory access, previous counter reads
+        // For each access, one tuple (address, instruction_as_word, previous_counter) is subtracted.
+        Self::constrain_subtract_access(eval, trace_eval, lookup_elements);
+
+        // add program memory access, new counter write backs
+        // For each access, one tuple (address, instruction_as_word, new_counter) is added.
+        Self::constrain_add_access(eval, trace_eval, lookup_elements);
+
+        // same for the ADDI of fused rows, at Pc + 4
+        Self::constrain_fused_access(
+            eval,
+            trace_eval,
+            lookup_elements,
+            Column::FusedProgCtrPrev,
+            true,
+        );
+        Self::constrain_fused_access(
+            eval,
+            trace_eval,
+            lookup_elements,
+            Column::FusedProgCtrCur,
+            false,
+        );
+    }
+}
+
+impl ProgramMemCheckChip {
+    /// Fills the previous and current access counters of `pc`, together with the carries of the increment.
+    fn fill_access(
+        traces: &mut TracesBuilder,
+        row_idx: usize,
+        pc: u32,
+        side_note: &mut SideNote,
+        [ctr_prev, ctr_cur, ctr_carry]: [Column; 3],
+    ) {
+        let last_access_counter = side_note
+            .program_mem_check
+            .last_access_counter
+            .get(&pc)
+            .unwrap_or(&0u32);
+        traces.fill_columns(row_idx, *last_access_counter, ctr_prev);
+        let new_access_counter = last_access_counter
+            .checked_add(1)
+            .expect("access counter overflow");
+        traces.fill_columns(row_idx, new_access_counter, ctr_cur);
+        // Compute and fill carry flags
+        let last_counter_bytes = last_access_counter.to_le_bytes();
+        let mut carry_bits = [false; WORD_SIZE];
+        let mut incremented_bytes = [0u8; WORD_SIZE];
+        (incremented_bytes[0], carry_bits[0]) = last_counter_bytes[0].overflowing_add(1);
+        for i in 1..WORD_SIZE {
+            // Add the bytes and the previous carry
+            (incremented_bytes[i], carry_bits[i]) =
+                last_counter_bytes[i].overflowing_add(carry_bits[i - 1] as u8);
+        }
+        assert!(!carry_bits[WORD_SIZE - 1]); // Check against overflow
+        assert_eq!(u32::from_le_bytes(incremented_bytes), new_access_counter);
+        traces.fill_columns(row_idx, [carry_bits[1], carry_bits[3]], ctr_carry);
+        side_note
+            .program_mem_check
+            .last_access_counter
+            .insert(pc, new_access_counter);
+    }
+
+    /// Constrains the current counter to be the previous counter plus one on rows where `selector` is set.
+    fn constrain_increment<E: EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &TraceEval<E>,
+        selector: E::F,
+        [ctr_prev, ctr_cur, ctr_carry]: [Column; 3],
+    ) {
+        let prg_prev_ctr = trace_eval.column_eval::<WORD_SIZE>(ctr_prev);
+        let prg_cur_ctr = trace_eval.column_eval::<WORD_SIZE>(ctr_cur);
+        let prg_ctr_carry = trace_eval.column_eval::<WORD_SIZE_HALVED>(ctr_carry);
         let modulus = E::F::from((1u32 << 8).into());
 
         // prg_cur_ctr[0] + prg_cur_ctr[1] * 256 + prg_ctr_carry[0] * 2^{16} = (prg_prev_ctr[0] + prg_prev_ctr[1] * 256) + 1
         eval.add_constraint(
-            (E::F::one() - is_padding.clone())
+            selector.clone()
                 * (prg_cur_ctr[0].clone()
                     + prg_cur_ctr[1].clone() * modulus.clone()
                     + prg_ctr_carry[0].clone() * E::F::from(BaseField::from(1 << 16))
@@ -186,7 +309,7 @@ impl MachineChip for ProgramMemCheckChip {
 
         // prg_cur_ctr[2] + prg_cur_ctr[3] * 256 + prg_ctr_carry[1] * 2^{16} = prg_prev_ctr[2] + prg_prev_ctr[3] * 256 + prg_ctr_carry[0]
         eval.add_constraint(
-            (E::F::one() - is_padding.clone())
+            selector
                 * (prg_cur_ctr[2].clone()
                     + prg_cur_ctr[3].clone() * modulus.clone()
                     + prg_ctr_carry[1].clone() * E::F::from(BaseField::from(1 << 16))
@@ -197,27 +320,8 @@ impl MachineChip for ProgramMemCheckChip {
 
         // Don't allow overflow
         eval.add_constraint(prg_ctr_carry[WORD_SIZE_HALVED - 1].clone());
-        // Logup constraints
-
-        // add initial digest
-        // For each used Pc, one tuple (address, instruction_as_word, 0u32) is added.
-        Self::constrain_add_initial_digest(eval, trace_eval, lookup_elements);
-
-        // subtract final digest
-        // For each used Pc, one tuple (address, instruction_as_word, final_counter) is subtracted.
-        Self::constrain_subtract_final_digest(eval, trace_eval, lookup_elements);
-
-        // subtract program memory access, previous counter reads
-        // For each access, one tuple (address, instruction_as_word, previous_counter) is subtracted.
-        Self::constrain_subtract_access(eval, trace_eval, lookup_elements);
-
-        // add program memory access, new counter write backs
-        // For each access, one tuple (address, instruction_as_word, new_counter) is added.
-        Self::constrain_add_access(eval, trace_eval, lookup_elements);
     }
-}
 
-impl ProgramMemCheckChip {
     /// Fills the interaction trace columns for adding the initial content of the program memory:
     /// * 1 / lookup_element.combine(tuple) is added for each instruction
     /// where tuples contain (the address, the whole word of the instruction, 0u32).
@@ -225,7 +329,7 @@ impl ProgramMemCheckChip {
     ///
     /// The initial content of the memory is located on rows where PrgMemoryFlag is 1.
     fn add_initial_digest(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         program_traces: &ProgramTraces,
         lookup_element: &ProgramCheckLookupElements,
@@ -306,7 +410,7 @@ impl ProgramMemCheckChip {
     /// Most columns are the same as the initial program memory content.
     /// The final counter is located on the FinalPrgMemoryCtr column.
     fn subtract_final_digest(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         program_traces: &ProgramTraces,
         lookup_element: &ProgramCheckLookupElements,
@@ -388,7 +492,7 @@ impl ProgramMemCheckChip {
     ///
     /// The numerator is zero on the padding rows, so that the row doesn't contribute to the logup sum.
     fn subtract_access(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         lookup_element: &ProgramCheckLookupElements,
     ) {
@@ -466,7 +570,7 @@ impl ProgramMemCheckChip {
     ///
     /// The numerator is zero when the row is padding, so that the row doesn't contribute to the logup sum.
     fn add_access(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         lookup_element: &ProgramCheckLookupElements,
     ) {
@@ -535,6 +639,92 @@ impl ProgramMemCheckChip {
             &tuple,
         ));
     }
+
+    /// On each fused row, the ADDI at Pc + 4 is accessed as well:
+    /// * 1 / lookup_element.combine(tuple) is subtracted with the previous counter, or added with the current one
+    /// where tuples contain (Pc + 4, the whole word of the ADDI, counter value).
+    ///
+    /// Pc + 4 is split in two halfwords using `FusedPcCarry`. The numerator is zero on rows that aren't fused.
+    fn fused_access(
+        logup_trace_gen: &mut LogupTraceBuilder,
+        original_traces: &FinalizedTraces,
+        lookup_element: &ProgramCheckLookupElements,
+        counter: Column,
+        subtract: bool,
+    ) {
+        let [is_fused_li] = original_traces.get_base_column(Column::IsFusedLi);
+        let [pc_carry] = original_traces.get_base_column(Column::FusedPcCarry);
+        let ctr = original_traces.get_base_column::<WORD_SIZE>(counter);
+        let pc = original_traces.get_base_column::<WORD_SIZE>(Column::Pc);
+        let instruction_word = original_traces.get_base_column::<WORD_SIZE>(Column::FusedInstrVal);
+        let mut logup_col_gen = logup_trace_gen.new_col();
+        let modulo = PackedBaseField::from(BaseField::from(1u32 << 8));
+        let modulo_16 = PackedBaseField::from(BaseField::from(1u32 << 16));
+        let pc_increment = PackedBaseField::from(BaseField::from(WORD_SIZE as u32));
+        for vec_row in 0..(1 << (original_traces.log_size() - LOG_N_LANES)) {
+            let carry = pc_carry.data[vec_row];
+            let mut tuple = vec![
+                pc[0].data[vec_row] + pc[1].data[vec_row] * modulo + pc_increment
+                    - carry * modulo_16,
+                pc[2].data[vec_row] + pc[3].data[vec_row] * modulo + carry,
+            ];
+            assert_eq!(tuple.len(), WORD_SIZE_HALVED);
+            for instruction_byte in instruction_word.chunks(2) {
+                tuple.push(
+                    instruction_byte[0].data[vec_row] + instruction_byte[1].data[vec_row] * modulo,
+                );
+            }
+            assert_eq!(tuple.len(), 2 * WORD_SIZE_HALVED);
+            for ctr_byte in ctr.iter() {
+                tuple.push(ctr_byte.data[vec_row]);
+            }
+            assert_eq!(tuple.len(), LOOKUP_TUPLE_SIZE);
+            let numerator = is_fused_li.data[vec_row];
+            let numerator = if subtract { -numerator } else { numerator };
+            logup_col_gen.write_frac(
+                vec_row,
+                numerator.into(),
+                lookup_element.combine(tuple.as_slice()),
+            );
+        }
+        logup_col_gen.finalize_col();
+    }
+
+    /// Adds logup constraints for the fractions in `fused_access()`
+    ///
+    /// `fused_access()` and `constrain_fused_access()` must be in sync, the same way with all stwo logup usage.
+    fn constrain_fused_access<E: EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &TraceEval<E>,
+        lookup_elements: &ProgramCheckLookupElements,
+        counter: Column,
+        subtract: bool,
+    ) {
+        let [is_fused_li] = trace_eval!(trace_eval, Column::IsFusedLi);
+        let [pc_carry] = trace_eval!(trace_eval, Column::FusedPcCarry);
+        let ctr = trace_eval.column_eval::<WORD_SIZE>(counter);
+        let pc = trace_eval!(trace_eval, Column::Pc);
+        let instruction_word = trace_eval!(trace_eval, Column::FusedInstrVal);
+        let modulo = E::F::from((1u32 << 8).into());
+        let mut tuple = vec![
+            pc[0].clone() + pc[1].clone() * modulo.clone() + E::F::from((WORD_SIZE as u32).into())
+                - pc_carry.clone() * E::F::from((1u32 << 16).into()),
+            pc[2].clone() + pc[3].clone() * modulo.clone() + pc_carry,
+        ];
+        assert_eq!(tuple.len(), WORD_SIZE_HALVED);
+        for instruction_byte in instruction_word.chunks(2) {
+            tuple.push(instruction_byte[0].clone() + instruction_byte[1].clone() * modulo.clone());
+        }
+        assert_eq!(tuple.len(), 2 * WORD_SIZE_HALVED);
+        tuple.extend(ctr);
+        assert_eq!(tuple.len(), LOOKUP_TUPLE_SIZE);
+        let numerator = if subtract { -is_fused_li } else { is_fused_li };
+        eval.add_to_relation(RelationEntry::new(
+            lookup_elements,
+            numerator.into(),
+            &tuple,
+        ));
+    }
 }
 #[cfg(test)]
 mod test {
@@ -1,6 +1,6 @@
 use num_traits::Zero;
 use stwo::{core::fields::m31::BaseField, prover::backend::simd::m31::LOG_N_LANES};
-use stwo_constraint_framework::{EvalAtRow, LogupTraceGenerator, Relation, RelationEntry};
+use stwo_constraint_framework::{EvalAtRow, Relation, RelationEntry};
 
 use crate::{
     column::{
@@ -12,9 +12,11 @@ use crate::{
         PreprocessedColumn,
     },
     components::AllLookupElements,
+    description::{ChipDescription, ConstraintDescription, LookupDescription},
     extensions::ExtensionsConfig,
     trace::{
         eval::{trace_eval, TraceEval},
+        logup::LogupTraceBuilder,
         program_trace::ProgramTraces,
         regs::AccessResult,
         sidenote::SideNote,
@@ -22,7 +24,7 @@ use crate::{
         FinalizedTraces, PreprocessedTraces, ProgramStep, TracesBuilder,
     },
     traits::MachineChip,
-    virtual_column::{self, IsTypeR, OpBFlag, Reg3Accessed, VirtualColumn},
+    virtual_column::{self, OpBFlag, Reg2Accessed, Reg3Accessed, VirtualColumn},
 };
 use nexus_vm::WORD_SIZE;
 
@@ -39,6 +41,8 @@ impl RegisterMemCheckChip {
 stwo_constraint_framework::relation!(RegisterCheckLookupElements, LOOKUP_TUPLE_SIZE);
 
 impl MachineChip for RegisterMemCheckChip {
+    const NAME: &'static str = "RegisterMemCheckChip";
+
     fn draw_lookup_elements(
         all_elements: &mut AllLookupElements,
         channel: &mut impl stwo::core::channel::Channel,
@@ -69,7 +73,7 @@ impl MachineChip for RegisterMemCheckChip {
 
         // Read inputs to the chip
         let reg1_accessed = virtual_column::OpBFlag::read_from_traces_builder(traces, row_idx);
-        let reg2_accessed = virtual_column::IsTypeR::read_from_traces_builder(traces, row_idx);
+        let reg2_accessed = virtual_column::Reg2Accessed::read_from_traces_builder(traces, row_idx);
         let reg3_accessed: [BaseField; 1] =
             virtual_column::Reg3Accessed::read_from_traces_builder(traces, row_idx);
         let reg1_address: [BaseField; 1] = traces.column(row_idx, Reg1Address);
@@ -146,7 +150,7 @@ impl MachineChip for RegisterMemCheckChip {
             Reg1TsPrev,
             Reg1ValPrev,
         );
-        let [reg2_accessed] = virtual_column::IsTypeR::eval(trace_eval);
+        let [reg2_accessed] = virtual_column::Reg2Accessed::eval(trace_eval);
         Self::constrain_subtract_prev_reg(
             eval,
             trace_eval,
@@ -226,7 +230,7 @@ impl MachineChip for RegisterMemCheckChip {
     }
 
     fn fill_interaction_trace(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         preprocessed_trace: &PreprocessedTraces,
         _program_trace: &ProgramTraces,
@@ -245,7 +249,7 @@ impl MachineChip for RegisterMemCheckChip {
             Reg1TsPrev,
             Reg1ValPrev,
         );
-        Self::subtract_prev_reg::<IsTypeR>(
+        Self::subtract_prev_reg::<Reg2Accessed>(
             logup_trace_gen,
             original_traces,
             lookup_element,
@@ -272,7 +276,7 @@ impl MachineChip for RegisterMemCheckChip {
             PreprocessedColumn::Reg1TsCur,
             ValueB,
         );
-        Self::add_cur_reg::<IsTypeR>(
+        Self::add_cur_reg::<Reg2Accessed>(
             logup_trace_gen,
             original_traces,
             preprocessed_trace,
@@ -291,11 +295,68 @@ impl MachineChip for RegisterMemCheckChip {
             ValueAEffective,
         );
     }
+
+    fn describe() -> Option<ChipDescription> {
+        const TUPLE: &[&str] = &[
+            "register index",
+            "timestamp byte 0",
+            "timestamp byte 1",
+            "timestamp byte 2",
+            "timestamp byte 3",
+            "value byte 0",
+            "value byte 1",
+            "value byte 2",
+            "value byte 3",
+        ];
+        Some(ChipDescription {
+            chip: "RegisterMemCheckChip",
+            constraints: &[
+                ConstraintDescription {
+                    id: "value_a_effective",
+                    description: "ValueAEffective is ValueA, or zero when the destination is x0. Per byte.",
+                    columns: &["ValueAEffective", "ValueA", "ValueAEffectiveFlag"],
+                    degree: 2,
+                    count: WORD_SIZE,
+                },
+                ConstraintDescription {
+                    id: "alu_reg1_unchanged",
+                    description: "ALU instructions read ValueB from the first register without changing it. Per byte.",
+                    columns: &["OpBFlag", "IsAlu", "ValueB", "Reg1ValPrev"],
+                    degree: 3,
+                    count: WORD_SIZE,
+                },
+                ConstraintDescription {
+                    id: "alu_reg2_unchanged",
+                    description: "Type R ALU instructions read ValueC from the second register without changing it. \
+                        Per byte.",
+                    columns: &["IsTypeR", "IsAlu", "ValueC", "Reg2ValPrev"],
+                    degree: 4,
+                    count: WORD_SIZE,
+                },
+            ],
+            lookups: &[
+                LookupDescription {
+                    relation: "RegisterCheckLookupElements",
+                    tuple: TUPLE,
+                    description: "Subtracts the previous access of Reg1, Reg2 and Reg3, with multiplicity one when \
+                        the register is accessed.",
+                    count: 3,
+                },
+                LookupDescription {
+                    relation: "RegisterCheckLookupElements",
+                    tuple: TUPLE,
+                    description: "Adds the current access of Reg1, Reg2 and Reg3 with the current timestamp, with \
+                        multiplicity one when the register is accessed.",
+                    count: 3,
+                },
+            ],
+        })
+    }
 }
 
 impl RegisterMemCheckChip {
     fn subtract_prev_reg<AccessFlag: VirtualColumn<1>>(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         lookup_element: &RegisterCheckLookupElements,
         reg_address: Column,
@@ -346,7 +407,7 @@ impl RegisterMemCheckChip {
     }
 
     fn add_cur_reg<AccessFlag: VirtualColumn<1>>(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         preprocessed_trace: &PreprocessedTraces,
         lookup_element: &RegisterCheckLookupElements,
@@ -507,6 +568,8 @@ mod test {
             init_memory: Default::default(),
             exit_code: Default::default(),
             public_output: Default::default(),
+            num_steps: 0,
+            committed_output_len: 0,
         };
         let program_traces = ProgramTracesBuilder::new(LOG_SIZE, program_trace_ref);
         let mut side_note = super::SideNote::new(&program_traces, &view);
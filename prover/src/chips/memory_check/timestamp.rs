@@ -8,18 +8,19 @@ use nexus_common::constants::WORD_SIZE_HALVED;
 use nexus_vm::WORD_SIZE;
 
 use crate::{
-    chips::subtract_with_borrow,
+    chips::{subtract_with_borrow, CpuColumns},
     column::{
         Column::{
-            self, CH1Minus, CH2Minus, CH3Minus, CReg1TsPrev, CReg2TsPrev, CReg3TsPrev, Reg1TsPrev,
-            Reg2TsPrev, Reg3TsPrev,
+            self, CH1Minus, CH2Minus, CH3Minus, CReg1TsPrev, CReg2TsPrev, CReg3TsPrev, Clk,
+            ClkCarry, IsPadding, Reg1TsPrev, Reg2TsPrev, Reg3TsPrev,
         },
         PreprocessedColumn,
     },
     components::AllLookupElements,
     extensions::ExtensionsConfig,
     trace::{
-        eval::{preprocessed_trace_eval, trace_eval, TraceEval},
+        eval::{preprocessed_trace_eval, trace_eval, trace_eval_next_row, TraceEval},
+        preprocessed::PreprocessedBuilder,
         sidenote::SideNote,
         utils::FromBaseFields,
         BoolWord, ProgramStep, TracesBuilder, Word,
@@ -29,16 +30,29 @@ use crate::{
 
 /// This chip adds constraints that the previous timestamp is smaller than the current timestamp
 /// This Chip needs to fill the main trace after RegisterMemCheckChip
+///
+/// Padding rows don't access registers and keep previous timestamps at zero. The current timestamps are derived from
+/// the preprocessed `RowCounter`, which is `row_idx + 1` on padding rows as well, so the inequalities hold without a
+/// selector.
+///
+/// The chip also constrains the clock of the rows, `Clk`: it starts at one and advances by `Cycles` on every row,
+/// by one on padding rows. A row taking `k` cycles owns the timestamps `clk..clk + k`, which RAM accesses of the row
+/// use as sub-timestamps, see [`LoadStoreChip`](crate::chips::LoadStoreChip). This chip needs to fill the main trace
+/// after CpuChip, and the rows in order.
 pub struct TimestampChip;
 
 impl MachineChip for TimestampChip {
+    const NAME: &'static str = "TimestampChip";
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
-        _step: &Option<ProgramStep>,
+        step: &Option<ProgramStep>,
         _side_note: &mut SideNote,
         _config: &ExtensionsConfig,
     ) {
+        Self::fill_clock(traces, row_idx, step);
+
         let clk: u32 = row_idx as u32 + 1;
         let reg1_ts_cur = clk * 3 + 1;
         let reg2_ts_cur = clk * 3 + 2;
@@ -76,6 +90,8 @@ impl MachineChip for TimestampChip {
         _lookup_elements: &AllLookupElements,
         _config: &ExtensionsConfig,
     ) {
+        Self::constrain_clock(eval, trace_eval);
+
         let ch1_minus = trace_eval!(trace_eval, CH1Minus);
         let ch2_minus = trace_eval!(trace_eval, CH2Minus);
         let ch3_minus = trace_eval!(trace_eval, CH3Minus);
@@ -103,6 +119,76 @@ impl MachineChip for TimestampChip {
     }
 }
 
+impl TimestampChip {
+    /// Fills the clock of the row from the clock and the cycles of the previous row, and the carry of advancing it
+    /// to the next row.
+    fn fill_clock(traces: &mut TracesBuilder, row_idx: usize, step: &Option<ProgramStep>) {
+        let clk = match row_idx.checked_sub(1) {
+            None => PreprocessedBuilder::FIRST_CLK,
+            Some(prev_row) => {
+                let prev_clk: [_; WORD_SIZE] = traces.column(prev_row, Clk);
+                let [prev_cycles] = traces.column(prev_row, CpuColumns::Cycles);
+                let [prev_is_padding] = traces.column(prev_row, IsPadding);
+                u32::from_base_fields(prev_clk) + prev_cycles.0 + prev_is_padding.0
+            }
+        };
+        if let Some(step) = step {
+            // Sanity check: the emulator advanced its clock by the same cycles
+            assert_eq!(step.step.timestamp, clk, "clock mismatch on row {row_idx}");
+        }
+        traces.fill_columns(row_idx, clk, Clk);
+
+        let [cycles] = traces.column(row_idx, CpuColumns::Cycles);
+        let [is_padding] = traces.column(row_idx, IsPadding);
+        let carry = (clk & 0xFFFF) + cycles.0 + is_padding.0 > 0xFFFF;
+        traces.fill_columns(row_idx, carry, ClkCarry);
+    }
+
+    /// Constrains the clock to start at one and advance by the cycles of every row, one on padding rows.
+    ///
+    /// The clock bytes are range checked, and the carry is boolean, so the low halves determine the carry and the
+    /// next clock can't wrap around.
+    fn constrain_clock<E: EvalAtRow>(eval: &mut E, trace_eval: &TraceEval<E>) {
+        let [is_first] = preprocessed_trace_eval!(trace_eval, PreprocessedColumn::IsFirst);
+        let [is_last] = preprocessed_trace_eval!(trace_eval, PreprocessedColumn::IsLast);
+        let clk = trace_eval!(trace_eval, Clk);
+        let next_clk = trace_eval_next_row!(trace_eval, Clk);
+        let [clk_carry] = trace_eval!(trace_eval, ClkCarry);
+        let [cycles] = trace_eval!(trace_eval, CpuColumns::Cycles);
+        let [is_padding] = trace_eval!(trace_eval, IsPadding);
+        let modulus = BaseField::from(1 << 8);
+
+        // is_first・(clk_1 + clk_2・2^8 - 1) = 0
+        // is_first・(clk_3 + clk_4・2^8) = 0
+        eval.add_constraint(
+            is_first.clone()
+                * (clk[0].clone() + clk[1].clone() * modulus
+                    - E::F::from(BaseField::from(PreprocessedBuilder::FIRST_CLK))),
+        );
+        eval.add_constraint(is_first * (clk[2].clone() + clk[3].clone() * modulus));
+
+        // (1 - is_last)・(next_clk_1 + next_clk_2・2^8 + clk_carry・2^16 - clk_1 - clk_2・2^8 - cycles - is_padding) = 0
+        // (1 - is_last)・(next_clk_3 + next_clk_4・2^8 - clk_3 - clk_4・2^8 - clk_carry) = 0
+        eval.add_constraint(
+            (E::F::one() - is_last.clone())
+                * (next_clk[0].clone()
+                    + next_clk[1].clone() * modulus
+                    + clk_carry.clone() * BaseField::from(1 << 16)
+                    - clk[0].clone()
+                    - clk[1].clone() * modulus
+                    - cycles
+                    - is_padding),
+        );
+        eval.add_constraint(
+            (E::F::one() - is_last)
+                * (next_clk[2].clone() + next_clk[3].clone() * modulus
+                    - clk[2].clone()
+                    - clk[3].clone() * modulus
+                    - clk_carry),
+        );
+    }
+}
+
 /// Performs x - 1 - y, returning the result and the borrow bits
 ///
 /// Note that for - 1 - y, for every limb, just one borrow bit suffices
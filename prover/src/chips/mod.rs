@@ -3,22 +3,31 @@ pub(crate) mod decoding;
 pub(crate) mod instructions;
 pub(crate) mod memory_check;
 pub(crate) mod range_check;
+pub(crate) mod step_count;
+pub(crate) mod word_decomp;
+
+mod component_set;
 
 pub(crate) mod custom;
 
 pub use instructions::{
     add_with_carries, subtract_with_borrow, AddChip, AuipcChip, BeqChip, BgeChip, BgeuChip,
-    BitOpChip, BltChip, BltuChip, BneChip, JalChip, JalrChip, LoadStoreChip, LuiChip, SllChip,
-    SltChip, SltuChip, SraChip, SrlChip, SubChip, SyscallChip,
+    BitOpChip, BltChip, BltuChip, BneChip, FusedLiChip, JalChip, JalrChip, LoadStoreChip, LuiChip,
+    SllChip, SltChip, SltuChip, SraChip, SraColumns, SrlChip, SubChip, SyscallChip, SyscallColumns,
+    SyscallOperandColumns,
 };
+pub(crate) use instructions::{SYSCALL_ARG0_REG, SYSCALL_ARG1_REG, SYSCALL_NUMBER_REG};
 
 // M extension
 pub use instructions::MExtensionChips;
 
-pub use cpu::CpuChip;
+pub use cpu::{CpuChip, CpuColumns};
 pub use custom::CustomInstructionChip;
 pub use decoding::DecodingCheckChip;
 pub use memory_check::{ProgramMemCheckChip, RegisterMemCheckChip, TimestampChip};
 pub use range_check::RangeCheckChip;
+pub use step_count::StepCountChip;
+
+pub use component_set::{ExcludedChips, Excluding, InstructionComponent};
 
 mod utils;
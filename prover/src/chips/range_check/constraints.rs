@@ -0,0 +1,218 @@
+//! Shared helpers for range-constraining expressions.
+//!
+//! Every chip that needs a boolean, a 5-bit, a byte or a 12-bit check on its helper columns should go through
+//! these functions, so that the checks are expressed the same way across the AIR. Lookup-based checks are only
+//! sound if the checked values are also counted in the [`SideNote`](crate::trace::sidenote::SideNote)
+//! multiplicities of the corresponding table, which is the responsibility of
+//! [`Range32Chip`](super::range32::Range32Chip), [`Range256Chip`](super::range256::Range256Chip) and
+//! [`Range4096Chip`](super::range4096::Range4096Chip).
+//!
+//! The decoding chips split immediates into limbs at the bit positions they occupy in the instruction word, and the
+//! shift chips split shift amounts into the bits driving the shift, both of which are needed by other constraints.
+//! The limbs are range-checked individually, the 12-bit immediates of type I instructions are also looked up as
+//! a whole in the range4096 table.
+
+use num_traits::One;
+use stwo::core::fields::qm31::SecureField;
+use stwo_constraint_framework::{EvalAtRow, RelationEntry};
+
+use super::{
+    range256::Range256LookupElements, range32::Range32LookupElements,
+    range4096::Range4096LookupElements,
+};
+
+/// Constrains `expr` to be either zero or one.
+pub(crate) fn constrain_bool<E: EvalAtRow>(eval: &mut E, expr: E::F) {
+    eval.add_constraint(expr.clone() * (expr - E::F::one()));
+}
+
+/// Constrains `expr` to be either zero or one whenever `selector` is non-zero.
+pub(crate) fn constrain_bool_if<E: EvalAtRow>(eval: &mut E, selector: E::F, expr: E::F) {
+    eval.add_constraint(selector * expr.clone() * (expr - E::F::one()));
+}
+
+/// Constrains `expr` to be in `0..=31` by looking it up in the range32 table.
+pub(crate) fn constrain_range32<E: EvalAtRow>(
+    eval: &mut E,
+    lookup_elements: &Range32LookupElements,
+    expr: E::F,
+) {
+    eval.add_to_relation(RelationEntry::new(
+        lookup_elements,
+        SecureField::one().into(),
+        &[expr],
+    ));
+}
+
+/// Constrains `expr` to be in `0..=4095` by looking it up in the range4096 table with the given multiplicity.
+pub(crate) fn constrain_range4096_if<E: EvalAtRow>(
+    eval: &mut E,
+    lookup_elements: &Range4096LookupElements,
+    multiplicity: E::EF,
+    expr: E::F,
+) {
+    eval.add_to_relation(RelationEntry::new(lookup_elements, multiplicity, &[expr]));
+}
+
+/// Constrains `expr` to be in `0..=255` by looking it up in the range256 table.
+pub(crate) fn constrain_byte<E: EvalAtRow>(
+    eval: &mut E,
+    lookup_elements: &Range256LookupElements,
+    expr: E::F,
+) {
+    constrain_byte_if(eval, lookup_elements, SecureField::one().into(), expr);
+}
+
+/// Constrains `expr` to be in `0..=255` by looking it up in the range256 table with the given multiplicity.
+pub(crate) fn constrain_byte_if<E: EvalAtRow>(
+    eval: &mut E,
+    lookup_elements: &Range256LookupElements,
+    multiplicity: E::EF,
+    expr: E::F,
+) {
+    eval.add_to_relation(RelationEntry::new(lookup_elements, multiplicity, &[expr]));
+}
+
+#[cfg(test)]
+mod tests {
+    use num_traits::Zero;
+    use stwo::core::fields::m31::BaseField;
+
+    use super::super::{range256::Range256Chip, range_bool::RangeBoolChip};
+    use crate::{
+        chips::{AddChip, CpuChip, SltuChip, SubChip},
+        column::Column::{CarryFlag, Helper1, ValueA},
+        error::TraceCheckError,
+        extensions::{ExtensionComponent, ExtensionsConfig},
+        test_utils::{check_chip, commit_traces, test_params, CommittedTraces},
+        trace::{
+            program::iter_program_steps,
+            program_trace::{ProgramTraceRef, ProgramTracesBuilder},
+            sidenote::SideNote,
+            PreprocessedTraces, TracesBuilder,
+        },
+        traits::MachineChip,
+    };
+    use nexus_vm::{
+        emulator::InternalView,
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+        trace::k_trace_direct,
+    };
+
+    const LOG_SIZE: u32 = PreprocessedTraces::MIN_LOG_SIZE;
+
+    /// Fills the main trace for a block starting with `ADDI x1, x0, 1` followed by `instruction`, which is placed at row 1.
+    fn fill_traces<C: MachineChip>(instruction: Instruction) -> (TracesBuilder, SideNote) {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            instruction,
+        ])];
+        let (view, vm_traces) = k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        let mut traces = TracesBuilder::new(LOG_SIZE);
+        let program_traces =
+            ProgramTracesBuilder::new_with_empty_memory(LOG_SIZE, view.get_program_memory());
+        let mut side_note = SideNote::new(&program_traces, &view);
+        for (row_idx, program_step) in iter_program_steps(&vm_traces, traces.num_rows()).enumerate()
+        {
+            C::fill_main_trace(
+                &mut traces,
+                row_idx,
+                &program_step,
+                &mut side_note,
+                &ExtensionsConfig::default(),
+            );
+        }
+        (traces, side_note)
+    }
+
+    /// Returns whether range256 lookups made by the main trace are balanced by the multiplicity table.
+    fn range256_logup_balanced(traces: TracesBuilder, side_note: &mut SideNote) -> bool {
+        let (config, twiddles) = test_params(LOG_SIZE);
+        let CommittedTraces {
+            claimed_sum,
+            lookup_elements,
+            ..
+        } = commit_traces::<Range256Chip>(config, &twiddles, &traces.finalize(), None);
+
+        let program_info = nexus_vm::emulator::ProgramInfo::dummy();
+        let program_trace_ref = ProgramTraceRef::new_with_empty_memory(&program_info);
+        let ext = ExtensionComponent::multiplicity256();
+        let component_trace =
+            ext.generate_component_trace(256u32.trailing_zeros(), program_trace_ref, side_note);
+        let (_, table_sum) =
+            ext.generate_interaction_trace(component_trace, side_note, &lookup_elements);
+        (claimed_sum + table_sum).is_zero()
+    }
+
+    #[test]
+    fn sltu_helper1_in_range() {
+        // 1 < 0xFFFFFFFF: helper1 = 1 - 0xFFFFFFFF = 2 with a borrow
+        let (traces, mut side_note) = fill_traces::<(CpuChip, AddChip, SltuChip, Range256Chip)>(
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLTIU), 2, 1, 0xFFF),
+        );
+        assert!(range256_logup_balanced(traces, &mut side_note));
+    }
+
+    #[test]
+    fn sltu_helper1_out_of_range_rejected() {
+        let (mut traces, mut side_note) = fill_traces::<(CpuChip, AddChip, SltuChip, Range256Chip)>(
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLTIU), 2, 1, 0xFFF),
+        );
+        // Shift 256 from limb 2 into limb 3, the arithmetic constraints of SLTU still hold,
+        // but both limbs are no longer bytes.
+        let [_, _, h1_2, h1_3] = traces.column_mut::<4>(1, Helper1);
+        *h1_2 -= BaseField::from(256u32);
+        *h1_3 += BaseField::from(1u32);
+
+        assert!(!range256_logup_balanced(traces, &mut side_note));
+    }
+
+    /// Asserts that `traces` fail the boolean checks of [`RangeBoolChip`] at row 1 and no constraint of `C` before.
+    fn assert_non_boolean_rejected<C: MachineChip>(traces: TracesBuilder) {
+        match check_chip::<C>(traces, None) {
+            Err(TraceCheckError::ConstraintNotSatisfied {
+                chip: "RangeBoolChip",
+                row: 1,
+                ..
+            }) => {}
+            result => panic!("unexpected result: {result:?}"),
+        }
+    }
+
+    #[test]
+    fn add_carry_flag_non_boolean_rejected() {
+        let (mut traces, _) = fill_traces::<(CpuChip, AddChip)>(Instruction::new_ir(
+            Opcode::from(BuiltinOpcode::ADD),
+            2,
+            1,
+            1,
+        ));
+        // Keep the ADD arithmetic satisfied while the first carry is set to 2.
+        let [carry_0, _] = traces.column_mut::<2>(1, CarryFlag);
+        *carry_0 += BaseField::from(2u32);
+        let [a_0, _, a_2, _] = traces.column_mut::<4>(1, ValueA);
+        *a_0 -= BaseField::from(1u32 << 17);
+        *a_2 += BaseField::from(2u32);
+
+        assert_non_boolean_rejected::<(AddChip, RangeBoolChip)>(traces);
+    }
+
+    #[test]
+    fn sub_borrow_flag_non_boolean_rejected() {
+        let (mut traces, _) = fill_traces::<(CpuChip, AddChip, SubChip)>(Instruction::new_ir(
+            Opcode::from(BuiltinOpcode::SUB),
+            2,
+            1,
+            1,
+        ));
+        // SUB reuses the carry flag as borrow, keep the SUB arithmetic satisfied while the first borrow is set to 2.
+        let [borrow_0, _] = traces.column_mut::<2>(1, CarryFlag);
+        *borrow_0 += BaseField::from(2u32);
+        let [a_0, _, a_2, _] = traces.column_mut::<4>(1, ValueA);
+        *a_0 += BaseField::from(1u32 << 17);
+        *a_2 -= BaseField::from(2u32);
+
+        assert_non_boolean_rejected::<(SubChip, RangeBoolChip)>(traces);
+    }
+}
@@ -11,10 +11,12 @@
 //! The current guard is to limit the size of the trace such that `2.pow(trace_log_size) * NUM_CHECKED_COLS < M31::P`
 //! for every chip.
 
+pub(crate) mod constraints;
 pub(crate) mod range128;
 pub(crate) mod range16;
 pub(crate) mod range256;
 pub(crate) mod range32;
+pub(crate) mod range4096;
 pub(crate) mod range8;
 pub(crate) mod range_bool;
 
@@ -24,5 +26,6 @@ pub type RangeCheckChip = (
     range32::Range32Chip,
     range128::Range128Chip,
     range256::Range256Chip,
+    range4096::Range4096Chip,
     range_bool::RangeBoolChip,
 );
@@ -2,7 +2,9 @@
 
 // The target of the 0..127 rangecheck depends on the opcode.
 
-use stwo_constraint_framework::{LogupTraceGenerator, Relation, RelationEntry};
+use std::ops::Range;
+
+use stwo_constraint_framework::{Relation, RelationEntry};
 
 use nexus_vm::WORD_SIZE;
 use num_traits::Zero as _;
@@ -18,8 +20,8 @@ use crate::{
     components::AllLookupElements,
     extensions::ExtensionsConfig,
     trace::{
-        program_trace::ProgramTraces, sidenote::SideNote, FinalizedTraces, PreprocessedTraces,
-        ProgramStep, TracesBuilder,
+        logup::LogupTraceBuilder, program_trace::ProgramTraces, sidenote::SideNote,
+        FinalizedTraces, PreprocessedTraces, ProgramStep, TracesBuilder,
     },
     traits::MachineChip,
 };
@@ -35,6 +37,8 @@ const LOOKUP_TUPLE_SIZE: usize = 1;
 stwo_constraint_framework::relation!(Range128LookupElements, LOOKUP_TUPLE_SIZE);
 
 impl MachineChip for Range128Chip {
+    const NAME: &'static str = "Range128Chip";
+
     fn draw_lookup_elements(
         all_elements: &mut AllLookupElements,
         channel: &mut impl stwo::core::channel::Channel,
@@ -72,11 +76,41 @@ impl MachineChip for Range128Chip {
         let [is_lb] = traces.column(row_idx, Column::IsLb);
         fill_main_col(qt_aux, is_lb, side_note);
     }
+
+    /// Same as [`Self::fill_main_trace`] on each row, reading column slices of the chunk
+    fn fill_main_trace_chunk(
+        traces: &mut TracesBuilder,
+        rows: Range<usize>,
+        _steps: &[Option<ProgramStep>],
+        side_note: &mut SideNote,
+        _config: &ExtensionsConfig,
+    ) {
+        let column = |col| traces.column_chunk(rows.clone(), col, 0);
+        let (is_slt, is_bge, is_blt) = (column(IsSlt), column(IsBge), column(IsBlt));
+        for col in [Helper2, Helper3] {
+            let last_limb = traces.column_chunk(rows.clone(), col, WORD_SIZE - 1);
+            for (i, &value) in last_limb.iter().enumerate() {
+                fill_main_col(value, is_slt[i] + is_bge[i] + is_blt[i], side_note);
+            }
+        }
+        let qt_aux = column(Column::QtAux);
+        let h2_sra = column(Helper2);
+        for (values, selectors) in [
+            (qt_aux, column(Column::IsJalr)),
+            (h2_sra, column(Column::IsSra)),
+            (qt_aux, column(Column::IsLh)),
+            (qt_aux, column(Column::IsLb)),
+        ] {
+            for (&value, &selector) in values.iter().zip(selectors) {
+                fill_main_col(value, selector, side_note);
+            }
+        }
+    }
     /// Fills the whole interaction trace in one-go using SIMD in the stwo-usual way
     ///
     /// data[vec_row] contains sixteen rows. A single write_frac() adds sixteen numbers.
     fn fill_interaction_trace(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         _preprocessed_traces: &PreprocessedTraces,
         _program_traces: &ProgramTraces,
@@ -197,7 +231,7 @@ fn check_col(
     base_column: &BaseColumn,
     selectors: &[&BaseColumn],
     log_size: u32,
-    logup_trace_gen: &mut LogupTraceGenerator,
+    logup_trace_gen: &mut LogupTraceBuilder,
     lookup_element: &Range128LookupElements,
 ) {
     let mut logup_col_gen = logup_trace_gen.new_col();
@@ -276,6 +310,8 @@ mod test {
             init_memory: Default::default(),
             exit_code: Default::default(),
             public_output: Default::default(),
+            num_steps: 0,
+            committed_output_len: 0,
         };
         let program_traces = ProgramTracesBuilder::new(LOG_SIZE, program_trace_ref);
         let mut side_note = SideNote::new(&program_traces, &HarvardEmulator::default().finalize());
@@ -1,7 +1,7 @@
 // This file contains range-checking values for 0..=15.
 
 use nexus_vm::riscv::{BuiltinOpcode, InstructionType};
-use stwo_constraint_framework::{LogupTraceGenerator, Relation, RelationEntry};
+use stwo_constraint_framework::{Relation, RelationEntry};
 
 use num_traits::Zero;
 use stwo::{
@@ -14,8 +14,8 @@ use crate::{
     components::AllLookupElements,
     extensions::ExtensionsConfig,
     trace::{
-        eval::TraceEval, program_trace::ProgramTraces, sidenote::SideNote, FinalizedTraces,
-        PreprocessedTraces, ProgramStep, TracesBuilder,
+        eval::TraceEval, logup::LogupTraceBuilder, program_trace::ProgramTraces,
+        sidenote::SideNote, FinalizedTraces, PreprocessedTraces, ProgramStep, TracesBuilder,
     },
     traits::MachineChip,
     virtual_column::{
@@ -40,6 +40,8 @@ const TYPE_B_CHECKED: [Column; 3] = [OpC1_4, OpA1_4, OpB0_3];
 const TYPE_S_CHECKED: [Column; 3] = [OpC1_4, OpA1_4, OpB0_3];
 
 impl MachineChip for Range16Chip {
+    const NAME: &'static str = "Range16Chip";
+
     fn draw_lookup_elements(
         all_elements: &mut AllLookupElements,
         channel: &mut impl stwo::core::channel::Channel,
@@ -121,7 +123,7 @@ impl MachineChip for Range16Chip {
     ///
     /// data[vec_row] contains sixteen rows. A single write_frac() adds sixteen numbers.
     fn fill_interaction_trace(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         _preprocessed_traces: &PreprocessedTraces,
         _program_traces: &ProgramTraces,
@@ -224,13 +226,13 @@ fn add_constraints_for_type<E: stwo_constraint_framework::EvalAtRow, VR: Virtual
 fn fill_interaction_for_type<VC: VirtualColumn<1>>(
     original_traces: &FinalizedTraces,
     lookup_element: &Range16LookupElements,
-    logup_trace_gen: &mut LogupTraceGenerator,
+    logup_trace_gen: &mut LogupTraceBuilder,
     cols: &[Column],
 ) {
     for col in cols.iter() {
         let [value_basecolumn]: [&BaseColumn; 1] = original_traces.get_base_column(*col);
         let log_size = original_traces.log_size();
-        let logup_trace_gen: &mut LogupTraceGenerator = logup_trace_gen;
+        let logup_trace_gen: &mut LogupTraceBuilder = logup_trace_gen;
         // TODO: we can deal with two limbs at a time.
         let mut logup_col_gen = logup_trace_gen.new_col();
         // vec_row is row_idx divided by 16. Because SIMD.
@@ -383,6 +385,8 @@ mod test {
             init_memory: Default::default(),
             exit_code: Default::default(),
             public_output: Default::default(),
+            num_steps: 0,
+            committed_output_len: 0,
         };
         let program_traces = ProgramTracesBuilder::new(LOG_SIZE, program_trace_ref);
         let mut side_note = SideNote::new(&program_traces, &HarvardEmulator::default().finalize());
@@ -1,6 +1,6 @@
 // This file contains range-checking values for 0..=255.
 
-use stwo_constraint_framework::{LogupTraceGenerator, Relation, RelationEntry};
+use stwo_constraint_framework::Relation;
 
 use nexus_vm::WORD_SIZE;
 use num_traits::{One, Zero};
@@ -9,9 +9,11 @@ use stwo::{
     prover::backend::simd::{column::BaseColumn, m31::LOG_N_LANES},
 };
 
+use super::constraints::{constrain_byte, constrain_byte_if};
 use crate::{
     column::Column::{
-        self, CReg1TsPrev, CReg2TsPrev, CReg3TsPrev, FinalPrgMemoryCtr, Helper1, HelperT, HelperU,
+        self, CReg1TsPrev, CReg2TsPrev, CReg3TsPrev, Clk, FinalPrgMemoryCtr, FusedBase,
+        FusedImmLow, FusedInstrVal, FusedProgCtrCur, FusedProgCtrPrev, Helper1, HelperT, HelperU,
         InstrVal, MulP1, MulP3Prime, MulP3PrimePrime, MulP5, OpC16_23, OpC24_31, Pc, PcNextAux,
         PrevCtr, ProgCtrCur, ProgCtrPrev, Qt, Quotient, Ram1TsPrev, Ram1TsPrevAux, Ram1ValCur,
         Ram1ValPrev, Ram2TsPrev, Ram2TsPrevAux, Ram2ValCur, Ram2ValPrev, Ram3TsPrev, Ram3TsPrevAux,
@@ -22,8 +24,8 @@ use crate::{
     components::AllLookupElements,
     extensions::ExtensionsConfig,
     trace::{
-        eval::TraceEval, program_trace::ProgramTraces, sidenote::SideNote, FinalizedTraces,
-        PreprocessedTraces, ProgramStep, TracesBuilder,
+        eval::TraceEval, logup::LogupTraceBuilder, program_trace::ProgramTraces,
+        sidenote::SideNote, FinalizedTraces, PreprocessedTraces, ProgramStep, TracesBuilder,
     },
     traits::MachineChip,
     virtual_column::{self, VirtualColumn},
@@ -38,8 +40,9 @@ const LOOKUP_TUPLE_SIZE: usize = 1;
 stwo_constraint_framework::relation!(Range256LookupElements, LOOKUP_TUPLE_SIZE);
 
 impl Range256Chip {
-    const CHECKED_WORDS: [Column; 38] = [
+    const CHECKED_WORDS: [Column; 43] = [
         Pc,
+        Clk,
         PcNextAux,
         InstrVal,
         PrevCtr,
@@ -77,9 +80,13 @@ impl Range256Chip {
         ValueAAbs,
         ValueAAbsHigh,
         ValueALow,
+        FusedInstrVal,
+        FusedBase,
+        FusedProgCtrPrev,
+        FusedProgCtrCur,
     ];
 
-    const CHECKED_BYTES: [Column; 8] = [
+    const CHECKED_BYTES: [Column; 9] = [
         Ram1ValCur,
         Ram2ValCur,
         Ram3ValCur,
@@ -88,14 +95,22 @@ impl Range256Chip {
         Ram2ValPrev,
         Ram3ValPrev,
         Ram4ValPrev,
+        FusedImmLow,
     ];
 
     const CHECKED_HALF_WORDS: [Column; 4] = [MulP1, MulP3Prime, MulP3PrimePrime, MulP5];
 
     const TYPE_U_CHECKED_BYTES: [Column; 2] = [OpC16_23, OpC24_31];
+
+    /// Whether every byte of the word column `col` is range-checked.
+    pub(crate) fn is_checked_word(col: Column) -> bool {
+        Self::CHECKED_WORDS.contains(&col)
+    }
 }
 
 impl MachineChip for Range256Chip {
+    const NAME: &'static str = "Range256Chip";
+
     fn draw_lookup_elements(
         all_elements: &mut AllLookupElements,
         channel: &mut impl stwo::core::channel::Channel,
@@ -143,7 +158,7 @@ impl MachineChip for Range256Chip {
     ///
     /// data[vec_row] contains sixteen rows. A single write_frac() adds sixteen numbers.
     fn fill_interaction_trace(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         _preprocessed_traces: &PreprocessedTraces,
         _program_traces: &ProgramTraces,
@@ -216,45 +231,27 @@ impl MachineChip for Range256Chip {
         for col in Self::CHECKED_WORDS.iter() {
             // not using trace_eval! macro because it doesn't accept *col as an argument.
             let value = trace_eval.column_eval::<WORD_SIZE>(*col);
-            for limb in value.into_iter().take(WORD_SIZE) {
-                eval.add_to_relation(RelationEntry::new(
-                    lookup_elements,
-                    SecureField::one().into(),
-                    &[limb],
-                ));
+            for limb in value.into_iter() {
+                constrain_byte(eval, lookup_elements, limb);
             }
         }
 
         for col in Self::CHECKED_HALF_WORDS.iter() {
             let value = trace_eval.column_eval::<2>(*col);
-            for limb in value.into_iter().take(2) {
-                eval.add_to_relation(RelationEntry::new(
-                    lookup_elements,
-                    SecureField::one().into(),
-                    &[limb],
-                ));
+            for limb in value.into_iter() {
+                constrain_byte(eval, lookup_elements, limb);
             }
         }
 
         for col in Self::CHECKED_BYTES.iter() {
             let [value] = trace_eval.column_eval(*col);
-
-            eval.add_to_relation(RelationEntry::new(
-                lookup_elements,
-                SecureField::one().into(),
-                &[value],
-            ));
+            constrain_byte(eval, lookup_elements, value);
         }
 
         for col in Self::TYPE_U_CHECKED_BYTES.iter() {
             let [value] = trace_eval.column_eval(*col);
             let [numerator] = virtual_column::IsTypeU::eval(trace_eval);
-
-            eval.add_to_relation(RelationEntry::new(
-                lookup_elements,
-                numerator.into(),
-                &[value],
-            ));
+            constrain_byte_if(eval, lookup_elements, numerator.into(), value);
         }
     }
 }
@@ -271,7 +268,7 @@ fn fill_main_cols<const N: usize>(value_col: [BaseField; N], side_note: &mut Sid
 fn check_bytes<const N: usize>(
     basecolumn: [&BaseColumn; N],
     log_size: u32,
-    logup_trace_gen: &mut LogupTraceGenerator,
+    logup_trace_gen: &mut LogupTraceBuilder,
     lookup_element: &Range256LookupElements,
 ) {
     // TODO: we can deal with two limbs at a time.
@@ -339,6 +336,8 @@ mod test {
             init_memory: Default::default(),
             exit_code: Default::default(),
             public_output: Default::default(),
+            num_steps: 0,
+            committed_output_len: 0,
         };
         let program_traces = ProgramTracesBuilder::new(LOG_SIZE, program_trace_ref);
         let mut side_note = SideNote::new(&program_traces, &HarvardEmulator::default().finalize());
@@ -1,6 +1,8 @@
 // This file contains range-checking values for 0..=31.
 
-use stwo_constraint_framework::{LogupTraceGenerator, Relation, RelationEntry};
+use std::ops::Range;
+
+use stwo_constraint_framework::Relation;
 
 use num_traits::One;
 use stwo::{
@@ -8,13 +10,14 @@ use stwo::{
     prover::backend::simd::m31::LOG_N_LANES,
 };
 
+use super::constraints::constrain_range32;
 use crate::{
     column::Column::{self, OpA, OpB, Reg1Address, Reg2Address, Reg3Address},
     components::AllLookupElements,
     extensions::ExtensionsConfig,
     trace::{
-        eval::TraceEval, program_trace::ProgramTraces, sidenote::SideNote, FinalizedTraces,
-        PreprocessedTraces, ProgramStep, TracesBuilder,
+        eval::TraceEval, logup::LogupTraceBuilder, program_trace::ProgramTraces,
+        sidenote::SideNote, FinalizedTraces, PreprocessedTraces, ProgramStep, TracesBuilder,
     },
     traits::MachineChip,
 };
@@ -30,6 +33,8 @@ stwo_constraint_framework::relation!(Range32LookupElements, LOOKUP_TUPLE_SIZE);
 const CHECKED: [Column; 5] = [OpA, OpB, Reg1Address, Reg2Address, Reg3Address];
 
 impl MachineChip for Range32Chip {
+    const NAME: &'static str = "Range32Chip";
+
     fn draw_lookup_elements(
         all_elements: &mut AllLookupElements,
         channel: &mut impl stwo::core::channel::Channel,
@@ -51,11 +56,26 @@ impl MachineChip for Range32Chip {
             fill_main_elm(val, side_note);
         }
     }
+
+    /// Increments Multiplicity32 column by column
+    fn fill_main_trace_chunk(
+        traces: &mut TracesBuilder,
+        rows: Range<usize>,
+        _steps: &[Option<ProgramStep>],
+        side_note: &mut SideNote,
+        _config: &ExtensionsConfig,
+    ) {
+        for col in CHECKED.into_iter() {
+            for &val in traces.column_chunk(rows.clone(), col, 0) {
+                fill_main_elm(val, side_note);
+            }
+        }
+    }
     /// Fills the whole interaction trace in one-go using SIMD in the stwo-usual way
     ///
     /// data[vec_row] contains sixteen rows. A single write_frac() adds sixteen numbers.
     fn fill_interaction_trace(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         _preprocessed_traces: &PreprocessedTraces,
         _program_traces: &ProgramTraces,
@@ -92,12 +112,7 @@ impl MachineChip for Range32Chip {
         for col in CHECKED.iter() {
             // not using trace_eval! macro because it doesn't accept *col as an argument.
             let [value] = trace_eval.column_eval(*col);
-
-            eval.add_to_relation(RelationEntry::new(
-                lookup_elements,
-                SecureField::one().into(),
-                &[value],
-            ));
+            constrain_range32(eval, lookup_elements, value);
         }
     }
 }
@@ -158,6 +173,8 @@ mod test {
             init_memory: Default::default(),
             exit_code: Default::default(),
             public_output: Default::default(),
+            num_steps: 0,
+            committed_output_len: 0,
         };
         let program_traces = ProgramTracesBuilder::new(LOG_SIZE, program_trace_ref);
         let mut side_note = SideNote::new(&program_traces, &HarvardEmulator::default().finalize());
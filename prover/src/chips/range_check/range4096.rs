@@ -0,0 +1,222 @@
+// This file contains range-checking values for 0..=4095.
+
+use stwo_constraint_framework::Relation;
+
+use num_traits::Zero;
+use stwo::{core::fields::m31::BaseField, prover::backend::simd::m31::LOG_N_LANES};
+
+use super::constraints::constrain_range4096_if;
+use crate::{
+    column::Column::OpC,
+    components::AllLookupElements,
+    extensions::ExtensionsConfig,
+    trace::{
+        eval::TraceEval, logup::LogupTraceBuilder, program_trace::ProgramTraces,
+        sidenote::SideNote, FinalizedTraces, PreprocessedTraces, ProgramStep, TracesBuilder,
+    },
+    traits::MachineChip,
+    virtual_column::{IsTypeINoShift, VirtualColumn},
+};
+
+/// A Chip for range-checking values for 0..=4095
+///
+/// Checks the 12-bit immediate of type I instructions without a shift amount as a whole, the limbs it is decoded
+/// into are checked by the smaller tables.
+///
+/// Range4096Chip needs to be located at the end of the chip composition together with the other range check chips
+pub struct Range4096Chip;
+
+const LOOKUP_TUPLE_SIZE: usize = 1;
+stwo_constraint_framework::relation!(Range4096LookupElements, LOOKUP_TUPLE_SIZE);
+
+impl MachineChip for Range4096Chip {
+    const NAME: &'static str = "Range4096Chip";
+
+    fn draw_lookup_elements(
+        all_elements: &mut AllLookupElements,
+        channel: &mut impl stwo::core::channel::Channel,
+        _config: &ExtensionsConfig,
+    ) {
+        all_elements.insert(Range4096LookupElements::draw(channel));
+    }
+
+    /// Increments Multiplicity4096 for every immediate checked
+    fn fill_main_trace(
+        traces: &mut TracesBuilder,
+        row_idx: usize,
+        _step: &Option<ProgramStep>,
+        side_note: &mut SideNote,
+        _config: &ExtensionsConfig,
+    ) {
+        let [is_type_i_no_shift] = IsTypeINoShift::read_from_traces_builder(traces, row_idx);
+        if is_type_i_no_shift.is_zero() {
+            return;
+        }
+        let [op_c] = traces.column(row_idx, OpC);
+        fill_main_elm(op_c, side_note);
+    }
+
+    /// Fills the whole interaction trace in one-go using SIMD in the stwo-usual way
+    ///
+    /// data[vec_row] contains sixteen rows. A single write_frac() adds sixteen numbers.
+    fn fill_interaction_trace(
+        logup_trace_gen: &mut LogupTraceBuilder,
+        original_traces: &FinalizedTraces,
+        _preprocessed_traces: &PreprocessedTraces,
+        _program_traces: &ProgramTraces,
+        lookup_element: &AllLookupElements,
+    ) {
+        let lookup_element: &Range4096LookupElements = lookup_element.as_ref();
+        let [op_c] = original_traces.get_base_column(OpC);
+        let log_size = original_traces.log_size();
+
+        let mut logup_col_gen = logup_trace_gen.new_col();
+        // vec_row is row_idx divided by 16. Because SIMD.
+        for vec_row in 0..(1 << (log_size - LOG_N_LANES)) {
+            let denom = lookup_element.combine(&[op_c.data[vec_row]]);
+            let [is_type_i_no_shift] =
+                IsTypeINoShift::read_from_finalized_traces(original_traces, vec_row);
+            logup_col_gen.write_frac(vec_row, is_type_i_no_shift.into(), denom);
+        }
+        logup_col_gen.finalize_col();
+    }
+
+    fn add_constraints<E: stwo_constraint_framework::EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &TraceEval<E>,
+        lookup_elements: &AllLookupElements,
+        _config: &ExtensionsConfig,
+    ) {
+        let lookup_elements: &Range4096LookupElements = lookup_elements.as_ref();
+        let [is_type_i_no_shift] = IsTypeINoShift::eval(trace_eval);
+        let [op_c] = trace_eval.column_eval(OpC);
+        constrain_range4096_if(eval, lookup_elements, is_type_i_no_shift.into(), op_c);
+    }
+}
+
+fn fill_main_elm(col: BaseField, side_note: &mut SideNote) {
+    let checked = col.0;
+    #[cfg(not(test))] // Tests need to go past this assertion and break constraints.
+    assert!(checked < 4096, "value is out of range {}", checked);
+    side_note.range4096.multiplicity[checked as usize] += 1;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::column::Column;
+    use crate::extensions::ExtensionComponent;
+    use crate::test_utils::{assert_chip, commit_traces, test_params, CommittedTraces};
+    use crate::trace::program_trace::{ProgramTraceRef, ProgramTracesBuilder};
+
+    use nexus_vm::emulator::{Emulator, HarvardEmulator, ProgramInfo};
+
+    use stwo::core::fields::{m31::P, qm31::SecureField};
+
+    const LOG_SIZE: u32 = PreprocessedTraces::MIN_LOG_SIZE;
+
+    /// Fills OpC of JALR rows with `values` in turn, and `tampered` into the last row after counting multiplicities.
+    ///
+    /// Returns the sum of the claimed sums of the chip and of the multiplicity table, zero if the lookups balance.
+    fn claimed_sum(values: &[u32], tampered: Option<u32>) -> SecureField {
+        let (config, twiddles) = test_params(LOG_SIZE);
+        let mut traces = TracesBuilder::new(LOG_SIZE);
+        let program_info = ProgramInfo::dummy();
+        let program_trace_ref = ProgramTraceRef {
+            program_memory: &program_info,
+            init_memory: Default::default(),
+            exit_code: Default::default(),
+            public_output: Default::default(),
+            num_steps: 0,
+            committed_output_len: 0,
+        };
+        let program_traces = ProgramTracesBuilder::new(LOG_SIZE, program_trace_ref);
+        let mut side_note = SideNote::new(&program_traces, &HarvardEmulator::default().finalize());
+
+        for row_idx in 0..traces.num_rows() {
+            let value = values[row_idx % values.len()];
+            traces.fill_columns(row_idx, BaseField::from(value), OpC);
+            traces.fill_columns(row_idx, true, Column::IsJalr);
+
+            Range4096Chip::fill_main_trace(
+                &mut traces,
+                row_idx,
+                &Some(ProgramStep::default()),
+                &mut side_note,
+                &ExtensionsConfig::default(),
+            );
+        }
+        if let Some(value) = tampered {
+            let last_row = traces.num_rows() - 1;
+            *traces.column_mut::<{ OpC.size() }>(last_row, OpC)[0] = BaseField::from(value);
+        }
+
+        let CommittedTraces {
+            claimed_sum,
+            lookup_elements,
+            ..
+        } = commit_traces::<Range4096Chip>(config, &twiddles, &traces.finalize(), None);
+
+        let ext = ExtensionComponent::multiplicity4096();
+        let component_trace = ext.generate_component_trace(
+            4096u32.trailing_zeros(),
+            program_trace_ref,
+            &mut side_note,
+        );
+        let (_, table_claimed_sum) =
+            ext.generate_interaction_trace(component_trace, &side_note, &lookup_elements);
+        claimed_sum + table_claimed_sum
+    }
+
+    #[test]
+    fn test_range4096_chip_success() {
+        let mut traces = TracesBuilder::new(LOG_SIZE);
+        let program_traces = ProgramTracesBuilder::dummy(LOG_SIZE);
+        let mut side_note = SideNote::new(&program_traces, &HarvardEmulator::default().finalize());
+
+        for row_idx in 0..traces.num_rows() {
+            let value = (row_idx * 97 % 4096) as u32;
+            traces.fill_columns(row_idx, BaseField::from(value), OpC);
+            traces.fill_columns(row_idx, true, Column::IsJalr);
+
+            Range4096Chip::fill_main_trace(
+                &mut traces,
+                row_idx,
+                &Some(ProgramStep::default()),
+                &mut side_note,
+                &ExtensionsConfig::default(),
+            );
+        }
+        assert_chip::<Range4096Chip>(traces, None);
+    }
+
+    #[test]
+    fn test_range4096_chip_boundaries() {
+        assert_eq!(claimed_sum(&[0, 4095], None), SecureField::zero());
+        assert_ne!(claimed_sum(&[0, 4095], Some(4096)), SecureField::zero());
+        // The table only has rows for 0..=4095, wrapping around the field doesn't help either.
+        assert_ne!(claimed_sum(&[0, 4095], Some(P - 1)), SecureField::zero());
+    }
+
+    #[test]
+    fn test_range4096_chip_skips_other_rows() {
+        let mut traces = TracesBuilder::new(LOG_SIZE);
+        let program_traces = ProgramTracesBuilder::dummy(LOG_SIZE);
+        let mut side_note = SideNote::new(&program_traces, &HarvardEmulator::default().finalize());
+
+        // Register indices and shift amounts aren't immediates of type I instructions without a shift.
+        for row_idx in 0..traces.num_rows() {
+            traces.fill_columns(row_idx, BaseField::from(4096), OpC);
+            Range4096Chip::fill_main_trace(
+                &mut traces,
+                row_idx,
+                &Some(ProgramStep::default()),
+                &mut side_note,
+                &ExtensionsConfig::default(),
+            );
+        }
+        assert!(side_note.range4096.multiplicity.iter().all(|&m| m == 0));
+        assert_chip::<Range4096Chip>(traces, None);
+    }
+}
@@ -1,19 +1,21 @@
 // This file contains range-checking for columns containing only {0, 1}
 
-use num_traits::One;
+use nexus_vm::WORD_SIZE;
 
 use crate::{
+    chips::SyscallColumns,
     column::Column::{
-        self, BorrowFlag, CH1Minus, CH2Minus, CH3Minus, CarryFlag, HelperUBorrow, ImmC, IsAZero,
-        IsAdd, IsAnd, IsAuipc, IsBeq, IsBge, IsBgeu, IsBlt, IsBltu, IsBne, IsDiv, IsDivideByZero,
-        IsDivu, IsEbreak, IsEcall, IsJal, IsJalr, IsLb, IsLbu, IsLh, IsLhu, IsLui, IsLw, IsMul,
-        IsMulh, IsMulhsu, IsMulhu, IsOr, IsOverflow, IsPadding, IsRem, IsRemu, IsSb, IsSh, IsSll,
-        IsSlt, IsSltu, IsSra, IsSrl, IsSub, IsSw, IsSysCycleCount, IsSysDebug, IsSysHalt,
-        IsSysHeapReset, IsSysPrivInput, IsSysStackReset, IsXor, LtFlag, MulC1, MulC3Prime,
+        self, BorrowFlag, CH1Minus, CH2Minus, CH3Minus, CarryFlag, ClkCarry, FusedCarry,
+        FusedImmBits, FusedPcCarry, FusedProgCtrCarry, HelperUBorrow, ImmC, IsAZero, IsAdd, IsAnd,
+        IsAuipc, IsBeq, IsBge, IsBgeu, IsBlt, IsBltu, IsBne, IsDiv, IsDivideByZero, IsDivu,
+        IsEbreak, IsEcall, IsFusedLi, IsJal, IsJalr, IsLb, IsLbu, IsLh, IsLhu, IsLui, IsLw, IsMul,
+        IsMulh, IsMulhsu, IsMulhu, IsNop, IsOr, IsOverflow, IsPadding, IsRem, IsRemu, IsSb, IsSh,
+        IsSll, IsSlt, IsSltu, IsSra, IsSrl, IsSub, IsSw, IsXor, LtFlag, MulC1, MulC3Prime,
         MulC3PrimePrime, MulC5, MulCarry0, MulCarry2_0, MulCarry2_1, MulCarry3, OpA0, OpB0, OpB4,
-        OpC0, OpC11, OpC12, OpC20, OpC4, PcCarry, ProgCtrCarry, RemAux, RemainderBorrow, SgnA,
-        SgnB, SgnC, ShiftBit1, ShiftBit2, ShiftBit3, ShiftBit4, ShiftBit5, ValueAAbsBorrow,
-        ValueAAbsBorrowHigh, ValueAEffectiveFlag, ValueBAbsBorrow, ValueCAbsBorrow,
+        OpC0, OpC11, OpC12, OpC20, OpC4, PcCarry, ProgCtrCarry, RamSubTsCarry, RemAux,
+        RemainderBorrow, SgnA, SgnB, SgnC, ShiftBit1, ShiftBit2, ShiftBit3, ShiftBit4, ShiftBit5,
+        ValueAAbsBorrow, ValueAAbsBorrowHigh, ValueAEffectiveFlag, ValueBAbsBorrow,
+        ValueCAbsBorrow,
     },
     components::AllLookupElements,
     extensions::ExtensionsConfig,
@@ -22,12 +24,14 @@ use crate::{
     virtual_column::{self, VirtualColumn},
 };
 
+use super::constraints::{constrain_bool, constrain_bool_if};
+
 /// A Chip for range-checking values for {0, 1}
 ///
 /// RangeBoolChip can be located anywhere in the chip composition.
 pub struct RangeBoolChip;
 
-const CHECKED_SINGLE: [Column; 57] = [
+const CHECKED_SINGLE: [Column; 55] = [
     ValueAEffectiveFlag,
     ImmC,
     IsAdd,
@@ -68,12 +72,7 @@ const CHECKED_SINGLE: [Column; 57] = [
     IsRem,
     IsEcall,
     IsEbreak,
-    IsSysCycleCount,
-    IsSysDebug,
-    IsSysHalt,
-    IsSysHeapReset,
-    IsSysPrivInput,
-    IsSysStackReset,
+    IsNop,
     IsPadding,
     LtFlag,
     RemAux,
@@ -85,8 +84,11 @@ const CHECKED_SINGLE: [Column; 57] = [
     ShiftBit3,
     ShiftBit4,
     ShiftBit5,
+    IsFusedLi,
+    FusedPcCarry,
+    ClkCarry,
 ];
-const CHECKED_HALF_WORD: [Column; 11] = [
+const CHECKED_HALF_WORD: [Column; 13] = [
     CarryFlag,
     PcCarry,
     CH1Minus,
@@ -98,7 +100,11 @@ const CHECKED_HALF_WORD: [Column; 11] = [
     ValueBAbsBorrow,
     ValueCAbsBorrow,
     ValueAAbsBorrowHigh,
+    FusedCarry,
+    FusedProgCtrCarry,
 ];
+const CHECKED_TRIPLE: [Column; 1] = [RamSubTsCarry];
+const CHECKED_WORD: [Column; 1] = [FusedImmBits];
 const TYPE_R_CHECKED_SINGLE: [Column; 16] = [
     OpC4,
     OpA0,
@@ -124,6 +130,8 @@ const TYPE_B_CHECKED_SINGLE: [Column; 4] = [OpC11, OpC12, OpA0, OpB4];
 const TYPE_S_CHECKED_SINGLE: [Column; 4] = [OpC0, OpC11, OpA0, OpB4];
 
 impl MachineChip for RangeBoolChip {
+    const NAME: &'static str = "RangeBoolChip";
+
     fn fill_main_trace(
         _traces: &mut TracesBuilder,
         _row_idx: usize,
@@ -142,50 +150,69 @@ impl MachineChip for RangeBoolChip {
     ) {
         for col in CHECKED_SINGLE.into_iter() {
             let [col] = trace_eval.column_eval(col);
-            eval.add_constraint(col.clone() * (col - E::F::one()));
+            constrain_bool(eval, col);
+        }
+
+        for &col in SyscallColumns::ALL_VARIANTS {
+            let [col] = trace_eval.column_eval(col);
+            constrain_bool(eval, col);
         }
 
         for col in CHECKED_HALF_WORD.into_iter() {
             let col_limbs = trace_eval.column_eval::<2>(col);
             for limb in col_limbs.into_iter() {
-                eval.add_constraint(limb.clone() * (limb - E::F::one()));
+                constrain_bool(eval, limb);
+            }
+        }
+
+        for col in CHECKED_TRIPLE.into_iter() {
+            let col_limbs = trace_eval.column_eval::<3>(col);
+            for limb in col_limbs.into_iter() {
+                constrain_bool(eval, limb);
+            }
+        }
+
+        for col in CHECKED_WORD.into_iter() {
+            let col_limbs = trace_eval.column_eval::<WORD_SIZE>(col);
+            for limb in col_limbs.into_iter() {
+                constrain_bool(eval, limb);
             }
         }
 
         let [type_r] = virtual_column::IsTypeR::eval(trace_eval);
         for col in TYPE_R_CHECKED_SINGLE.into_iter() {
             let [col] = trace_eval.column_eval(col);
-            eval.add_constraint(type_r.clone() * col.clone() * (col - E::F::one()));
+            constrain_bool_if(eval, type_r.clone(), col);
         }
 
         let [is_type_i_no_shift] = virtual_column::IsTypeINoShift::eval(trace_eval);
         for col in TYPE_I_NO_SHIFT_SINGLE {
             let [col] = trace_eval.column_eval(col);
-            eval.add_constraint(is_type_i_no_shift.clone() * col.clone() * (col - E::F::one()));
+            constrain_bool_if(eval, is_type_i_no_shift.clone(), col);
         }
 
         let [is_alu_imm_shift] = virtual_column::IsAluImmShift::eval(trace_eval);
         for col in TYPE_I_SHIFT_SINGLE {
             let [col] = trace_eval.column_eval(col);
-            eval.add_constraint(is_alu_imm_shift.clone() * col.clone() * (col - E::F::one()));
+            constrain_bool_if(eval, is_alu_imm_shift.clone(), col);
         }
 
         let [is_type_j] = virtual_column::IsTypeJ::eval(trace_eval);
         for col in TYPE_J_CHECKED_SINGLE {
             let [col] = trace_eval.column_eval(col);
-            eval.add_constraint(is_type_j.clone() * col.clone() * (col - E::F::one()));
+            constrain_bool_if(eval, is_type_j.clone(), col);
         }
 
         let [is_type_b] = virtual_column::IsTypeB::eval(trace_eval);
         for col in TYPE_B_CHECKED_SINGLE {
             let [col] = trace_eval.column_eval(col);
-            eval.add_constraint(is_type_b.clone() * col.clone() * (col - E::F::one()));
+            constrain_bool_if(eval, is_type_b.clone(), col);
         }
 
         let [is_type_s] = virtual_column::IsTypeS::eval(trace_eval);
         for col in TYPE_S_CHECKED_SINGLE {
             let [col] = trace_eval.column_eval(col);
-            eval.add_constraint(is_type_s.clone() * col.clone() * (col - E::F::one()));
+            constrain_bool_if(eval, is_type_s.clone(), col);
         }
     }
 }
@@ -0,0 +1,134 @@
+use num_traits::One;
+use stwo::core::fields::m31::BaseField;
+use stwo_constraint_framework::EvalAtRow;
+
+use nexus_vm::WORD_SIZE;
+
+use crate::{
+    column::{Column, PreprocessedColumn, ProgramColumn},
+    components::AllLookupElements,
+    extensions::ExtensionsConfig,
+    trace::{
+        eval::{
+            preprocessed_trace_eval, program_trace_eval, trace_eval, trace_eval_next_row, TraceEval,
+        },
+        sidenote::SideNote,
+        ProgramStep, TracesBuilder,
+    },
+    traits::MachineChip,
+};
+
+/// Binds `PrgNumSteps` and `PrgNumRows` of the program trace to the main trace.
+///
+/// The last row before padding, or the last row if there is no padding, has `RowCounter` equal to the number of steps, and
+/// a trace starting with padding has no steps. Since padding is monotone, see [`CpuChip`](super::CpuChip), the
+/// boundary is unique. The last row has `RowCounter` equal to the number of rows.
+pub struct StepCountChip;
+
+impl MachineChip for StepCountChip {
+    const NAME: &'static str = "StepCountChip";
+
+    fn fill_main_trace(
+        _traces: &mut TracesBuilder,
+        _row_idx: usize,
+        _vm_step: &Option<ProgramStep>,
+        _side_note: &mut SideNote,
+        _config: &ExtensionsConfig,
+    ) {
+    }
+
+    fn add_constraints<E: EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &TraceEval<E>,
+        _lookup_elements: &AllLookupElements,
+        _config: &ExtensionsConfig,
+    ) {
+        let [is_padding] = trace_eval!(trace_eval, Column::IsPadding);
+        let [next_is_padding] = trace_eval_next_row!(trace_eval, Column::IsPadding);
+        let [is_first] = preprocessed_trace_eval!(trace_eval, PreprocessedColumn::IsFirst);
+        let [is_last] = preprocessed_trace_eval!(trace_eval, PreprocessedColumn::IsLast);
+        let row_counter_bytes =
+            preprocessed_trace_eval!(trace_eval, PreprocessedColumn::RowCounter);
+        let [num_steps] = program_trace_eval!(trace_eval, ProgramColumn::PrgNumSteps);
+        let [num_rows] = program_trace_eval!(trace_eval, ProgramColumn::PrgNumRows);
+
+        // row_counter = Σ row_counter_i・2^(8・(i - 1)), which doesn't wrap around for any trace size.
+        let row_counter = (1..WORD_SIZE).fold(row_counter_bytes[0].clone(), |row_counter, i| {
+            row_counter + row_counter_bytes[i].clone() * BaseField::from(1 << (8 * i))
+        });
+
+        // (1 - is_padding)・(next_is_padding)・(row_counter - num_steps) = 0
+        eval.add_constraint(
+            (E::F::one() - is_padding.clone())
+                * next_is_padding
+                * (row_counter.clone() - num_steps.clone()),
+        );
+        // (is_last)・(1 - is_padding)・(row_counter - num_steps) = 0
+        eval.add_constraint(
+            is_last.clone()
+                * (E::F::one() - is_padding.clone())
+                * (row_counter.clone() - num_steps.clone()),
+        );
+        // (is_first)・(is_padding)・(num_steps) = 0
+        eval.add_constraint(is_first * is_padding * num_steps);
+        // (is_last)・(row_counter - num_rows) = 0
+        eval.add_constraint(is_last * (row_counter - num_rows));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::panic::{self, AssertUnwindSafe};
+
+    use super::*;
+    use crate::{
+        chips::CpuChip,
+        test_utils::assert_chip,
+        testing::fill_traces,
+        trace::program_trace::{ProgramTraceRef, ProgramTracesBuilder},
+    };
+    use nexus_vm::{
+        emulator::InternalView,
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+        trace::k_trace_direct,
+    };
+
+    const LOG_SIZE: u32 = 6;
+
+    fn basic_block(num_steps: usize) -> Vec<BasicBlock> {
+        let addi = Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 1, 1);
+        vec![BasicBlock::new(vec![addi; num_steps])]
+    }
+
+    #[test]
+    fn test_step_count_constrained() {
+        for num_steps in [0, 1, 40, 1 << LOG_SIZE] {
+            let (traces, program_trace) = fill_traces::<CpuChip>(&basic_block(num_steps), LOG_SIZE);
+            assert_chip::<StepCountChip>(traces, Some(program_trace));
+        }
+    }
+
+    #[test]
+    fn test_wrong_step_count_rejected() {
+        const NUM_STEPS: usize = 40;
+        let basic_block = basic_block(NUM_STEPS);
+        let (view, _) = k_trace_direct(&basic_block, 1).expect("Failed to create trace");
+
+        for num_steps in [0, NUM_STEPS - 1, NUM_STEPS + 1, 1 << LOG_SIZE] {
+            let (traces, _) = fill_traces::<CpuChip>(&basic_block, LOG_SIZE);
+            let program_trace = ProgramTracesBuilder::new(
+                LOG_SIZE,
+                ProgramTraceRef {
+                    num_steps,
+                    ..ProgramTraceRef::new_with_empty_memory(view.get_program_memory())
+                },
+            )
+            .finalize();
+
+            let rejected = panic::catch_unwind(AssertUnwindSafe(|| {
+                assert_chip::<StepCountChip>(traces, Some(program_trace))
+            }));
+            assert!(rejected.is_err(), "{num_steps} steps accepted");
+        }
+    }
+}
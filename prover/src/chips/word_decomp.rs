@@ -0,0 +1,107 @@
+//! Byte limbs of word columns.
+//!
+//! Every column of [`WORD_SIZE`] bytes is range-checked in one place, [`Range256Chip`] reads the limbs back from the
+//! main trace on the last row and bumps the multiplicities for all of them. Chips fill word columns with
+//! [`fill_word_limbs`], which asserts that the column is covered by the range lookup, so that a new word column
+//! cannot be filled without adding it to `Range256Chip`. Word columns that are sound without the range check are
+//! listed with the reason in the test below.
+//!
+//! Constraints recompose limbs into 16-bit halves with [`word_halves`], a full word doesn't fit into M31.
+
+use stwo_constraint_framework::EvalAtRow;
+
+use nexus_vm::WORD_SIZE;
+
+use crate::{
+    chips::range_check::range256::Range256Chip,
+    column::Column,
+    trace::{utils::IntoBaseFields, TracesBuilder},
+};
+
+/// Fills byte limbs of the word column `col` at `row_idx`.
+///
+/// Panics if the limbs of `col` are not looked up in [`Range256Chip`], in release builds as well: a word column
+/// without the lookup would let the prover fill it with limbs outside of the byte range.
+pub(crate) fn fill_word_limbs<T: IntoBaseFields<WORD_SIZE>>(
+    traces: &mut TracesBuilder,
+    row_idx: usize,
+    value: T,
+    col: Column,
+) {
+    assert!(
+        Range256Chip::is_checked_word(col),
+        "limbs of {col:?} are not range-checked"
+    );
+    traces.fill_columns(row_idx, value, col);
+}
+
+/// Same as [`fill_word_limbs`] for several rows at once, see [`TracesBuilder::fill_columns_batch`].
+pub(crate) fn fill_word_limbs_batch<T: IntoBaseFields<WORD_SIZE>>(
+    traces: &mut TracesBuilder,
+    values: impl IntoIterator<Item = (usize, T)>,
+    col: Column,
+) {
+    assert!(
+        Range256Chip::is_checked_word(col),
+        "limbs of {col:?} are not range-checked"
+    );
+    traces.fill_columns_batch(values, col);
+}
+
+/// Recomposes byte limbs into low and high 16-bit halves, `limb_1 + limb_2·2^8` and `limb_3 + limb_4·2^8`.
+pub(crate) fn word_halves<E: EvalAtRow>(limbs: &[E::F; WORD_SIZE]) -> [E::F; 2] {
+    let modulus = E::F::from(256u32.into());
+    [
+        limbs[0].clone() + limbs[1].clone() * modulus.clone(),
+        limbs[2].clone() + limbs[3].clone() * modulus,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::column::Column::{
+        FusedImmBits, Helper2, Helper3, Helper4, PcNext, Reg1ValPrev, Reg2ValPrev, Reg3ValPrev,
+        ValueA4_7, ValueAEffective, ValueB4_7, ValueC4_7,
+    };
+    use crate::trace::PreprocessedTraces;
+
+    /// Word columns whose limbs are not looked up in [`Range256Chip`], together with the reason it is sound.
+    const UNCHECKED_WORDS: [(Column, &str); 12] = [
+        (PcNext, "equal to Pc of the next row, or to PcNextAux for JALR"),
+        (ValueAEffective, "copy of ValueA, zeroed for writes to x0"),
+        (Helper2, "boolean borrows for loads and stores, bytes used elsewhere are checked in Range128Chip"),
+        (Helper3, "boolean borrows for loads and stores, bytes used elsewhere are checked in Range128Chip"),
+        (Helper4, "boolean borrows for loads and stores"),
+        (Reg1ValPrev, "read from register memory, written as a checked value"),
+        (Reg2ValPrev, "read from register memory, written as a checked value"),
+        (Reg3ValPrev, "read from register memory, written as a checked value"),
+        (ValueA4_7, "nibbles, checked by the bitwise lookup"),
+        (ValueB4_7, "nibbles, checked by the bitwise lookup"),
+        (ValueC4_7, "nibbles, checked by the bitwise lookup"),
+        (FusedImmBits, "bits, checked in RangeBoolChip"),
+    ];
+
+    #[test]
+    fn word_columns_are_range_checked() {
+        for &col in Column::ALL_VARIANTS {
+            if col.size() != WORD_SIZE {
+                continue;
+            }
+            let exempt = UNCHECKED_WORDS
+                .iter()
+                .any(|(unchecked, _)| *unchecked == col);
+            assert!(
+                Range256Chip::is_checked_word(col) != exempt,
+                "{col:?} must be either range-checked in Range256Chip or listed in UNCHECKED_WORDS"
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "are not range-checked")]
+    fn filling_unchecked_word_panics() {
+        let mut traces = TracesBuilder::new(PreprocessedTraces::MIN_LOG_SIZE);
+        fill_word_limbs(&mut traces, 0, 0u32, PcNext);
+    }
+}
@@ -1,8 +1,9 @@
 #![allow(clippy::assertions_on_constants)]
 
-use nexus_vm_prover_macros::ColumnsEnum;
+use nexus_vm_prover_macros::{compose_columns, ColumnsEnum};
 
 use super::WORD_SIZE;
+use crate::chips::{CpuColumns, SraColumns, SyscallColumns, SyscallOperandColumns};
 
 const _: () = {
     // This assert is needed to prevent invalid definition of columns sizes.
@@ -15,7 +16,33 @@ impl Column {
     /// constraints require both values at the current **and** next row, e.g. for constraining next
     /// pc value.
     pub(crate) const fn reads_next_row_mask(&self) -> bool {
-        matches!(self, Self::Pc | Self::IsPadding)
+        matches!(self, Self::Pc | Self::IsPadding | Self::Clk)
+    }
+}
+
+compose_columns! {
+    /// Layout of the main trace.
+    ///
+    /// Chips declare the columns only they use in their own group, e.g. [`SyscallColumns`], which is appended
+    /// after the shared [`Column`] group. Trace accessors take anything convertible into a global column, so
+    /// chips that haven't been migrated keep using [`Column`] as is.
+    #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+    pub enum GlobalColumn {
+        Column,
+        CpuColumns,
+        SraColumns,
+        SyscallColumns,
+        SyscallOperandColumns,
+    }
+}
+
+impl GlobalColumn {
+    /// See [`Column::reads_next_row_mask`], columns of chip groups are only read at the current row.
+    pub(crate) const fn reads_next_row_mask(&self) -> bool {
+        match self {
+            Self::Column(col) => col.reads_next_row_mask(),
+            _ => false,
+        }
     }
 }
 
@@ -184,30 +211,12 @@ pub enum Column {
     /// Boolean flag on whether the row is an EBREAK.
     #[size = 1]
     IsEbreak,
-    /// Boolean flag on whether the row is an ECALL_DEBUG (Write).
-    #[size = 1]
-    IsSysDebug,
-    /// Boolean flag on whether the row is an ECALL_MADVISE (Heap Allocation).
-    #[size = 1]
-    IsSysMemoryAdvise,
-    /// Boolean flag on whether the row is an ECALL_HALT (Exit).
-    #[size = 1]
-    IsSysHalt,
-    /// Boolean flag on whether the row is an ECALL_PRIVATE_INPUT (ReadFromPrivateInput).
-    #[size = 1]
-    IsSysPrivInput,
-    /// Boolean flag on whether the row is an ECALL_CYCLECOUNT (CycleCount).
-    #[size = 1]
-    IsSysCycleCount,
-    /// Boolean flag on whether the row is an ECALL_STACK_RESET (OverwriteStackPointer).
-    #[size = 1]
-    IsSysStackReset,
-    /// Boolean flag on whether the row is an ECALL_HEAP_RESET (OverwriteHeapPointer).
-    #[size = 1]
-    IsSysHeapReset,
     /// Boolean flag on whether the row is a custom keccakf instruction call.
     #[size = 1]
     IsCustomKeccak,
+    /// Boolean flag on whether the row is a canonical NOP, `ADDI x0, x0, 0`.
+    #[size = 1]
+    IsNop,
     /// Boolean flag on whether the row is a padding.
     #[size = 1]
     IsPadding,
@@ -224,10 +233,10 @@ pub enum Column {
     /// Helper variable 4. Called h_4 in document.
     #[size = 4]
     Helper4,
-    /// Signed bit of A.
+    /// Signed bit of A. Signed bit of the left operand in comparison chips.
     #[size = 1]
     SgnA,
-    /// Signed bit of B.
+    /// Signed bit of B. Signed bit of the right operand in comparison chips.
     #[size = 1]
     SgnB,
     /// Signed bit of C.
@@ -275,9 +284,6 @@ pub enum Column {
     /// Exp1_3. Called exp1_3 in document.
     #[size = 1]
     Exp1_3,
-    /// Exp. Called exp in document.
-    #[size = 1]
-    Exp,
     /// RemDiff. Called rem{1,2,3,4}_diff in document.
     #[size = 4]
     RemDiff,
@@ -294,9 +300,6 @@ pub enum Column {
     /// neq_34_flag_aux_inv in document. Inverse of [`Column::Neq34Aux`].
     #[size = 1]
     Neq34AuxInv,
-    /// Auxiliary column for SRA chip, equals sgn_b・(exp1_3-1)・exp in the doc, to keep the constraint degree low.
-    #[size = 1]
-    SraDegreeAux,
 
     // M Extension
     /// Intermediate products for M Extension
@@ -406,12 +409,6 @@ pub enum Column {
     /// 1 indicates OpA is non-zero, 0 indicates OpA is zero
     #[size = 1]
     ValueAEffectiveFlag,
-    /// Auxiliary variable for computing ValueAEffectiveFlag
-    #[size = 1]
-    ValueAEffectiveFlagAux,
-    /// Another auxiliary variable for computing ValueAEffectiveFlag
-    #[size = 1]
-    ValueAEffectiveFlagAuxInv,
 
     /// Register index of register access slot 1
     #[size = 1]
@@ -471,6 +468,13 @@ pub enum Column {
     #[size = 2]
     CH3Minus,
 
+    /// The clock of the row: one on the first row, then advanced by the cycles of every row, one on padding rows
+    #[size = 4]
+    Clk,
+    /// Carry of the low 16-bit halves when advancing Clk to the next row
+    #[size = 1]
+    ClkCarry,
+
     /// The starting address of the read-write memory access
     #[size = 4]
     RamBaseAddr,
@@ -522,6 +526,9 @@ pub enum Column {
     /// Auxiliary columns for comparing Ram4TsPrev and Clk
     #[size = 4]
     Ram4TsPrevAux,
+    /// Carries of the low 16-bit halves of the sub-timestamps Clk + i of Ram{2,3,4} on multi-cycle rows, zero elsewhere
+    #[size = 3]
+    RamSubTsCarry,
 
     /// Auxiliary variable for decoding instruction: bits[0..=3] of OpC argument
     #[size = 1]
@@ -588,7 +595,7 @@ pub enum Column {
     #[size = 1]
     OpC24_31,
 
-    /// Auxiliary variable for incrementing program counter by four, assumes 16-bit limbs
+    /// Auxiliary variable for incrementing program counter by four, or by eight on fused rows, assumes 16-bit limbs
     #[size = 2]
     PcCarry,
 
@@ -601,6 +608,37 @@ pub enum Column {
     /// On bit-op rows, the more-significant four bits of each limb of ValueC. On those rows, ValueC4_7[i] contains ValueC[i] >> 4.
     #[size = 4]
     ValueC4_7,
+
+    /// Boolean flag on whether the LUI or AUIPC on the row is fused with the following ADDI into the same register.
+    #[size = 1]
+    IsFusedLi,
+    /// On fused rows, the instruction word of the ADDI, stored at Pc + 4.
+    #[size = 4]
+    FusedInstrVal,
+    /// On fused rows, the result of the LUI or AUIPC, the ADDI adds its immediate to it.
+    #[size = 4]
+    FusedBase,
+    /// On fused rows, bits[0..=7] of the ADDI immediate.
+    #[size = 1]
+    FusedImmLow,
+    /// On fused rows, bits 8, 9, 10 and 11 of the ADDI immediate, the last one is the sign.
+    #[size = 4]
+    FusedImmBits,
+    /// On fused rows, carry flags of adding the immediate to FusedBase, at 16-bit boundaries.
+    #[size = 2]
+    FusedCarry,
+    /// On fused rows, carry of adding four to the lower 16 bits of Pc, for the address of the ADDI.
+    #[size = 1]
+    FusedPcCarry,
+    /// On fused rows, the last access counter of the program memory at Pc + 4
+    #[size = 4]
+    FusedProgCtrPrev,
+    /// On fused rows, the current access counter of the program memory at Pc + 4, FusedProgCtrPrev + 1
+    #[size = 4]
+    FusedProgCtrCur,
+    /// Carry flags for incrementing FusedProgCtrPrev into FusedProgCtrCur, only kept at 16 bit and 32 bit boundaries
+    #[size = 2]
+    FusedProgCtrCarry,
 }
 
 // proc macro derived:
@@ -627,6 +665,15 @@ pub enum ProgramColumn {
     /// The first program counter for finding the first executed instruction
     #[size = 4]
     PrgInitialPc,
+    /// Number of rows holding executed steps, the rest of the main trace is padding. Same on every row.
+    #[size = 1]
+    PrgNumSteps,
+    /// Number of rows of the main trace, `1 << log_size`. Same on every row.
+    #[size = 1]
+    PrgNumRows,
+    /// Number of public output bytes committed by the guest, stored in two 16-bit limbs. Same on every row.
+    #[size = 2]
+    PrgCommittedOutputLen,
 }
 
 // proc macro derived:
@@ -647,9 +694,10 @@ pub enum PreprocessedColumn {
     /// Zero everywhere except the last row.
     #[size = 1]
     IsLast,
-    /// One on the first row, then incremented by one per row.
+    /// One on the first row, then incremented by one per row. Register accesses are timestamped with it, RAM
+    /// accesses with the clock of the row, [`Column::Clk`].
     #[size = 4]
-    Clk,
+    RowCounter,
     /// Timestamp for the first register access
     #[size = 4]
     Reg1TsCur,
@@ -670,3 +718,28 @@ pub enum PreprocessedColumn {
 //     pub const fn size(self) -> usize { /* ... */ }
 //     pub const fn offset(self) -> usize { /* ... */ }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_columns_keep_their_offsets() {
+        for &col in Column::ALL_VARIANTS {
+            let global = GlobalColumn::from(col);
+            assert_eq!(global.offset(), col.offset(), "{col:?}");
+            assert_eq!(global.size(), col.size(), "{col:?}");
+        }
+        assert_eq!(GlobalColumn::GROUP_OFFSETS[1], Column::COLUMNS_NUM);
+    }
+
+    #[test]
+    fn global_columns_are_contiguous() {
+        let mut offset = 0;
+        for col in GlobalColumn::all_variants() {
+            assert_eq!(col.offset(), offset, "{col:?}");
+            offset += col.size();
+        }
+        assert_eq!(offset, GlobalColumn::COLUMNS_NUM);
+    }
+}
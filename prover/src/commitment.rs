@@ -0,0 +1,106 @@
+//! Commitment trees built outside of a commitment scheme.
+//!
+//! Committing with stwo's `TreeBuilder` interpolates the columns, extends and hashes them, and mixes the root into
+//! the channel in a single step. [`CommittedTree`] splits these steps: the tree is built once, on its own, and grafted
+//! into a commitment scheme as its next tree without hashing again. The root is mixed into the channel when grafting,
+//! or earlier with [`CommittedTree::mix_root`], so that a tree can be bound to the transcript before the trees
+//! committed in front of it.
+//!
+//! In test builds, [`hashed_nodes`] counts the Merkle nodes hashed by the trees committed through this module.
+
+use stwo::{
+    core::{
+        channel::MerkleChannel, fields::m31::BaseField, pcs::PcsConfig,
+        vcs::blake2_hash::Blake2sHash, ColumnVec,
+    },
+    prover::{
+        backend::simd::SimdBackend,
+        poly::{circle::CircleEvaluation, twiddles::TwiddleTree, BitReversedOrder},
+        vcs::prover::MerkleProver,
+        CommitmentSchemeProver, CommitmentTreeProver,
+    },
+};
+
+use crate::transcript::{TranscriptChannel, TranscriptMerkleChannel};
+
+type TreeProver = CommitmentTreeProver<SimdBackend, TranscriptMerkleChannel>;
+
+/// A committed tree, ready to be grafted into a commitment scheme, see the [module documentation](self).
+pub(crate) struct CommittedTree {
+    tree: TreeProver,
+}
+
+impl CommittedTree {
+    /// Interpolates, extends and hashes `evals` with the parameters of a commitment scheme with `config` and
+    /// `twiddles`. The tree is the same as the one committed to by a tree builder of that scheme.
+    pub(crate) fn commit(
+        config: PcsConfig,
+        twiddles: &TwiddleTree<SimdBackend>,
+        evals: ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    ) -> Self {
+        let mut scheme =
+            CommitmentSchemeProver::<SimdBackend, TranscriptMerkleChannel>::new(config, twiddles);
+        let mut tree_builder = scheme.tree_builder();
+        let _location = tree_builder.extend_evals(evals);
+        // The root is mixed into the channel of the proof when the tree is grafted.
+        tree_builder.commit(&mut TranscriptChannel::default());
+        let tree = scheme.trees.0.pop().expect("tree was just committed");
+
+        #[cfg(test)]
+        HASHED_NODES.with(|count| {
+            let nodes: usize = tree.commitment.layers.iter().map(Vec::len).sum();
+            count.set(count.get() + nodes);
+        });
+        Self { tree }
+    }
+
+    pub(crate) fn root(&self) -> Blake2sHash {
+        self.tree.commitment.root()
+    }
+
+    /// Mixes the root of the tree into `channel`, as committing to it does.
+    pub(crate) fn mix_root(&self, channel: &mut TranscriptChannel) {
+        TranscriptMerkleChannel::mix_root(channel, self.root());
+    }
+
+    /// Appends a copy of the tree to the trees of `scheme` and mixes its root into `channel`, the same as committing
+    /// to its columns with a tree builder of `scheme`.
+    pub(crate) fn graft(
+        &self,
+        scheme: &mut CommitmentSchemeProver<'_, SimdBackend, TranscriptMerkleChannel>,
+        channel: &mut TranscriptChannel,
+    ) {
+        self.mix_root(channel);
+        scheme.trees.push(self.copy());
+    }
+
+    /// Appends the tree to the trees of `scheme` without mixing its root, which must have been mixed before with
+    /// [`Self::mix_root`].
+    pub(crate) fn graft_mixed(
+        self,
+        scheme: &mut CommitmentSchemeProver<'_, SimdBackend, TranscriptMerkleChannel>,
+    ) {
+        scheme.trees.push(self.tree);
+    }
+
+    fn copy(&self) -> TreeProver {
+        CommitmentTreeProver {
+            polynomials: self.tree.polynomials.clone(),
+            evaluations: self.tree.evaluations.clone(),
+            commitment: MerkleProver {
+                layers: self.tree.commitment.layers.clone(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+thread_local! {
+    static HASHED_NODES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Returns the number of Merkle nodes hashed by [`CommittedTree::commit`] on the current thread so far.
+#[cfg(test)]
+pub(crate) fn hashed_nodes() -> usize {
+    HASHED_NODES.with(std::cell::Cell::get)
+}
@@ -0,0 +1,147 @@
+//! Compact encoding of proofs for bandwidth-constrained settings.
+//!
+//! [`CompactProof`] drops the fields of a [`Proof`] that the verifier is able to reconstruct:
+//!
+//! - the last claimed sum, the verifier requires all claimed sums to add up to zero,
+//! - the polynomial commitment scheme parameters, if they are the ones used by the prover and the verifier by default.
+//!
+//! The [`StarkProof`] and per-component log sizes are stored as is: the verifier derives the sizes of committed
+//! columns from [`Proof::log_size`], not the other way around.
+//!
+//! [`Proof::compact`] and [`CompactProof::expand`] are lossless for every proof accepted by the verifier.
+
+use serde::{Deserialize, Serialize};
+use stwo::core::{
+    fields::qm31::SecureField, pcs::PcsConfig, proof::StarkProof,
+    vcs::blake2_merkle::Blake2sMerkleHasher,
+};
+
+use crate::machine::{PcsParams, Proof, ProofMetadata};
+
+/// A [`Proof`] without fields derivable by the verifier, see the [module documentation](self).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CompactProof {
+    stark_proof: StarkProof<Blake2sMerkleHasher>,
+    /// All claimed sums except for the last one, `None` if the proof has no claimed sum at all.
+    #[serde(alias = "claimed_sum")]
+    claimed_sums: Option<Vec<SecureField>>,
+    log_size: Vec<u32>,
+    #[serde(default)]
+    num_steps: u32,
+    committed_output_len: Option<u32>,
+    crate_version: String,
+    components_id: [u8; 32],
+    /// `None` if equal to the default parameters.
+    pcs_params: Option<PcsParams>,
+    salt: Option<[u8; 32]>,
+}
+
+impl Proof {
+    /// Converts the proof into its compact encoding.
+    pub fn compact(self) -> CompactProof {
+        let Self {
+            stark_proof,
+            mut claimed_sums,
+            log_size,
+            num_steps,
+            committed_output_len,
+            metadata,
+        } = self;
+        let claimed_sums = claimed_sums.pop().map(|_| claimed_sums);
+        let default_params = PcsParams::from(&PcsConfig::default());
+
+        CompactProof {
+            stark_proof,
+            claimed_sums,
+            log_size,
+            num_steps,
+            committed_output_len,
+            crate_version: metadata.crate_version,
+            components_id: metadata.components_id,
+            pcs_params: (metadata.pcs_params != default_params).then_some(metadata.pcs_params),
+            salt: metadata.salt,
+        }
+    }
+}
+
+impl CompactProof {
+    /// Restores the full proof.
+    pub fn expand(self) -> Proof {
+        let Self {
+            stark_proof,
+            claimed_sums,
+            log_size,
+            num_steps,
+            committed_output_len,
+            crate_version,
+            components_id,
+            pcs_params,
+            salt,
+        } = self;
+        let claimed_sums = claimed_sums
+            .map(|mut claimed_sums| {
+                claimed_sums.push(-claimed_sums.iter().sum::<SecureField>());
+                claimed_sums
+            })
+            .unwrap_or_default();
+
+        Proof {
+            stark_proof,
+            claimed_sums,
+            log_size,
+            num_steps,
+            committed_output_len,
+            metadata: ProofMetadata {
+                crate_version,
+                components_id,
+                pcs_params: pcs_params.unwrap_or_else(|| PcsParams::from(&PcsConfig::default())),
+                salt,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nexus_vm::{
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+        trace::k_trace_direct,
+    };
+
+    use crate::machine::{BaseComponent, Machine, Proof};
+
+    fn round_trip(proof: &Proof) {
+        let expanded = proof.clone().compact().expand();
+        assert_eq!(
+            postcard::to_stdvec(&expanded).unwrap(),
+            postcard::to_stdvec(proof).unwrap()
+        );
+    }
+
+    #[test]
+    fn compact_proof_round_trip() {
+        let basic_block = BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 3, 2, 1),
+        ]);
+        let (view, trace) = k_trace_direct(&vec![basic_block], 1).expect("error generating trace");
+        let proof = Machine::<BaseComponent>::prove(&trace, &view).unwrap();
+        let proof_bytes = postcard::to_stdvec(&proof).unwrap();
+
+        round_trip(&proof);
+
+        let mut without_sums = proof.clone();
+        without_sums.claimed_sums.clear();
+        round_trip(&without_sums);
+
+        let compact_bytes = postcard::to_stdvec(&proof.compact()).unwrap();
+        assert!(compact_bytes.len() < proof_bytes.len());
+
+        let expanded = postcard::from_bytes::<super::CompactProof>(&compact_bytes)
+            .unwrap()
+            .expand();
+        assert_eq!(postcard::to_stdvec(&expanded).unwrap(), proof_bytes);
+        crate::verify(expanded, &view).unwrap();
+    }
+}
@@ -5,11 +5,16 @@
 //!     2. Add this type to [`register_relation`] enum.
 //!     3. Implement [`MachineChip::draw_lookup_elements`] and [`MachineChip::fill_interaction_trace`] for the chip.
 //!
-//! Internally, [`AllLookupElements`] is a hashmap storing a set of generated alphas and z (=lookup elements) for each
-//! type. Since [`stwo_constraint_framework::Relation`] is not object safe and cannot be boxed, the only way
-//! to store it is by using an enum.
+//! Internally, [`AllLookupElements`] is a list storing a set of generated alphas and z (=lookup elements) for each
+//! type, keyed by the relation name. Since [`stwo_constraint_framework::Relation`] is not object safe and cannot be
+//! boxed, the only way to store it is by using an enum.
+//!
+//! Relations are kept in the order chips register them, which is the order their elements are drawn from the
+//! channel. The order is part of [`Machine::components_id`](crate::machine::Machine::components_id): a prover and a
+//! verifier drawing the same relations in a different order are rejected by the components check rather than by an
+//! unsatisfied logup sum.
 
-use std::{any::TypeId, collections::HashMap};
+use std::fmt;
 
 pub use crate::chips::{
     custom::keccak_lookups::{
@@ -18,7 +23,7 @@ pub use crate::chips::{
         StateLookupElements as KeccakStateLookupElements,
         XorLookupElements as KeccakXorLookupElements,
     },
-    instructions::{BitOpLookupElements, LoadStoreLookupElements},
+    instructions::{BitOpLookupElements, CommitLookupElements, LoadStoreLookupElements},
     memory_check::{
         program_mem_check::ProgramCheckLookupElements,
         register_mem_check::RegisterCheckLookupElements,
@@ -26,7 +31,7 @@ pub use crate::chips::{
     range_check::{
         range128::Range128LookupElements, range16::Range16LookupElements,
         range256::Range256LookupElements, range32::Range32LookupElements,
-        range8::Range8LookupElements,
+        range4096::Range4096LookupElements, range8::Range8LookupElements,
     },
 };
 
@@ -45,43 +50,70 @@ register_relation! {
         Range32LookupElements,
         Range128LookupElements,
         Range256LookupElements,
+        Range4096LookupElements,
         KeccakXorLookupElements,
         KeccakBitNotAndLookupElements,
         KeccakStateLookupElements,
         KeccakBitRotateLookupElements,
+        CommitLookupElements,
     };
     pub(crate) trait RegisteredLookupBound {}
 }
 
-#[derive(Default, Debug, Clone)]
-pub struct AllLookupElements(HashMap<TypeId, RelationVariant>);
+#[derive(Default, Clone)]
+pub struct AllLookupElements(Vec<RelationVariant>);
 
 impl AllLookupElements {
     pub fn insert<T: Into<RelationVariant> + 'static>(&mut self, relation: T) {
-        if self.0.insert(TypeId::of::<T>(), relation.into()).is_some() {
-            panic!("attempt to insert duplicate relation")
+        let relation = relation.into();
+        if self.0.iter().any(|r| r.id() == relation.id()) {
+            panic!("attempt to insert duplicate relation {}", relation.id())
         }
+        self.0.push(relation);
     }
 
     pub fn dummy() -> Self {
-        Self(HashMap::from_iter(RelationVariant::dummy_array()))
+        Self(RelationVariant::dummy_array().into())
     }
 
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    /// Returns names of the registered relations, in registration order.
+    pub fn relation_ids(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.0.iter().map(RelationVariant::id)
+    }
 }
 
 impl<T: RegisteredLookupBound> AsRef<T> for AllLookupElements {
     fn as_ref(&self) -> &T {
         let variant = self
             .0
-            .get(&TypeId::of::<T>())
+            .iter()
+            .find(|r| r.id() == T::ID)
             .expect("lookup elements weren't initialized");
         T::unwrap_ref(variant)
     }
 }
 
+/// Lists registered relations without their elements, which are drawn from the channel and only add noise.
+impl fmt::Debug for AllLookupElements {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Redacted;
+
+        impl fmt::Debug for Redacted {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("..")
+            }
+        }
+
+        f.debug_map()
+            .entries(self.relation_ids().map(|id| (id, Redacted)))
+            .finish()
+    }
+}
+
 macro_rules! register_relation {
     (enum $_enum:ident { $( $name:ident ),* $(,)? }; $_vis:vis trait $_trait:ident {}) => {
         #[allow(clippy::enum_variant_names)]
@@ -91,6 +123,9 @@ macro_rules! register_relation {
         }
 
         $_vis trait $_trait: Sync + Clone + 'static {
+            /// Stable name of the relation, independent of the module it's declared in.
+            const ID: &'static str;
+
             type Relation<
                 F: Clone,
                 EF: stwo_constraint_framework::RelationEFTraitBound<F>
@@ -116,6 +151,8 @@ macro_rules! register_relation {
             }
 
             impl $_trait for $name {
+                const ID: &'static str = stringify!($name);
+
                 type Relation<
                     F: Clone,
                     EF: stwo_constraint_framework::RelationEFTraitBound<F>
@@ -150,12 +187,14 @@ macro_rules! register_relation {
                 <[()]>::len(&[$($crate::components::lookups::replace_expr!($name ())),*])
             };
 
-            fn dummy_array() -> [(std::any::TypeId, Self); Self::NUM_VARIANTS] {
-                [
-                    $(
-                        (std::any::TypeId::of::<$name>(), Self::$name($name::dummy())),
-                    )*
-                ]
+            fn dummy_array() -> [Self; Self::NUM_VARIANTS] {
+                [$(Self::$name($name::dummy()),)*]
+            }
+
+            fn id(&self) -> &'static str {
+                match self {
+                    $(Self::$name(_) => stringify!($name),)*
+                }
             }
         }
     };
@@ -168,3 +207,32 @@ macro_rules! replace_expr {
     };
 }
 pub(self) use replace_expr;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registration_order_is_preserved() {
+        let mut elements = AllLookupElements::default();
+        elements.insert(Range8LookupElements::dummy());
+        elements.insert(BitOpLookupElements::dummy());
+        assert_eq!(
+            elements.relation_ids().collect::<Vec<_>>(),
+            ["Range8LookupElements", "BitOpLookupElements"]
+        );
+        assert_eq!(
+            format!("{elements:?}"),
+            r#"{"Range8LookupElements": .., "BitOpLookupElements": ..}"#
+        );
+        let _: &BitOpLookupElements = elements.as_ref();
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate relation BitOpLookupElements")]
+    fn duplicate_relation_is_rejected() {
+        let mut elements = AllLookupElements::default();
+        elements.insert(BitOpLookupElements::dummy());
+        elements.insert(BitOpLookupElements::dummy());
+    }
+}
@@ -4,7 +4,10 @@ use stwo_constraint_framework::{EvalAtRow, FrameworkComponent, FrameworkEval, In
 
 use crate::extensions::ExtensionsConfig;
 
-use super::{trace::eval::TraceEval, traits::MachineChip};
+use super::{
+    trace::{eval::TraceEval, logup::finalize_logup},
+    traits::MachineChip,
+};
 
 pub(crate) mod lookups;
 pub(crate) use lookups::RegisteredLookupBound;
@@ -50,7 +53,7 @@ impl<C: MachineChip> FrameworkEval for MachineEval<C> {
         C::add_constraints(&mut eval, &trace_eval, &self.lookup_elements, &self.config);
 
         if !self.lookup_elements.is_empty() {
-            eval.finalize_logup();
+            finalize_logup(&mut eval);
         }
         eval
     }
@@ -65,3 +68,37 @@ pub(crate) fn machine_component_info<C: MachineChip>(config: ExtensionsConfig) -
     };
     eval.evaluate(InfoEvaluator::empty())
 }
+
+#[cfg(test)]
+mod tests {
+    use stwo_constraint_framework::expr::ExprEvaluator;
+
+    use super::*;
+    use crate::{extensions::ExtensionComponent, machine::BaseComponent};
+
+    fn max_constraint_degree(config: ExtensionsConfig) -> usize {
+        let eval = MachineEval::<BaseComponent>::new(1, AllLookupElements::dummy(), config);
+        let expr_eval = eval.evaluate(ExprEvaluator::new());
+        expr_eval
+            .constraint_degree_bounds()
+            .into_iter()
+            .max()
+            .expect("main component has constraints")
+    }
+
+    #[test]
+    fn constraint_degree_fits_log_bound() {
+        // Pairing logup fractions raises the degree of the logup constraints by one, the bound of the component
+        // must still cover it.
+        for config in [
+            ExtensionsConfig::default(),
+            ExtensionsConfig::from(ExtensionComponent::keccak_extensions()),
+        ] {
+            let degree = max_constraint_degree(config);
+            assert!(
+                degree <= 1 << LOG_CONSTRAINT_DEGREE,
+                "max constraint degree {degree} exceeds the bound 2^{LOG_CONSTRAINT_DEGREE}"
+            );
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! Constants assumed by the prover, checked against the ones shared with the emulator, see
+//! [`nexus_common::consistency`].
+
+use nexus_common::consistency::{AssumedConstants, SharedConstantMismatch, SHARED_CONSTANTS};
+
+use crate::{extensions::final_reg::FinalRegEval, trace::preprocessed::PreprocessedBuilder};
+
+const PROVER_CONSTANTS: AssumedConstants = AssumedConstants {
+    word_size: Some(crate::WORD_SIZE),
+    num_registers: Some(1 << FinalRegEval::LOG_SIZE),
+    first_clk: Some(PreprocessedBuilder::FIRST_CLK),
+    // Addresses of the memory layout are read from the view rather than assumed.
+    ..AssumedConstants::NONE
+};
+
+const _: () = assert!(
+    SHARED_CONSTANTS.first_mismatch(&PROVER_CONSTANTS).is_none(),
+    "the prover assumes constants that differ from nexus_common"
+);
+
+#[cfg(test)]
+thread_local! {
+    // Constants assumed by the prover on the current thread instead of `PROVER_CONSTANTS`, to simulate a mismatch.
+    static ASSUMED_OVERRIDE: std::cell::Cell<Option<AssumedConstants>> = const { std::cell::Cell::new(None) };
+}
+
+fn assumed() -> AssumedConstants {
+    #[cfg(test)]
+    if let Some(assumed) = ASSUMED_OVERRIDE.with(std::cell::Cell::get) {
+        return assumed;
+    }
+    PROVER_CONSTANTS
+}
+
+/// Fails, naming the constant, if the prover assumes a constant that differs from the one shared with the emulator.
+pub fn verify_shared_constants() -> Result<(), SharedConstantMismatch> {
+    SHARED_CONSTANTS.check(&assumed(), "prover")
+}
+
+#[cfg(test)]
+mod tests {
+    use nexus_vm::{
+        riscv::{BasicBlock, Instruction},
+        trace::k_trace_direct,
+    };
+
+    use super::*;
+    use crate::{
+        error::NexusProvingError,
+        machine::{BaseComponent, Machine},
+    };
+
+    #[test]
+    fn prover_constants_match() {
+        verify_shared_constants().unwrap();
+    }
+
+    #[test]
+    fn mismatch_stops_proving() {
+        let (view, trace) = k_trace_direct(&vec![BasicBlock::new(vec![Instruction::nop()])], 1)
+            .expect("Failed to create trace");
+        ASSUMED_OVERRIDE.with(|assumed| {
+            assumed.set(Some(AssumedConstants {
+                first_clk: Some(SHARED_CONSTANTS.first_clk + 1),
+                ..PROVER_CONSTANTS
+            }))
+        });
+        let result = Machine::<BaseComponent>::prove(&trace, &view);
+        ASSUMED_OVERRIDE.with(|assumed| assumed.set(None));
+
+        let Err(NexusProvingError::SharedConstants(err)) = result else {
+            panic!("proving with mismatching constants didn't fail");
+        };
+        assert_eq!(err.name, "first_clk");
+        assert_eq!(err.assumed, err.shared + 1);
+    }
+}
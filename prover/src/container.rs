@@ -0,0 +1,281 @@
+//! Compressed container of serialized proofs.
+//!
+//! Most of a serialized [`Proof`] consists of FRI query decommitments, which compress well. The container stores
+//! the canonical (postcard) serialization of a proof compressed with zstd, behind a fixed-size header:
+//!
+//! | bytes    | field                                                   |
+//! |----------|---------------------------------------------------------|
+//! | 0..4     | magic, `NXPC`                                           |
+//! | 4        | container version, [`VERSION`]                          |
+//! | 5        | compression id, [`ZSTD`]                                |
+//! | 6..14    | length of the uncompressed serialization, little-endian |
+//! | 14..46   | keccak-256 of bytes 0..14 and of the payload            |
+//! | 46..     | compressed payload                                      |
+//!
+//! The checksum guards against corruption in storage or transit, it doesn't authenticate the proof. Decoding checks
+//! it and the declared length against [`DeserLimits::max_bytes`] before decompressing the payload, and deserializes
+//! it with [`Proof::from_bytes_bounded`].
+
+use std::io::Read;
+
+use thiserror::Error;
+use tiny_keccak::{Hasher, Keccak};
+
+use crate::{
+    deser::{DeserError, DeserLimits},
+    machine::Proof,
+};
+
+const MAGIC: [u8; 4] = *b"NXPC";
+/// Version of the container layout.
+pub const VERSION: u8 = 1;
+/// Compression id of zstd.
+pub const ZSTD: u8 = 1;
+const HEADER_LEN: usize = 46;
+const CHECKSUM_OFFSET: usize = 14;
+const ZSTD_LEVEL: i32 = 3;
+
+/// Reasons for [`Proof::from_compressed_bytes`] to reject a container.
+#[derive(Debug, Error)]
+pub enum ContainerError {
+    #[error("not a compressed proof container")]
+    InvalidMagic,
+
+    #[error("unsupported container version {0}")]
+    UnsupportedVersion(u8),
+
+    #[error("unknown compression id {0}")]
+    UnknownCompression(u8),
+
+    #[error("container is truncated")]
+    Truncated,
+
+    #[error("checksum mismatch")]
+    ChecksumMismatch,
+
+    #[error("decompressed length doesn't match the header, expected {expected} bytes")]
+    LengthMismatch { expected: u64 },
+
+    #[error("declared length of {len} bytes exceeds the limit of {limit} bytes")]
+    TooLarge { len: u64, limit: usize },
+
+    #[error("compression error: {0}")]
+    Compression(#[from] std::io::Error),
+
+    #[error("serialization error: {0}")]
+    Serialization(#[from] postcard::Error),
+
+    #[error(transparent)]
+    Deserialization(#[from] DeserError),
+}
+
+impl Proof {
+    /// Serializes the proof into a compressed container, see the [module documentation](crate::container).
+    pub fn to_compressed_bytes(&self) -> Result<Vec<u8>, ContainerError> {
+        let serialized = postcard::to_stdvec(self)?;
+        let payload = zstd::bulk::compress(&serialized, ZSTD_LEVEL)?;
+
+        let mut bytes = Vec::with_capacity(HEADER_LEN + payload.len());
+        bytes.extend_from_slice(&MAGIC);
+        bytes.push(VERSION);
+        bytes.push(ZSTD);
+        bytes.extend_from_slice(&(serialized.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&checksum(&bytes, &payload));
+        bytes.extend_from_slice(&payload);
+        Ok(bytes)
+    }
+
+    /// Deserializes a proof from a container created by [`Self::to_compressed_bytes`], within the default
+    /// [`DeserLimits`].
+    pub fn from_compressed_bytes(bytes: &[u8]) -> Result<Self, ContainerError> {
+        Self::from_compressed_bytes_bounded(bytes, DeserLimits::default())
+    }
+
+    /// Same as [`Self::from_compressed_bytes`], deserializing the payload within `limits`.
+    pub fn from_compressed_bytes_bounded(
+        bytes: &[u8],
+        limits: DeserLimits,
+    ) -> Result<Self, ContainerError> {
+        if bytes.len() < HEADER_LEN {
+            return Err(if bytes.starts_with(&MAGIC) {
+                ContainerError::Truncated
+            } else {
+                ContainerError::InvalidMagic
+            });
+        }
+        let (header, payload) = bytes.split_at(HEADER_LEN);
+        if header[0..4] != MAGIC {
+            return Err(ContainerError::InvalidMagic);
+        }
+        if header[4] != VERSION {
+            return Err(ContainerError::UnsupportedVersion(header[4]));
+        }
+        if header[5] != ZSTD {
+            return Err(ContainerError::UnknownCompression(header[5]));
+        }
+        if header[CHECKSUM_OFFSET..] != checksum(&header[..CHECKSUM_OFFSET], payload) {
+            return Err(ContainerError::ChecksumMismatch);
+        }
+        let expected = u64::from_le_bytes(header[6..14].try_into().expect("slice of 8 bytes"));
+        if expected > limits.max_bytes as u64 {
+            return Err(ContainerError::TooLarge {
+                len: expected,
+                limit: limits.max_bytes,
+            });
+        }
+
+        // Reading one byte past the declared length detects longer payloads without decompressing all of them.
+        let mut serialized = Vec::new();
+        zstd::stream::read::Decoder::new(payload)?
+            .take(expected.saturating_add(1))
+            .read_to_end(&mut serialized)?;
+        if serialized.len() as u64 != expected {
+            return Err(ContainerError::LengthMismatch { expected });
+        }
+        Ok(Self::from_bytes_bounded(&serialized, limits)?)
+    }
+}
+
+fn checksum(header: &[u8], payload: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    hasher.update(header);
+    hasher.update(payload);
+    let mut output = [0u8; 32];
+    hasher.finalize(&mut output);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use nexus_vm::{
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+        trace::k_trace_direct,
+    };
+
+    use super::*;
+    use crate::machine::{BaseComponent, Machine};
+
+    fn prove() -> (Proof, nexus_vm::emulator::View) {
+        let basic_block = BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 3, 2, 1),
+        ]);
+        let (view, trace) = k_trace_direct(&vec![basic_block], 1).expect("error generating trace");
+        let proof = Machine::<BaseComponent>::prove(&trace, &view).unwrap();
+        (proof, view)
+    }
+
+    #[test]
+    fn compressed_proof_round_trip() {
+        let (proof, view) = prove();
+        let proof_bytes = postcard::to_stdvec(&proof).unwrap();
+
+        let compressed = proof.to_compressed_bytes().unwrap();
+        assert!(compressed.len() < proof_bytes.len());
+
+        let decoded = Proof::from_compressed_bytes(&compressed).unwrap();
+        assert_eq!(postcard::to_stdvec(&decoded).unwrap(), proof_bytes);
+        crate::verify(decoded, &view).unwrap();
+    }
+
+    #[test]
+    fn corrupted_container_is_rejected() {
+        let (proof, _) = prove();
+        let compressed = proof.to_compressed_bytes().unwrap();
+
+        let mut corrupted = compressed.clone();
+        *corrupted.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            Proof::from_compressed_bytes(&corrupted),
+            Err(ContainerError::ChecksumMismatch)
+        ));
+
+        let mut corrupted = compressed.clone();
+        corrupted[5] = 7;
+        assert!(matches!(
+            Proof::from_compressed_bytes(&corrupted),
+            Err(ContainerError::UnknownCompression(7))
+        ));
+
+        let mut corrupted = compressed.clone();
+        corrupted[4] = VERSION + 1;
+        assert!(matches!(
+            Proof::from_compressed_bytes(&corrupted),
+            Err(ContainerError::UnsupportedVersion(_))
+        ));
+
+        // The checksum covers the declared length.
+        let mut corrupted = compressed.clone();
+        corrupted[6] ^= 1;
+        assert!(matches!(
+            Proof::from_compressed_bytes(&corrupted),
+            Err(ContainerError::ChecksumMismatch)
+        ));
+
+        assert!(matches!(
+            Proof::from_compressed_bytes(&compressed[..HEADER_LEN - 1]),
+            Err(ContainerError::Truncated)
+        ));
+        assert!(matches!(
+            Proof::from_compressed_bytes(&compressed[..compressed.len() - 1]),
+            Err(ContainerError::ChecksumMismatch)
+        ));
+        assert!(matches!(
+            Proof::from_compressed_bytes(&postcard::to_stdvec(&proof).unwrap()),
+            Err(ContainerError::InvalidMagic)
+        ));
+    }
+
+    /// Rewrites the declared length of a container, with a matching checksum.
+    fn with_declared_len(compressed: &[u8], len: u64) -> Vec<u8> {
+        let mut bytes = compressed.to_vec();
+        bytes[6..CHECKSUM_OFFSET].copy_from_slice(&len.to_le_bytes());
+        let (header, payload) = bytes.split_at(HEADER_LEN);
+        let checksum = checksum(&header[..CHECKSUM_OFFSET], payload);
+        bytes[CHECKSUM_OFFSET..HEADER_LEN].copy_from_slice(&checksum);
+        bytes
+    }
+
+    #[test]
+    fn declared_length_is_bounded() {
+        let (proof, _) = prove();
+        let serialized_len = postcard::to_stdvec(&proof).unwrap().len();
+        let compressed = proof.to_compressed_bytes().unwrap();
+
+        // Rejected before decompressing anything.
+        assert!(matches!(
+            Proof::from_compressed_bytes(&with_declared_len(&compressed, u64::MAX)),
+            Err(ContainerError::TooLarge { len: u64::MAX, .. })
+        ));
+        let limits = DeserLimits {
+            max_bytes: serialized_len - 1,
+            ..DeserLimits::default()
+        };
+        assert!(matches!(
+            Proof::from_compressed_bytes_bounded(&compressed, limits),
+            Err(ContainerError::TooLarge { .. })
+        ));
+
+        // A consistent header with a wrong length is caught after decompression.
+        assert!(matches!(
+            Proof::from_compressed_bytes(&with_declared_len(
+                &compressed,
+                serialized_len as u64 - 1
+            )),
+            Err(ContainerError::LengthMismatch { .. })
+        ));
+
+        // The payload is deserialized within the limits.
+        let limits = DeserLimits {
+            max_elements: 100,
+            ..DeserLimits::default()
+        };
+        assert!(matches!(
+            Proof::from_compressed_bytes_bounded(&compressed, limits),
+            Err(ContainerError::Deserialization(DeserError::ElementLimit {
+                limit: 100
+            }))
+        ));
+    }
+}
@@ -0,0 +1,180 @@
+//! Machine-readable descriptions of chip constraints, see [`MachineChip::describe`].
+//!
+//! A description lists the constraints a chip adds in [`MachineChip::add_constraints`], in the order they are added,
+//! and the lookup relations it contributes to. Constraints repeated for every limb or limb pair are described once
+//! with their count. Tests compare the described counts with the constraints and logup fractions the chips actually
+//! add, so a description can't silently drift from the code.
+
+use serde::Serialize;
+
+#[cfg(doc)]
+use crate::traits::MachineChip;
+
+/// Constraints and lookups of a chip.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ChipDescription {
+    pub chip: &'static str,
+    pub constraints: &'static [ConstraintDescription],
+    pub lookups: &'static [LookupDescription],
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ConstraintDescription {
+    /// Identifier, unique within the chip.
+    pub id: &'static str,
+    /// What the constraint enforces and why it is required.
+    pub description: &'static str,
+    /// Columns the constraint reads, virtual columns are named after the columns they combine.
+    pub columns: &'static [&'static str],
+    /// Degree of the constraint polynomial in the trace columns.
+    pub degree: u32,
+    /// Number of copies, one for every limb or limb pair the constraint applies to.
+    pub count: usize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct LookupDescription {
+    /// Name of the relation.
+    pub relation: &'static str,
+    /// Meaning of every element of the tuple, in order.
+    pub tuple: &'static [&'static str],
+    /// Multiplicity and purpose of the entries.
+    pub description: &'static str,
+    /// Number of entries added on every row.
+    pub count: usize,
+}
+
+impl ChipDescription {
+    /// Number of constraints added on every row.
+    pub fn num_constraints(&self) -> usize {
+        self.constraints
+            .iter()
+            .map(|constraint| constraint.count)
+            .sum()
+    }
+
+    /// Number of lookup entries added on every row.
+    pub fn num_lookups(&self) -> usize {
+        self.lookups.iter().map(|lookup| lookup.count).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Mul;
+
+    use stwo::core::{fields::qm31::SECURE_EXTENSION_DEGREE, fraction::Fraction};
+    use stwo_constraint_framework::{EvalAtRow, InfoEvaluator};
+
+    use super::*;
+    use crate::{
+        chips::{AddChip, CpuChip, RegisterMemCheckChip},
+        components::AllLookupElements,
+        extensions::ExtensionsConfig,
+        trace::eval::TraceEval,
+        traits::MachineChip,
+    };
+
+    /// Counts constraints and logup fractions, delegating masks to an [`InfoEvaluator`].
+    struct CountingEvaluator {
+        inner: InfoEvaluator,
+        constraints: usize,
+        fractions: usize,
+    }
+
+    impl EvalAtRow for CountingEvaluator {
+        type F = <InfoEvaluator as EvalAtRow>::F;
+        type EF = <InfoEvaluator as EvalAtRow>::EF;
+
+        fn next_interaction_mask<const N: usize>(
+            &mut self,
+            interaction: usize,
+            offsets: [isize; N],
+        ) -> [Self::F; N] {
+            self.inner.next_interaction_mask(interaction, offsets)
+        }
+
+        fn add_constraint<G>(&mut self, constraint: G)
+        where
+            Self::EF: Mul<G, Output = Self::EF> + From<G>,
+        {
+            self.inner.add_constraint(constraint);
+            self.constraints += 1;
+        }
+
+        fn combine_ef(values: [Self::F; SECURE_EXTENSION_DEGREE]) -> Self::EF {
+            InfoEvaluator::combine_ef(values)
+        }
+
+        fn write_logup_frac(&mut self, fraction: Fraction<Self::EF, Self::EF>) {
+            self.inner.write_logup_frac(fraction);
+            self.fractions += 1;
+        }
+
+        fn finalize_logup(&mut self) {
+            self.inner.finalize_logup();
+        }
+
+        fn finalize_logup_in_pairs(&mut self) {
+            self.inner.finalize_logup_in_pairs();
+        }
+    }
+
+    fn assert_description_matches<C: MachineChip>() {
+        let description = C::describe().expect("chip is described");
+        let mut eval = CountingEvaluator {
+            inner: InfoEvaluator::empty(),
+            constraints: 0,
+            fractions: 0,
+        };
+        let trace_eval = TraceEval::new(&mut eval);
+        C::add_constraints(
+            &mut eval,
+            &trace_eval,
+            &AllLookupElements::dummy(),
+            &ExtensionsConfig::default(),
+        );
+
+        assert_eq!(
+            description.num_constraints(),
+            eval.constraints,
+            "{}: described constraints don't match",
+            description.chip
+        );
+        assert_eq!(
+            description.num_lookups(),
+            eval.fractions,
+            "{}: described lookups don't match",
+            description.chip
+        );
+        for (idx, constraint) in description.constraints.iter().enumerate() {
+            assert!(
+                description.constraints[..idx]
+                    .iter()
+                    .all(|other| other.id != constraint.id),
+                "{}: duplicate constraint id {}",
+                description.chip,
+                constraint.id
+            );
+            assert!(constraint.degree >= 1 && !constraint.columns.is_empty());
+        }
+    }
+
+    #[test]
+    fn descriptions_match_constraints() {
+        assert_description_matches::<AddChip>();
+        assert_description_matches::<CpuChip>();
+        assert_description_matches::<RegisterMemCheckChip>();
+    }
+
+    #[test]
+    fn tuples_collect_descriptions() {
+        let mut descriptions = Vec::new();
+        <(CpuChip, AddChip, RegisterMemCheckChip)>::collect_descriptions(&mut descriptions);
+        let chips: Vec<_> = descriptions
+            .iter()
+            .map(|description| description.chip)
+            .collect();
+        assert_eq!(chips, ["CpuChip", "AddChip", "RegisterMemCheckChip"]);
+    }
+}
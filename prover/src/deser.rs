@@ -0,0 +1,666 @@
+//! Deserialization of untrusted proofs with bounded allocations.
+//!
+//! Postcard prefixes every sequence with its length, and deserializing a [`Proof`] honors these prefixes before
+//! reading the elements. [`Proof::from_bytes_bounded`] wraps the postcard deserializer so that a declared length is
+//! checked against the remaining element budget of [`DeserLimits`] before the sequence is deserialized, and every
+//! nested sequence, map, option or enum counts towards a maximum nesting depth. Once the proof is deserialized, sizes
+//! derived from its log sizes and PCS parameters are checked against the limits as well.
+//!
+//! The limits bound the work done on an invalid payload. A proof within them still has to be verified.
+
+use std::{cell::Cell, fmt};
+
+use serde::{
+    de::{
+        self, DeserializeOwned, DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess,
+    },
+    Deserializer,
+};
+use thiserror::Error;
+
+use crate::{components::LOG_CONSTRAINT_DEGREE, machine::Proof};
+
+/// Limits of [`Proof::from_bytes_bounded`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DeserLimits {
+    /// Length of the serialized proof in bytes.
+    pub max_bytes: usize,
+    /// Elements of all sequences and entries of all maps, fixed-size arrays included.
+    pub max_elements: usize,
+    /// Nesting of sequences, maps, options and enums.
+    pub max_depth: usize,
+    /// Largest log size of a component trace.
+    pub max_log_size: u32,
+    /// Largest number of FRI queries.
+    pub max_queries: usize,
+}
+
+impl Default for DeserLimits {
+    /// Limits well above the proofs of traces of up to `2^24` rows with the default configuration.
+    fn default() -> Self {
+        Self {
+            max_bytes: 1 << 28,
+            max_elements: 1 << 24,
+            max_depth: 32,
+            max_log_size: nexus_common::trace_size::MAX_LOG_SIZE,
+            max_queries: 256,
+        }
+    }
+}
+
+/// Reasons for [`Proof::from_bytes_bounded`] to reject a payload.
+#[derive(Debug, Error)]
+pub enum DeserError {
+    #[error("payload has more than {limit} elements")]
+    ElementLimit { limit: usize },
+
+    #[error("payload is nested deeper than {limit}")]
+    DepthLimit { limit: usize },
+
+    #[error("{field} is {value}, at most {limit} is allowed")]
+    SizeLimit {
+        field: &'static str,
+        value: usize,
+        limit: usize,
+    },
+
+    #[error("serialization error: {0}")]
+    Serialization(#[from] postcard::Error),
+}
+
+impl Proof {
+    /// Deserializes a proof from its canonical (postcard) serialization within `limits`, see the
+    /// [module documentation](crate::deser).
+    pub fn from_bytes_bounded(bytes: &[u8], limits: DeserLimits) -> Result<Self, DeserError> {
+        check_size("serialized length", bytes.len(), limits.max_bytes)?;
+        let proof: Self = deserialize_bounded(bytes, limits)?;
+
+        let max_log_size = proof.log_size.iter().copied().max().unwrap_or(0);
+        check_size(
+            "log size",
+            max_log_size as usize,
+            limits.max_log_size as usize,
+        )?;
+        check_size(
+            "number of FRI queries",
+            proof.metadata.pcs_params.n_queries,
+            limits.max_queries,
+        )?;
+        // Inner FRI layers halve the composition polynomial domain, there can't be more than its log size.
+        check_size(
+            "number of FRI layers",
+            proof.stark_proof.fri_proof.inner_layers.len(),
+            (max_log_size + LOG_CONSTRAINT_DEGREE) as usize,
+        )?;
+        Ok(proof)
+    }
+}
+
+fn check_size(field: &'static str, value: usize, limit: usize) -> Result<(), DeserError> {
+    if value > limit {
+        return Err(DeserError::SizeLimit {
+            field,
+            value,
+            limit,
+        });
+    }
+    Ok(())
+}
+
+/// Deserializes any postcard payload within the element and depth limits.
+pub(crate) fn deserialize_bounded<T: DeserializeOwned>(
+    bytes: &[u8],
+    limits: DeserLimits,
+) -> Result<T, DeserError> {
+    let budget = Budget {
+        limits,
+        elements: Cell::new(0),
+        depth: Cell::new(0),
+        exceeded: Cell::new(None),
+    };
+    let mut deserializer = postcard::Deserializer::from_bytes(bytes);
+    T::deserialize(Bounded {
+        inner: &mut deserializer,
+        budget: &budget,
+    })
+    // Postcard reports limits as custom errors, the budget knows which one was exceeded.
+    .map_err(|err| {
+        budget
+            .exceeded
+            .take()
+            .unwrap_or(DeserError::Serialization(err))
+    })
+}
+
+/// Elements and depth consumed so far, shared by all wrappers of one deserialization.
+struct Budget {
+    limits: DeserLimits,
+    elements: Cell<usize>,
+    depth: Cell<usize>,
+    exceeded: Cell<Option<DeserError>>,
+}
+
+impl Budget {
+    /// Reserves `count` elements, before any of them is deserialized.
+    fn reserve<E: de::Error>(&self, count: usize) -> Result<(), E> {
+        let limit = self.limits.max_elements;
+        match self.elements.get().checked_add(count) {
+            Some(elements) if elements <= limit => {
+                self.elements.set(elements);
+                Ok(())
+            }
+            _ => Err(self.exceed(DeserError::ElementLimit { limit })),
+        }
+    }
+
+    /// Runs `f` one nesting level deeper.
+    fn nested<T, E: de::Error>(&self, f: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+        let limit = self.limits.max_depth;
+        if self.depth.get() >= limit {
+            return Err(self.exceed(DeserError::DepthLimit { limit }));
+        }
+        self.depth.set(self.depth.get() + 1);
+        let result = f();
+        self.depth.set(self.depth.get() - 1);
+        result
+    }
+
+    fn exceed<E: de::Error>(&self, err: DeserError) -> E {
+        let msg = E::custom(&err);
+        self.exceeded.set(Some(err));
+        msg
+    }
+}
+
+/// Deserializer enforcing the budget on everything it deserializes.
+struct Bounded<'b, D> {
+    inner: D,
+    budget: &'b Budget,
+}
+
+impl<'b, D> Bounded<'b, D> {
+    fn visitor<V>(&self, inner: V) -> BoundedVisitor<'b, V> {
+        BoundedVisitor {
+            inner,
+            budget: self.budget,
+        }
+    }
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident),+ $(,)?) => {
+        $(
+            fn $method<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+                let visitor = self.visitor(visitor);
+                self.inner.$method(visitor)
+            }
+        )+
+    };
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for Bounded<'_, D> {
+    type Error = D::Error;
+
+    forward_deserialize! {
+        deserialize_any, deserialize_bool, deserialize_i8, deserialize_i16, deserialize_i32, deserialize_i64,
+        deserialize_i128, deserialize_u8, deserialize_u16, deserialize_u32, deserialize_u64, deserialize_u128,
+        deserialize_f32, deserialize_f64, deserialize_char, deserialize_str, deserialize_string,
+        deserialize_bytes, deserialize_byte_buf, deserialize_option, deserialize_unit, deserialize_seq,
+        deserialize_map, deserialize_identifier, deserialize_ignored_any,
+    }
+
+    fn deserialize_unit_struct<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        let visitor = self.visitor(visitor);
+        self.inner.deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        let visitor = self.visitor(visitor);
+        self.inner.deserialize_newtype_struct(name, visitor)
+    }
+
+    fn deserialize_tuple<V: de::Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        let visitor = self.visitor(visitor);
+        self.inner.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_tuple_struct<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        let visitor = self.visitor(visitor);
+        self.inner.deserialize_tuple_struct(name, len, visitor)
+    }
+
+    fn deserialize_struct<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        let visitor = self.visitor(visitor);
+        self.inner.deserialize_struct(name, fields, visitor)
+    }
+
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        let visitor = self.visitor(visitor);
+        self.inner.deserialize_enum(name, variants, visitor)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+/// Visitor checking declared lengths and nesting before handing compound values to the wrapped visitor.
+struct BoundedVisitor<'b, V> {
+    inner: V,
+    budget: &'b Budget,
+}
+
+macro_rules! forward_visit {
+    ($($method:ident($ty:ty)),+ $(,)?) => {
+        $(
+            fn $method<E: de::Error>(self, v: $ty) -> Result<V::Value, E> {
+                self.inner.$method(v)
+            }
+        )+
+    };
+}
+
+impl<'de, V: de::Visitor<'de>> de::Visitor<'de> for BoundedVisitor<'_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.inner.expecting(f)
+    }
+
+    forward_visit! {
+        visit_bool(bool), visit_i8(i8), visit_i16(i16), visit_i32(i32), visit_i64(i64), visit_i128(i128),
+        visit_u8(u8), visit_u16(u16), visit_u32(u32), visit_u64(u64), visit_u128(u128), visit_f32(f32),
+        visit_f64(f64), visit_char(char), visit_str(&str), visit_borrowed_str(&'de str), visit_string(String),
+        visit_bytes(&[u8]), visit_borrowed_bytes(&'de [u8]), visit_byte_buf(Vec<u8>),
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<V::Value, E> {
+        self.inner.visit_none()
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<V::Value, E> {
+        self.inner.visit_unit()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<V::Value, D::Error> {
+        let Self { inner, budget } = self;
+        budget.nested(|| {
+            inner.visit_some(Bounded {
+                inner: deserializer,
+                budget,
+            })
+        })
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<V::Value, D::Error> {
+        let Self { inner, budget } = self;
+        budget.nested(|| {
+            inner.visit_newtype_struct(Bounded {
+                inner: deserializer,
+                budget,
+            })
+        })
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<V::Value, A::Error> {
+        let Self { inner, budget } = self;
+        // The wrapped visitor may allocate for the declared length, it has to fit in the budget.
+        budget.reserve::<A::Error>(seq.size_hint().unwrap_or(0))?;
+        budget.nested(|| inner.visit_seq(BoundedSeq { inner: seq, budget }))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<V::Value, A::Error> {
+        let Self { inner, budget } = self;
+        budget.reserve::<A::Error>(map.size_hint().unwrap_or(0))?;
+        budget.nested(|| inner.visit_map(BoundedMap { inner: map, budget }))
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<V::Value, A::Error> {
+        let Self { inner, budget } = self;
+        budget.nested(|| {
+            inner.visit_enum(BoundedEnum {
+                inner: data,
+                budget,
+            })
+        })
+    }
+}
+
+/// Seed deserializing an element of a sequence or map, or the content of an enum variant, with the budget.
+struct BoundedSeed<'b, S> {
+    inner: S,
+    budget: &'b Budget,
+}
+
+impl<'de, S: DeserializeSeed<'de>> DeserializeSeed<'de> for BoundedSeed<'_, S> {
+    type Value = S::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<S::Value, D::Error> {
+        self.inner.deserialize(Bounded {
+            inner: deserializer,
+            budget: self.budget,
+        })
+    }
+}
+
+/// Sequence whose elements count towards the budget.
+///
+/// The declared length is reserved when the sequence starts, elements past it are reserved one by one.
+struct BoundedSeq<'b, A> {
+    inner: A,
+    budget: &'b Budget,
+}
+
+impl<'de, A: SeqAccess<'de>> SeqAccess<'de> for BoundedSeq<'_, A> {
+    type Error = A::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, A::Error> {
+        if self.inner.size_hint().is_none() {
+            self.budget.reserve::<A::Error>(1)?;
+        }
+        self.inner.next_element_seed(BoundedSeed {
+            inner: seed,
+            budget: self.budget,
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+/// Map whose entries count towards the budget, like [`BoundedSeq`].
+struct BoundedMap<'b, A> {
+    inner: A,
+    budget: &'b Budget,
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for BoundedMap<'_, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, A::Error> {
+        if self.inner.size_hint().is_none() {
+            self.budget.reserve::<A::Error>(1)?;
+        }
+        self.inner.next_key_seed(BoundedSeed {
+            inner: seed,
+            budget: self.budget,
+        })
+    }
+
+    fn next_value_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<T::Value, A::Error> {
+        self.inner.next_value_seed(BoundedSeed {
+            inner: seed,
+            budget: self.budget,
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+struct BoundedEnum<'b, A> {
+    inner: A,
+    budget: &'b Budget,
+}
+
+impl<'de, 'b, A: EnumAccess<'de>> EnumAccess<'de> for BoundedEnum<'b, A> {
+    type Error = A::Error;
+    type Variant = BoundedEnum<'b, A::Variant>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), A::Error> {
+        let budget = self.budget;
+        let (value, variant) = self.inner.variant_seed(seed)?;
+        Ok((
+            value,
+            BoundedEnum {
+                inner: variant,
+                budget,
+            },
+        ))
+    }
+}
+
+impl<'de, A: VariantAccess<'de>> VariantAccess<'de> for BoundedEnum<'_, A> {
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), A::Error> {
+        self.inner.unit_variant()
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, A::Error> {
+        self.inner.newtype_variant_seed(BoundedSeed {
+            inner: seed,
+            budget: self.budget,
+        })
+    }
+
+    fn tuple_variant<V: de::Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, A::Error> {
+        self.inner.tuple_variant(
+            len,
+            BoundedVisitor {
+                inner: visitor,
+                budget: self.budget,
+            },
+        )
+    }
+
+    fn struct_variant<V: de::Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, A::Error> {
+        self.inner.struct_variant(
+            fields,
+            BoundedVisitor {
+                inner: visitor,
+                budget: self.budget,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nexus_vm::{riscv::builder::ProgramBuilder, trace::k_trace_direct};
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::machine::{BaseComponent, Machine};
+
+    fn proof_bytes() -> (Vec<u8>, nexus_vm::emulator::View) {
+        let program = ProgramBuilder::new()
+            .addi(1, 0, 1)
+            .add(2, 1, 1)
+            .sub(3, 2, 1)
+            .build()
+            .unwrap();
+        let (view, trace) = k_trace_direct(&program, 1).expect("error generating trace");
+        let proof = Machine::<BaseComponent>::prove(&trace, &view).unwrap();
+        (postcard::to_stdvec(&proof).unwrap(), view)
+    }
+
+    /// Postcard varint of `value`.
+    fn varint(mut value: u64) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        while value >= 0x80 {
+            bytes.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        bytes.push(value as u8);
+        bytes
+    }
+
+    #[test]
+    fn bounded_proof_round_trip() {
+        let (bytes, view) = proof_bytes();
+        let proof = Proof::from_bytes_bounded(&bytes, DeserLimits::default()).unwrap();
+        assert_eq!(postcard::to_stdvec(&proof).unwrap(), bytes);
+        crate::verify(proof, &view).unwrap();
+    }
+
+    #[test]
+    fn truncated_proofs_are_rejected() {
+        let (bytes, _) = proof_bytes();
+        for len in (0..bytes.len()).step_by(97) {
+            assert!(matches!(
+                Proof::from_bytes_bounded(&bytes[..len], DeserLimits::default()),
+                Err(DeserError::Serialization(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn inflated_lengths_are_rejected_before_allocation() {
+        // Nested vectors declaring 2^32 elements each, with nothing following the prefixes.
+        let mut payload = varint(1 << 32);
+        payload.extend(varint(1 << 32));
+        assert!(matches!(
+            deserialize_bounded::<Vec<Vec<u64>>>(&payload, DeserLimits::default()),
+            Err(DeserError::ElementLimit { .. })
+        ));
+
+        // Many small vectors add up.
+        let limits = DeserLimits {
+            max_elements: 1000,
+            ..DeserLimits::default()
+        };
+        let mut payload = varint(100);
+        for _ in 0..100 {
+            payload.extend(varint(20));
+            payload.extend([0; 20]);
+        }
+        assert!(matches!(
+            deserialize_bounded::<Vec<Vec<u8>>>(&payload, limits),
+            Err(DeserError::ElementLimit { limit: 1000 })
+        ));
+
+        let (bytes, _) = proof_bytes();
+        let limits = DeserLimits {
+            max_elements: 100,
+            ..DeserLimits::default()
+        };
+        assert!(matches!(
+            Proof::from_bytes_bounded(&bytes, limits),
+            Err(DeserError::ElementLimit { limit: 100 })
+        ));
+    }
+
+    #[test]
+    fn deep_nesting_is_rejected() {
+        #[derive(Deserialize)]
+        #[allow(dead_code)]
+        struct Nested(Option<Box<Nested>>);
+
+        // A million levels would overflow the stack without the depth limit.
+        let mut payload = vec![1u8; 1 << 20];
+        payload.push(0);
+        assert!(matches!(
+            deserialize_bounded::<Nested>(&payload, DeserLimits::default()),
+            Err(DeserError::DepthLimit { limit: 32 })
+        ));
+
+        let shallow = [1, 1, 0];
+        deserialize_bounded::<Nested>(&shallow, DeserLimits::default()).unwrap();
+    }
+
+    #[test]
+    fn derived_sizes_are_checked() {
+        let (bytes, _) = proof_bytes();
+        let limits = DeserLimits {
+            max_bytes: bytes.len() - 1,
+            ..DeserLimits::default()
+        };
+        assert!(matches!(
+            Proof::from_bytes_bounded(&bytes, limits),
+            Err(DeserError::SizeLimit {
+                field: "serialized length",
+                ..
+            })
+        ));
+        let limits = DeserLimits {
+            max_log_size: 4,
+            ..DeserLimits::default()
+        };
+        assert!(matches!(
+            Proof::from_bytes_bounded(&bytes, limits),
+            Err(DeserError::SizeLimit {
+                field: "log size",
+                ..
+            })
+        ));
+        let limits = DeserLimits {
+            max_queries: 1,
+            ..DeserLimits::default()
+        };
+        assert!(matches!(
+            Proof::from_bytes_bounded(&bytes, limits),
+            Err(DeserError::SizeLimit {
+                field: "number of FRI queries",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn corrupted_proofs_fail_cleanly() {
+        let (bytes, _) = proof_bytes();
+        // Deterministic xorshift, every corrupted payload must be rejected or deserialized without panicking.
+        let mut state = 0x9e3779b97f4a7c15u64;
+        for _ in 0..256 {
+            let mut corrupted = bytes.clone();
+            for _ in 0..4 {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                let idx = state as usize % corrupted.len();
+                corrupted[idx] = (state >> 32) as u8 | 0x80;
+            }
+            let _ = Proof::from_bytes_bounded(&corrupted, DeserLimits::default());
+        }
+    }
+}
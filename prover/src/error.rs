@@ -0,0 +1,292 @@
+use nexus_common::consistency::SharedConstantMismatch;
+use nexus_vm::{
+    emulator::MemoryInitializationError,
+    riscv::{Opcode, Register},
+};
+use thiserror::Error;
+
+/// Errors that can occur while proving a zkVM execution.
+///
+/// Malformed inputs are reported with a variant rather than a panic, errors of stwo itself are wrapped in
+/// [`Self::Stwo`].
+#[derive(Debug, Error)]
+pub enum NexusProvingError {
+    /// An error raised by the underlying stwo prover.
+    #[error(transparent)]
+    Stwo(#[from] stwo::prover::ProvingError),
+
+    /// The initial memory supplied by the view is malformed.
+    #[error("invalid initial memory: {0}")]
+    InvalidInitialMemory(#[from] MemoryInitializationError),
+
+    /// The view and the trace do not describe the same execution.
+    #[error("inconsistent view and trace: {0}")]
+    InconsistentInputs(String),
+
+    /// Reading or writing a prover checkpoint failed.
+    #[error("checkpoint i/o error: {0}")]
+    CheckpointIo(#[from] std::io::Error),
+
+    /// A prover checkpoint is malformed or doesn't match the machine resuming from it.
+    #[error("invalid checkpoint: {0}")]
+    InvalidCheckpoint(String),
+
+    /// Proving was cancelled through a [`CancellationToken`](crate::cancellation::CancellationToken).
+    #[error(transparent)]
+    Aborted(#[from] ProvingAborted),
+
+    /// The trace executes an instruction that no chip of the machine constrains.
+    #[error(transparent)]
+    UnsupportedOpcode(#[from] UnsupportedOpcode),
+
+    /// The trace accesses registers inconsistently, see
+    /// [`validate_register_accesses`](crate::trace::validate_register_accesses).
+    #[error(transparent)]
+    RegisterAccess(#[from] RegisterAccessError),
+
+    /// The program trace template of the prover context was built for another program or trace size.
+    #[error(transparent)]
+    ProgramTemplate(#[from] ProgramTemplateMismatch),
+
+    /// The traces of the execution would exceed the largest log size supported by the machine, see
+    /// [`MAX_LOG_SIZE`](nexus_common::trace_size::MAX_LOG_SIZE), or by the
+    /// [`ProverContext`](crate::machine::ProverContext) used to prove them.
+    #[error("trace of log size {log_size} exceeds the maximum of {max_log_size}")]
+    TraceTooLarge { log_size: u32, max_log_size: u32 },
+
+    /// A block of the trace doesn't hold exactly one step, only traces with `k = 1` can be proved.
+    #[error("block {block} of the trace has {num_steps} steps, expected one")]
+    UnsupportedBlockSize { block: usize, num_steps: usize },
+
+    /// A lookup table is used more often than its multiplicity column can represent.
+    #[error("multiplicity of the {table} lookup table overflows the base field")]
+    MultiplicityOverflow { table: &'static str },
+
+    /// The exit code or the public output of the view can't have been written by the guest.
+    #[error(transparent)]
+    PublicOutput(#[from] PublicOutputError),
+
+    /// The prover assumes a constant that differs from the one shared with the emulator, see
+    /// [`nexus_common::consistency`].
+    #[error(transparent)]
+    SharedConstants(#[from] SharedConstantMismatch),
+}
+
+/// Former name of [`NexusProvingError`].
+#[deprecated(since = "0.3.5", note = "renamed to `NexusProvingError`")]
+pub type ProvingError = NexusProvingError;
+
+/// Proving was stopped by its [`CancellationToken`](crate::cancellation::CancellationToken).
+#[derive(Debug, Error)]
+#[error("proving was cancelled")]
+pub struct ProvingAborted;
+
+/// An instruction of the trace isn't constrained by any chip of the machine, see
+/// [`Machine::supported_opcodes`](crate::machine::Machine::supported_opcodes). Custom opcodes are only supported
+/// by the extension constraining them.
+#[derive(Debug, Error)]
+#[error("{opcode} at pc 0x{pc:08x} (row {row}) is not supported by the machine")]
+pub struct UnsupportedOpcode {
+    pub opcode: Opcode,
+    pub pc: u32,
+    pub row: usize,
+}
+
+/// A register access of the trace that the register memory check would reject, found by
+/// [`validate_register_accesses`](crate::trace::validate_register_accesses).
+///
+/// Access timestamps are those of the register memory check, the `slot`-th access of the step at timestamp `ts` happens
+/// at `3·ts + slot + 1`, reads first.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RegisterAccessError {
+    /// A read doesn't see the value last written to the register.
+    #[error("row {row}: {register} reads 0x{found:08x}, last written value is 0x{expected:08x}")]
+    StaleRead {
+        row: usize,
+        register: Register,
+        expected: u32,
+        found: u32,
+    },
+
+    /// A register is accessed at a timestamp not after its previous access, `expected` is the previous one.
+    #[error("row {row}: {register} is accessed at timestamp {found}, not after its previous access at {expected}")]
+    NonIncreasingTimestamp {
+        row: usize,
+        register: Register,
+        expected: u32,
+        found: u32,
+    },
+
+    /// `x0` reads a non-zero value.
+    #[error("row {row}: zero reads 0x{found:08x}")]
+    NonZeroX0 { row: usize, found: u32 },
+}
+
+/// Exit code or public output entries that the guest couldn't have written, found by
+/// [`check_public_output`](crate::machine::check_public_output).
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PublicOutputError {
+    /// The exit code isn't a word at consecutive addresses from `expected`, the start of the output memory.
+    #[error("exit code isn't a word at address 0x{expected:08x}")]
+    MalformedExitCode { expected: u32 },
+
+    /// Two public output entries have the same address.
+    #[error("public output address 0x{address:08x} appears more than once")]
+    DuplicateAddress { address: u32 },
+
+    /// A public output entry lies outside of the output region, or on the exit code.
+    #[error("public output entry at address 0x{address:08x} is outside of the output region")]
+    OutsideOutputRegion { address: u32 },
+
+    /// The guest committed its output, but the public output isn't exactly the committed bytes.
+    #[error("public output has {len} bytes, the guest committed {committed}")]
+    UncommittedOutput { committed: u32, len: usize },
+}
+
+/// A [`ProgramTraceTemplate`](crate::trace::program_trace::ProgramTraceTemplate) was applied to traces it wasn't
+/// built for.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ProgramTemplateMismatch {
+    /// The template was built for another program. Hashes are those of
+    /// [`ProgramTraceTemplate::program_hash`](crate::trace::program_trace::ProgramTraceTemplate::program_hash).
+    #[error(
+        "program trace template was built for program {}, got {}",
+        hex_id(expected),
+        hex_id(actual)
+    )]
+    Program {
+        expected: [u8; 32],
+        actual: [u8; 32],
+    },
+
+    /// The template was built for another trace size.
+    #[error("program trace template has log size {expected}, the traces have log size {actual}")]
+    LogSize { expected: u32, actual: u32 },
+}
+
+/// Inconsistencies found by [`Machine::check_trace`](crate::machine::Machine::check_trace).
+#[derive(Debug, Error)]
+pub enum TraceCheckError {
+    /// Traces couldn't be built from the execution.
+    #[error(transparent)]
+    Proving(#[from] NexusProvingError),
+
+    /// A constraint evaluates to a non-zero value. `constraint` is the index among constraints of `chip`.
+    #[error("constraint {constraint} of {chip} is not satisfied at row {row}")]
+    ConstraintNotSatisfied {
+        chip: &'static str,
+        row: usize,
+        constraint: usize,
+    },
+
+    /// The interaction trace doesn't match the lookups of the row.
+    #[error("logup constraint is not satisfied at row {row}")]
+    LogupNotSatisfied { row: usize },
+
+    /// Lookups of the machine and its extensions are unbalanced.
+    #[error("claimed logup sums don't add up to zero")]
+    LogupSumNotZero,
+}
+
+/// Errors that can occur while verifying a zkVM proof.
+#[derive(Debug, Error)]
+pub enum VerificationError {
+    /// An error raised by the underlying stwo verifier.
+    #[error(transparent)]
+    Stwo(#[from] stwo::core::verifier::VerificationError),
+
+    /// The proof was generated for a different set of components than the verifier expects.
+    #[error(
+        "components mismatch: verifier expects {}, proof was generated for {}",
+        hex_id(expected),
+        hex_id(actual)
+    )]
+    ComponentsMismatch {
+        expected: [u8; 32],
+        actual: [u8; 32],
+    },
+
+    /// A streamed public output entry has a lower address than the entry before it.
+    #[error("public output entry at address 0x{address:08x} is out of order")]
+    UnsortedPublicOutput { address: u32 },
+
+    /// The public memory entries don't fit into the trace sizes of the proof.
+    #[error("public memory entries don't fit into the proof")]
+    PublicMemoryOverflow,
+
+    /// The program trace template given to the verifier was built for another program or log size.
+    #[error(transparent)]
+    ProgramTemplate(#[from] ProgramTemplateMismatch),
+
+    /// The conjectured security level of the proof is below the minimum required by the verifier.
+    #[error("proof has {conjectured_bits} bits of conjectured security, at least {min_bits} are required")]
+    InsufficientSecurity {
+        conjectured_bits: u32,
+        min_bits: u32,
+    },
+
+    /// The log size of the proof is not allowed by the verifier policy.
+    #[error("proof has log size {log_size}, the policy allows {allowed:?}")]
+    LogSizeNotAllowed { log_size: u32, allowed: Vec<u32> },
+
+    /// The public output is longer than allowed by the verifier policy.
+    #[error("public output of {len} bytes exceeds the maximum of {max}")]
+    PublicOutputTooLong { len: usize, max: usize },
+
+    /// The verifier artifacts were computed for other chips, or lack the log size of the proof.
+    #[error("verifier artifacts don't match the machine or the proof")]
+    ArtifactsMismatch,
+
+    /// The exit code or the public output given to the verifier can't have been written by the guest.
+    #[error(transparent)]
+    PublicOutput(#[from] PublicOutputError),
+}
+
+/// Errors returned by [`verify_with_exit_code`](crate::verify_with_exit_code) and
+/// [`verify_with_output_claims`](crate::verify_with_output_claims).
+#[derive(Debug, Error)]
+pub enum VerifyOrExitError {
+    /// The proof is invalid.
+    #[error(transparent)]
+    Verification(#[from] VerificationError),
+
+    /// The guest exited with an exit code other than the expected one.
+    #[error("guest exited with code {actual}, expected {expected}")]
+    UnexpectedExitCode { expected: u32, actual: u32 },
+
+    /// The guest didn't exit, e.g. it was stopped by the cycle limit or trapped in a loop.
+    #[error("guest did not exit")]
+    NoExitCode,
+
+    /// The public output doesn't hold the claimed bytes at the given offset.
+    #[error("public output at offset {offset} is {actual:02x?}, claimed {expected:02x?}")]
+    OutputClaimViolated {
+        offset: u32,
+        expected: Vec<u8>,
+        actual: Vec<u8>,
+    },
+
+    /// No output region has the given name.
+    #[error("no output region {0:?}")]
+    UnknownOutputRegion(String),
+
+    /// The expected bytes of an output region are longer than the region.
+    #[error("output region {region:?} has {len} bytes, expected {expected_len}")]
+    OutputRegionTooShort {
+        region: String,
+        len: u32,
+        expected_len: usize,
+    },
+
+    /// An output region doesn't hold the expected bytes.
+    #[error("output region {region:?} is {actual:02x?}, expected {expected:02x?}")]
+    OutputRegionMismatch {
+        region: String,
+        expected: Vec<u8>,
+        actual: Vec<u8>,
+    },
+}
+
+fn hex_id(id: &[u8; 32]) -> String {
+    id.iter().map(|byte| format!("{byte:02x}")).collect()
+}
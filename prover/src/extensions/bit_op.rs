@@ -125,6 +125,7 @@ impl BuiltInExtension for BitOpMultiplicity {
             log_size,
             preprocessed_trace,
             original_trace,
+            program_trace: Vec::new(),
         }
     }
 
@@ -132,10 +133,13 @@ impl BuiltInExtension for BitOpMultiplicity {
         BitOpMultiplicityEval::LOG_SIZE
     }
 
+    fn accepts_log_size(&self, log_size: u32) -> bool {
+        log_size == BitOpMultiplicityEval::LOG_SIZE
+    }
+
     fn generate_preprocessed_trace(
         &self,
         _log_size: u32,
-        _program_trace_ref: ProgramTraceRef,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
         let base_cols = Self::preprocessed_base_columns();
         let domain = CanonicCoset::new(BitOpMultiplicityEval::LOG_SIZE).circle_domain();
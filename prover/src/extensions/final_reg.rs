@@ -21,13 +21,16 @@ use stwo_constraint_framework::{
 };
 
 use nexus_common::constants::NUM_REGISTERS;
-use nexus_vm::WORD_SIZE;
+use nexus_vm::{emulator::ProgramInfo, WORD_SIZE};
 
 use super::{BuiltInExtension, ComponentTrace, FrameworkEvalExt};
 use crate::{
     chips::memory_check::register_mem_check::RegisterCheckLookupElements,
     components::AllLookupElements,
-    trace::{program_trace::ProgramTraceRef, sidenote::SideNote, utils::IntoBaseFields},
+    trace::{
+        eval::PROGRAM_TRACE_IDX, program_trace::ProgramTraceRef, sidenote::SideNote,
+        utils::IntoBaseFields,
+    },
 };
 
 /// A column with {0, ..., 31}
@@ -46,7 +49,10 @@ impl RegisterIdx {
     }
 }
 
-/// A component for the final register memory state
+/// A component for the initial and final register memory state
+///
+/// Registers start at zero, except for the arguments passed to the entrypoint, see [`ProgramInfo::initial_args`].
+/// Initial values are part of the program trace, so that the verifier checks them against the program info.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct FinalReg {
     _private: (),
@@ -84,10 +90,17 @@ impl FrameworkEval for FinalRegEval {
         let reg_idx = eval.get_preprocessed_column(reg_idx.id());
         let final_timestamp: Vec<_> = (0..4).map(|_| eval.next_trace_mask()).collect();
         let final_value: Vec<_> = (0..4).map(|_| eval.next_trace_mask()).collect();
+        // The initial value of each register, a byte of the word per column.
+        let init_value: Vec<_> = (0..WORD_SIZE)
+            .map(|_| eval.next_interaction_mask(PROGRAM_TRACE_IDX, [0])[0].clone())
+            .collect();
 
-        // Add initial register memory state
+        // Add initial register memory state, accessed at timestamp zero
         let mut tuple: [E::F; Self::TUPLE_SIZE] = std::array::from_fn(|_| E::F::zero());
         tuple[0] = reg_idx.clone();
+        for (i, elm) in init_value.into_iter().enumerate() {
+            tuple[1 + WORD_SIZE + i] = elm;
+        }
         let numerator = E::F::one();
 
         eval.add_to_relation(RelationEntry::new(
@@ -138,12 +151,24 @@ impl BuiltInExtension for FinalReg {
         FinalRegEval::LOG_SIZE
     }
 
+    fn accepts_log_size(&self, log_size: u32) -> bool {
+        log_size == FinalRegEval::LOG_SIZE
+    }
+
     fn generate_preprocessed_trace(
         &self,
         _log_size: u32,
-        _program_trace_ref: ProgramTraceRef,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
-        let base_cols = Self::preprocessed_base_columns();
+        let domain = CanonicCoset::new(FinalRegEval::LOG_SIZE).circle_domain();
+        vec![CircleEvaluation::new(domain, Self::register_idx_column())]
+    }
+
+    fn generate_program_trace(
+        &self,
+        _log_size: u32,
+        program_trace_ref: ProgramTraceRef,
+    ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
+        let base_cols = Self::init_value_columns(program_trace_ref.program_memory);
         let domain = CanonicCoset::new(FinalRegEval::LOG_SIZE).circle_domain();
         base_cols
             .into_iter()
@@ -157,16 +182,16 @@ impl BuiltInExtension for FinalReg {
     fn generate_component_trace(
         &self,
         log_size: u32,
-        _: ProgramTraceRef,
+        program_trace_ref: ProgramTraceRef,
         side_note: &mut SideNote,
     ) -> ComponentTrace {
-        let preprocessed_trace = Self::preprocessed_base_columns();
         let original_trace = Self::base_columns(side_note);
 
         ComponentTrace {
             log_size,
-            preprocessed_trace,
+            preprocessed_trace: vec![Self::register_idx_column()],
             original_trace,
+            program_trace: Self::init_value_columns(program_trace_ref.program_memory),
         }
     }
 
@@ -187,6 +212,7 @@ impl BuiltInExtension for FinalReg {
 
         let mut logup_trace_gen = LogupTraceGenerator::new(FinalRegEval::LOG_SIZE);
         let row_idx = &component_trace.preprocessed_trace[0];
+        let init_values = &component_trace.program_trace;
         let base_cols = &component_trace.original_trace;
 
         // Adding the initial register memory state and subtracting the final register memory state
@@ -196,6 +222,9 @@ impl BuiltInExtension for FinalReg {
             let mut tuple: [PackedBaseField; FinalRegEval::TUPLE_SIZE] =
                 [BaseField::zero().into(); FinalRegEval::TUPLE_SIZE]; // reg_idx, cur_timestamp, cur_value
             tuple[0] = row_idx; // Use row_idx as register index
+            for (i, col) in init_values.iter().enumerate() {
+                tuple[1 + WORD_SIZE + i] = col.data[vec_row];
+            }
             let denom_a: PackedSecureField = lookup_element.combine(tuple.as_slice());
             let numerator_a: PackedSecureField =
                 PackedBaseField::broadcast(BaseField::one()).into();
@@ -220,9 +249,17 @@ impl BuiltInExtension for FinalReg {
 }
 
 impl FinalReg {
-    fn preprocessed_base_columns() -> Vec<BaseColumn> {
-        let reg_idx = BaseColumn::from_iter((0..32).map(BaseField::from));
-        vec![reg_idx]
+    fn register_idx_column() -> BaseColumn {
+        BaseColumn::from_iter((0..32).map(BaseField::from))
+    }
+
+    fn init_value_columns(program_info: &ProgramInfo) -> Vec<BaseColumn> {
+        let init_values = program_info
+            .initial_registers()
+            .map(|value| value.into_base_fields());
+        (0..WORD_SIZE)
+            .map(|i| BaseColumn::from_iter(init_values.iter().map(|val| val[i])))
+            .collect()
     }
     fn base_columns(side_note: &SideNote) -> Vec<BaseColumn> {
         let mut base_cols: Vec<BaseColumn> = vec![];
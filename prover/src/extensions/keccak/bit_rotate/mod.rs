@@ -114,7 +114,6 @@ impl BuiltInExtension for BitRotateTable {
     fn generate_preprocessed_trace(
         &self,
         _: u32,
-        _: ProgramTraceRef,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
         let base_cols = Self::preprocessed_base_columns();
         let domain = CanonicCoset::new(Self::Eval::LOG_SIZE).circle_domain();
@@ -138,6 +137,7 @@ impl BuiltInExtension for BitRotateTable {
             log_size: Self::Eval::LOG_SIZE,
             preprocessed_trace: preprocessed,
             original_trace: vec![mult],
+            program_trace: Vec::new(),
         }
     }
 
@@ -174,6 +174,10 @@ impl BuiltInExtension for BitRotateTable {
         Self::Eval::LOG_SIZE
     }
 
+    fn accepts_log_size(&self, log_size: u32) -> bool {
+        log_size == Self::Eval::LOG_SIZE
+    }
+
     fn preprocessed_trace_sizes(_: u32) -> Vec<u32> {
         vec![Self::Eval::LOG_SIZE; 4]
     }
@@ -157,7 +157,6 @@ impl<const ELEM_BITS: u32, const EXPAND_BITS: u32, R: RegisteredLookupBound, B:
     fn generate_preprocessed_trace(
         &self,
         _log_size: u32,
-        _program_trace_ref: ProgramTraceRef,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
         if B::PREPROCESSED_TRACE_GEN {
             let table = preprocessed_columns::BitwiseTable::new(ELEM_BITS, EXPAND_BITS, 0);
@@ -192,6 +191,7 @@ impl<const ELEM_BITS: u32, const EXPAND_BITS: u32, R: RegisteredLookupBound, B:
             log_size: Self::Eval::LOG_SIZE,
             preprocessed_trace: preprocessed,
             original_trace: mults,
+            program_trace: Vec::new(),
         }
     }
 
@@ -215,6 +215,10 @@ impl<const ELEM_BITS: u32, const EXPAND_BITS: u32, R: RegisteredLookupBound, B:
         Self::Eval::LOG_SIZE
     }
 
+    fn accepts_log_size(&self, log_size: u32) -> bool {
+        log_size == Self::Eval::LOG_SIZE
+    }
+
     fn preprocessed_trace_sizes(_log_size: u32) -> Vec<u32> {
         if B::PREPROCESSED_TRACE_GEN {
             vec![Self::Eval::LOG_SIZE; 4]
@@ -84,7 +84,6 @@ impl BuiltInExtension for PermutationMemoryCheck {
     fn generate_preprocessed_trace(
         &self,
         log_size: u32,
-        _: ProgramTraceRef,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
         let base_col = trace::preprocessed_is_last_column(log_size);
         let domain = CanonicCoset::new(log_size).circle_domain();
@@ -119,6 +119,7 @@ pub fn generate_keccak_mem_check_trace(log_size: u32, side_note: &SideNote) -> C
         log_size,
         preprocessed_trace,
         original_trace,
+        program_trace: Vec::new(),
     }
 }
 
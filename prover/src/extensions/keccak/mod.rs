@@ -46,7 +46,7 @@ mod tests {
         traits::MachineChip,
     };
     use nexus_vm::{
-        emulator::InternalView,
+        emulator::{canonical_public_output, InternalView},
         riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
         trace::k_trace_direct,
     };
@@ -88,6 +88,8 @@ mod tests {
             .concat(),
             exit_code: view.get_exit_code(),
             public_output: view.get_public_output(),
+            num_steps: 0,
+            committed_output_len: 0,
         };
 
         let program_traces =
@@ -218,20 +220,31 @@ mod tests {
             &view,
         )
         .unwrap();
+        let init_memory = [
+            view.get_public_input(),
+            view.get_ro_initial_memory(),
+            view.get_rw_initial_memory(),
+        ]
+        .concat();
         Machine::<BaseComponent>::verify_with_extensions(
             keccak_extensions(),
-            proof,
+            proof.clone(),
             view.get_program_memory(),
             &[],
-            &[
-                view.get_public_input(),
-                view.get_ro_initial_memory(),
-                view.get_rw_initial_memory(),
-            ]
-            .concat(),
+            &init_memory,
             view.get_exit_code(),
             view.get_public_output(),
         )
         .unwrap();
+        Machine::<BaseComponent>::verify_streaming(
+            keccak_extensions(),
+            proof,
+            view.get_program_memory(),
+            &[],
+            &init_memory,
+            view.get_exit_code(),
+            canonical_public_output(view.get_public_output()),
+        )
+        .unwrap();
     }
 }
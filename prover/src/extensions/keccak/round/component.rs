@@ -49,7 +49,6 @@ impl BuiltInExtension for KeccakRound {
     fn generate_preprocessed_trace(
         &self,
         log_size: u32,
-        _: ProgramTraceRef,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
         let log_n_instances = log_size - self.rounds.ilog2();
         let domain = CanonicCoset::new(log_size).circle_domain();
@@ -128,6 +127,12 @@ impl BuiltInExtension for KeccakRound {
         log_size.max(LOG_N_LANES)
     }
 
+    fn parameters(&self) -> Vec<u32> {
+        [self.index, self.rounds, self.offset]
+            .map(|param| param as u32)
+            .to_vec()
+    }
+
     fn preprocessed_trace_sizes(log_size: u32) -> Vec<u32> {
         // round constants + is_last
         vec![log_size; LANE_SIZE + 1]
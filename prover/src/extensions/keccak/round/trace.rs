@@ -202,6 +202,7 @@ impl<'b: 'a, 'a> TraceBuilder<'a> {
             log_size: self.log_size,
             preprocessed_trace: base_column_from_simd!(self.round_constants),
             original_trace: base_column_from_simd!(self.trace),
+            program_trace: Vec::new(),
         }
     }
 }
@@ -23,7 +23,7 @@ use stwo::{
         ColumnVec,
     },
     prover::{
-        backend::simd::SimdBackend,
+        backend::simd::{m31::LOG_N_LANES, SimdBackend},
         poly::{circle::CircleEvaluation, BitReversedOrder},
         ComponentProver,
     },
@@ -32,9 +32,14 @@ use stwo_constraint_framework::{
     FrameworkComponent, FrameworkEval, InfoEvaluator, TraceLocationAllocator,
 };
 
+use nexus_common::trace_size::MAX_LOG_SIZE;
+use nexus_vm::emulator::PublicOutputEntry;
+
 use crate::{
     components::AllLookupElements,
+    error::TraceCheckError,
     trace::{program_trace::ProgramTraceRef, sidenote::SideNote},
+    trace_check,
 };
 
 pub(crate) mod bit_op;
@@ -56,7 +61,9 @@ pub(crate) use trace::ComponentTrace;
 
 use bit_op::BitOpMultiplicity;
 use final_reg::FinalReg;
-use multiplicity::{Multiplicity128, Multiplicity16, Multiplicity256, Multiplicity32};
+use multiplicity::{
+    Multiplicity128, Multiplicity16, Multiplicity256, Multiplicity32, Multiplicity4096,
+};
 use multiplicity8::Multiplicity8;
 
 use keccak::{
@@ -71,12 +78,37 @@ trait FrameworkEvalExt: FrameworkEval + Sync + 'static {
 trait BuiltInExtension {
     type Eval: FrameworkEvalExt;
 
+    /// Columns committed in the preprocessed tree. They only depend on `log_size`, so that the tree is the same for
+    /// every program.
     fn generate_preprocessed_trace(
         &self,
         log_size: u32,
-        program_trace_ref: ProgramTraceRef,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>;
 
+    /// Columns depending on the program, committed in the program tree, see
+    /// [`PROGRAM_TRACE_IDX`](crate::trace::eval::PROGRAM_TRACE_IDX). Extensions read them with
+    /// `next_interaction_mask(PROGRAM_TRACE_IDX, [0])`, in the order of the returned columns.
+    fn generate_program_trace(
+        &self,
+        _log_size: u32,
+        _program_trace_ref: ProgramTraceRef,
+    ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
+        Vec::new()
+    }
+
+    /// Same as [`Self::generate_program_trace`], reading the public output from `public_output` after the entries
+    /// of `program_trace_ref`. Returns `None` if the entries don't fit into `log_size`.
+    ///
+    /// Only extensions whose program trace depends on the public output need to override it.
+    fn generate_program_trace_streaming(
+        &self,
+        log_size: u32,
+        program_trace_ref: ProgramTraceRef,
+        _public_output: &mut dyn Iterator<Item = PublicOutputEntry>,
+    ) -> Option<ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>> {
+        Some(self.generate_program_trace(log_size, program_trace_ref))
+    }
+
     fn generate_component_trace(
         &self,
         log_size: u32,
@@ -124,6 +156,18 @@ trait BuiltInExtension {
 
     fn compute_log_size(&self, side_note: &SideNote) -> u32;
 
+    /// Returns the parameters of the extension that change its constraints, identifying it in
+    /// [`Machine::components_id`](crate::machine::Machine::components_id) together with its name.
+    fn parameters(&self) -> Vec<u32> {
+        Vec::new()
+    }
+
+    /// Returns whether the component can be built with `log_size`. The verifier checks the log sizes of a proof with
+    /// it before generating any trace of them.
+    fn accepts_log_size(&self, log_size: u32) -> bool {
+        (LOG_N_LANES..=MAX_LOG_SIZE).contains(&log_size)
+    }
+
     fn trace_sizes(&self, log_size: u32) -> TreeVec<Vec<u32>> {
         <Self as BuiltInExtension>::Eval::dummy(log_size)
             .evaluate(InfoEvaluator::empty())
@@ -144,6 +188,7 @@ extension_dispatch! {
         Multiplicity32,
         Multiplicity128,
         Multiplicity256,
+        Multiplicity4096,
         BitOpMultiplicity,
         RamInitFinal,
         XorTable,
@@ -173,6 +218,9 @@ impl ExtensionComponent {
     pub(super) const fn multiplicity256() -> Self {
         Self::Multiplicity256(Multiplicity256::new())
     }
+    pub(super) const fn multiplicity4096() -> Self {
+        Self::Multiplicity4096(Multiplicity4096::new())
+    }
     pub(super) const fn bit_op_multiplicity() -> Self {
         Self::BitOpMultiplicity(BitOpMultiplicity::new())
     }
@@ -212,10 +260,20 @@ macro_rules! extension_dispatch {
             pub(crate) fn generate_preprocessed_trace(
                 &self,
                 log_size: u32,
-                program_trace_ref: ProgramTraceRef,
             ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
                 match self {
-                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::generate_preprocessed_trace(inner, log_size, program_trace_ref), )*
+                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::generate_preprocessed_trace(inner, log_size), )*
+                }
+            }
+
+            pub(crate) fn generate_program_trace_streaming(
+                &self,
+                log_size: u32,
+                program_trace_ref: ProgramTraceRef,
+                public_output: &mut dyn Iterator<Item = PublicOutputEntry>,
+            ) -> Option<ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>> {
+                match self {
+                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::generate_program_trace_streaming(inner, log_size, program_trace_ref, public_output), )*
                 }
             }
 
@@ -274,6 +332,45 @@ macro_rules! extension_dispatch {
                 }
             }
 
+            /// Evaluates the constraints of the extension on every row of `trace`, see
+            /// [`Machine::check_trace`](crate::machine::Machine::check_trace).
+            pub(crate) fn check_constraints(
+                &self,
+                trace: &TreeVec<Vec<Vec<BaseField>>>,
+                log_size: u32,
+                lookup_elements: &AllLookupElements,
+                claimed_sum: SecureField,
+            ) -> Result<(), TraceCheckError> {
+                match self {
+                    $( $_enum::$name(_) => trace_check::check_component_constraints(
+                        &<<$name as BuiltInExtension>::Eval as FrameworkEvalExt>::new(log_size, lookup_elements),
+                        stringify!($name),
+                        trace,
+                        log_size,
+                        claimed_sum,
+                    ), )*
+                }
+            }
+
+            /// Returns the name of the extension, independent of its `Debug` output.
+            pub(crate) fn name(&self) -> &'static str {
+                match self {
+                    $( $_enum::$name(_) => stringify!($name), )*
+                }
+            }
+
+            pub(crate) fn parameters(&self) -> Vec<u32> {
+                match self {
+                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::parameters(inner), )*
+                }
+            }
+
+            pub(crate) fn accepts_log_size(&self, log_size: u32) -> bool {
+                match self {
+                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::accepts_log_size(inner, log_size), )*
+                }
+            }
+
             pub(crate) fn trace_sizes(&self, log_size: u32) -> TreeVec<Vec<u32>> {
                 match self {
                     $( $_enum::$name(inner) => <$name as BuiltInExtension>::trace_sizes(inner, log_size), )*
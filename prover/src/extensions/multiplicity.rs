@@ -23,6 +23,7 @@ use crate::{
     chips::range_check::{
         range128::Range128LookupElements, range16::Range16LookupElements,
         range256::Range256LookupElements, range32::Range32LookupElements,
+        range4096::Range4096LookupElements,
     },
     components::{AllLookupElements, RegisteredLookupBound},
     trace::{
@@ -35,6 +36,8 @@ use super::{BuiltInExtension, ComponentTrace, FrameworkEvalExt};
 
 /// A component for range check multiplicity
 ///
+/// The table has exactly LEN rows regardless of the main trace height, so that every value in the range is present.
+///
 /// LEN is the size of the multiplicity table
 /// L is the lookup challenge type for a Relation
 #[derive(Debug, Clone)]
@@ -72,6 +75,8 @@ impl<const LEN: usize, L> MultiplicityEval<LEN, L> {
     const LOG_SIZE: u32 = {
         let log_size = LEN.ilog2();
         assert!(1 << log_size == LEN, "LEN must be a power of 2");
+        // Smaller tables would have to be padded, see `Multiplicity8`.
+        assert!(log_size >= LOG_N_LANES, "LEN must be at least N_LANES");
         log_size
     };
 }
@@ -153,6 +158,10 @@ where
         MultiplicityEval::<LEN, L>::LOG_SIZE
     }
 
+    fn accepts_log_size(&self, log_size: u32) -> bool {
+        log_size == MultiplicityEval::<LEN, L>::LOG_SIZE
+    }
+
     /// Contains only one column, representing the multiplicity
     ///
     /// The ordering of rows is the same as the ordering of the preprocessed value column.
@@ -169,13 +178,13 @@ where
             log_size,
             preprocessed_trace,
             original_trace,
+            program_trace: Vec::new(),
         }
     }
 
     fn generate_preprocessed_trace(
         &self,
         _log_size: u32,
-        _program_trace_ref: ProgramTraceRef,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
         let base_cols = Self::preprocessed_base_columns();
         let domain = CanonicCoset::new(Self::Eval::LOG_SIZE).circle_domain();
@@ -240,3 +249,26 @@ pub(crate) type Multiplicity16 = Multiplicity<16, Range16LookupElements>;
 pub(crate) type Multiplicity32 = Multiplicity<32, Range32LookupElements>;
 pub(crate) type Multiplicity128 = Multiplicity<128, Range128LookupElements>;
 pub(crate) type Multiplicity256 = Multiplicity<256, Range256LookupElements>;
+pub(crate) type Multiplicity4096 = Multiplicity<4096, Range4096LookupElements>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_tables_are_complete() {
+        fn assert_complete<const LEN: usize, L>() {
+            let values = Multiplicity::<LEN, L>::preprocessed_base_columns();
+            assert_eq!(1 << MultiplicityEval::<LEN, L>::LOG_SIZE, LEN);
+            assert_eq!(
+                values[0].to_cpu(),
+                (0..LEN).map(BaseField::from).collect::<Vec<_>>()
+            );
+        }
+        assert_complete::<16, Range16LookupElements>();
+        assert_complete::<32, Range32LookupElements>();
+        assert_complete::<128, Range128LookupElements>();
+        assert_complete::<256, Range256LookupElements>();
+        assert_complete::<4096, Range4096LookupElements>();
+    }
+}
@@ -110,6 +110,10 @@ impl BuiltInExtension for Multiplicity8 {
         Self::Eval::LOG_SIZE
     }
 
+    fn accepts_log_size(&self, log_size: u32) -> bool {
+        log_size == Self::Eval::LOG_SIZE
+    }
+
     /// Contains only one column, representing the multiplicity
     ///
     /// The ordering of rows is the same as the ordering of the preprocessed value column.
@@ -126,13 +130,13 @@ impl BuiltInExtension for Multiplicity8 {
             log_size,
             preprocessed_trace,
             original_trace,
+            program_trace: Vec::new(),
         }
     }
 
     fn generate_preprocessed_trace(
         &self,
         _log_size: u32,
-        _program_trace_ref: ProgramTraceRef,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
         let base_cols = Self::preprocessed_base_columns();
         let domain = CanonicCoset::new(Self::Eval::LOG_SIZE).circle_domain();
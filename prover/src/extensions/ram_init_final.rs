@@ -17,29 +17,42 @@ use stwo::{
     },
 };
 use stwo_constraint_framework::{
-    preprocessed_columns::PreProcessedColumnId, EvalAtRow, FrameworkEval, LogupTraceGenerator,
-    Relation, RelationEntry,
+    EvalAtRow, FrameworkEval, LogupTraceGenerator, Relation, RelationEntry,
 };
 
 use nexus_common::constants::WORD_SIZE_HALVED;
-use nexus_vm::WORD_SIZE;
+use nexus_vm::{emulator::PublicOutputEntry, WORD_SIZE};
 
 use crate::{
-    chips::{instructions::LoadStoreLookupElements, range_check::range256::Range256LookupElements},
+    chips::{
+        instructions::LoadStoreLookupElements,
+        range_check::{constraints::constrain_bool, range256::Range256LookupElements},
+    },
     components::AllLookupElements,
-    trace::{program_trace::ProgramTraceRef, sidenote::SideNote, utils::IntoBaseFields},
+    trace::{
+        eval::PROGRAM_TRACE_IDX, program_trace::ProgramTraceRef, sidenote::SideNote,
+        utils::IntoBaseFields,
+    },
 };
 
 use super::{BuiltInExtension, ComponentTrace, FrameworkEvalExt};
 
 /// An extension component for initial write set and final read set of the RAM memory checking
+///
+/// Every accessed address gets an initial write, with the value of the public initial memory when the address is
+/// part of it and zero otherwise. Zero-initialized memory such as `.bss` is therefore left out of the initial
+/// memory: reading it before any write yields zero, and it costs rows only for the addresses actually touched.
+///
+/// Every entry of the public initial memory takes a row, accessed or not. This includes the program bytes, four rows
+/// per instruction, which are mirrored into the read-only initial memory so that guests can load from `.text`. For
+/// guests with little data the program usually determines the size of this component.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct RamInitFinal {
     _private: (),
 }
 
 impl RamInitFinal {
-    const NUM_PREPROCESSED_TRACE_COLS: usize = WORD_SIZE + 4;
+    const NUM_PROGRAM_TRACE_COLS: usize = WORD_SIZE + 4;
     pub(super) const fn new() -> Self {
         Self { _private: () }
     }
@@ -59,26 +72,14 @@ impl FrameworkEval for RamInitFinalEval {
         self.log_size + 1
     }
     fn evaluate<E: stwo_constraint_framework::EvalAtRow>(&self, mut eval: E) -> E {
-        // Retrieve all preprocessed columns in the same order as generated
-        let preprocessed_ram_addr: Vec<E::F> = (0..WORD_SIZE)
-            .map(|i| {
-                let col_id = format!("preprocessed_ram_init_final_addr{}", i);
-                eval.get_preprocessed_column(PreProcessedColumnId { id: col_id })
-            })
-            .collect();
-
-        let preprocessed_init_flag = eval.get_preprocessed_column(PreProcessedColumnId {
-            id: "preprocessed_ram_init_final_init_flag".to_owned(),
-        });
-        let preprocessed_init_value = eval.get_preprocessed_column(PreProcessedColumnId {
-            id: "preprocessed_ram_init_final_init_value".to_owned(),
-        });
-        let preprocessed_output_flag = eval.get_preprocessed_column(PreProcessedColumnId {
-            id: "preprocessed_ram_init_final_output_flag".to_owned(),
-        });
-        let preprocessed_output_value = eval.get_preprocessed_column(PreProcessedColumnId {
-            id: "preprocessed_ram_init_final_output_value".to_owned(),
-        });
+        // Retrieve all program columns in the same order as generated
+        let mut next_program_column =
+            || eval.next_interaction_mask(PROGRAM_TRACE_IDX, [0])[0].clone();
+        let program_ram_addr: Vec<E::F> = (0..WORD_SIZE).map(|_| next_program_column()).collect();
+        let program_init_flag = next_program_column();
+        let program_init_value = next_program_column();
+        let program_output_flag = next_program_column();
+        let program_output_value = next_program_column();
         // The byte-address of RAM initial & final states. Each row contains information about one byte of initial & final RAM states.
         let ram_init_final_addr = (0..WORD_SIZE).map(|_| eval.next_trace_mask()).collect_vec();
         // The flag indicating whether (RamInitFinalAddr, RamFinalValue, RamFinalCounter) represents a byte in the final RAM state.
@@ -92,25 +93,21 @@ impl FrameworkEval for RamInitFinalEval {
         // (initial_memory_flag + public_output_flag) * (ram_init_final_addr[i] - public_ram_addr[i]) = 0
         for i in 0..WORD_SIZE {
             eval.add_constraint(
-                (preprocessed_init_flag.clone() + preprocessed_output_flag.clone())
-                    * (ram_init_final_addr[i].clone() - preprocessed_ram_addr[i].clone()),
+                (program_init_flag.clone() + program_output_flag.clone())
+                    * (ram_init_final_addr[i].clone() - program_ram_addr[i].clone()),
             );
         }
         // Enforce: public_output_flag * (ram_final_value - public_output_value) = 0
-        eval.add_constraint(
-            preprocessed_output_flag * (ram_final_value.clone() - preprocessed_output_value),
-        );
+        eval.add_constraint(program_output_flag * (ram_final_value.clone() - program_output_value));
 
         // Enforce RemInitFinalFlag is boolean
-        eval.add_constraint(
-            ram_init_final_flag.clone() * (ram_init_final_flag.clone() - E::F::one()),
-        );
+        constrain_bool(&mut eval, ram_init_final_flag.clone());
 
         self.constrain_add_initial_values(
             &mut eval,
             &ram_init_final_addr,
-            preprocessed_init_flag,
-            preprocessed_init_value,
+            program_init_flag,
+            program_init_value,
             ram_init_final_flag.clone(),
         );
         self.constrain_subtract_final_values(
@@ -157,8 +154,8 @@ impl RamInitFinalEval {
         &self,
         eval: &mut E,
         ram_init_final_addr: &[E::F],
-        preprocessed_init_flag: E::F,
-        preprocessed_init_value: E::F,
+        program_init_flag: E::F,
+        program_init_value: E::F,
         ram_init_final_flag: E::F,
     ) {
         let mut tuple = vec![];
@@ -169,8 +166,8 @@ impl RamInitFinalEval {
             + ram_init_final_addr[3].clone() * E::F::from((1 << 8).into());
         tuple.push(addr_low);
         tuple.push(addr_high);
-        // Add the product of preprocessed init flag and value.
-        tuple.push(preprocessed_init_flag * preprocessed_init_value);
+        // Add the product of the init flag and value of the program trace.
+        tuple.push(program_init_flag * program_init_value);
         // Append WORD_SIZE zeros as the counter.
         for _ in 0..WORD_SIZE_HALVED {
             tuple.push(E::F::zero());
@@ -257,7 +254,7 @@ impl BuiltInExtension for RamInitFinal {
         program_trace_ref: ProgramTraceRef,
         side_note: &mut SideNote,
     ) -> ComponentTrace {
-        let preprocessed_cols = Self::preprocessed_columns(log_size, program_trace_ref);
+        let program_cols = Self::program_columns(log_size, program_trace_ref);
         let original_cols = Self::original_columns(log_size, side_note);
         // update multiplicity for init_final_addr
         for col in &original_cols[0..WORD_SIZE] {
@@ -273,26 +270,58 @@ impl BuiltInExtension for RamInitFinal {
 
         ComponentTrace {
             log_size,
-            preprocessed_trace: preprocessed_cols,
+            preprocessed_trace: Vec::new(),
             original_trace: original_cols,
+            program_trace: program_cols,
         }
     }
 
     fn generate_preprocessed_trace(
+        &self,
+        _log_size: u32,
+    ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
+        Vec::new()
+    }
+
+    fn generate_program_trace(
         &self,
         log_size: u32,
         program_trace_ref: ProgramTraceRef,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
         let domain = CanonicCoset::new(log_size).circle_domain();
-        let preprocessed_cols = Self::preprocessed_columns(log_size, program_trace_ref);
-        preprocessed_cols
+        let program_cols = Self::program_columns(log_size, program_trace_ref);
+        program_cols
             .into_iter()
             .map(|col| CircleEvaluation::new(domain, col))
             .collect()
     }
 
-    fn preprocessed_trace_sizes(log_size: u32) -> Vec<u32> {
-        vec![log_size; Self::NUM_PREPROCESSED_TRACE_COLS]
+    fn generate_program_trace_streaming(
+        &self,
+        log_size: u32,
+        program_trace_ref: ProgramTraceRef,
+        public_output: &mut dyn Iterator<Item = PublicOutputEntry>,
+    ) -> Option<ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>> {
+        let domain = CanonicCoset::new(log_size).circle_domain();
+        let program_cols = Self::program_columns_from_iter(
+            log_size,
+            program_trace_ref,
+            program_trace_ref
+                .public_output
+                .iter()
+                .copied()
+                .chain(public_output),
+        )?;
+        Some(
+            program_cols
+                .into_iter()
+                .map(|col| CircleEvaluation::new(domain, col))
+                .collect(),
+        )
+    }
+
+    fn preprocessed_trace_sizes(_log_size: u32) -> Vec<u32> {
+        Vec::new()
     }
 
     fn generate_interaction_trace(
@@ -306,7 +335,7 @@ impl BuiltInExtension for RamInitFinal {
     ) {
         let load_store_elements: &LoadStoreLookupElements = lookup_elements.as_ref();
         let range256_elements: &Range256LookupElements = lookup_elements.as_ref();
-        let preprocessed_cols = &component_trace.preprocessed_trace;
+        let program_cols = &component_trace.program_trace;
         let original_cols = &component_trace.original_trace;
         let log_size = component_trace.log_size;
 
@@ -314,7 +343,7 @@ impl BuiltInExtension for RamInitFinal {
 
         Self::add_initial_values(
             log_size,
-            preprocessed_cols,
+            program_cols,
             original_cols,
             load_store_elements,
             &mut logup_trace_gen,
@@ -344,117 +373,71 @@ impl BuiltInExtension for RamInitFinal {
 }
 
 impl RamInitFinal {
-    fn preprocessed_columns(log_size: u32, program_trace_ref: ProgramTraceRef) -> Vec<BaseColumn> {
-        let total_len = program_trace_ref.init_memory.len()
-            + program_trace_ref.exit_code.len()
-            + program_trace_ref.public_output.len();
-        let padding_length = (1usize << log_size)
-            .checked_sub(total_len)
-            .expect("log_size too small");
-        let mut preprocessed_cols = vec![];
-
-        // Iterator for PublicRamAddr: take the address from each group.
-        let public_ram_addr_iter = program_trace_ref
-            .init_memory
-            .iter()
-            .map(|entry| entry.address)
-            .chain(
-                program_trace_ref
-                    .exit_code
-                    .iter()
-                    .map(|entry| entry.address),
-            )
-            .chain(
-                program_trace_ref
-                    .public_output
-                    .iter()
-                    .map(|entry| entry.address),
-            )
-            .chain(std::iter::repeat_n(0, padding_length));
-        let public_ram_addr_iter = public_ram_addr_iter
-            .map(|address| -> [BaseField; WORD_SIZE] { address.into_base_fields() });
-        assert_eq!(public_ram_addr_iter.clone().count(), 1 << log_size);
-        (0..WORD_SIZE).for_each(|i| {
-            let base_column =
-                BaseColumn::from_iter(public_ram_addr_iter.clone().map(|address| address[i]));
-            preprocessed_cols.push(base_column);
-        });
+    fn program_columns(log_size: u32, program_trace_ref: ProgramTraceRef) -> Vec<BaseColumn> {
+        Self::program_columns_from_iter(
+            log_size,
+            program_trace_ref,
+            program_trace_ref.public_output.iter().copied(),
+        )
+        .expect("log_size too small")
+    }
 
-        // Iterator for PublicInitialMemoryFlag: true for init_memory rows, false for others.
-        let public_initial_memory_flag_iter = program_trace_ref
-            .init_memory
-            .iter()
-            .map(|_| true)
-            .chain(std::iter::repeat_n(
-                false,
-                program_trace_ref.exit_code.len(),
-            ))
-            .chain(std::iter::repeat_n(
-                false,
-                program_trace_ref.public_output.len(),
-            ))
-            .chain(std::iter::repeat_n(false, padding_length));
-        assert_eq!(
-            public_initial_memory_flag_iter.clone().count(),
-            1 << log_size
-        );
-        let public_initial_memory_flag_iter =
-            public_initial_memory_flag_iter.map(|flag| flag.into_base_fields()[0]);
-        let public_initial_memory_flag_column =
-            BaseColumn::from_iter(public_initial_memory_flag_iter);
-        preprocessed_cols.push(public_initial_memory_flag_column);
-
-        // Iterator for PublicInitialMemoryValue: use the init_memory value, zero otherwise.
-        let public_initial_memory_value_iter = program_trace_ref
+    /// Fills the program columns row by row, reading the public output from `public_output` after the entries of
+    /// `program_trace_ref`, so that the output is consumed lazily.
+    ///
+    /// Rows hold the initial memory, then the exit code, then the public output, followed by zero padding. Returns
+    /// `None` if the entries don't fit into `1 << log_size` rows.
+    pub(crate) fn program_columns_from_iter(
+        log_size: u32,
+        program_trace_ref: ProgramTraceRef,
+        public_output: impl Iterator<Item = PublicOutputEntry>,
+    ) -> Option<Vec<BaseColumn>> {
+        const INITIAL_MEMORY_FLAG: usize = WORD_SIZE;
+        const INITIAL_MEMORY_VALUE: usize = WORD_SIZE + 1;
+        const OUTPUT_FLAG: usize = WORD_SIZE + 2;
+        const OUTPUT_VALUE: usize = WORD_SIZE + 3;
+
+        let num_rows = 1usize << log_size;
+        let mut program_cols =
+            vec![vec![BaseField::zero(); num_rows]; Self::NUM_PROGRAM_TRACE_COLS];
+        // Each row is (PublicRamAddr, PublicInitialMemoryValue, PublicOutputValue), exactly one value is set.
+        let rows = program_trace_ref
             .init_memory
             .iter()
-            .map(|entry| entry.value)
-            .chain(std::iter::repeat_n(0, program_trace_ref.exit_code.len()))
-            .chain(std::iter::repeat_n(
-                0,
-                program_trace_ref.public_output.len(),
-            ))
-            .chain(std::iter::repeat_n(0, padding_length));
-        assert_eq!(
-            public_initial_memory_value_iter.clone().count(),
-            1 << log_size
-        );
-        let public_initial_memory_value_iter =
-            public_initial_memory_value_iter.map(|value| value.into_base_fields());
-        let base_column =
-            BaseColumn::from_iter(public_initial_memory_value_iter.map(|value| value[0]));
-        preprocessed_cols.push(base_column);
-
-        // Iterator for PublicOutputFlag: false for init_memory rows, true for exit_code and public_output.
-        let public_output_flag_iter =
-            std::iter::repeat_n(false, program_trace_ref.init_memory.len())
-                .chain(program_trace_ref.exit_code.iter().map(|_| true))
-                .chain(program_trace_ref.public_output.iter().map(|_| true))
-                .chain(std::iter::repeat_n(false, padding_length));
-        assert_eq!(public_output_flag_iter.clone().count(), 1 << log_size);
-        let public_output_flag_iter =
-            public_output_flag_iter.map(|flag| flag.into_base_fields()[0]);
-        let public_output_flag_column = BaseColumn::from_iter(public_output_flag_iter);
-        preprocessed_cols.push(public_output_flag_column);
-
-        // Iterator for PublicOutputValue: zero for init_memory rows, use the provided value for the others.
-        let public_output_value_iter = std::iter::repeat_n(0, program_trace_ref.init_memory.len())
-            .chain(program_trace_ref.exit_code.iter().map(|entry| entry.value))
+            .map(|entry| (entry.address, Some(entry.value), None))
             .chain(
                 program_trace_ref
-                    .public_output
+                    .exit_code
                     .iter()
-                    .map(|entry| entry.value),
+                    .map(|entry| (entry.address, None, Some(entry.value))),
             )
-            .chain(std::iter::repeat_n(0, padding_length));
-        assert_eq!(public_output_value_iter.clone().count(), 1 << log_size);
-        let public_output_value_iter =
-            public_output_value_iter.map(|value| value.into_base_fields());
-        let base_column = BaseColumn::from_iter(public_output_value_iter.map(|value| value[0]));
-        preprocessed_cols.push(base_column);
-        assert_eq!(preprocessed_cols.len(), Self::NUM_PREPROCESSED_TRACE_COLS);
-        preprocessed_cols
+            .chain(public_output.map(|entry| (entry.address, None, Some(entry.value))));
+        for (row_idx, (address, initial_value, output_value)) in rows.enumerate() {
+            if row_idx >= num_rows {
+                return None;
+            }
+            let address: [BaseField; WORD_SIZE] = address.into_base_fields();
+            for (col, limb) in program_cols.iter_mut().zip(address) {
+                col[row_idx] = limb;
+            }
+            if let Some(value) = initial_value {
+                program_cols[INITIAL_MEMORY_FLAG][row_idx] = BaseField::one();
+                program_cols[INITIAL_MEMORY_VALUE][row_idx] = value.into_base_fields()[0];
+            }
+            if let Some(value) = output_value {
+                program_cols[OUTPUT_FLAG][row_idx] = BaseField::one();
+                program_cols[OUTPUT_VALUE][row_idx] = value.into_base_fields()[0];
+            }
+        }
+        assert_eq!(program_cols.len(), Self::NUM_PROGRAM_TRACE_COLS);
+        Some(
+            program_cols
+                .into_iter()
+                .map(BaseColumn::from_iter)
+                .collect(),
+        )
     }
+
     fn original_columns(log_size: u32, side_note: &SideNote) -> Vec<BaseColumn> {
         // First, create an iterator on rw_mem_check_last_access extended to the expected number of rows.
         let num_rows = 1usize << log_size;
@@ -520,17 +503,17 @@ impl RamInitFinal {
     /// The counter of the initial value is always zero.
     fn add_initial_values(
         log_size: u32,
-        preprocessed_cols: &[BaseColumn],
+        program_cols: &[BaseColumn],
         original_cols: &[BaseColumn],
         lookup_element: &LoadStoreLookupElements,
         logup_trace_gen: &mut LogupTraceGenerator,
     ) {
-        let _preprocessed_ram_init_final_addr = &preprocessed_cols[0..WORD_SIZE];
-        let initial_memory_flag = &preprocessed_cols[WORD_SIZE];
-        let initial_memory_value = &preprocessed_cols[WORD_SIZE + 1];
-        let _preprocessed_output_flag = &preprocessed_cols[WORD_SIZE + 2];
-        let _preprocessed_output_value = &preprocessed_cols[WORD_SIZE + 3];
-        assert_eq!(preprocessed_cols.len(), Self::NUM_PREPROCESSED_TRACE_COLS);
+        let _program_ram_init_final_addr = &program_cols[0..WORD_SIZE];
+        let initial_memory_flag = &program_cols[WORD_SIZE];
+        let initial_memory_value = &program_cols[WORD_SIZE + 1];
+        let _program_output_flag = &program_cols[WORD_SIZE + 2];
+        let _program_output_value = &program_cols[WORD_SIZE + 3];
+        assert_eq!(program_cols.len(), Self::NUM_PROGRAM_TRACE_COLS);
 
         let ram_init_final_addr = &original_cols[0..WORD_SIZE];
         let ram_init_final_flag = &original_cols[WORD_SIZE];
@@ -665,3 +648,103 @@ impl RamInitFinal {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::TraceCheckError, trace_check::check_component_constraints};
+    use nexus_vm::emulator::ProgramInfo;
+    use stwo::core::{fields::qm31::SECURE_EXTENSION_DEGREE, pcs::TreeVec};
+
+    #[test]
+    fn non_boolean_flag_rejected() {
+        const LOG_SIZE: u32 = 4;
+        let zeros = || vec![BaseField::zero(); 1 << LOG_SIZE];
+        // Rows without public memory, with `flag` as the init-final flag of row 0. The interaction columns are only
+        // read by the logup constraints, which follow the flag check.
+        let check = |flag: u32| {
+            let mut main = vec![zeros(); 2 * WORD_SIZE + 2];
+            main[WORD_SIZE][0] = BaseField::from(flag);
+            let trace = TreeVec::new(vec![
+                vec![zeros()],
+                main,
+                vec![zeros(); 4 * SECURE_EXTENSION_DEGREE],
+                vec![zeros(); RamInitFinal::NUM_PROGRAM_TRACE_COLS],
+            ]);
+            check_component_constraints(
+                &RamInitFinalEval::dummy(LOG_SIZE),
+                "RamInitFinal",
+                &trace,
+                LOG_SIZE,
+                SecureField::zero(),
+            )
+        };
+        // The address and output constraints come first, and hold on rows without public memory.
+        let is_flag_check = |result| {
+            matches!(
+                result,
+                Err(TraceCheckError::ConstraintNotSatisfied {
+                    chip: "RamInitFinal",
+                    row: 0,
+                    constraint,
+                }) if constraint == WORD_SIZE + 1
+            )
+        };
+
+        assert!(is_flag_check(check(2)));
+        assert!(!is_flag_check(check(0)));
+        assert!(!is_flag_check(check(1)));
+    }
+
+    #[test]
+    fn program_columns_stream_large_output() {
+        const LOG_SIZE: u32 = 22;
+        const OUTPUT_BASE: u32 = 0x1000;
+        // 4 MiB of output, generated on the fly.
+        let output = |len: usize| {
+            let mut address = OUTPUT_BASE;
+            std::iter::from_fn(move || {
+                let entry = PublicOutputEntry::new(address, (address % 251) as u8);
+                address += 1;
+                Some(entry)
+            })
+            .take(len)
+        };
+        let program_memory = ProgramInfo::dummy();
+        let program_trace_ref = ProgramTraceRef {
+            program_memory: &program_memory,
+            init_memory: &[],
+            exit_code: &[],
+            public_output: &[],
+            num_steps: 0,
+            committed_output_len: 0,
+        };
+
+        let cols = RamInitFinal::program_columns_from_iter(
+            LOG_SIZE,
+            program_trace_ref,
+            output(1 << LOG_SIZE),
+        )
+        .expect("output fits");
+        let last_row = (1 << LOG_SIZE) - 1;
+        let last_address = OUTPUT_BASE + last_row as u32;
+        let expected: [BaseField; WORD_SIZE] = last_address.into_base_fields();
+        for (i, limb) in expected.into_iter().enumerate() {
+            assert_eq!(cols[i].at(last_row), limb);
+        }
+        assert_eq!(cols[WORD_SIZE].at(last_row), BaseField::zero());
+        assert_eq!(cols[WORD_SIZE + 2].at(last_row), BaseField::one());
+        assert_eq!(
+            cols[WORD_SIZE + 3].at(last_row),
+            BaseField::from(last_address % 251)
+        );
+
+        // One more byte doesn't fit.
+        assert!(RamInitFinal::program_columns_from_iter(
+            LOG_SIZE,
+            program_trace_ref,
+            output((1 << LOG_SIZE) + 1),
+        )
+        .is_none());
+    }
+}
@@ -7,11 +7,15 @@ use stwo::{
 };
 use stwo_constraint_framework::{ORIGINAL_TRACE_IDX, PREPROCESSED_TRACE_IDX};
 
+use crate::trace::eval::PROGRAM_TRACE_IDX;
+
 /// Intermediate representation of the component trace.
 pub struct ComponentTrace {
     pub log_size: u32,
     pub preprocessed_trace: Vec<BaseColumn>,
     pub original_trace: Vec<BaseColumn>,
+    /// Columns depending on the program, see [`PROGRAM_TRACE_IDX`].
+    pub program_trace: Vec<BaseColumn>,
 }
 
 impl ComponentTrace {
@@ -23,6 +27,7 @@ impl ComponentTrace {
         let trace = match trace_idx {
             PREPROCESSED_TRACE_IDX => &self.preprocessed_trace,
             ORIGINAL_TRACE_IDX => &self.original_trace,
+            PROGRAM_TRACE_IDX => &self.program_trace,
             _ => panic!("invalid trace index"),
         };
         let preprocessed = trace
@@ -2,41 +2,74 @@
 // Need this feature to use the `borrowing_sub` method
 #![feature(bigint_helper_methods)]
 
+pub mod cancellation;
+#[cfg(feature = "capi")]
+pub mod capi;
+pub mod checkpoint;
 pub mod chips;
+pub mod compact;
 pub mod components;
+pub mod consistency;
+#[cfg(feature = "compression")]
+pub mod container;
+pub mod description;
+pub mod deser;
+pub mod error;
 pub mod extensions;
 pub mod trace;
+pub mod transcript;
 
 pub mod column;
+mod commitment;
 pub mod traits;
 pub mod virtual_column;
 
 pub mod machine;
+pub mod pipeline;
+pub mod policy;
+mod trace_check;
+
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
 #[cfg(test)]
 mod test_utils;
 
 use nexus_vm::emulator::InternalView;
 pub(crate) use nexus_vm::WORD_SIZE;
+use serde::{Deserialize, Serialize};
+use tiny_keccak::{Hasher, Keccak};
 
-pub use machine::Proof;
-
-pub use stwo::{core::verifier::VerificationError, prover::ProvingError};
+#[allow(deprecated)]
+pub use error::ProvingError;
+pub use error::{
+    NexusProvingError, ProgramTemplateMismatch, PublicOutputError, RegisterAccessError,
+    TraceCheckError, UnsupportedOpcode, VerificationError, VerifyOrExitError,
+};
+pub use machine::{
+    MachineLayout, Proof, ProofMetadata, ProveConfig, SecurityReport, VerificationCost,
+    VerifierOptions,
+};
+pub use policy::{LayoutArtifact, VerifierArtifacts, VerifierPolicy};
 
 pub fn prove(
     trace: &impl nexus_vm::trace::Trace,
     view: &nexus_vm::emulator::View,
-) -> Result<Proof, ProvingError> {
+) -> Result<Proof, NexusProvingError> {
     machine::Machine::<machine::BaseComponent>::prove(trace, view)
 }
 
+/// Verifies the proof against the public data of the view.
+///
+/// The exit code and the public output must be where the guest writes them according to the memory layout of the
+/// view, see [`check_public_output`](machine::check_public_output).
 pub fn verify(proof: Proof, view: &nexus_vm::emulator::View) -> Result<(), VerificationError> {
+    check_view_output(view)?;
     machine::Machine::<machine::BaseComponent>::verify(
         proof,
         view.get_program_memory(),
         view.view_associated_data().as_deref().unwrap_or_default(),
         &[
-            // preprocessed trace is sensitive to this ordering
             view.get_ro_initial_memory(),
             view.get_rw_initial_memory(),
             view.get_public_input(),
@@ -46,3 +79,217 @@ pub fn verify(proof: Proof, view: &nexus_vm::emulator::View) -> Result<(), Verif
         view.get_public_output(),
     )
 }
+
+/// Proves the execution like [`prove`] with the given options, see [`ProveConfig`].
+pub fn prove_with_config(
+    trace: &impl nexus_vm::trace::Trace,
+    view: &nexus_vm::emulator::View,
+    config: &ProveConfig,
+) -> Result<Proof, NexusProvingError> {
+    machine::Machine::<machine::BaseComponent>::prove_with_config(&[], trace, view, config)
+}
+
+/// Verifies the proof like [`verify`] with the given options, see [`VerifierOptions`].
+pub fn verify_with_options(
+    proof: Proof,
+    view: &nexus_vm::emulator::View,
+    options: &VerifierOptions,
+) -> Result<(), VerificationError> {
+    check_view_output(view)?;
+    machine::Machine::<machine::BaseComponent>::verify_with_options(
+        options,
+        proof,
+        view.get_program_memory(),
+        view.view_associated_data().as_deref().unwrap_or_default(),
+        &[
+            view.get_ro_initial_memory(),
+            view.get_rw_initial_memory(),
+            view.get_public_input(),
+        ]
+        .concat(),
+        view.get_exit_code(),
+        view.get_public_output(),
+    )
+}
+
+fn check_view_output(view: &nexus_vm::emulator::View) -> Result<(), PublicOutputError> {
+    machine::check_public_output(
+        view.view_memory_layout(),
+        view.get_exit_code(),
+        view.get_public_output(),
+    )
+}
+
+/// Public outputs of a verified execution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicOutputs {
+    /// Exit code the guest terminated with.
+    pub exit_code: u32,
+    /// Public output bytes, see [`View::public_output_bytes`](nexus_vm::emulator::View::public_output_bytes).
+    pub output: Vec<u8>,
+}
+
+/// Public data of a verified execution, returned by [`verify_returning_summary`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedExecutionSummary {
+    /// Number of executed steps, see [`Proof::num_steps`].
+    pub num_steps: u32,
+    /// Number of rows of the main trace, executed steps followed by padding.
+    pub num_rows: u32,
+    /// Exit code the guest terminated with, if it exited.
+    pub exit_code: Option<u32>,
+    /// Keccak-256 hash of the public output bytes, see
+    /// [`View::public_output_bytes`](nexus_vm::emulator::View::public_output_bytes).
+    pub public_output_hash: [u8; 32],
+}
+
+impl VerifiedExecutionSummary {
+    /// Returns the number of padding rows after the executed steps.
+    pub fn num_padding_rows(&self) -> u32 {
+        self.num_rows - self.num_steps
+    }
+}
+
+/// Claim that the public output holds `bytes` starting at `offset`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputClaim {
+    /// Offset from the start of the public output, see
+    /// [`View::public_output_bytes`](nexus_vm::emulator::View::public_output_bytes).
+    pub offset: u32,
+    /// Bytes expected at `offset`.
+    pub bytes: Vec<u8>,
+}
+
+impl OutputClaim {
+    /// Creates a claim that the public output holds `bytes` starting at `offset`.
+    pub fn new(offset: u32, bytes: impl Into<Vec<u8>>) -> Self {
+        Self {
+            offset,
+            bytes: bytes.into(),
+        }
+    }
+
+    /// Checks the claim against the full public output.
+    ///
+    /// Bytes past the end of the output don't exist, a claim reaching past the end is violated.
+    pub fn check(&self, output: &[u8]) -> Result<(), VerifyOrExitError> {
+        let start = (self.offset as usize).min(output.len());
+        let end = (self.offset as usize)
+            .saturating_add(self.bytes.len())
+            .min(output.len());
+        let actual = &output[start..end];
+        if actual != self.bytes.as_slice() {
+            return Err(VerifyOrExitError::OutputClaimViolated {
+                offset: self.offset,
+                expected: self.bytes.clone(),
+                actual: actual.to_vec(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Verifies the proof and checks that the public output satisfies every claim.
+///
+/// See [`Machine::verify_with_output_claims`](machine::Machine::verify_with_output_claims).
+pub fn verify_with_output_claims(
+    proof: Proof,
+    view: &nexus_vm::emulator::View,
+    claims: &[OutputClaim],
+) -> Result<(), VerifyOrExitError> {
+    check_view_output(view).map_err(VerificationError::from)?;
+    machine::Machine::<machine::BaseComponent>::verify_with_output_claims(
+        proof,
+        view.get_program_memory(),
+        view.view_associated_data().as_deref().unwrap_or_default(),
+        &[
+            view.get_ro_initial_memory(),
+            view.get_rw_initial_memory(),
+            view.get_public_input(),
+        ]
+        .concat(),
+        view.get_exit_code(),
+        view.get_public_output(),
+        claims,
+    )
+}
+
+/// Verifies the proof and checks the listed regions of the public output, see
+/// [`View::output_regions`](nexus_vm::emulator::View::output_regions).
+///
+/// See [`Machine::verify_with_output_regions`](machine::Machine::verify_with_output_regions).
+pub fn verify_with_output_regions(
+    proof: Proof,
+    view: &nexus_vm::emulator::View,
+    expected: &[(&str, &[u8])],
+) -> Result<(), VerifyOrExitError> {
+    check_view_output(view).map_err(VerificationError::from)?;
+    machine::Machine::<machine::BaseComponent>::verify_with_output_regions(
+        proof,
+        view.get_program_memory(),
+        view.view_associated_data().as_deref().unwrap_or_default(),
+        &[
+            view.get_ro_initial_memory(),
+            view.get_rw_initial_memory(),
+            view.get_public_input(),
+        ]
+        .concat(),
+        view.get_exit_code(),
+        view.get_public_output(),
+        &view.output_regions(),
+        expected,
+    )
+}
+
+/// Verifies the proof and returns the public data of the execution.
+///
+/// The number of steps and rows are committed to in the program trace, and constrained against the padding of the
+/// main trace, so they can be relied upon once the proof is verified.
+pub fn verify_returning_summary(
+    proof: Proof,
+    view: &nexus_vm::emulator::View,
+) -> Result<VerifiedExecutionSummary, VerificationError> {
+    let num_steps = proof.num_steps;
+    let log_size = proof.log_size.first().copied();
+    verify(proof, view)?;
+
+    let mut public_output_hash = [0u8; 32];
+    let mut hasher = Keccak::v256();
+    hasher.update(&view.public_output_bytes());
+    hasher.finalize(&mut public_output_hash);
+    Ok(VerifiedExecutionSummary {
+        num_steps,
+        num_rows: 1 << log_size.expect("verified proofs have a machine component"),
+        exit_code: view.exit_code_u32(),
+        public_output_hash,
+    })
+}
+
+/// Verifies the proof and checks that the guest exited cleanly, with exit code zero.
+pub fn verify_successful_execution(
+    proof: Proof,
+    view: &nexus_vm::emulator::View,
+) -> Result<PublicOutputs, VerifyOrExitError> {
+    verify_with_exit_code(proof, view, 0)
+}
+
+/// Verifies the proof and checks that the guest exited with the `expected` exit code.
+pub fn verify_with_exit_code(
+    proof: Proof,
+    view: &nexus_vm::emulator::View,
+    expected: u32,
+) -> Result<PublicOutputs, VerifyOrExitError> {
+    verify(proof, view)?;
+
+    let exit_code = view.exit_code_u32().ok_or(VerifyOrExitError::NoExitCode)?;
+    if exit_code != expected {
+        return Err(VerifyOrExitError::UnexpectedExitCode {
+            expected,
+            actual: exit_code,
+        });
+    }
+    Ok(PublicOutputs {
+        exit_code,
+        output: view.public_output_bytes(),
+    })
+}
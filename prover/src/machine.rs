@@ -1,82 +1,93 @@
-use std::marker::PhantomData;
+use std::{
+    collections::{BTreeSet, HashMap},
+    marker::PhantomData,
+    ops::Range,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
 use num_traits::Zero;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use stwo::{
     core::{
         air::Component,
         channel::{Blake2sChannel, Channel},
-        fields::qm31::SecureField,
+        fields::{
+            m31::BaseField,
+            qm31::{SecureField, SECURE_EXTENSION_DEGREE},
+        },
+        fri::CIRCLE_TO_LINE_FOLD_STEP,
         pcs::{CommitmentSchemeVerifier, PcsConfig, TreeVec},
         poly::circle::CanonicCoset,
         proof::StarkProof,
-        vcs::blake2_merkle::{Blake2sMerkleChannel, Blake2sMerkleHasher},
-        verifier::{verify, VerificationError},
+        utils::{bit_reverse_index, coset_index_to_circle_domain_index},
+        vcs::{blake2_hash::Blake2sHash, blake2_merkle::Blake2sMerkleHasher},
+        verifier::{verify, VerificationError as StwoVerificationError},
     },
     prover::{
-        backend::simd::SimdBackend, poly::circle::PolyOps, prove, CommitmentSchemeProver,
-        ComponentProver, ProvingError,
+        backend::simd::SimdBackend,
+        poly::{
+            circle::{CircleEvaluation, CirclePoly, PolyOps},
+            twiddles::TwiddleTree,
+            BitReversedOrder,
+        },
+        prove, CommitmentSchemeProver, ComponentProver,
     },
 };
 use stwo_constraint_framework::TraceLocationAllocator;
+use tiny_keccak::{Hasher, Keccak};
 
-use super::trace::eval::{INTERACTION_TRACE_IDX, ORIGINAL_TRACE_IDX, PREPROCESSED_TRACE_IDX};
+use super::trace::eval::{
+    INTERACTION_TRACE_IDX, ORIGINAL_TRACE_IDX, PREPROCESSED_TRACE_IDX, PROGRAM_TRACE_IDX,
+};
 use super::trace::{
-    program::iter_program_steps, program_trace::ProgramTracesBuilder, sidenote::SideNote,
-    PreprocessedTraces, TracesBuilder,
+    program::iter_program_steps,
+    program_trace::{ProgramTraceTemplate, ProgramTraces, ProgramTracesBuilder},
+    sidenote::SideNote,
+    validate_register_accesses, FinalizedTraces, PreprocessedTraces, TracesBuilder,
+};
+use nexus_common::{
+    constants::KECCAKF_OPCODE,
+    cpu::{OpcodeCost, Registers},
+    trace_size,
 };
 use nexus_vm::{
-    emulator::{InternalView, MemoryInitializationEntry, ProgramInfo, PublicOutputEntry, View},
+    emulator::{
+        canonical_init_memory, canonical_public_output, io_entries_into_vec, InternalView,
+        LinearMemoryLayout, MemoryInitializationEntry, OutputRegion, ProgramInfo,
+        PublicOutputEntry, View,
+    },
+    riscv::BuiltinOpcode,
     trace::Trace,
+    SyscallCode, WORD_SIZE,
 };
 
 use super::components::{MachineComponent, MachineEval, LOG_CONSTRAINT_DEGREE};
 use super::traits::MachineChip;
 use crate::{
-    chips::{
-        AddChip, AuipcChip, BeqChip, BgeChip, BgeuChip, BitOpChip, BltChip, BltuChip, BneChip,
-        CpuChip, CustomInstructionChip, DecodingCheckChip, JalChip, JalrChip, LoadStoreChip,
-        LuiChip, MExtensionChips, ProgramMemCheckChip, RangeCheckChip, RegisterMemCheckChip,
-        SllChip, SltChip, SltuChip, SraChip, SrlChip, SubChip, SyscallChip, TimestampChip,
-    },
-    column::{PreprocessedColumn, ProgramColumn},
+    cancellation::{self, CancellationToken},
+    checkpoint::{self, CheckpointColumn, ProverCheckpoint},
+    chips::{SYSCALL_ARG0_REG, SYSCALL_NUMBER_REG},
+    column::{GlobalColumn, PreprocessedColumn, ProgramColumn},
+    commitment::CommittedTree,
     components::{self, AllLookupElements},
+    consistency,
+    description::ChipDescription,
+    error::{
+        NexusProvingError, PublicOutputError, TraceCheckError, UnsupportedOpcode,
+        VerificationError, VerifyOrExitError,
+    },
     extensions::{ComponentTrace, ExtensionComponent, ExtensionsConfig},
+    policy::{LayoutArtifact, VerifierArtifacts, VerifierPolicy},
     trace::program_trace::ProgramTraceRef,
+    trace_check,
     traits::generate_interaction_trace,
+    transcript::{self, TranscriptChannel, TranscriptLog, TranscriptMerkleChannel},
 };
 use serde::{Deserialize, Serialize};
 /// Base component tuple for constraining virtual machine execution based on RV32I ISA.
-pub type BaseComponent = (
-    CpuChip,
-    DecodingCheckChip,
-    AddChip,
-    SubChip,
-    SltuChip,
-    BitOpChip,
-    SltChip,
-    BneChip,
-    BeqChip,
-    BltuChip,
-    BltChip,
-    BgeuChip,
-    BgeChip,
-    JalChip,
-    LuiChip,
-    AuipcChip,
-    JalrChip,
-    SllChip,
-    SrlChip,
-    SraChip,
-    LoadStoreChip,
-    SyscallChip,
-    MExtensionChips,
-    CustomInstructionChip,
-    ProgramMemCheckChip,
-    RegisterMemCheckChip,
-    TimestampChip,
-    // Range checks must be positioned at the end. They use values filled by instruction chips.
-    RangeCheckChip,
-);
+pub type BaseComponent = crate::components!(Base);
 /// Base extensions used in conjunction with [`BaseComponent`]. These components are always enabled and are not accessible
 /// to downstream crates. ram_init_final() modifies multiplicities for multiplicity256(), so the ordering between these is important.
 const BASE_EXTENSIONS: &[ExtensionComponent] = &[
@@ -88,13 +99,31 @@ const BASE_EXTENSIONS: &[ExtensionComponent] = &[
     ExtensionComponent::multiplicity32(),
     ExtensionComponent::multiplicity128(),
     ExtensionComponent::multiplicity256(),
+    ExtensionComponent::multiplicity4096(),
 ];
 
+/// Number of main trace rows filled between checks of the cancellation token, see [`Machine::prove_cancellable`].
+///
+/// Chips fill the main trace in chunks of this many rows, see [`MachineChip::fill_main_trace_chunk`].
+pub const CANCELLATION_CHECK_ROWS: usize = 1 << 12;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Proof {
     pub stark_proof: StarkProof<Blake2sMerkleHasher>,
-    pub claimed_sum: Vec<SecureField>, // one per component
-    pub log_size: Vec<u32>,            // one per component
+    /// Logup claimed sum of each component, the machine component first followed by the extensions in the order
+    /// identified by [`ProofMetadata::components_id`]. Only their total is required to be zero.
+    #[serde(alias = "claimed_sum")]
+    pub claimed_sums: Vec<SecureField>,
+    pub log_size: Vec<u32>, // one per component
+    /// Number of executed steps, the main trace of `1 << log_size[0]` rows is padded past them. Committed to in the
+    /// program trace, a wrong count fails verification.
+    pub num_steps: u32,
+    /// Number of public output bytes committed by the guest with the commit ecall, `None` if it never committed.
+    /// Committed to in the program trace, where the syscall chip checks that the commits of the guest cover exactly
+    /// these bytes. The public output must then be the committed bytes.
+    #[serde(default)]
+    pub committed_output_len: Option<u32>,
+    pub metadata: ProofMetadata,
 }
 
 impl Proof {
@@ -102,13 +131,347 @@ impl Proof {
     pub fn size_estimate(&self) -> usize {
         let Self {
             stark_proof,
-            claimed_sum,
+            claimed_sums,
             log_size,
+            num_steps,
+            committed_output_len,
+            metadata,
         } = self;
         stark_proof.size_estimate()
-            + claimed_sum.len() * std::mem::size_of::<SecureField>()
+            + claimed_sums.len() * std::mem::size_of::<SecureField>()
             + log_size.len() * std::mem::size_of::<u32>()
+            + std::mem::size_of_val(num_steps)
+            + std::mem::size_of_val(committed_output_len)
+            + metadata.size_estimate()
+    }
+
+    /// Returns the conjectured security level of the proof, see [`SecurityReport`].
+    ///
+    /// The parameters are the ones the verifier checks the proof against, see [`PcsParams::verifier_config`], rather
+    /// than the ones declared in the metadata, which are informational except for the proof of work bits.
+    pub fn security_report(&self) -> SecurityReport {
+        let config = self.metadata.pcs_params.verifier_config();
+        let n_columns = self.stark_proof.sampled_values.iter().map(Vec::len).sum();
+        let max_log_size = self.log_size.iter().copied().max().unwrap_or_default();
+        SecurityReport::new(&config, n_columns, max_log_size + LOG_CONSTRAINT_DEGREE)
+    }
+}
+
+/// Conjectured security level of a proof, see [`Proof::security_report`].
+///
+/// Bits are computed with the conjectured FRI soundness of the ethSTARK paper (Conjecture 2.1), which stwo and
+/// most STARK deployments rely on: every query of a FRI proof over a domain with blowup `2^log_blowup_factor`
+/// passes for a far from low degree function with probability at most `2^-log_blowup_factor`, and grinding
+/// `pow_bits` of proof of work multiplies the cost of every attempt by `2^pow_bits`, so that
+///
+/// `query_bits = n_queries * log_blowup_factor + pow_bits`.
+///
+/// The query phase isn't the only source of error. Random linear combinations of the committed columns and the
+/// out-of-domain sample each fail with probability about `n / |F|`, where `n` is the number of combined columns
+/// and the degree of the composition polynomial respectively, and `|F|` is the size of the secure field QM31, just
+/// below `2^124`. Bounding both by the larger term, the union bound gives
+///
+/// `algebraic_bits = floor(log2 |F|) - max(ceil(log2 n_columns), composition_log_size) - 1`,
+///
+/// and the conjectured security level is the minimum of `query_bits` and `algebraic_bits`. The figure assumes
+/// the conjecture, a Blake2s channel behaving as a random oracle, and 2-to-1 folding in every FRI layer; it is
+/// not a proven bound.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecurityReport {
+    pub pow_bits: u32,
+    pub log_blowup_factor: u32,
+    pub n_queries: usize,
+    /// `floor(log2 |F|)` of the secure field.
+    pub field_bits: u32,
+    /// Number of columns committed to and sampled out of domain, including the composition polynomial.
+    pub n_columns: usize,
+    /// Log degree bound of the composition polynomial.
+    pub composition_log_size: u32,
+    /// Security of the FRI query phase, including proof of work.
+    pub query_bits: u32,
+    /// Security of the random linear combinations and of the out-of-domain sample.
+    pub algebraic_bits: u32,
+    /// Minimum of `query_bits` and `algebraic_bits`.
+    pub conjectured_bits: u32,
+}
+
+impl SecurityReport {
+    /// `floor(log2 |QM31|)`, with `|QM31| = (2^31 - 1)^4`.
+    pub const SECURE_FIELD_BITS: u32 = 123;
+
+    fn new(config: &PcsConfig, n_columns: usize, composition_log_size: u32) -> Self {
+        let pow_bits = config.pow_bits;
+        let log_blowup_factor = config.fri_config.log_blowup_factor;
+        let n_queries = config.fri_config.n_queries;
+        let query_bits = (n_queries as u32)
+            .saturating_mul(log_blowup_factor)
+            .saturating_add(pow_bits);
+        let algebraic_bits = Self::SECURE_FIELD_BITS
+            .saturating_sub(
+                n_columns
+                    .max(1)
+                    .next_power_of_two()
+                    .ilog2()
+                    .max(composition_log_size),
+            )
+            .saturating_sub(1);
+        Self {
+            pow_bits,
+            log_blowup_factor,
+            n_queries,
+            field_bits: Self::SECURE_FIELD_BITS,
+            n_columns,
+            composition_log_size,
+            query_bits,
+            algebraic_bits,
+            conjectured_bits: query_bits.min(algebraic_bits),
+        }
+    }
+}
+
+/// Diagnostic information about the prover build and configuration that produced a proof.
+///
+/// Only [`ProofMetadata::components_id`] and [`ProofMetadata::salt`] are bound to the proof by being mixed into the
+/// Fiat-Shamir channel. Of the remaining fields, the verifier reads the proof of work bits of
+/// [`ProofMetadata::pcs_params`], the others are informational.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofMetadata {
+    /// Version of the prover crate.
+    pub crate_version: String,
+    /// Hash of the ordered list of chips, extensions and column layout, see [`Machine::components_id`].
+    pub components_id: [u8; 32],
+    /// Polynomial commitment scheme parameters.
+    pub pcs_params: PcsParams,
+    /// Salt mixed into the Fiat-Shamir channel, see [`ProveConfig::salt`]. It doesn't hide which execution the proof
+    /// is of.
+    ///
+    /// Always serialized, even when absent: postcard encodes fields by position, so a skipped field couldn't be
+    /// decoded.
+    #[serde(default)]
+    pub salt: Option<[u8; 32]>,
+}
+
+impl ProofMetadata {
+    fn size_estimate(&self) -> usize {
+        self.crate_version.len()
+            + self.components_id.len()
+            + std::mem::size_of::<PcsParams>()
+            + std::mem::size_of_val(&self.salt)
+    }
+}
+
+/// Serializable mirror of [`PcsConfig`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PcsParams {
+    pub pow_bits: u32,
+    pub log_blowup_factor: u32,
+    pub log_last_layer_degree_bound: u32,
+    pub n_queries: usize,
+}
+
+impl PcsParams {
+    /// Returns the configuration the verifier checks a proof declaring these parameters against.
+    ///
+    /// Provers may grind more than the default, never less, the other parameters are always the default.
+    pub fn verifier_config(&self) -> PcsConfig {
+        let default_config = PcsConfig::default();
+        PcsConfig {
+            pow_bits: self.pow_bits.max(default_config.pow_bits),
+            ..default_config
+        }
+    }
+}
+
+impl From<&PcsConfig> for PcsParams {
+    fn from(config: &PcsConfig) -> Self {
+        Self {
+            pow_bits: config.pow_bits,
+            log_blowup_factor: config.fri_config.log_blowup_factor,
+            log_last_layer_degree_bound: config.fri_config.log_last_layer_degree_bound,
+            n_queries: config.fri_config.n_queries,
+        }
+    }
+}
+
+/// Options of [`Machine::prove_with_config`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ProveConfig {
+    /// Bits of proof of work ground before FRI queries are drawn, at least the default for the proof to verify.
+    ///
+    /// Grinding takes about `2^pow_bits` hashes. Stwo grinds on a single thread, the `parallel-grinding` feature
+    /// splits the search over the rayon thread pool, which pays off from around 20 bits.
+    pub pow_bits: u32,
+    /// Salt mixed into the Fiat-Shamir channel before the first commitment, and carried in the proof for the
+    /// verifier to do the same.
+    ///
+    /// Proofs of the same execution are identical without a salt. With distinct salts every challenge differs,
+    /// from the lookup elements to the FRI queries, and so do the interaction and composition commitments: the salt
+    /// gives each proof its own independent queries.
+    ///
+    /// The salt is not a privacy feature. Traces aren't blinded, the preprocessed, main and program trace
+    /// commitments don't depend on the channel and are the same in proofs of the same execution, which can therefore
+    /// be linked whatever their salts.
+    pub salt: Option<[u8; 32]>,
+    /// Identifier of the deployment, mixed into the Fiat-Shamir channel before anything else, see
+    /// [`Self::with_context_id`].
+    pub context_id: Option<[u8; 32]>,
+}
+
+impl Default for ProveConfig {
+    fn default() -> Self {
+        Self {
+            pow_bits: PcsConfig::default().pow_bits,
+            salt: None,
+            context_id: None,
+        }
+    }
+}
+
+impl ProveConfig {
+    /// Separates proofs of this deployment from proofs of any other by mixing `context_id` into the Fiat-Shamir
+    /// channel as its very first input.
+    ///
+    /// Unlike [`Self::salt`], the context id is not carried in the proof: it's a constant of the deployment, and
+    /// the verifier must be given the same one, see [`VerifierOptions::with_context_id`]. Every challenge depends on
+    /// it, so a proof of one context fails verification in another, even for the same program and inputs.
+    pub fn with_context_id(mut self, context_id: [u8; 32]) -> Self {
+        self.context_id = Some(context_id);
+        self
+    }
+}
+
+/// Options of [`Machine::verify_with_options`], checks are skipped unless set.
+///
+/// Checks of the proof against the options run before any work proportional to the size of the proof.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VerifierOptions {
+    /// Identifier of the deployment, see [`Self::with_context_id`].
+    pub context_id: Option<[u8; 32]>,
+    /// Minimum conjectured security level of the proof, see [`Proof::security_report`].
+    pub min_security_bits: Option<u32>,
+    /// Limits on the proof, see [`VerifierPolicy::check`].
+    pub policy: Option<VerifierPolicy>,
+    /// Layout of the machine component precomputed for the log sizes allowed by its policy, see
+    /// [`Self::with_artifacts`].
+    pub artifacts: Option<VerifierArtifacts>,
+}
+
+impl VerifierOptions {
+    /// Verifies proofs generated with [`ProveConfig::with_context_id`], `context_id` must be the one the proof was
+    /// generated with.
+    pub fn with_context_id(mut self, context_id: [u8; 32]) -> Self {
+        self.context_id = Some(context_id);
+        self
+    }
+
+    /// Rejects proofs whose conjectured security level is below `min_bits`.
+    pub fn with_min_security(mut self, min_bits: u32) -> Self {
+        self.min_security_bits = Some(min_bits);
+        self
+    }
+
+    /// Rejects proofs outside of `policy`.
+    pub fn with_policy(mut self, policy: VerifierPolicy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Rejects proofs outside of the policy of `artifacts`, and takes the layout of the machine component from them
+    /// instead of deriving it from the AIR.
+    ///
+    /// Artifacts computed for other chips, or lacking the log size of the proof, are rejected with
+    /// [`VerificationError::ArtifactsMismatch`].
+    pub fn with_artifacts(mut self, artifacts: VerifierArtifacts) -> Self {
+        self.artifacts = Some(artifacts);
+        self
+    }
+}
+
+/// Estimated work of verifying a proof, see [`Machine::verification_cost`].
+///
+/// Merkle paths are counted as if no two queries shared a node, so hash counts are upper bounds. Field operations
+/// are counted for out-of-domain quotients and FRI folding only, evaluation of constraints at the out-of-domain
+/// point is not included.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct VerificationCost {
+    /// Blake2s compressions to verify Merkle paths of trace and FRI layer commitments, and the proof of work.
+    pub blake2s_compressions: usize,
+    /// Secure field multiplications.
+    pub qm31_multiplications: usize,
+    /// FRI layers, including the first layer folding from the circle to the line.
+    pub fri_layers: usize,
+    /// Queries over all FRI layers.
+    pub fri_layer_queries: usize,
+    pub pow_checks: usize,
+}
+
+/// Columns committed to by the machine component, see [`Machine::layout`].
+///
+/// Every column has the log size of the machine traces as its log degree bound. Extensions commit to columns of
+/// sizes depending on the execution, which aren't included.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MachineLayout {
+    pub log_size: u32,
+    /// Preprocessed columns, committed first in the preprocessed tree.
+    pub preprocessed_columns: Vec<PreprocessedColumn>,
+    /// Program columns, committed first in the program tree, see [`PROGRAM_TRACE_IDX`].
+    pub program_columns: Vec<ProgramColumn>,
+    /// Columns of the original trace.
+    pub main_columns: Vec<GlobalColumn>,
+    /// Number of base field columns of the interaction trace, which depends on the lookups of the chips.
+    pub interaction_width: usize,
+}
+
+impl MachineLayout {
+    /// Number of base field columns committed to in the preprocessed tree.
+    pub fn preprocessed_width(&self) -> usize {
+        self.preprocessed_columns.iter().map(|col| col.size()).sum()
+    }
+
+    pub fn program_width(&self) -> usize {
+        self.program_columns.iter().map(|col| col.size()).sum()
     }
+
+    pub fn main_width(&self) -> usize {
+        self.main_columns.iter().map(|col| col.size()).sum()
+    }
+
+    /// Log degree bounds of the committed base field columns, by tree.
+    pub fn log_degree_bounds(&self) -> TreeVec<Vec<u32>> {
+        let mut bounds = TreeVec::new(vec![Vec::new(); PROGRAM_TRACE_IDX + 1]);
+        bounds[PREPROCESSED_TRACE_IDX] = vec![self.log_size; self.preprocessed_width()];
+        bounds[ORIGINAL_TRACE_IDX] = vec![self.log_size; self.main_width()];
+        bounds[INTERACTION_TRACE_IDX] = vec![self.log_size; self.interaction_width];
+        bounds[PROGRAM_TRACE_IDX] = vec![self.log_size; self.program_width()];
+        bounds
+    }
+}
+
+const BLAKE2S_BLOCK_BYTES: usize = 64;
+const BLAKE2S_HASH_BYTES: usize = 32;
+
+/// Blake2s compressions to verify a single Merkle path of a tree committing to columns of `log_sizes`.
+///
+/// Every node hashes its children together with values of columns of the node's layer size.
+fn merkle_path_compressions(log_sizes: &[u32], log_blowup: u32) -> usize {
+    let Some(height) = log_sizes.iter().max().map(|log_size| log_size + log_blowup) else {
+        return 0;
+    };
+    (0..=height)
+        .map(|layer_log_size| {
+            let n_columns = log_sizes
+                .iter()
+                .filter(|&&log_size| log_size + log_blowup == layer_log_size)
+                .count();
+            let children_bytes = if layer_log_size < height {
+                2 * BLAKE2S_HASH_BYTES
+            } else {
+                0
+            };
+            let input_bytes = children_bytes + n_columns * std::mem::size_of::<BaseField>();
+            input_bytes.div_ceil(BLAKE2S_BLOCK_BYTES).max(1)
+        })
+        .sum()
 }
 
 /// Main (empty) struct implementing proving functionality of zkVM.
@@ -123,7 +486,11 @@ pub struct Machine<C = BaseComponent> {
 }
 
 impl<C: MachineChip + Sync> Machine<C> {
-    pub fn prove(trace: &impl Trace, view: &View) -> Result<Proof, ProvingError> {
+    /// Proves the execution described by the trace and the view.
+    ///
+    /// Initial memory entries of the view are committed to in ascending address order, duplicate addresses
+    /// result in [`NexusProvingError::InvalidInitialMemory`].
+    pub fn prove(trace: &impl Trace, view: &View) -> Result<Proof, NexusProvingError> {
         Self::prove_with_extensions(&[], trace, view)
     }
 
@@ -131,44 +498,266 @@ impl<C: MachineChip + Sync> Machine<C> {
         extensions: &[ExtensionComponent],
         trace: &impl Trace,
         view: &View,
-    ) -> Result<Proof, ProvingError> {
-        let num_steps = trace.get_num_steps();
-        let program_len = view.get_program_memory().program.len();
-        let log_size =
-            Self::max_log_size(&[num_steps, program_len]).max(PreprocessedTraces::MIN_LOG_SIZE);
+    ) -> Result<Proof, NexusProvingError> {
+        Self::prove_with_config(extensions, trace, view, &ProveConfig::default())
+    }
+
+    /// Proves the execution like [`Self::prove_with_extensions`] with the given options.
+    pub fn prove_with_config(
+        extensions: &[ExtensionComponent],
+        trace: &impl Trace,
+        view: &View,
+        config: &ProveConfig,
+    ) -> Result<Proof, NexusProvingError> {
+        match Self::prove_from(
+            extensions,
+            view,
+            ProverStart::Trace(trace),
+            CheckpointMode::None,
+            None,
+            config,
+        )? {
+            ProverOutcome::Proof(proof) => Ok(*proof),
+            ProverOutcome::Checkpoint(_) => {
+                unreachable!("proving doesn't stop without checkpointing")
+            }
+        }
+    }
+
+    /// Proves the execution like [`Self::prove`], stopping with [`NexusProvingError::Aborted`] once `token` is cancelled.
+    ///
+    /// The token is checked after trace generation, after each tree commitment and before FRI, as well as every
+    /// [`CANCELLATION_CHECK_ROWS`] rows while filling the main trace. Proving itself inside stwo can't be
+    /// interrupted, in particular the last check happens right before the stwo prover is invoked.
+    pub fn prove_cancellable(
+        trace: &impl Trace,
+        view: &View,
+        token: CancellationToken,
+    ) -> Result<Proof, NexusProvingError> {
+        match Self::prove_from(
+            &[],
+            view,
+            ProverStart::Trace(trace),
+            CheckpointMode::None,
+            Some(&token),
+            &ProveConfig::default(),
+        )? {
+            ProverOutcome::Proof(proof) => Ok(*proof),
+            ProverOutcome::Checkpoint(_) => {
+                unreachable!("proving doesn't stop without checkpointing")
+            }
+        }
+    }
+
+    /// Proves the execution like [`Self::prove_with_extensions`], resuming from the checkpoint in `checkpoint_dir`
+    /// if there is one.
+    ///
+    /// Without a checkpoint proving starts from scratch, and a checkpoint is saved to `checkpoint_dir` once traces
+    /// are generated. The checkpoint must be resumed with the same view and extensions it was created with.
+    /// See [`crate::checkpoint`] for the supported boundaries.
+    pub fn prove_resumable(
+        extensions: &[ExtensionComponent],
+        trace: &impl Trace,
+        view: &View,
+        checkpoint_dir: impl AsRef<Path>,
+    ) -> Result<Proof, NexusProvingError> {
+        let checkpoint_dir = checkpoint_dir.as_ref();
+        let (start, mode) = match ProverCheckpoint::load(checkpoint_dir)? {
+            Some(checkpoint) => (ProverStart::Checkpoint(checkpoint), CheckpointMode::None),
+            None => (
+                ProverStart::Trace(trace),
+                CheckpointMode::Save(checkpoint_dir, view),
+            ),
+        };
+        match Self::prove_from(extensions, view, start, mode, None, &ProveConfig::default())? {
+            ProverOutcome::Proof(proof) => Ok(*proof),
+            ProverOutcome::Checkpoint(_) => {
+                unreachable!("proving doesn't stop when saving checkpoints")
+            }
+        }
+    }
+
+    /// Runs proving until the checkpoint boundary and returns the checkpoint instead of the proof.
+    pub fn prove_until_checkpoint(
+        extensions: &[ExtensionComponent],
+        trace: &impl Trace,
+        view: &View,
+    ) -> Result<ProverCheckpoint, NexusProvingError> {
+        match Self::prove_from(
+            extensions,
+            view,
+            ProverStart::Trace(trace),
+            CheckpointMode::Stop(view),
+            None,
+            &ProveConfig::default(),
+        )? {
+            ProverOutcome::Checkpoint(checkpoint) => Ok(checkpoint),
+            ProverOutcome::Proof(_) => unreachable!("proving stops at the checkpoint"),
+        }
+    }
+
+    /// Proves the execution like [`Self::prove`], recording every interaction with the Fiat-Shamir channel.
+    ///
+    /// Recording doesn't affect the proof, see [`crate::transcript`]. Meant for debugging verifiers written
+    /// independently against [`Self::verify_with_transcript`].
+    pub fn prove_with_transcript(
+        trace: &impl Trace,
+        view: &View,
+    ) -> Result<(Proof, TranscriptLog), NexusProvingError> {
+        let bundle = Self::generate_traces(&[], trace, view, None, None)?;
+        let context = ProverContext::new(bundle.max_log_size());
+        match Self::commit_with_checkpoint(
+            bundle,
+            &context,
+            CheckpointMode::None,
+            None,
+            TranscriptChannel::recording(),
+        )? {
+            Committed::Bundle(bundle) => {
+                let (proof, channel) = Self::finish(*bundle)?;
+                Ok((proof, channel.into_log()))
+            }
+            Committed::Checkpoint(_) => {
+                unreachable!("committing doesn't stop without checkpointing")
+            }
+        }
+    }
+
+    fn prove_from<T: Trace>(
+        extensions: &[ExtensionComponent],
+        view: &View,
+        start: ProverStart<'_, T>,
+        checkpoint_mode: CheckpointMode<'_>,
+        cancel: Option<&CancellationToken>,
+        config: &ProveConfig,
+    ) -> Result<ProverOutcome, NexusProvingError> {
+        let bundle = match start {
+            ProverStart::Trace(trace) => {
+                Self::generate_traces(extensions, trace, view, cancel, None)?
+            }
+            ProverStart::Checkpoint(checkpoint) => {
+                Self::restore_traces(extensions, view, checkpoint)?
+            }
+        };
+        let context = ProverContext::with_config(bundle.max_log_size(), config);
+
+        match Self::commit_with_checkpoint(
+            bundle,
+            &context,
+            checkpoint_mode,
+            cancel,
+            TranscriptChannel::default(),
+        )? {
+            Committed::Bundle(bundle) => {
+                cancellation::check(cancel)?;
+                Ok(ProverOutcome::Proof(Box::new(Self::finish_proof(*bundle)?)))
+            }
+            Committed::Checkpoint(checkpoint) => Ok(ProverOutcome::Checkpoint(checkpoint)),
+        }
+    }
+
+    /// Generates the traces of the execution, the first stage of proving.
+    ///
+    /// Together with [`Self::commit_and_interact`] and [`Self::finish_proof`] this splits
+    /// [`Self::prove_with_extensions`] into stages with owned inputs and outputs, which can run on different threads,
+    /// see [`ProvingPipeline`](crate::pipeline::ProvingPipeline).
+    pub fn build_traces(
+        extensions: &[ExtensionComponent],
+        trace: &impl Trace,
+        view: &View,
+    ) -> Result<TraceBundle, NexusProvingError> {
+        Self::generate_traces(extensions, trace, view, None, None)
+    }
 
+    /// Proves the execution like [`Self::prove_with_extensions`], with twiddles, options and the program trace
+    /// template of `context`, see [`ProverContext::with_program_template`].
+    ///
+    /// `context` must support traces at least as large as the ones of the execution.
+    pub fn prove_with_context(
+        extensions: &[ExtensionComponent],
+        trace: &impl Trace,
+        view: &View,
+        context: &ProverContext,
+    ) -> Result<Proof, NexusProvingError> {
+        let bundle = Self::generate_traces(
+            extensions,
+            trace,
+            view,
+            None,
+            context.program_template.as_ref(),
+        )?;
+        Self::finish_proof(Self::commit_and_interact(bundle, context)?)
+    }
+
+    fn generate_traces(
+        extensions: &[ExtensionComponent],
+        trace: &impl Trace,
+        view: &View,
+        cancel: Option<&CancellationToken>,
+        program_template: Option<&ProgramTraceTemplate>,
+    ) -> Result<TraceBundle, NexusProvingError> {
+        consistency::verify_shared_constants()?;
+        check_inputs(trace, view)?;
         let extensions_config = ExtensionsConfig::from(extensions);
+        Self::check_opcodes(trace, &extensions_config)?;
+        // Emulator bugs otherwise surface as an unsatisfied logup sum after the proof is computed.
+        if cfg!(debug_assertions) {
+            validate_register_accesses(trace)?;
+        }
         let extensions_iter = BASE_EXTENSIONS.iter().chain(extensions);
 
-        // Fill columns of the preprocessed trace.
-        let preprocessed_trace = PreprocessedTraces::new(log_size);
-
-        // Fill columns of the original trace.
-        let mut prover_traces = TracesBuilder::new(log_size);
-        let program_trace_ref = ProgramTraceRef {
-            program_memory: view.get_program_memory(),
-            init_memory: &[
-                // preprocessed trace is sensitive to this ordering
+        let init_memory = canonical_init_memory(
+            &[
                 view.get_ro_initial_memory(),
                 view.get_rw_initial_memory(),
                 view.get_public_input(),
             ]
             .concat(),
+        )?;
+
+        let num_steps = trace.get_num_steps();
+        let program_len = view.get_program_memory().program.len();
+        let log_size = trace_size::main_log_size(num_steps, program_len);
+        check_log_size(log_size)?;
+
+        // Fill columns of the original trace.
+        let mut prover_traces = TracesBuilder::new(log_size);
+        let public_output = canonical_public_output(view.get_public_output());
+        let committed_output_len = view.view_committed_output_len();
+        let program_trace_ref = ProgramTraceRef {
+            program_memory: view.get_program_memory(),
+            init_memory: &init_memory,
             exit_code: view.get_exit_code(),
-            public_output: view.get_public_output(),
+            public_output: &public_output,
+            num_steps,
+            committed_output_len: committed_output_len.unwrap_or_default(),
+        };
+        let program_traces = match program_template {
+            Some(template) => {
+                ProgramTracesBuilder::from_template(template, log_size, program_trace_ref)?
+            }
+            None => ProgramTracesBuilder::new(log_size, program_trace_ref),
         };
-        let program_traces = ProgramTracesBuilder::new(log_size, program_trace_ref);
         let mut prover_side_note = SideNote::new(&program_traces, view);
-        let program_steps = iter_program_steps(trace, prover_traces.num_rows());
-        for (row_idx, program_step) in program_steps.enumerate() {
-            C::fill_main_trace(
+        let mut program_steps = iter_program_steps(trace, prover_traces.num_rows());
+        let chunk_rows = main_trace_chunk_rows::<C>(&extensions_config);
+        let mut row_idx = 0;
+        while row_idx < prover_traces.num_rows() {
+            if row_idx % CANCELLATION_CHECK_ROWS == 0 {
+                cancellation::check(cancel)?;
+            }
+            let steps: Vec<_> = program_steps.by_ref().take(chunk_rows).collect();
+            let rows = row_idx..row_idx + steps.len();
+            C::fill_main_trace_chunk(
                 &mut prover_traces,
-                row_idx,
-                &program_step,
+                rows.clone(),
+                &steps,
                 &mut prover_side_note,
                 &extensions_config,
             );
+            row_idx = rows.end;
         }
+        cancellation::check(cancel)?;
 
         let finalized_trace = prover_traces.finalize();
         let finalized_program_trace = program_traces.finalize();
@@ -180,92 +769,353 @@ impl<C: MachineChip + Sync> Machine<C> {
                     .map(|ext| ext.compute_log_size(&prover_side_note)),
             )
             .collect();
+        all_log_sizes
+            .iter()
+            .try_for_each(|&log_size| check_log_size(log_size))?;
 
-        let config = PcsConfig::default();
-        // Precompute twiddles.
-        let twiddles = SimdBackend::precompute_twiddles(
-            CanonicCoset::new(
-                log_size.max(all_log_sizes.iter().copied().max().unwrap_or(0))
-                    + LOG_CONSTRAINT_DEGREE
-                    + config.fri_config.log_blowup_factor,
-            )
-            .circle_domain()
-            .half_coset,
-        );
+        let extension_traces: Vec<ComponentTrace> = extensions_iter
+            .zip(all_log_sizes.get(1..).unwrap_or_default())
+            .map(|(ext, log_size)| {
+                ext.generate_component_trace(*log_size, program_trace_ref, &mut prover_side_note)
+            })
+            .collect();
+        if let Some(table) = prover_side_note.overflowing_multiplicity() {
+            return Err(NexusProvingError::MultiplicityOverflow { table });
+        }
+
+        let program_evals: Vec<_> = finalized_program_trace
+            .clone()
+            .into_circle_evaluation()
+            .into_iter()
+            // Handle extensions for the program trace
+            .chain(extension_traces.iter().flat_map(|extension_trace| {
+                extension_trace.to_circle_evaluation(PROGRAM_TRACE_IDX)
+            }))
+            .collect();
+        // Traces are moved into their evaluations rather than copied, so that a single copy is alive while committing.
+        // The interaction trace regenerates the preprocessed trace and recovers the main trace from its commitment.
+        let main_evals: Vec<_> = finalized_trace
+            .into_circle_evaluation()
+            .into_iter()
+            // Handle extensions for the main trace
+            .chain(extension_traces.iter().flat_map(|extension_trace| {
+                extension_trace.to_circle_evaluation(ORIGINAL_TRACE_IDX)
+            }))
+            .collect();
+
+        Ok(TraceBundle {
+            extensions: extensions.to_vec(),
+            components_id: Self::components_id(extensions),
+            associated_data: view.view_associated_data().unwrap_or_default(),
+            all_log_sizes,
+            num_steps: u32::try_from(num_steps).expect("number of steps fits in u32"),
+            committed_output_len,
+            main_evals,
+            program_evals,
+            traces: BundleTraces::Generated(GeneratedTraces {
+                finalized_program_trace,
+                extension_traces,
+                side_note: prover_side_note,
+            }),
+        })
+    }
+
+    /// Restores the traces saved in `checkpoint`, together with the interaction trace.
+    fn restore_traces(
+        extensions: &[ExtensionComponent],
+        view: &View,
+        checkpoint: ProverCheckpoint,
+    ) -> Result<TraceBundle, NexusProvingError> {
+        consistency::verify_shared_constants()?;
+        let components_id = Self::components_id(extensions);
+        if checkpoint.components_id != components_id {
+            return Err(NexusProvingError::InvalidCheckpoint(
+                "checkpoint was created for a different set of components".to_string(),
+            ));
+        }
+        checkpoint.check_execution(view)?;
+        let [main_evals, interaction_evals, program_evals] = checkpoint.trees.map(|tree| {
+            tree.into_iter()
+                .map(CheckpointColumn::into_circle_evaluation)
+                .collect::<Vec<_>>()
+        });
+        if digest_evals(main_evals.iter().chain(&program_evals)) != checkpoint.trace_digest {
+            return Err(NexusProvingError::InvalidCheckpoint(
+                "stored trees don't match the trace digest".to_string(),
+            ));
+        }
+
+        Ok(TraceBundle {
+            extensions: extensions.to_vec(),
+            components_id,
+            associated_data: view.view_associated_data().unwrap_or_default(),
+            all_log_sizes: checkpoint.log_size,
+            num_steps: checkpoint.num_steps,
+            committed_output_len: checkpoint.committed_output_len,
+            main_evals,
+            program_evals,
+            traces: BundleTraces::Restored {
+                interaction_evals,
+                claimed_sum: checkpoint.claimed_sum,
+            },
+        })
+    }
+
+    /// Commits to the preprocessed and main traces, then generates and commits to the interaction trace. This is
+    /// the second stage of proving, see [`Self::build_traces`].
+    ///
+    /// `context` must support traces at least as large as the ones in `bundle`.
+    pub fn commit_and_interact(
+        bundle: TraceBundle,
+        context: &ProverContext,
+    ) -> Result<CommittedBundle<'_>, NexusProvingError> {
+        match Self::commit_with_checkpoint(
+            bundle,
+            context,
+            CheckpointMode::None,
+            None,
+            TranscriptChannel::default(),
+        )? {
+            Committed::Bundle(bundle) => Ok(*bundle),
+            Committed::Checkpoint(_) => {
+                unreachable!("committing doesn't stop without checkpointing")
+            }
+        }
+    }
+
+    fn commit_with_checkpoint<'a>(
+        bundle: TraceBundle,
+        context: &'a ProverContext,
+        checkpoint_mode: CheckpointMode<'_>,
+        cancel: Option<&CancellationToken>,
+        mut prover_channel: TranscriptChannel,
+    ) -> Result<Committed<'a>, NexusProvingError> {
+        let max_log_size = bundle.max_log_size();
+        let TraceBundle {
+            extensions,
+            components_id,
+            associated_data,
+            all_log_sizes,
+            num_steps,
+            committed_output_len,
+            main_evals,
+            program_evals,
+            traces,
+        } = bundle;
+        if components_id != Self::components_id(&extensions) {
+            return Err(NexusProvingError::InconsistentInputs(
+                "traces were built for a different set of components".to_string(),
+            ));
+        }
+        if max_log_size > context.max_log_size {
+            return Err(NexusProvingError::TraceTooLarge {
+                log_size: max_log_size,
+                max_log_size: context.max_log_size,
+            });
+        }
+        let extensions_config = ExtensionsConfig::from(&*extensions);
+        let extensions_iter = BASE_EXTENSIONS.iter().chain(&extensions);
 
         // Setup protocol.
-        let prover_channel = &mut Blake2sChannel::default();
-        for byte in view.view_associated_data().unwrap_or_default() {
+        if let Some(context_id) = &context.context_id {
+            Self::mix_context_id(&mut prover_channel, context_id);
+        }
+        prover_channel.set_stage(transcript::ASSOCIATED_DATA);
+        for byte in associated_data {
             prover_channel.mix_u64(byte.into());
         }
+        Self::mix_components_id(&mut prover_channel, &components_id);
+        if let Some(salt) = &context.salt {
+            Self::mix_salt(&mut prover_channel, salt);
+        }
 
         let mut commitment_scheme =
-            CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(config, &twiddles);
+            CommitmentSchemeProver::<SimdBackend, TranscriptMerkleChannel>::new(
+                context.config,
+                &context.twiddles,
+            );
+        prover_channel.set_stage(transcript::LOG_SIZES);
         all_log_sizes.iter().for_each(|log_size| {
             prover_channel.mix_u64(*log_size as u64);
         });
 
-        let mut tree_builder = commitment_scheme.tree_builder();
-        let _preprocessed_trace_location = tree_builder.extend_evals(
-            preprocessed_trace
-                .clone()
-                .into_circle_evaluation()
-                .into_iter()
-                .chain(finalized_program_trace.clone().into_circle_evaluation()),
-        );
+        let checkpoint_trees = (matches!(traces, BundleTraces::Generated(_))
+            && !matches!(checkpoint_mode, CheckpointMode::None))
+        .then(|| {
+            let trees = [&main_evals, &program_evals].map(|evals| {
+                evals
+                    .iter()
+                    .map(CheckpointColumn::from_circle_evaluation)
+                    .collect::<Vec<_>>()
+            });
+            (trees, digest_evals(main_evals.iter().chain(&program_evals)))
+        });
 
-        let extension_traces: Vec<ComponentTrace> = extensions_iter
-            .clone()
-            .zip(all_log_sizes.get(1..).unwrap_or_default())
-            .map(|(ext, log_size)| {
-                ext.generate_component_trace(*log_size, program_trace_ref, &mut prover_side_note)
-            })
-            .collect();
-        // Handle extensions for the preprocessed trace
-        for extension_trace in &extension_traces {
-            tree_builder.extend_evals(extension_trace.to_circle_evaluation(PREPROCESSED_TRACE_IDX));
-        }
-        tree_builder.commit(prover_channel);
+        prover_channel.set_stage(transcript::PREPROCESSED_TRACE);
+        context
+            .preprocessed_tree(&extensions, &all_log_sizes)
+            .graft(&mut commitment_scheme, &mut prover_channel);
+        // The program tree is committed to last, its root is bound to the transcript before the main trace.
+        let program_tree = CommittedTree::commit(context.config, &context.twiddles, program_evals);
+        prover_channel.set_stage(transcript::PROGRAM_TRACE);
+        program_tree.mix_root(&mut prover_channel);
+        cancellation::check(cancel)?;
 
         let mut tree_builder = commitment_scheme.tree_builder();
-        let _main_trace_location =
-            tree_builder.extend_evals(finalized_trace.clone().into_circle_evaluation());
-        // Handle extensions for the main trace
-        for extension_trace in &extension_traces {
-            tree_builder.extend_evals(extension_trace.to_circle_evaluation(ORIGINAL_TRACE_IDX));
-        }
-        tree_builder.commit(prover_channel);
+        let _main_trace_location = tree_builder.extend_evals(main_evals);
+        prover_channel.set_stage(transcript::MAIN_TRACE);
+        tree_builder.commit(&mut prover_channel);
+        cancellation::check(cancel)?;
 
         let mut lookup_elements = AllLookupElements::default();
-        C::draw_lookup_elements(&mut lookup_elements, prover_channel, &extensions_config);
-
-        let (interaction_trace, claimed_sum) = generate_interaction_trace::<C>(
-            &finalized_trace,
-            &preprocessed_trace,
-            &finalized_program_trace,
-            &lookup_elements,
+        prover_channel.set_stage(transcript::LOOKUP_ELEMENTS);
+        C::draw_lookup_elements(
+            &mut lookup_elements,
+            &mut prover_channel,
+            &extensions_config,
         );
 
+        // The main component's interaction columns are the only ones reading the main, preprocessed and program
+        // traces, which are dropped as soon as they are generated, before any extension runs.
+        let (main_interaction, extension_inputs, mut all_claimed_sum) = match traces {
+            BundleTraces::Generated(generated) => {
+                let GeneratedTraces {
+                    finalized_program_trace,
+                    extension_traces,
+                    side_note,
+                } = generated;
+                let preprocessed_trace = PreprocessedTraces::new(all_log_sizes[0]);
+                let finalized_trace = Self::committed_main_trace(
+                    &commitment_scheme.trees[ORIGINAL_TRACE_IDX].polynomials,
+                    all_log_sizes[0],
+                    &context.twiddles,
+                );
+                let (interaction_trace, claimed_sum) = generate_interaction_trace::<C>(
+                    &finalized_trace,
+                    &preprocessed_trace,
+                    &finalized_program_trace,
+                    &lookup_elements,
+                );
+                (
+                    interaction_trace,
+                    Some((extension_traces, side_note)),
+                    vec![claimed_sum],
+                )
+            }
+            BundleTraces::Restored {
+                interaction_evals,
+                claimed_sum,
+            } => (interaction_evals, None, claimed_sum),
+        };
+
+        // Each component's columns are handed to the tree builder as soon as they are generated, which interpolates
+        // them and frees their evaluations before the next component is generated. The tree, and the proof, are the
+        // same as when extending all columns at once. Stopping at the checkpoint only needs copies of the columns.
+        let stop_at_checkpoint = matches!(checkpoint_mode, CheckpointMode::Stop(_));
+        let mut checkpoint_interaction = checkpoint_trees.is_some().then(Vec::new);
         let mut tree_builder = commitment_scheme.tree_builder();
-        let _interaction_trace_location = tree_builder.extend_evals(interaction_trace);
-        // Handle extensions for the interaction trace
-        let mut all_claimed_sum = vec![claimed_sum];
-        for (ext, extension_trace) in extensions_iter.clone().zip(extension_traces) {
-            let (interaction_trace, claimed_sum) = ext.generate_interaction_trace(
-                extension_trace,
-                &prover_side_note,
-                &lookup_elements,
-            );
-            all_claimed_sum.push(claimed_sum);
-            tree_builder.extend_evals(interaction_trace);
+        let mut hand_off = |evals: TraceEvals| {
+            if let Some(columns) = &mut checkpoint_interaction {
+                columns.extend(evals.iter().map(CheckpointColumn::from_circle_evaluation));
+            }
+            if !stop_at_checkpoint {
+                let _interaction_trace_location = tree_builder.extend_evals(evals);
+            }
+        };
+        hand_off(main_interaction);
+        if let Some((extension_traces, side_note)) = extension_inputs {
+            for (ext, extension_trace) in extensions_iter.zip(extension_traces) {
+                let (interaction_trace, claimed_sum) =
+                    ext.generate_interaction_trace(extension_trace, &side_note, &lookup_elements);
+                all_claimed_sum.push(claimed_sum);
+                hand_off(interaction_trace);
+            }
         }
-        tree_builder.commit(prover_channel);
+
+        // Components pass lookups to each other, the verifier only accepts claimed sums that cancel out in total.
+        // Fail early instead of producing a proof that is going to be rejected.
+        if all_claimed_sum.iter().sum::<SecureField>() != SecureField::zero() {
+            return Err(stwo::prover::ProvingError::ConstraintsNotSatisfied.into());
+        }
+
+        if let Some(([main, program], trace_digest)) = checkpoint_trees {
+            let view = match checkpoint_mode {
+                CheckpointMode::Save(_, view) | CheckpointMode::Stop(view) => view,
+                CheckpointMode::None => unreachable!("trees are only copied when checkpointing"),
+            };
+            let checkpoint = ProverCheckpoint {
+                components_id,
+                program_hash: ProgramTraceTemplate::program_hash(view.get_program_memory()),
+                public_input_hash: checkpoint::public_input_hash(view),
+                trace_digest,
+                log_size: all_log_sizes.clone(),
+                num_steps,
+                committed_output_len,
+                claimed_sum: all_claimed_sum.clone(),
+                trees: [
+                    main,
+                    checkpoint_interaction
+                        .expect("interaction columns are copied when checkpointing"),
+                    program,
+                ],
+            };
+            match checkpoint_mode {
+                CheckpointMode::Save(dir, _) => checkpoint.save(dir)?,
+                CheckpointMode::Stop(_) => return Ok(Committed::Checkpoint(checkpoint)),
+                CheckpointMode::None => {}
+            }
+        }
+
+        prover_channel.set_stage(transcript::INTERACTION_TRACE);
+        tree_builder.commit(&mut prover_channel);
+        program_tree.graft_mixed(&mut commitment_scheme);
+        Self::mix_claimed_sums(&mut prover_channel, &all_claimed_sum);
+        cancellation::check(cancel)?;
+
+        Ok(Committed::Bundle(Box::new(CommittedBundle {
+            extensions,
+            components_id,
+            all_log_sizes,
+            num_steps,
+            committed_output_len,
+            all_claimed_sum,
+            lookup_elements,
+            prover_channel,
+            commitment_scheme,
+            config: context.config,
+            salt: context.salt,
+        })))
+    }
+
+    /// Proves the committed traces, the last stage of proving, see [`Self::build_traces`].
+    pub fn finish_proof(bundle: CommittedBundle<'_>) -> Result<Proof, NexusProvingError> {
+        Self::finish(bundle).map(|(proof, _)| proof)
+    }
+
+    fn finish(
+        bundle: CommittedBundle<'_>,
+    ) -> Result<(Proof, TranscriptChannel), NexusProvingError> {
+        let CommittedBundle {
+            extensions,
+            components_id,
+            all_log_sizes,
+            num_steps,
+            committed_output_len,
+            all_claimed_sum,
+            lookup_elements,
+            mut prover_channel,
+            commitment_scheme,
+            config,
+            salt,
+        } = bundle;
+        let extensions_config = ExtensionsConfig::from(&*extensions);
+        let extensions_iter = BASE_EXTENSIONS.iter().chain(&extensions);
 
         let tree_span_provider = &mut TraceLocationAllocator::default();
         let main_component = MachineComponent::new(
             tree_span_provider,
-            MachineEval::<C>::new(log_size, lookup_elements.clone(), extensions_config.clone()),
-            claimed_sum,
+            MachineEval::<C>::new(all_log_sizes[0], lookup_elements.clone(), extensions_config),
+            all_claimed_sum[0],
         );
         let ext_components: Vec<Box<dyn ComponentProver<SimdBackend>>> = extensions_iter
             .zip(all_claimed_sum.get(1..).unwrap_or_default())
@@ -282,19 +1132,38 @@ impl<C: MachineChip + Sync> Machine<C> {
         let mut components_ref: Vec<&dyn ComponentProver<SimdBackend>> =
             ext_components.iter().map(|c| &**c).collect();
         components_ref.insert(0, &main_component);
-        let proof = prove::<SimdBackend, Blake2sMerkleChannel>(
+        prover_channel.set_stage(transcript::STARK_PROOF);
+        let proof = prove::<SimdBackend, TranscriptMerkleChannel>(
             &components_ref,
-            prover_channel,
+            &mut prover_channel,
             commitment_scheme,
         )?;
 
-        Ok(Proof {
+        let proof = Proof {
             stark_proof: proof,
-            claimed_sum: all_claimed_sum,
+            claimed_sums: all_claimed_sum,
             log_size: all_log_sizes,
-        })
+            num_steps,
+            committed_output_len,
+            metadata: ProofMetadata {
+                crate_version: env!("CARGO_PKG_VERSION").to_string(),
+                components_id,
+                pcs_params: PcsParams::from(&config),
+                salt,
+            },
+        };
+        Ok((proof, prover_channel))
     }
 
+    /// Verifies the proof against the public data of the execution.
+    ///
+    /// `init_memory` may be given in any order, it is sorted by address before being committed to. Entries
+    /// with duplicate addresses are rejected.
+    ///
+    /// A proof generated for a different set of components is rejected with [`VerificationError::ComponentsMismatch`].
+    /// A malformed exit code, or output entries overlapping it or each other, are rejected with
+    /// [`VerificationError::PublicOutput`]. The proof doesn't commit to a memory layout, the output region is only
+    /// checked by [`Self::verify_with_memory_layout`].
     pub fn verify(
         proof: Proof,
         program_info: &ProgramInfo,
@@ -314,6 +1183,168 @@ impl<C: MachineChip + Sync> Machine<C> {
         )
     }
 
+    /// Verifies the proof like [`Self::verify`], with the exit code and the public output checked against the memory
+    /// layout of the guest by [`check_public_output`].
+    pub fn verify_with_memory_layout(
+        layout: &LinearMemoryLayout,
+        proof: Proof,
+        program_info: &ProgramInfo,
+        ad: &[u8],
+        init_memory: &[MemoryInitializationEntry],
+        exit_code: &[PublicOutputEntry],
+        output_memory: &[PublicOutputEntry],
+    ) -> Result<(), VerificationError> {
+        check_public_output(Some(layout), exit_code, output_memory)?;
+        Self::verify(
+            proof,
+            program_info,
+            ad,
+            init_memory,
+            exit_code,
+            output_memory,
+        )
+    }
+
+    /// Verifies the proof like [`Self::verify`], with the additional checks and inputs of `options`.
+    pub fn verify_with_options(
+        options: &VerifierOptions,
+        proof: Proof,
+        program_info: &ProgramInfo,
+        ad: &[u8],
+        init_memory: &[MemoryInitializationEntry],
+        exit_code: &[PublicOutputEntry],
+        output_memory: &[PublicOutputEntry],
+    ) -> Result<(), VerificationError> {
+        if let Some(min_bits) = options.min_security_bits {
+            let conjectured_bits = proof.security_report().conjectured_bits;
+            if conjectured_bits < min_bits {
+                return Err(VerificationError::InsufficientSecurity {
+                    conjectured_bits,
+                    min_bits,
+                });
+            }
+        }
+        if let Some(policy) = &options.policy {
+            policy.check(&proof, output_memory.len())?;
+        }
+        let layout_artifact = match &options.artifacts {
+            Some(artifacts) => {
+                if !artifacts.matches::<C>() {
+                    return Err(VerificationError::ArtifactsMismatch);
+                }
+                artifacts.policy.check(&proof, output_memory.len())?;
+                let layout = artifacts
+                    .layouts
+                    .get(&proof.log_size[0])
+                    .ok_or(VerificationError::ArtifactsMismatch)?;
+                Some(*layout)
+            }
+            None => None,
+        };
+
+        let output_memory = canonical_public_output(output_memory);
+        let mut verifier_channel = TranscriptChannel::default();
+        if let Some(context_id) = &options.context_id {
+            Self::mix_context_id(&mut verifier_channel, context_id);
+        }
+        Self::verify_with_output_stream(
+            &[],
+            proof,
+            program_info,
+            ad,
+            init_memory,
+            exit_code,
+            &mut output_memory.into_iter(),
+            &mut verifier_channel,
+            None,
+            layout_artifact.as_ref(),
+        )
+    }
+
+    /// Verifies the proof and checks that the public output satisfies every claim.
+    ///
+    /// Claims don't replace `output_memory`: the memory checking argument commits to every byte of the public
+    /// output, so the verifier needs all of them to reconstruct the statement, and the claims are checked against
+    /// the reconstructed output once the proof is verified. Opening only part of the output would require a
+    /// separate commitment to it, changing the proof format.
+    ///
+    /// Offsets of the claims are relative to the start of the public output, the first address past the exit code.
+    /// A proof of a guest that didn't exit fails with [`VerifyOrExitError::NoExitCode`].
+    pub fn verify_with_output_claims(
+        proof: Proof,
+        program_info: &ProgramInfo,
+        ad: &[u8],
+        init_memory: &[MemoryInitializationEntry],
+        exit_code: &[PublicOutputEntry],
+        output_memory: &[PublicOutputEntry],
+        claims: &[crate::OutputClaim],
+    ) -> Result<(), VerifyOrExitError> {
+        Self::verify(
+            proof,
+            program_info,
+            ad,
+            init_memory,
+            exit_code,
+            output_memory,
+        )?;
+
+        let base = public_output_start(exit_code).ok_or(VerifyOrExitError::NoExitCode)?;
+        let output = io_entries_into_vec(base, output_memory);
+        claims.iter().try_for_each(|claim| claim.check(&output))
+    }
+
+    /// Verifies the proof and checks the contents of some regions of the public output.
+    ///
+    /// `expected` lists region names from `regions` together with their expected bytes, regions that aren't listed
+    /// are left unchecked. Expected bytes shorter than the region are padded with zeros. As with
+    /// [`Self::verify_with_output_claims`], `output_memory` must still hold the entries of every region: the proof
+    /// commits to the whole output, unchecked regions are only carried along.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_with_output_regions(
+        proof: Proof,
+        program_info: &ProgramInfo,
+        ad: &[u8],
+        init_memory: &[MemoryInitializationEntry],
+        exit_code: &[PublicOutputEntry],
+        output_memory: &[PublicOutputEntry],
+        regions: &[OutputRegion],
+        expected: &[(&str, &[u8])],
+    ) -> Result<(), VerifyOrExitError> {
+        Self::verify(
+            proof,
+            program_info,
+            ad,
+            init_memory,
+            exit_code,
+            output_memory,
+        )?;
+
+        for &(name, bytes) in expected {
+            let region = regions
+                .iter()
+                .find(|region| region.name == name)
+                .ok_or_else(|| VerifyOrExitError::UnknownOutputRegion(name.to_string()))?;
+            let mut expected = bytes.to_vec();
+            if expected.len() > region.len as usize {
+                return Err(VerifyOrExitError::OutputRegionTooShort {
+                    region: name.to_string(),
+                    len: region.len,
+                    expected_len: expected.len(),
+                });
+            }
+            expected.resize(region.len as usize, 0);
+            let actual = region.bytes(output_memory);
+            if actual != expected {
+                return Err(VerifyOrExitError::OutputRegionMismatch {
+                    region: name.to_string(),
+                    expected,
+                    actual,
+                });
+            }
+        }
+        Ok(())
+    }
+
     pub fn verify_with_extensions(
         extensions: &[ExtensionComponent],
         proof: Proof,
@@ -322,47 +1353,218 @@ impl<C: MachineChip + Sync> Machine<C> {
         init_memory: &[MemoryInitializationEntry],
         exit_code: &[PublicOutputEntry],
         output_memory: &[PublicOutputEntry],
+    ) -> Result<(), VerificationError> {
+        let output_memory = canonical_public_output(output_memory);
+        Self::verify_with_output_stream(
+            extensions,
+            proof,
+            program_info,
+            ad,
+            init_memory,
+            exit_code,
+            &mut output_memory.into_iter(),
+            &mut TranscriptChannel::default(),
+            None,
+            None,
+        )
+    }
+
+    /// Verifies the proof like [`Self::verify`], with the program trace built from `template`.
+    ///
+    /// A template built for a program other than `program_info`, or for a log size other than the one of the proof,
+    /// is rejected with [`VerificationError::ProgramTemplate`].
+    pub fn verify_with_program_template(
+        template: &ProgramTraceTemplate,
+        proof: Proof,
+        program_info: &ProgramInfo,
+        ad: &[u8],
+        init_memory: &[MemoryInitializationEntry],
+        exit_code: &[PublicOutputEntry],
+        output_memory: &[PublicOutputEntry],
+    ) -> Result<(), VerificationError> {
+        let output_memory = canonical_public_output(output_memory);
+        Self::verify_with_output_stream(
+            &[],
+            proof,
+            program_info,
+            ad,
+            init_memory,
+            exit_code,
+            &mut output_memory.into_iter(),
+            &mut TranscriptChannel::default(),
+            Some(template),
+            None,
+        )
+    }
+
+    /// Verifies the proof like [`Self::verify`], recording every interaction with the Fiat-Shamir channel.
+    ///
+    /// The log is returned whether verification succeeds or not, up to the point where it stopped. Logs of the
+    /// prover and the verifier of the same proof are identical, see [`Self::prove_with_transcript`].
+    pub fn verify_with_transcript(
+        proof: Proof,
+        program_info: &ProgramInfo,
+        ad: &[u8],
+        init_memory: &[MemoryInitializationEntry],
+        exit_code: &[PublicOutputEntry],
+        output_memory: &[PublicOutputEntry],
+    ) -> (Result<(), VerificationError>, TranscriptLog) {
+        let output_memory = canonical_public_output(output_memory);
+        let mut verifier_channel = TranscriptChannel::recording();
+        let result = Self::verify_with_output_stream(
+            &[],
+            proof,
+            program_info,
+            ad,
+            init_memory,
+            exit_code,
+            &mut output_memory.into_iter(),
+            &mut verifier_channel,
+            None,
+            None,
+        );
+        (result, verifier_channel.into_log())
+    }
+
+    /// Same as [`Self::verify_with_extensions`], with the public output consumed lazily from `output_source`.
+    ///
+    /// Entries must be sorted by address, as after [`canonical_public_output`], which the slice-based API applies to
+    /// its input; an entry with a lower address than the previous one is rejected with
+    /// [`VerificationError::UnsortedPublicOutput`]. The output is written into the preprocessed trace of the RAM
+    /// extension while it is read, it is never collected on the side.
+    pub fn verify_streaming(
+        extensions: &[ExtensionComponent],
+        proof: Proof,
+        program_info: &ProgramInfo,
+        ad: &[u8],
+        init_memory: &[MemoryInitializationEntry],
+        exit_code: &[PublicOutputEntry],
+        output_source: impl IntoIterator<Item = PublicOutputEntry>,
+    ) -> Result<(), VerificationError> {
+        let mut unsorted = None;
+        let mut last_address = 0;
+        let mut sorted_output = output_source.into_iter().map_while(|entry| {
+            if entry.address < last_address {
+                unsorted = Some(entry.address);
+                return None;
+            }
+            last_address = entry.address;
+            Some(entry)
+        });
+        let result = Self::verify_with_output_stream(
+            extensions,
+            proof,
+            program_info,
+            ad,
+            init_memory,
+            exit_code,
+            &mut sorted_output,
+            &mut TranscriptChannel::default(),
+            None,
+            None,
+        );
+        match unsorted {
+            Some(address) => Err(VerificationError::UnsortedPublicOutput { address }),
+            None => result,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn verify_with_output_stream(
+        extensions: &[ExtensionComponent],
+        proof: Proof,
+        program_info: &ProgramInfo,
+        ad: &[u8],
+        init_memory: &[MemoryInitializationEntry],
+        exit_code: &[PublicOutputEntry],
+        output_source: &mut dyn Iterator<Item = PublicOutputEntry>,
+        verifier_channel: &mut TranscriptChannel,
+        program_template: Option<&ProgramTraceTemplate>,
+        layout_artifact: Option<&LayoutArtifact>,
     ) -> Result<(), VerificationError> {
         let Proof {
             stark_proof: proof,
-            claimed_sum,
+            claimed_sums,
             log_size: all_log_sizes,
+            num_steps,
+            committed_output_len,
+            metadata,
         } = proof;
 
-        if claimed_sum.len() != extensions.len() + BASE_EXTENSIONS.len() + 1 {
-            return Err(VerificationError::InvalidStructure(
+        let components_id = Self::components_id(extensions);
+        if metadata.components_id != components_id {
+            return Err(VerificationError::ComponentsMismatch {
+                expected: components_id,
+                actual: metadata.components_id,
+            });
+        }
+        if claimed_sums.len() != extensions.len() + BASE_EXTENSIONS.len() + 1 {
+            return Err(StwoVerificationError::InvalidStructure(
                 "claimed sum len mismatch".to_string(),
-            ));
+            )
+            .into());
         }
-        if all_log_sizes.len() != extensions.len() + BASE_EXTENSIONS.len() + 1 {
-            return Err(VerificationError::InvalidStructure(
-                "log size len mismatch".to_string(),
-            ));
+        // Traces of these sizes are allocated below, the sizes must be checked before. The main trace has the size the
+        // prover derives from the number of steps and the length of the program.
+        Self::check_log_sizes(extensions, all_log_sizes)?;
+        if all_log_sizes[0]
+            != trace_size::main_log_size(num_steps as usize, program_info.program.len())
+        {
+            return Err(StwoVerificationError::InvalidStructure(format!(
+                "unsupported log sizes {all_log_sizes:?}"
+            ))
+            .into());
+        }
+        if proof.commitments.len() <= PROGRAM_TRACE_IDX {
+            return Err(StwoVerificationError::InvalidStructure(
+                "commitments len mismatch".to_string(),
+            )
+            .into());
+        }
+        if u64::from(num_steps) > 1u64.checked_shl(all_log_sizes[0]).unwrap_or(u64::MAX) {
+            return Err(StwoVerificationError::InvalidStructure(
+                "more steps than rows in the main trace".to_string(),
+            )
+            .into());
         }
-        if claimed_sum.iter().sum::<SecureField>() != SecureField::zero() {
-            return Err(VerificationError::InvalidStructure(
+        // Components pass lookups to each other, only the sum over all of them cancels out.
+        if claimed_sums.iter().sum::<SecureField>() != SecureField::zero() {
+            return Err(StwoVerificationError::InvalidStructure(
                 "claimed logup sum is not zero".to_string(),
-            ));
+            )
+            .into());
         }
+        program_info
+            .check_initial_args()
+            .map_err(|err| StwoVerificationError::InvalidStructure(err.to_string()))?;
+        let init_memory = &canonical_init_memory(init_memory)
+            .map_err(|err| StwoVerificationError::InvalidStructure(err.to_string()))?;
+        let exit_code_addresses = exit_code_addresses(None, exit_code)?;
 
         let extensions_config = ExtensionsConfig::from(extensions);
         let extensions_iter = BASE_EXTENSIONS.iter().chain(extensions);
 
-        let config = PcsConfig::default();
-        let verifier_channel = &mut Blake2sChannel::default();
+        let config = metadata.pcs_params.verifier_config();
+        verifier_channel.set_stage(transcript::ASSOCIATED_DATA);
         for &byte in ad {
             verifier_channel.mix_u64(byte.into());
         }
+        Self::mix_components_id(verifier_channel, &components_id);
+        if let Some(salt) = &metadata.salt {
+            Self::mix_salt(verifier_channel, salt);
+        }
+        verifier_channel.set_stage(transcript::LOG_SIZES);
         all_log_sizes.iter().for_each(|log_size| {
             verifier_channel.mix_u64(*log_size as u64);
         });
 
-        let commitment_scheme = &mut CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(config);
+        let commitment_scheme =
+            &mut CommitmentSchemeVerifier::<TranscriptMerkleChannel>::new(config);
 
-        // simulate the prover and compute expected commitment to preprocessed trace
+        // simulate the prover and compute expected commitments to the preprocessed and program trees, the preprocessed
+        // root is precomputed in the artifacts if there are any
         {
             let config = PcsConfig::default();
-            let verifier_channel = &mut verifier_channel.clone();
             let twiddles = SimdBackend::precompute_twiddles(
                 CanonicCoset::new(
                     all_log_sizes
@@ -376,90 +1578,134 @@ impl<C: MachineChip + Sync> Machine<C> {
                 .circle_domain()
                 .half_coset,
             );
-            let commitment_scheme =
-                &mut CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(
-                    config, &twiddles,
-                );
-            let preprocessed_trace = PreprocessedTraces::new(all_log_sizes[0]);
+            let preprocessed_expected = match layout_artifact {
+                Some(layout) => layout.preprocessed_root,
+                None => CommittedTree::commit(
+                    config,
+                    &twiddles,
+                    preprocessed_evals(extensions, &all_log_sizes),
+                )
+                .root(),
+            };
+            // The public output is read from `output_source` by the extensions that need it.
             let program_trace_ref = ProgramTraceRef {
                 program_memory: program_info,
                 init_memory,
                 exit_code,
-                public_output: output_memory,
+                public_output: &[],
+                num_steps: num_steps as usize,
+                committed_output_len: committed_output_len.unwrap_or_default(),
             };
-            let program_trace =
-                ProgramTracesBuilder::new(all_log_sizes[0], program_trace_ref).finalize();
-
-            let mut tree_builder = commitment_scheme.tree_builder();
-            let _preprocessed_trace_location = tree_builder.extend_evals(
-                preprocessed_trace
-                    .into_circle_evaluation()
-                    .into_iter()
-                    .chain(program_trace.into_circle_evaluation()),
-            );
-            // Handle extensions for the preprocessed trace
+            let program_trace = match program_template {
+                Some(template) => ProgramTracesBuilder::from_template(
+                    template,
+                    all_log_sizes[0],
+                    program_trace_ref,
+                )?,
+                None => ProgramTracesBuilder::new(all_log_sizes[0], program_trace_ref),
+            }
+            .finalize();
+
+            let mut program_evals = program_trace.into_circle_evaluation();
+            // Output entries are sorted by address, a duplicate follows the entry it repeats. The output is checked
+            // while it is read, as in `check_public_output` without a memory layout.
+            let mut invalid_output = None;
+            let mut last_address = None;
+            let mut output_len = 0;
+            let mut checked_output = output_source.map_while(|entry| {
+                let address = entry.address;
+                if exit_code_addresses.contains(&address) {
+                    invalid_output = Some(PublicOutputError::OutsideOutputRegion { address });
+                    return None;
+                }
+                if last_address.replace(address) == Some(address) {
+                    invalid_output = Some(PublicOutputError::DuplicateAddress { address });
+                    return None;
+                }
+                output_len += 1;
+                Some(entry)
+            });
+            // Handle extensions for the program trace
             for (ext, log_size) in extensions_iter
                 .clone()
                 .zip(all_log_sizes.get(1..).unwrap_or_default())
             {
-                tree_builder
-                    .extend_evals(ext.generate_preprocessed_trace(*log_size, program_trace_ref));
+                let program_trace = ext
+                    .generate_program_trace_streaming(
+                        *log_size,
+                        program_trace_ref,
+                        &mut checked_output,
+                    )
+                    .ok_or(VerificationError::PublicMemoryOverflow)?;
+                program_evals.extend(program_trace);
+            }
+            if let Some(error) = invalid_output {
+                return Err(error.into());
             }
-            tree_builder.commit(verifier_channel);
+            // Once the guest commits, bytes it wrote past the committed ones aren't part of the public output.
+            if let Some(committed) = committed_output_len {
+                if output_len != committed as usize {
+                    return Err(PublicOutputError::UncommittedOutput {
+                        committed,
+                        len: output_len,
+                    }
+                    .into());
+                }
+            }
+            let program_tree = CommittedTree::commit(config, &twiddles, program_evals);
 
-            let preprocessed_expected = commitment_scheme.roots()[PREPROCESSED_TRACE_IDX];
             let preprocessed = proof.commitments[PREPROCESSED_TRACE_IDX];
             if preprocessed_expected != preprocessed {
-                return Err(VerificationError::InvalidStructure(format!("invalid commitment to preprocessed trace: \
-                                                                        expected {preprocessed_expected}, got {preprocessed}")));
+                return Err(StwoVerificationError::InvalidStructure(format!("invalid commitment to preprocessed trace: \
+                                                                           expected {preprocessed_expected}, got {preprocessed}")).into());
+            }
+            let program_expected = program_tree.root();
+            let program = proof.commitments[PROGRAM_TRACE_IDX];
+            if program_expected != program {
+                return Err(StwoVerificationError::InvalidStructure(format!(
+                    "invalid commitment to program trace: expected {program_expected}, got {program}"
+                ))
+                .into());
             }
         }
 
-        // Retrieve the expected column sizes in each commitment interaction, from the AIR.
-
-        // Info evaluation can be avoided if the prover sends lookup elements along with the proof, this requires
-        // implementing  [`serde::Serialize`] for all relations and [`AllLookupElements`]. Note that the verifier
-        // should still independently draw elements and match it against received ones.
-        let mut sizes = vec![
-            components::machine_component_info::<C>(extensions_config.clone())
-                .mask_offsets
-                .as_cols_ref()
-                .map_cols(|_| all_log_sizes[0]),
-        ];
-        for (ext, log_size) in extensions_iter
-            .clone()
-            .zip(all_log_sizes.get(1..).unwrap_or_default())
-        {
-            sizes.push(ext.trace_sizes(*log_size));
-        }
-        let mut log_sizes = TreeVec::concat_cols(sizes.into_iter());
-        // use the fact that preprocessed columns are only allowed to have [0] mask
-        log_sizes[PREPROCESSED_TRACE_IDX] =
-            vec![all_log_sizes[0]; PreprocessedColumn::COLUMNS_NUM + ProgramColumn::COLUMNS_NUM];
-        for (ext, log_size) in extensions_iter
-            .clone()
-            .zip(all_log_sizes.get(1..).unwrap_or_default())
-        {
-            // extending log_sizes[PREPROCESSED_TRACE_IDX] with the dimension of the preprocessed columns
-            log_sizes[PREPROCESSED_TRACE_IDX].extend(ext.preprocessed_trace_sizes(*log_size));
-        }
+        // Retrieve the expected column sizes in each commitment interaction, from the AIR unless precomputed.
+        let machine_log_sizes = match layout_artifact {
+            Some(layout) => layout.log_degree_bounds(all_log_sizes[0]),
+            None => Self::layout_with_extensions(extensions, all_log_sizes[0]).log_degree_bounds(),
+        };
+        let log_sizes =
+            Self::extend_committed_log_sizes(extensions, &all_log_sizes, machine_log_sizes);
 
-        for idx in [PREPROCESSED_TRACE_IDX, ORIGINAL_TRACE_IDX] {
-            commitment_scheme.commit(proof.commitments[idx], &log_sizes[idx], verifier_channel);
-        }
+        verifier_channel.set_stage(transcript::PREPROCESSED_TRACE);
+        commitment_scheme.commit(
+            proof.commitments[PREPROCESSED_TRACE_IDX],
+            &log_sizes[PREPROCESSED_TRACE_IDX],
+            verifier_channel,
+        );
+        // The program tree is committed to last, see `PROGRAM_TRACE_IDX`.
+        verifier_channel.set_stage(transcript::PROGRAM_TRACE);
+        TranscriptMerkleChannel::mix_root(verifier_channel, proof.commitments[PROGRAM_TRACE_IDX]);
+        verifier_channel.set_stage(transcript::MAIN_TRACE);
+        commitment_scheme.commit(
+            proof.commitments[ORIGINAL_TRACE_IDX],
+            &log_sizes[ORIGINAL_TRACE_IDX],
+            verifier_channel,
+        );
 
         let mut lookup_elements = AllLookupElements::default();
+        verifier_channel.set_stage(transcript::LOOKUP_ELEMENTS);
         C::draw_lookup_elements(&mut lookup_elements, verifier_channel, &extensions_config);
 
         let tree_span_provider = &mut TraceLocationAllocator::default();
         let main_component = MachineComponent::new(
             tree_span_provider,
             MachineEval::<C>::new(all_log_sizes[0], lookup_elements.clone(), extensions_config),
-            claimed_sum[0],
+            claimed_sums[0],
         );
 
         let ext_components: Vec<Box<dyn Component>> = extensions_iter
-            .zip(claimed_sum.get(1..).unwrap_or_default())
+            .zip(claimed_sums.get(1..).unwrap_or_default())
             .zip(all_log_sizes.get(1..).unwrap_or_default())
             .map(|((ext, claimed_sum), log_size)| {
                 ext.to_component(
@@ -473,60 +1719,4107 @@ impl<C: MachineChip + Sync> Machine<C> {
         let mut components_ref: Vec<&dyn Component> = ext_components.iter().map(|c| &**c).collect();
         components_ref.insert(0, &main_component);
 
+        verifier_channel.set_stage(transcript::INTERACTION_TRACE);
         commitment_scheme.commit(
             proof.commitments[INTERACTION_TRACE_IDX],
             &log_sizes[INTERACTION_TRACE_IDX],
             verifier_channel,
         );
+        // Its root is already mixed into the channel.
+        commitment_scheme.commit(
+            proof.commitments[PROGRAM_TRACE_IDX],
+            &log_sizes[PROGRAM_TRACE_IDX],
+            &mut TranscriptChannel::default(),
+        );
+        Self::mix_claimed_sums(verifier_channel, claimed_sums);
 
-        verify(&components_ref, verifier_channel, commitment_scheme, proof)
+        verifier_channel.set_stage(transcript::STARK_PROOF);
+        verify(&components_ref, verifier_channel, commitment_scheme, proof)?;
+        Ok(())
     }
 
-    /// Computes minimum allowed log_size from a slice of lengths.
-    fn max_log_size(sizes: &[usize]) -> u32 {
-        sizes
-            .iter()
-            .map(|size| size.next_power_of_two().trailing_zeros())
-            .max()
-            .expect("sizes is empty")
+    /// Returns the columns committed to by the machine component for traces of `log_size`, without proving.
+    pub fn layout(log_size: u32) -> MachineLayout {
+        Self::layout_with_extensions(&[], log_size)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use nexus_vm::{
-        riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
-        trace::k_trace_direct,
-    };
+    /// Returns descriptions of the constraints and lookups of the chips that describe them, in proving order.
+    pub fn chip_descriptions() -> Vec<ChipDescription> {
+        let mut descriptions = Vec::new();
+        C::collect_descriptions(&mut descriptions);
+        descriptions
+    }
 
-    #[test]
-    fn prove_verify() {
-        let basic_block = vec![BasicBlock::new(vec![
-            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
-            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 0),
-            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 3, 2, 1),
-            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 4, 3, 2),
-            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 5, 4, 3),
-            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 6, 5, 4),
-        ])];
-        let (view, program_trace) =
-            k_trace_direct(&basic_block, 1).expect("error generating trace");
+    /// Same as [`Self::layout`], for a machine proving with `extensions`, which may add lookups to the chips.
+    pub fn layout_with_extensions(
+        extensions: &[ExtensionComponent],
+        log_size: u32,
+    ) -> MachineLayout {
+        // Info evaluation can be avoided if the prover sends lookup elements along with the proof, this requires
+        // implementing  [`serde::Serialize`] for all relations and [`AllLookupElements`]. Note that the verifier
+        // should still independently draw elements and match it against received ones.
+        let info = components::machine_component_info::<C>(ExtensionsConfig::from(extensions));
+        let width = |tree_idx: usize| info.mask_offsets.get(tree_idx).map_or(0, Vec::len);
+        debug_assert_eq!(width(ORIGINAL_TRACE_IDX), GlobalColumn::COLUMNS_NUM);
 
-        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
-        Machine::<BaseComponent>::verify(
-            proof,
-            view.get_program_memory(),
-            &[],
-            &[
-                view.get_public_input(),
-                view.get_ro_initial_memory(),
-                view.get_rw_initial_memory(),
-            ]
-            .concat(),
-            view.get_exit_code(),
-            view.get_public_output(),
-        )
-        .unwrap();
+        MachineLayout {
+            log_size,
+            preprocessed_columns: PreprocessedColumn::ALL_VARIANTS.to_vec(),
+            program_columns: ProgramColumn::ALL_VARIANTS.to_vec(),
+            main_columns: GlobalColumn::all_variants().collect(),
+            interaction_width: width(INTERACTION_TRACE_IDX),
+        }
+    }
+
+    /// Log sizes of the columns committed in each tree, derived from the AIR.
+    fn committed_log_sizes(
+        extensions: &[ExtensionComponent],
+        all_log_sizes: &[u32],
+    ) -> TreeVec<Vec<u32>> {
+        let machine_log_sizes =
+            Self::layout_with_extensions(extensions, all_log_sizes[0]).log_degree_bounds();
+        Self::extend_committed_log_sizes(extensions, all_log_sizes, machine_log_sizes)
+    }
+
+    /// Log sizes of the columns committed in each tree, given those of the machine component.
+    fn extend_committed_log_sizes(
+        extensions: &[ExtensionComponent],
+        all_log_sizes: &[u32],
+        machine_log_sizes: TreeVec<Vec<u32>>,
+    ) -> TreeVec<Vec<u32>> {
+        let extensions_iter = BASE_EXTENSIONS.iter().chain(extensions);
+
+        let mut sizes = vec![machine_log_sizes.clone()];
+        for (ext, log_size) in extensions_iter
+            .clone()
+            .zip(all_log_sizes.get(1..).unwrap_or_default())
+        {
+            sizes.push(ext.trace_sizes(*log_size));
+        }
+        let mut log_sizes = TreeVec::concat_cols(sizes.into_iter());
+        // use the fact that preprocessed columns are only allowed to have [0] mask
+        log_sizes[PREPROCESSED_TRACE_IDX] = machine_log_sizes[PREPROCESSED_TRACE_IDX].clone();
+        for (ext, log_size) in extensions_iter.zip(all_log_sizes.get(1..).unwrap_or_default()) {
+            // extending log_sizes[PREPROCESSED_TRACE_IDX] with the dimension of the preprocessed columns
+            log_sizes[PREPROCESSED_TRACE_IDX].extend(ext.preprocessed_trace_sizes(*log_size));
+        }
+        log_sizes
+    }
+
+    /// Checks that `all_log_sizes` has one log size per component, each of them supported by its component.
+    fn check_log_sizes(
+        extensions: &[ExtensionComponent],
+        all_log_sizes: &[u32],
+    ) -> Result<(), VerificationError> {
+        if all_log_sizes.len() != extensions.len() + BASE_EXTENSIONS.len() + 1 {
+            return Err(StwoVerificationError::InvalidStructure(
+                "log size len mismatch".to_string(),
+            )
+            .into());
+        }
+        if !(PreprocessedTraces::MIN_LOG_SIZE..=trace_size::MAX_LOG_SIZE)
+            .contains(&all_log_sizes[0])
+            || !BASE_EXTENSIONS
+                .iter()
+                .chain(extensions)
+                .zip(&all_log_sizes[1..])
+                .all(|(ext, &log_size)| ext.accepts_log_size(log_size))
+        {
+            return Err(StwoVerificationError::InvalidStructure(format!(
+                "unsupported log sizes {all_log_sizes:?}"
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Estimates the work of verifying a proof with the given log sizes, as in [`Proof::log_size`], for `config`.
+    ///
+    /// Column sizes are derived from the AIR in the same way as in [`Self::verify`], query and FRI layer counts
+    /// from `config`. See [`VerificationCost`] for what is counted. Returns an error if the verifier would reject
+    /// the log sizes, e.g. if there isn't one for every component.
+    pub fn verification_cost(
+        extensions: &[ExtensionComponent],
+        all_log_sizes: &[u32],
+        config: &PcsConfig,
+    ) -> Result<VerificationCost, VerificationError> {
+        Self::check_log_sizes(extensions, all_log_sizes)?;
+        let log_blowup = config.fri_config.log_blowup_factor;
+        let n_queries = config.fri_config.n_queries;
+        let max_log_size = all_log_sizes.iter().copied().fold(0, u32::max);
+        let composition_log_size = max_log_size + LOG_CONSTRAINT_DEGREE;
+
+        let mut trees = Self::committed_log_sizes(extensions, all_log_sizes).0;
+        trees.push(vec![composition_log_size; SECURE_EXTENSION_DEGREE]);
+        let n_columns: usize = trees.iter().map(Vec::len).sum();
+
+        // The first FRI layer folds quotients of every column size from the circle to the line, inner layers halve
+        // the line until its degree bound reaches the last layer.
+        let mut column_log_sizes: Vec<u32> = trees.iter().flatten().copied().collect();
+        column_log_sizes.sort_unstable();
+        column_log_sizes.dedup();
+        let first_layer: Vec<u32> = column_log_sizes
+            .iter()
+            .flat_map(|&log_size| [log_size; SECURE_EXTENSION_DEGREE])
+            .collect();
+        let inner_layers: Vec<Vec<u32>> = (config.fri_config.log_last_layer_degree_bound + 1
+            ..=composition_log_size - CIRCLE_TO_LINE_FOLD_STEP)
+            .rev()
+            .map(|log_size| vec![log_size; SECURE_EXTENSION_DEGREE])
+            .collect();
+        let fri_layers = 1 + inner_layers.len();
+
+        let path_compressions: usize = trees
+            .iter()
+            .chain([&first_layer])
+            .chain(&inner_layers)
+            .map(|log_sizes| merkle_path_compressions(log_sizes, log_blowup))
+            .sum();
+
+        Ok(VerificationCost {
+            blake2s_compressions: n_queries * path_compressions + 1,
+            qm31_multiplications: n_queries * (n_columns + fri_layers),
+            fri_layers,
+            fri_layer_queries: n_queries * fri_layers,
+            pow_checks: 1,
+        })
+    }
+
+    /// Checks that the execution satisfies the constraints of the machine, without proving it.
+    ///
+    /// Traces are built as for [`Self::prove`], then every constraint of `C` is evaluated on every row and the logup
+    /// sums of the machine and base extensions are checked to add up to zero. Constraints of base extensions are
+    /// evaluated on their own rows the same way. Unlike proving, lookup elements are drawn without committing to the
+    /// traces first, therefore this is a consistency check rather than a replacement for verification.
+    pub fn check_trace(trace: &impl Trace, view: &View) -> Result<(), TraceCheckError> {
+        Self::check_bundle(Self::build_traces(&[], trace, view)?)
+    }
+
+    /// Checks traces built by [`Self::build_traces`] without extensions, see [`Self::check_trace`].
+    pub(crate) fn check_bundle(bundle: TraceBundle) -> Result<(), TraceCheckError> {
+        let TraceBundle {
+            all_log_sizes,
+            main_evals,
+            program_evals,
+            traces,
+            ..
+        } = bundle;
+        let BundleTraces::Generated(GeneratedTraces {
+            finalized_program_trace,
+            extension_traces,
+            side_note,
+        }) = traces
+        else {
+            unreachable!("traces are built from scratch")
+        };
+        let log_size = all_log_sizes[0];
+        let extensions_config = ExtensionsConfig::default();
+
+        let mut lookup_elements = AllLookupElements::default();
+        C::draw_lookup_elements(
+            &mut lookup_elements,
+            &mut Blake2sChannel::default(),
+            &extensions_config,
+        );
+        let main_trace = FinalizedTraces::from_columns(
+            main_evals[..GlobalColumn::COLUMNS_NUM]
+                .iter()
+                .map(|eval| eval.values.clone())
+                .collect(),
+            log_size,
+        );
+        let (interaction_trace, claimed_sum) = generate_interaction_trace::<C>(
+            &main_trace,
+            &PreprocessedTraces::new(log_size),
+            &finalized_program_trace,
+            &lookup_elements,
+        );
+        let mut total_sum = claimed_sum;
+        for ((ext, extension_trace), &ext_log_size) in BASE_EXTENSIONS
+            .iter()
+            .zip(extension_traces)
+            .zip(&all_log_sizes[1..])
+        {
+            let rows = |evals: &[CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>]| {
+                committed_rows(evals, ext_log_size)
+            };
+            let preprocessed = rows(&ext.generate_preprocessed_trace(ext_log_size));
+            let original = rows(&extension_trace.to_circle_evaluation(ORIGINAL_TRACE_IDX));
+            let program = rows(&extension_trace.to_circle_evaluation(PROGRAM_TRACE_IDX));
+            let (interaction_trace, claimed_sum) =
+                ext.generate_interaction_trace(extension_trace, &side_note, &lookup_elements);
+            let trace = TreeVec::new(vec![
+                preprocessed,
+                original,
+                rows(&interaction_trace),
+                program,
+            ]);
+            ext.check_constraints(&trace, ext_log_size, &lookup_elements, claimed_sum)?;
+            total_sum += claimed_sum;
+        }
+
+        let rows = |evals: &[CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>]| {
+            committed_rows(evals, log_size)
+        };
+        let trace = TreeVec::new(vec![
+            rows(&PreprocessedTraces::new(log_size).into_circle_evaluation()),
+            rows(&main_evals[..GlobalColumn::COLUMNS_NUM]),
+            rows(&interaction_trace),
+            rows(&program_evals[..ProgramColumn::COLUMNS_NUM]),
+        ]);
+        trace_check::check_constraints::<C>(
+            &trace,
+            log_size,
+            &lookup_elements,
+            claimed_sum,
+            &extensions_config,
+        )?;
+
+        if total_sum != SecureField::zero() {
+            return Err(TraceCheckError::LogupSumNotZero);
+        }
+        Ok(())
+    }
+
+    /// Takes built traces through the remaining phases of [`compare_components`], proving if `context` is given.
+    fn measure(
+        bundle: TraceBundle,
+        build_traces: Duration,
+        context: Option<&ProverContext>,
+        view: &View,
+    ) -> Result<ComponentRun, NexusProvingError> {
+        let mut run = ComponentRun {
+            components_id: bundle.components_id,
+            log_sizes: bundle.all_log_sizes.clone(),
+            preprocessed_columns: Self::committed_log_sizes(
+                &bundle.extensions,
+                &bundle.all_log_sizes,
+            )[PREPROCESSED_TRACE_IDX]
+                .len(),
+            main_columns: bundle.main_evals.len(),
+            program_columns: bundle.program_evals.len(),
+            interaction_columns: 0,
+            timings: PhaseTimings {
+                build_traces,
+                ..Default::default()
+            },
+            proof_size: None,
+            verified: None,
+        };
+        let Some(context) = context else {
+            let (interaction_columns, interaction) = timed(|| Self::dry_run_interaction(bundle));
+            run.interaction_columns = interaction_columns;
+            run.timings.interaction = interaction;
+            return Ok(run);
+        };
+
+        let (committed, interaction) = timed(|| Self::commit_and_interact(bundle, context));
+        let committed = committed?;
+        run.interaction_columns = committed.commitment_scheme.trees[INTERACTION_TRACE_IDX]
+            .polynomials
+            .len();
+        run.timings.interaction = interaction;
+
+        let (proof, prove) = timed(|| Self::finish_proof(committed));
+        let proof = proof?;
+        run.timings.prove = Some(prove);
+        run.proof_size = Some(
+            postcard::to_stdvec(&proof)
+                .expect("proof is serializable")
+                .len(),
+        );
+
+        let (verified, verify) = timed(|| {
+            Self::verify(
+                proof,
+                view.get_program_memory(),
+                view.view_associated_data().as_deref().unwrap_or_default(),
+                &[
+                    view.get_ro_initial_memory(),
+                    view.get_rw_initial_memory(),
+                    view.get_public_input(),
+                ]
+                .concat(),
+                view.get_exit_code(),
+                view.get_public_output(),
+            )
+        });
+        run.timings.verify = Some(verify);
+        run.verified = Some(verified.is_ok());
+        Ok(run)
+    }
+
+    /// Generates the interaction trace of built traces without committing to them, returns its number of columns.
+    fn dry_run_interaction(bundle: TraceBundle) -> usize {
+        let TraceBundle {
+            extensions,
+            all_log_sizes,
+            main_evals,
+            traces,
+            ..
+        } = bundle;
+        let BundleTraces::Generated(GeneratedTraces {
+            finalized_program_trace,
+            extension_traces,
+            side_note,
+        }) = traces
+        else {
+            unreachable!("traces are built from scratch")
+        };
+        let log_size = all_log_sizes[0];
+        let extensions_config = ExtensionsConfig::from(&*extensions);
+
+        // Lookup elements only affect values of the interaction trace, not its shape.
+        let mut lookup_elements = AllLookupElements::default();
+        C::draw_lookup_elements(
+            &mut lookup_elements,
+            &mut Blake2sChannel::default(),
+            &extensions_config,
+        );
+        let main_trace = FinalizedTraces::from_columns(
+            main_evals[..GlobalColumn::COLUMNS_NUM]
+                .iter()
+                .map(|eval| eval.values.clone())
+                .collect(),
+            log_size,
+        );
+        let (interaction_trace, _) = generate_interaction_trace::<C>(
+            &main_trace,
+            &PreprocessedTraces::new(log_size),
+            &finalized_program_trace,
+            &lookup_elements,
+        );
+        let extension_columns: usize = BASE_EXTENSIONS
+            .iter()
+            .chain(&extensions)
+            .zip(extension_traces)
+            .map(|(ext, extension_trace)| {
+                ext.generate_interaction_trace(extension_trace, &side_note, &lookup_elements)
+                    .0
+                    .len()
+            })
+            .sum();
+        interaction_trace.len() + extension_columns
+    }
+
+    /// Returns the identifier of the AIR defined by `C` together with base and given extensions.
+    ///
+    /// The identifier is a hash of the ordered list of chip names, of extension names and parameters, of lookup
+    /// relations in the order chips register them and of the column layout of the main, preprocessed and program
+    /// traces. Only names given explicitly in the source are hashed, see [`MachineChip::NAME`], so that the
+    /// identifier doesn't depend on the compiler or on module paths.
+    ///
+    /// Lookup elements are drawn from the channel in registration order, a verifier registering relations in another
+    /// order draws different elements and is rejected by this identifier.
+    pub fn components_id(extensions: &[ExtensionComponent]) -> [u8; 32] {
+        // Names are length-prefixed so that no two lists hash the same.
+        fn update_name(hasher: &mut Keccak, name: &str) {
+            hasher.update(&(name.len() as u32).to_le_bytes());
+            hasher.update(name.as_bytes());
+        }
+        let mut hasher = Keccak::v256();
+        let mut chip_names = Vec::new();
+        C::collect_names(&mut chip_names);
+        hasher.update(&(chip_names.len() as u32).to_le_bytes());
+        for name in chip_names {
+            update_name(&mut hasher, name);
+        }
+        let all_extensions: Vec<_> = BASE_EXTENSIONS.iter().chain(extensions).collect();
+        hasher.update(&(all_extensions.len() as u32).to_le_bytes());
+        for ext in all_extensions {
+            update_name(&mut hasher, ext.name());
+            let parameters = ext.parameters();
+            hasher.update(&(parameters.len() as u32).to_le_bytes());
+            for param in parameters {
+                hasher.update(&param.to_le_bytes());
+            }
+        }
+        let mut lookup_elements = AllLookupElements::default();
+        C::draw_lookup_elements(
+            &mut lookup_elements,
+            &mut Blake2sChannel::default(),
+            &ExtensionsConfig::from(extensions),
+        );
+        for relation in lookup_elements.relation_ids() {
+            update_name(&mut hasher, relation);
+        }
+        for col in GlobalColumn::all_variants() {
+            update_name(&mut hasher, col.group_name());
+            update_name(&mut hasher, col.name());
+            hasher.update(&(col.size() as u32).to_le_bytes());
+        }
+        for col in PreprocessedColumn::ALL_VARIANTS {
+            update_name(&mut hasher, col.name());
+            hasher.update(&(col.size() as u32).to_le_bytes());
+        }
+        for col in ProgramColumn::ALL_VARIANTS {
+            update_name(&mut hasher, col.name());
+            hasher.update(&(col.size() as u32).to_le_bytes());
+        }
+
+        let mut id = [0u8; 32];
+        hasher.finalize(&mut id);
+        id
+    }
+
+    /// Returns the opcodes constrained by the chips of `C`, in the order the chips are listed.
+    ///
+    /// Traces executing any other builtin opcode are rejected with [`NexusProvingError::UnsupportedOpcode`]. Custom
+    /// instructions are constrained by extensions and aren't listed.
+    pub fn supported_opcodes() -> Vec<BuiltinOpcode> {
+        let mut opcodes = Vec::new();
+        C::collect_supported_opcodes(&mut opcodes);
+        opcodes
+    }
+
+    /// Checks that every instruction of the trace is constrained, builtin ones by a chip of `C` and custom ones by
+    /// an enabled extension, so that an unsupported instruction is reported before traces are generated rather than
+    /// as a panic while filling them or an unsatisfied constraint.
+    fn check_opcodes(
+        trace: &impl Trace,
+        config: &ExtensionsConfig,
+    ) -> Result<(), UnsupportedOpcode> {
+        let supported = Self::supported_opcodes();
+        let steps = trace.get_blocks_iter().flat_map(|block| &block.steps);
+        for (row, step) in steps.enumerate() {
+            let opcode = &step.instruction.opcode;
+            let is_supported = match opcode.builtin() {
+                // Padding steps aren't executed.
+                Some(builtin) => builtin == BuiltinOpcode::UNIMPL || supported.contains(&builtin),
+                None => opcode.raw == KECCAKF_OPCODE && config.is_keccak_enabled(),
+            };
+            if !is_supported {
+                return Err(UnsupportedOpcode {
+                    opcode: opcode.clone(),
+                    pc: step.pc,
+                    row,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn mix_context_id(channel: &mut TranscriptChannel, context_id: &[u8; 32]) {
+        channel.set_stage(transcript::CONTEXT_ID);
+        for chunk in context_id.chunks_exact(8) {
+            let chunk: [u8; 8] = chunk.try_into().expect("chunk size is 8");
+            channel.mix_u64(u64::from_le_bytes(chunk));
+        }
+    }
+
+    fn mix_salt(channel: &mut TranscriptChannel, salt: &[u8; 32]) {
+        channel.set_stage(transcript::SALT);
+        for chunk in salt.chunks_exact(8) {
+            let chunk: [u8; 8] = chunk.try_into().expect("chunk size is 8");
+            channel.mix_u64(u64::from_le_bytes(chunk));
+        }
+    }
+
+    /// Binds the claimed sums of the components to the interaction commitment, before the constraint randomness
+    /// is drawn.
+    fn mix_claimed_sums(channel: &mut TranscriptChannel, claimed_sums: &[SecureField]) {
+        channel.set_stage(transcript::CLAIMED_SUMS);
+        channel.mix_felts(claimed_sums);
+    }
+
+    fn mix_components_id(channel: &mut TranscriptChannel, components_id: &[u8; 32]) {
+        channel.set_stage(transcript::COMPONENTS_ID);
+        for chunk in components_id.chunks_exact(8) {
+            let chunk: [u8; 8] = chunk.try_into().expect("chunk size is 8");
+            channel.mix_u64(u64::from_le_bytes(chunk));
+        }
+    }
+
+    /// Recovers the main trace of the machine component by evaluating its committed polynomials, which come first
+    /// in the main tree, over the trace domain.
+    fn committed_main_trace(
+        polynomials: &[CirclePoly<SimdBackend>],
+        log_size: u32,
+        twiddles: &TwiddleTree<SimdBackend>,
+    ) -> FinalizedTraces {
+        let domain = CanonicCoset::new(log_size).circle_domain();
+        let cols = polynomials[..GlobalColumn::COLUMNS_NUM]
+            .par_iter()
+            .map(|poly| poly.evaluate_with_twiddles(domain, twiddles).values)
+            .collect();
+        FinalizedTraces::from_columns(cols, log_size)
+    }
+}
+
+type TraceEvals = Vec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>;
+
+/// Traces of an execution, produced by [`Machine::build_traces`].
+pub struct TraceBundle {
+    extensions: Vec<ExtensionComponent>,
+    components_id: [u8; 32],
+    associated_data: Vec<u8>,
+    all_log_sizes: Vec<u32>,
+    num_steps: u32,
+    committed_output_len: Option<u32>,
+    main_evals: TraceEvals,
+    program_evals: TraceEvals,
+    traces: BundleTraces,
+}
+
+impl TraceBundle {
+    /// Returns the largest log size among the machine and extension components.
+    pub fn max_log_size(&self) -> u32 {
+        self.all_log_sizes.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Returns the Keccak-256 hash of the main and program trees, column by column in commitment order, each column
+    /// as its log size followed by its values in the committed, bit-reversed, order. The preprocessed tree only
+    /// depends on the log sizes.
+    ///
+    /// Trace generation doesn't depend on the host, executions with the same digest produce identical proofs on
+    /// every platform.
+    pub fn trace_digest(&self) -> [u8; 32] {
+        digest_evals(self.main_evals.iter().chain(&self.program_evals))
+    }
+}
+
+/// Returns the number of rows filled at once by [`MachineChip::fill_main_trace_chunk`], a single row if two chips
+/// of `C` update the same side-note state.
+fn main_trace_chunk_rows<C: MachineChip>(config: &ExtensionsConfig) -> usize {
+    let mut states = Vec::new();
+    C::collect_side_note_state(&mut states, config);
+    let shared = states
+        .iter()
+        .enumerate()
+        .any(|(i, state)| states[..i].contains(state));
+    if shared {
+        1
+    } else {
+        CANCELLATION_CHECK_ROWS
+    }
+}
+
+/// Returns the values of `evals` in row order, committed columns are bit-reversed and in circle domain order.
+pub(crate) fn committed_rows(
+    evals: &[CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>],
+    log_size: u32,
+) -> Vec<Vec<BaseField>> {
+    evals
+        .iter()
+        .map(|eval| {
+            let values = eval.values.to_cpu();
+            (0..values.len())
+                .map(|row| {
+                    let index = coset_index_to_circle_domain_index(row, log_size);
+                    values[bit_reverse_index(index, log_size)]
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Hashes `evals` as in [`TraceBundle::trace_digest`].
+fn digest_evals<'a>(
+    evals: impl Iterator<Item = &'a CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    for eval in evals {
+        hasher.update(&eval.domain.log_size().to_le_bytes());
+        for value in eval.values.as_slice() {
+            hasher.update(&value.0.to_le_bytes());
+        }
+    }
+    let mut digest = [0u8; 32];
+    hasher.finalize(&mut digest);
+    digest
+}
+
+#[cfg(test)]
+impl TraceBundle {
+    /// Applies `tamper` to the main trace of the main component, in row order, as if the prover had filled it that
+    /// way. The interaction trace is generated from the tampered trace.
+    pub(crate) fn tamper_main_trace(&mut self, tamper: impl FnOnce(&mut TracesBuilder)) {
+        let log_size = self.all_log_sizes[0];
+        let mut traces = TracesBuilder::new(log_size);
+        for (col, eval) in traces.cols.iter_mut().zip(&self.main_evals) {
+            let values = eval.values.to_cpu();
+            for (row, value) in col.iter_mut().enumerate() {
+                *value = values[bit_reverse_index(
+                    coset_index_to_circle_domain_index(row, log_size),
+                    log_size,
+                )];
+            }
+        }
+        tamper(&mut traces);
+        let tampered = traces.finalize().into_circle_evaluation();
+        self.main_evals
+            .splice(..GlobalColumn::COLUMNS_NUM, tampered);
+    }
+}
+
+/// Traces needed for the interaction trace, or the interaction trace itself if it was restored from a checkpoint.
+enum BundleTraces {
+    Generated(GeneratedTraces),
+    Restored {
+        interaction_evals: TraceEvals,
+        claimed_sum: Vec<SecureField>,
+    },
+}
+
+/// Commitment scheme parameters and precomputed twiddles, shared between proofs of traces up to a given size.
+///
+/// The context also caches committed preprocessed trees. The preprocessed tree only depends on the extensions and
+/// the log sizes of the components, the columns depending on the program are committed in the program tree, see
+/// [`PROGRAM_TRACE_IDX`]. Proofs of different programs with the same log sizes graft the cached tree into their
+/// commitment scheme instead of committing to it again. Proofs of a single program can also share the program
+/// columns of the program trace, see [`Self::with_program_template`].
+pub struct ProverContext {
+    config: PcsConfig,
+    salt: Option<[u8; 32]>,
+    context_id: Option<[u8; 32]>,
+    max_log_size: u32,
+    twiddles: TwiddleTree<SimdBackend>,
+    program_template: Option<ProgramTraceTemplate>,
+    preprocessed_trees: Mutex<HashMap<PreprocessedTreeKey, Arc<CommittedTree>>>,
+}
+
+/// Extensions and log sizes of all components, the inputs of the preprocessed tree.
+type PreprocessedTreeKey = (Vec<ExtensionComponent>, Vec<u32>);
+
+impl ProverContext {
+    /// Precomputes twiddles for traces with log size up to `max_log_size`.
+    pub fn new(max_log_size: u32) -> Self {
+        Self::with_config(max_log_size, &ProveConfig::default())
+    }
+
+    /// Like [`Self::new`], proofs use the given options.
+    pub fn with_config(max_log_size: u32, prove_config: &ProveConfig) -> Self {
+        let config = PcsConfig {
+            pow_bits: prove_config.pow_bits,
+            ..PcsConfig::default()
+        };
+        let twiddles = SimdBackend::precompute_twiddles(
+            CanonicCoset::new(
+                max_log_size + LOG_CONSTRAINT_DEGREE + config.fri_config.log_blowup_factor,
+            )
+            .circle_domain()
+            .half_coset,
+        );
+        Self {
+            config,
+            salt: prove_config.salt,
+            context_id: prove_config.context_id,
+            max_log_size,
+            twiddles,
+            program_template: None,
+            preprocessed_trees: Mutex::default(),
+        }
+    }
+
+    /// Reuses `template` for the program trace of executions, see [`Machine::prove_with_context`]. Proving an
+    /// execution of another program, or with a log size other than the one of the template, fails with
+    /// [`NexusProvingError::ProgramTemplate`].
+    pub fn with_program_template(mut self, template: ProgramTraceTemplate) -> Self {
+        self.program_template = Some(template);
+        self
+    }
+
+    /// Returns the largest log size of traces this context can be used for.
+    pub fn max_log_size(&self) -> u32 {
+        self.max_log_size
+    }
+
+    /// Returns the committed preprocessed tree of components with `extensions` and `all_log_sizes`, committing to it
+    /// on the first request.
+    fn preprocessed_tree(
+        &self,
+        extensions: &[ExtensionComponent],
+        all_log_sizes: &[u32],
+    ) -> Arc<CommittedTree> {
+        let key = (extensions.to_vec(), all_log_sizes.to_vec());
+        let mut trees = self
+            .preprocessed_trees
+            .lock()
+            .expect("no panic while committing to a preprocessed tree");
+        trees
+            .entry(key)
+            .or_insert_with(|| {
+                Arc::new(CommittedTree::commit(
+                    self.config,
+                    &self.twiddles,
+                    preprocessed_evals(extensions, all_log_sizes),
+                ))
+            })
+            .clone()
+    }
+}
+
+/// Returns the preprocessed columns of the machine component followed by those of the base extensions and
+/// `extensions`, in commitment order.
+fn preprocessed_evals(extensions: &[ExtensionComponent], all_log_sizes: &[u32]) -> TraceEvals {
+    PreprocessedTraces::new(all_log_sizes[0])
+        .into_circle_evaluation()
+        .into_iter()
+        .chain(
+            BASE_EXTENSIONS
+                .iter()
+                .chain(extensions)
+                .zip(&all_log_sizes[1..])
+                .flat_map(|(ext, &log_size)| ext.generate_preprocessed_trace(log_size)),
+        )
+        .collect()
+}
+
+/// Returns the root of the preprocessed tree of a machine without extensions, whose main trace has `log_size`.
+///
+/// The preprocessed columns of the base extensions have fixed sizes, the tree only depends on the main log size.
+pub(crate) fn base_preprocessed_root(log_size: u32) -> Blake2sHash {
+    // The base extensions ignore their log size when generating their preprocessed columns.
+    let evals = preprocessed_evals(&[], &vec![log_size; BASE_EXTENSIONS.len() + 1]);
+    let max_log_size = evals
+        .iter()
+        .map(|eval| eval.domain.log_size())
+        .max()
+        .expect("preprocessed trace is empty");
+    let config = PcsConfig::default();
+    let twiddles = SimdBackend::precompute_twiddles(
+        CanonicCoset::new(
+            max_log_size + LOG_CONSTRAINT_DEGREE + config.fri_config.log_blowup_factor,
+        )
+        .circle_domain()
+        .half_coset,
+    );
+    CommittedTree::commit(config, &twiddles, evals).root()
+}
+
+/// Committed traces together with the channel state, produced by [`Machine::commit_and_interact`].
+pub struct CommittedBundle<'a> {
+    extensions: Vec<ExtensionComponent>,
+    components_id: [u8; 32],
+    all_log_sizes: Vec<u32>,
+    num_steps: u32,
+    committed_output_len: Option<u32>,
+    all_claimed_sum: Vec<SecureField>,
+    lookup_elements: AllLookupElements,
+    prover_channel: TranscriptChannel,
+    commitment_scheme: CommitmentSchemeProver<'a, SimdBackend, TranscriptMerkleChannel>,
+    config: PcsConfig,
+    salt: Option<[u8; 32]>,
+}
+
+/// Where [`Machine::prove_from`] starts proving from.
+enum ProverStart<'a, T> {
+    Trace(&'a T),
+    Checkpoint(ProverCheckpoint),
+}
+
+/// What [`Machine::prove_from`] does once it reaches the checkpoint boundary.
+///
+/// Checkpoints record the program and public input of the view, see [`crate::checkpoint`].
+enum CheckpointMode<'a> {
+    None,
+    Save(&'a Path, &'a View),
+    Stop(&'a View),
+}
+
+enum ProverOutcome {
+    Proof(Box<Proof>),
+    Checkpoint(ProverCheckpoint),
+}
+
+enum Committed<'a> {
+    Bundle(Box<CommittedBundle<'a>>),
+    Checkpoint(ProverCheckpoint),
+}
+
+/// How far [`compare_components`] takes each component set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComparisonMode {
+    /// Build the main and interaction traces without committing to them.
+    DryRun,
+    /// Prove and verify.
+    Prove,
+}
+
+/// Time spent in each phase of a run of [`compare_components`].
+#[derive(Debug, Clone, Default)]
+pub struct PhaseTimings {
+    pub build_traces: Duration,
+    /// Generating the interaction trace, together with committing to all trees unless dry-running.
+    pub interaction: Duration,
+    /// `None` when dry-running.
+    pub prove: Option<Duration>,
+    /// `None` when dry-running.
+    pub verify: Option<Duration>,
+}
+
+/// Measurements of a single component set, see [`compare_components`].
+#[derive(Debug, Clone)]
+pub struct ComponentRun {
+    pub components_id: [u8; 32],
+    /// Log size of each component, the machine component first followed by the extensions.
+    pub log_sizes: Vec<u32>,
+    /// Number of columns of the preprocessed tree, including extension columns.
+    pub preprocessed_columns: usize,
+    /// Number of columns of the main tree, including extension columns.
+    pub main_columns: usize,
+    /// Number of columns of the program tree, including extension columns.
+    pub program_columns: usize,
+    /// Number of columns of the interaction tree, including extension columns.
+    pub interaction_columns: usize,
+    pub timings: PhaseTimings,
+    /// Size of the serialized proof in bytes, `None` when dry-running.
+    pub proof_size: Option<usize>,
+    /// Whether the proof is accepted by the verifier of the same component set, `None` when dry-running.
+    pub verified: Option<bool>,
+}
+
+/// Measurements of two component sets on the same execution, see [`compare_components`].
+#[derive(Debug, Clone)]
+pub struct ComponentComparison {
+    pub a: ComponentRun,
+    pub b: ComponentRun,
+}
+
+/// Proves the same execution under component sets `A` and `B`, or only builds their traces in
+/// [`ComparisonMode::DryRun`], and reports measurements of both side by side.
+///
+/// This is meant for developing replacement chips. Both runs use the same trace and view, and proofs share a single
+/// [`ProverContext`] sized for the larger of the two, so that twiddles are computed once. The runs are sequential,
+/// timings of one set don't overlap with the other.
+pub fn compare_components<A, B>(
+    trace: &impl Trace,
+    view: &View,
+    mode: ComparisonMode,
+) -> Result<ComponentComparison, NexusProvingError>
+where
+    A: MachineChip + Sync,
+    B: MachineChip + Sync,
+{
+    let (bundle_a, build_a) = timed(|| Machine::<A>::build_traces(&[], trace, view));
+    let bundle_a = bundle_a?;
+    let (bundle_b, build_b) = timed(|| Machine::<B>::build_traces(&[], trace, view));
+    let bundle_b = bundle_b?;
+
+    let context = (mode == ComparisonMode::Prove)
+        .then(|| ProverContext::new(bundle_a.max_log_size().max(bundle_b.max_log_size())));
+    Ok(ComponentComparison {
+        a: Machine::<A>::measure(bundle_a, build_a, context.as_ref(), view)?,
+        b: Machine::<B>::measure(bundle_b, build_b, context.as_ref(), view)?,
+    })
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let value = f();
+    (value, start.elapsed())
+}
+
+/// Traces generated from scratch that are still needed for the interaction trace.
+struct GeneratedTraces {
+    finalized_program_trace: ProgramTraces,
+    extension_traces: Vec<ComponentTrace>,
+    side_note: SideNote,
+}
+
+/// Cheap sanity checks that `trace` and `view` come from the same execution, so that mismatched inputs are
+/// rejected before any proving work is done.
+fn check_inputs(trace: &impl Trace, view: &View) -> Result<(), NexusProvingError> {
+    if let Some((block, num_steps)) = trace
+        .get_blocks_iter()
+        .map(|block| block.steps.len())
+        .enumerate()
+        .find(|&(_, num_steps)| num_steps != 1)
+    {
+        return Err(NexusProvingError::UnsupportedBlockSize { block, num_steps });
+    }
+    let output_addresses: BTreeSet<u32> = view
+        .get_public_output()
+        .iter()
+        .map(|entry| entry.address)
+        .collect();
+    if let Some(entry) = view
+        .get_exit_code()
+        .iter()
+        .find(|entry| output_addresses.contains(&entry.address))
+    {
+        return Err(NexusProvingError::InconsistentInputs(format!(
+            "exit code overlaps with public output at address 0x{:08x}",
+            entry.address
+        )));
+    }
+    check_public_output(
+        view.view_memory_layout(),
+        view.get_exit_code(),
+        view.get_public_output(),
+    )?;
+
+    let mut blocks = trace
+        .get_blocks_iter()
+        .filter(|block| !block.steps.is_empty());
+    let (Some(first), last) = (blocks.next(), blocks.last()) else {
+        return Ok(());
+    };
+    let last = last.unwrap_or(first);
+
+    let initial_pc = view.get_program_memory().initial_pc;
+    if trace.get_start() == 0 && first.steps[0].pc != initial_pc {
+        return Err(NexusProvingError::InconsistentInputs(format!(
+            "trace starts at pc 0x{:08x}, but the program entry point is 0x{initial_pc:08x}",
+            first.steps[0].pc
+        )));
+    }
+
+    // Halting does not advance the clock, see `View::view_cycle_count`.
+    let last_step = last.steps.last().expect("block is not empty");
+    let halt = matches!(
+        last_step.instruction.opcode.builtin(),
+        Some(BuiltinOpcode::ECALL)
+    ) && last.regs.read(SYSCALL_NUMBER_REG) == u32::from(SyscallCode::Exit);
+    let exit_code = halt.then(|| last.regs.read(SYSCALL_ARG0_REG));
+
+    // Steps are timestamped with the global clock of the emulator, which advances by the cost of every instruction.
+    // The clock of the circuit is the `Clk` column, which advances by the cycles of every row, see `TimestampChip`.
+    // Padding steps aren't executed, the clock skips a tick before them.
+    let mut num_cycles = 0;
+    let mut next_timestamp = None;
+    for block in trace.get_blocks_iter() {
+        let Some(step) = block.steps.first() else {
+            continue;
+        };
+        let is_padding = step.instruction.opcode.builtin() == Some(BuiltinOpcode::UNIMPL);
+        if let Some(expected) = next_timestamp.filter(|&ts| !is_padding && ts != step.timestamp) {
+            return Err(NexusProvingError::InconsistentInputs(format!(
+                "step at pc 0x{:08x} has timestamp {}, expected {expected}",
+                step.pc, step.timestamp
+            )));
+        }
+        let cycles = OpcodeCost::instruction_cycles(
+            &step.instruction.opcode,
+            block.regs.read(SYSCALL_NUMBER_REG),
+        );
+        next_timestamp = Some(step.timestamp + cycles);
+        // A fused step shares its clock tick with the ADDI it absorbed, which still counts as executed.
+        let fused_cycles = step
+            .fused
+            .as_ref()
+            .map_or(0, |fused| OpcodeCost::cycles(&fused.instruction.opcode));
+        num_cycles += (cycles + fused_cycles) as usize;
+    }
+    if let Some(cycle_count) = view.view_cycle_count() {
+        let num_cycles = num_cycles - usize::from(halt);
+        if trace.get_start() == 0 && num_cycles != cycle_count {
+            return Err(NexusProvingError::InconsistentInputs(format!(
+                "trace spans {num_cycles} cycles, but the view records {cycle_count}"
+            )));
+        }
+    }
+
+    // The exit code is written to the output by the guest before the exit syscall, programs that don't use
+    // the runtime may exit without recording it.
+    match (exit_code, view.exit_code_u32()) {
+        (Some(code), Some(view_code)) if code != view_code => {
+            Err(NexusProvingError::InconsistentInputs(format!(
+                "trace ends in an exit syscall with code {code}, but the view records exit code {view_code}"
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that the exit code and the public output could have been written by the guest.
+///
+/// The exit code must be empty, if the guest didn't exit, or one word at consecutive addresses from the start of the
+/// output memory, and output entries must have distinct addresses past the exit code. With a memory layout, the
+/// exit code must be at [`LinearMemoryLayout::exit_code`] and the output within the public output region of the
+/// layout. Without one, as for views of Harvard emulators whose output addresses are relative, only the exit code
+/// must start at a word-aligned address.
+pub fn check_public_output(
+    layout: Option<&LinearMemoryLayout>,
+    exit_code: &[PublicOutputEntry],
+    output_memory: &[PublicOutputEntry],
+) -> Result<(), PublicOutputError> {
+    let exit_code_addresses = exit_code_addresses(layout, exit_code)?;
+    let output_region =
+        layout.map(|layout| layout.public_output_start()..layout.public_output_end());
+    let mut addresses = BTreeSet::new();
+    for &PublicOutputEntry { address, .. } in output_memory {
+        if exit_code_addresses.contains(&address)
+            || output_region
+                .as_ref()
+                .is_some_and(|region| !region.contains(&address))
+        {
+            return Err(PublicOutputError::OutsideOutputRegion { address });
+        }
+        if !addresses.insert(address) {
+            return Err(PublicOutputError::DuplicateAddress { address });
+        }
+    }
+    Ok(())
+}
+
+/// Returns the start of the public output, which follows the exit code, or `None` if the guest didn't exit.
+fn public_output_start(exit_code: &[PublicOutputEntry]) -> Option<u32> {
+    exit_code
+        .first()
+        .map(|entry| entry.address + WORD_SIZE as u32)
+}
+
+/// Returns the addresses of the exit code, see [`check_public_output`].
+fn exit_code_addresses(
+    layout: Option<&LinearMemoryLayout>,
+    exit_code: &[PublicOutputEntry],
+) -> Result<Range<u32>, PublicOutputError> {
+    let Some(first) = exit_code.first() else {
+        return Ok(0..0);
+    };
+    let expected = layout.map_or(first.address, |layout| layout.exit_code());
+    let addresses = expected..expected.saturating_add(WORD_SIZE as u32);
+    if expected % WORD_SIZE as u32 != 0
+        || addresses.len() != exit_code.len()
+        || !addresses
+            .clone()
+            .zip(exit_code)
+            .all(|(address, entry)| entry.address == address)
+    {
+        return Err(PublicOutputError::MalformedExitCode { expected });
+    }
+    Ok(addresses)
+}
+
+/// Checks that traces of `log_size` can be committed to, larger traces would exhaust memory or overflow the
+/// domains of stwo.
+fn check_log_size(log_size: u32) -> Result<(), NexusProvingError> {
+    if log_size > trace_size::MAX_LOG_SIZE {
+        return Err(NexusProvingError::TraceTooLarge {
+            log_size,
+            max_log_size: trace_size::MAX_LOG_SIZE,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chips::{AddChip, BeqChip, SubChip},
+        error::{ProgramTemplateMismatch, RegisterAccessError},
+        test_utils::{prove_worst_case, synthesize_worst_case_trace},
+        transcript::TranscriptOp,
+    };
+    use nexus_common::constants::{ELF_TEXT_START, MEMORY_TOP};
+    use nexus_vm::{
+        elf::ElfFile,
+        emulator::{
+            Emulator, HarvardEmulator, LoadedProgram, MemoryInitializationEntry,
+            MemoryInitializationError, StackGuard, TerminationCause,
+        },
+        memory::{MemoryRecord, MemoryRecords, MemorySegmentImage, ZeroRegion},
+        riscv::{
+            builder::ProgramBuilder, BasicBlock, BuiltinOpcode, Instruction, Opcode, Register,
+        },
+        system::{HintRegistry, SyscallCode},
+        trace::{
+            fuse_address_materialization, k_trace, k_trace_direct, k_trace_direct_with_hints,
+            k_trace_harvard, k_trace_with_cycle_limit, k_trace_with_options, TraceOptions,
+            UniformTrace,
+        },
+        WORD_SIZE,
+    };
+    use num_traits::One;
+    use std::collections::{BTreeMap, BTreeSet};
+    use stwo::core::fri::FriConfig;
+
+    #[test]
+    fn prove_verify_single_padding_row() {
+        // Executed steps end one row before a power of two, the only padding row is the last one and wraps around
+        // to the first row.
+        let log_size = PreprocessedTraces::MIN_LOG_SIZE;
+        let num_rows = 1 << log_size;
+        let basic_block = vec![BasicBlock::new(
+            (1..num_rows)
+                .map(|_| Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 1, 1))
+                .collect(),
+        )];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        assert_eq!(program_trace.get_num_steps(), num_rows - 1);
+
+        let mut traces = TracesBuilder::new(log_size);
+        let program_traces =
+            ProgramTracesBuilder::new_with_empty_memory(log_size, view.get_program_memory());
+        let mut side_note = SideNote::new(&program_traces, &view);
+        for (row_idx, program_step) in
+            iter_program_steps(&program_trace, traces.num_rows()).enumerate()
+        {
+            crate::chips::CpuChip::fill_main_trace(
+                &mut traces,
+                row_idx,
+                &program_step,
+                &mut side_note,
+                &ExtensionsConfig::default(),
+            );
+        }
+        assert_eq!(traces.num_real_rows(), num_rows - 1);
+        assert_eq!(traces.finalize().num_padded_rows(), 1);
+
+        let bundle = Machine::<BaseComponent>::build_traces(&[], &program_trace, &view).unwrap();
+        assert_eq!(bundle.all_log_sizes[0], log_size);
+        let main_trace = FinalizedTraces::from_columns(
+            bundle.main_evals[..GlobalColumn::COLUMNS_NUM]
+                .iter()
+                .map(|eval| eval.values.clone())
+                .collect(),
+            log_size,
+        );
+        assert_eq!(main_trace.num_padded_rows(), 1);
+
+        Machine::<BaseComponent>::check_trace(&program_trace, &view).unwrap();
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        crate::verify(proof, &view).unwrap();
+    }
+
+    #[test]
+    fn prove_verify() {
+        let basic_blocks = ProgramBuilder::new()
+            .addi(1, 0, 1)
+            .add(2, 1, 0)
+            .add(3, 2, 1)
+            .add(4, 3, 2)
+            .add(5, 4, 3)
+            .add(6, 5, 4)
+            .build()
+            .unwrap();
+        let (view, program_trace) =
+            k_trace_direct(&basic_blocks, 1).expect("error generating trace");
+
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        Machine::<BaseComponent>::verify(
+            proof,
+            view.get_program_memory(),
+            &[],
+            &[
+                view.get_public_input(),
+                view.get_ro_initial_memory(),
+                view.get_rw_initial_memory(),
+            ]
+            .concat(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn prove_verify_branch_edge_cases() {
+        const VALUES: [i32; 5] = [0, 1, -1, i32::MIN, i32::MAX];
+        const BRANCHES: [BuiltinOpcode; 6] = [
+            BuiltinOpcode::BEQ,
+            BuiltinOpcode::BNE,
+            BuiltinOpcode::BLT,
+            BuiltinOpcode::BGE,
+            BuiltinOpcode::BLTU,
+            BuiltinOpcode::BGEU,
+        ];
+
+        // Sets `reg` to `value`, assuming x31 = 1.
+        fn load(reg: u8, value: i32) -> Vec<Instruction> {
+            let op = |opcode, op_a, op_b, op_c| {
+                Instruction::new_ir(Opcode::from(opcode), op_a, op_b, op_c)
+            };
+            match value {
+                0 => vec![op(BuiltinOpcode::ADD, reg, 0, 0)],
+                1 => vec![op(BuiltinOpcode::ADD, reg, 31, 0)],
+                -1 => vec![op(BuiltinOpcode::SUB, reg, 0, 31)],
+                i32::MIN => vec![op(BuiltinOpcode::LUI, reg, 0, 0x80000)],
+                i32::MAX => vec![
+                    op(BuiltinOpcode::LUI, reg, 0, 0x80000),
+                    op(BuiltinOpcode::SUB, reg, reg, 31),
+                ],
+                _ => unreachable!(),
+            }
+        }
+
+        let mut instructions = vec![Instruction::new_ir(
+            Opcode::from(BuiltinOpcode::ADDI),
+            31,
+            0,
+            1,
+        )];
+        // Every branch either skips the following NOP or falls through to it.
+        for (a, b) in VALUES
+            .into_iter()
+            .flat_map(|a| VALUES.into_iter().map(move |b| (a, b)))
+        {
+            instructions.extend(load(1, a));
+            instructions.extend(load(2, b));
+            for branch in BRANCHES {
+                instructions.push(Instruction::new_ir(Opcode::from(branch), 1, 2, 8));
+                instructions.push(Instruction::nop());
+            }
+        }
+        let basic_block = vec![BasicBlock::new(instructions)];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        Machine::<BaseComponent>::verify(
+            proof,
+            view.get_program_memory(),
+            &[],
+            &[
+                view.get_public_input(),
+                view.get_ro_initial_memory(),
+                view.get_rw_initial_memory(),
+            ]
+            .concat(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn prove_verify_x0_destination() {
+        const TESTED_IDX: usize = 6;
+        let jump_target = ELF_TEXT_START + (TESTED_IDX as u32 + 2) * WORD_SIZE as u32;
+        let cases = [
+            (
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 0, 3, 3),
+                Some(256),
+            ),
+            (
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 0, 2, 0),
+                Some(128),
+            ),
+            (
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::JAL), 0, 0, 8),
+                Some(jump_target - WORD_SIZE as u32),
+            ),
+            (
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::JALR), 0, 4, 0),
+                Some(jump_target - WORD_SIZE as u32),
+            ),
+            (
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::LUI), 0, 0, 1),
+                Some(1 << 12),
+            ),
+        ];
+
+        for (instruction, expected_result) in cases {
+            let opcode = instruction.opcode.clone();
+            let basic_block = vec![BasicBlock::new(vec![
+                // x2 = 0x80000 + x2 is a usable heap address, store 128 there.
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 1, 1, 19),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 2),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 3, 0, 128),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 2, 3, 0),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 4, 0, jump_target),
+                instruction,
+                // Skipped by jumps.
+                Instruction::nop(),
+                // x5 = x0 + x3, x0 must still read as zero.
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 5, 0, 3),
+            ])];
+            let (view, program_trace) =
+                k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+            let steps: Vec<_> = program_trace
+                .get_blocks_iter()
+                .flat_map(|block| block.steps.iter())
+                .collect();
+            let step = steps[TESTED_IDX];
+            assert_eq!(step.instruction.opcode, opcode);
+            // The result is the value that would have been written to rd, it is kept for the prover.
+            assert_eq!(step.result, expected_result, "{opcode}");
+
+            let is_load = opcode.builtin() == Some(BuiltinOpcode::LW);
+            let is_jump = matches!(
+                opcode.builtin(),
+                Some(BuiltinOpcode::JAL | BuiltinOpcode::JALR)
+            );
+            // Loads still perform the memory access.
+            assert_eq!(step.memory_records.len(), is_load as usize, "{opcode}");
+            if is_jump {
+                assert_eq!(step.next_pc, jump_target, "{opcode}");
+            } else {
+                assert_eq!(step.next_pc, step.pc + WORD_SIZE as u32, "{opcode}");
+            }
+            let last_step = steps.last().unwrap();
+            assert_eq!(last_step.result, Some(128), "{opcode}");
+
+            let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+            Machine::<BaseComponent>::verify(
+                proof,
+                view.get_program_memory(),
+                &[],
+                &[
+                    view.get_public_input(),
+                    view.get_ro_initial_memory(),
+                    view.get_rw_initial_memory(),
+                ]
+                .concat(),
+                view.get_exit_code(),
+                view.get_public_output(),
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn prove_verify_fused_address_materialization() {
+        let basic_block = vec![BasicBlock::new(vec![
+            // x1 = 0x80000 - 4 is a usable heap address, store x1 there.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LUI), 1, 0, 0x80),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 1, 0xFFC),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 1, 1, 0),
+            // x2 = pc + 0x800
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::AUIPC), 2, 0, 0x1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 2, 0x800),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 3, 1, 0),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let fused_trace = fuse_address_materialization(&program_trace);
+        assert_eq!(
+            fused_trace.get_num_steps() + 2,
+            program_trace.get_num_steps()
+        );
+
+        let proof = Machine::<BaseComponent>::prove(&fused_trace, &view).unwrap();
+        Machine::<BaseComponent>::verify(
+            proof,
+            view.get_program_memory(),
+            &[],
+            &[
+                view.get_public_input(),
+                view.get_ro_initial_memory(),
+                view.get_rw_initial_memory(),
+            ]
+            .concat(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap();
+    }
+
+    fn trace_with_ro_memory(ro_memory: Vec<MemoryInitializationEntry>) -> (View, UniformTrace) {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 0),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let view = View::new(
+            &None,
+            &vec![],
+            view.get_program_memory(),
+            &ro_memory,
+            &view.get_rw_initial_memory().to_vec(),
+            &view.get_public_input().to_vec(),
+            view.view_tracked_ram_size(),
+            &view.get_exit_code().to_vec(),
+            &view.get_public_output().to_vec(),
+            &vec![],
+        );
+        (view, program_trace)
+    }
+
+    #[test]
+    fn prove_rejects_duplicate_init_memory() {
+        let ro_memory = vec![
+            MemoryInitializationEntry::new(0x1000, 1),
+            MemoryInitializationEntry::new(0x1001, 2),
+            MemoryInitializationEntry::new(0x1000, 3),
+        ];
+        let (view, program_trace) = trace_with_ro_memory(ro_memory);
+
+        let result = Machine::<BaseComponent>::prove(&program_trace, &view);
+        assert!(matches!(
+            result,
+            Err(NexusProvingError::InvalidInitialMemory(
+                MemoryInitializationError::DuplicateAddress { address: 0x1000 }
+            ))
+        ));
+    }
+
+    #[test]
+    fn prove_rejects_multi_step_blocks() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 3, 2, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 4, 3, 2),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 2).expect("error generating trace");
+
+        let result = Machine::<BaseComponent>::prove(&program_trace, &view);
+        assert!(matches!(
+            result,
+            Err(NexusProvingError::UnsupportedBlockSize {
+                block: 0,
+                num_steps: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn prove_rejects_exit_code_overlapping_output() {
+        let basic_block = vec![BasicBlock::new(vec![Instruction::new_ir(
+            Opcode::from(BuiltinOpcode::ADDI),
+            1,
+            0,
+            1,
+        )])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let view = View::new(
+            &None,
+            &vec![],
+            view.get_program_memory(),
+            &vec![],
+            &vec![],
+            &vec![],
+            view.view_tracked_ram_size(),
+            &vec![PublicOutputEntry::new(0x80, 0)],
+            &vec![PublicOutputEntry::new(0x80, 1)],
+            &vec![],
+        );
+
+        let result = Machine::<BaseComponent>::prove(&program_trace, &view);
+        assert!(matches!(
+            result,
+            Err(NexusProvingError::InconsistentInputs(msg)) if msg.contains("overlaps")
+        ));
+    }
+
+    #[test]
+    fn check_log_size_rejects_large_traces() {
+        assert!(check_log_size(trace_size::MAX_LOG_SIZE).is_ok());
+        assert!(matches!(
+            check_log_size(trace_size::MAX_LOG_SIZE + 1),
+            Err(NexusProvingError::TraceTooLarge {
+                log_size,
+                max_log_size: trace_size::MAX_LOG_SIZE,
+            }) if log_size == trace_size::MAX_LOG_SIZE + 1
+        ));
+    }
+
+    #[test]
+    fn side_note_detects_multiplicity_overflow() {
+        let (view, _) = trace_with_ro_memory(vec![]);
+        let program_traces = ProgramTracesBuilder::dummy(PreprocessedTraces::MIN_LOG_SIZE);
+        let mut side_note = SideNote::new(&program_traces, &view);
+        assert_eq!(side_note.overflowing_multiplicity(), None);
+
+        side_note.range16.multiplicity[3] = stwo::core::fields::m31::P;
+        assert_eq!(side_note.overflowing_multiplicity(), Some("range16"));
+        side_note.range16.multiplicity[3] = 0;
+        side_note.bit_op.multiplicity_xor.insert(0x12, u32::MAX);
+        assert_eq!(side_note.overflowing_multiplicity(), Some("xor"));
+    }
+
+    #[test]
+    fn verify_rejects_duplicate_init_memory() {
+        let ro_memory = vec![
+            MemoryInitializationEntry::new(0x1000, 1),
+            MemoryInitializationEntry::new(0x1001, 2),
+        ];
+        let (view, program_trace) = trace_with_ro_memory(ro_memory.clone());
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+
+        let duplicated = [ro_memory.as_slice(), &ro_memory[..1]].concat();
+        let err = Machine::<BaseComponent>::verify(
+            proof,
+            view.get_program_memory(),
+            &[],
+            &duplicated,
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            &err,
+            VerificationError::Stwo(StwoVerificationError::InvalidStructure(msg)) if msg.contains("0x00001000")
+        ));
+    }
+
+    #[test]
+    fn verify_rejects_unsupported_log_sizes() {
+        let basic_blocks = ProgramBuilder::new().addi(1, 0, 1).build().unwrap();
+        let (view, program_trace) =
+            k_trace_direct(&basic_blocks, 1).expect("error generating trace");
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+
+        // Main trace below the minimum, above the maximum or larger than the execution, and a fixed-size extension
+        // resized.
+        for (idx, log_size) in [
+            (0, PreprocessedTraces::MIN_LOG_SIZE - 1),
+            (0, trace_size::MAX_LOG_SIZE + 1),
+            (0, u32::MAX),
+            (0, proof.log_size[0] + 1),
+            (1, proof.log_size[1] + 1),
+        ] {
+            let mut proof = proof.clone();
+            proof.log_size[idx] = log_size;
+            let err = crate::verify(proof, &view).unwrap_err();
+            assert!(matches!(
+                &err,
+                VerificationError::Stwo(StwoVerificationError::InvalidStructure(msg)) if msg.contains("unsupported log sizes")
+            ));
+        }
+    }
+
+    #[test]
+    fn claimed_sums_are_split_per_component() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 0b1100),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 0b1010),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::AND), 3, 1, 2),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::OR), 4, 1, 2),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::XOR), 5, 1, 2),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+
+        // The bit operations are looked up by the main component and answered by the bit-op multiplicity
+        // extension, neither sum is zero on its own.
+        let bit_op_idx = 1 + BASE_EXTENSIONS
+            .iter()
+            .position(|ext| *ext == ExtensionComponent::bit_op_multiplicity())
+            .unwrap();
+        assert_eq!(proof.claimed_sums.len(), BASE_EXTENSIONS.len() + 1);
+        assert!(!proof.claimed_sums[0].is_zero());
+        assert!(!proof.claimed_sums[bit_op_idx].is_zero());
+        assert!(proof.claimed_sums.iter().sum::<SecureField>().is_zero());
+        crate::verify(proof.clone(), &view).unwrap();
+
+        for idx in 0..proof.claimed_sums.len() {
+            let mut tampered = proof.clone();
+            tampered.claimed_sums[idx] += SecureField::one();
+            assert!(crate::verify(tampered, &view).is_err());
+        }
+        // Moving part of a sum to another component keeps the total, every component still checks its own sum.
+        for idx in 1..proof.claimed_sums.len() {
+            let mut tampered = proof.clone();
+            tampered.claimed_sums[0] += SecureField::one();
+            tampered.claimed_sums[idx] -= SecureField::one();
+            assert!(crate::verify(tampered, &view).is_err());
+        }
+    }
+
+    #[test]
+    fn claimed_sums_are_mixed_into_transcript() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 0b1100),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 0b1010),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::AND), 3, 1, 2),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let (proof, prover_log) =
+            Machine::<BaseComponent>::prove_with_transcript(&program_trace, &view).unwrap();
+        let verify = |proof| {
+            Machine::<BaseComponent>::verify_with_transcript(
+                proof,
+                view.get_program_memory(),
+                &[],
+                &[
+                    view.get_ro_initial_memory(),
+                    view.get_rw_initial_memory(),
+                    view.get_public_input(),
+                ]
+                .concat(),
+                view.get_exit_code(),
+                view.get_public_output(),
+            )
+        };
+        let claimed_sums_idx = prover_log
+            .entries
+            .iter()
+            .position(|entry| entry.stage == transcript::CLAIMED_SUMS)
+            .unwrap();
+
+        // Moving part of a sum to another component keeps the total at zero, the verifier draws different
+        // randomness from the claimed sums on and rejects.
+        let mut tampered = proof.clone();
+        let last = tampered.claimed_sums.len() - 1;
+        tampered.claimed_sums[0] += SecureField::one();
+        tampered.claimed_sums[last] -= SecureField::one();
+        let (result, verifier_log) = verify(tampered);
+        assert!(result.is_err());
+        assert_eq!(
+            prover_log.first_mismatch(&verifier_log),
+            Some(claimed_sums_idx)
+        );
+
+        let (result, verifier_log) = verify(proof);
+        result.unwrap();
+        assert_eq!(prover_log.first_mismatch(&verifier_log), None);
+    }
+
+    #[test]
+    fn components_id_hashes_chip_names() {
+        let mut names = Vec::new();
+        BaseComponent::collect_names(&mut names);
+        let mut unique = names.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), names.len(), "chip names must be unique");
+        assert!(names
+            .iter()
+            .all(|name| !name.is_empty() && !name.contains("::")));
+
+        // The order of the chips changes the identifier.
+        assert_ne!(
+            Machine::<(AddChip, SubChip)>::components_id(&[]),
+            Machine::<(SubChip, AddChip)>::components_id(&[])
+        );
+        assert_ne!(
+            Machine::<(AddChip,)>::components_id(&[]),
+            Machine::<(AddChip, AddChip)>::components_id(&[])
+        );
+    }
+
+    #[test]
+    fn verify_rejects_components_mismatch() {
+        type ReducedComponent = crate::components!(AddChip);
+
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 0),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        assert_eq!(
+            proof.metadata.components_id,
+            Machine::<BaseComponent>::components_id(&[])
+        );
+        assert_eq!(proof.metadata.crate_version, env!("CARGO_PKG_VERSION"));
+
+        let err = Machine::<ReducedComponent>::verify(
+            proof,
+            view.get_program_memory(),
+            &[],
+            &[
+                view.get_ro_initial_memory(),
+                view.get_rw_initial_memory(),
+                view.get_public_input(),
+            ]
+            .concat(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap_err();
+        match err {
+            VerificationError::ComponentsMismatch { expected, actual } => {
+                assert_eq!(expected, Machine::<ReducedComponent>::components_id(&[]));
+                assert_eq!(actual, Machine::<BaseComponent>::components_id(&[]));
+            }
+            err => panic!("unexpected error: {err}"),
+        }
+    }
+
+    #[test]
+    fn verify_rejects_relation_order_mismatch() {
+        use crate::chips::{
+            BitOpChip, CpuChip, DecodingCheckChip, FusedLiChip, LoadStoreChip, ProgramMemCheckChip,
+            RangeCheckChip, RegisterMemCheckChip, TimestampChip,
+        };
+
+        type Ordered = crate::components!(AddChip);
+        // Same chips as `Ordered`, memory checks register their relations the other way around.
+        type Reordered = (
+            CpuChip,
+            DecodingCheckChip,
+            (AddChip,),
+            FusedLiChip,
+            BitOpChip,
+            LoadStoreChip,
+            RegisterMemCheckChip,
+            ProgramMemCheckChip,
+            TimestampChip,
+            RangeCheckChip,
+        );
+
+        fn relation_ids<C: MachineChip>() -> Vec<&'static str> {
+            let mut elements = AllLookupElements::default();
+            C::draw_lookup_elements(
+                &mut elements,
+                &mut Blake2sChannel::default(),
+                &ExtensionsConfig::default(),
+            );
+            elements.relation_ids().collect()
+        }
+        let (ordered, reordered) = (relation_ids::<Ordered>(), relation_ids::<Reordered>());
+        assert_ne!(ordered, reordered);
+        assert_eq!(
+            ordered.iter().collect::<BTreeSet<_>>(),
+            reordered.iter().collect::<BTreeSet<_>>()
+        );
+
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 0),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let proof = Machine::<Ordered>::prove(&program_trace, &view).unwrap();
+
+        let err = Machine::<Reordered>::verify(
+            proof,
+            view.get_program_memory(),
+            &[],
+            &[
+                view.get_ro_initial_memory(),
+                view.get_rw_initial_memory(),
+                view.get_public_input(),
+            ]
+            .concat(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, VerificationError::ComponentsMismatch { .. }),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn trace_digest_is_reproducible() {
+        let store_and_load = |value| {
+            vec![BasicBlock::new(vec![
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 0x100),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, value),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 1, 2, 4),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::SB), 1, 2, 9),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 3, 1, 4),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::LBU), 4, 1, 9),
+            ])]
+        };
+        // Every run starts from a fresh emulator, as on different hosts.
+        let run = |value| {
+            let (view, program_trace) =
+                k_trace_direct(&store_and_load(value), 1).expect("error generating trace");
+            let digest = Machine::<BaseComponent>::build_traces(&[], &program_trace, &view)
+                .unwrap()
+                .trace_digest();
+            let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+            (digest, postcard::to_stdvec(&proof).unwrap())
+        };
+
+        let (digest, proof) = run(7);
+        assert_eq!(run(7), (digest, proof));
+        assert_ne!(run(8).0, digest);
+    }
+
+    #[test]
+    fn prove_resumable_from_checkpoint() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 3, 2, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 4, 3, 2),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let checkpoint_dir =
+            std::env::temp_dir().join(format!("nexus-prover-checkpoint-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&checkpoint_dir);
+
+        // Interrupt proving at the checkpoint, as if the job was preempted.
+        let checkpoint =
+            Machine::<BaseComponent>::prove_until_checkpoint(&[], &program_trace, &view).unwrap();
+        checkpoint.save(&checkpoint_dir).unwrap();
+        drop(checkpoint);
+        assert!(ProverCheckpoint::load(&checkpoint_dir).unwrap().is_some());
+
+        let proof =
+            Machine::<BaseComponent>::prove_resumable(&[], &program_trace, &view, &checkpoint_dir)
+                .unwrap();
+        std::fs::remove_dir_all(&checkpoint_dir).unwrap();
+
+        // Resuming reproduces the proof of an uninterrupted run.
+        let expected = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        assert_eq!(
+            postcard::to_stdvec(&proof).unwrap(),
+            postcard::to_stdvec(&expected).unwrap()
+        );
+
+        Machine::<BaseComponent>::verify(
+            proof,
+            view.get_program_memory(),
+            &[],
+            &[
+                view.get_ro_initial_memory(),
+                view.get_rw_initial_memory(),
+                view.get_public_input(),
+            ]
+            .concat(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn prove_resumable_rejects_foreign_checkpoint() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 0),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let checkpoint_dir = std::env::temp_dir().join(format!(
+            "nexus-prover-foreign-checkpoint-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&checkpoint_dir);
+
+        Machine::<BaseComponent>::prove_until_checkpoint(&[], &program_trace, &view)
+            .unwrap()
+            .save(&checkpoint_dir)
+            .unwrap();
+        let result = Machine::<crate::components!(AddChip)>::prove_resumable(
+            &[],
+            &program_trace,
+            &view,
+            &checkpoint_dir,
+        );
+        std::fs::remove_dir_all(&checkpoint_dir).unwrap();
+        assert!(matches!(
+            result,
+            Err(NexusProvingError::InvalidCheckpoint(_))
+        ));
+    }
+
+    /// Saves the checkpoint of `basic_block` after applying `tamper` and resumes from it with the trace of
+    /// `resume_block`.
+    fn resume_tampered_checkpoint(
+        name: &str,
+        resume_block: &[BasicBlock],
+        tamper: impl FnOnce(&mut ProverCheckpoint),
+    ) -> Result<Proof, NexusProvingError> {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 0),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let (resume_view, resume_trace) =
+            k_trace_direct(resume_block, 1).expect("error generating trace");
+        let checkpoint_dir = std::env::temp_dir().join(format!(
+            "nexus-prover-{name}-checkpoint-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&checkpoint_dir);
+
+        let mut checkpoint =
+            Machine::<BaseComponent>::prove_until_checkpoint(&[], &program_trace, &view).unwrap();
+        tamper(&mut checkpoint);
+        checkpoint.save(&checkpoint_dir).unwrap();
+        let result = Machine::<BaseComponent>::prove_resumable(
+            &[],
+            &resume_trace,
+            &resume_view,
+            &checkpoint_dir,
+        );
+        std::fs::remove_dir_all(&checkpoint_dir).unwrap();
+        result
+    }
+
+    #[test]
+    fn prove_resumable_checks_execution_and_trace() {
+        let same_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 0),
+        ])];
+        let other_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 2),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 0),
+        ])];
+
+        assert!(resume_tampered_checkpoint("untampered", &same_block, |_| {}).is_ok());
+
+        for result in [
+            resume_tampered_checkpoint("other-program", &other_block, |_| {}),
+            resume_tampered_checkpoint("other-input", &same_block, |checkpoint| {
+                checkpoint.public_input_hash[0] ^= 1;
+            }),
+            resume_tampered_checkpoint("swapped-columns", &same_block, |checkpoint| {
+                checkpoint.trees[0].swap(0, 1);
+            }),
+        ] {
+            assert!(matches!(
+                result,
+                Err(NexusProvingError::InvalidCheckpoint(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn prove_cancellable_stops_when_cancelled() {
+        // Counts x1 up to 2^18, executing about 2^19 instructions.
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LUI), 2, 0, 0x40),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 1, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::BNE), 1, 2, 0x1FFC), // -4
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let result = Machine::<BaseComponent>::prove_cancellable(&program_trace, &view, token);
+        assert!(matches!(result, Err(NexusProvingError::Aborted(_))));
+
+        let token = CancellationToken::new();
+        let (result, cancelled_at) = std::thread::scope(|scope| {
+            let prover = scope.spawn(|| {
+                let result = Machine::<BaseComponent>::prove_cancellable(
+                    &program_trace,
+                    &view,
+                    token.clone(),
+                );
+                (result, std::time::Instant::now())
+            });
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            token.cancel();
+            let cancelled_at = std::time::Instant::now();
+            (prover.join().expect("proving panicked"), cancelled_at)
+        });
+        let (result, returned_at) = result;
+        assert!(matches!(result, Err(NexusProvingError::Aborted(_))));
+        assert!(
+            returned_at.saturating_duration_since(cancelled_at) < std::time::Duration::from_secs(5)
+        );
+    }
+
+    #[test]
+    fn check_trace_accepts_valid_executions() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 0, 2, 8),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 3, 0, 8),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        Machine::<BaseComponent>::check_trace(&program_trace, &view).unwrap();
+
+        // Exits with code 3, writing it to the public output.
+        let elf = elf_from_instructions(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 5, 0, 0x84).encode(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 3).encode(),
+            wou(5, 10, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, 0x201).encode(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0).encode(),
+        ]);
+        let (view, program_trace) = k_trace(elf, &[], &[], &[], 1).expect("error generating trace");
+        Machine::<BaseComponent>::check_trace(&program_trace, &view).unwrap();
+    }
+
+    #[test]
+    fn check_trace_reports_unsatisfied_constraint() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 8),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 3, 0, 128),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SB), 1, 3, 0),
+        ])];
+        let (view, mut program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let store_step = &mut program_trace.blocks.last_mut().unwrap().steps[0];
+        let mut memory_record = std::mem::take(&mut store_step.memory_records)
+            .into_iter()
+            .next()
+            .unwrap();
+        match &mut memory_record {
+            MemoryRecord::StoreRecord((_, _, value, _), _) => *value += 10,
+            _ => panic!("store record expected"),
+        };
+        store_step.memory_records = MemoryRecords::from_iter([memory_record]);
+
+        let result = Machine::<BaseComponent>::check_trace(&program_trace, &view);
+        match result {
+            Err(TraceCheckError::ConstraintNotSatisfied { chip, row, .. }) => {
+                let mut names = Vec::new();
+                BaseComponent::collect_names(&mut names);
+                assert!(names.contains(&chip), "{chip}");
+                assert_eq!(row, 2);
+            }
+            result => panic!("unexpected result: {result:?}"),
+        }
+    }
+
+    #[test]
+    fn prove_verify_reduced_components() {
+        type ReducedComponent = crate::components!(AddChip, SubChip, BeqChip);
+
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 5),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 3, 2, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::BEQ), 3, 1, 8),
+            Instruction::unimpl(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 0, 3, 8),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 4, 0, 8),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        let proof = Machine::<ReducedComponent>::prove(&program_trace, &view).unwrap();
+        assert_ne!(
+            proof.metadata.components_id,
+            Machine::<BaseComponent>::components_id(&[])
+        );
+        Machine::<ReducedComponent>::verify(
+            proof,
+            view.get_program_memory(),
+            &[],
+            &[
+                view.get_ro_initial_memory(),
+                view.get_rw_initial_memory(),
+                view.get_public_input(),
+            ]
+            .concat(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn prove_verify_worst_case_trace() {
+        // All instruction chips on adjacent rows, which tests of a single opcode never exercise.
+        let log_size = PreprocessedTraces::MIN_LOG_SIZE + 1;
+        let (_, trace) = synthesize_worst_case_trace(log_size);
+        let executed: Vec<BuiltinOpcode> = trace
+            .get_blocks_iter()
+            .flat_map(|block| &block.steps)
+            .filter_map(|step| step.instruction.opcode.builtin())
+            .collect();
+        let supported = Machine::<BaseComponent>::supported_opcodes();
+        assert!(executed.iter().all(|opcode| supported.contains(opcode)));
+        for opcode in [
+            BuiltinOpcode::AUIPC,
+            BuiltinOpcode::SRA,
+            BuiltinOpcode::MULHSU,
+            BuiltinOpcode::REMU,
+            BuiltinOpcode::LHU,
+            BuiltinOpcode::SB,
+            BuiltinOpcode::BGEU,
+            BuiltinOpcode::JALR,
+            BuiltinOpcode::ECALL,
+        ] {
+            assert!(executed.contains(&opcode), "{opcode} is not executed");
+        }
+
+        let stats = prove_worst_case(log_size);
+        assert_eq!(stats.num_steps, trace.get_num_steps());
+        assert!(stats.num_steps < 1 << log_size && stats.num_steps > 7 << (log_size - 3));
+        // The counting allocator is only installed by the peak memory tests.
+        assert_eq!(stats.peak_heap_bytes, None);
+    }
+
+    #[test]
+    fn prove_rejects_unsupported_opcode() {
+        type NoSubComponent = crate::components!(AddChip, BeqChip);
+
+        let supported = Machine::<NoSubComponent>::supported_opcodes();
+        assert!(supported.contains(&BuiltinOpcode::ADDI));
+        assert!(supported.contains(&BuiltinOpcode::SW));
+        assert!(!supported.contains(&BuiltinOpcode::SUB));
+        assert!(Machine::<BaseComponent>::supported_opcodes().contains(&BuiltinOpcode::SUB));
+
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 5),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 3, 2, 1),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        let result = Machine::<NoSubComponent>::prove(&program_trace, &view);
+        let Err(NexusProvingError::UnsupportedOpcode(err)) = result else {
+            panic!("SUB must be rejected");
+        };
+        assert_eq!(err.opcode, Opcode::from(BuiltinOpcode::SUB));
+        assert_eq!(err.row, 2);
+        assert_eq!(err.pc, program_trace.blocks[2].steps[0].pc);
+        Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+    }
+
+    #[test]
+    fn prove_rejects_unknown_opcode() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 5),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+        ])];
+        let (view, mut program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        // Neither the machine nor any extension knows this opcode.
+        let unknown = Opcode::new(0x0B, Some(0b000), None, "unknown");
+        program_trace.blocks[1].steps[0].instruction.opcode = unknown.clone();
+        let result = Machine::<BaseComponent>::prove(&program_trace, &view);
+        let Err(NexusProvingError::UnsupportedOpcode(err)) = result else {
+            panic!("unknown opcode must be rejected");
+        };
+        assert_eq!(err.opcode, unknown);
+        assert_eq!(err.row, 1);
+        assert_eq!(err.pc, program_trace.blocks[1].steps[0].pc);
+
+        // Keccakf is only supported with the keccak extension enabled.
+        program_trace.blocks[1].steps[0].instruction.opcode =
+            Opcode::new(KECCAKF_OPCODE, Some(0b000), None, "keccakf");
+        let result = Machine::<BaseComponent>::prove(&program_trace, &view);
+        assert!(matches!(
+            result,
+            Err(NexusProvingError::UnsupportedOpcode(UnsupportedOpcode {
+                row: 1,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn prove_verify_self_loop_halt() {
+        let instructions = vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 7).encode(),
+            // j .
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::JAL), 0, 0, 0).encode(),
+        ];
+        let elf = elf_from_instructions(instructions);
+        let options = TraceOptions {
+            halt_on_self_loop: true,
+            ..Default::default()
+        };
+        let (view, program_trace) =
+            k_trace_with_options(elf, &[], &[], &[], 1, options).expect("error generating trace");
+        assert_eq!(
+            view.view_termination_cause(),
+            Some(TerminationCause::SelfLoopHalt(ELF_TEXT_START + 4))
+        );
+
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        Machine::<BaseComponent>::verify(
+            proof,
+            view.get_program_memory(),
+            &[],
+            &[
+                view.get_public_input(),
+                view.get_ro_initial_memory(),
+                view.get_rw_initial_memory(),
+            ]
+            .concat(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn prove_verify_load_from_text() {
+        // A constant placed after the exit, e.g. a jump table entry, is never executed.
+        let constant = Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 5, 5, 0x345).encode();
+        let program = |access: Instruction| {
+            vec![
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::AUIPC), 1, 0, 0).encode(),
+                access.encode(),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0).encode(),
+                Instruction::new_ir(
+                    Opcode::from(BuiltinOpcode::ADDI),
+                    17,
+                    0,
+                    u32::from(SyscallCode::Exit),
+                )
+                .encode(),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0).encode(),
+                constant,
+            ]
+        };
+
+        let load = Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 2, 1, 20);
+        let (view, program_trace) = k_trace(elf_from_instructions(program(load)), &[], &[], &[], 1)
+            .expect("error generating trace");
+        assert_eq!(program_trace.blocks[1].steps[0].result, Some(constant));
+        assert!(view
+            .get_ro_initial_memory()
+            .contains(&MemoryInitializationEntry {
+                address: ELF_TEXT_START + 20,
+                value: constant.to_le_bytes()[0],
+            }));
+
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        Machine::<BaseComponent>::verify(
+            proof,
+            view.get_program_memory(),
+            &[],
+            &[
+                view.get_public_input(),
+                view.get_ro_initial_memory(),
+                view.get_rw_initial_memory(),
+            ]
+            .concat(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap();
+
+        // The program is read-only.
+        let store = Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 1, 2, 20);
+        assert!(k_trace(elf_from_instructions(program(store)), &[], &[], &[], 1).is_err());
+    }
+
+    #[test]
+    fn prove_verify_stack_overflow() {
+        let overwrite_sp = u32::from(SyscallCode::OverwriteStackPointer);
+        let instructions = vec![
+            // la sp, __memory_top
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LUI), 2, 0, MEMORY_TOP >> 12).encode(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, overwrite_sp).encode(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0).encode(),
+            // Unbounded recursion pushing 16 bytes at a time.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 2, 0xFF0).encode(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 2, 1, 0).encode(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::JAL), 0, 0, -8i32 as u32).encode(),
+        ];
+        let options = TraceOptions {
+            stack_guard: Some(StackGuard {
+                max_stack_size: 64,
+                guard_pages: 1,
+            }),
+            ..Default::default()
+        };
+        let (view, program_trace) = k_trace_with_options(
+            elf_from_instructions(instructions),
+            &[],
+            &[],
+            &[],
+            1,
+            options,
+        )
+        .expect("error generating trace");
+
+        // Four frames fit, the store of the fifth one hits the guard region and is not executed.
+        let stack_top = view.view_memory_layout().unwrap().stack_top();
+        assert_eq!(
+            view.view_termination_cause(),
+            Some(TerminationCause::StackOverflow {
+                sp: stack_top - 80,
+                addr: stack_top - 80,
+            })
+        );
+        assert_eq!(view.view_max_stack_depth(), Some(80));
+        assert_eq!(program_trace.get_num_steps(), 3 + 4 * 3 + 1);
+
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        Machine::<BaseComponent>::verify(
+            proof,
+            view.get_program_memory(),
+            &[],
+            &[
+                view.get_public_input(),
+                view.get_ro_initial_memory(),
+                view.get_rw_initial_memory(),
+            ]
+            .concat(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn verify_successful_execution_checks_exit_code() {
+        let exit_with = |code| {
+            elf_from_instructions(vec![
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 5, 0, 0x84).encode(),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, code).encode(),
+                wou(5, 10, 0),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, 0x201).encode(),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0).encode(),
+            ])
+        };
+
+        let (view, program_trace) =
+            k_trace(exit_with(0), &[], &[], &[], 1).expect("error generating trace");
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        let outputs = crate::verify_successful_execution(proof, &view).unwrap();
+        assert_eq!(outputs.exit_code, 0);
+        assert_eq!(outputs.output, view.public_output_bytes());
+
+        let (view, program_trace) =
+            k_trace(exit_with(7), &[], &[], &[], 1).expect("error generating trace");
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        assert!(matches!(
+            crate::verify_successful_execution(proof.clone(), &view),
+            Err(crate::VerifyOrExitError::UnexpectedExitCode {
+                expected: 0,
+                actual: 7
+            })
+        ));
+        assert_eq!(
+            crate::verify_with_exit_code(proof, &view, 7)
+                .unwrap()
+                .exit_code,
+            7
+        );
+
+        // j .
+        let trapped = elf_from_instructions(vec![Instruction::new_ir(
+            Opcode::from(BuiltinOpcode::JAL),
+            0,
+            0,
+            0,
+        )
+        .encode()]);
+        let options = TraceOptions {
+            halt_on_self_loop: true,
+            ..Default::default()
+        };
+        let (view, program_trace) = k_trace_with_options(trapped, &[], &[], &[], 1, options)
+            .expect("error generating trace");
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        assert!(matches!(
+            crate::verify_successful_execution(proof, &view),
+            Err(crate::VerifyOrExitError::NoExitCode)
+        ));
+    }
+
+    #[test]
+    fn verify_returning_summary_reports_public_data() {
+        let elf = elf_from_instructions(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 5, 0, 0x84).encode(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0x2A).encode(),
+            wou(5, 10, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 3).encode(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, 0x201).encode(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0).encode(),
+        ]);
+        let (view, program_trace) = k_trace(elf, &[], &[], &[], 1).expect("error generating trace");
+        assert_eq!(program_trace.get_num_steps(), 6);
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        assert_eq!(proof.num_steps, 6);
+        let log_size = proof.log_size[0];
+
+        let summary = crate::verify_returning_summary(proof.clone(), &view).unwrap();
+        let mut output_hash = [0u8; 32];
+        let mut hasher = Keccak::v256();
+        hasher.update(&view.public_output_bytes());
+        hasher.finalize(&mut output_hash);
+        assert_eq!(
+            summary,
+            crate::VerifiedExecutionSummary {
+                num_steps: 6,
+                num_rows: 1 << log_size,
+                exit_code: Some(3),
+                public_output_hash: output_hash,
+            }
+        );
+        assert_eq!(summary.num_padding_rows(), (1 << log_size) - 6);
+
+        // Counts that don't match the padding of the main trace change the committed program trace.
+        for num_steps in [0, 5, 7, 1 << log_size, (1 << log_size) + 1, u32::MAX] {
+            let proof = Proof {
+                num_steps,
+                ..proof.clone()
+            };
+            assert!(crate::verify_returning_summary(proof, &view).is_err());
+        }
+        let proof = Proof {
+            log_size: [vec![log_size + 1], proof.log_size[1..].to_vec()].concat(),
+            ..proof
+        };
+        assert!(crate::verify_returning_summary(proof, &view).is_err());
+    }
+
+    #[test]
+    fn verify_with_output_claims_checks_output() {
+        let elf = elf_from_instructions(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 5, 0, 0x84).encode(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0x2A).encode(),
+            wou(5, 10, 4),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0x7F).encode(),
+            wou(5, 10, 8),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0).encode(),
+            wou(5, 10, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, 0x201).encode(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0).encode(),
+        ]);
+        let (view, program_trace) = k_trace(elf, &[], &[], &[], 1).expect("error generating trace");
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+
+        let satisfied = [
+            crate::OutputClaim::new(0, [0x2A, 0, 0, 0]),
+            crate::OutputClaim::new(4, [0x7F]),
+        ];
+        crate::verify_with_output_claims(proof.clone(), &view, &satisfied).unwrap();
+        crate::verify_with_output_claims(proof.clone(), &view, &[]).unwrap();
+
+        assert!(matches!(
+            crate::verify_with_output_claims(
+                proof.clone(),
+                &view,
+                &[crate::OutputClaim::new(0, [0x2B])],
+            ),
+            Err(crate::VerifyOrExitError::OutputClaimViolated { offset: 0, actual, .. })
+                if actual == [0x2A]
+        ));
+
+        // Claims can't extend the output with zeros.
+        let len = view.public_output_bytes().len() as u32;
+        assert!(matches!(
+            crate::verify_with_output_claims(proof, &view, &[crate::OutputClaim::new(len, [0])]),
+            Err(crate::VerifyOrExitError::OutputClaimViolated { actual, .. }) if actual.is_empty()
+        ));
+    }
+
+    #[test]
+    fn verify_with_output_claims_counts_offsets_from_output_start() {
+        // Only the second word of the output is written.
+        let elf = elf_from_instructions(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 5, 0, 0x84).encode(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0x7F).encode(),
+            wou(5, 10, 8),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0).encode(),
+            wou(5, 10, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, 0x201).encode(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0).encode(),
+        ]);
+        let (view, program_trace) = k_trace(elf, &[], &[], &[], 1).expect("error generating trace");
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+
+        crate::verify_with_output_claims(
+            proof.clone(),
+            &view,
+            &[crate::OutputClaim::new(4, [0x7F])],
+        )
+        .unwrap();
+        assert!(matches!(
+            crate::verify_with_output_claims(proof, &view, &[crate::OutputClaim::new(0, [0x7F])]),
+            Err(crate::VerifyOrExitError::OutputClaimViolated { offset: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn verify_rejects_malformed_public_output() {
+        let elf = elf_from_instructions(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 5, 0, 0x84).encode(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0x2A).encode(),
+            wou(5, 10, 4),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0).encode(),
+            wou(5, 10, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, 0x201).encode(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0).encode(),
+        ]);
+        let (view, program_trace) = k_trace(elf, &[], &[], &[], 1).expect("error generating trace");
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        let layout = *view.view_memory_layout().unwrap();
+        let init_memory = [
+            view.get_ro_initial_memory(),
+            view.get_rw_initial_memory(),
+            view.get_public_input(),
+        ]
+        .concat();
+        let exit_code = view.get_exit_code();
+        let output = view.get_public_output();
+        assert!(!output.is_empty());
+        let verify = |exit_code: &[PublicOutputEntry], output: &[PublicOutputEntry]| {
+            Machine::<BaseComponent>::verify_with_memory_layout(
+                &layout,
+                proof.clone(),
+                view.get_program_memory(),
+                &[],
+                &init_memory,
+                exit_code,
+                output,
+            )
+        };
+
+        verify(exit_code, output).unwrap();
+        crate::verify(proof.clone(), &view).unwrap();
+
+        // The exit code is missing a byte, or isn't at the start of the output memory.
+        assert!(matches!(
+            verify(&exit_code[..WORD_SIZE - 1], output),
+            Err(VerificationError::PublicOutput(PublicOutputError::MalformedExitCode { expected }))
+                if expected == layout.exit_code()
+        ));
+        let moved: Vec<_> = exit_code
+            .iter()
+            .map(|entry| PublicOutputEntry::new(entry.address + WORD_SIZE as u32, entry.value))
+            .collect();
+        assert!(matches!(
+            verify(&moved, output),
+            Err(VerificationError::PublicOutput(
+                PublicOutputError::MalformedExitCode { .. }
+            ))
+        ));
+
+        let duplicated = [output, &output[..1]].concat();
+        assert!(matches!(
+            verify(exit_code, &duplicated),
+            Err(VerificationError::PublicOutput(PublicOutputError::DuplicateAddress { address }))
+                if address == output[0].address
+        ));
+
+        let past_region = [
+            output,
+            &[PublicOutputEntry::new(layout.public_output_end(), 0)],
+        ]
+        .concat();
+        assert!(matches!(
+            verify(exit_code, &past_region),
+            Err(VerificationError::PublicOutput(PublicOutputError::OutsideOutputRegion { address }))
+                if address == layout.public_output_end()
+        ));
+
+        // Without a memory layout, duplicates and entries on the exit code are still rejected.
+        let on_exit_code = [output, &[PublicOutputEntry::new(layout.exit_code(), 0)]].concat();
+        for (output, expected) in [
+            (
+                &duplicated,
+                PublicOutputError::DuplicateAddress {
+                    address: output[0].address,
+                },
+            ),
+            (
+                &on_exit_code,
+                PublicOutputError::OutsideOutputRegion {
+                    address: layout.exit_code(),
+                },
+            ),
+        ] {
+            let result = Machine::<BaseComponent>::verify(
+                proof.clone(),
+                view.get_program_memory(),
+                &[],
+                &init_memory,
+                exit_code,
+                output,
+            );
+            assert!(matches!(result, Err(VerificationError::PublicOutput(err)) if err == expected));
+        }
+        assert!(matches!(
+            Machine::<BaseComponent>::verify(
+                proof,
+                view.get_program_memory(),
+                &[],
+                &init_memory,
+                &exit_code[1..],
+                output,
+            ),
+            Err(VerificationError::PublicOutput(
+                PublicOutputError::MalformedExitCode { .. }
+            ))
+        ));
+
+        // The prover rejects the same public data.
+        let with_output = |output: &Vec<PublicOutputEntry>| {
+            View::new(
+                &Some(layout),
+                &vec![],
+                view.get_program_memory(),
+                &view.get_ro_initial_memory().to_vec(),
+                &view.get_rw_initial_memory().to_vec(),
+                &view.get_public_input().to_vec(),
+                view.view_tracked_ram_size(),
+                &exit_code.to_vec(),
+                output,
+                &vec![],
+            )
+        };
+        assert!(matches!(
+            Machine::<BaseComponent>::prove(&program_trace, &with_output(&past_region)),
+            Err(NexusProvingError::PublicOutput(
+                PublicOutputError::OutsideOutputRegion { .. }
+            ))
+        ));
+        assert!(matches!(
+            Machine::<BaseComponent>::prove(&program_trace, &with_output(&duplicated)),
+            Err(NexusProvingError::PublicOutput(
+                PublicOutputError::DuplicateAddress { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn verify_streaming_matches_slice_verification() {
+        let elf = elf_from_instructions(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 5, 0, 0x84).encode(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0x2A).encode(),
+            wou(5, 10, 4),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0x7F).encode(),
+            wou(5, 10, 8),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0).encode(),
+            wou(5, 10, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, 0x201).encode(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0).encode(),
+        ]);
+        let (view, program_trace) = k_trace(elf, &[], &[], &[], 1).expect("error generating trace");
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        let init_memory = [
+            view.get_public_input(),
+            view.get_ro_initial_memory(),
+            view.get_rw_initial_memory(),
+        ]
+        .concat();
+        let bytes = view.public_output_bytes();
+        let base = public_output_start(view.get_exit_code()).unwrap();
+        // Entries are generated on demand, `altered` flips the byte at that offset.
+        let stream = |altered: Option<usize>| {
+            let bytes = bytes.clone();
+            let mut offset = 0;
+            std::iter::from_fn(move || {
+                let mut value = *bytes.get(offset)?;
+                if altered == Some(offset) {
+                    value ^= 1;
+                }
+                let entry = PublicOutputEntry::new(base + offset as u32, value);
+                offset += 1;
+                Some(entry)
+            })
+        };
+        let verify_streaming = |proof, output: &mut dyn Iterator<Item = PublicOutputEntry>| {
+            Machine::<BaseComponent>::verify_streaming(
+                &[],
+                proof,
+                view.get_program_memory(),
+                &[],
+                &init_memory,
+                view.get_exit_code(),
+                output,
+            )
+        };
+
+        Machine::<BaseComponent>::verify(
+            proof.clone(),
+            view.get_program_memory(),
+            &[],
+            &init_memory,
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap();
+        verify_streaming(proof.clone(), &mut stream(None)).unwrap();
+
+        for offset in [0, bytes.len() - 1] {
+            assert!(verify_streaming(proof.clone(), &mut stream(Some(offset))).is_err());
+        }
+        let mut reversed: Vec<_> = stream(None).collect();
+        reversed.reverse();
+        assert!(matches!(
+            verify_streaming(proof, &mut reversed.into_iter()),
+            Err(VerificationError::UnsortedPublicOutput { .. })
+        ));
+    }
+
+    #[test]
+    fn verify_with_output_regions_checks_listed_regions() {
+        let elf = elf_from_instructions(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 5, 0, 0x84).encode(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0x2A).encode(),
+            wou(5, 10, 4),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0x7F).encode(),
+            wou(5, 10, 8),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0).encode(),
+            wou(5, 10, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, 0x201).encode(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0).encode(),
+        ]);
+        let (mut view, program_trace) =
+            k_trace(elf, &[], &[], &[], 1).expect("error generating trace");
+        let [output] = &view.output_regions()[..] else {
+            panic!("the output is a single region by default");
+        };
+        let start = output.base;
+        view.set_output_regions(vec![
+            OutputRegion::new("events", start + 4, 4),
+            OutputRegion::new("root", start, 4),
+        ])
+        .unwrap();
+        let root = view.get_public_output_region("root").unwrap();
+        assert_eq!(
+            OutputRegion::new("", start, 4).bytes(&root),
+            [0x2A, 0, 0, 0]
+        );
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+
+        // The verifier checks the events only, the root is carried along unchecked.
+        crate::verify_with_output_regions(proof.clone(), &view, &[("events", &[0x7F])]).unwrap();
+        crate::verify_with_output_regions(proof.clone(), &view, &[]).unwrap();
+
+        assert!(matches!(
+            crate::verify_with_output_regions(proof.clone(), &view, &[("events", &[0x7E])]),
+            Err(crate::VerifyOrExitError::OutputRegionMismatch { region, actual, .. })
+                if region == "events" && actual == [0x7F, 0, 0, 0]
+        ));
+        assert!(matches!(
+            crate::verify_with_output_regions(proof.clone(), &view, &[("root", &[0x2A; 5])]),
+            Err(crate::VerifyOrExitError::OutputRegionTooShort { region, len: 4, expected_len: 5 })
+                if region == "root"
+        ));
+        assert!(matches!(
+            crate::verify_with_output_regions(proof, &view, &[("state", &[])]),
+            Err(crate::VerifyOrExitError::UnknownOutputRegion(_))
+        ));
+    }
+
+    #[test]
+    fn prove_verify_entry_point_with_args() {
+        let exit = [
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 5, 0, 0x84).encode(),
+            wou(5, 10, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, 0x201).encode(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0).encode(),
+        ];
+        // Two functions exiting with a0 + a1 and a0 - a1.
+        let add = Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 10, 10, 11).encode();
+        let sub = Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 10, 10, 11).encode();
+        let instructions = [&[add][..], &exit, &[sub], &exit].concat();
+        let sub_entry = ELF_TEXT_START + ((1 + exit.len()) * WORD_SIZE) as u32;
+
+        for (entry, result) in [(ELF_TEXT_START, 42), (sub_entry, 18)] {
+            let elf = elf_from_instructions(instructions.clone())
+                .with_entry(entry)
+                .unwrap();
+            let options = TraceOptions {
+                initial_args: vec![30, 12],
+                ..Default::default()
+            };
+            let (view, program_trace) = k_trace_with_options(elf, &[], &[], &[], 1, options)
+                .expect("error generating trace");
+            let program_memory = view.get_program_memory();
+            assert_eq!(program_memory.initial_args, [30, 12]);
+
+            let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+            assert_eq!(
+                crate::verify_with_exit_code(proof.clone(), &view, result)
+                    .unwrap()
+                    .exit_code,
+                result
+            );
+
+            // The proof binds both the entrypoint and the arguments.
+            let verify = |program_info: &ProgramInfo| {
+                Machine::<BaseComponent>::verify(
+                    proof.clone(),
+                    program_info,
+                    &[],
+                    &[
+                        view.get_public_input(),
+                        view.get_ro_initial_memory(),
+                        view.get_rw_initial_memory(),
+                    ]
+                    .concat(),
+                    view.get_exit_code(),
+                    view.get_public_output(),
+                )
+            };
+            verify(program_memory).unwrap();
+            assert!(verify(&ProgramInfo {
+                initial_args: vec![30, 13],
+                ..program_memory.clone()
+            })
+            .is_err());
+            assert!(verify(&ProgramInfo {
+                initial_pc: program_memory.initial_pc + WORD_SIZE as u32,
+                ..program_memory.clone()
+            })
+            .is_err());
+            assert!(matches!(
+                verify(&ProgramInfo {
+                    initial_args: vec![30; ProgramInfo::MAX_INITIAL_ARGS + 1],
+                    ..program_memory.clone()
+                }),
+                Err(VerificationError::Stwo(StwoVerificationError::InvalidStructure(msg)))
+                    if msg.contains("at most 8")
+            ));
+        }
+    }
+
+    #[test]
+    fn prove_verify_checked_sqrt_hint() {
+        const SQRT_HINT: u32 = 1;
+        let sqrt = |root_offset: u32| {
+            let mut hints = HintRegistry::default();
+            hints.register(SQRT_HINT, move |input| {
+                let n = u32::from_le_bytes(input.try_into().unwrap());
+                ((n as f64).sqrt() as u32 + root_offset)
+                    .to_le_bytes()
+                    .to_vec()
+            });
+            hints
+        };
+
+        // Asks the host for the square root of 1369 and checks it by squaring, a wrong root hits UNIMPL.
+        let op = |opcode, a, b, c| Instruction::new_ir(Opcode::from(opcode), a, b, c);
+        let basic_block = vec![BasicBlock::new(vec![
+            op(BuiltinOpcode::ADDI, 5, 0, 1369),
+            op(BuiltinOpcode::SW, 0, 5, 8),
+            op(BuiltinOpcode::ADDI, 10, 0, SQRT_HINT),
+            op(BuiltinOpcode::ADDI, 11, 0, 8),
+            op(BuiltinOpcode::ADDI, 12, 0, WORD_SIZE as u32),
+            op(BuiltinOpcode::ADDI, 17, 0, SyscallCode::Hint as u32),
+            op(BuiltinOpcode::ECALL, 0, 0, 0),
+            op(BuiltinOpcode::ADDI, 17, 0, SyscallCode::ReadHint as u32),
+            op(BuiltinOpcode::ECALL, 0, 0, 0),
+            op(BuiltinOpcode::MUL, 6, 10, 10),
+            op(BuiltinOpcode::BEQ, 6, 5, 8),
+            Instruction::unimpl(),
+            op(BuiltinOpcode::ADDI, 7, 10, 0),
+        ])];
+
+        let (view, program_trace) =
+            k_trace_direct_with_hints(&basic_block, 1, sqrt(0)).expect("error generating trace");
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        crate::verify(proof, &view).unwrap();
+
+        assert!(k_trace_direct_with_hints(&basic_block, 1, sqrt(1)).is_err());
+        assert!(k_trace_direct(&basic_block, 1).is_err());
+    }
+
+    #[test]
+    fn prove_verify_cycle_counter() {
+        const ITERATIONS: u32 = 10;
+
+        // Reads the cycle counter around a loop of two instructions per iteration.
+        let op = |opcode, a, b, c| Instruction::new_ir(Opcode::from(opcode), a, b, c);
+        let basic_block = vec![BasicBlock::new(vec![
+            op(BuiltinOpcode::ADDI, 7, 0, ITERATIONS),
+            op(
+                BuiltinOpcode::ADDI,
+                17,
+                0,
+                SyscallCode::ReadCycleCounter as u32,
+            ),
+            op(BuiltinOpcode::ECALL, 0, 0, 0),
+            op(BuiltinOpcode::ADDI, 5, 10, 0),
+            op(BuiltinOpcode::ADDI, 6, 6, 1),
+            op(BuiltinOpcode::BNE, 6, 7, 0x1FFC),
+            op(BuiltinOpcode::ECALL, 0, 0, 0),
+            op(BuiltinOpcode::SUB, 28, 10, 5),
+        ])];
+
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let last_step = program_trace.blocks.last().unwrap().steps.last().unwrap();
+        assert_eq!(last_step.result, Some(2 * ITERATIONS + 2));
+
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        crate::verify(proof, &view).unwrap();
+    }
+
+    #[test]
+    fn prove_verify_read_private_input_word() {
+        // Reads a word of the private input at address 8, which takes a cycle per byte, loads it back and reads the
+        // cycle counter, twice to reach the end of the tape.
+        let op = |opcode, a, b, c| Instruction::new_ir(Opcode::from(opcode), a, b, c);
+        let read_word = [
+            op(BuiltinOpcode::ADDI, 10, 0, 8),
+            op(
+                BuiltinOpcode::ADDI,
+                17,
+                0,
+                SyscallCode::ReadPrivateInputWord as u32,
+            ),
+            op(BuiltinOpcode::ECALL, 0, 0, 0),
+            op(BuiltinOpcode::LW, 11, 0, 8),
+            op(
+                BuiltinOpcode::ADDI,
+                17,
+                0,
+                SyscallCode::ReadCycleCounter as u32,
+            ),
+            op(BuiltinOpcode::ECALL, 0, 0, 0),
+        ];
+        let basic_block = vec![BasicBlock::new(read_word.repeat(2))];
+        let mut harvard = HarvardEmulator::from_basic_blocks(&basic_block);
+        harvard.set_private_input(&[1, 2, 3, 4, 5, 6]);
+
+        let (view, program_trace) = k_trace_harvard(&mut harvard, 1).unwrap();
+        let results: Vec<_> = program_trace
+            .get_blocks_iter()
+            .filter(|block| {
+                block.steps[0].instruction.opcode.builtin() != Some(BuiltinOpcode::ADDI)
+            })
+            .map(|block| block.steps[0].result)
+            .collect();
+        // The syscall takes four cycles, each repetition nine.
+        assert_eq!(
+            results,
+            [
+                Some(4),
+                Some(0x04030201),
+                Some(9),
+                Some(2),
+                Some(0x0605),
+                Some(18)
+            ]
+        );
+
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        crate::verify(proof, &view).unwrap();
+    }
+
+    #[test]
+    fn prove_verify_with_program_template() {
+        let program = |imm| {
+            vec![BasicBlock::new(vec![
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, imm),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+            ])]
+        };
+        let (view, program_trace) = k_trace_direct(&program(1), 1).unwrap();
+        let log_size = trace_size::main_log_size(
+            program_trace.get_num_steps(),
+            view.get_program_memory().program.len(),
+        );
+        let template = ProgramTraceTemplate::new(log_size, view.get_program_memory());
+        let context = ProverContext::new(log_size).with_program_template(template.clone());
+        let verify = |proof, view: &View, template| {
+            Machine::<BaseComponent>::verify_with_program_template(
+                template,
+                proof,
+                view.get_program_memory(),
+                view.view_associated_data().as_deref().unwrap_or_default(),
+                &[
+                    view.get_ro_initial_memory(),
+                    view.get_rw_initial_memory(),
+                    view.get_public_input(),
+                ]
+                .concat(),
+                view.get_exit_code(),
+                view.get_public_output(),
+            )
+        };
+
+        // Proofs from the template are the ones proven without it.
+        let expected = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        for _ in 0..2 {
+            let proof =
+                Machine::<BaseComponent>::prove_with_context(&[], &program_trace, &view, &context)
+                    .unwrap();
+            assert_eq!(
+                postcard::to_stdvec(&proof).unwrap(),
+                postcard::to_stdvec(&expected).unwrap()
+            );
+            verify(proof, &view, &template).unwrap();
+        }
+
+        // A template of another program is rejected, even with the same trace size.
+        let (other_view, other_trace) = k_trace_direct(&program(2), 1).unwrap();
+        let other_template = ProgramTraceTemplate::new(log_size, other_view.get_program_memory());
+        assert!(matches!(
+            Machine::<BaseComponent>::prove_with_context(&[], &other_trace, &other_view, &context),
+            Err(NexusProvingError::ProgramTemplate(_))
+        ));
+        assert!(matches!(
+            verify(expected.clone(), &view, &other_template),
+            Err(VerificationError::ProgramTemplate(
+                ProgramTemplateMismatch::Program { .. }
+            ))
+        ));
+
+        // A template of another log size is rejected instead of being ignored.
+        let larger_template = ProgramTraceTemplate::new(log_size + 1, view.get_program_memory());
+        let larger_context =
+            ProverContext::new(log_size + 1).with_program_template(larger_template.clone());
+        assert!(matches!(
+            Machine::<BaseComponent>::prove_with_context(&[], &program_trace, &view, &larger_context),
+            Err(NexusProvingError::ProgramTemplate(ProgramTemplateMismatch::LogSize {
+                expected,
+                actual,
+            })) if expected == log_size + 1 && actual == log_size
+        ));
+        assert!(matches!(
+            verify(expected, &view, &larger_template),
+            Err(VerificationError::ProgramTemplate(
+                ProgramTemplateMismatch::LogSize { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn preprocessed_tree_is_shared_across_programs() {
+        let program = |imm| {
+            vec![BasicBlock::new(vec![
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, imm),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+            ])]
+        };
+        let (view_a, trace_a) = k_trace_direct(&program(1), 1).unwrap();
+        let (view_b, trace_b) = k_trace_direct(&program(2), 1).unwrap();
+        let prove = |trace, view, context: &ProverContext| {
+            let before = crate::commitment::hashed_nodes();
+            let proof =
+                Machine::<BaseComponent>::prove_with_context(&[], trace, view, context).unwrap();
+            (proof, crate::commitment::hashed_nodes() - before)
+        };
+
+        let log_size = trace_size::main_log_size(
+            trace_a.get_num_steps(),
+            view_a.get_program_memory().program.len(),
+        );
+        let context = ProverContext::new(log_size);
+        let (proof_a, _) = prove(&trace_a, &view_a, &context);
+        let (proof_b, warm) = prove(&trace_b, &view_b, &context);
+        assert_eq!(proof_a.log_size, proof_b.log_size);
+
+        // The second proof only hashes its program tree, the preprocessed tree is grafted from the context.
+        let (cold_proof, cold) = prove(
+            &trace_b,
+            &view_b,
+            &ProverContext::new(context.max_log_size()),
+        );
+        let before = crate::commitment::hashed_nodes();
+        let _tree = CommittedTree::commit(
+            context.config,
+            &context.twiddles,
+            preprocessed_evals(&[], &proof_b.log_size),
+        );
+        let preprocessed = crate::commitment::hashed_nodes() - before;
+        assert!(preprocessed > 0);
+        assert_eq!(cold - warm, preprocessed);
+        assert_eq!(
+            postcard::to_stdvec(&proof_b).unwrap(),
+            postcard::to_stdvec(&cold_proof).unwrap()
+        );
+
+        assert_eq!(
+            proof_a.stark_proof.commitments[PREPROCESSED_TRACE_IDX],
+            proof_b.stark_proof.commitments[PREPROCESSED_TRACE_IDX]
+        );
+        assert_ne!(
+            proof_a.stark_proof.commitments[PROGRAM_TRACE_IDX],
+            proof_b.stark_proof.commitments[PROGRAM_TRACE_IDX]
+        );
+        crate::verify(proof_a, &view_a).unwrap();
+        crate::verify(proof_b, &view_b).unwrap();
+    }
+
+    #[test]
+    fn transcript_matches_between_prover_and_verifier() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 3, 2, 1),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        // Recording doesn't change the proof.
+        let (proof, prover_log) =
+            Machine::<BaseComponent>::prove_with_transcript(&program_trace, &view).unwrap();
+        let expected = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        assert_eq!(
+            postcard::to_stdvec(&proof).unwrap(),
+            postcard::to_stdvec(&expected).unwrap()
+        );
+
+        let roots: Vec<_> = prover_log
+            .entries
+            .iter()
+            .filter(|entry| matches!(entry.op, TranscriptOp::MixRoot { .. }))
+            .map(|entry| entry.stage.as_str())
+            .collect();
+        assert_eq!(
+            roots[..4],
+            [
+                transcript::PREPROCESSED_TRACE,
+                transcript::PROGRAM_TRACE,
+                transcript::MAIN_TRACE,
+                transcript::INTERACTION_TRACE
+            ]
+        );
+        assert!(prover_log.entries.iter().any(|entry| {
+            entry.stage == transcript::LOOKUP_ELEMENTS
+                && matches!(entry.op, TranscriptOp::DrawFelts { .. })
+        }));
+        let interaction_root = prover_log
+            .entries
+            .iter()
+            .position(|entry| entry.stage == transcript::INTERACTION_TRACE)
+            .unwrap();
+        assert_eq!(
+            prover_log.entries[interaction_root + 1].stage,
+            transcript::CLAIMED_SUMS
+        );
+
+        let (result, verifier_log) = Machine::<BaseComponent>::verify_with_transcript(
+            proof,
+            view.get_program_memory(),
+            &[],
+            &[
+                view.get_ro_initial_memory(),
+                view.get_rw_initial_memory(),
+                view.get_public_input(),
+            ]
+            .concat(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        );
+        result.unwrap();
+        assert_eq!(prover_log.first_mismatch(&verifier_log), None);
+        assert_eq!(
+            TranscriptLog::from_json(&prover_log.to_json()).unwrap(),
+            prover_log
+        );
+    }
+
+    #[test]
+    fn prove_verify_runs_after_warm_reset() {
+        // Increments the counter at address 8, each run starts from the value left by the previous one.
+        let op = |opcode, a, b, c| Instruction::new_ir(Opcode::from(opcode), a, b, c);
+        let basic_block = vec![BasicBlock::new(vec![
+            op(BuiltinOpcode::LW, 5, 0, 8),
+            op(BuiltinOpcode::ADDI, 5, 5, 1),
+            op(BuiltinOpcode::SW, 0, 5, 8),
+        ])];
+        let mut harvard = HarvardEmulator::from_basic_blocks(&basic_block);
+
+        let (view, program_trace) = k_trace_harvard(&mut harvard, 1).unwrap();
+        assert!(view.get_rw_initial_memory().is_empty());
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        crate::verify(proof, &view).unwrap();
+
+        harvard.warm_reset();
+        assert_eq!(harvard.executor.cpu.registers.read(Register::X5), 0);
+        assert_eq!(harvard.executor.global_clock, 1);
+
+        let (view, program_trace) = k_trace_harvard(&mut harvard, 1).unwrap();
+        assert_eq!(
+            view.get_rw_initial_memory(),
+            [MemoryInitializationEntry {
+                address: 8,
+                value: 1
+            }]
+        );
+        assert_eq!(harvard.executor.cpu.registers.read(Register::X5), 2);
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        crate::verify(proof, &view).unwrap();
+    }
+
+    #[test]
+    fn prove_verify_raw_words() {
+        // Computes 5! with a loop, without an ELF file or basic blocks.
+        let words: Vec<u32> = [
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 5, 0, 5),
+            // loop:
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::MUL), 10, 10, 5),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 5, 5, -1i32 as u32),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::BNE), 5, 0, -8i32 as u32),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::JAL), 0, 0, 8),
+            Instruction::unimpl(),
+            Instruction::new_ir(
+                Opcode::from(BuiltinOpcode::ADDI),
+                17,
+                0,
+                SyscallCode::Exit as u32,
+            ),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+        ]
+        .iter()
+        .map(Instruction::encode)
+        .collect();
+        assert_eq!(
+            nexus_vm::load_raw(&words, 0x1000, 0).unwrap_err(),
+            nexus_vm::RawProgramError::UnsupportedInstruction { index: 6, word: 0 }
+        );
+
+        // Skipped by the jump, but every word must decode.
+        let mut words = words;
+        words[6] = Instruction::nop().encode();
+        let program = nexus_vm::load_raw(&words, 0x1000, 0).unwrap();
+        let mut harvard = HarvardEmulator::new_run(&program, &[], &[]);
+        let (view, program_trace) = k_trace_harvard(&mut harvard, 1).unwrap();
+        assert_eq!(view.exit_code_u32(), Some(120));
+        assert_eq!(view.get_program_memory().initial_pc, 0x1000);
+
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        crate::verify(proof, &view).unwrap();
+    }
+
+    /// Encodes a custom store into the public output segment, see `write_output!` in the runtime.
+    fn wou(rs1: u32, rs2: u32, imm: u32) -> u32 {
+        ((imm & 0xFE0) << 20) | (rs2 << 20) | (rs1 << 15) | ((imm & 0x1F) << 7) | 0b1011011
+    }
+
+    fn elf_from_instructions(instructions: Vec<u32>) -> ElfFile {
+        ElfFile::new(
+            instructions,
+            ELF_TEXT_START,
+            ELF_TEXT_START,
+            Default::default(),
+            Default::default(),
+            vec![],
+        )
+    }
+
+    /// Same as [`SubChip`], standing in for a replacement chip under development.
+    struct SubChipDouble;
+
+    impl MachineChip for SubChipDouble {
+        const NAME: &'static str = "SubChipDouble";
+
+        fn supported_opcodes() -> &'static [BuiltinOpcode] {
+            SubChip::supported_opcodes()
+        }
+
+        fn fill_main_trace(
+            traces: &mut TracesBuilder,
+            row_idx: usize,
+            vm_step: &Option<crate::trace::ProgramStep>,
+            side_note: &mut SideNote,
+            config: &ExtensionsConfig,
+        ) {
+            SubChip::fill_main_trace(traces, row_idx, vm_step, side_note, config);
+        }
+
+        fn add_constraints<E: stwo_constraint_framework::EvalAtRow>(
+            eval: &mut E,
+            trace_eval: &crate::trace::eval::TraceEval<E>,
+            lookup_elements: &AllLookupElements,
+            config: &ExtensionsConfig,
+        ) {
+            SubChip::add_constraints(eval, trace_eval, lookup_elements, config);
+        }
+    }
+
+    impl crate::chips::InstructionComponent for SubChipDouble {
+        type Chip = Self;
+    }
+
+    #[test]
+    fn compare_components_with_equivalent_chip() {
+        type Swapped = crate::components!(Base, exclude(SubChip), append(SubChipDouble));
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 5),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 3, 2, 1),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        let dry_run = compare_components::<BaseComponent, Swapped>(
+            &program_trace,
+            &view,
+            ComparisonMode::DryRun,
+        )
+        .unwrap();
+        let (a, b) = (&dry_run.a, &dry_run.b);
+        assert_ne!(a.components_id, b.components_id);
+        assert_eq!(a.log_sizes, b.log_sizes);
+        assert_eq!(a.preprocessed_columns, b.preprocessed_columns);
+        assert_eq!(a.main_columns, b.main_columns);
+        assert_eq!(a.interaction_columns, b.interaction_columns);
+        assert!(a.proof_size.is_none() && a.verified.is_none() && a.timings.prove.is_none());
+
+        let proved = compare_components::<BaseComponent, Swapped>(
+            &program_trace,
+            &view,
+            ComparisonMode::Prove,
+        )
+        .unwrap();
+        for (run, dry_run) in [(&proved.a, a), (&proved.b, b)] {
+            assert_eq!(run.verified, Some(true));
+            assert!(run.proof_size.is_some_and(|size| size > 0));
+            assert_eq!(run.interaction_columns, dry_run.interaction_columns);
+        }
+    }
+
+    #[test]
+    fn estimated_log_size_matches_prover() {
+        let addi = |rd, rs1, imm| {
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), rd, rs1, imm).encode()
+        };
+        let exit = [
+            addi(10, 0, 0),
+            addi(17, 0, u32::from(SyscallCode::Exit)),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0).encode(),
+        ];
+        // Fewer steps than the minimum trace size.
+        let short = exit.to_vec();
+        // 200 iterations of a two-instruction loop.
+        let looping = [
+            addi(1, 0, 200),
+            addi(1, 1, 0xFFF), // -1
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::BNE), 1, 0, 0x1FFC).encode(), // -4
+        ]
+        .into_iter()
+        .chain(exit)
+        .collect();
+        // More instructions than executed steps: the program skips most of its code.
+        let skipping =
+            [Instruction::new_ir(Opcode::from(BuiltinOpcode::JAL), 0, 0, 600 * 4).encode()]
+                .into_iter()
+                .chain(std::iter::repeat_n(addi(1, 1, 1), 599))
+                .chain(exit)
+                .collect();
+
+        for instructions in [short, looping, skipping] {
+            let elf = elf_from_instructions(instructions);
+            let estimate =
+                nexus_vm::estimate_trace_size(&LoadedProgram::from_elf(&elf), &[], &[]).unwrap();
+            let (view, program_trace) = k_trace(elf, &[], &[], &[], 1).unwrap();
+            assert_eq!(estimate.num_steps, program_trace.get_num_steps());
+
+            let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+            assert_eq!(estimate.log_size, proof.log_size[0]);
+        }
+    }
+
+    #[test]
+    fn prove_verify_committed_output_after_cycle_limit() {
+        let ecall = Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0).encode();
+        let addi = |rd, rs1, imm| {
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), rd, rs1, imm).encode()
+        };
+        let commit = u32::from(SyscallCode::Commit);
+
+        let instructions = vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 5, 0, 0x84).encode(),
+            addi(6, 0, 0x11),
+            wou(5, 6, 4),
+            addi(10, 0, 0),
+            addi(11, 0, 4),
+            addi(17, 0, commit),
+            ecall,
+            addi(6, 0, 0x22),
+            wou(5, 6, 8),
+            addi(10, 0, 4),
+            ecall,
+            // Never halts, stopped by the cycle limit.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::JAL), 0, 0, 0).encode(),
+        ];
+        let elf = elf_from_instructions(instructions);
+        let (view, program_trace) = k_trace_with_cycle_limit(elf, &[], &[], &[], 1, Some(64))
+            .expect("error generating trace");
+
+        assert_eq!(view.public_output_bytes(), [0x11, 0, 0, 0, 0x22, 0, 0, 0]);
+        assert_eq!(view.view_committed_output_len(), Some(8));
+
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        assert_eq!(proof.committed_output_len, Some(8));
+        let verify = |proof, output_memory| {
+            Machine::<BaseComponent>::verify(
+                proof,
+                view.get_program_memory(),
+                &[],
+                &[
+                    view.get_public_input(),
+                    view.get_ro_initial_memory(),
+                    view.get_rw_initial_memory(),
+                ]
+                .concat(),
+                view.get_exit_code(),
+                output_memory,
+            )
+        };
+        verify(proof.clone(), view.get_public_output()).unwrap();
+
+        // The committed length is bound to the program trace, where the commits of the guest must add up to it.
+        for committed_output_len in [None, Some(4), Some(12)] {
+            let proof = Proof {
+                committed_output_len,
+                ..proof.clone()
+            };
+            assert!(verify(proof, view.get_public_output()).is_err());
+        }
+        // Only committed bytes are public, and all of them.
+        assert!(matches!(
+            verify(proof, &view.get_public_output()[..4]),
+            Err(VerificationError::PublicOutput(
+                PublicOutputError::UncommittedOutput {
+                    committed: 8,
+                    len: 4
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn prove_verify_scattered_zero_region_accesses() {
+        const DATA_WORD: u32 = 0x1234_5678;
+        const ZERO_REGION_LEN: u32 = 2 << 20;
+
+        // Loads a 32-bit constant, the ADDI immediate is sign-extended.
+        let li = |rd, value: u32| {
+            [
+                Instruction::new_ir(
+                    Opcode::from(BuiltinOpcode::LUI),
+                    rd,
+                    0,
+                    value.wrapping_add(0x800) >> 12,
+                ),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), rd, rd, value & 0xFFF),
+            ]
+        };
+        let program = |data_base: u32| {
+            let bss = data_base + WORD_SIZE as u32;
+            [
+                li(1, data_base).to_vec(),
+                vec![Instruction::new_ir(
+                    Opcode::from(BuiltinOpcode::LW),
+                    2,
+                    1,
+                    0,
+                )],
+                // Read a word of the zero region before it is written, then write and read it back.
+                li(3, bss + 0x1000).to_vec(),
+                vec![
+                    Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 4, 3, 0),
+                    Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 3, 2, 8),
+                    Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 5, 3, 8),
+                ],
+                // Far away within the zero region.
+                li(6, bss + ZERO_REGION_LEN - 0x1000).to_vec(),
+                vec![
+                    Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 7, 6, 0x7FC),
+                    Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 6, 2, 0),
+                    Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0),
+                    Instruction::new_ir(
+                        Opcode::from(BuiltinOpcode::ADDI),
+                        17,
+                        0,
+                        u32::from(SyscallCode::Exit),
+                    ),
+                    Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+                ],
+            ]
+            .concat()
+        };
+        // The data word follows the code, like the linker lays out `.data` after `.text`.
+        let num_instructions = program(0).len() as u32;
+        let data_base = ELF_TEXT_START + (num_instructions + 1) * WORD_SIZE as u32;
+        let elf = ElfFile {
+            ram_image: MemorySegmentImage::try_from_contiguous_btree(&BTreeMap::from([(
+                data_base, DATA_WORD,
+            )]))
+            .unwrap(),
+            zero_regions: vec![ZeroRegion {
+                base: data_base + WORD_SIZE as u32,
+                len: ZERO_REGION_LEN,
+            }],
+            ..elf_from_instructions(program(data_base).iter().map(Instruction::encode).collect())
+        };
+
+        let (view, program_trace) = k_trace(elf, &[], &[], &[], 1).expect("error generating trace");
+        // Only the data word is initial memory, the zero region is not listed byte by byte.
+        assert_eq!(view.get_rw_initial_memory().len(), WORD_SIZE);
+        assert_eq!(view.view_zero_regions().len(), 1);
+        let loaded: Vec<u32> = program_trace
+            .get_blocks_iter()
+            .flat_map(|block| &block.steps)
+            .filter(|step| step.instruction.opcode.builtin() == Some(BuiltinOpcode::LW))
+            .map(|step| step.result.unwrap())
+            .collect();
+        assert_eq!(loaded, [DATA_WORD, 0, DATA_WORD, 0]);
+
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        // The memory checking components are sized by the touched addresses, not by the zero region.
+        assert!(proof.log_size.iter().all(|&log_size| log_size < 20));
+        Machine::<BaseComponent>::verify(
+            proof,
+            view.get_program_memory(),
+            &[],
+            &[
+                view.get_public_input(),
+                view.get_ro_initial_memory(),
+                view.get_rw_initial_memory(),
+            ]
+            .concat(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn init_memory_order_independent() {
+        let ro_memory = vec![
+            MemoryInitializationEntry::new(0x1002, 3),
+            MemoryInitializationEntry::new(0x1000, 1),
+            MemoryInitializationEntry::new(0x1001, 2),
+        ];
+        let (view, program_trace) = trace_with_ro_memory(ro_memory.clone());
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+
+        let mut reordered = ro_memory;
+        reordered.reverse();
+        Machine::<BaseComponent>::verify(
+            proof,
+            view.get_program_memory(),
+            &[],
+            &reordered,
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn prove_rejects_mismatched_entry_point() {
+        let (view, program_trace) = trace_with_ro_memory(vec![]);
+        let program_memory = ProgramInfo {
+            initial_pc: view.get_program_memory().initial_pc + WORD_SIZE as u32,
+            ..view.get_program_memory().clone()
+        };
+        let view = View::new(
+            &None,
+            &vec![],
+            &program_memory,
+            &vec![],
+            &vec![],
+            &vec![],
+            view.view_tracked_ram_size(),
+            &vec![],
+            &vec![],
+            &vec![],
+        );
+
+        let result = Machine::<BaseComponent>::prove(&program_trace, &view);
+        assert!(matches!(
+            result,
+            Err(NexusProvingError::InconsistentInputs(msg)) if msg.contains("entry point")
+        ));
+    }
+
+    #[test]
+    fn prove_rejects_mismatched_cycle_count() {
+        let short = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 0),
+        ])];
+        let long = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 3, 2, 1),
+        ])];
+        let (view, _) = k_trace_direct(&short, 1).expect("error generating trace");
+        let (_, program_trace) = k_trace_direct(&long, 1).expect("error generating trace");
+
+        let result = Machine::<BaseComponent>::prove(&program_trace, &view);
+        assert!(matches!(
+            result,
+            Err(NexusProvingError::InconsistentInputs(msg)) if msg.contains("3 cycles")
+        ));
+    }
+
+    #[test]
+    fn prove_rejects_mismatched_timestamps() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 3, 2, 1),
+        ])];
+        let (view, mut program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        // Timestamp the last step as if the second instruction took two cycles.
+        program_trace.blocks[2].steps[0].timestamp += 1;
+
+        let result = Machine::<BaseComponent>::prove(&program_trace, &view);
+        assert!(matches!(
+            result,
+            Err(NexusProvingError::InconsistentInputs(msg)) if msg.contains("timestamp 4, expected 3")
+        ));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn prove_rejects_stale_register_read() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 0),
+        ])];
+        let (view, mut program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        // The second instruction reads x1 as if the first one hadn't run.
+        program_trace.blocks[1].regs.write(Register::X1, 0);
+
+        let result = Machine::<BaseComponent>::prove(&program_trace, &view);
+        assert!(matches!(
+            result,
+            Err(NexusProvingError::RegisterAccess(
+                RegisterAccessError::StaleRead {
+                    row: 1,
+                    register: Register::X1,
+                    expected: 1,
+                    found: 0,
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn prove_rejects_mismatched_exit_code() {
+        let exit_with = |code| {
+            elf_from_instructions(vec![
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 5, 0, 0x84).encode(),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, code).encode(),
+                wou(5, 10, 0),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, 0x201).encode(),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0).encode(),
+            ])
+        };
+        let (view, _) = k_trace(exit_with(2), &[], &[], &[], 1).expect("error generating trace");
+        let (_, program_trace) =
+            k_trace(exit_with(1), &[], &[], &[], 1).expect("error generating trace");
+
+        let result = Machine::<BaseComponent>::prove(&program_trace, &view);
+        assert!(matches!(
+            result,
+            Err(NexusProvingError::InconsistentInputs(msg)) if msg.contains("exit syscall with code 1")
+        ));
+    }
+
+    #[test]
+    fn chunked_fill_matches_row_by_row() {
+        let op = |opcode, a, b, c| Instruction::new_ir(Opcode::from(opcode), a, b, c);
+        let basic_block = vec![BasicBlock::new(vec![
+            op(BuiltinOpcode::ADDI, 1, 0, 0xFFF),
+            op(BuiltinOpcode::ADDI, 2, 0, 7),
+            op(BuiltinOpcode::ADD, 3, 1, 2),
+            op(BuiltinOpcode::SUB, 4, 2, 1),
+            op(BuiltinOpcode::SLT, 5, 1, 2),
+            op(BuiltinOpcode::SLTU, 6, 1, 2),
+            op(BuiltinOpcode::BLT, 1, 2, 4),
+            op(BuiltinOpcode::BGEU, 1, 2, 4),
+            op(BuiltinOpcode::SW, 0, 3, 8),
+            op(BuiltinOpcode::LW, 7, 0, 8),
+            op(BuiltinOpcode::LB, 8, 0, 8),
+            op(BuiltinOpcode::SRA, 9, 1, 2),
+            op(BuiltinOpcode::SLL, 10, 2, 2),
+            op(BuiltinOpcode::MUL, 11, 1, 2),
+            op(BuiltinOpcode::XOR, 12, 1, 2),
+            op(BuiltinOpcode::ADD, 0, 1, 2),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let log_size = PreprocessedTraces::MIN_LOG_SIZE;
+        let config = ExtensionsConfig::default();
+
+        let fill = |chunk_rows: usize| {
+            let mut traces = TracesBuilder::new(log_size);
+            let program_traces =
+                ProgramTracesBuilder::new_with_empty_memory(log_size, view.get_program_memory());
+            let mut side_note = SideNote::new(&program_traces, &view);
+            let steps: Vec<_> = iter_program_steps(&program_trace, traces.num_rows()).collect();
+            for (i, chunk) in steps.chunks(chunk_rows).enumerate() {
+                let rows = i * chunk_rows..i * chunk_rows + chunk.len();
+                BaseComponent::fill_main_trace_chunk(
+                    &mut traces,
+                    rows,
+                    chunk,
+                    &mut side_note,
+                    &config,
+                );
+            }
+            (traces, side_note)
+        };
+
+        let mut traces = TracesBuilder::new(log_size);
+        let program_traces =
+            ProgramTracesBuilder::new_with_empty_memory(log_size, view.get_program_memory());
+        let mut side_note = SideNote::new(&program_traces, &view);
+        for (row_idx, program_step) in
+            iter_program_steps(&program_trace, traces.num_rows()).enumerate()
+        {
+            BaseComponent::fill_main_trace(
+                &mut traces,
+                row_idx,
+                &program_step,
+                &mut side_note,
+                &config,
+            );
+        }
+
+        for chunk_rows in [1, 5, 64, CANCELLATION_CHECK_ROWS] {
+            let (chunked_traces, chunked_side_note) = fill(chunk_rows);
+            assert!(
+                chunked_traces.cols == traces.cols,
+                "main trace differs with chunks of {chunk_rows} rows"
+            );
+            assert_eq!(
+                chunked_side_note.range8.multiplicity,
+                side_note.range8.multiplicity
+            );
+            assert_eq!(
+                chunked_side_note.range16.multiplicity,
+                side_note.range16.multiplicity
+            );
+            assert_eq!(
+                chunked_side_note.range32.multiplicity,
+                side_note.range32.multiplicity
+            );
+            assert_eq!(
+                chunked_side_note.range128.multiplicity,
+                side_note.range128.multiplicity
+            );
+            assert_eq!(
+                chunked_side_note.range256.multiplicity,
+                side_note.range256.multiplicity
+            );
+            assert_eq!(
+                chunked_side_note.rw_mem_check.last_access,
+                side_note.rw_mem_check.last_access
+            );
+        }
+    }
+
+    #[test]
+    fn main_trace_chunks_fall_back_to_rows_for_shared_side_note_state() {
+        assert_eq!(
+            main_trace_chunk_rows::<BaseComponent>(&ExtensionsConfig::default()),
+            CANCELLATION_CHECK_ROWS
+        );
+        // The keccak and load-store chips both update the last access of read-write memory.
+        let keccak = ExtensionsConfig::from(ExtensionComponent::keccak_extensions());
+        assert_eq!(main_trace_chunk_rows::<BaseComponent>(&keccak), 1);
+        assert_eq!(
+            main_trace_chunk_rows::<(crate::chips::LoadStoreChip,)>(&keccak),
+            CANCELLATION_CHECK_ROWS
+        );
+    }
+
+    #[test]
+    fn prove_verify_with_more_pow_bits() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let config = ProveConfig {
+            pow_bits: ProveConfig::default().pow_bits + 4,
+            ..ProveConfig::default()
+        };
+        let proof =
+            Machine::<BaseComponent>::prove_with_config(&[], &program_trace, &view, &config)
+                .unwrap();
+        assert_eq!(proof.metadata.pcs_params.pow_bits, config.pow_bits);
+        crate::verify(proof, &view).unwrap();
+    }
+
+    #[test]
+    fn security_report_enforced_by_verifier() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let verify = |proof: Proof, min_bits| {
+            Machine::<BaseComponent>::verify_with_options(
+                &VerifierOptions::default().with_min_security(min_bits),
+                proof,
+                view.get_program_memory(),
+                &[],
+                &[
+                    view.get_public_input(),
+                    view.get_ro_initial_memory(),
+                    view.get_rw_initial_memory(),
+                ]
+                .concat(),
+                view.get_exit_code(),
+                view.get_public_output(),
+            )
+        };
+
+        // The default config is bounded by its queries, the field leaves plenty of room.
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        let report = proof.security_report();
+        let config = PcsConfig::default();
+        let default_bits = config.fri_config.n_queries as u32 * config.fri_config.log_blowup_factor
+            + config.pow_bits;
+        assert_eq!(report.query_bits, default_bits);
+        assert_eq!(report.conjectured_bits, default_bits);
+        assert!(report.algebraic_bits > report.query_bits);
+        assert_eq!(report.field_bits, 123);
+        assert_eq!(
+            report.composition_log_size,
+            proof.log_size.iter().max().unwrap() + LOG_CONSTRAINT_DEGREE
+        );
+        assert!(report.n_columns > GlobalColumn::COLUMNS_NUM);
+        verify(proof.clone(), default_bits).unwrap();
+
+        // Requiring more than the default config provides rejects its proofs, the proof of work of a stronger
+        // config makes up for it.
+        assert!(matches!(
+            verify(proof, default_bits + 2),
+            Err(VerificationError::InsufficientSecurity {
+                conjectured_bits,
+                min_bits,
+            }) if conjectured_bits == default_bits && min_bits == default_bits + 2
+        ));
+        let config = ProveConfig {
+            pow_bits: ProveConfig::default().pow_bits + 2,
+            ..ProveConfig::default()
+        };
+        let proof =
+            Machine::<BaseComponent>::prove_with_config(&[], &program_trace, &view, &config)
+                .unwrap();
+        assert_eq!(proof.security_report().conjectured_bits, default_bits + 2);
+        verify(proof, default_bits + 2).unwrap();
+    }
+
+    #[test]
+    fn salt_changes_challenges_but_not_trace_commitments() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let prove = |salt| {
+            let config = ProveConfig {
+                salt,
+                ..ProveConfig::default()
+            };
+            Machine::<BaseComponent>::prove_with_config(&[], &program_trace, &view, &config)
+                .unwrap()
+        };
+
+        // Without a salt, the proof is the one of plain proving.
+        let unsalted = prove(None);
+        let plain = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        assert_eq!(
+            postcard::to_stdvec(&unsalted).unwrap(),
+            postcard::to_stdvec(&plain).unwrap()
+        );
+
+        let first = prove(Some([1; 32]));
+        let second = prove(Some([2; 32]));
+        assert_eq!(first.metadata.salt, Some([1; 32]));
+        // Committed traces don't depend on the channel, the interaction trace does through the lookup elements
+        // and the composition polynomial through the random coefficient.
+        let first_roots = &first.stark_proof.commitments;
+        let second_roots = &second.stark_proof.commitments;
+        assert_eq!(first_roots.len(), second_roots.len());
+        for (idx, (first_root, second_root)) in
+            first_roots.iter().zip(second_roots.iter()).enumerate()
+        {
+            if idx < INTERACTION_TRACE_IDX || idx == PROGRAM_TRACE_IDX {
+                assert_eq!(first_root, second_root, "commitment {idx}");
+            } else {
+                assert_ne!(first_root, second_root, "commitment {idx}");
+            }
+        }
+        assert_ne!(
+            postcard::to_stdvec(&first).unwrap(),
+            postcard::to_stdvec(&unsalted).unwrap()
+        );
+        crate::verify(first.clone(), &view).unwrap();
+        crate::verify(second, &view).unwrap();
+
+        for salt in [None, Some([2; 32])] {
+            let mut proof = first.clone();
+            proof.metadata.salt = salt;
+            assert!(crate::verify(proof, &view).is_err());
+        }
+    }
+
+    #[test]
+    fn context_id_separates_deployments() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let config = ProveConfig::default().with_context_id([0xa; 32]);
+        let proof =
+            Machine::<BaseComponent>::prove_with_config(&[], &program_trace, &view, &config)
+                .unwrap();
+        assert_eq!(proof.metadata.salt, None);
+
+        let ad = view.view_associated_data().unwrap_or_default();
+        let init_memory = [
+            view.get_ro_initial_memory(),
+            view.get_rw_initial_memory(),
+            view.get_public_input(),
+        ]
+        .concat();
+        let verify = |proof, context_id: Option<&[u8; 32]>| match context_id {
+            Some(context_id) => Machine::<BaseComponent>::verify_with_options(
+                &VerifierOptions::default().with_context_id(*context_id),
+                proof,
+                view.get_program_memory(),
+                &ad,
+                &init_memory,
+                view.get_exit_code(),
+                view.get_public_output(),
+            ),
+            None => Machine::<BaseComponent>::verify(
+                proof,
+                view.get_program_memory(),
+                &ad,
+                &init_memory,
+                view.get_exit_code(),
+                view.get_public_output(),
+            ),
+        };
+        verify(proof.clone(), Some(&[0xa; 32])).unwrap();
+        assert!(matches!(
+            verify(proof.clone(), Some(&[0xb; 32])),
+            Err(VerificationError::Stwo(_))
+        ));
+        assert!(matches!(
+            verify(proof, None),
+            Err(VerificationError::Stwo(_))
+        ));
+
+        // Proofs without a context id don't verify under one either.
+        let plain = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        assert!(verify(plain, Some(&[0xa; 32])).is_err());
+    }
+
+    #[test]
+    fn verification_cost_follows_config() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+
+        // The prover uses the default config, the FRI proof has a layer for each one in the estimate.
+        let config = PcsConfig::default();
+        let cost =
+            Machine::<BaseComponent>::verification_cost(&[], &proof.log_size, &config).unwrap();
+        assert_eq!(
+            cost.fri_layers,
+            proof.stark_proof.fri_proof.inner_layers.len() + 1
+        );
+        assert_eq!(
+            cost.fri_layer_queries,
+            config.fri_config.n_queries * cost.fri_layers
+        );
+        assert_eq!(cost.pow_checks, 1);
+
+        let FriConfig {
+            log_last_layer_degree_bound,
+            log_blowup_factor,
+            n_queries,
+        } = config.fri_config;
+        let more_queries = PcsConfig {
+            fri_config: FriConfig::new(
+                log_last_layer_degree_bound,
+                log_blowup_factor,
+                2 * n_queries,
+            ),
+            ..config
+        };
+        let cost_more_queries =
+            Machine::<BaseComponent>::verification_cost(&[], &proof.log_size, &more_queries)
+                .unwrap();
+        assert_eq!(cost_more_queries.fri_layers, cost.fri_layers);
+        assert_eq!(
+            cost_more_queries.blake2s_compressions - 1,
+            2 * (cost.blake2s_compressions - 1)
+        );
+        assert_eq!(
+            cost_more_queries.qm31_multiplications,
+            2 * cost.qm31_multiplications
+        );
+
+        let larger_last_layer = PcsConfig {
+            fri_config: FriConfig::new(
+                log_last_layer_degree_bound + 1,
+                log_blowup_factor,
+                n_queries,
+            ),
+            ..config
+        };
+        let cost_larger_last_layer =
+            Machine::<BaseComponent>::verification_cost(&[], &proof.log_size, &larger_last_layer)
+                .unwrap();
+        assert_eq!(cost_larger_last_layer.fri_layers, cost.fri_layers - 1);
+        assert!(cost_larger_last_layer.blake2s_compressions < cost.blake2s_compressions);
+
+        for log_sizes in [&[][..], &proof.log_size[1..]] {
+            assert!(matches!(
+                Machine::<BaseComponent>::verification_cost(&[], log_sizes, &config),
+                Err(VerificationError::Stwo(
+                    StwoVerificationError::InvalidStructure(_)
+                ))
+            ));
+        }
+    }
+
+    #[test]
+    fn layout_matches_committed_traces() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let bundle = Machine::<BaseComponent>::build_traces(&[], &program_trace, &view).unwrap();
+        let log_size = bundle.all_log_sizes[0];
+
+        let layout = Machine::<BaseComponent>::layout(log_size);
+        assert_eq!(layout.main_width(), GlobalColumn::COLUMNS_NUM);
+        assert_eq!(layout.program_width(), ProgramColumn::COLUMNS_NUM);
+        assert_eq!(layout.preprocessed_width(), PreprocessedColumn::COLUMNS_NUM);
+        assert!(layout.interaction_width > 0);
+        assert_eq!(layout.interaction_width % SECURE_EXTENSION_DEGREE, 0);
+
+        // The machine component is committed first in every tree, followed by the extensions.
+        let bounds = layout.log_degree_bounds();
+        let preprocessed_evals = preprocessed_evals(&[], &bundle.all_log_sizes);
+        for (evals, tree_idx) in [
+            (&preprocessed_evals, PREPROCESSED_TRACE_IDX),
+            (&bundle.main_evals, ORIGINAL_TRACE_IDX),
+            (&bundle.program_evals, PROGRAM_TRACE_IDX),
+        ] {
+            let machine_log_sizes: Vec<u32> = evals[..bounds[tree_idx].len()]
+                .iter()
+                .map(|eval| eval.domain.log_size())
+                .collect();
+            assert_eq!(machine_log_sizes, bounds[tree_idx]);
+        }
+        let committed = Machine::<BaseComponent>::committed_log_sizes(&[], &bundle.all_log_sizes);
+        for tree_idx in [
+            PREPROCESSED_TRACE_IDX,
+            ORIGINAL_TRACE_IDX,
+            INTERACTION_TRACE_IDX,
+            PROGRAM_TRACE_IDX,
+        ] {
+            assert_eq!(
+                committed[tree_idx][..bounds[tree_idx].len()],
+                bounds[tree_idx]
+            );
+        }
+        assert_eq!(
+            committed[PREPROCESSED_TRACE_IDX].len(),
+            preprocessed_evals.len()
+        );
+        assert_eq!(committed[ORIGINAL_TRACE_IDX].len(), bundle.main_evals.len());
+        assert_eq!(
+            committed[PROGRAM_TRACE_IDX].len(),
+            bundle.program_evals.len()
+        );
+
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        crate::verify(proof, &view).unwrap();
+    }
+
+    #[test]
+    fn chip_descriptions_serialize() {
+        let descriptions = Machine::<BaseComponent>::chip_descriptions();
+        let chips: Vec<_> = descriptions
+            .iter()
+            .map(|description| description.chip)
+            .collect();
+        assert_eq!(chips, ["CpuChip", "AddChip", "RegisterMemCheckChip"]);
+
+        let json = serde_json::to_value(&descriptions).unwrap();
+        assert_eq!(json[1]["constraints"][0]["id"], "sum_low");
+        assert_eq!(json[2]["lookups"][0]["tuple"].as_array().unwrap().len(), 9);
     }
 }
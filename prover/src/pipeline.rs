@@ -0,0 +1,169 @@
+//! Pipelined proving of multiple executions.
+//!
+//! Proving consists of three stages: [`Machine::build_traces`], [`Machine::commit_and_interact`] and
+//! [`Machine::finish_proof`]. [`ProvingPipeline`] runs each stage on its own thread, so that traces of the next job
+//! are generated while the previous one is being committed to and proved. Proofs are identical to the ones produced
+//! by [`Machine::prove_with_extensions`].
+
+use std::{marker::PhantomData, thread};
+
+use crossbeam_channel::bounded;
+use nexus_vm::{emulator::View, trace::Trace};
+
+use crate::{
+    error::NexusProvingError,
+    extensions::ExtensionComponent,
+    machine::{BaseComponent, Machine, Proof, ProverContext},
+    traits::MachineChip,
+};
+
+/// Proves a stream of `(trace, view)` jobs, yielding proofs in job order.
+pub struct ProvingPipeline<C = BaseComponent> {
+    extensions: Vec<ExtensionComponent>,
+    context: ProverContext,
+    _phantom_data: PhantomData<C>,
+}
+
+impl<C: MachineChip + Sync> ProvingPipeline<C> {
+    /// Creates a pipeline for jobs with traces of log size up to `max_log_size`.
+    ///
+    /// Jobs exceeding `max_log_size` fail with [`NexusProvingError::TraceTooLarge`].
+    pub fn new(extensions: &[ExtensionComponent], max_log_size: u32) -> Self {
+        Self {
+            extensions: extensions.to_vec(),
+            context: ProverContext::new(max_log_size),
+            _phantom_data: PhantomData,
+        }
+    }
+
+    /// Proves every job and passes the results to `sink` in job order.
+    ///
+    /// A failed job doesn't stop the pipeline, its error is passed to `sink` in place of the proof.
+    pub fn for_each_proof<T, I, F>(&self, jobs: I, mut sink: F)
+    where
+        T: Trace + Send,
+        I: IntoIterator<Item = (T, View)>,
+        I::IntoIter: Send,
+        F: FnMut(Result<Proof, NexusProvingError>),
+    {
+        let jobs = jobs.into_iter();
+        // Bounded channels keep at most one job buffered between stages, so that memory use stays proportional to
+        // the number of stages rather than the number of jobs.
+        let (bundle_tx, bundle_rx) = bounded(1);
+        let (committed_tx, committed_rx) = bounded(1);
+
+        thread::scope(|scope| {
+            scope.spawn(move || {
+                for (trace, view) in jobs {
+                    let bundle = Machine::<C>::build_traces(&self.extensions, &trace, &view);
+                    if bundle_tx.send(bundle).is_err() {
+                        break;
+                    }
+                }
+            });
+            scope.spawn(move || {
+                for bundle in bundle_rx {
+                    let committed = bundle.and_then(|bundle| {
+                        Machine::<C>::commit_and_interact(bundle, &self.context)
+                    });
+                    if committed_tx.send(committed).is_err() {
+                        break;
+                    }
+                }
+            });
+
+            for committed in committed_rx {
+                sink(committed.and_then(Machine::<C>::finish_proof));
+            }
+        });
+    }
+
+    /// Proves every job, returning the results in job order.
+    pub fn prove_all<T, I>(&self, jobs: I) -> Vec<Result<Proof, NexusProvingError>>
+    where
+        T: Trace + Send,
+        I: IntoIterator<Item = (T, View)>,
+        I::IntoIter: Send,
+    {
+        let mut proofs = Vec::new();
+        self.for_each_proof(jobs, |proof| proofs.push(proof));
+        proofs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::machine::{CommittedBundle, TraceBundle};
+    use nexus_vm::{
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+        trace::k_trace_direct,
+    };
+
+    fn assert_send<T: Send>() {}
+
+    #[test]
+    fn stages_are_send() {
+        assert_send::<TraceBundle>();
+        assert_send::<CommittedBundle<'static>>();
+    }
+
+    fn add_chain(len: u32) -> Vec<BasicBlock> {
+        let mut instructions = vec![Instruction::new_ir(
+            Opcode::from(BuiltinOpcode::ADDI),
+            1,
+            0,
+            1,
+        )];
+        instructions.extend((0..len).map(|i| {
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2 + i % 8, 1 + i % 8, 1)
+        }));
+        vec![BasicBlock::new(instructions)]
+    }
+
+    #[test]
+    fn pipeline_proofs_in_order() {
+        let jobs: Vec<_> = [4, 40, 400]
+            .into_iter()
+            .map(|len| k_trace_direct(&add_chain(len), 1).expect("error generating trace"))
+            .map(|(view, trace)| (trace, view))
+            .collect();
+        let expected: Vec<_> = jobs
+            .iter()
+            .map(|(trace, view)| Machine::<BaseComponent>::prove(trace, view).unwrap())
+            .collect();
+        let max_log_size = expected
+            .iter()
+            .flat_map(|proof| proof.log_size.iter().copied())
+            .max()
+            .unwrap();
+
+        let pipeline = ProvingPipeline::<BaseComponent>::new(&[], max_log_size);
+        let proofs = pipeline.prove_all(jobs.clone());
+        assert_eq!(proofs.len(), expected.len());
+
+        for ((proof, expected), (_, view)) in proofs.into_iter().zip(expected).zip(&jobs) {
+            let proof = proof.unwrap();
+            assert_eq!(
+                postcard::to_stdvec(&proof).unwrap(),
+                postcard::to_stdvec(&expected).unwrap()
+            );
+            crate::verify(proof, view).unwrap();
+        }
+    }
+
+    #[test]
+    fn pipeline_rejects_oversized_job() {
+        let (view, trace) = k_trace_direct(&add_chain(4), 1).expect("error generating trace");
+        let pipeline = ProvingPipeline::<BaseComponent>::new(&[], 1);
+        let proofs = pipeline.prove_all([(trace, view)]);
+
+        assert!(matches!(
+            proofs.as_slice(),
+            [Err(NexusProvingError::TraceTooLarge {
+                max_log_size: 1,
+                ..
+            })]
+        ));
+    }
+}
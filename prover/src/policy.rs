@@ -0,0 +1,348 @@
+//! Verification under a fixed policy, for verifiers whose costs must be known ahead of time.
+//!
+//! A [`VerifierPolicy`] restricts the log sizes, the security level and the public output of the proofs a verifier
+//! accepts. A verifier given the policy through [`VerifierOptions`](crate::VerifierOptions) rejects proofs outside
+//! of it before doing any work proportional to the size of the proof.
+//!
+//! [`VerifierArtifacts`] additionally hold the layout of the machine component and the root of the preprocessed tree
+//! at every allowed log size, which the verifier uses instead of deriving the layout from the AIR and committing to
+//! the preprocessed trace. They serialize, so that they can be generated offline and embedded in the verifier. The
+//! commitment to the program tree isn't part of them, it depends on the statement, see
+//! [`PROGRAM_TRACE_IDX`](crate::trace::eval::PROGRAM_TRACE_IDX).
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use stwo::core::{
+    pcs::TreeVec, vcs::blake2_hash::Blake2sHash,
+    verifier::VerificationError as StwoVerificationError,
+};
+
+use crate::{
+    error::VerificationError,
+    machine::{base_preprocessed_root, Machine, Proof},
+    traits::MachineChip,
+};
+
+/// Limits on the proofs accepted by a verifier, see [`VerifierOptions`](crate::VerifierOptions).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifierPolicy {
+    /// Log sizes of the main trace, [`Proof::log_size`]`[0]`, the verifier accepts.
+    pub allowed_log_sizes: Vec<u32>,
+    /// Minimum conjectured security level, see [`Proof::security_report`].
+    pub min_security_bits: u32,
+    /// Maximum number of bytes of public output.
+    pub max_public_output_len: usize,
+}
+
+impl VerifierPolicy {
+    /// Checks the proof and the length of its public output against the policy, without verifying the proof.
+    pub fn check(&self, proof: &Proof, public_output_len: usize) -> Result<(), VerificationError> {
+        let &log_size = proof.log_size.first().ok_or_else(|| {
+            StwoVerificationError::InvalidStructure("log size len mismatch".to_string())
+        })?;
+        if !self.allowed_log_sizes.contains(&log_size) {
+            return Err(VerificationError::LogSizeNotAllowed {
+                log_size,
+                allowed: self.allowed_log_sizes.clone(),
+            });
+        }
+        let conjectured_bits = proof.security_report().conjectured_bits;
+        if conjectured_bits < self.min_security_bits {
+            return Err(VerificationError::InsufficientSecurity {
+                conjectured_bits,
+                min_bits: self.min_security_bits,
+            });
+        }
+        if public_output_len > self.max_public_output_len {
+            return Err(VerificationError::PublicOutputTooLong {
+                len: public_output_len,
+                max: self.max_public_output_len,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Widths of the trees committed to by the machine component, see [`MachineLayout`](crate::MachineLayout), and the
+/// root of the preprocessed tree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LayoutArtifact {
+    pub preprocessed_width: usize,
+    pub main_width: usize,
+    pub interaction_width: usize,
+    pub program_width: usize,
+    /// Root of the preprocessed tree, committed to with the machine component and the base extensions.
+    pub preprocessed_root: Blake2sHash,
+}
+
+impl LayoutArtifact {
+    /// Log degree bounds of the columns committed to by the machine component at `log_size` by tree, as in
+    /// [`MachineLayout::log_degree_bounds`](crate::MachineLayout::log_degree_bounds).
+    pub(crate) fn log_degree_bounds(&self, log_size: u32) -> TreeVec<Vec<u32>> {
+        TreeVec::new(vec![
+            vec![log_size; self.preprocessed_width],
+            vec![log_size; self.main_width],
+            vec![log_size; self.interaction_width],
+            vec![log_size; self.program_width],
+        ])
+    }
+}
+
+/// Verifier data precomputed for the log sizes allowed by a policy, see the [module documentation](self).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifierArtifacts {
+    pub policy: VerifierPolicy,
+    /// Names of the chips the artifacts were computed for, in order, see
+    /// [`MachineChip::NAME`](crate::traits::MachineChip::NAME).
+    ///
+    /// Names identify the constraints of the chips and only change with them, unlike [`Machine::components_id`]
+    /// which also changes with the names of columns and relations, which don't affect the layout.
+    pub chips: Vec<String>,
+    /// Layout of the machine component at every allowed log size.
+    pub layouts: BTreeMap<u32, LayoutArtifact>,
+}
+
+impl VerifierArtifacts {
+    /// Computes the artifacts of the machine with components `C`, without extensions, for every log size allowed
+    /// by `policy`.
+    pub fn precompute<C: MachineChip + Sync>(policy: VerifierPolicy) -> Self {
+        let layouts = policy
+            .allowed_log_sizes
+            .iter()
+            .map(|&log_size| {
+                let layout = Machine::<C>::layout(log_size);
+                let artifact = LayoutArtifact {
+                    preprocessed_width: layout.preprocessed_width(),
+                    main_width: layout.main_width(),
+                    interaction_width: layout.interaction_width,
+                    program_width: layout.program_width(),
+                    preprocessed_root: base_preprocessed_root(log_size),
+                };
+                (log_size, artifact)
+            })
+            .collect();
+        Self {
+            policy,
+            chips: chip_names::<C>(),
+            layouts,
+        }
+    }
+
+    /// Returns whether the artifacts were computed for the chips `C`.
+    pub fn matches<C: MachineChip>(&self) -> bool {
+        self.chips == chip_names::<C>()
+    }
+}
+
+fn chip_names<C: MachineChip>() -> Vec<String> {
+    let mut names = Vec::new();
+    C::collect_names(&mut names);
+    names.into_iter().map(str::to_string).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use nexus_vm::{
+        emulator::{InternalView, MemoryInitializationEntry, PublicOutputEntry, View},
+        riscv::builder::ProgramBuilder,
+        trace::{k_trace_direct, UniformTrace},
+    };
+
+    use super::*;
+    use crate::{
+        commitment::hashed_nodes,
+        machine::{BaseComponent, VerifierOptions},
+        trace::{eval::PREPROCESSED_TRACE_IDX, PreprocessedTraces},
+    };
+
+    const MIN_LOG_SIZE: u32 = PreprocessedTraces::MIN_LOG_SIZE;
+
+    fn policy() -> VerifierPolicy {
+        VerifierPolicy {
+            allowed_log_sizes: vec![MIN_LOG_SIZE, MIN_LOG_SIZE + 1, MIN_LOG_SIZE + 2],
+            min_security_bits: 0,
+            max_public_output_len: 0,
+        }
+    }
+
+    /// Executes 2^(log_size - 1) + 1 steps, for a main trace of `log_size`.
+    fn trace(log_size: u32) -> (View, UniformTrace) {
+        let blocks = ProgramBuilder::new()
+            .li(2, 1 << (log_size - 2))
+            .label("loop")
+            .addi(1, 1, 1)
+            .bne(1, 2, "loop")
+            .build()
+            .unwrap();
+        k_trace_direct(&blocks, 1).unwrap()
+    }
+
+    fn init_memory(view: &View) -> Vec<MemoryInitializationEntry> {
+        [
+            view.get_ro_initial_memory(),
+            view.get_rw_initial_memory(),
+            view.get_public_input(),
+        ]
+        .concat()
+    }
+
+    fn verify(
+        artifacts: &VerifierArtifacts,
+        proof: Proof,
+        view: &View,
+        output_memory: &[PublicOutputEntry],
+    ) -> Result<(), VerificationError> {
+        Machine::<BaseComponent>::verify_with_options(
+            &VerifierOptions::default().with_artifacts(artifacts.clone()),
+            proof,
+            view.get_program_memory(),
+            &[],
+            &init_memory(view),
+            view.get_exit_code(),
+            output_memory,
+        )
+    }
+
+    #[test]
+    fn accepts_proofs_at_allowed_log_sizes() {
+        let artifacts = VerifierArtifacts::precompute::<BaseComponent>(policy());
+        for log_size in policy().allowed_log_sizes {
+            let layout = Machine::<BaseComponent>::layout(log_size);
+            assert_eq!(
+                artifacts.layouts[&log_size].log_degree_bounds(log_size),
+                layout.log_degree_bounds()
+            );
+
+            let (view, trace) = trace(log_size);
+            let proof = Machine::<BaseComponent>::prove(&trace, &view).unwrap();
+            assert_eq!(proof.log_size[0], log_size);
+            assert_eq!(
+                artifacts.layouts[&log_size].preprocessed_root,
+                proof.stark_proof.commitments[PREPROCESSED_TRACE_IDX]
+            );
+            Machine::<BaseComponent>::verify_with_options(
+                &VerifierOptions::default().with_policy(artifacts.policy.clone()),
+                proof.clone(),
+                view.get_program_memory(),
+                &[],
+                &init_memory(&view),
+                view.get_exit_code(),
+                view.get_public_output(),
+            )
+            .unwrap();
+            verify(&artifacts, proof, &view, view.get_public_output()).unwrap();
+        }
+    }
+
+    #[test]
+    fn rejects_proofs_outside_of_policy() {
+        let (view, trace) = trace(MIN_LOG_SIZE + 3);
+        let proof = Machine::<BaseComponent>::prove(&trace, &view).unwrap();
+        let artifacts = VerifierArtifacts::precompute::<BaseComponent>(policy());
+        assert!(matches!(
+            verify(&artifacts, proof, &view, &[]),
+            Err(VerificationError::LogSizeNotAllowed { log_size, allowed })
+                if log_size == MIN_LOG_SIZE + 3 && allowed == policy().allowed_log_sizes
+        ));
+
+        let (view, trace) = trace(MIN_LOG_SIZE);
+        let proof = Machine::<BaseComponent>::prove(&trace, &view).unwrap();
+        let conjectured_bits = proof.security_report().conjectured_bits;
+        let artifacts = VerifierArtifacts::precompute::<BaseComponent>(VerifierPolicy {
+            min_security_bits: conjectured_bits + 1,
+            ..policy()
+        });
+        assert!(matches!(
+            verify(&artifacts, proof.clone(), &view, &[]),
+            Err(VerificationError::InsufficientSecurity { conjectured_bits: bits, .. })
+                if bits == conjectured_bits
+        ));
+
+        // Rejected for its length, before the output is compared with the proof.
+        let artifacts = VerifierArtifacts::precompute::<BaseComponent>(policy());
+        let output = [PublicOutputEntry {
+            address: 0x1000,
+            value: 1,
+        }];
+        assert!(matches!(
+            verify(&artifacts, proof.clone(), &view, &output),
+            Err(VerificationError::PublicOutputTooLong { len: 1, max: 0 })
+        ));
+
+        // Artifacts of another machine, or missing the layout of an allowed log size.
+        let mut foreign = artifacts.clone();
+        foreign.chips.pop();
+        assert!(matches!(
+            verify(&foreign, proof.clone(), &view, &[]),
+            Err(VerificationError::ArtifactsMismatch)
+        ));
+        let mut incomplete = artifacts;
+        incomplete.layouts.remove(&MIN_LOG_SIZE);
+        assert!(matches!(
+            verify(&incomplete, proof, &view, &[]),
+            Err(VerificationError::ArtifactsMismatch)
+        ));
+    }
+
+    #[test]
+    fn preprocessed_tree_is_not_committed_with_artifacts() {
+        let (view, trace) = trace(MIN_LOG_SIZE);
+        let proof = Machine::<BaseComponent>::prove(&trace, &view).unwrap();
+        let artifacts = VerifierArtifacts::precompute::<BaseComponent>(policy());
+
+        let before = hashed_nodes();
+        base_preprocessed_root(MIN_LOG_SIZE);
+        let preprocessed = hashed_nodes() - before;
+        assert!(preprocessed > 0);
+
+        let before = hashed_nodes();
+        Machine::<BaseComponent>::verify_with_options(
+            &VerifierOptions::default(),
+            proof.clone(),
+            view.get_program_memory(),
+            &[],
+            &init_memory(&view),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap();
+        let without_artifacts = hashed_nodes() - before;
+
+        // Only the program tree is committed to.
+        let before = hashed_nodes();
+        verify(&artifacts, proof.clone(), &view, view.get_public_output()).unwrap();
+        let with_artifacts = hashed_nodes() - before;
+        assert_eq!(without_artifacts - with_artifacts, preprocessed);
+
+        // The proof is checked against the stored root.
+        let mut tampered = artifacts;
+        tampered
+            .layouts
+            .get_mut(&MIN_LOG_SIZE)
+            .unwrap()
+            .preprocessed_root = Blake2sHash::default();
+        assert!(matches!(
+            verify(&tampered, proof, &view, view.get_public_output()),
+            Err(VerificationError::Stwo(StwoVerificationError::InvalidStructure(msg)))
+                if msg.contains("preprocessed trace")
+        ));
+    }
+
+    #[test]
+    fn artifacts_serialize() {
+        let artifacts = VerifierArtifacts::precompute::<BaseComponent>(policy());
+        assert_eq!(artifacts.layouts.len(), 3);
+
+        let json = serde_json::to_string(&artifacts).unwrap();
+        assert_eq!(
+            serde_json::from_str::<VerifierArtifacts>(&json).unwrap(),
+            artifacts
+        );
+        let bytes = postcard::to_stdvec(&artifacts).unwrap();
+        assert_eq!(
+            postcard::from_bytes::<VerifierArtifacts>(&bytes).unwrap(),
+            artifacts
+        );
+    }
+}
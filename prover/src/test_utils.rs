@@ -1,168 +1,103 @@
-use stwo::{
-    core::{
-        channel::Blake2sChannel,
-        fields::{m31::BaseField, qm31::SecureField},
-        pcs::{PcsConfig, TreeVec},
-        poly::circle::CanonicCoset,
-        vcs::blake2_merkle::Blake2sMerkleChannel,
-    },
-    prover::{
-        backend::simd::SimdBackend,
-        poly::{
-            circle::{CircleEvaluation, PolyOps},
-            BitReversedOrder,
-        },
-        CommitmentSchemeProver,
-    },
+use nexus_vm::{
+    emulator::InternalView,
+    riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+    trace::{k_trace_direct, Trace},
 };
-use stwo_constraint_framework::{assert_constraints_on_polys, EvalAtRow};
 
 use crate::{
-    components::{AllLookupElements, LOG_CONSTRAINT_DEGREE},
+    chips::{
+        CpuChip, DecodingCheckChip, ProgramMemCheckChip, RangeCheckChip, RegisterMemCheckChip,
+        TimestampChip,
+    },
     extensions::ExtensionsConfig,
-    trace::{program_trace::ProgramTracesBuilder, FinalizedTraces, PreprocessedTraces},
-    traits::generate_interaction_trace,
-};
-
-use super::{
-    trace::{eval::TraceEval, program_trace::ProgramTraces, TracesBuilder},
+    trace::{
+        program::iter_program_steps, program_trace::ProgramTracesBuilder, sidenote::SideNote,
+        PreprocessedTraces, TracesBuilder,
+    },
     traits::MachineChip,
 };
 
-pub(crate) fn test_params(
-    log_size: u32,
-) -> (
-    PcsConfig,
-    stwo::prover::poly::twiddles::TwiddleTree<SimdBackend>,
-) {
-    let config = PcsConfig::default();
-    let twiddles = SimdBackend::precompute_twiddles(
-        CanonicCoset::new(log_size + config.fri_config.log_blowup_factor + LOG_CONSTRAINT_DEGREE)
-            .circle_domain()
-            .half_coset,
-    );
-    (config, twiddles)
-}
-
-/// Filled out traces, mainly for testing
-pub(crate) struct CommittedTraces<'a> {
-    pub(crate) commitment_scheme: CommitmentSchemeProver<'a, SimdBackend, Blake2sMerkleChannel>,
-    pub(crate) prover_channel: Blake2sChannel,
-    pub(crate) lookup_elements: AllLookupElements,
-    pub(crate) preprocessed_trace: PreprocessedTraces,
-    pub(crate) interaction_trace: Vec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
-    pub(crate) claimed_sum: SecureField,
-    pub(crate) program_trace: ProgramTraces,
-}
+pub(crate) use crate::testing::{
+    assert_chip, check_chip, commit_traces, prove_worst_case, synthesize_worst_case_trace,
+    test_params, CommittedTraces,
+};
 
-/// Testing utility for filling in traces
-pub(crate) fn commit_traces<'a, C: MachineChip>(
-    config: PcsConfig,
-    twiddles: &'a stwo::prover::poly::twiddles::TwiddleTree<SimdBackend>,
-    traces: &FinalizedTraces,
-    program_traces: Option<ProgramTraces>,
-) -> CommittedTraces<'a> {
-    let mut commitment_scheme =
-        CommitmentSchemeProver::<_, Blake2sMerkleChannel>::new(config, twiddles);
-    let mut prover_channel = Blake2sChannel::default();
+/// Operand values around carries, sign bits and limb boundaries, see [`exhaustive_chip_test`].
+pub(crate) const SMALL_DOMAIN: &[u32] = &[
+    0,
+    1,
+    2,
+    0x7F,
+    0x80,
+    0xFF,
+    0xFFFF,
+    0x7FFF_FFFF,
+    0x8000_0000,
+    0xFFFF_FFFF,
+];
 
-    let program_trace =
-        program_traces.unwrap_or_else(|| ProgramTracesBuilder::dummy(traces.log_size()).finalize());
-    // Preprocessed trace
-    let preprocessed_trace = PreprocessedTraces::new(traces.log_size());
-    let mut tree_builder = commitment_scheme.tree_builder();
-    let _preprocessed_trace_location = tree_builder.extend_evals(
-        preprocessed_trace
-            .clone()
-            .into_circle_evaluation()
-            .into_iter()
-            .chain(program_trace.clone().into_circle_evaluation()),
-    );
-    tree_builder.commit(&mut prover_channel);
+/// Shift amounts of 32 and more, of which register-sourced shifts only use the low 5 bits, with a value to shift.
+pub(crate) const LARGE_SHIFT_DOMAIN: &[u32] = &[0x8765_4321, 32, 33, 63, 255];
 
-    // Original trace
-    let mut tree_builder = commitment_scheme.tree_builder();
-    let _main_trace_location = tree_builder.extend_evals(traces.clone().into_circle_evaluation());
-    tree_builder.commit(&mut prover_channel);
-    let mut all_elements = AllLookupElements::default();
-    C::draw_lookup_elements(
-        &mut all_elements,
-        &mut prover_channel,
-        &ExtensionsConfig::default(),
+/// Asserts the constraints of chips `C` on the R-type `opcode` applied to every pair of operands from `domain`.
+///
+/// Operands are loaded into `x1` and `x2` with `lui` and `addi`, therefore `C` must contain [`AddChip`] and
+/// [`LuiChip`] besides the chips constraining `opcode`. Results are computed by the emulator, which serves as the
+/// ground truth. The CPU, decoding, memory check and range check chips are added around `C`, all other chips are
+/// left out so that their selectors stay off.
+///
+/// [`AddChip`]: crate::chips::AddChip
+/// [`LuiChip`]: crate::chips::LuiChip
+pub(crate) fn exhaustive_chip_test<C: MachineChip>(opcode: BuiltinOpcode, domain: &[u32]) {
+    type Chips<C> = (
+        CpuChip,
+        DecodingCheckChip,
+        C,
+        RegisterMemCheckChip,
+        ProgramMemCheckChip,
+        TimestampChip,
+        RangeCheckChip,
     );
 
-    // Interaction Trace
-    let (interaction_trace, claimed_sum) =
-        generate_interaction_trace::<C>(traces, &preprocessed_trace, &program_trace, &all_elements);
-    let mut tree_builder = commitment_scheme.tree_builder();
-    let _interaction_trace_location = tree_builder.extend_evals(interaction_trace.clone());
-    tree_builder.commit(&mut prover_channel);
-
-    CommittedTraces {
-        commitment_scheme,
-        prover_channel,
-        lookup_elements: all_elements,
-        preprocessed_trace,
-        interaction_trace,
-        claimed_sum,
-        program_trace,
-    }
-}
-
-/// Assuming traces are filled, assert constraints
-pub(crate) fn assert_chip<C: MachineChip>(
-    traces: TracesBuilder,
-    program_trace: Option<ProgramTraces>,
-) -> (AllLookupElements, SecureField) {
-    let (config, twiddles) = test_params(traces.log_size());
-
-    let finalized_trace = traces.finalize();
-    let log_size = finalized_trace.log_size();
-
-    let CommittedTraces {
-        commitment_scheme: _,
-        prover_channel: _,
-        lookup_elements,
-        preprocessed_trace,
-        interaction_trace,
-        claimed_sum,
-        program_trace,
-    } = commit_traces::<C>(config, &twiddles, &finalized_trace, program_trace);
-
-    let trace_evals = TreeVec::new(vec![
+    let load = |reg: u8, value: u32| {
+        // addi sign-extends its immediate, the upper part compensates for it.
+        let upper = value.wrapping_add(0x800) >> 12;
         [
-            preprocessed_trace.into_circle_evaluation(),
-            program_trace.into_circle_evaluation(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LUI), reg, 0, upper),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), reg, reg, value & 0xFFF),
         ]
-        .concat(),
-        finalized_trace.into_circle_evaluation(),
-        interaction_trace,
-    ]);
-    let trace_polys = trace_evals.map(|trace| {
-        trace
-            .into_iter()
-            .map(|c| c.interpolate())
-            .collect::<Vec<_>>()
-    });
+    };
+    let mut instructions = Vec::new();
+    for &b in domain {
+        instructions.extend(load(1, b));
+        for &c in domain {
+            instructions.extend(load(2, c));
+            instructions.push(Instruction::new_ir(Opcode::from(opcode), 3, 1, 2));
+        }
+    }
 
-    // Now check the constraints to make sure they're satisfied
-    assert_constraints_on_polys(
-        &trace_polys,
-        CanonicCoset::new(log_size),
-        |mut eval| {
-            let trace_eval = TraceEval::new(&mut eval);
-            C::add_constraints(
-                &mut eval,
-                &trace_eval,
-                &lookup_elements,
-                &ExtensionsConfig::default(),
-            );
+    let (view, vm_traces) =
+        k_trace_direct(&vec![BasicBlock::new(instructions)], 1).expect("Failed to create trace");
+    let program_info = view.get_program_memory();
 
-            if !lookup_elements.is_empty() {
-                eval.finalize_logup();
-            }
-        },
-        claimed_sum,
-    );
-    (lookup_elements, claimed_sum)
+    let log_size = vm_traces
+        .get_num_steps()
+        .next_power_of_two()
+        .trailing_zeros()
+        .max(PreprocessedTraces::MIN_LOG_SIZE);
+    let mut traces = TracesBuilder::new(log_size);
+    let program_steps = iter_program_steps(&vm_traces, traces.num_rows());
+    let program_trace = ProgramTracesBuilder::new_with_empty_memory(log_size, program_info);
+    let mut side_note = SideNote::new(&program_trace, &view);
+
+    for (row_idx, program_step) in program_steps.enumerate() {
+        Chips::<C>::fill_main_trace(
+            &mut traces,
+            row_idx,
+            &program_step,
+            &mut side_note,
+            &ExtensionsConfig::default(),
+        );
+    }
+    assert_chip::<Chips<C>>(traces, Some(program_trace.finalize()));
 }
@@ -0,0 +1,504 @@
+//! Utilities for unit testing chips, enabled with the `testing` feature.
+//!
+//! A chip is tested by filling the main trace of a short program with [`fill_traces`] and checking its constraints
+//! on every row with [`assert_chip`], which panics on the first violated constraint. Tests of tampered traces use
+//! [`check_chip`] instead, which reports the violated constraint. [`commit_traces`] stops after
+//! committing to the traces, for tests inspecting the interaction trace or the lookup elements.
+//!
+//! [`synthesize_worst_case_trace`] generates an execution that exercises every instruction chip on adjacent rows,
+//! and [`prove_worst_case`] proves it to measure the memory a prover needs, see [`PeakAllocator`].
+//!
+//! ```rust
+//! use nexus_vm::riscv::{BasicBlock, Instruction};
+//! use nexus_vm_prover::{
+//!     column::Column,
+//!     components::AllLookupElements,
+//!     extensions::ExtensionsConfig,
+//!     testing::{assert_chip, fill_traces},
+//!     trace::{eval::TraceEval, sidenote::SideNote, PreprocessedTraces, ProgramStep, TracesBuilder},
+//!     traits::MachineChip,
+//! };
+//! use num_traits::One;
+//! use stwo::core::fields::m31::BaseField;
+//! use stwo_constraint_framework::EvalAtRow;
+//!
+//! /// Sets `ImmC` on executed rows, which is boolean.
+//! struct ImmCChip;
+//!
+//! impl MachineChip for ImmCChip {
+//!     const NAME: &'static str = "ImmCChip";
+//!
+//!     fn fill_main_trace(
+//!         traces: &mut TracesBuilder,
+//!         row_idx: usize,
+//!         vm_step: &Option<ProgramStep>,
+//!         _side_note: &mut SideNote,
+//!         _config: &ExtensionsConfig,
+//!     ) {
+//!         if vm_step.is_some() {
+//!             let [imm_c] = traces.column_mut(row_idx, Column::ImmC);
+//!             *imm_c = BaseField::one();
+//!         }
+//!     }
+//!
+//!     fn add_constraints<E: EvalAtRow>(
+//!         eval: &mut E,
+//!         trace_eval: &TraceEval<E>,
+//!         _lookup_elements: &AllLookupElements,
+//!         _config: &ExtensionsConfig,
+//!     ) {
+//!         let [imm_c] = trace_eval.column_eval(Column::ImmC);
+//!         eval.add_constraint(imm_c.clone() * (E::F::one() - imm_c));
+//!     }
+//! }
+//!
+//! let basic_blocks = vec![BasicBlock::new(vec![Instruction::nop(); 4])];
+//! let (traces, program_trace) =
+//!     fill_traces::<ImmCChip>(&basic_blocks, PreprocessedTraces::MIN_LOG_SIZE);
+//! assert_chip::<ImmCChip>(traces, Some(program_trace));
+//! ```
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use stwo::{
+    core::{
+        channel::Blake2sChannel,
+        fields::{m31::BaseField, qm31::SecureField},
+        pcs::{PcsConfig, TreeVec},
+        poly::circle::CanonicCoset,
+        vcs::blake2_merkle::Blake2sMerkleChannel,
+    },
+    prover::{
+        backend::simd::SimdBackend,
+        poly::{
+            circle::{CircleEvaluation, PolyOps},
+            twiddles::TwiddleTree,
+            BitReversedOrder,
+        },
+        CommitmentSchemeProver,
+    },
+};
+use stwo_constraint_framework::assert_constraints_on_polys;
+
+use nexus_vm::{
+    emulator::{InternalView, View},
+    riscv::{builder::ProgramBuilder, BasicBlock},
+    system::SyscallCode,
+    trace::{k_trace_direct, Trace, UniformTrace},
+};
+
+use crate::{
+    components::{AllLookupElements, LOG_CONSTRAINT_DEGREE},
+    error::TraceCheckError,
+    extensions::ExtensionsConfig,
+    machine::{committed_rows, BaseComponent, Machine},
+    trace::{
+        eval::TraceEval,
+        logup::finalize_logup,
+        program::iter_program_steps,
+        program_trace::{ProgramTraceRef, ProgramTraces, ProgramTracesBuilder},
+        sidenote::SideNote,
+        FinalizedTraces, PreprocessedTraces, TracesBuilder,
+    },
+    trace_check::check_constraints,
+    traits::{generate_interaction_trace, MachineChip},
+};
+
+/// Returns the default commitment scheme configuration, with twiddles for traces of `log_size`.
+pub fn test_params(log_size: u32) -> (PcsConfig, TwiddleTree<SimdBackend>) {
+    let config = PcsConfig::default();
+    let twiddles = SimdBackend::precompute_twiddles(
+        CanonicCoset::new(log_size + config.fri_config.log_blowup_factor + LOG_CONSTRAINT_DEGREE)
+            .circle_domain()
+            .half_coset,
+    );
+    (config, twiddles)
+}
+
+/// Traces committed to by [`commit_traces`], together with the state of the commitment scheme.
+pub struct CommittedTraces<'a> {
+    pub commitment_scheme: CommitmentSchemeProver<'a, SimdBackend, Blake2sMerkleChannel>,
+    pub prover_channel: Blake2sChannel,
+    pub lookup_elements: AllLookupElements,
+    pub preprocessed_trace: PreprocessedTraces,
+    pub interaction_trace: Vec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    pub claimed_sum: SecureField,
+    pub program_trace: ProgramTraces,
+}
+
+/// Fills the main trace of chips `C` with the execution of `basic_blocks`, one step per row.
+///
+/// The program memory is empty, apart from the instructions, and `log_size` must leave room for every step. The
+/// program trace records the number of steps.
+pub fn fill_traces<C: MachineChip>(
+    basic_blocks: &Vec<BasicBlock>,
+    log_size: u32,
+) -> (TracesBuilder, ProgramTraces) {
+    let (view, vm_traces) = k_trace_direct(basic_blocks, 1).expect("Failed to create trace");
+
+    let mut traces = TracesBuilder::new(log_size);
+    let program_steps = iter_program_steps(&vm_traces, traces.num_rows());
+    let program_trace = ProgramTracesBuilder::new(
+        log_size,
+        ProgramTraceRef {
+            num_steps: vm_traces.get_num_steps(),
+            ..ProgramTraceRef::new_with_empty_memory(view.get_program_memory())
+        },
+    );
+    let mut side_note = SideNote::new(&program_trace, &view);
+    for (row_idx, program_step) in program_steps.enumerate() {
+        C::fill_main_trace(
+            &mut traces,
+            row_idx,
+            &program_step,
+            &mut side_note,
+            &ExtensionsConfig::default(),
+        );
+    }
+    (traces, program_trace.finalize())
+}
+
+/// Commits to the preprocessed, main, interaction and program traces of chips `C`, in this order.
+///
+/// Without `program_traces`, an empty program of the same size is committed to.
+pub fn commit_traces<'a, C: MachineChip>(
+    config: PcsConfig,
+    twiddles: &'a TwiddleTree<SimdBackend>,
+    traces: &FinalizedTraces,
+    program_traces: Option<ProgramTraces>,
+) -> CommittedTraces<'a> {
+    let mut commitment_scheme =
+        CommitmentSchemeProver::<_, Blake2sMerkleChannel>::new(config, twiddles);
+    let mut prover_channel = Blake2sChannel::default();
+
+    let program_trace =
+        program_traces.unwrap_or_else(|| ProgramTracesBuilder::dummy(traces.log_size()).finalize());
+    // Preprocessed trace
+    let preprocessed_trace = PreprocessedTraces::new(traces.log_size());
+    let mut tree_builder = commitment_scheme.tree_builder();
+    let _preprocessed_trace_location =
+        tree_builder.extend_evals(preprocessed_trace.clone().into_circle_evaluation());
+    tree_builder.commit(&mut prover_channel);
+
+    // Original trace
+    let mut tree_builder = commitment_scheme.tree_builder();
+    let _main_trace_location = tree_builder.extend_evals(traces.clone().into_circle_evaluation());
+    tree_builder.commit(&mut prover_channel);
+    let mut all_elements = AllLookupElements::default();
+    C::draw_lookup_elements(
+        &mut all_elements,
+        &mut prover_channel,
+        &ExtensionsConfig::default(),
+    );
+
+    // Interaction Trace
+    let (interaction_trace, claimed_sum) =
+        generate_interaction_trace::<C>(traces, &preprocessed_trace, &program_trace, &all_elements);
+    let mut tree_builder = commitment_scheme.tree_builder();
+    let _interaction_trace_location = tree_builder.extend_evals(interaction_trace.clone());
+    tree_builder.commit(&mut prover_channel);
+
+    // Program trace
+    let mut tree_builder = commitment_scheme.tree_builder();
+    let _program_trace_location =
+        tree_builder.extend_evals(program_trace.clone().into_circle_evaluation());
+    tree_builder.commit(&mut prover_channel);
+
+    CommittedTraces {
+        commitment_scheme,
+        prover_channel,
+        lookup_elements: all_elements,
+        preprocessed_trace,
+        interaction_trace,
+        claimed_sum,
+        program_trace,
+    }
+}
+
+/// Asserts the constraints of chips `C` on every row of the filled `traces`, panicking on the first violation.
+///
+/// Returns the lookup elements and the claimed sum of the interaction trace, which is zero if the chips balance
+/// all their lookups.
+pub fn assert_chip<C: MachineChip>(
+    traces: TracesBuilder,
+    program_trace: Option<ProgramTraces>,
+) -> (AllLookupElements, SecureField) {
+    let (config, twiddles) = test_params(traces.log_size());
+
+    let finalized_trace = traces.finalize();
+    let log_size = finalized_trace.log_size();
+
+    let CommittedTraces {
+        commitment_scheme: _,
+        prover_channel: _,
+        lookup_elements,
+        preprocessed_trace,
+        interaction_trace,
+        claimed_sum,
+        program_trace,
+    } = commit_traces::<C>(config, &twiddles, &finalized_trace, program_trace);
+
+    let trace_evals = TreeVec::new(vec![
+        preprocessed_trace.into_circle_evaluation(),
+        finalized_trace.into_circle_evaluation(),
+        interaction_trace,
+        program_trace.into_circle_evaluation(),
+    ]);
+    let trace_polys = trace_evals.map(|trace| {
+        trace
+            .into_iter()
+            .map(|c| c.interpolate())
+            .collect::<Vec<_>>()
+    });
+
+    // Now check the constraints to make sure they're satisfied
+    assert_constraints_on_polys(
+        &trace_polys,
+        CanonicCoset::new(log_size),
+        |mut eval| {
+            let trace_eval = TraceEval::new(&mut eval);
+            C::add_constraints(
+                &mut eval,
+                &trace_eval,
+                &lookup_elements,
+                &ExtensionsConfig::default(),
+            );
+
+            if !lookup_elements.is_empty() {
+                finalize_logup(&mut eval);
+            }
+        },
+        claimed_sum,
+    );
+    (lookup_elements, claimed_sum)
+}
+
+/// Checks the constraints of chips `C` on every row of the filled `traces` like [`assert_chip`], returning the first
+/// violation instead of panicking.
+///
+/// Tests tampering with a trace match on the returned error, so that they fail if the trace is rejected for another
+/// reason than the one they target.
+pub fn check_chip<C: MachineChip>(
+    traces: TracesBuilder,
+    program_trace: Option<ProgramTraces>,
+) -> Result<(), TraceCheckError> {
+    let finalized_trace = traces.finalize();
+    let log_size = finalized_trace.log_size();
+    let program_trace =
+        program_trace.unwrap_or_else(|| ProgramTracesBuilder::dummy(log_size).finalize());
+    let preprocessed_trace = PreprocessedTraces::new(log_size);
+
+    let mut lookup_elements = AllLookupElements::default();
+    C::draw_lookup_elements(
+        &mut lookup_elements,
+        &mut Blake2sChannel::default(),
+        &ExtensionsConfig::default(),
+    );
+    let (interaction_trace, claimed_sum) = generate_interaction_trace::<C>(
+        &finalized_trace,
+        &preprocessed_trace,
+        &program_trace,
+        &lookup_elements,
+    );
+
+    let trace = TreeVec::new(vec![
+        committed_rows(&preprocessed_trace.into_circle_evaluation(), log_size),
+        committed_rows(&finalized_trace.into_circle_evaluation(), log_size),
+        committed_rows(&interaction_trace, log_size),
+        committed_rows(&program_trace.into_circle_evaluation(), log_size),
+    ]);
+    check_constraints::<C>(
+        &trace,
+        log_size,
+        &lookup_elements,
+        claimed_sum,
+        &ExtensionsConfig::default(),
+    )
+}
+
+/// Appends one iteration of the worst-case loop, every instruction of which is executed.
+///
+/// Operands are derived from the loop counter in x31, so that they change from one iteration to the next and cross
+/// zero, signs and division by zero. Branches target the next instruction, whether they are taken or not.
+fn worst_case_iteration(builder: ProgramBuilder) -> ProgramBuilder {
+    builder
+        .addi(1, 31, -3)
+        .lui(2, 0x87654)
+        .xor(2, 2, 1)
+        .auipc(3, 1)
+        // Arithmetic and comparisons
+        .add(4, 1, 2)
+        .sub(4, 4, 3)
+        .slt(5, 1, 2)
+        .sltu(5, 2, 1)
+        .addi(6, 1, -1)
+        .slti(5, 1, 0)
+        .sltiu(5, 1, 1)
+        // Bit operations
+        .and(6, 1, 2)
+        .or(6, 6, 2)
+        .xor(6, 6, 1)
+        .andi(7, 2, 0x7F0)
+        .ori(7, 7, -0x100)
+        .xori(7, 1, 0x555)
+        // Shifts
+        .sll(8, 2, 1)
+        .srl(8, 2, 1)
+        .sra(8, 2, 1)
+        .slli(8, 1, 31)
+        .srli(8, 2, 4)
+        .srai(8, 2, 17)
+        // M extension
+        .mul(9, 1, 2)
+        .mulh(9, 1, 2)
+        .mulhsu(9, 2, 1)
+        .mulhu(9, 2, 1)
+        .div(9, 2, 1)
+        .divu(9, 2, 1)
+        .rem(9, 2, 1)
+        .remu(9, 2, 1)
+        // Stores and loads of every width, within the word at address 8
+        .sw(0, 2, 8)
+        .sh(0, 1, 12)
+        .sb(0, 4, 14)
+        .lw(10, 0, 8)
+        .lh(11, 0, 12)
+        .lhu(11, 0, 10)
+        .lb(12, 0, 14)
+        .lbu(12, 0, 9)
+        // Branches
+        .beq(1, 2, "beq")
+        .label("beq")
+        .bne(1, 2, "bne")
+        .label("bne")
+        .blt(1, 2, "blt")
+        .label("blt")
+        .bge(1, 2, "bge")
+        .label("bge")
+        .bltu(1, 2, "bltu")
+        .label("bltu")
+        .bgeu(1, 2, "bgeu")
+        .label("bgeu")
+        // Jumps, jalr returns to the instruction following it
+        .jal(13, "jal")
+        .label("jal")
+        .jalr(14, 13, 4)
+        // Syscall
+        .addi(17, 0, SyscallCode::ReadCycleCounter as i32)
+        .ecall()
+}
+
+/// Generates an execution of just under `2^log_size` steps exercising every instruction chip on every few rows.
+///
+/// A loop executes every RV32IM instruction, including loads and stores of every width, branches both taken and
+/// not taken, jumps and a syscall, so that the columns of all instruction chips are dense and all lookup tables are
+/// hit. Custom instructions, precompiles and fused address materialization are not exercised. The execution is an
+/// actual run of the emulator, and as such satisfies the constraints of the machine.
+///
+/// `log_size` must be at least [`PreprocessedTraces::MIN_LOG_SIZE`].
+pub fn synthesize_worst_case_trace(log_size: u32) -> (View, UniformTrace) {
+    assert!(log_size >= PreprocessedTraces::MIN_LOG_SIZE);
+    // x30 holds the number of iterations, which is set by a single instruction as long as it is small.
+    let program = |iterations: u32| {
+        worst_case_iteration(ProgramBuilder::new().li(30, iterations).label("loop"))
+            .addi(31, 31, 1)
+            .bne(31, 30, "loop")
+            .build()
+            .expect("worst-case program is valid")
+    };
+    let num_instructions =
+        |blocks: &[BasicBlock]| -> usize { blocks.iter().map(|b| b.0.len()).sum() };
+
+    let iteration_len = num_instructions(&program(1)) - 1;
+    // Two instructions for a large iteration count, and at least one padding row.
+    let iterations = ((1usize << log_size) - 3) / iteration_len;
+    let (view, trace) =
+        k_trace_direct(&program(iterations as u32), 1).expect("Failed to create trace");
+    debug_assert!(trace.get_num_steps() < 1 << log_size);
+    (view, trace)
+}
+
+/// Resources used by [`prove_worst_case`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorstCaseStats {
+    pub log_size: u32,
+    /// Number of executed steps, just under `2^log_size`.
+    pub num_steps: usize,
+    /// Time spent generating the trace and proving it.
+    pub duration: Duration,
+    /// Peak of heap allocations while generating and proving the trace, not counting the heap already allocated
+    /// when the measurement started. `None` unless [`PeakAllocator`] is the global allocator.
+    pub peak_heap_bytes: Option<usize>,
+}
+
+/// Proves and verifies the execution of [`synthesize_worst_case_trace`], recording the resources it used.
+///
+/// Panics if the execution can't be proven or the proof doesn't verify.
+pub fn prove_worst_case(log_size: u32) -> WorstCaseStats {
+    let baseline = PeakAllocator::reset_peak();
+    let start = Instant::now();
+
+    let (view, trace) = synthesize_worst_case_trace(log_size);
+    let proof =
+        Machine::<BaseComponent>::prove(&trace, &view).expect("worst-case trace is provable");
+
+    let duration = start.elapsed();
+    // Only the counting allocator tracks allocations, the trace is still alive while it's installed.
+    let peak_heap_bytes =
+        (PeakAllocator::allocated() > 0).then(|| PeakAllocator::peak() - baseline);
+    crate::verify(proof, &view).expect("worst-case proof verifies");
+
+    WorstCaseStats {
+        log_size,
+        num_steps: trace.get_num_steps(),
+        duration,
+        peak_heap_bytes,
+    }
+}
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// Global allocator counting the bytes allocated on the heap and their peak, see [`prove_worst_case`].
+///
+/// Counting slows down every allocation, install it with `#[global_allocator]` in a test binary of its own.
+pub struct PeakAllocator;
+
+impl PeakAllocator {
+    /// Bytes currently allocated, zero unless the allocator is installed.
+    pub fn allocated() -> usize {
+        ALLOCATED.load(Ordering::Relaxed)
+    }
+
+    /// Highest number of bytes allocated at once since the last [`Self::reset_peak`].
+    pub fn peak() -> usize {
+        PEAK.load(Ordering::Relaxed)
+    }
+
+    /// Resets the peak to the bytes currently allocated, and returns them.
+    pub fn reset_peak() -> usize {
+        let allocated = ALLOCATED.load(Ordering::Relaxed);
+        PEAK.store(allocated, Ordering::Relaxed);
+        allocated
+    }
+}
+
+unsafe impl GlobalAlloc for PeakAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(allocated, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
@@ -3,15 +3,25 @@ use std::array;
 use num_traits::Zero;
 use stwo_constraint_framework::{preprocessed_columns::PreProcessedColumnId, EvalAtRow};
 
-use crate::column::{
-    Column, {PreprocessedColumn, ProgramColumn},
-};
+use crate::column::{GlobalColumn, PreprocessedColumn, ProgramColumn};
 
 pub use stwo_constraint_framework::{
     INTERACTION_TRACE_IDX, ORIGINAL_TRACE_IDX, PREPROCESSED_TRACE_IDX,
 };
 
+/// Index of the tree with the columns that depend on the program and its public input and output: the program trace
+/// and the program columns of extensions. They are kept out of the preprocessed tree, so that it only depends on
+/// the log sizes of the components and can be committed once for every program.
+///
+/// The tree comes last by index, after the interaction tree, but its root is mixed into the channel right after the
+/// root of the preprocessed tree.
+pub const PROGRAM_TRACE_IDX: usize = INTERACTION_TRACE_IDX + 1;
+
 // Trace evaluation at the current row and the next row.
+//
+// Masks are requested once per evaluation and looked up by column offset, so that reading a column in a chip
+// neither hashes nor allocates. Only columns declared with `reads_next_row_mask` request the next row, reading the
+// next row of any other column panics instead of walking off the declared mask.
 pub struct TraceEval<E: EvalAtRow> {
     evals: Vec<[E::F; 2]>,
     preprocessed_evals: Vec<E::F>,
@@ -24,24 +34,26 @@ impl<E: EvalAtRow> TraceEval<E> {
             .iter()
             .map(|&id| eval.get_preprocessed_column(PreProcessedColumnId { id: id.to_owned() }))
             .collect();
-        let program_evals = ProgramColumn::STRING_IDS
-            .iter()
-            .map(|&id| eval.get_preprocessed_column(PreProcessedColumnId { id: id.to_owned() }))
-            .collect();
-        let evals = Column::ALL_VARIANTS
-            .iter()
-            .flat_map(|col| std::iter::repeat_n(col, col.size()))
-            .map(|col| {
-                if col.reads_next_row_mask() {
-                    eval.next_interaction_mask(ORIGINAL_TRACE_IDX, [0, 1])
-                } else {
-                    [
-                        eval.next_interaction_mask(ORIGINAL_TRACE_IDX, [0])[0].clone(),
-                        <E::F as Zero>::zero(), // pad with zero, this value shouldn't be accessed
-                    ]
-                }
-            })
+        let program_evals = (0..ProgramColumn::COLUMNS_NUM)
+            .map(|_| eval.next_interaction_mask(PROGRAM_TRACE_IDX, [0])[0].clone())
             .collect();
+        // The flattened columns don't have an exact size hint, reserve upfront to allocate once per evaluation.
+        let mut evals = Vec::with_capacity(GlobalColumn::COLUMNS_NUM);
+        evals.extend(
+            GlobalColumn::all_variants()
+                .flat_map(|col| std::iter::repeat_n(col, col.size()))
+                .map(|col| {
+                    if col.reads_next_row_mask() {
+                        eval.next_interaction_mask(ORIGINAL_TRACE_IDX, [0, 1])
+                    } else {
+                        [
+                            eval.next_interaction_mask(ORIGINAL_TRACE_IDX, [0])[0].clone(),
+                            <E::F as Zero>::zero(), // pad with zero, this value shouldn't be accessed
+                        ]
+                    }
+                }),
+        );
+        debug_assert_eq!(evals.len(), GlobalColumn::COLUMNS_NUM);
         Self {
             evals,
             preprocessed_evals,
@@ -50,7 +62,8 @@ impl<E: EvalAtRow> TraceEval<E> {
     }
 
     #[doc(hidden)]
-    pub fn column_eval<const N: usize>(&self, col: Column) -> [E::F; N] {
+    pub fn column_eval<const N: usize>(&self, col: impl Into<GlobalColumn>) -> [E::F; N] {
+        let col = col.into();
         assert_eq!(col.size(), N, "column size mismatch");
         let offset = col.offset();
 
@@ -58,7 +71,8 @@ impl<E: EvalAtRow> TraceEval<E> {
     }
 
     #[doc(hidden)]
-    pub fn column_eval_next_row<const N: usize>(&self, col: Column) -> [E::F; N] {
+    pub fn column_eval_next_row<const N: usize>(&self, col: impl Into<GlobalColumn>) -> [E::F; N] {
+        let col = col.into();
         assert_eq!(col.size(), N, "column size mismatch");
         assert!(
             col.reads_next_row_mask(),
@@ -105,7 +119,7 @@ impl<E: EvalAtRow> TraceEval<E> {
 /// ```
 macro_rules! trace_eval {
     ($traces:expr, $col:expr) => {{
-        $traces.column_eval::<{ Column::size($col) }>($col)
+        $traces.column_eval::<{ $col.size() }>($col)
     }};
 }
 
@@ -120,7 +134,7 @@ pub(crate) use trace_eval;
 /// ```
 macro_rules! trace_eval_next_row {
     ($traces:expr, $col:expr) => {{
-        $traces.column_eval_next_row::<{ Column::size($col) }>($col)
+        $traces.column_eval_next_row::<{ $col.size() }>($col)
     }};
 }
 
@@ -0,0 +1,173 @@
+//! Interaction trace of the main component.
+//!
+//! Chips fill one logup column per fraction, in the same order as they add relation entries in their constraints.
+//! [`LogupTraceBuilder`] combines consecutive columns in pairs, `a/b + c/d = (ad + cb)/(bd)`, so that each pair
+//! takes a single SecureField column, and [`finalize_logup`] makes the constraints expect the same pairing. A
+//! leftover fraction keeps a column of its own.
+//!
+//! Relation entries of the main component have denominators linear in the trace, pairing them adds one to the
+//! degree of the logup constraints, which stays within the constraint degree bound of the component.
+//!
+//! The `unbatched-logup` feature writes every fraction into its own column, which is easier to debug.
+
+use num_traits::{One, Zero};
+use stwo::{
+    core::fields::{m31::BaseField, qm31::SecureField},
+    prover::{
+        backend::simd::{m31::LOG_N_LANES, qm31::PackedSecureField, SimdBackend},
+        poly::{circle::CircleEvaluation, BitReversedOrder},
+    },
+};
+use stwo_constraint_framework::{EvalAtRow, LogupTraceGenerator};
+
+/// Fractions of a single column, indexed by vec_row.
+type Fractions = Vec<(PackedSecureField, PackedSecureField)>;
+
+/// Generator of the main component interaction trace, with the interface of [`LogupTraceGenerator`].
+pub struct LogupTraceBuilder {
+    inner: LogupTraceGenerator,
+    log_size: u32,
+    /// Column waiting to be paired with the next one.
+    pending: Option<Fractions>,
+}
+
+impl LogupTraceBuilder {
+    pub fn new(log_size: u32) -> Self {
+        Self {
+            inner: LogupTraceGenerator::new(log_size),
+            log_size,
+            pending: None,
+        }
+    }
+
+    /// Starts a new column, one fraction is written per vec_row.
+    pub fn new_col(&mut self) -> LogupColBuilder<'_> {
+        let fractions = vec![
+            (
+                PackedSecureField::zero(),
+                PackedSecureField::broadcast(SecureField::one())
+            );
+            1 << (self.log_size - LOG_N_LANES)
+        ];
+        LogupColBuilder {
+            builder: self,
+            fractions,
+        }
+    }
+
+    fn push_col(&mut self, fractions: Fractions) {
+        if cfg!(feature = "unbatched-logup") {
+            return self.write_col(fractions);
+        }
+        match self.pending.take() {
+            Some(pending) => self.write_col(
+                pending
+                    .into_iter()
+                    .zip(fractions)
+                    .map(|((a, b), (c, d))| (a * d + c * b, b * d))
+                    .collect(),
+            ),
+            None => self.pending = Some(fractions),
+        }
+    }
+
+    fn write_col(&mut self, fractions: Fractions) {
+        let mut col_gen = self.inner.new_col();
+        for (vec_row, (numerator, denom)) in fractions.into_iter().enumerate() {
+            col_gen.write_frac(vec_row, numerator, denom);
+        }
+        col_gen.finalize_col();
+    }
+
+    /// Writes the leftover column, if any, and returns the interaction trace together with its claimed sum.
+    pub fn finalize_last(
+        mut self,
+    ) -> (
+        Vec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+        SecureField,
+    ) {
+        if let Some(pending) = self.pending.take() {
+            self.write_col(pending);
+        }
+        self.inner.finalize_last()
+    }
+}
+
+/// A single column of [`LogupTraceBuilder`], written when finalized.
+pub struct LogupColBuilder<'a> {
+    builder: &'a mut LogupTraceBuilder,
+    fractions: Fractions,
+}
+
+impl LogupColBuilder<'_> {
+    pub fn write_frac(
+        &mut self,
+        vec_row: usize,
+        numerator: PackedSecureField,
+        denom: PackedSecureField,
+    ) {
+        self.fractions[vec_row] = (numerator, denom);
+    }
+
+    pub fn finalize_col(self) {
+        self.builder.push_col(self.fractions);
+    }
+}
+
+/// Finalizes the logup constraints of the main component, pairing fractions the same way as [`LogupTraceBuilder`].
+pub(crate) fn finalize_logup<E: EvalAtRow>(eval: &mut E) {
+    if cfg!(feature = "unbatched-logup") {
+        eval.finalize_logup();
+    } else {
+        eval.finalize_logup_in_pairs();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stwo::core::fields::m31::M31;
+
+    use super::*;
+
+    fn packed(value: u32) -> PackedSecureField {
+        PackedSecureField::broadcast(SecureField::from(M31::from(value)))
+    }
+
+    #[test]
+    fn pairing_preserves_claimed_sum() {
+        const LOG_SIZE: u32 = LOG_N_LANES + 2;
+        let num_vec_rows = 1 << (LOG_SIZE - LOG_N_LANES);
+        // An odd number of columns leaves the last one unpaired.
+        let columns: Vec<Vec<(u32, u32)>> = (0..5u32)
+            .map(|col| {
+                (0..num_vec_rows)
+                    .map(|row| (col + row, 3 + col * row))
+                    .collect()
+            })
+            .collect();
+
+        let mut unbatched = LogupTraceGenerator::new(LOG_SIZE);
+        let mut builder = LogupTraceBuilder::new(LOG_SIZE);
+        for column in &columns {
+            let mut col_gen = unbatched.new_col();
+            let mut col_builder = builder.new_col();
+            for (vec_row, &(numerator, denom)) in column.iter().enumerate() {
+                col_gen.write_frac(vec_row, packed(numerator), packed(denom));
+                col_builder.write_frac(vec_row, packed(numerator), packed(denom));
+            }
+            col_gen.finalize_col();
+            col_builder.finalize_col();
+        }
+
+        let (unbatched_trace, unbatched_sum) = unbatched.finalize_last();
+        let (trace, claimed_sum) = builder.finalize_last();
+        assert_eq!(claimed_sum, unbatched_sum);
+        if cfg!(feature = "unbatched-logup") {
+            assert_eq!(trace.len(), unbatched_trace.len());
+        } else {
+            // Three SecureField columns instead of five.
+            assert_eq!(trace.len(), 3 * 4);
+            assert_eq!(unbatched_trace.len(), 5 * 4);
+        }
+    }
+}
@@ -1,4 +1,5 @@
 pub mod eval;
+pub mod logup;
 pub mod preprocessed;
 pub mod program;
 pub mod program_trace;
@@ -9,5 +10,6 @@ pub mod utils;
 pub mod utils_external;
 
 pub use preprocessed::PreprocessedTraces;
-pub use program::{BoolWord, ProgramStep, Word, WordWithEffectiveBits};
+pub use program::{BoolWord, MemAccess, ProgramStep, Word, WordWithEffectiveBits};
+pub use regs::validate_register_accesses;
 pub use trace_builder::{FinalizedTraces, TracesBuilder};
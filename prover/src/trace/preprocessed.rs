@@ -1,4 +1,8 @@
 use num_traits::{One, Zero};
+use rayon::{
+    iter::{IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator},
+    slice::ParallelSliceMut,
+};
 use stwo::{
     core::{fields::m31::BaseField, poly::circle::CanonicCoset, ColumnVec},
     prover::{
@@ -21,8 +25,16 @@ pub(crate) struct PreprocessedBuilder(TracesBuilder);
 impl PreprocessedBuilder {
     /// Min supported log size of the trace. This constant determines the size of the main component's trace.
     ///
-    /// `LOG_N_LANES` trace size is not supported in the current prover configuration.
-    pub const MIN_LOG_SIZE: u32 = 8;
+    /// `LOG_N_LANES` trace size is not supported in the current prover configuration. Range check tables don't depend
+    /// on it, they are committed by multiplicity extensions of fixed height instead of the preprocessed trace.
+    pub const MIN_LOG_SIZE: u32 = nexus_common::trace_size::MIN_LOG_SIZE;
+
+    /// `RowCounter` of the first row, and the clock of the first row in the main trace. The emulator starts its clock
+    /// at the same value.
+    pub(crate) const FIRST_CLK: u32 = 1;
+
+    /// Rows filled by one rayon task.
+    const ROW_CHUNK: usize = 1 << 14;
 
     /// Returns [`PreprocessedColumn::COLUMNS_NUM`] columns, each one `2.pow(log_size)` in length, filled with preprocessed trace content.
     fn new(log_size: u32) -> Self {
@@ -33,7 +45,11 @@ impl PreprocessedBuilder {
             Self::MIN_LOG_SIZE,
         );
         let cols = vec![vec![BaseField::zero(); 1 << log_size]; PreprocessedColumn::COLUMNS_NUM];
-        let mut ret = Self(TracesBuilder { cols, log_size });
+        let mut ret = Self(TracesBuilder {
+            cols,
+            log_size,
+            num_real_rows: 1 << log_size,
+        });
         ret.fill_is_first();
         ret.fill_is_last();
         ret.fill_timestamps();
@@ -50,6 +66,7 @@ impl PreprocessedBuilder {
         self.0.num_rows()
     }
 
+    #[cfg(test)]
     fn fill_preprocessed_word(
         &mut self,
         row_idx: usize,
@@ -72,12 +89,47 @@ impl PreprocessedBuilder {
             .expect("preprocessed trace must be non-empty") = BaseField::one();
     }
 
+    /// Fills the row counter and the current register access timestamps of every row.
+    ///
+    /// Every limb column is filled independently and in row chunks on the rayon thread pool, each cell only depends on
+    /// its row index so the result doesn't depend on scheduling.
     pub(crate) fn fill_timestamps(&mut self) {
+        // Make sure the last reg3_ts_cur computation doesn't overflow
+        assert!(self.num_rows() < (u32::MAX as usize - 3) / 3);
+        // Timestamp of a row as a function of its clock.
+        let timestamps: [(PreprocessedColumn, fn(u32) -> u32); 4] = [
+            (PreprocessedColumn::RowCounter, |clk| clk),
+            (PreprocessedColumn::Reg1TsCur, |clk| clk * 3 + 1),
+            (PreprocessedColumn::Reg2TsCur, |clk| clk * 3 + 2),
+            (PreprocessedColumn::Reg3TsCur, |clk| clk * 3 + 3),
+        ];
+        for (col, timestamp) in timestamps {
+            self.0.cols[col.offset()..col.offset() + WORD_SIZE]
+                .par_iter_mut()
+                .enumerate()
+                .for_each(|(limb_idx, column)| {
+                    column.par_chunks_mut(Self::ROW_CHUNK).enumerate().for_each(
+                        |(chunk_idx, chunk)| {
+                            let first_row = chunk_idx * Self::ROW_CHUNK;
+                            for (row_idx, cell) in (first_row..).zip(chunk) {
+                                let clk = row_idx as u32 + Self::FIRST_CLK;
+                                let byte = timestamp(clk).to_le_bytes()[limb_idx];
+                                *cell = BaseField::from(byte as u32);
+                            }
+                        },
+                    );
+                });
+        }
+    }
+
+    /// Row by row version of [`Self::fill_timestamps`], the parallel fill must produce identical columns.
+    #[cfg(test)]
+    fn fill_timestamps_sequential(&mut self) {
         // Make sure the last reg3_ts_cur computation doesn't overflow
         assert!(self.num_rows() < (u32::MAX as usize - 3) / 3);
         for row_idx in 0..(1 << self.log_size()) {
-            let clk = (row_idx + 1) as u32;
-            self.fill_preprocessed_word(row_idx, PreprocessedColumn::Clk, clk.to_le_bytes());
+            let clk = row_idx as u32 + Self::FIRST_CLK;
+            self.fill_preprocessed_word(row_idx, PreprocessedColumn::RowCounter, clk.to_le_bytes());
             let reg1_ts_cur = clk * 3 + 1;
             self.fill_preprocessed_word(
                 row_idx,
@@ -145,3 +197,35 @@ impl PreprocessedTraces {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn min_log_size_is_supported() {
+        let traces = PreprocessedTraces::new(PreprocessedTraces::MIN_LOG_SIZE);
+        assert_eq!(traces.log_size(), 8);
+    }
+
+    #[test]
+    fn parallel_timestamps_match_sequential() {
+        for log_size in [PreprocessedTraces::MIN_LOG_SIZE, 16] {
+            let parallel = PreprocessedBuilder::new(log_size);
+            let mut sequential = PreprocessedBuilder::new(log_size);
+            for col in &mut sequential.0.cols {
+                col.fill(BaseField::zero());
+            }
+            sequential.fill_is_first();
+            sequential.fill_is_last();
+            sequential.fill_timestamps_sequential();
+            assert_eq!(parallel.0.cols, sequential.0.cols);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "log_size must be at least 8")]
+    fn below_min_log_size_is_rejected() {
+        PreprocessedTraces::new(PreprocessedTraces::MIN_LOG_SIZE - 1);
+    }
+}
@@ -1,12 +1,45 @@
 use nexus_common::cpu::Registers;
 use nexus_vm::{
     cpu::RegisterFile,
-    riscv::{BuiltinOpcode, InstructionType, Register},
+    memory::{MemAccessSize, MemoryRecord},
+    riscv::{BuiltinOpcode, Instruction, InstructionType, Register},
     trace::{Step, Trace},
     SyscallCode, WORD_SIZE,
 };
 
-/// Program execution step.
+use crate::chips::{SYSCALL_ARG0_REG, SYSCALL_ARG1_REG, SYSCALL_NUMBER_REG};
+
+/// Program execution step, the input of [`MachineChip::fill_main_trace`](crate::traits::MachineChip::fill_main_trace)
+/// for every row of the trace.
+///
+/// A step is one executed instruction together with the register file before its execution, see
+/// [`iter_program_steps`]. Its contents are read through accessors, so that the representation can change without
+/// breaking chips defined outside of the crate.
+///
+/// ```
+/// use nexus_vm::{
+///     riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode, Register},
+///     trace::k_trace_direct,
+/// };
+/// use nexus_vm_prover::trace::program::iter_program_steps;
+///
+/// let basic_block = vec![BasicBlock::new(vec![
+///     Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 5),
+///     Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+/// ])];
+/// let (_view, trace) = k_trace_direct(&basic_block, 1).unwrap();
+/// let steps: Vec<_> = iter_program_steps(&trace, 4).collect();
+///
+/// let add = steps[1].as_ref().unwrap();
+/// assert_eq!(add.clk(), 2);
+/// assert_eq!(add.next_pc(), add.pc() + 4);
+/// assert_eq!(add.register_read(0), Some((Register::X1, 5)));
+/// assert_eq!(add.register_read(1), Some((Register::X1, 5)));
+/// assert_eq!(add.register_write(), Some((Register::X2, 10)));
+/// assert_eq!(add.memory_access(), None);
+/// // Rows past the end of the trace are padding.
+/// assert!(steps[2].is_none());
+/// ```
 #[derive(Clone, Debug, Default)]
 pub struct ProgramStep {
     /// Machine registers.
@@ -25,7 +58,115 @@ pub type BoolWord = [bool; WORD_SIZE];
 /// along with the count of effective bits.
 pub type WordWithEffectiveBits = (Word, usize);
 
+/// Memory access of a load or a store, see [`ProgramStep::memory_access`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemAccess {
+    pub address: u32,
+    pub size: MemAccessSize,
+    /// Loaded or stored value.
+    pub value: u32,
+    /// Value overwritten by a store, `None` for loads.
+    pub prev_value: Option<u32>,
+}
+
+impl MemAccess {
+    pub fn is_store(&self) -> bool {
+        self.prev_value.is_some()
+    }
+}
+
 impl ProgramStep {
+    /// Address of the instruction.
+    pub fn pc(&self) -> u32 {
+        self.step.pc
+    }
+
+    /// Address of the next executed instruction.
+    pub fn next_pc(&self) -> u32 {
+        self.step.next_pc
+    }
+
+    /// Clock of the step, which is the row index plus one.
+    pub fn clk(&self) -> u32 {
+        self.step.timestamp
+    }
+
+    /// The executed instruction.
+    pub fn instruction(&self) -> &Instruction {
+        &self.step.instruction
+    }
+
+    /// The `slot`-th source register of the instruction together with its value before the step: `0` for rs1 and
+    /// `1` for rs2. Returns `None` if the instruction doesn't read a register in that slot.
+    ///
+    /// System calls read their number from `a7` in the first slot and their first argument from `a0` in the second. The timestamp of the previous access to
+    /// the register isn't part of the step, it is tracked in the side note, see
+    /// [`SideNote::last_register_access`](crate::trace::sidenote::SideNote::last_register_access).
+    pub fn register_read(&self, slot: usize) -> Option<(Register, u32)> {
+        let instruction = &self.step.instruction;
+        let register = match (instruction.ins_type, slot) {
+            (_, 1) if self.get_syscall_code().is_some() => SYSCALL_ARG0_REG,
+            (InstructionType::RType | InstructionType::IType | InstructionType::ITypeShamt, 0) => {
+                self.get_op_b()
+            }
+            (InstructionType::SType | InstructionType::BType, 0) => instruction.op_a,
+            (InstructionType::RType, 1) => Register::from(instruction.op_c as u8),
+            (InstructionType::SType | InstructionType::BType, 1) => instruction.op_b,
+            _ => return None,
+        };
+        Some((register, self.regs.read(register)))
+    }
+
+    /// The destination register of the instruction together with the value written to it, if any.
+    ///
+    /// Instructions with `x0` as their destination don't write a register.
+    pub fn register_write(&self) -> Option<(Register, u32)> {
+        if matches!(
+            self.step.instruction.ins_type,
+            InstructionType::SType | InstructionType::BType | InstructionType::Unimpl
+        ) {
+            return None;
+        }
+        let register = self.get_op_a();
+        // The commit syscall reads its length through the destination access and writes it back unchanged.
+        let value = match self.get_syscall_code().map(SyscallCode::try_from) {
+            Some(Ok(SyscallCode::Commit)) => self.regs.read(register),
+            _ => self.step.result?,
+        };
+        (register != Register::X0).then_some((register, value))
+    }
+
+    /// The memory access of a load or a store.
+    ///
+    /// Returns `None` for steps that don't access memory, and for system calls, which may access several addresses.
+    pub fn memory_access(&self) -> Option<MemAccess> {
+        if self.get_syscall_code().is_some() || self.step.memory_records.len() != 1 {
+            return None;
+        }
+        let access = match self.step.memory_records.iter().next()? {
+            MemoryRecord::LoadRecord((size, address, value), _) => MemAccess {
+                address: *address,
+                size: *size,
+                value: *value,
+                prev_value: None,
+            },
+            MemoryRecord::StoreRecord((size, address, value, prev_value), _) => MemAccess {
+                address: *address,
+                size: *size,
+                value: *value,
+                prev_value: Some(*prev_value),
+            },
+        };
+        Some(access)
+    }
+
+    /// Whether the step is padding inserted by the VM rather than an executed instruction.
+    ///
+    /// Rows past the end of the trace are `None` in [`iter_program_steps`] instead.
+    pub fn is_padding(&self) -> bool {
+        self.step.instruction.opcode.builtin() == Some(BuiltinOpcode::UNIMPL)
+    }
+
     /// Returns the value of the first operand (rd or rs1) as bytes.
     /// Always a register value in range u32.
     pub(crate) fn get_value_a(&self) -> Word {
@@ -47,7 +188,7 @@ impl ProgramStep {
     /// Returns the first read register if no register gets accessed, zero
     pub(crate) fn get_op_b(&self) -> Register {
         if self.get_syscall_code().is_some() {
-            Register::X17
+            SYSCALL_NUMBER_REG
         } else {
             self.step.instruction.op_b
         }
@@ -78,12 +219,17 @@ impl ProgramStep {
     pub(crate) fn get_op_a(&self) -> Register {
         // Special cases: ECALL and EBREAK OpA depend on syscall number
         if let Some(syscall_value) = self.get_syscall_code() {
-            let syscall_number = SyscallCode::from(syscall_value);
+            let syscall_number = SyscallCode::try_from(syscall_value)
+                .expect("syscall number is validated by the emulator");
             match syscall_number {
-                SyscallCode::ReadFromPrivateInput | SyscallCode::OverwriteHeapPointer => {
-                    Register::X10
-                }
+                SyscallCode::ReadFromPrivateInput
+                | SyscallCode::OverwriteHeapPointer
+                | SyscallCode::Hint
+                | SyscallCode::ReadHint
+                | SyscallCode::ReadCycleCounter
+                | SyscallCode::ReadPrivateInputWord => SYSCALL_ARG0_REG,
                 SyscallCode::OverwriteStackPointer => Register::X2,
+                SyscallCode::Commit => SYSCALL_ARG1_REG,
                 _ => Register::X0,
             }
         } else {
@@ -120,17 +266,25 @@ impl ProgramStep {
         (c[WORD_SIZE - 1] >> 7) == 1
     }
 
-    /// Returns the syscall code value at register X17
+    /// Returns the syscall code value at [`SYSCALL_NUMBER_REG`]
     pub(crate) fn get_syscall_code(&self) -> Option<u32> {
         // Make sure the current instruction is ECALL, otherwise it's None
         match self.step.instruction.opcode.builtin() {
             Some(BuiltinOpcode::ECALL) | Some(BuiltinOpcode::EBREAK) => {
-                Some(self.regs.read(Register::X17))
+                Some(self.regs.read(SYSCALL_NUMBER_REG))
             }
             _ => None,
         }
     }
 
+    /// Returns the first syscall argument, the value at [`SYSCALL_ARG0_REG`] before the step
+    ///
+    /// Panics if the step is not a syscall.
+    pub(crate) fn get_syscall_arg0(&self) -> u32 {
+        assert!(self.get_syscall_code().is_some(), "step is not a syscall");
+        self.regs.read(SYSCALL_ARG0_REG)
+    }
+
     /// Returns true if the opcode is built-in.
     pub(crate) fn is_builtin(&self) -> bool {
         self.step.instruction.opcode.is_builtin()
@@ -157,3 +311,49 @@ pub fn iter_program_steps<TR: Trace>(
         .chain(std::iter::repeat(None))
         .take(num_rows)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nexus_vm::{
+        riscv::{BasicBlock, Opcode},
+        trace::k_trace_direct,
+    };
+
+    #[test]
+    fn accessors_of_loads_and_stores() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 0x100),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 7),
+            // mem[x1 + 4] = x2
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 1, 2, 4),
+            // x3 = mem[x1 + 4]
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 3, 1, 4),
+        ])];
+        let (_view, trace) = k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let steps: Vec<ProgramStep> = iter_program_steps(&trace, 4).flatten().collect();
+
+        let store = &steps[2];
+        assert_eq!(store.register_read(0), Some((Register::X1, 0x100)));
+        assert_eq!(store.register_read(1), Some((Register::X2, 7)));
+        assert_eq!(store.register_write(), None);
+        assert_eq!(
+            store.memory_access(),
+            Some(MemAccess {
+                address: 0x104,
+                size: MemAccessSize::Word,
+                value: 7,
+                prev_value: Some(0),
+            })
+        );
+
+        let load = &steps[3];
+        assert_eq!(load.register_read(0), Some((Register::X1, 0x100)));
+        assert_eq!(load.register_read(1), None);
+        assert_eq!(load.register_write(), Some((Register::X3, 7)));
+        let access = load.memory_access().unwrap();
+        assert!(!access.is_store());
+        assert_eq!((access.address, access.value), (0x104, 7));
+        assert!(!load.is_padding());
+    }
+}
@@ -1,4 +1,8 @@
-use num_traits::Zero;
+use num_traits::{One, Zero};
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator,
+    IntoParallelRefMutIterator, ParallelIterator,
+};
 use stwo::{
     core::{fields::m31::BaseField, poly::circle::CanonicCoset, ColumnVec},
     prover::{
@@ -6,12 +10,13 @@ use stwo::{
         poly::{circle::CircleEvaluation, BitReversedOrder},
     },
 };
+use tiny_keccak::{Hasher, Keccak};
 
 use super::{
     utils::{finalize_columns, IntoBaseFields},
     TracesBuilder,
 };
-use crate::column::ProgramColumn;
+use crate::{column::ProgramColumn, error::ProgramTemplateMismatch};
 
 use nexus_vm::{
     emulator::{MemoryInitializationEntry, ProgramInfo, ProgramMemoryEntry, PublicOutputEntry},
@@ -19,6 +24,7 @@ use nexus_vm::{
 };
 
 /// Wrapper around [`TracesBuilder`] that contains the program layout for figuring out the row_idx out of pc.
+#[derive(Debug, Clone)]
 pub struct ProgramTracesBuilder {
     traces_builder: TracesBuilder,
     /// Program counter written on the first row. The current assumption is that the program is in contiguous memory starting from [`Self::pc_offset`].
@@ -37,9 +43,13 @@ pub struct ProgramTraceRef<'a> {
     pub exit_code: &'a [PublicOutputEntry],
     /// Slice of public output entries.
     pub public_output: &'a [PublicOutputEntry],
+    /// Number of executed steps, one per row of the main trace before padding.
+    pub num_steps: usize,
+    /// Number of public output bytes committed with the commit ecall, zero if the guest never committed.
+    pub committed_output_len: u32,
 }
 
-#[cfg(test)]
+#[cfg(any(test, feature = "testing"))]
 impl<'a> ProgramTraceRef<'a> {
     pub(crate) fn new_with_empty_memory(program_memory: &'a ProgramInfo) -> Self {
         Self {
@@ -47,38 +57,203 @@ impl<'a> ProgramTraceRef<'a> {
             init_memory: &[],
             exit_code: &[],
             public_output: &[],
+            num_steps: 0,
+            committed_output_len: 0,
         }
     }
 }
 
+/// Program trace of a program without the columns depending on its execution, see [`ProgramTracesBuilder::from_template`].
+///
+/// Building the template walks the whole program, a prover or verifier handling many executions of one program builds
+/// it once per trace size and only fills [`ProgramColumn::PrgNumSteps`] for each of them.
+///
+/// The template keeps the program it was built for, executions are matched against it by comparing instructions, and
+/// the program hash is only computed once.
+#[derive(Debug, Clone)]
+pub struct ProgramTraceTemplate {
+    builder: ProgramTracesBuilder,
+    initial_pc: u32,
+    program: Vec<ProgramMemoryEntry>,
+    program_hash: [u8; 32],
+}
+
+impl ProgramTraceTemplate {
+    pub fn new(log_size: u32, program_memory: &ProgramInfo) -> Self {
+        Self {
+            builder: ProgramTracesBuilder::new_without_steps(log_size, program_memory),
+            initial_pc: program_memory.initial_pc,
+            program: program_memory.program.clone(),
+            program_hash: Self::program_hash(program_memory),
+        }
+    }
+
+    /// Returns true if the template was built for `program_memory`, without hashing it.
+    fn is_built_for(&self, program_memory: &ProgramInfo) -> bool {
+        self.initial_pc == program_memory.initial_pc
+            && self.program.len() == program_memory.program.len()
+            && self
+                .program
+                .iter()
+                .zip(&program_memory.program)
+                .all(|(a, b)| a.pc == b.pc && a.instruction_word == b.instruction_word)
+    }
+
+    /// Returns the log_size of columns.
+    pub fn log_size(&self) -> u32 {
+        self.builder.traces_builder.log_size
+    }
+
+    /// Returns the Keccak-256 hash of the initial pc followed by every pc and instruction word of the program.
+    pub fn program_hash(program_memory: &ProgramInfo) -> [u8; 32] {
+        let mut hasher = Keccak::v256();
+        hasher.update(&program_memory.initial_pc.to_le_bytes());
+        for entry in &program_memory.program {
+            hasher.update(&entry.pc.to_le_bytes());
+            hasher.update(&entry.instruction_word.to_le_bytes());
+        }
+        let mut hash = [0u8; 32];
+        hasher.finalize(&mut hash);
+        hash
+    }
+}
+
 impl ProgramTracesBuilder {
     pub fn new(log_size: u32, params: ProgramTraceRef) -> Self {
+        let mut ret = Self::new_without_steps(log_size, params.program_memory);
+        ret.fill_num_steps(params.num_steps);
+        ret.fill_committed_output_len(params.committed_output_len);
+        ret
+    }
+
+    /// Same as [`Self::new`], with the program columns copied from `template`.
+    ///
+    /// The template must have been built for `log_size` and `params.program_memory`, otherwise an error is returned.
+    pub fn from_template(
+        template: &ProgramTraceTemplate,
+        log_size: u32,
+        params: ProgramTraceRef,
+    ) -> Result<Self, ProgramTemplateMismatch> {
+        if template.log_size() != log_size {
+            return Err(ProgramTemplateMismatch::LogSize {
+                expected: template.log_size(),
+                actual: log_size,
+            });
+        }
+        if !template.is_built_for(params.program_memory) {
+            return Err(ProgramTemplateMismatch::Program {
+                expected: template.program_hash,
+                actual: ProgramTraceTemplate::program_hash(params.program_memory),
+            });
+        }
+        let mut ret = template.builder.clone();
+        ret.fill_num_steps(params.num_steps);
+        ret.fill_committed_output_len(params.committed_output_len);
+        Ok(ret)
+    }
+
+    fn new_without_steps(log_size: u32, program_memory: &ProgramInfo) -> Self {
+        assert!(log_size >= LOG_N_LANES);
+        let program = &program_memory.program;
+        assert!(
+            program.len() <= 1 << log_size,
+            "Program is longer than program trace size"
+        );
+        let pc_offset = program.first().map_or(0, |entry| entry.pc);
+        for (row_idx, entry) in program.iter().enumerate() {
+            assert_eq!(
+                row_idx * WORD_SIZE + pc_offset as usize,
+                entry.pc as usize,
+                "The program is assumed to be in contiguous memory."
+            );
+        }
+
+        // Value of each program memory column on the row of a program entry, the remaining rows are zero.
+        let program_columns: [(usize, fn(&ProgramMemoryEntry) -> BaseField); 5] = [
+            (ProgramColumn::PrgMemoryPc.offset(), |entry| {
+                BaseField::from(entry.pc & 0xFFFF)
+            }),
+            (ProgramColumn::PrgMemoryPc.offset() + 1, |entry| {
+                BaseField::from(entry.pc >> 16)
+            }),
+            (ProgramColumn::PrgMemoryWord.offset(), |entry| {
+                BaseField::from(entry.instruction_word & 0xFFFF)
+            }),
+            (ProgramColumn::PrgMemoryWord.offset() + 1, |entry| {
+                BaseField::from(entry.instruction_word >> 16)
+            }),
+            (ProgramColumn::PrgMemoryFlag.offset(), |_| BaseField::one()),
+        ];
+        let num_rows = BaseField::from(1u32 << log_size);
+        // Columns are independent, each one is filled on the rayon thread pool.
+        let cols = (0..ProgramColumn::COLUMNS_NUM)
+            .into_par_iter()
+            .map(|col_idx| {
+                let mut column = vec![BaseField::zero(); 1 << log_size];
+                if col_idx == ProgramColumn::PrgNumRows.offset() {
+                    column.par_iter_mut().for_each(|cell| *cell = num_rows);
+                } else if let Some((_, value)) = program_columns
+                    .iter()
+                    .find(|(offset, _)| *offset == col_idx)
+                {
+                    column[..program.len()]
+                        .par_iter_mut()
+                        .zip(program.par_iter())
+                        .for_each(|(cell, entry)| *cell = value(entry));
+                }
+                column
+            })
+            .collect();
+
+        let mut ret = Self {
+            traces_builder: TracesBuilder {
+                cols,
+                log_size,
+                num_real_rows: 1 << log_size,
+            },
+            pc_offset,
+            num_instructions: program.len(),
+        };
+        ret.fill_program_columns(0, program_memory.initial_pc, ProgramColumn::PrgInitialPc);
+        ret
+    }
+
+    /// Row by row version of [`Self::new_without_steps`], the parallel fill must produce identical columns.
+    #[cfg(test)]
+    fn new_without_steps_sequential(log_size: u32, program_memory: &ProgramInfo) -> Self {
         assert!(log_size >= LOG_N_LANES);
         assert!(
-            params.program_memory.program.len() <= 1 << log_size,
+            program_memory.program.len() <= 1 << log_size,
             "Program is longer than program trace size"
         );
 
         let cols = vec![vec![BaseField::zero(); 1 << log_size]; ProgramColumn::COLUMNS_NUM];
-        let builder = TracesBuilder { cols, log_size };
+        let builder = TracesBuilder {
+            cols,
+            log_size,
+            num_real_rows: 1 << log_size,
+        };
         let mut ret = Self {
             traces_builder: builder,
             pc_offset: 0u32,
             num_instructions: 0usize,
         };
 
-        ret.fill_program_columns(
-            0,
-            params.program_memory.initial_pc,
-            ProgramColumn::PrgInitialPc,
-        );
+        ret.fill_program_columns(0, program_memory.initial_pc, ProgramColumn::PrgInitialPc);
+        for row_idx in 0..1 << log_size {
+            ret.fill_program_columns(
+                row_idx,
+                BaseField::from(1u32 << log_size),
+                ProgramColumn::PrgNumRows,
+            );
+        }
         for (
             row_idx,
             ProgramMemoryEntry {
                 pc,
                 instruction_word,
             },
-        ) in params.program_memory.program.iter().enumerate()
+        ) in program_memory.program.iter().enumerate()
         {
             if row_idx == 0 {
                 ret.pc_offset = *pc;
@@ -107,13 +282,37 @@ impl ProgramTracesBuilder {
         ret
     }
 
-    #[cfg(test)]
+    fn fill_num_steps(&mut self, num_steps: usize) {
+        let log_size = self.traces_builder.log_size;
+        assert!(
+            num_steps <= 1 << log_size,
+            "Execution is longer than program trace size"
+        );
+        let num_steps = BaseField::from(num_steps as u32);
+        self.traces_builder.cols[ProgramColumn::PrgNumSteps.offset()]
+            .par_iter_mut()
+            .for_each(|cell| *cell = num_steps);
+    }
+
+    fn fill_committed_output_len(&mut self, committed_output_len: u32) {
+        let limbs =
+            [committed_output_len & 0xFFFF, committed_output_len >> 16].map(BaseField::from);
+        let offset = ProgramColumn::PrgCommittedOutputLen.offset();
+        for (col, limb) in self.traces_builder.cols[offset..offset + limbs.len()]
+            .iter_mut()
+            .zip(limbs)
+        {
+            col.par_iter_mut().for_each(|cell| *cell = limb);
+        }
+    }
+
+    #[cfg(any(test, feature = "testing"))]
     pub(crate) fn new_with_empty_memory(log_size: u32, program_memory: &ProgramInfo) -> Self {
         let params = ProgramTraceRef::new_with_empty_memory(program_memory);
         Self::new(log_size, params)
     }
 
-    #[cfg(test)]
+    #[cfg(any(test, feature = "testing"))]
     pub(crate) fn dummy(log_size: u32) -> Self {
         Self::new_with_empty_memory(log_size, &ProgramInfo::dummy())
     }
@@ -188,3 +387,30 @@ impl ProgramTraces {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parallel_program_trace_matches_sequential() {
+        let program_memory = ProgramInfo {
+            initial_pc: 0x1008,
+            program: (0..3000u32)
+                .map(|idx| ProgramMemoryEntry {
+                    pc: 0x1000 + idx * WORD_SIZE as u32,
+                    instruction_word: idx.wrapping_mul(0x9e3779b9),
+                })
+                .collect(),
+            initial_args: vec![],
+        };
+        for log_size in [12, 16] {
+            let parallel = ProgramTracesBuilder::new_without_steps(log_size, &program_memory);
+            let sequential =
+                ProgramTracesBuilder::new_without_steps_sequential(log_size, &program_memory);
+            assert_eq!(parallel.traces_builder.cols, sequential.traces_builder.cols);
+            assert_eq!(parallel.pc_offset, sequential.pc_offset);
+            assert_eq!(parallel.num_instructions, sequential.num_instructions);
+        }
+    }
+}
@@ -1,4 +1,8 @@
 use nexus_common::riscv::register::NUM_REGISTERS;
+use nexus_vm::{riscv::Register, trace::Trace};
+
+use super::program::iter_program_steps;
+use crate::error::RegisterAccessError;
 
 // This file contains utilities for register memory checking
 
@@ -21,9 +25,13 @@ pub(crate) struct AccessResult {
 
 impl RegisterMemCheckSideNote {
     pub fn new() -> Self {
+        Self::with_initial_values([0; NUM_REGISTERS])
+    }
+    /// Starts with the given register values, accessed at timestamp zero.
+    pub(crate) fn with_initial_values(values: [u32; NUM_REGISTERS]) -> Self {
         Self {
             last_access_timestamp: [0; NUM_REGISTERS],
-            last_access_value: [0; NUM_REGISTERS],
+            last_access_value: values,
         }
     }
     pub(crate) fn access(&mut self, reg: u32, cur_timestamp: u32, cur_value: u32) -> AccessResult {
@@ -37,3 +45,150 @@ impl RegisterMemCheckSideNote {
         ret
     }
 }
+
+/// Checks the invariants of the register memory check on `trace` directly, without building traces: every register
+/// read sees the value of the last write, accesses to a register have strictly increasing timestamps and `x0` always
+/// reads zero.
+///
+/// Registers start with the values read by the first row. Returns the first violation found.
+///
+/// # Panics
+///
+/// Panics if `trace` has more than one step per block, like the prover.
+pub fn validate_register_accesses(trace: &impl Trace) -> Result<(), RegisterAccessError> {
+    let mut last_access: Option<RegisterMemCheckSideNote> = None;
+    for (row, program_step) in iter_program_steps(trace, trace.get_num_steps())
+        .flatten()
+        .enumerate()
+    {
+        let regs = program_step.regs;
+        let last_access = last_access.get_or_insert_with(|| {
+            let mut values = [0; NUM_REGISTERS];
+            for (reg, value) in values.iter_mut().enumerate() {
+                *value = regs[Register::from(reg as u8)];
+            }
+            RegisterMemCheckSideNote::with_initial_values(values)
+        });
+
+        let base_ts = 3 * program_step.step.timestamp;
+        let reads =
+            (0..2).filter_map(|slot| program_step.register_read(slot).map(|(reg, _)| (slot, reg)));
+        for (slot, register) in reads {
+            // Unlike `Registers::read`, indexing doesn't hardwire x0 to zero.
+            let found = regs[register];
+            if register == Register::X0 && found != 0 {
+                return Err(RegisterAccessError::NonZeroX0 { row, found });
+            }
+            let cur_ts = base_ts + slot as u32 + 1;
+            let previous = last_access.access(register as u32, cur_ts, found);
+            check_access(row, register, &previous, cur_ts)?;
+            if previous.prev_value != found {
+                return Err(RegisterAccessError::StaleRead {
+                    row,
+                    register,
+                    expected: previous.prev_value,
+                    found,
+                });
+            }
+        }
+        if let Some((register, value)) = program_step.register_write() {
+            let cur_ts = base_ts + 3;
+            let previous = last_access.access(register as u32, cur_ts, value);
+            check_access(row, register, &previous, cur_ts)?;
+        }
+    }
+    Ok(())
+}
+
+fn check_access(
+    row: usize,
+    register: Register,
+    previous: &AccessResult,
+    cur_ts: u32,
+) -> Result<(), RegisterAccessError> {
+    if previous.prev_timestamp >= cur_ts {
+        return Err(RegisterAccessError::NonIncreasingTimestamp {
+            row,
+            register,
+            expected: previous.prev_timestamp,
+            found: cur_ts,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nexus_common::cpu::Registers;
+    use nexus_vm::{
+        cpu::RegisterFile,
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+        trace::{k_trace_direct, UniformTrace},
+    };
+
+    /// x1 = 1, x2 = x1 + x0, x3 = x2 + x1.
+    fn trace() -> UniformTrace {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 3, 2, 1),
+        ])];
+        k_trace_direct(&basic_block, 1)
+            .expect("error generating trace")
+            .1
+    }
+
+    #[test]
+    fn consistent_trace() {
+        assert_eq!(validate_register_accesses(&trace()), Ok(()));
+    }
+
+    #[test]
+    fn stale_read() {
+        let mut trace = trace();
+        trace.blocks[2].regs.write(Register::X2, 5);
+        assert_eq!(
+            validate_register_accesses(&trace),
+            Err(RegisterAccessError::StaleRead {
+                row: 2,
+                register: Register::X2,
+                expected: 1,
+                found: 5,
+            })
+        );
+    }
+
+    #[test]
+    fn non_increasing_timestamp() {
+        let mut trace = trace();
+        let first_ts = trace.blocks[0].steps[0].timestamp;
+        trace.blocks[1].steps[0].timestamp = first_ts;
+        // Row 1 reads x1 before row 0 writes it.
+        assert_eq!(
+            validate_register_accesses(&trace),
+            Err(RegisterAccessError::NonIncreasingTimestamp {
+                row: 1,
+                register: Register::X1,
+                expected: 3 * first_ts + 3,
+                found: 3 * first_ts + 1,
+            })
+        );
+    }
+
+    #[test]
+    fn non_zero_x0() {
+        let mut trace = trace();
+        // `RegisterFile::write` ignores x0, a deserialized register file isn't checked.
+        let mut registers = [0u32; NUM_REGISTERS];
+        registers[0] = 7;
+        registers[1] = 1;
+        trace.blocks[1].regs =
+            serde_json::from_value::<RegisterFile>(serde_json::json!({ "registers": registers }))
+                .unwrap();
+        assert_eq!(
+            validate_register_accesses(&trace),
+            Err(RegisterAccessError::NonZeroX0 { row: 1, found: 7 })
+        );
+    }
+}
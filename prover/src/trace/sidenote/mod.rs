@@ -4,8 +4,10 @@ use std::collections::BTreeMap;
 
 use nexus_vm::{
     emulator::{InternalView, MemoryInitializationEntry, PublicOutputEntry, View},
+    riscv::Register,
     WORD_SIZE,
 };
+use stwo::core::fields::m31::P;
 
 use super::{program_trace::ProgramTracesBuilder, regs::RegisterMemCheckSideNote};
 
@@ -53,6 +55,11 @@ impl ReadWriteMemCheckSideNote {
                 panic!("exit code overlaps with public output at address={address} value={val}")
             }
         }
+        // Public output that is never written keeps its initial zero value, it still needs an entry
+        // so that the final memory state covers every public address.
+        for address in public_output.keys() {
+            ret.last_access.entry(*address).or_insert((0, 0));
+        }
         ret.public_output = public_output;
         ret
     }
@@ -109,6 +116,7 @@ pub struct SideNote {
     pub(crate) range32: RangeCheckSideNote<{ 1 << 5 }>,
     pub(crate) range128: RangeCheckSideNote<{ 1 << 7 }>,
     pub(crate) range256: RangeCheckSideNote<{ 1 << 8 }>,
+    pub(crate) range4096: RangeCheckSideNote<{ 1 << 12 }>,
     pub(crate) keccak: keccak::KeccakSideNote,
 }
 
@@ -120,10 +128,11 @@ impl SideNote {
                 pc_offset: program_traces.pc_offset,
                 num_instructions: program_traces.num_instructions,
             },
-            register_mem_check: RegisterMemCheckSideNote::default(),
+            register_mem_check: RegisterMemCheckSideNote::with_initial_values(
+                view.get_program_memory().initial_registers(),
+            ),
             rw_mem_check: ReadWriteMemCheckSideNote::new(
                 &[
-                    // preprocessed trace is sensitive to this ordering
                     view.get_ro_initial_memory(),
                     view.get_rw_initial_memory(),
                     view.get_public_input(),
@@ -138,9 +147,51 @@ impl SideNote {
             range32: RangeCheckSideNote::<{ 1 << 5 }>::default(),
             range128: RangeCheckSideNote::<{ 1 << 7 }>::default(),
             range256: RangeCheckSideNote::<{ 1 << 8 }>::default(),
+            range4096: RangeCheckSideNote::<{ 1 << 12 }>::default(),
             keccak: keccak::KeccakSideNote::default(),
         }
     }
+
+    /// Timestamp and value of the last access to `reg` recorded so far by the register memory check.
+    ///
+    /// Accesses are recorded by `RegisterMemCheckChip`, which is placed after the instruction chips, so those see the
+    /// accesses of previous rows only. Row `clk - 1` accesses registers at timestamps `3·clk + 1` to `3·clk + 3`.
+    pub fn last_register_access(&self, reg: Register) -> (u32, u32) {
+        let reg_idx = reg as usize;
+        (
+            self.register_mem_check.last_access_timestamp[reg_idx],
+            self.register_mem_check.last_access_value[reg_idx],
+        )
+    }
+
+    /// Returns the name of a lookup table whose multiplicities can't be represented in the base field, if any.
+    pub(crate) fn overflowing_multiplicity(&self) -> Option<&'static str> {
+        let overflows = |multiplicities: &mut dyn Iterator<Item = &u32>| {
+            multiplicities.any(|&multiplicity| multiplicity >= P)
+        };
+        [
+            ("range8", overflows(&mut self.range8.multiplicity.iter())),
+            ("range16", overflows(&mut self.range16.multiplicity.iter())),
+            ("range32", overflows(&mut self.range32.multiplicity.iter())),
+            (
+                "range128",
+                overflows(&mut self.range128.multiplicity.iter()),
+            ),
+            (
+                "range256",
+                overflows(&mut self.range256.multiplicity.iter()),
+            ),
+            (
+                "range4096",
+                overflows(&mut self.range4096.multiplicity.iter()),
+            ),
+            ("and", overflows(&mut self.bit_op.multiplicity_and.values())),
+            ("or", overflows(&mut self.bit_op.multiplicity_or.values())),
+            ("xor", overflows(&mut self.bit_op.multiplicity_xor.values())),
+        ]
+        .into_iter()
+        .find_map(|(table, overflows)| overflows.then_some(table))
+    }
 }
 
 pub(crate) trait RangeCheckSideNoteGetter<const LEN: usize> {
@@ -170,3 +221,9 @@ impl RangeCheckSideNoteGetter<{ 1 << 8 }> for SideNote {
         &self.range256
     }
 }
+
+impl RangeCheckSideNoteGetter<{ 1 << 12 }> for SideNote {
+    fn get_range_check_side_note(&self) -> &RangeCheckSideNote<{ 1 << 12 }> {
+        &self.range4096
+    }
+}
@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use itertools::Itertools;
 use nexus_vm::WORD_SIZE;
 use num_traits::Zero;
@@ -10,25 +12,34 @@ use stwo::{
 };
 
 use super::utils::{finalize_columns, IntoBaseFields};
-use crate::column::Column;
+use crate::column::{Column, GlobalColumn};
 
 /// Main ([`stwo_prover::constraint_framework::ORIGINAL_TRACE_IDX`]) trace builder which implements
 /// mutable access to columns.
 ///
 /// Values are stored in original (coset) order.
+///
+/// Rows after the last executed step are padding, they are marked with `IsPadding` by [`CpuChip`] and must not
+/// access memory or registers. Their timestamps come from the preprocessed trace, so they keep increasing past the
+/// last executed step rather than dropping to zero.
+///
+/// [`CpuChip`]: crate::chips::CpuChip
 #[derive(Debug, Clone)]
 pub struct TracesBuilder {
     pub cols: Vec<Vec<BaseField>>,
     pub log_size: u32,
+    /// Number of rows filled with executed steps, see [`Self::num_real_rows`].
+    pub(crate) num_real_rows: usize,
 }
 
 impl TracesBuilder {
-    /// Returns [`Column::TOTAL_COLUMNS_NUM`] zeroed columns, each one `2.pow(log_size)` in length.
+    /// Returns [`GlobalColumn::COLUMNS_NUM`] zeroed columns, each one `2.pow(log_size)` in length.
     pub fn new(log_size: u32) -> Self {
         assert!(log_size >= LOG_N_LANES);
         Self {
-            cols: vec![vec![BaseField::zero(); 1 << log_size]; Column::COLUMNS_NUM],
+            cols: vec![vec![BaseField::zero(); 1 << log_size]; GlobalColumn::COLUMNS_NUM],
             log_size,
+            num_real_rows: 0,
         }
     }
 
@@ -47,9 +58,25 @@ impl TracesBuilder {
         1 << self.log_size
     }
 
+    /// Returns the number of rows before the first padding row, as recorded when filling the trace.
+    pub fn num_real_rows(&self) -> usize {
+        self.num_real_rows
+    }
+
+    /// Records that rows up to and including `row_idx` hold executed steps.
+    pub(crate) fn record_real_row(&mut self, row_idx: usize) {
+        assert!(row_idx < self.num_rows(), "row index out of bounds");
+        self.num_real_rows = self.num_real_rows.max(row_idx + 1);
+    }
+
     /// Returns a copy of `N` raw columns in range `[offset..offset + N]` at `row`, where
     /// `N` is assumed to be equal `Column::size` of a `col`.
-    pub fn column<const N: usize>(&self, row: usize, col: Column) -> [BaseField; N] {
+    pub fn column<const N: usize>(
+        &self,
+        row: usize,
+        col: impl Into<GlobalColumn>,
+    ) -> [BaseField; N] {
+        let col = col.into();
         assert_eq!(col.size(), N, "column size mismatch");
 
         let offset = col.offset();
@@ -59,7 +86,12 @@ impl TracesBuilder {
 
     /// Returns mutable reference to `N` raw columns in range `[offset..offset + N]` at `row`,
     /// where `N` is assumed to be equal `Column::size` of a `col`.
-    pub fn column_mut<const N: usize>(&mut self, row: usize, col: Column) -> [&mut BaseField; N] {
+    pub fn column_mut<const N: usize>(
+        &mut self,
+        row: usize,
+        col: impl Into<GlobalColumn>,
+    ) -> [&mut BaseField; N] {
+        let col = col.into();
         assert_eq!(col.size(), N, "column size mismatch");
 
         let offset = col.offset();
@@ -74,14 +106,47 @@ impl TracesBuilder {
         &mut self,
         row: usize,
         value: T,
-        col: Column,
+        col: impl Into<GlobalColumn>,
     ) {
         let base_field_values = value.into_base_fields();
         self.fill_columns_base_field(row, &base_field_values, col);
     }
 
+    /// Fills `col` with `(row, value)` pairs, writing one raw column at a time so that accesses stay within a
+    /// column.
+    pub(crate) fn fill_columns_batch<const N: usize, T: IntoBaseFields<N>>(
+        &mut self,
+        values: impl IntoIterator<Item = (usize, T)>,
+        col: impl Into<GlobalColumn>,
+    ) {
+        let col = col.into();
+        assert_eq!(col.size(), N, "column size mismatch");
+        let values: Vec<(usize, [BaseField; N])> = values
+            .into_iter()
+            .map(|(row, value)| (row, value.into_base_fields()))
+            .collect();
+        for i in 0..N {
+            let column = &mut self.cols[col.offset() + i];
+            for (row, value) in &values {
+                column[*row] = value[i];
+            }
+        }
+    }
+
+    /// Returns the `limb`-th raw column of `col` in `rows`.
+    pub(crate) fn column_chunk(
+        &self,
+        rows: Range<usize>,
+        col: impl Into<GlobalColumn>,
+        limb: usize,
+    ) -> &[BaseField] {
+        let col = col.into();
+        assert!(limb < col.size(), "limb out of range");
+        &self.cols[col.offset() + limb][rows]
+    }
+
     /// Fills columns with values from a byte slice.
-    pub fn fill_columns_bytes(&mut self, row: usize, value: &[u8], col: Column) {
+    pub fn fill_columns_bytes(&mut self, row: usize, value: &[u8], col: impl Into<GlobalColumn>) {
         let base_field_values = value
             .iter()
             .map(|b| BaseField::from(*b as u32))
@@ -90,7 +155,13 @@ impl TracesBuilder {
     }
 
     /// Fills columns with values from BaseField slice.
-    pub fn fill_columns_base_field(&mut self, row: usize, value: &[BaseField], col: Column) {
+    pub fn fill_columns_base_field(
+        &mut self,
+        row: usize,
+        value: &[BaseField],
+        col: impl Into<GlobalColumn>,
+    ) {
+        let col = col.into();
         let n = value.len();
         assert_eq!(col.size(), n, "column size mismatch");
         for (i, b) in value.iter().enumerate() {
@@ -104,10 +175,11 @@ impl TracesBuilder {
     pub fn fill_effective_columns(
         &mut self,
         row: usize,
-        src: Column,
-        dst: Column,
-        selector: Column,
+        src: impl Into<GlobalColumn>,
+        dst: impl Into<GlobalColumn>,
+        selector: impl Into<GlobalColumn>,
     ) {
+        let (src, dst) = (src.into(), dst.into());
         let src_len = src.size();
         let dst_len = dst.size();
         assert_eq!(src_len, dst_len, "column size mismatch");
@@ -132,6 +204,7 @@ impl TracesBuilder {
         FinalizedTraces {
             cols,
             log_size: self.log_size,
+            num_real_rows: self.num_real_rows,
         }
     }
 }
@@ -141,14 +214,45 @@ impl TracesBuilder {
 pub struct FinalizedTraces {
     cols: Vec<BaseColumn>,
     log_size: u32,
+    num_real_rows: usize,
 }
 
 impl FinalizedTraces {
+    /// Wraps [`GlobalColumn::COLUMNS_NUM`] columns in bit-reversed order, each one `2.pow(log_size)` in length.
+    ///
+    /// Padding rows are counted from the `IsPadding` column.
+    pub(crate) fn from_columns(cols: Vec<BaseColumn>, log_size: u32) -> Self {
+        assert_eq!(
+            cols.len(),
+            GlobalColumn::COLUMNS_NUM,
+            "column count mismatch"
+        );
+        let num_padded_rows = cols[GlobalColumn::from(Column::IsPadding).offset()]
+            .as_slice()
+            .iter()
+            .filter(|is_padding| !is_padding.is_zero())
+            .count();
+        Self {
+            cols,
+            log_size,
+            num_real_rows: (1 << log_size) - num_padded_rows,
+        }
+    }
+
     pub fn log_size(&self) -> u32 {
         self.log_size
     }
 
-    pub fn get_base_column<const N: usize>(&self, col: Column) -> [&BaseColumn; N] {
+    /// Returns the number of padding rows following the executed steps.
+    pub fn num_padded_rows(&self) -> usize {
+        (1 << self.log_size) - self.num_real_rows
+    }
+
+    pub fn get_base_column<const N: usize>(
+        &self,
+        col: impl Into<GlobalColumn>,
+    ) -> [&BaseColumn; N] {
+        let col = col.into();
         assert_eq!(col.size(), N, "column size mismatch");
         std::array::from_fn(|i| &self.cols[col.offset() + i])
     }
@@ -0,0 +1,231 @@
+//! Evaluation of machine constraints directly on trace values, see [`Machine::check_trace`].
+//!
+//! [`CheckEvaluator`] reads masks from the trace like stwo's `AssertEvaluator`, but records the first unsatisfied
+//! constraint of a row together with the chip that added it instead of panicking. Logup constraints go through the
+//! same path, a failure there is reported without a constraint index. Fractions of the main component are paired
+//! the same way as when proving, see [`finalize_logup`].
+//!
+//! [`Machine::check_trace`]: crate::machine::Machine::check_trace
+
+use std::ops::Mul;
+
+use num_traits::{One, Zero};
+use stwo::core::{
+    fields::{
+        m31::BaseField,
+        qm31::{SecureField, SECURE_EXTENSION_DEGREE},
+    },
+    fraction::Fraction,
+    pcs::TreeVec,
+};
+use stwo_constraint_framework::{
+    logup_proxy, EvalAtRow, FrameworkEval, LogupAtRow, INTERACTION_TRACE_IDX,
+};
+
+use crate::{
+    components::AllLookupElements,
+    error::TraceCheckError,
+    extensions::ExtensionsConfig,
+    trace::{eval::TraceEval, logup::finalize_logup},
+    traits::MachineChip,
+};
+
+/// First unsatisfied constraint of a row, `chip` is `None` for logup constraints of the main component.
+struct Failure {
+    chip: Option<&'static str>,
+    constraint: usize,
+}
+
+impl Failure {
+    fn into_error(self, row: usize) -> TraceCheckError {
+        match self.chip {
+            Some(chip) => TraceCheckError::ConstraintNotSatisfied {
+                chip,
+                row,
+                constraint: self.constraint,
+            },
+            None => TraceCheckError::LogupNotSatisfied { row },
+        }
+    }
+}
+
+struct CheckEvaluator<'a> {
+    trace: &'a TreeVec<Vec<Vec<BaseField>>>,
+    col_index: Vec<usize>,
+    row: usize,
+    logup: LogupAtRow<Self>,
+    chip: Option<&'static str>,
+    constraint: usize,
+    failure: Option<Failure>,
+}
+
+impl<'a> CheckEvaluator<'a> {
+    fn new(
+        trace: &'a TreeVec<Vec<Vec<BaseField>>>,
+        row: usize,
+        log_size: u32,
+        claimed_sum: SecureField,
+    ) -> Self {
+        Self {
+            trace,
+            col_index: vec![0; trace.len()],
+            row,
+            logup: LogupAtRow::new(INTERACTION_TRACE_IDX, claimed_sum, log_size),
+            chip: None,
+            constraint: 0,
+            failure: None,
+        }
+    }
+
+    /// Attributes the following constraints to `chip`, or to the logup of the main component if `None`.
+    fn enter_chip(&mut self, chip: Option<&'static str>) {
+        self.chip = chip;
+        self.constraint = 0;
+    }
+}
+
+impl EvalAtRow for CheckEvaluator<'_> {
+    type F = BaseField;
+    type EF = SecureField;
+
+    fn next_interaction_mask<const N: usize>(
+        &mut self,
+        interaction: usize,
+        offsets: [isize; N],
+    ) -> [Self::F; N] {
+        let column = &self.trace[interaction][self.col_index[interaction]];
+        self.col_index[interaction] += 1;
+        // The mask row might wrap around the column size.
+        offsets.map(|offset| {
+            column[(self.row as isize + offset).rem_euclid(column.len() as isize) as usize]
+        })
+    }
+
+    fn add_constraint<G>(&mut self, constraint: G)
+    where
+        Self::EF: Mul<G, Output = Self::EF> + From<G>,
+    {
+        let value = SecureField::one() * constraint;
+        if !value.is_zero() && self.failure.is_none() {
+            self.failure = Some(Failure {
+                chip: self.chip,
+                constraint: self.constraint,
+            });
+        }
+        self.constraint += 1;
+    }
+
+    fn combine_ef(values: [Self::F; SECURE_EXTENSION_DEGREE]) -> Self::EF {
+        SecureField::from_m31_array(values)
+    }
+
+    logup_proxy!();
+}
+
+/// Evaluates constraints of chips `C` on every row of `trace`, columns are given in row order.
+pub(crate) fn check_constraints<C: MachineChip>(
+    trace: &TreeVec<Vec<Vec<BaseField>>>,
+    log_size: u32,
+    lookup_elements: &AllLookupElements,
+    claimed_sum: SecureField,
+    config: &ExtensionsConfig,
+) -> Result<(), TraceCheckError> {
+    for row in 0..1 << log_size {
+        let mut eval = CheckEvaluator::new(trace, row, log_size, claimed_sum);
+        let trace_eval = TraceEval::new(&mut eval);
+        C::add_constraints_by_chip(
+            &mut eval,
+            &trace_eval,
+            lookup_elements,
+            config,
+            &mut |eval, chip| eval.enter_chip(Some(chip)),
+        );
+        if !lookup_elements.is_empty() {
+            eval.enter_chip(None);
+            finalize_logup(&mut eval);
+        }
+
+        if let Some(failure) = eval.failure {
+            return Err(failure.into_error(row));
+        }
+    }
+    Ok(())
+}
+
+/// Evaluates constraints of the extension component `component`, named `name`, on every row of `trace`. Its logup
+/// constraints are attributed to the extension.
+pub(crate) fn check_component_constraints(
+    component: &impl FrameworkEval,
+    name: &'static str,
+    trace: &TreeVec<Vec<Vec<BaseField>>>,
+    log_size: u32,
+    claimed_sum: SecureField,
+) -> Result<(), TraceCheckError> {
+    for row in 0..1 << log_size {
+        let mut eval = CheckEvaluator::new(trace, row, log_size, claimed_sum);
+        eval.enter_chip(Some(name));
+        let eval = component.evaluate(eval);
+
+        if let Some(failure) = eval.failure {
+            return Err(failure.into_error(row));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG_SIZE: u32 = 4;
+
+    /// Constrains the first two columns of the main trace to be equal.
+    struct EqualColumns;
+
+    impl FrameworkEval for EqualColumns {
+        fn log_size(&self) -> u32 {
+            LOG_SIZE
+        }
+
+        fn max_constraint_log_degree_bound(&self) -> u32 {
+            LOG_SIZE + 1
+        }
+
+        fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+            let a = eval.next_trace_mask();
+            let b = eval.next_trace_mask();
+            eval.add_constraint(a - b);
+            eval
+        }
+    }
+
+    #[test]
+    fn reports_unsatisfied_component_constraint() {
+        let column: Vec<BaseField> = (0..1 << LOG_SIZE).map(BaseField::from).collect();
+        let trace = |other: Vec<BaseField>| {
+            TreeVec::new(vec![vec![], vec![column.clone(), other], vec![], vec![]])
+        };
+        let check = |trace| {
+            check_component_constraints(
+                &EqualColumns,
+                "EqualColumns",
+                &trace,
+                LOG_SIZE,
+                SecureField::zero(),
+            )
+        };
+
+        check(trace(column.clone())).unwrap();
+
+        let mut other = column.clone();
+        other[5] += BaseField::one();
+        match check(trace(other)) {
+            Err(TraceCheckError::ConstraintNotSatisfied {
+                chip: "EqualColumns",
+                row: 5,
+                constraint: 0,
+            }) => {}
+            result => panic!("unexpected result: {result:?}"),
+        }
+    }
+}
@@ -1,5 +1,8 @@
+use std::ops::Range;
+
 use impl_trait_for_tuples::impl_for_tuples;
 
+use nexus_vm::riscv::BuiltinOpcode;
 use num_traits::Zero;
 use stwo::{
     core::{
@@ -12,14 +15,16 @@ use stwo::{
         poly::{circle::CircleEvaluation, BitReversedOrder},
     },
 };
-use stwo_constraint_framework::{EvalAtRow, LogupTraceGenerator};
+use stwo_constraint_framework::EvalAtRow;
 
 use crate::{
     components::AllLookupElements,
+    description::ChipDescription,
     extensions::ExtensionsConfig,
     trace::{
-        eval::TraceEval, preprocessed::PreprocessedTraces, program_trace::ProgramTraces,
-        sidenote::SideNote, FinalizedTraces, ProgramStep, TracesBuilder,
+        eval::TraceEval, logup::LogupTraceBuilder, preprocessed::PreprocessedTraces,
+        program_trace::ProgramTraces, sidenote::SideNote, FinalizedTraces, ProgramStep,
+        TracesBuilder,
     },
 };
 
@@ -29,7 +34,55 @@ pub trait ExecuteChip {
     fn execute(program_step: &ProgramStep) -> Self::ExecutionResult;
 }
 
+/// Side-note state that chips update row by row while filling the main trace, see
+/// [`MachineChip::collect_side_note_state`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SideNoteState {
+    /// The last access of every byte of the read-write memory.
+    ReadWriteMemory,
+}
+
 pub trait MachineChip {
+    /// Name of the chip, identifying it in [`Machine::components_id`](crate::machine::Machine::components_id) and in
+    /// diagnostics. It must not change as long as the constraints of the chip don't.
+    ///
+    /// Tuples have no name of their own, use [`Self::collect_names`] to gather the names of their elements.
+    const NAME: &'static str;
+
+    /// Appends the names of the chip to `names`, see [`Self::NAME`].
+    fn collect_names(names: &mut Vec<&'static str>) {
+        names.push(Self::NAME);
+    }
+
+    /// Opcodes whose execution is constrained by this chip.
+    ///
+    /// Instruction chips list the opcodes they handle, other chips leave the default empty list. Tuples don't list
+    /// opcodes themselves, use [`Self::collect_supported_opcodes`] to gather them from their elements.
+    fn supported_opcodes() -> &'static [BuiltinOpcode] {
+        &[]
+    }
+
+    /// Appends opcodes supported by the chip to `opcodes`, skipping those already present.
+    fn collect_supported_opcodes(opcodes: &mut Vec<BuiltinOpcode>) {
+        for opcode in Self::supported_opcodes() {
+            if !opcodes.contains(opcode) {
+                opcodes.push(*opcode);
+            }
+        }
+    }
+
+    /// Describes the constraints added by [`Self::add_constraints`] and the lookups of the chip, if documented.
+    ///
+    /// Tuples don't describe themselves, use [`Self::collect_descriptions`] to gather descriptions of their elements.
+    fn describe() -> Option<ChipDescription> {
+        None
+    }
+
+    /// Appends the description of the chip to `descriptions`, if it has one.
+    fn collect_descriptions(descriptions: &mut Vec<ChipDescription>) {
+        descriptions.extend(Self::describe());
+    }
+
     /// Called on each row during main trace generation.
     fn fill_main_trace(
         traces: &mut TracesBuilder,
@@ -39,6 +92,33 @@ pub trait MachineChip {
         config: &ExtensionsConfig,
     );
 
+    /// Called on chunks of consecutive rows during main trace generation, `steps[i]` is the step of row
+    /// `rows.start + i`.
+    ///
+    /// The default implementation calls [`Self::fill_main_trace`] on each row, chips override it to write whole
+    /// column slices at once. Tuples fill the chunk one element after another, which produces the same trace as
+    /// filling row by row as long as no chip reads side-note state that a later chip of the tuple writes on an
+    /// earlier row, chips declare such state with [`Self::collect_side_note_state`]. Chips reading columns of
+    /// earlier chips on the same row are unaffected.
+    fn fill_main_trace_chunk(
+        traces: &mut TracesBuilder,
+        rows: Range<usize>,
+        steps: &[Option<ProgramStep>],
+        side_note: &mut SideNote,
+        config: &ExtensionsConfig,
+    ) {
+        assert_eq!(rows.len(), steps.len(), "chunk size mismatch");
+        for (row_idx, vm_step) in rows.zip(steps) {
+            Self::fill_main_trace(traces, row_idx, vm_step, side_note, config);
+        }
+    }
+
+    /// Appends the side-note state that the chip reads and updates row by row while filling the main trace.
+    ///
+    /// The main trace is filled a single row at a time if two chips of the machine update the same state, so that
+    /// neither of them observes the updates the other one makes on later rows.
+    fn collect_side_note_state(_states: &mut Vec<SideNoteState>, _config: &ExtensionsConfig) {}
+
     /// Called on each row during constraint evaluation.
     ///
     /// This method **should not** read masks from `eval`.
@@ -49,12 +129,26 @@ pub trait MachineChip {
         config: &ExtensionsConfig,
     );
 
+    /// Same as [`Self::add_constraints`], calling `on_chip` with the name of every chip before its constraints.
+    ///
+    /// Tuples forward the call to their elements, so that constraints can be attributed to individual chips.
+    fn add_constraints_by_chip<E: EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &TraceEval<E>,
+        lookup_elements: &AllLookupElements,
+        config: &ExtensionsConfig,
+        on_chip: &mut dyn FnMut(&mut E, &'static str),
+    ) {
+        on_chip(eval, Self::NAME);
+        Self::add_constraints(eval, trace_eval, lookup_elements, config);
+    }
+
     /// Called just once for generating the interaction trace.
     ///
     /// The signature of this method is intentionally similar to `gen_interaction_trace()` in stwo examples.
     /// This method isn't called row-by-row because stwo logup library fills 16 rows of the interaction trace at a time.
     fn fill_interaction_trace(
-        _logup_trace_gen: &mut LogupTraceGenerator,
+        _logup_trace_gen: &mut LogupTraceBuilder,
         _original_traces: &FinalizedTraces,
         _preprocessed_trace: &PreprocessedTraces,
         _program_traces: &ProgramTraces,
@@ -83,6 +177,20 @@ pub trait MachineChip {
 
 #[impl_for_tuples(1, 28)]
 impl MachineChip for Tuple {
+    const NAME: &'static str = "";
+
+    fn collect_names(names: &mut Vec<&'static str>) {
+        for_tuples!( #( Tuple::collect_names(names); )* );
+    }
+
+    fn collect_supported_opcodes(opcodes: &mut Vec<BuiltinOpcode>) {
+        for_tuples!( #( Tuple::collect_supported_opcodes(opcodes); )* );
+    }
+
+    fn collect_descriptions(descriptions: &mut Vec<ChipDescription>) {
+        for_tuples!( #( Tuple::collect_descriptions(descriptions); )* );
+    }
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
@@ -93,6 +201,20 @@ impl MachineChip for Tuple {
         for_tuples!( #( Tuple::fill_main_trace(traces, row_idx, vm_step, side_note, config); )* );
     }
 
+    fn fill_main_trace_chunk(
+        traces: &mut TracesBuilder,
+        rows: Range<usize>,
+        steps: &[Option<ProgramStep>],
+        side_note: &mut SideNote,
+        config: &ExtensionsConfig,
+    ) {
+        for_tuples!( #( Tuple::fill_main_trace_chunk(traces, rows.clone(), steps, side_note, config); )* );
+    }
+
+    fn collect_side_note_state(states: &mut Vec<SideNoteState>, config: &ExtensionsConfig) {
+        for_tuples!( #( Tuple::collect_side_note_state(states, config); )* );
+    }
+
     fn add_constraints<E: EvalAtRow>(
         eval: &mut E,
         trace_eval: &TraceEval<E>,
@@ -102,8 +224,18 @@ impl MachineChip for Tuple {
         for_tuples!( #( Tuple::add_constraints(eval, trace_eval, lookup_elements, config); )* );
     }
 
+    fn add_constraints_by_chip<E: EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &TraceEval<E>,
+        lookup_elements: &AllLookupElements,
+        config: &ExtensionsConfig,
+        on_chip: &mut dyn FnMut(&mut E, &'static str),
+    ) {
+        for_tuples!( #( Tuple::add_constraints_by_chip(eval, trace_eval, lookup_elements, config, on_chip); )* );
+    }
+
     fn fill_interaction_trace(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         preprocessed_traces: &PreprocessedTraces,
         program_traces: &ProgramTraces,
@@ -133,7 +265,7 @@ pub fn generate_interaction_trace<C: MachineChip>(
     if lookup_elements.is_empty() {
         return (ColumnVec::new(), SecureField::zero());
     }
-    let mut logup_trace_gen = LogupTraceGenerator::new(original_traces.log_size());
+    let mut logup_trace_gen = LogupTraceBuilder::new(original_traces.log_size());
     C::fill_interaction_trace(
         &mut logup_trace_gen,
         original_traces,
@@ -0,0 +1,307 @@
+//! Recording of the Fiat-Shamir transcript.
+//!
+//! The prover and the verifier talk to the Fiat-Shamir channel through [`TranscriptChannel`], a thin wrapper of the
+//! Blake2s channel that forwards every call and, when recording, appends it to a [`TranscriptLog`]. Recording never
+//! changes what is mixed or drawn, proofs are the same with and without it.
+//!
+//! Entries are labeled with the stage of the protocol set by [`Machine`](crate::machine::Machine), and with the
+//! channel operation. Operations performed by the stwo prover and verifier, from the composition commitment on, all
+//! belong to the [`STARK_PROOF`] stage, in the order of the protocol: the composition random coefficient, the root
+//! of the composition polynomial, the OODS point, the sampled values, the FRI random coefficient, a root and a
+//! folding alpha per FRI layer, the last FRI layer, the proof of work nonce and the query positions.
+//!
+//! The log serializes to JSON, so that a verifier written in another language can be checked against it entry by
+//! entry.
+//!
+//! Grinding for the proof of work also goes through this channel, with the `parallel-grinding` feature it is done
+//! by [`grind_parallel`] instead of the single-threaded grinder of stwo.
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+use stwo::{
+    core::{
+        channel::{Blake2sChannel, Channel, MerkleChannel},
+        fields::qm31::SecureField,
+        vcs::{
+            blake2_merkle::{Blake2sMerkleChannel, Blake2sMerkleHasher},
+            ops::MerkleHasher,
+        },
+    },
+    prover::{backend::simd::SimdBackend, backend::BackendForChannel, proof_of_work::GrindOps},
+};
+
+pub const CONTEXT_ID: &str = "context id";
+pub const ASSOCIATED_DATA: &str = "associated data";
+pub const COMPONENTS_ID: &str = "components id";
+pub const SALT: &str = "salt";
+pub const LOG_SIZES: &str = "log sizes";
+pub const PREPROCESSED_TRACE: &str = "preprocessed trace";
+pub const PROGRAM_TRACE: &str = "program trace";
+pub const MAIN_TRACE: &str = "main trace";
+pub const LOOKUP_ELEMENTS: &str = "lookup elements";
+pub const INTERACTION_TRACE: &str = "interaction trace";
+pub const CLAIMED_SUMS: &str = "claimed sums";
+pub const STARK_PROOF: &str = "stark proof";
+
+/// A single interaction with the channel.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum TranscriptOp {
+    /// Root of a Merkle commitment, hex-encoded.
+    MixRoot {
+        root: String,
+    },
+    MixU32s {
+        values: Vec<u32>,
+    },
+    MixU64 {
+        value: u64,
+    },
+    /// Secure field elements, as their four base field coordinates.
+    MixFelts {
+        felts: Vec<[u32; 4]>,
+    },
+    DrawFelts {
+        felts: Vec<[u32; 4]>,
+    },
+    DrawU32s {
+        values: Vec<u32>,
+    },
+}
+
+/// An interaction with the channel, together with the stage of the protocol it belongs to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptEntry {
+    pub stage: String,
+    #[serde(flatten)]
+    pub op: TranscriptOp,
+}
+
+/// Interactions with the Fiat-Shamir channel in the order they happened.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptLog {
+    pub entries: Vec<TranscriptEntry>,
+}
+
+impl TranscriptLog {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("transcript is serializable")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Returns the index of the first entry that differs between the two logs, if any. A log that is a strict
+    /// prefix of the other differs at its length.
+    pub fn first_mismatch(&self, other: &Self) -> Option<usize> {
+        let common_len = self.entries.len().min(other.entries.len());
+        (0..common_len)
+            .find(|&idx| self.entries[idx] != other.entries[idx])
+            .or((self.entries.len() != other.entries.len()).then_some(common_len))
+    }
+}
+
+/// Blake2s channel recording its interactions, see the [module documentation](self).
+#[derive(Debug, Clone, Default)]
+pub struct TranscriptChannel {
+    inner: Blake2sChannel,
+    stage: &'static str,
+    log: Option<TranscriptLog>,
+}
+
+impl TranscriptChannel {
+    /// Creates a channel that records its interactions.
+    pub fn recording() -> Self {
+        Self {
+            log: Some(TranscriptLog::default()),
+            ..Self::default()
+        }
+    }
+
+    /// Labels the following interactions with `stage`.
+    pub(crate) fn set_stage(&mut self, stage: &'static str) {
+        self.stage = stage;
+    }
+
+    /// Returns the recorded interactions, empty unless the channel was created with [`Self::recording`].
+    pub fn into_log(self) -> TranscriptLog {
+        self.log.unwrap_or_default()
+    }
+
+    fn record(&mut self, op: impl FnOnce() -> TranscriptOp) {
+        if let Some(log) = &mut self.log {
+            log.entries.push(TranscriptEntry {
+                stage: self.stage.to_string(),
+                op: op(),
+            });
+        }
+    }
+}
+
+fn felts_to_u32s(felts: &[SecureField]) -> Vec<[u32; 4]> {
+    felts
+        .iter()
+        .map(|felt| felt.to_m31_array().map(|coordinate| coordinate.0))
+        .collect()
+}
+
+impl Channel for TranscriptChannel {
+    const BYTES_PER_HASH: usize = Blake2sChannel::BYTES_PER_HASH;
+
+    fn trailing_zeros(&self) -> u32 {
+        self.inner.trailing_zeros()
+    }
+
+    fn mix_u32s(&mut self, data: &[u32]) {
+        self.record(|| TranscriptOp::MixU32s {
+            values: data.to_vec(),
+        });
+        self.inner.mix_u32s(data);
+    }
+
+    fn mix_felts(&mut self, felts: &[SecureField]) {
+        self.record(|| TranscriptOp::MixFelts {
+            felts: felts_to_u32s(felts),
+        });
+        self.inner.mix_felts(felts);
+    }
+
+    fn mix_u64(&mut self, value: u64) {
+        self.record(|| TranscriptOp::MixU64 { value });
+        self.inner.mix_u64(value);
+    }
+
+    fn draw_secure_felt(&mut self) -> SecureField {
+        let felt = self.inner.draw_secure_felt();
+        self.record(|| TranscriptOp::DrawFelts {
+            felts: felts_to_u32s(&[felt]),
+        });
+        felt
+    }
+
+    fn draw_secure_felts(&mut self, n_felts: usize) -> Vec<SecureField> {
+        let felts = self.inner.draw_secure_felts(n_felts);
+        self.record(|| TranscriptOp::DrawFelts {
+            felts: felts_to_u32s(&felts),
+        });
+        felts
+    }
+
+    fn draw_u32s(&mut self) -> Vec<u32> {
+        let values = self.inner.draw_u32s();
+        self.record(|| TranscriptOp::DrawU32s {
+            values: values.clone(),
+        });
+        values
+    }
+}
+
+/// Merkle channel of [`TranscriptChannel`], committing with Blake2s like [`Blake2sMerkleChannel`].
+#[derive(Debug, Default)]
+pub struct TranscriptMerkleChannel;
+
+impl MerkleChannel for TranscriptMerkleChannel {
+    type C = TranscriptChannel;
+    type H = Blake2sMerkleHasher;
+
+    fn mix_root(channel: &mut Self::C, root: <Self::H as MerkleHasher>::Hash) {
+        channel.record(|| TranscriptOp::MixRoot {
+            root: root.to_string(),
+        });
+        Blake2sMerkleChannel::mix_root(&mut channel.inner, root);
+    }
+}
+
+impl GrindOps<TranscriptChannel> for SimdBackend {
+    fn grind(channel: &TranscriptChannel, pow_bits: u32) -> u64 {
+        if cfg!(feature = "parallel-grinding") {
+            grind_parallel(&channel.inner, pow_bits)
+        } else {
+            <SimdBackend as GrindOps<Blake2sChannel>>::grind(&channel.inner, pow_bits)
+        }
+    }
+}
+
+/// Nonces searched by [`grind_parallel`] before checking whether one was found.
+const GRIND_BATCH_SIZE: u64 = 1 << 16;
+
+/// Returns the smallest nonce that, mixed into `channel`, leaves at least `pow_bits` trailing zeros, which is what
+/// the verifier checks. Batches of nonces are searched on the rayon thread pool.
+pub fn grind_parallel(channel: &Blake2sChannel, pow_bits: u32) -> u64 {
+    (0..)
+        .step_by(GRIND_BATCH_SIZE as usize)
+        .find_map(|start: u64| {
+            (start..start + GRIND_BATCH_SIZE)
+                .into_par_iter()
+                .find_first(|&nonce| {
+                    let mut channel = channel.clone();
+                    channel.mix_u64(nonce);
+                    channel.trailing_zeros() >= pow_bits
+                })
+        })
+        .expect("nonce space is exhausted")
+}
+
+impl BackendForChannel<TranscriptMerkleChannel> for SimdBackend {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recording_is_observational() {
+        let mut plain = Blake2sChannel::default();
+        let mut channel = TranscriptChannel::recording();
+        channel.set_stage(ASSOCIATED_DATA);
+        for value in [1, 2, 3] {
+            plain.mix_u64(value);
+            channel.mix_u64(value);
+        }
+        channel.set_stage(LOOKUP_ELEMENTS);
+        assert_eq!(channel.draw_secure_felts(2), plain.draw_secure_felts(2));
+        assert_eq!(channel.draw_u32s(), plain.draw_u32s());
+
+        let log = channel.into_log();
+        assert_eq!(log.entries.len(), 5);
+        assert_eq!(log.entries[0].stage, ASSOCIATED_DATA);
+        assert_eq!(log.entries[3].stage, LOOKUP_ELEMENTS);
+        assert_eq!(TranscriptLog::from_json(&log.to_json()).unwrap(), log);
+    }
+
+    #[test]
+    fn grind_parallel_finds_smallest_nonce() {
+        const POW_BITS: u32 = 10;
+        let mut channel = Blake2sChannel::default();
+        channel.mix_u64(42);
+        let passes = |nonce| {
+            let mut channel = channel.clone();
+            channel.mix_u64(nonce);
+            channel.trailing_zeros() >= POW_BITS
+        };
+
+        let nonce = grind_parallel(&channel, POW_BITS);
+        assert!(passes(nonce));
+        assert!((0..nonce).all(|nonce| !passes(nonce)));
+    }
+
+    #[test]
+    fn first_mismatch() {
+        let entry = |value| TranscriptEntry {
+            stage: STARK_PROOF.to_string(),
+            op: TranscriptOp::MixU64 { value },
+        };
+        let log = TranscriptLog {
+            entries: vec![entry(1), entry(2)],
+        };
+        assert_eq!(log.first_mismatch(&log), None);
+
+        let mut other = log.clone();
+        other.entries[1] = entry(3);
+        assert_eq!(log.first_mismatch(&other), Some(1));
+
+        other.entries.truncate(1);
+        assert_eq!(log.first_mismatch(&other), Some(1));
+        assert_eq!(other.first_mismatch(&log), Some(1));
+    }
+}
@@ -5,11 +5,12 @@ use stwo::{core::fields::m31::BaseField, prover::backend::simd::m31::PackedBaseF
 use stwo_constraint_framework::EvalAtRow;
 
 use crate::{
+    chips::SyscallColumns,
     column::Column::{
         self, ImmC, IsAdd, IsAnd, IsAuipc, IsBeq, IsBge, IsBgeu, IsBlt, IsBltu, IsBne,
         IsCustomKeccak, IsDiv, IsDivu, IsEbreak, IsEcall, IsJal, IsJalr, IsLb, IsLbu, IsLh, IsLhu,
-        IsLui, IsLw, IsMul, IsMulh, IsMulhsu, IsMulhu, IsOr, IsRem, IsRemu, IsSb, IsSh, IsSll,
-        IsSlt, IsSltu, IsSra, IsSrl, IsSub, IsSw, IsXor,
+        IsLui, IsLw, IsMul, IsMulh, IsMulhsu, IsMulhu, IsNop, IsOr, IsRem, IsRemu, IsSb, IsSh,
+        IsSll, IsSlt, IsSltu, IsSra, IsSrl, IsSub, IsSw, IsXor,
     },
     trace::{eval::trace_eval, eval::TraceEval, FinalizedTraces, TracesBuilder},
 };
@@ -307,14 +308,16 @@ impl VirtualColumn<1> for IsPcIncremented {
         let [is_type_u] = IsTypeU::read_from_traces_builder(traces, row_idx);
         let [is_type_sys] = IsTypeSys::read_from_traces_builder(traces, row_idx);
         let [is_custom_keccak] = traces.column(row_idx, IsCustomKeccak);
+        let [is_nop] = traces.column(row_idx, IsNop);
 
-        let [is_sys_halt] = traces.column(row_idx, Column::IsSysHalt);
+        let [is_sys_halt] = traces.column(row_idx, SyscallColumns::IsSysHalt);
         let ret = is_alu
             + is_load
             + is_type_s
             + is_type_sys * (BaseField::one() - is_sys_halt)
             + is_type_u
-            + is_custom_keccak;
+            + is_custom_keccak
+            + is_nop;
         [ret]
     }
     fn read_from_finalized_traces(
@@ -327,14 +330,16 @@ impl VirtualColumn<1> for IsPcIncremented {
         let is_type_u = IsTypeU::read_from_finalized_traces(traces, vec_idx)[0];
         let is_type_sys = IsTypeSys::read_from_finalized_traces(traces, vec_idx)[0];
 
-        let is_sys_halt = traces.get_base_column::<1>(Column::IsSysHalt)[0].data[vec_idx];
+        let is_sys_halt = traces.get_base_column::<1>(SyscallColumns::IsSysHalt)[0].data[vec_idx];
         let is_custom_keccak = traces.get_base_column::<1>(Column::IsCustomKeccak)[0].data[vec_idx];
+        let is_nop = traces.get_base_column::<1>(Column::IsNop)[0].data[vec_idx];
         let ret = is_alu
             + is_load
             + is_type_s
             + is_type_sys * (PackedBaseField::one() - is_sys_halt)
             + is_type_u
-            + is_custom_keccak;
+            + is_custom_keccak
+            + is_nop;
         [ret]
     }
     fn eval<E: EvalAtRow>(trace_eval: &TraceEval<E>) -> [E::F; 1] {
@@ -344,14 +349,16 @@ impl VirtualColumn<1> for IsPcIncremented {
         let [is_type_u] = IsTypeU::eval(trace_eval);
         let [is_type_sys] = IsTypeSys::eval(trace_eval);
 
-        let [is_sys_halt] = trace_eval!(trace_eval, Column::IsSysHalt);
+        let [is_sys_halt] = trace_eval!(trace_eval, SyscallColumns::IsSysHalt);
         let [is_custom_keccak] = trace_eval!(trace_eval, Column::IsCustomKeccak);
+        let [is_nop] = trace_eval!(trace_eval, Column::IsNop);
         let ret = is_alu
             + is_load
             + is_type_s
             + is_type_sys * (E::F::one() - is_sys_halt)
             + is_type_u
-            + is_custom_keccak;
+            + is_custom_keccak
+            + is_nop;
         [ret]
     }
 }
@@ -376,6 +383,32 @@ impl VirtualColumnForSum for OpBFlag {
     }
 }
 
+/// A virtual column that regulates the second register access
+///
+/// Type R instructions read rs2 and system calls read their first argument through the second access.
+pub(crate) struct Reg2Accessed;
+
+impl VirtualColumn<1> for Reg2Accessed {
+    fn read_from_traces_builder(traces: &TracesBuilder, row_idx: usize) -> [BaseField; 1] {
+        let [is_type_r] = IsTypeR::read_from_traces_builder(traces, row_idx);
+        let [is_type_sys] = IsTypeSys::read_from_traces_builder(traces, row_idx);
+        [is_type_r + is_type_sys]
+    }
+    fn read_from_finalized_traces(
+        traces: &FinalizedTraces,
+        vec_idx: usize,
+    ) -> [PackedBaseField; 1] {
+        let [is_type_r] = IsTypeR::read_from_finalized_traces(traces, vec_idx);
+        let [is_type_sys] = IsTypeSys::read_from_finalized_traces(traces, vec_idx);
+        [is_type_r + is_type_sys]
+    }
+    fn eval<E: EvalAtRow>(trace_eval: &TraceEval<E>) -> [E::F; 1] {
+        let [is_type_r] = IsTypeR::eval(trace_eval);
+        let [is_type_sys] = IsTypeSys::eval(trace_eval);
+        [is_type_r + is_type_sys]
+    }
+}
+
 /// A virtual column that regulates the third register access
 ///
 /// The third register access is done using ValueAEffective and Reg3Address.
@@ -386,7 +419,9 @@ pub(crate) struct Reg3Accessed;
 // reg3_accessed =
 // (is_type_s + is_type_b) +   // When reading from rs1
 // (is_type_r + is_type_i + is_type_u + is_type_j)  + // For instructions with rd
-// (is_type_sys)·(is_sys_priv_input + is_sys_heap_reset + is_sys_stack_reset) // For some syscalls
+// (is_type_sys)·(is_sys_priv_input + is_sys_heap_reset + is_sys_stack_reset
+//   + is_sys_hint + is_sys_read_hint + is_sys_read_cycle_counter + is_sys_priv_input_word) // For syscalls writing a register
+// + (is_type_sys)·(is_sys_commit) // Reading a1, written back unchanged
 impl VirtualColumn<1> for Reg3Accessed {
     fn read_from_traces_builder(traces: &TracesBuilder, row_idx: usize) -> [BaseField; 1] {
         let [is_type_s] = IsTypeS::read_from_traces_builder(traces, row_idx);
@@ -396,9 +431,15 @@ impl VirtualColumn<1> for Reg3Accessed {
         let [is_type_u] = IsTypeU::read_from_traces_builder(traces, row_idx);
         let [is_type_j] = IsTypeJ::read_from_traces_builder(traces, row_idx);
         let [is_type_sys] = IsTypeSys::read_from_traces_builder(traces, row_idx);
-        let [is_sys_priv_input] = traces.column(row_idx, Column::IsSysPrivInput);
-        let [is_sys_heap_reset] = traces.column(row_idx, Column::IsSysHeapReset);
-        let [is_sys_stack_reset] = traces.column(row_idx, Column::IsSysStackReset);
+        let [is_sys_priv_input] = traces.column(row_idx, SyscallColumns::IsSysPrivInput);
+        let [is_sys_heap_reset] = traces.column(row_idx, SyscallColumns::IsSysHeapReset);
+        let [is_sys_stack_reset] = traces.column(row_idx, SyscallColumns::IsSysStackReset);
+        let [is_sys_hint] = traces.column(row_idx, SyscallColumns::IsSysHint);
+        let [is_sys_read_hint] = traces.column(row_idx, SyscallColumns::IsSysReadHint);
+        let [is_sys_read_cycle_counter] =
+            traces.column(row_idx, SyscallColumns::IsSysReadCycleCounter);
+        let [is_sys_priv_input_word] = traces.column(row_idx, SyscallColumns::IsSysPrivInputWord);
+        let [is_sys_commit] = traces.column(row_idx, SyscallColumns::IsSysCommit);
 
         let ret = is_type_s
             + is_type_b
@@ -406,7 +447,15 @@ impl VirtualColumn<1> for Reg3Accessed {
             + is_type_i
             + is_type_u
             + is_type_j
-            + is_type_sys * (is_sys_priv_input + is_sys_heap_reset + is_sys_stack_reset);
+            + is_type_sys
+                * (is_sys_priv_input
+                    + is_sys_heap_reset
+                    + is_sys_stack_reset
+                    + is_sys_hint
+                    + is_sys_read_hint
+                    + is_sys_read_cycle_counter
+                    + is_sys_priv_input_word
+                    + is_sys_commit);
         [ret]
     }
     fn read_from_finalized_traces(
@@ -421,18 +470,35 @@ impl VirtualColumn<1> for Reg3Accessed {
         let is_type_j = IsTypeJ::read_from_finalized_traces(traces, vec_idx)[0];
         let is_type_sys = IsTypeSys::read_from_finalized_traces(traces, vec_idx)[0];
         let is_sys_priv_input =
-            traces.get_base_column::<1>(Column::IsSysPrivInput)[0].data[vec_idx];
+            traces.get_base_column::<1>(SyscallColumns::IsSysPrivInput)[0].data[vec_idx];
         let is_sys_heap_reset =
-            traces.get_base_column::<1>(Column::IsSysHeapReset)[0].data[vec_idx];
+            traces.get_base_column::<1>(SyscallColumns::IsSysHeapReset)[0].data[vec_idx];
         let is_sys_stack_reset =
-            traces.get_base_column::<1>(Column::IsSysStackReset)[0].data[vec_idx];
+            traces.get_base_column::<1>(SyscallColumns::IsSysStackReset)[0].data[vec_idx];
+        let is_sys_hint = traces.get_base_column::<1>(SyscallColumns::IsSysHint)[0].data[vec_idx];
+        let is_sys_read_hint =
+            traces.get_base_column::<1>(SyscallColumns::IsSysReadHint)[0].data[vec_idx];
+        let is_sys_read_cycle_counter =
+            traces.get_base_column::<1>(SyscallColumns::IsSysReadCycleCounter)[0].data[vec_idx];
+        let is_sys_priv_input_word =
+            traces.get_base_column::<1>(SyscallColumns::IsSysPrivInputWord)[0].data[vec_idx];
+        let is_sys_commit =
+            traces.get_base_column::<1>(SyscallColumns::IsSysCommit)[0].data[vec_idx];
         let ret = is_type_s
             + is_type_b
             + is_type_r
             + is_type_i
             + is_type_u
             + is_type_j
-            + is_type_sys * (is_sys_priv_input + is_sys_heap_reset + is_sys_stack_reset);
+            + is_type_sys
+                * (is_sys_priv_input
+                    + is_sys_heap_reset
+                    + is_sys_stack_reset
+                    + is_sys_hint
+                    + is_sys_read_hint
+                    + is_sys_read_cycle_counter
+                    + is_sys_priv_input_word
+                    + is_sys_commit);
         [ret]
     }
     fn eval<E: EvalAtRow>(trace_eval: &TraceEval<E>) -> [E::F; 1] {
@@ -443,16 +509,30 @@ impl VirtualColumn<1> for Reg3Accessed {
         let [is_type_u] = IsTypeU::eval(trace_eval);
         let [is_type_j] = IsTypeJ::eval(trace_eval);
         let [is_type_sys] = IsTypeSys::eval(trace_eval);
-        let [is_sys_priv_input] = trace_eval!(trace_eval, Column::IsSysPrivInput);
-        let [is_sys_heap_reset] = trace_eval!(trace_eval, Column::IsSysHeapReset);
-        let [is_sys_stack_reset] = trace_eval!(trace_eval, Column::IsSysStackReset);
+        let [is_sys_priv_input] = trace_eval!(trace_eval, SyscallColumns::IsSysPrivInput);
+        let [is_sys_heap_reset] = trace_eval!(trace_eval, SyscallColumns::IsSysHeapReset);
+        let [is_sys_stack_reset] = trace_eval!(trace_eval, SyscallColumns::IsSysStackReset);
+        let [is_sys_hint] = trace_eval!(trace_eval, SyscallColumns::IsSysHint);
+        let [is_sys_read_hint] = trace_eval!(trace_eval, SyscallColumns::IsSysReadHint);
+        let [is_sys_read_cycle_counter] =
+            trace_eval!(trace_eval, SyscallColumns::IsSysReadCycleCounter);
+        let [is_sys_priv_input_word] = trace_eval!(trace_eval, SyscallColumns::IsSysPrivInputWord);
+        let [is_sys_commit] = trace_eval!(trace_eval, SyscallColumns::IsSysCommit);
         let ret = is_type_s
             + is_type_b
             + is_type_r
             + is_type_i
             + is_type_u
             + is_type_j
-            + is_type_sys * (is_sys_priv_input + is_sys_heap_reset + is_sys_stack_reset);
+            + is_type_sys
+                * (is_sys_priv_input
+                    + is_sys_heap_reset
+                    + is_sys_stack_reset
+                    + is_sys_hint
+                    + is_sys_read_hint
+                    + is_sys_read_cycle_counter
+                    + is_sys_priv_input_word
+                    + is_sys_commit);
         [ret]
     }
 }
@@ -0,0 +1,115 @@
+//! Memory footprint of proving, measured with a counting global allocator.
+//!
+//! Lives in its own test binary so that the allocator doesn't slow down unit tests. Proving at log_size 20 and 22
+//! is expensive, run with
+//! `cargo test --release -p nexus-vm-prover --features testing --test peak_memory -- --ignored`.
+
+use nexus_vm::{
+    riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+    trace::k_trace_direct,
+};
+use nexus_vm_prover::{
+    column::{Column, PreprocessedColumn},
+    machine::{BaseComponent, Machine, ProverContext},
+    testing::{prove_worst_case, PeakAllocator},
+};
+
+#[global_allocator]
+static GLOBAL: PeakAllocator = PeakAllocator;
+
+/// Counts x1 up to 2^(log_size - 1) - 2, executing just under 2^log_size instructions.
+fn counting_loop(log_size: u32) -> Vec<BasicBlock> {
+    vec![BasicBlock::new(vec![
+        Instruction::new_ir(Opcode::from(BuiltinOpcode::LUI), 2, 0, 1 << (log_size - 13)),
+        Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 2, 0xFFE), // -2
+        Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 1, 1),
+        Instruction::new_ir(Opcode::from(BuiltinOpcode::BNE), 1, 2, 0x1FFC), // -4
+    ])]
+}
+
+#[test]
+#[ignore = "proves a 2^22 step trace"]
+fn main_trace_is_not_retained_while_committing() {
+    const LOG_SIZE: u32 = 22;
+    let (view, trace) =
+        k_trace_direct(&counting_loop(LOG_SIZE), 1).expect("error generating trace");
+
+    let before_build = PeakAllocator::reset_peak();
+    let bundle = Machine::<BaseComponent>::build_traces(&[], &trace, &view).unwrap();
+    let bundle_bytes = PeakAllocator::allocated() - before_build;
+    assert_eq!(bundle.max_log_size(), LOG_SIZE);
+
+    // Previously the bundle kept the machine's main and preprocessed traces next to their evaluations, holding
+    // at least two copies of them. Now it only holds the evaluations, plus the much smaller program trace,
+    // extension traces and side note, which takes at least 25% less.
+    let traces_bytes = (Column::COLUMNS_NUM + PreprocessedColumn::COLUMNS_NUM)
+        * (1 << LOG_SIZE)
+        * std::mem::size_of::<u32>();
+    let retained_before = 2 * traces_bytes;
+    assert!(
+        bundle_bytes * 4 <= retained_before * 3,
+        "trace bundle holds {bundle_bytes} bytes, less than 25% below the {retained_before} bytes held before"
+    );
+
+    let context = ProverContext::new(bundle.max_log_size());
+    let committed = Machine::<BaseComponent>::commit_and_interact(bundle, &context).unwrap();
+    let proof = Machine::<BaseComponent>::finish_proof(committed).unwrap();
+
+    nexus_vm_prover::verify(proof, &view).unwrap();
+}
+
+#[test]
+#[ignore = "proves a 2^20 step trace twice"]
+fn interaction_trace_is_committed_per_component() {
+    const LOG_SIZE: u32 = 20;
+    let (view, trace) =
+        k_trace_direct(&counting_loop(LOG_SIZE), 1).expect("error generating trace");
+    let checkpoint_dir =
+        std::env::temp_dir().join(format!("nexus-peak-memory-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&checkpoint_dir);
+
+    // A restored checkpoint holds every interaction column while the main trace is committed, and until they are
+    // all committed together, which is what committing generated traces used to do.
+    Machine::<BaseComponent>::prove_until_checkpoint(&[], &trace, &view)
+        .unwrap()
+        .save(&checkpoint_dir)
+        .unwrap();
+    let before_resume = PeakAllocator::reset_peak();
+    let resumed =
+        Machine::<BaseComponent>::prove_resumable(&[], &trace, &view, &checkpoint_dir).unwrap();
+    let resumed_peak = PeakAllocator::peak() - before_resume;
+    std::fs::remove_dir_all(&checkpoint_dir).unwrap();
+
+    let bundle = Machine::<BaseComponent>::build_traces(&[], &trace, &view).unwrap();
+    assert_eq!(bundle.max_log_size(), LOG_SIZE);
+    let context = ProverContext::new(bundle.max_log_size());
+    let before_prove = PeakAllocator::reset_peak();
+    let committed = Machine::<BaseComponent>::commit_and_interact(bundle, &context).unwrap();
+    let proof = Machine::<BaseComponent>::finish_proof(committed).unwrap();
+    let streamed_peak = PeakAllocator::peak() - before_prove;
+    assert!(
+        streamed_peak < resumed_peak,
+        "peak while proving: {streamed_peak} bytes, resuming a checkpoint: {resumed_peak} bytes"
+    );
+
+    // Handing the columns over one component at a time doesn't change the committed tree.
+    assert_eq!(
+        postcard::to_stdvec(&proof).unwrap(),
+        postcard::to_stdvec(&resumed).unwrap()
+    );
+    nexus_vm_prover::verify(proof, &view).unwrap();
+}
+
+#[test]
+#[ignore = "proves a 2^20 step trace"]
+fn worst_case_peak_memory() {
+    const LOG_SIZE: u32 = 20;
+    let stats = prove_worst_case(LOG_SIZE);
+    let peak = stats
+        .peak_heap_bytes
+        .expect("counting allocator is installed");
+
+    // Every column of the main trace is needed at least once.
+    let traces_bytes = Column::COLUMNS_NUM * (1 << LOG_SIZE) * std::mem::size_of::<u32>();
+    assert!(peak > traces_bytes);
+}
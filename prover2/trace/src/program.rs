@@ -118,12 +118,12 @@ impl ProgramStep<'_> {
     pub fn get_op_a(&self) -> Register {
         // Special cases: ECALL and EBREAK OpA depend on syscall number
         if let Some(syscall_value) = self.get_syscall_code() {
-            let syscall_number = SyscallCode::from(syscall_value);
-            match syscall_number {
-                SyscallCode::ReadFromPrivateInput | SyscallCode::OverwriteHeapPointer => {
+            // Unknown syscall numbers are rejected by the emulator, they don't write any register.
+            match SyscallCode::try_from(syscall_value) {
+                Ok(SyscallCode::ReadFromPrivateInput | SyscallCode::OverwriteHeapPointer) => {
                     Register::X10
                 }
-                SyscallCode::OverwriteStackPointer => Register::X2,
+                Ok(SyscallCode::OverwriteStackPointer) => Register::X2,
                 _ => Register::X0,
             }
         } else {
@@ -22,6 +22,8 @@ SECTIONS
   {
     /* Must be called __global_pointer$ for linker relaxations to work. */
     __global_pointer$ = . + 0x800;
+    /* read by the emulator to check the ecall ABI of the guest (see: src/runtime.rs) */
+    KEEP(*(.rodata.nexus_ecall_abi));
     *(.srodata .srodata.*);
     *(.rodata .rodata.*);
     *(.sdata .sdata.* .sdata2 .sdata2.*);
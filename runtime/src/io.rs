@@ -5,9 +5,11 @@ extern crate alloc;
 mod riscv32 {
     extern crate alloc;
     use crate::{
-        ecall, read_input, write_output, NexusRTError, SYS_CYCLE_COUNT, SYS_EXIT, SYS_LOG,
-        SYS_READ_PRIVATE_INPUT, WORD_SIZE,
+        ecall, read_input, write_output, NexusRTError, PANIC_FD, SYS_COMMIT, SYS_CYCLE_COUNT,
+        SYS_EXIT, SYS_HINT, SYS_LOG, SYS_READ_CYCLE_COUNTER, SYS_READ_HINT,
+        SYS_READ_PRIVATE_INPUT_WORD, WORD_SIZE,
     };
+    use core::sync::atomic::{AtomicUsize, Ordering};
     use serde::{de::DeserializeOwned, Serialize};
 
     /// Write a string to the output console (if any).
@@ -39,19 +41,24 @@ mod riscv32 {
     ///
     /// exhausts the private input tape, so can only be used once
     pub fn read_private_input<T: DeserializeOwned>() -> Result<T, NexusRTError> {
-        let mut bytes: alloc::vec::Vec<u8> = core::iter::from_fn(read_from_private_input).collect();
+        let mut bytes = alloc::vec::Vec::new();
+        loop {
+            let (word, len) = read_word_from_private_input();
+            bytes.extend_from_slice(&word[..len]);
+            if len < WORD_SIZE {
+                break;
+            }
+        }
         Ok(postcard::from_bytes_cobs::<T>(bytes.as_mut_slice())?)
     }
 
-    /// Read a byte from the private input tape
-    fn read_from_private_input() -> Option<u8> {
-        let out = ecall!(SYS_READ_PRIVATE_INPUT);
-
-        if out == u32::MAX {
-            None
-        } else {
-            Some(out.to_le_bytes()[0])
-        } // u32::MAX is used a sentinel value that there is nothing (left) on the input tape
+    /// Read up to a word from the private input tape, returning it with the number of bytes read
+    ///
+    /// fewer than `WORD_SIZE` bytes are read only at the end of the tape
+    fn read_word_from_private_input() -> ([u8; WORD_SIZE], usize) {
+        let mut word = 0u32;
+        let len = ecall!(SYS_READ_PRIVATE_INPUT_WORD, &mut word as *mut u32);
+        (word.to_le_bytes(), len as usize)
     }
 
     /// Read an object from the public input segment.
@@ -94,6 +101,67 @@ mod riscv32 {
         Ok(())
     }
 
+    /// Append an object to the committed public output.
+    ///
+    /// Committed output is part of the public output even if the program never exits, e.g. when it is
+    /// stopped by a cycle limit. Once an object is committed, the public output can only be extended by
+    /// further commits, mixing it with [`write_public_output`] is not supported.
+    pub fn commit_public_output<T: Serialize + ?Sized>(val: &T) -> Result<(), NexusRTError> {
+        // Number of bytes of the public output committed so far. The guest is single threaded, only loads and stores
+        // are needed, which targets without the atomic extension support.
+        static COMMITTED_LEN: AtomicUsize = AtomicUsize::new(0);
+
+        // Serialize the value into bytes.
+        let mut bytes = postcard::to_allocvec_cobs(val)?;
+        let len = bytes.len();
+
+        let padded_len = len
+            .checked_next_multiple_of(WORD_SIZE)
+            .ok_or(NexusRTError::OutputLengthOverflow(len))?;
+        bytes.resize(padded_len, 0); // cobs ignores 0x00 padding
+
+        let offset = COMMITTED_LEN.load(Ordering::Relaxed);
+
+        // Write bytes in word chunks to output memory, after the already committed ones.
+        bytes.chunks(WORD_SIZE).enumerate().for_each(|(i, chunk)| {
+            let word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            write_output!(offset + (i + 1) * WORD_SIZE, word); // word 0 is reserved for the exit code
+        });
+        let _ = ecall!(SYS_COMMIT, offset, ("a1", padded_len));
+
+        COMMITTED_LEN.store(offset + padded_len, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Run the host hint registered under `hint_id` on `input` and return its output.
+    ///
+    /// The output is untrusted advice chosen by the prover: check it before relying on it, e.g. by
+    /// squaring a returned square root.
+    pub fn hint(hint_id: u32, input: &[u8]) -> alloc::vec::Vec<u8> {
+        let len = ecall!(
+            SYS_HINT,
+            hint_id,
+            ("a1", input.as_ptr()),
+            ("a2", input.len())
+        ) as usize;
+
+        // Read the output back one word at a time.
+        let mut output = alloc::vec::Vec::with_capacity(len.next_multiple_of(WORD_SIZE));
+        while output.len() < len {
+            output.extend_from_slice(&ecall!(SYS_READ_HINT).to_le_bytes());
+        }
+        output.truncate(len);
+        output
+    }
+
+    /// Return the number of cycles executed so far, including the current one.
+    ///
+    /// The value is part of the proven execution, so the difference of two readings measures the
+    /// cycles spent in between.
+    pub fn cycles() -> u32 {
+        ecall!(SYS_READ_CYCLE_COUNTER)
+    }
+
     /// Bench cycles, where input is the function name
     pub fn cycle_count_ecall(s: &str) {
         let buf = s.as_ptr();
@@ -110,6 +178,17 @@ mod riscv32 {
             Ok(())
         }
     }
+
+    /// The panic payload read by the host: the line, the file and the message of the panic, separated by
+    /// newlines.
+    pub(crate) struct NexusPanicLog;
+
+    impl core::fmt::Write for NexusPanicLog {
+        fn write_str(&mut self, s: &str) -> Result<(), core::fmt::Error> {
+            let _ = ecall!(SYS_LOG, PANIC_FD, ("a1", s.as_ptr()), ("a2", s.len()));
+            Ok(())
+        }
+    }
 }
 
 #[cfg(target_arch = "riscv32")]
@@ -170,6 +249,18 @@ mod native {
     pub fn write_public_output<UNUSABLE: RequiresRV32Target, T: Serialize + ?Sized>(_val: &T) {
         unimplemented!()
     }
+
+    pub fn commit_public_output<UNUSABLE: RequiresRV32Target, T: Serialize + ?Sized>(_val: &T) {
+        unimplemented!()
+    }
+
+    pub fn hint<UNUSABLE: RequiresRV32Target>(_hint_id: u32, _input: &[u8]) -> Vec<u8> {
+        unimplemented!()
+    }
+
+    pub fn cycles<UNUSABLE: RequiresRV32Target>() -> u32 {
+        unimplemented!()
+    }
 }
 #[cfg(not(target_arch = "riscv32"))]
 pub use native::*;
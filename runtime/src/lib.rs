@@ -22,13 +22,17 @@ pub use postcard;
 
 pub mod keccak;
 
-// Ecall codes. Allow dead code here because these are only used in the RISC-V runtime, not when
-// compiling for the host.
+// Ecall codes, mirroring `nexus_common::ecall::Ecall` which can't be used from a `no_std` guest. Changing
+// them requires bumping `ECALL_ABI_VERSION` there and here, a test of `nexus_common::ecall` checks that both
+// agree. Allow dead code here because these are only used in the RISC-V runtime, not when compiling for the host.
+#[cfg(target_arch = "riscv32")]
+pub(crate) const ECALL_ABI_VERSION: u32 = 1;
 #[cfg(target_arch = "riscv32")]
 pub(crate) const SYS_LOG: u32 = 0x200;
 #[cfg(target_arch = "riscv32")]
 pub(crate) const SYS_EXIT: u32 = 0x201;
 #[cfg(target_arch = "riscv32")]
+#[allow(dead_code)]
 pub(crate) const SYS_READ_PRIVATE_INPUT: u32 = 0x400;
 #[cfg(target_arch = "riscv32")]
 pub(crate) const SYS_CYCLE_COUNT: u32 = 0x401;
@@ -39,6 +43,16 @@ pub(crate) const SYS_OVERWRITE_SP: u32 = 0x402;
 pub(crate) const SYS_ALLOC_ALIGNED: u32 = 0x403;
 #[cfg(target_arch = "riscv32")]
 pub(crate) const SYS_PERFORM_HEAP_ALLOCATION: u32 = 0x405;
+#[cfg(target_arch = "riscv32")]
+pub(crate) const SYS_COMMIT: u32 = 0x406;
+#[cfg(target_arch = "riscv32")]
+pub(crate) const SYS_HINT: u32 = 0x407;
+#[cfg(target_arch = "riscv32")]
+pub(crate) const SYS_READ_HINT: u32 = 0x408;
+#[cfg(target_arch = "riscv32")]
+pub(crate) const SYS_READ_CYCLE_COUNTER: u32 = 0x409;
+#[cfg(target_arch = "riscv32")]
+pub(crate) const SYS_READ_PRIVATE_INPUT_WORD: u32 = 0x40a;
 // Error codes.
 #[cfg(target_arch = "riscv32")]
 pub(crate) const EXIT_SUCCESS: u32 = 0;
@@ -47,6 +61,9 @@ pub(crate) const EXIT_PANIC: u32 = 1;
 // Constants.
 #[cfg(target_arch = "riscv32")]
 pub(crate) const WORD_SIZE: usize = 4;
+/// File descriptor of the panic payload, written with the log syscall and parsed by the host.
+#[cfg(target_arch = "riscv32")]
+pub(crate) const PANIC_FD: u32 = 3;
 
 /// Macro for making an ecall with variable number of parameters:
 /// - First parameter: syscall code (placed in a7)
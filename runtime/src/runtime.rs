@@ -1,8 +1,8 @@
 // Nexus VM runtime environment
 // Note: adapted from riscv-rt, which was adapted from cortex-m.
 use crate::alloc::sys_alloc_aligned;
-use crate::NexusLog;
-use crate::{ecall, write_output, EXIT_PANIC, EXIT_SUCCESS, SYS_EXIT};
+use crate::{ecall, write_output, ECALL_ABI_VERSION, EXIT_PANIC, EXIT_SUCCESS, SYS_EXIT};
+use crate::{NexusLog, NexusPanicLog};
 use core::alloc::{GlobalAlloc, Layout};
 use core::fmt::Write as _;
 use core::panic::PanicInfo;
@@ -24,6 +24,8 @@ fn panic(info: &PanicInfo) -> ! {
         info.message()
     )
     .unwrap();
+    // Structured copy of the panic for the host, the message may span several lines so it comes last.
+    write!(NexusPanicLog, "{}\n{}\n{}", line, file, info.message()).unwrap();
 
     // Write the exit code to the output.
     let _ = write_output!(0, EXIT_PANIC);
@@ -107,3 +109,9 @@ core::arch::global_asm!(
 
 #[no_mangle]
 pub static __memory_top: u32 = 0x80400000;
+
+/// Version of the ecall ABI the guest is built against, checked by the emulator when it loads the guest.
+#[no_mangle]
+#[used]
+#[link_section = ".rodata.nexus_ecall_abi"]
+pub static __nexus_ecall_abi_version: u32 = ECALL_ABI_VERSION;
@@ -51,3 +51,15 @@ pub enum PathError {
     #[error("provided path has invalid encoding for use with filesystem")]
     EncodingError,
 }
+
+/// Errors reported by the guest program itself through its exit code.
+#[derive(Debug, Error)]
+pub enum GuestError {
+    /// The guest panicked, with the panic reported by the runtime.
+    #[error("guest {0}")]
+    Panic(nexus_core::nvm::PanicInfo),
+
+    /// The guest exited with an unsuccessful exit code.
+    #[error("guest exited with code {0}")]
+    Exit(u32),
+}
@@ -5,14 +5,14 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::marker::PhantomData;
 use thiserror::Error;
 
-use crate::error::{BuildError, ConfigurationError, IOError, PathError};
+use crate::error::{BuildError, ConfigurationError, GuestError, IOError, PathError};
 
 /// Errors that occur while proving using Stwo.
 #[derive(Debug, Error)]
 pub enum Error {
     /// An error occurred during proving a zkVM execution.
     #[error(transparent)]
-    ProvingError(#[from] nexus_core::stwo::ProvingError),
+    ProvingError(#[from] nexus_core::stwo::NexusProvingError),
 
     /// An error occurred verifying a claimed proof of a zkVM execution.
     #[error(transparent)]
@@ -45,6 +45,10 @@ pub enum Error {
     /// An error occured configuring the prover.
     #[error(transparent)]
     ConfigurationError(#[from] ConfigurationError),
+
+    /// The guest program panicked or exited unsuccessfully, see [`Viewable::exit_status`].
+    #[error(transparent)]
+    GuestError(#[from] GuestError),
 }
 
 /// Prover for the Nexus zkVM, when using Stwo.
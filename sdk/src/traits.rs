@@ -26,7 +26,9 @@ pub trait CheckedView {
         expected_public_output: &[u8],
         expected_elf: &nexus_core::nvm::ElfFile,
         expected_ad: &[u8],
-    ) -> Self;
+    ) -> Result<Self, nexus_core::nvm::VMError>
+    where
+        Self: Sized;
 }
 
 impl CheckedView for nexus_core::nvm::View {
@@ -37,7 +39,7 @@ impl CheckedView for nexus_core::nvm::View {
         expected_public_output: &[u8],
         expected_elf: &nexus_core::nvm::ElfFile,
         expected_ad: &[u8],
-    ) -> Self {
+    ) -> Result<Self, nexus_core::nvm::VMError> {
         let emulator = LinearEmulator::default();
 
         // Replace custom instructions `rin` and `wou` with `lw` and `sw`.
@@ -52,7 +54,7 @@ impl CheckedView for nexus_core::nvm::View {
             ..expected_elf.clone()
         };
 
-        let program_memory = elf_into_program_info(&converted_elf, memory_layout);
+        let program_memory = elf_into_program_info(&converted_elf, memory_layout)?;
 
         let input_memory = slice_into_io_entries::<MemoryInitializationEntry>(
             memory_layout.public_input_start(),
@@ -72,27 +74,32 @@ impl CheckedView for nexus_core::nvm::View {
             .concat(),
         )
         .iter()
-        .chain(map_into_io_entries::<MemoryInitializationEntry>(&expected_elf.rom_image).iter())
         .copied()
+        // The program is read-only memory too, guests may load constants embedded in `.text`.
+        .chain(program_memory.memory_entries())
+        .chain(
+            map_into_io_entries::<MemoryInitializationEntry>(&expected_elf.rom_image)
+                .iter()
+                .copied(),
+        )
         .collect();
 
         let rw_initial_memory =
             map_into_io_entries::<MemoryInitializationEntry>(&expected_elf.ram_image);
 
-        let exit_code = slice_into_io_entries::<PublicOutputEntry>(
-            memory_layout.exit_code(),
-            expected_exit_code,
-        );
+        let exit_code =
+            PublicOutputEntry::from_bytes(memory_layout.exit_code(), expected_exit_code);
 
-        let output_memory = slice_into_io_entries::<PublicOutputEntry>(
+        let output_memory = PublicOutputEntry::from_bytes(
             memory_layout.public_output_start(),
             expected_public_output,
         );
 
         let static_memory_size =
-            (&expected_elf.rom_image.len_bytes() + &expected_elf.ram_image.len_bytes()) * WORD_SIZE;
+            (&expected_elf.rom_image.len_bytes() + &expected_elf.ram_image.len_bytes()) * WORD_SIZE
+                + program_memory.program.len() * WORD_SIZE;
 
-        Self::new(
+        Ok(Self::new(
             &Some(*memory_layout),
             &Vec::new(),
             &program_memory,
@@ -103,7 +110,7 @@ impl CheckedView for nexus_core::nvm::View {
             &exit_code,
             &output_memory,
             &expected_ad.to_vec(),
-        )
+        ))
     }
 }
 
@@ -167,6 +174,26 @@ pub trait Viewable {
 
     /// Recover any debug logs produced by the execution.
     fn logs(&self) -> Result<Vec<String>, IOError>;
+
+    /// Recover the message, file and line of the guest panic, if the guest panicked.
+    fn panic_info(&self) -> Option<nexus_core::nvm::PanicInfo>;
+
+    /// Check that the guest exited successfully.
+    ///
+    /// If the exit code indicates a panic, the error carries the panic as reported by the runtime.
+    fn exit_status(&self) -> Result<(), GuestError> {
+        match self.exit_code() {
+            Ok(code) if code == crate::KnownExitCodes::ExitSuccess as u32 => Ok(()),
+            Ok(code) => match self.panic_info() {
+                Some(info) if code == crate::KnownExitCodes::ExitPanic as u32 => {
+                    Err(GuestError::Panic(info))
+                }
+                _ => Err(GuestError::Exit(code)),
+            },
+            // Without an exit code, the guest did not exit.
+            Err(_) => Ok(()),
+        }
+    }
 }
 
 impl Viewable for nexus_core::nvm::View {
@@ -181,11 +208,8 @@ impl Viewable for nexus_core::nvm::View {
 
     /// Deserialize the exit code resulting from the execution.
     fn exit_code(&self) -> Result<u32, IOError> {
-        if let Some(bytes) = self.view_exit_code() {
-            Ok(postcard::from_bytes::<u32>(&bytes).map_err(IOError::from)?)
-        } else {
-            Err(IOError::NotYetAvailableError)
-        }
+        // The exit code is stored as a raw little-endian word, not postcard-encoded.
+        self.exit_code_u32().ok_or(IOError::NotYetAvailableError)
     }
 
     /// Deserialize the public output resulting from the execution.
@@ -217,6 +241,11 @@ impl Viewable for nexus_core::nvm::View {
             Err(IOError::NotYetAvailableError)
         }
     }
+
+    /// Recover the message, file and line of the guest panic, if the guest panicked.
+    fn panic_info(&self) -> Option<nexus_core::nvm::PanicInfo> {
+        nexus_core::nvm::View::panic_info(self)
+    }
 }
 
 /// A proving instance that can be constructed through compiling a guest program.
@@ -472,7 +501,7 @@ pub trait Verifiable: Serialize + DeserializeOwned {
             output_encoded.as_slice(),
             expected_elf,
             expected_ad,
-        );
+        )?;
 
         self.verify(&view)
     }
@@ -0,0 +1,22 @@
+#![cfg_attr(target_arch = "riscv32", no_std, no_main)]
+
+/// 2 MiB of `.bss`.
+static mut ZEROED: [u32; 1 << 19] = [0; 1 << 19];
+/// Non-zero `.data`.
+static mut INITIALIZED: [u32; 4] = [1, 2, 3, 4];
+
+#[nexus_rt::main]
+fn main() -> u32 {
+    let zeroed = unsafe { &mut *core::ptr::addr_of_mut!(ZEROED) };
+    let initialized = unsafe { &mut *core::ptr::addr_of_mut!(INITIALIZED) };
+
+    let mut sum = 0;
+    for i in (0..zeroed.len()).step_by(65521) {
+        let i = core::hint::black_box(i);
+        assert_eq!(zeroed[i], 0);
+        zeroed[i] = initialized[i % initialized.len()];
+        sum += zeroed[i];
+    }
+    initialized[0] = sum;
+    core::hint::black_box(initialized[0])
+}
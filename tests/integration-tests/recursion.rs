@@ -0,0 +1,17 @@
+#![cfg_attr(target_arch = "riscv32", no_std, no_main)]
+
+/// Recurses `depth` times, keeping a 64-byte buffer alive in every frame.
+#[inline(never)]
+fn recurse(depth: u32) -> u32 {
+    let frame = core::hint::black_box([depth; 16]);
+    if depth == 0 {
+        return 0;
+    }
+    frame[depth as usize % frame.len()] + recurse(depth - 1)
+}
+
+#[nexus_rt::main]
+#[nexus_rt::public_input(depth)]
+fn main(depth: u32) -> u32 {
+    recurse(core::hint::black_box(depth))
+}
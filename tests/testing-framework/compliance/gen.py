@@ -0,0 +1,462 @@
+#!/usr/bin/env python3
+"""Generates the RV32I compliance vectors of this directory.
+
+Every vector is a small assembly program testing one instruction on edge-case operands, in the style of
+riscv-arch-test: results are stored into the signature region between `begin_signature` and `end_signature`. The
+program is assembled with `llvm-mc`, linked here into an executable laid out like a guest, and run on the RV32I model
+at the bottom of this file to produce the reference signature.
+
+Usage: `./gen.py [output directory]`, the directory of this script by default. Needs `llvm-mc` with the RISC-V
+target, set `LLVM_MC` to use another binary.
+"""
+
+import os
+import struct
+import subprocess
+import sys
+import tempfile
+
+TEXT_START = 0x88
+EXIT_ECALL = 0x201
+# Filled into the signature region before the program runs, as riscv-arch-test does.
+CANARY = 0xDEADBEEF
+
+VALUES = [0, 1, -1, 2, 0x7FFFFFFF, 0x80000000, 0x55555555, 0xAAAAAAAA, 31, 0x12345678, -0x800, 0x800]
+IMMEDIATES = [0, 1, -1, 0x7FF, -0x800, 0x555, -0x556]
+SHAMTS = [0, 1, 15, 31]
+UPPER = [0, 1, 0x80000, 0xFFFFF, 0x12345, 0x7FFFF]
+# Bytes at `test_data`, read by the load vectors.
+TEST_DATA = bytes.fromhex("00ff7f8001027fffff8000807856341200000000")
+
+R_OPS = ["add", "sub", "and", "or", "xor", "sll", "srl", "sra", "slt", "sltu"]
+I_OPS = ["addi", "andi", "ori", "xori", "slti", "sltiu"]
+SHIFT_I_OPS = ["slli", "srli", "srai"]
+BRANCHES = ["beq", "bne", "blt", "bge", "bltu", "bgeu"]
+LOADS = {"lb": 1, "lbu": 1, "lh": 2, "lhu": 2, "lw": 4}
+STORES = {"sb": 1, "sh": 2, "sw": 4}
+
+
+def u32(value):
+    return value & 0xFFFFFFFF
+
+
+class Body:
+    """Instructions of a vector. `x31` points at the signature, `x30` at the test data, `x28` and `x29` are
+    scratch registers."""
+
+    def __init__(self):
+        self.lines = []
+        self.slots = 0
+        self.labels = 0
+
+    def emit(self, line):
+        self.lines.append(line)
+
+    def label(self):
+        self.labels += 1
+        return f"L{self.labels}"
+
+    def store_result(self, reg):
+        self.emit(f"sw {reg}, {4 * self.slots}(x31)")
+        self.slots += 1
+
+    def cycle(self, index):
+        """Registers x1..x27, rotated so that every vector exercises most of the register file."""
+        return [f"x{1 + (index + k) % 27}" for k in range(3)]
+
+
+def r_type(op):
+    body = Body()
+    pairs = [(a, b) for a in VALUES for b in VALUES[:8]]
+    for index, (a, b) in enumerate(pairs):
+        rd, rs1, rs2 = body.cycle(index)
+        body.emit(f"li {rs1}, {u32(a)}")
+        body.emit(f"li {rs2}, {u32(b)}")
+        body.emit(f"{op} {rd}, {rs1}, {rs2}")
+        body.store_result(rd)
+    # Writes to x0 are discarded, reads of x0 are zero.
+    body.emit("li x1, 5")
+    body.emit(f"{op} x0, x1, x1")
+    body.store_result("x0")
+    body.emit(f"{op} x2, x0, x1")
+    body.store_result("x2")
+    return body
+
+
+def i_type(op):
+    body = Body()
+    for index, (a, imm) in enumerate((a, imm) for a in VALUES for imm in IMMEDIATES):
+        rd, rs1, _ = body.cycle(index)
+        body.emit(f"li {rs1}, {u32(a)}")
+        body.emit(f"{op} {rd}, {rs1}, {imm}")
+        body.store_result(rd)
+    return body
+
+
+def shift_i_type(op):
+    body = Body()
+    for index, (a, shamt) in enumerate((a, shamt) for a in VALUES for shamt in SHAMTS):
+        rd, rs1, _ = body.cycle(index)
+        body.emit(f"li {rs1}, {u32(a)}")
+        body.emit(f"{op} {rd}, {rs1}, {shamt}")
+        body.store_result(rd)
+    return body
+
+
+def upper(op):
+    body = Body()
+    for index, imm in enumerate(UPPER):
+        rd, _, _ = body.cycle(index)
+        body.emit(f"{op} {rd}, {imm}")
+        body.store_result(rd)
+    return body
+
+
+def branch(op):
+    body = Body()
+    pairs = [(a, b) for a in VALUES[:8] for b in VALUES[:8]]
+    for index, (a, b) in enumerate(pairs):
+        _, rs1, rs2 = body.cycle(index)
+        taken = body.label()
+        body.emit(f"li {rs1}, {u32(a)}")
+        body.emit(f"li {rs2}, {u32(b)}")
+        body.emit("li x29, 0")
+        body.emit(f"{op} {rs1}, {rs2}, {taken}")
+        body.emit("addi x29, x29, 1")
+        body.emit(f"{taken}:")
+        body.emit("addi x29, x29, 2")
+        body.store_result("x29")
+    # A backward branch, counting x1 up to x2.
+    loop = body.label()
+    body.emit("li x1, 0" if op.endswith("u") else "li x1, -3")
+    body.emit("li x2, 4")
+    body.emit("li x29, 0")
+    body.emit(f"{loop}:")
+    body.emit("addi x29, x29, 1")
+    body.emit("addi x1, x1, 1")
+    if op == "beq":
+        body.emit("slt x3, x1, x2")
+        body.emit("li x4, 1")
+        body.emit(f"beq x3, x4, {loop}")
+    elif op in ("bge", "bgeu"):
+        body.emit(f"{op} x2, x1, {loop}")
+    else:
+        body.emit(f"{op} x1, x2, {loop}")
+    body.store_result("x29")
+    body.store_result("x1")
+    return body
+
+
+def jal(_op):
+    body = Body()
+    for rd in ["x1", "x5", "x0"]:
+        target = body.label()
+        body.emit("li x29, 0")
+        body.emit(f"jal {rd}, {target}")
+        body.emit("addi x29, x29, 1")
+        body.emit(f"{target}:")
+        body.emit("addi x29, x29, 2")
+        body.store_result("x29")
+        body.store_result(rd)
+    # A backward jump over the instructions stored so far.
+    back, done = body.label(), body.label()
+    body.emit("li x29, 0")
+    body.emit(f"jal x0, {done}")
+    body.emit(f"{back}:")
+    body.emit("addi x29, x29, 4")
+    body.emit(f"jal x0, {done}_end")
+    body.emit(f"{done}:")
+    body.emit("addi x29, x29, 8")
+    body.emit(f"jal x0, {back}")
+    body.emit(f"{done}_end:")
+    body.store_result("x29")
+    return body
+
+
+def jalr(_op):
+    body = Body()
+    # `auipc x5, 0` is the base, each case jumps over the first `addi`.
+    for rd, setup, offset in [
+        ("x1", "addi x5, x5, 0", 16),
+        ("x6", "addi x5, x5, 24", -8),
+        ("x7", "addi x5, x5, 0", 17),  # the lowest bit of the target is cleared
+        ("x0", "addi x5, x5, 0", 16),
+    ]:
+        body.emit("li x29, 0")
+        body.emit("auipc x5, 0")
+        body.emit(setup)
+        body.emit(f"jalr {rd}, {offset}(x5)")
+        body.emit("addi x29, x29, 1")
+        body.emit("addi x29, x29, 2")
+        body.store_result("x29")
+        body.store_result(rd)
+    return body
+
+
+def load(op):
+    body = Body()
+    width = LOADS[op]
+    for index, offset in enumerate(range(0, 16, width)):
+        rd, _, _ = body.cycle(index)
+        body.emit(f"{op} {rd}, {offset}(x30)")
+        body.store_result(rd)
+    # Negative offsets from a base past the data.
+    body.emit("addi x29, x30, 16")
+    for index, offset in enumerate(range(-width, -17, -width)):
+        rd, _, _ = body.cycle(index)
+        body.emit(f"{op} {rd}, {offset}(x29)")
+        body.store_result(rd)
+    return body
+
+
+def store(op):
+    body = Body()
+    width = STORES[op]
+    words = 8
+    # The stores leave the rest of the signature words at the canary.
+    for index, offset in enumerate(range(0, 4 * words, 4 + width)):
+        _, rs2, _ = body.cycle(index)
+        body.emit(f"li {rs2}, {u32(VALUES[index % len(VALUES)] ^ 0x0F0F0F0F)}")
+        aligned = offset - offset % width
+        body.emit(f"{op} {rs2}, {aligned}(x31)")
+    body.slots = words
+    return body
+
+
+GENERATORS = (
+    [(op, r_type) for op in R_OPS]
+    + [(op, i_type) for op in I_OPS]
+    + [(op, shift_i_type) for op in SHIFT_I_OPS]
+    + [(op, upper) for op in ["lui", "auipc"]]
+    + [(op, branch) for op in BRANCHES]
+    + [("jal", jal), ("jalr", jalr)]
+    + [(op, load) for op in LOADS]
+    + [(op, store) for op in STORES]
+)
+
+
+def source(body, data_address):
+    signature = data_address + len(TEST_DATA)
+    lines = [
+        ".text",
+        ".globl _start",
+        "_start:",
+        f"lui x31, %hi({signature})",
+        f"addi x31, x31, %lo({signature})",
+        f"lui x30, %hi({data_address})",
+        f"addi x30, x30, %lo({data_address})",
+        *body.lines,
+        "li a0, 0",
+        f"li a7, {EXIT_ECALL}",
+        "ecall",
+    ]
+    return "\n".join(lines) + "\n"
+
+
+def assemble(text):
+    """Returns the contents of the `.text` section of `text` assembled, which must not need relocations."""
+    llvm_mc = os.environ.get("LLVM_MC", "llvm-mc")
+    with tempfile.TemporaryDirectory() as tmp:
+        src, obj = os.path.join(tmp, "test.s"), os.path.join(tmp, "test.o")
+        with open(src, "w") as f:
+            f.write(text)
+        subprocess.run(
+            [llvm_mc, "-triple=riscv32", "-mattr=-relax,-c", "-filetype=obj", src, "-o", obj],
+            check=True,
+        )
+        with open(obj, "rb") as f:
+            data = f.read()
+
+    shoff, = struct.unpack_from("<I", data, 0x20)
+    shnum, shstrndx = struct.unpack_from("<HH", data, 0x30)
+    sections = [struct.unpack_from("<10I", data, shoff + 40 * i) for i in range(shnum)]
+    names = sections[shstrndx]
+    text_section = None
+    for section in sections:
+        name_start = names[4] + section[0]
+        name = data[name_start : data.index(b"\0", name_start)].decode()
+        if name.startswith(".rela") or name.startswith(".rel."):
+            raise SystemExit(f"unexpected relocations in {name}")
+        if name == ".text":
+            text_section = section
+    offset, size = text_section[4], text_section[5]
+    return data[offset : offset + size]
+
+
+def link(text, data, symbols):
+    """Lays out an ELF32 RISC-V executable with `text` at `TEXT_START` and `data` right after it.
+
+    `symbols` maps names to `(section, address)`, with section 1 for `.text` and 2 for `.data`.
+    """
+    data_address = data_start(len(text))
+    text_offset = 0x100
+    data_offset = text_offset + len(text)
+    shstrtab = b"\0.text\0.data\0.symtab\0.strtab\0.shstrtab\0"
+    strtab = b"\0"
+    symtab = bytes(16)
+    for name, (section, address) in symbols.items():
+        kind = 2 if section == 1 else 0  # STT_FUNC or STT_NOTYPE
+        symtab += struct.pack("<IIIBBH", len(strtab), address, 0, 0x10 | kind, 0, section)
+        strtab += name.encode() + b"\0"
+    symtab_offset = data_offset + len(data)
+    strtab_offset = symtab_offset + len(symtab)
+    shstrtab_offset = strtab_offset + len(strtab)
+    shoff = (shstrtab_offset + len(shstrtab) + 3) & ~3
+
+    def name(n):
+        return shstrtab.index(n.encode() + b"\0")
+
+    header = b"\x7fELF" + bytes([1, 1, 1]) + bytes(9)
+    header += struct.pack(
+        "<HHIIIIIHHHHHH", 2, 243, 1, TEXT_START, 52, shoff, 0, 52, 32, 2, 40, 6, 5
+    )
+    header += struct.pack("<8I", 1, text_offset, TEXT_START, TEXT_START, len(text), len(text), 5, 4)
+    header += struct.pack("<8I", 1, data_offset, data_address, data_address, len(data), len(data), 6, 4)
+    sections = bytes(40)
+    sections += struct.pack("<10I", name(".text"), 1, 6, TEXT_START, text_offset, len(text), 0, 0, 4, 0)
+    sections += struct.pack("<10I", name(".data"), 1, 3, data_address, data_offset, len(data), 0, 0, 4, 0)
+    sections += struct.pack("<10I", name(".symtab"), 2, 0, 0, symtab_offset, len(symtab), 4, 1, 4, 16)
+    sections += struct.pack("<10I", name(".strtab"), 3, 0, 0, strtab_offset, len(strtab), 0, 0, 1, 0)
+    sections += struct.pack("<10I", name(".shstrtab"), 3, 0, 0, shstrtab_offset, len(shstrtab), 0, 0, 1, 0)
+
+    image = bytearray(header.ljust(text_offset, b"\0"))
+    image += text + data + symtab + strtab + shstrtab
+    image = image.ljust(shoff, b"\0") + sections
+    return bytes(image)
+
+
+def data_start(text_len):
+    return (TEXT_START + text_len + 7) & ~7
+
+
+def generate(op, body):
+    # The layout only depends on the number of instructions, which doesn't depend on the addresses.
+    text_len = len(assemble(source(body, 0x80000)))
+    data_address = data_start(text_len)
+    text = assemble(source(body, data_address))
+    assert len(text) == text_len
+
+    signature = data_address + len(TEST_DATA)
+    data = TEST_DATA + struct.pack("<I", CANARY) * body.slots
+    elf = link(
+        text,
+        data,
+        {
+            "_start": (1, TEXT_START),
+            "test_data": (2, data_address),
+            "begin_signature": (2, signature),
+            "end_signature": (2, signature + 4 * body.slots),
+        },
+    )
+    memory = Memory(text, data, data_address)
+    run(memory)
+    reference = [memory.load(signature + 4 * i, 4) for i in range(body.slots)]
+    return elf, reference
+
+
+class Memory:
+    def __init__(self, text, data, data_address):
+        self.bytes = {}
+        for base, contents in [(TEXT_START, text), (data_address, data)]:
+            for i, byte in enumerate(contents):
+                self.bytes[base + i] = byte
+
+    def load(self, address, width):
+        return int.from_bytes(bytes(self.bytes[address + i] for i in range(width)), "little")
+
+    def store(self, address, width, value):
+        for i, byte in enumerate(u32(value).to_bytes(4, "little")[:width]):
+            if address + i not in self.bytes:
+                raise SystemExit(f"store outside of the program at {address + i:#x}")
+            self.bytes[address + i] = byte
+
+
+def signed(value, bits=32):
+    value &= (1 << bits) - 1
+    return value - (1 << bits) if value >> (bits - 1) else value
+
+
+def run(memory, limit=1 << 20):
+    """Executes the program in `memory` from `TEXT_START` until the exit ecall."""
+    regs = [0] * 32
+    pc = TEXT_START
+    for _ in range(limit):
+        word = memory.load(pc, 4)
+        opcode, rd, funct3 = word & 0x7F, (word >> 7) & 0x1F, (word >> 12) & 7
+        rs1, rs2, funct7 = regs[(word >> 15) & 0x1F], regs[(word >> 20) & 0x1F], word >> 25
+        imm_i = signed(word >> 20, 12)
+        imm_s = signed((funct7 << 5) | rd, 12)
+        imm_b = signed(
+            ((word >> 31) << 12) | (((word >> 7) & 1) << 11) | (((word >> 25) & 0x3F) << 5) | (((word >> 8) & 0xF) << 1),
+            13,
+        )
+        imm_j = signed(
+            ((word >> 31) << 20) | (((word >> 12) & 0xFF) << 12) | (((word >> 20) & 1) << 11) | (((word >> 21) & 0x3FF) << 1),
+            21,
+        )
+        next_pc, result = pc + 4, None
+        if opcode == 0x37:  # LUI
+            result = word & 0xFFFFF000
+        elif opcode == 0x17:  # AUIPC
+            result = pc + (word & 0xFFFFF000)
+        elif opcode == 0x6F:  # JAL
+            result, next_pc = pc + 4, pc + imm_j
+        elif opcode == 0x67:  # JALR
+            result, next_pc = pc + 4, (rs1 + imm_i) & ~1
+        elif opcode == 0x63:  # branches
+            a, b = rs1, rs2
+            taken = {
+                0: a == b,
+                1: a != b,
+                4: signed(a) < signed(b),
+                5: signed(a) >= signed(b),
+                6: a < b,
+                7: a >= b,
+            }[funct3]
+            if taken:
+                next_pc = pc + imm_b
+        elif opcode == 0x03:  # loads
+            width = 1 << (funct3 & 3)
+            value = memory.load(u32(rs1 + imm_i), width)
+            result = value if funct3 & 4 else signed(value, 8 * width)
+        elif opcode == 0x23:  # stores
+            memory.store(u32(rs1 + imm_s), 1 << funct3, rs2)
+        elif opcode in (0x13, 0x33):  # OP-IMM and OP
+            b = imm_i if opcode == 0x13 else rs2
+            alt = funct7 == 0x20 and (opcode == 0x33 or funct3 == 5)
+            shamt = b & 0x1F
+            result = {
+                0: rs1 - b if alt else rs1 + b,
+                1: rs1 << shamt,
+                2: int(signed(rs1) < signed(b)),
+                3: int(rs1 < u32(b)),
+                4: rs1 ^ b,
+                5: signed(rs1) >> shamt if alt else rs1 >> shamt,
+                6: rs1 | b,
+                7: rs1 & b,
+            }[funct3]
+        elif opcode == 0x73:  # ECALL
+            if regs[17] != EXIT_ECALL or regs[10] != 0:
+                raise SystemExit(f"unexpected ecall at {pc:#x}")
+            return
+        else:
+            raise SystemExit(f"unsupported instruction {word:#010x} at {pc:#x}")
+        if result is not None and rd != 0:
+            regs[rd] = u32(result)
+        pc = u32(next_pc)
+    raise SystemExit("the program didn't exit")
+
+
+def main():
+    out = sys.argv[1] if len(sys.argv) > 1 else os.path.dirname(os.path.abspath(__file__))
+    for op, generator in GENERATORS:
+        elf, reference = generate(op, generator(op))
+        name = f"{op}-01"
+        with open(os.path.join(out, f"{name}.elf"), "wb") as f:
+            f.write(elf)
+        with open(os.path.join(out, f"{name}.reference_output"), "w") as f:
+            f.write("".join(f"{word:08x}\n" for word in reference))
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,209 @@
+//! RV32I compliance vectors in the format of riscv-arch-test, run through the emulator and the prover.
+//!
+//! Every `<name>.elf` in the fixture directory comes with the reference signature `<name>.reference_output`, one
+//! hex word per line as produced by a reference model. The harness executes each ELF, reads the region between
+//! the `begin_signature` and `end_signature` symbols once execution ends, and compares it against the reference.
+//! A deterministic sample of the vectors is then proven and verified, proving all of them is too slow.
+//!
+//! The fixture directory is `compliance/` next to this crate, or `NEXUS_COMPLIANCE_DIR`. The number of proven
+//! vectors is `NEXUS_COMPLIANCE_PROVE_SAMPLE`, [`DEFAULT_PROVE_SAMPLE`] by default, and zero skips proving. See
+//! `compliance/README.md` for generating the fixtures.
+
+use std::{
+    env, fs,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use nexus_vm::{
+    elf::ElfFile,
+    emulator::{TerminationCause, View},
+    trace::{k_trace_with_options, TraceOptions, UniformTrace},
+};
+use nexus_vm_prover::{prove, verify};
+
+const DEFAULT_PROVE_SAMPLE: usize = 4;
+/// Compliance vectors run a few thousand instructions, a vector that doesn't halt within this many is broken.
+const CYCLE_LIMIT: usize = 1 << 20;
+
+/// A compliance vector and its reference signature.
+struct Vector {
+    name: String,
+    elf_bytes: Vec<u8>,
+    reference: Vec<u32>,
+}
+
+fn fixture_dir() -> PathBuf {
+    env::var_os("NEXUS_COMPLIANCE_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("compliance"))
+}
+
+fn prove_sample_size() -> usize {
+    env::var("NEXUS_COMPLIANCE_PROVE_SAMPLE")
+        .map(|sample| {
+            sample
+                .parse()
+                .expect("NEXUS_COMPLIANCE_PROVE_SAMPLE must be a number")
+        })
+        .unwrap_or(DEFAULT_PROVE_SAMPLE)
+}
+
+/// Parses a reference signature, one hex word per line as written by the reference model.
+fn parse_reference(text: &str) -> Vec<u32> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            u32::from_str_radix(line, 16)
+                .unwrap_or_else(|_| panic!("invalid signature word {line:?}"))
+        })
+        .collect()
+}
+
+/// Loads the vectors of the fixture directory in name order, empty if the directory doesn't exist.
+fn load_vectors(dir: &Path) -> Vec<Vector> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut vectors: Vec<_> = entries
+        .map(|entry| entry.expect("failed to read fixture directory").path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "elf"))
+        .map(|path| {
+            let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+            let reference_path = path.with_extension("reference_output");
+            let reference = fs::read_to_string(&reference_path)
+                .unwrap_or_else(|_| panic!("{name} has no {}", reference_path.display()));
+            Vector {
+                name,
+                elf_bytes: fs::read(&path).unwrap(),
+                reference: parse_reference(&reference),
+            }
+        })
+        .collect();
+    vectors.sort_by(|a, b| a.name.cmp(&b.name));
+    vectors
+}
+
+/// FNV-1a hash of the name, which orders the sample independently of which other vectors are present.
+fn sample_key(name: &str) -> u64 {
+    name.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Returns the `size` vectors with the smallest sample keys.
+fn sample(vectors: &[Vector], size: usize) -> Vec<&Vector> {
+    let mut sampled: Vec<_> = vectors.iter().collect();
+    sampled.sort_by_key(|vector| sample_key(&vector.name));
+    sampled.truncate(size);
+    sampled
+}
+
+/// Executes the vector and returns its signature, checking that it exits cleanly.
+fn run(vector: &Vector) -> (Vec<u32>, View, UniformTrace) {
+    let elf = ElfFile::from_bytes(&vector.elf_bytes).unwrap();
+    let symbol = |name| {
+        ElfFile::symbol_address(&vector.elf_bytes, name)
+            .unwrap_or_else(|_| panic!("{} has no {name} symbol", vector.name))
+    };
+    let signature: Range<u32> = symbol("begin_signature")..symbol("end_signature");
+    let options = TraceOptions {
+        cycle_limit: Some(CYCLE_LIMIT),
+        captured_memory: vec![signature.clone()],
+        ..Default::default()
+    };
+    let (view, trace) = k_trace_with_options(elf, &[], &[], &[], 1, options)
+        .unwrap_or_else(|err| panic!("{} failed: {err:?}", vector.name));
+    assert_eq!(
+        view.view_termination_cause(),
+        Some(TerminationCause::Exit(0)),
+        "{} didn't exit cleanly",
+        vector.name
+    );
+
+    let bytes = view
+        .view_captured_memory(&signature)
+        .unwrap_or_else(|| panic!("{} signature region is not readable", vector.name));
+    let words = bytes
+        .chunks_exact(4)
+        .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+        .collect();
+    (words, view, trace)
+}
+
+#[test]
+fn test_parse_reference() {
+    assert_eq!(
+        parse_reference("deadbeef\n00000001\n\nFFFFFFFF\n"),
+        [0xdeadbeef, 1, 0xffffffff]
+    );
+}
+
+#[test]
+fn test_sample_is_deterministic() {
+    let vectors: Vec<_> = ["add-01", "sll-01", "srai-01", "lb-align-01", "jalr-01"]
+        .into_iter()
+        .map(|name| Vector {
+            name: name.to_string(),
+            elf_bytes: Vec::new(),
+            reference: Vec::new(),
+        })
+        .collect();
+    let names = |sampled: Vec<&Vector>| -> Vec<String> {
+        sampled
+            .into_iter()
+            .map(|vector| vector.name.clone())
+            .collect()
+    };
+
+    let sampled = names(sample(&vectors, 2));
+    assert_eq!(sampled.len(), 2);
+    assert_eq!(names(sample(&vectors, 2)), sampled);
+    // Removing a vector outside of the sample keeps the sample.
+    let remaining: Vec<_> = vectors
+        .into_iter()
+        .filter(|vector| sampled.contains(&vector.name) || vector.name == "add-01")
+        .collect();
+    assert_eq!(names(sample(&remaining, 2)), sampled);
+}
+
+#[test]
+fn test_compliance_vectors() {
+    let dir = fixture_dir();
+    let vectors = load_vectors(&dir);
+    assert!(
+        !vectors.is_empty(),
+        "no compliance vectors in {}, see compliance/README.md",
+        dir.display()
+    );
+
+    let mut failures = Vec::new();
+    for vector in &vectors {
+        let (signature, ..) = run(vector);
+        if signature != vector.reference {
+            let first_mismatch = signature
+                .iter()
+                .zip(&vector.reference)
+                .position(|(actual, expected)| actual != expected)
+                .unwrap_or(signature.len().min(vector.reference.len()));
+            failures.push(format!(
+                "{}: signature differs at word {first_mismatch}",
+                vector.name
+            ));
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "signature mismatches:\n{}",
+        failures.join("\n")
+    );
+
+    for vector in sample(&vectors, prove_sample_size()) {
+        let (_, view, trace) = run(vector);
+        let proof = prove(&trace, &view)
+            .unwrap_or_else(|err| panic!("proving {} failed: {err}", vector.name));
+        verify(proof, &view)
+            .unwrap_or_else(|err| panic!("verifying {} failed: {err}", vector.name));
+    }
+}
@@ -1,3 +1,6 @@
+#[cfg(test)]
+mod compliance;
+
 #[cfg(test)]
 mod test {
     use nexus_common::memory::alignment::Alignable;
@@ -6,8 +9,10 @@ mod test {
     };
     use nexus_common_testing::program_trace;
     use nexus_vm::elf::ElfFile;
-    use nexus_vm::emulator::InternalView;
-    use nexus_vm::trace::{k_trace, k_trace_direct};
+    use nexus_vm::emulator::{InternalView, PanicInfo, StackGuard, TerminationCause, View};
+    use nexus_vm::trace::{
+        k_trace, k_trace_direct, k_trace_with_options, TraceOptions, UniformTrace,
+    };
     use nexus_vm_prover::{
         extensions::ExtensionComponent,
         machine::{BaseComponent, Machine},
@@ -152,8 +157,7 @@ mod test {
         )
         .expect("error generating trace");
 
-        let output = view.get_public_output();
-        let output_bytes = output.iter().map(|entry| entry.value).collect::<Vec<_>>();
+        let output_bytes = view.public_output_bytes();
 
         assert_eq!(
             output_bytes, expected_output_bytes,
@@ -515,8 +519,7 @@ mod test {
         )
         .expect("error generating trace");
 
-        let output = view.get_public_output();
-        let output_bytes = output.iter().map(|entry| entry.value).collect::<Vec<_>>();
+        let output_bytes = view.public_output_bytes();
 
         assert_eq!(
             output_bytes, expected_output_bytes,
@@ -554,6 +557,117 @@ mod test {
         verify(proof, &view).unwrap();
     }
 
+    #[test]
+    #[serial]
+    fn test_panic_info() {
+        let elfs = compile_multi(
+            "tests/integration-tests/panic_message",
+            &["-C opt-level=3"],
+            &HOME_PATH,
+        );
+        let (view, execution_trace) =
+            k_trace(elfs[0].clone(), &[], &[], &[], K).expect("error generating trace");
+
+        // The guest source is compiled as the main file of a temporary project.
+        assert_eq!(
+            view.panic_info(),
+            Some(PanicInfo {
+                message: "value 42 is too large".to_string(),
+                file: "src/main.rs".to_string(),
+                line: 6,
+            })
+        );
+        let proof = prove(&execution_trace, &view).unwrap();
+        verify(proof, &view).unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn test_prove_bss() {
+        let elfs = compile_multi(
+            "tests/integration-tests/bss",
+            &["-C opt-level=3"],
+            &HOME_PATH,
+        );
+        let (view, execution_trace) =
+            k_trace(elfs[0].clone(), &[], &[], &[], K).expect("error generating trace");
+        let mut expected_output_bytes = to_allocvec_cobs(&mut 21u32).unwrap();
+        let padded_len = expected_output_bytes.len().word_align();
+        expected_output_bytes.resize(padded_len, 0);
+        assert_eq!(view.public_output_bytes(), expected_output_bytes);
+
+        // The 2 MiB `.bss` is a zero region, only `.data` is listed in the initial memory.
+        let zero_region_len: u32 = view
+            .view_zero_regions()
+            .iter()
+            .map(|region| region.len)
+            .sum();
+        assert!(zero_region_len >= 2 << 20);
+        assert!(view.get_rw_initial_memory().len() < 1 << 16);
+        let initialized: Vec<u8> = [1u32, 2, 3, 4]
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect();
+        let mut rw_initial_memory = view.get_rw_initial_memory().to_vec();
+        rw_initial_memory.sort_by_key(|entry| entry.address);
+        assert!(rw_initial_memory
+            .windows(initialized.len())
+            .any(|window| window
+                .iter()
+                .map(|entry| entry.value)
+                .eq(initialized.clone())));
+
+        let proof = prove(&execution_trace, &view).unwrap();
+        assert!(proof.log_size.iter().all(|&log_size| log_size < 21));
+        verify(proof, &view).unwrap();
+    }
+
+    /// Traces the recursion guest with a 64 KiB stack and a single guard page below it.
+    fn trace_recursion(elf: ElfFile, mut depth: u32) -> (View, UniformTrace) {
+        let mut public_input = to_allocvec_cobs(&mut depth).unwrap();
+        public_input.resize(public_input.len().word_align(), 0);
+        let options = TraceOptions {
+            stack_guard: Some(StackGuard {
+                max_stack_size: 64 << 10,
+                guard_pages: 1,
+            }),
+            ..Default::default()
+        };
+        k_trace_with_options(elf, &[], &public_input, &[], K, options)
+            .expect("error generating trace")
+    }
+
+    #[test]
+    #[serial]
+    fn test_prove_stack_overflow() {
+        let elfs = compile_multi(
+            "tests/integration-tests/recursion",
+            &["-C opt-level=0"],
+            &HOME_PATH,
+        );
+
+        let (view, _) = trace_recursion(elfs[0].clone(), 10);
+        assert_eq!(
+            view.view_termination_cause(),
+            Some(TerminationCause::Exit(0))
+        );
+        let depth = view.view_max_stack_depth().unwrap();
+        assert!(depth >= 10 * 64 && depth < 64 << 10);
+
+        let (view, execution_trace) = trace_recursion(elfs[0].clone(), 1 << 20);
+        let Some(TerminationCause::StackOverflow { sp, addr }) = view.view_termination_cause()
+        else {
+            panic!("the guard region must stop the recursion");
+        };
+        let stack_top = view.view_memory_layout().unwrap().stack_top();
+        assert!(addr < stack_top - (64 << 10) && addr >= stack_top - (68 << 10));
+        assert!(view.view_deepest_sp().unwrap() <= sp);
+
+        // The faulting access is not part of the trace, execution up to it is proved as usual.
+        let proof = prove(&execution_trace, &view).unwrap();
+        verify(proof, &view).unwrap();
+    }
+
     #[test]
     #[serial]
     #[ignore]
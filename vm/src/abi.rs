@@ -0,0 +1,337 @@
+//! Test vectors for the guest syscall ABI.
+//!
+//! A guest issues a syscall by placing its code in `a7` and its arguments in `a0` to `a6` before executing
+//! `ecall`, see [`SyscallCode`](crate::SyscallCode). [`golden_vectors`] runs a fixed set of syscalls on the
+//! [`HarvardEmulator`] and records the state before and after each of them, so that alternative implementations
+//! of the guest runtime or of the host can be checked against the emulator. Hints are served by [`HINT_ID`], which
+//! returns its input reversed.
+//!
+//! Vectors are generated without randomness, files or clocks, and serialize to JSON with a stable schema. The
+//! checked-in copy lives in `vm/test/syscall_vectors.json`.
+
+use nexus_common::cpu::Registers;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    emulator::{Emulator, HarvardEmulator},
+    error::VMErrorKind,
+    memory::MemoryProcessor,
+    riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode, Register},
+};
+
+/// Bytes placed in memory before the syscall.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MemorySegment {
+    pub address: u32,
+    pub bytes: Vec<u8>,
+}
+
+/// Guest state before the syscall.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SyscallInput {
+    /// Registers `a0` to `a7`, `a7` holds the syscall code.
+    pub registers: [u32; 8],
+    /// Stack pointer `sp`.
+    pub sp: u32,
+    pub memory: Vec<MemorySegment>,
+    /// Remaining bytes of the private input tape.
+    pub private_input: Vec<u8>,
+    /// Remaining bytes of the output of the last hint.
+    pub hint_tape: Vec<u8>,
+}
+
+/// Guest state after the syscall.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SyscallOutput {
+    /// Registers `a0` to `a7`.
+    pub registers: [u32; 8],
+    /// Stack pointer `sp`.
+    pub sp: u32,
+    /// The segments of [`SyscallInput::memory`], read back after the syscall.
+    pub memory: Vec<MemorySegment>,
+    /// Buffers written to stdout, in order.
+    pub stdout: Vec<Vec<u8>>,
+    /// Remaining bytes of the private input tape.
+    pub private_input: Vec<u8>,
+    /// Remaining bytes of the output of the last hint.
+    pub hint_tape: Vec<u8>,
+    /// Error returned by the emulator, if the syscall stopped execution.
+    pub error: Option<SyscallError>,
+}
+
+/// Why a syscall stopped execution, the schema of the errors of [`VMErrorKind`] a syscall is able to return.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SyscallError {
+    /// The guest exited with `code`.
+    Exited { code: u32 },
+    /// `a7` doesn't hold the code of a syscall supported by the emulator.
+    UnimplementedSyscall { code: u32 },
+    /// The label passed to the cycle counter doesn't start with `^#` or `$#`.
+    InvalidProfileLabel { label: String },
+    /// The committed output doesn't continue at the end of the output committed so far.
+    NonContiguousOutputCommit { offset: u32, committed: u32 },
+    /// The committed output extends past the output segment.
+    OutputCommitOutOfBounds { offset: u32, len: u32 },
+    /// No hint is registered under `id`.
+    UnknownHint { id: u32 },
+    /// A buffer argument can't be accessed, `message` describes the memory error.
+    Memory { message: String },
+    /// Any other error of the emulator, `message` is its description.
+    Other { message: String },
+}
+
+impl From<&VMErrorKind> for SyscallError {
+    fn from(error: &VMErrorKind) -> Self {
+        match error {
+            VMErrorKind::VMExited(code) => Self::Exited { code: *code },
+            VMErrorKind::UnimplementedSyscall(code, _) => {
+                Self::UnimplementedSyscall { code: *code }
+            }
+            VMErrorKind::InvalidProfileLabel(label) => Self::InvalidProfileLabel {
+                label: label.clone(),
+            },
+            VMErrorKind::NonContiguousOutputCommit(offset, committed) => {
+                Self::NonContiguousOutputCommit {
+                    offset: *offset,
+                    committed: *committed,
+                }
+            }
+            VMErrorKind::OutputCommitOutOfBounds(offset, len) => Self::OutputCommitOutOfBounds {
+                offset: *offset,
+                len: *len,
+            },
+            VMErrorKind::UnknownHint(id) => Self::UnknownHint { id: *id },
+            VMErrorKind::MemoryError(error) | VMErrorKind::MemoryFault { error, .. } => {
+                Self::Memory {
+                    message: error.to_string(),
+                }
+            }
+            error => Self::Other {
+                message: error.to_string(),
+            },
+        }
+    }
+}
+
+/// A single syscall, with the guest state before and after it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SyscallVector {
+    pub name: String,
+    pub pre: SyscallInput,
+    pub post: SyscallOutput,
+}
+
+const A0: u8 = 10;
+const A7: u8 = 17;
+const SP: u8 = 2;
+
+/// Address of the buffers passed to syscalls.
+const BUFFER_ADDRESS: u32 = 0x1000;
+
+/// Id of the hint registered by the vectors, returning its input reversed.
+pub const HINT_ID: u32 = 1;
+
+/// Executes a single `ecall` on the first-pass emulator, starting from `pre`.
+fn run_syscall(name: &str, pre: SyscallInput) -> SyscallVector {
+    let ecall = Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0);
+    let mut emulator = HarvardEmulator::from_basic_blocks(&vec![BasicBlock::new(vec![ecall])]);
+    emulator.executor.capture_logs(true);
+    emulator.executor.private_input_tape = pre.private_input.iter().copied().collect();
+    emulator.executor.hint_tape = pre.hint_tape.iter().copied().collect();
+    emulator
+        .executor
+        .hints
+        .register(HINT_ID, |input| input.iter().rev().copied().collect());
+
+    let registers = &mut emulator.executor.cpu.registers;
+    for (i, &value) in pre.registers.iter().enumerate() {
+        registers.write(Register::from(A0 + i as u8), value);
+    }
+    registers.write(Register::from(SP), pre.sp);
+    for segment in &pre.memory {
+        emulator
+            .data_memory
+            .write_bytes(segment.address, &segment.bytes)
+            .expect("segments are writable");
+    }
+
+    let error = emulator
+        .execute_instruction(&ecall, false)
+        .err()
+        .map(|err| SyscallError::from(&err.source));
+
+    let memory = pre
+        .memory
+        .iter()
+        .map(|segment| MemorySegment {
+            address: segment.address,
+            bytes: emulator
+                .data_memory
+                .read_bytes(segment.address, segment.bytes.len())
+                .expect("segments are readable"),
+        })
+        .collect();
+    let registers = &emulator.executor.cpu.registers;
+    let post = SyscallOutput {
+        registers: std::array::from_fn(|i| registers.read(Register::from(A0 + i as u8))),
+        sp: registers.read(Register::from(SP)),
+        memory,
+        stdout: emulator.executor.logs.clone().unwrap_or_default(),
+        private_input: emulator
+            .executor
+            .private_input_tape
+            .iter()
+            .copied()
+            .collect(),
+        hint_tape: emulator.executor.hint_tape.iter().copied().collect(),
+        error,
+    };
+    SyscallVector {
+        name: name.to_string(),
+        pre,
+        post,
+    }
+}
+
+/// Syscall `code` with arguments `args`, with `memory` at [`BUFFER_ADDRESS`].
+fn syscall(code: u32, args: &[u32], memory: &[u8], private_input: &[u8]) -> SyscallInput {
+    hint_syscall(code, args, memory, private_input, &[])
+}
+
+/// Same as [`syscall`], with `hint_tape` left by a previous hint.
+fn hint_syscall(
+    code: u32,
+    args: &[u32],
+    memory: &[u8],
+    private_input: &[u8],
+    hint_tape: &[u8],
+) -> SyscallInput {
+    let mut registers = [0; 8];
+    registers[..args.len()].copy_from_slice(args);
+    registers[(A7 - A0) as usize] = code;
+    SyscallInput {
+        registers,
+        sp: 0,
+        memory: if memory.is_empty() {
+            Vec::new()
+        } else {
+            vec![MemorySegment {
+                address: BUFFER_ADDRESS,
+                bytes: memory.to_vec(),
+            }]
+        },
+        private_input: private_input.to_vec(),
+        hint_tape: hint_tape.to_vec(),
+    }
+}
+
+/// Generates vectors covering every syscall supported by the emulator, together with their failure modes.
+pub fn golden_vectors() -> Vec<SyscallVector> {
+    let len = |bytes: &[u8]| bytes.len() as u32;
+    [
+        (
+            "write_stdout",
+            syscall(0x200, &[1, BUFFER_ADDRESS, 5], b"hello", &[]),
+        ),
+        (
+            "write_bad_fd",
+            syscall(0x200, &[2, BUFFER_ADDRESS, 5], b"hello", &[]),
+        ),
+        (
+            "write_pointer_out_of_bounds",
+            syscall(0x200, &[1, 0xFFFF_FFFD, 8], &[], &[]),
+        ),
+        ("exit", syscall(0x201, &[7], &[], &[])),
+        (
+            "read_private_input",
+            syscall(0x400, &[], &[], &[0xAB, 0xCD]),
+        ),
+        ("read_private_input_empty", syscall(0x400, &[], &[], &[])),
+        (
+            "cycle_count_start",
+            syscall(0x401, &[BUFFER_ADDRESS, len(b"^#main")], b"^#main", &[]),
+        ),
+        (
+            "cycle_count_unmatched_end",
+            syscall(0x401, &[BUFFER_ADDRESS, len(b"$#main")], b"$#main", &[]),
+        ),
+        (
+            "cycle_count_invalid_label",
+            syscall(0x401, &[BUFFER_ADDRESS, len(b"main")], b"main", &[]),
+        ),
+        ("overwrite_stack_pointer", syscall(0x402, &[], &[], &[])),
+        ("overwrite_heap_pointer", syscall(0x403, &[1], &[], &[])),
+        ("read_auxiliary_input", syscall(0x404, &[], &[], &[])),
+        (
+            "memory_advise",
+            syscall(0x405, &[BUFFER_ADDRESS, 64], &[], &[]),
+        ),
+        ("commit", syscall(0x406, &[0, 4], &[], &[])),
+        ("commit_non_contiguous", syscall(0x406, &[4, 4], &[], &[])),
+        (
+            "hint",
+            syscall(0x407, &[HINT_ID, BUFFER_ADDRESS, 3], b"abc", &[]),
+        ),
+        (
+            "hint_unknown_id",
+            syscall(0x407, &[HINT_ID + 1, BUFFER_ADDRESS, 3], b"abc", &[]),
+        ),
+        (
+            "read_hint",
+            hint_syscall(0x408, &[], &[], &[], &[1, 2, 3, 4, 5]),
+        ),
+        (
+            "read_hint_past_end",
+            hint_syscall(0x408, &[], &[], &[], &[1, 2]),
+        ),
+        ("read_cycle_counter", syscall(0x409, &[], &[], &[])),
+        (
+            "read_private_input_word",
+            syscall(0x40a, &[BUFFER_ADDRESS], &[0; 4], &[1, 2, 3]),
+        ),
+        (
+            "read_private_input_word_unaligned",
+            syscall(0x40a, &[BUFFER_ADDRESS + 1], &[0; 4], &[1, 2, 3]),
+        ),
+    ]
+    .into_iter()
+    .map(|(name, pre)| run_syscall(name, pre))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn golden_vectors_match_checked_in_copy() {
+        let golden: Vec<SyscallVector> =
+            serde_json::from_str(include_str!("../test/syscall_vectors.json"))
+                .expect("golden vectors follow the schema");
+        let vectors = golden_vectors();
+
+        // Compare one by one for a readable failure, then check nothing was added or removed.
+        for (vector, expected) in vectors.iter().zip(&golden) {
+            assert_eq!(vector, expected, "vector {} changed", expected.name);
+        }
+        assert_eq!(
+            vectors.iter().map(|v| &v.name).collect::<Vec<_>>(),
+            golden.iter().map(|v| &v.name).collect::<Vec<_>>(),
+            "regenerate with `serde_json::to_string_pretty(&golden_vectors())`"
+        );
+    }
+
+    #[test]
+    fn golden_vectors_are_deterministic() {
+        let json = serde_json::to_string(&golden_vectors()).unwrap();
+        assert_eq!(json, serde_json::to_string(&golden_vectors()).unwrap());
+
+        let parsed: Vec<SyscallVector> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, golden_vectors());
+    }
+}
@@ -0,0 +1,224 @@
+//! Static analysis of guest programs.
+//!
+//! Decodes every instruction of a program with the same decoder the emulator uses, so that a program can be
+//! checked for instructions the zkVM cannot execute or prove before running it.
+//!
+//! ```rust
+//! use nexus_vm::{analyze_program, riscv::BuiltinOpcode};
+//!
+//! let elf_bytes = std::fs::read("test/fib_10.elf").expect("Failed to read ELF");
+//! let analysis = analyze_program(&elf_bytes).expect("Failed to parse ELF");
+//!
+//! for instruction in &analysis.unsupported {
+//!     println!("unsupported instruction 0x{:08x} at 0x{:08x}", instruction.word, instruction.pc);
+//! }
+//! assert!(analysis.opcode_counts[&BuiltinOpcode::ADDI] > 0);
+//! ```
+//!
+//! Hints depending on an execution of the program are reported by [`optimization_report`].
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    elf::ElfFile,
+    error::Result,
+    riscv::{decode_instruction, BuiltinOpcode},
+    WORD_SIZE,
+};
+
+mod optimization;
+
+pub use optimization::{
+    optimization_report, BiasedBranch, BranchDirection, DeadCode, HotBlock, OptimizationReport,
+    RedundantLoad, HOT_BLOCKS,
+};
+
+const AMO_OPCODE: u32 = 0b0101111;
+const SYSTEM_OPCODE: u32 = 0b1110011;
+
+/// A RISC-V extension beyond the RV32I base ISA.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Extension {
+    /// Integer multiplication and division.
+    M,
+    /// Atomic instructions.
+    A,
+    /// Control and status register instructions.
+    Zicsr,
+}
+
+/// An instruction word the zkVM cannot execute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnsupportedInstruction {
+    /// Address of the instruction.
+    pub pc: u32,
+    /// Raw instruction word.
+    pub word: u32,
+}
+
+/// Result of the static analysis of a program, see [`analyze_program`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProgramAnalysis {
+    /// Size of the executable code in bytes.
+    pub code_size: usize,
+    /// Number of occurrences of every builtin opcode.
+    pub opcode_counts: HashMap<BuiltinOpcode, usize>,
+    /// Number of custom (precompile) instructions.
+    pub custom_count: usize,
+    /// Instruction words that don't decode to an instruction supported by the zkVM, in address order.
+    pub unsupported: Vec<UnsupportedInstruction>,
+    /// Addresses of the instructions requiring each extension, in address order.
+    pub extensions: BTreeMap<Extension, Vec<u32>>,
+}
+
+impl ProgramAnalysis {
+    /// Analyze the instructions of an already parsed ELF file.
+    pub fn from_elf(elf: &ElfFile) -> Self {
+        let mut analysis = Self {
+            code_size: elf.instructions.len() * WORD_SIZE,
+            ..Default::default()
+        };
+
+        for (i, &word) in elf.instructions.iter().enumerate() {
+            let pc = elf.base + (i * WORD_SIZE) as u32;
+
+            // A and Zicsr instructions aren't decoded, so they are recognized by their major opcode.
+            let extension = match word & 0x7F {
+                AMO_OPCODE => Some(Extension::A),
+                SYSTEM_OPCODE if (word >> 12) & 0x7 != 0 => Some(Extension::Zicsr),
+                _ => None,
+            };
+
+            let instruction = decode_instruction(word);
+            let extension = match instruction.opcode.builtin() {
+                Some(BuiltinOpcode::UNIMPL) => {
+                    analysis
+                        .unsupported
+                        .push(UnsupportedInstruction { pc, word });
+                    extension
+                }
+                Some(opcode) => {
+                    *analysis.opcode_counts.entry(opcode).or_default() += 1;
+                    match opcode {
+                        BuiltinOpcode::MUL
+                        | BuiltinOpcode::MULH
+                        | BuiltinOpcode::MULHSU
+                        | BuiltinOpcode::MULHU
+                        | BuiltinOpcode::DIV
+                        | BuiltinOpcode::DIVU
+                        | BuiltinOpcode::REM
+                        | BuiltinOpcode::REMU => Some(Extension::M),
+                        _ => extension,
+                    }
+                }
+                None => {
+                    analysis.custom_count += 1;
+                    extension
+                }
+            };
+
+            if let Some(extension) = extension {
+                analysis.extensions.entry(extension).or_default().push(pc);
+            }
+        }
+
+        analysis
+    }
+
+    /// Return whether every instruction of the program is supported by the zkVM.
+    pub fn is_supported(&self) -> bool {
+        self.unsupported.is_empty()
+    }
+
+    /// Return whether the program contains instructions of the given extension.
+    pub fn requires(&self, extension: Extension) -> bool {
+        self.extensions.contains_key(&extension)
+    }
+}
+
+/// Decode every instruction in the executable segments of an ELF file and report on them.
+pub fn analyze_program(elf_bytes: &[u8]) -> Result<ProgramAnalysis> {
+    let elf = ElfFile::from_bytes(elf_bytes)?;
+    Ok(ProgramAnalysis::from_elf(&elf))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::riscv::{Instruction, Opcode};
+    use nexus_common::constants::ELF_TEXT_START;
+
+    fn elf_from_instructions(instructions: Vec<Instruction>) -> ElfFile {
+        ElfFile::new(
+            instructions.iter().map(Instruction::encode).collect(),
+            ELF_TEXT_START,
+            ELF_TEXT_START,
+            Default::default(),
+            Default::default(),
+            vec![],
+        )
+    }
+
+    #[test]
+    fn test_analyze_flags_m_extension() {
+        let elf = elf_from_instructions(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 3),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 5),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::MUL), 3, 1, 2),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 4, 3, 1),
+        ]);
+
+        let analysis = ProgramAnalysis::from_elf(&elf);
+        assert!(analysis.is_supported());
+        assert_eq!(analysis.code_size, 16);
+        assert_eq!(analysis.opcode_counts[&BuiltinOpcode::ADDI], 2);
+        assert_eq!(analysis.opcode_counts[&BuiltinOpcode::MUL], 1);
+        assert_eq!(analysis.extensions[&Extension::M], vec![ELF_TEXT_START + 8]);
+        assert!(!analysis.requires(Extension::A));
+    }
+
+    #[test]
+    fn test_analyze_reports_unsupported() {
+        let mut elf = elf_from_instructions(vec![Instruction::new_ir(
+            Opcode::from(BuiltinOpcode::ADDI),
+            1,
+            0,
+            3,
+        )]);
+        // csrrs x1, cycle, x0
+        elf.instructions.push(0xC00020F3);
+        // amoadd.w x1, x2, (x3)
+        elf.instructions.push(0x0021A0AF);
+
+        let analysis = ProgramAnalysis::from_elf(&elf);
+        assert!(!analysis.is_supported());
+        assert_eq!(
+            analysis.unsupported,
+            vec![
+                UnsupportedInstruction {
+                    pc: ELF_TEXT_START + 4,
+                    word: 0xC00020F3
+                },
+                UnsupportedInstruction {
+                    pc: ELF_TEXT_START + 8,
+                    word: 0x0021A0AF
+                },
+            ]
+        );
+        assert_eq!(
+            analysis.extensions[&Extension::Zicsr],
+            vec![ELF_TEXT_START + 4]
+        );
+        assert_eq!(analysis.extensions[&Extension::A], vec![ELF_TEXT_START + 8]);
+    }
+
+    #[test]
+    fn test_analyze_program_from_bytes() {
+        let elf_bytes = crate::read_testing_binary_from_path!("/test/fib_10.elf");
+        let analysis = analyze_program(&elf_bytes).unwrap();
+        let elf = ElfFile::from_bytes(&elf_bytes).unwrap();
+
+        assert_eq!(analysis, ProgramAnalysis::from_elf(&elf));
+        assert_eq!(analysis.code_size, elf.instructions.len() * WORD_SIZE);
+    }
+}
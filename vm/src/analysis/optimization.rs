@@ -0,0 +1,403 @@
+//! Optimization hints for guest programs, derived from the trace of an execution.
+//!
+//! Unlike the static analysis of [`analyze_program`](super::analyze_program), findings depend on the input of the
+//! execution: code that isn't executed with one input may well be with another.
+
+use std::{collections::HashMap, fmt};
+
+use nexus_common::riscv::register::NUM_REGISTERS;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    emulator::ProgramInfo,
+    riscv::{
+        decode_instruction, decode_instructions, BuiltinOpcode, ControlFlowGraph, Instruction,
+        InstructionType, Register,
+    },
+    trace::Trace,
+    WORD_SIZE,
+};
+
+/// Number of basic blocks listed in [`OptimizationReport::hot_blocks`].
+pub const HOT_BLOCKS: usize = 5;
+
+/// A run of consecutive instructions that were never executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeadCode {
+    /// Address of the first instruction.
+    pub start: u32,
+    /// Address past the last instruction.
+    pub end: u32,
+}
+
+impl DeadCode {
+    pub fn num_instructions(&self) -> usize {
+        (self.end - self.start) as usize / WORD_SIZE
+    }
+}
+
+/// Direction of a branch that went the same way every time it was executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BranchDirection {
+    AlwaysTaken,
+    NeverTaken,
+}
+
+/// A conditional branch that went the same way every time it was executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BiasedBranch {
+    pub pc: u32,
+    /// Raw instruction word.
+    pub word: u32,
+    pub executions: usize,
+    pub direction: BranchDirection,
+}
+
+/// A basic block and the share of the cycles of the execution spent in it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HotBlock {
+    /// Address of the first instruction.
+    pub start: u32,
+    /// Address past the last instruction.
+    pub end: u32,
+    pub cycles: usize,
+    /// Fraction of all cycles, between 0 and 1.
+    pub share: f64,
+}
+
+/// A load whose value was overwritten before being read at least once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedundantLoad {
+    pub pc: u32,
+    /// Raw instruction word.
+    pub word: u32,
+    pub executions: usize,
+    /// Number of executions whose loaded value was overwritten without being read.
+    pub unused: usize,
+}
+
+/// Optimization hints for a program from one of its executions, see [`optimization_report`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OptimizationReport {
+    /// Steps of the execution, each of which takes one cycle.
+    pub num_steps: usize,
+    /// Instructions of the program.
+    pub num_instructions: usize,
+    /// Instructions of the program that were never executed, in address order.
+    pub dead_code: Vec<DeadCode>,
+    /// Conditional branches that were executed and always went the same way, in address order.
+    pub biased_branches: Vec<BiasedBranch>,
+    /// The [`HOT_BLOCKS`] basic blocks with the most cycles, hottest first.
+    pub hot_blocks: Vec<HotBlock>,
+    /// Loads whose value was overwritten without being read, in address order.
+    pub redundant_loads: Vec<RedundantLoad>,
+}
+
+/// Registers read and written by an instruction. Instructions that may read any register, system calls and
+/// precompiles, read all of them and write none.
+fn register_accesses(instruction: &Instruction) -> (Vec<Register>, Option<Register>) {
+    let all = || (0..NUM_REGISTERS as u8).map(Register::from).collect();
+    let Some(opcode) = instruction.opcode.builtin() else {
+        return (all(), None);
+    };
+    if matches!(opcode, BuiltinOpcode::ECALL | BuiltinOpcode::EBREAK) {
+        return (all(), None);
+    }
+    let (reads, writes) = match instruction.ins_type {
+        InstructionType::RType => (
+            vec![instruction.op_b, Register::from(instruction.op_c as u8)],
+            true,
+        ),
+        InstructionType::IType | InstructionType::ITypeShamt => (vec![instruction.op_b], true),
+        InstructionType::SType | InstructionType::BType => {
+            (vec![instruction.op_a, instruction.op_b], false)
+        }
+        InstructionType::UType | InstructionType::JType => (vec![], true),
+        InstructionType::Unimpl => (vec![], false),
+    };
+    let write = Some(instruction.op_a).filter(|&rd| writes && rd != Register::X0);
+    (reads, write)
+}
+
+fn is_load(instruction: &Instruction) -> bool {
+    matches!(
+        instruction.opcode.builtin(),
+        Some(
+            BuiltinOpcode::LB
+                | BuiltinOpcode::LH
+                | BuiltinOpcode::LW
+                | BuiltinOpcode::LBU
+                | BuiltinOpcode::LHU
+        )
+    )
+}
+
+/// Reports dead code, biased branches, hot basic blocks and redundant loads of `program_info` in the execution of
+/// `trace`.
+///
+/// Loaded values are tracked per register along the trace: a load is redundant when its destination is written
+/// again before being read. Values still live at the end of the trace aren't reported. System calls and precompiles
+/// count as reading every register.
+pub fn optimization_report(trace: &impl Trace, program_info: &ProgramInfo) -> OptimizationReport {
+    let mut executions: HashMap<u32, usize> = HashMap::new();
+    let mut taken: HashMap<u32, usize> = HashMap::new();
+    let mut unused_loads: HashMap<u32, usize> = HashMap::new();
+    // Load that last wrote each register, if the value wasn't read since.
+    let mut pending_loads: [Option<u32>; NUM_REGISTERS] = [None; NUM_REGISTERS];
+    let mut num_steps = 0;
+
+    for step in trace.get_blocks_iter().flat_map(|block| &block.steps) {
+        num_steps += 1;
+        if step.instruction.ins_type == InstructionType::BType
+            && step.next_pc != step.pc + WORD_SIZE as u32
+        {
+            *taken.entry(step.pc).or_default() += 1;
+        }
+        let fused = step
+            .fused
+            .iter()
+            .map(|fused| (step.pc + WORD_SIZE as u32, &fused.instruction));
+        for (pc, instruction) in std::iter::once((step.pc, &step.instruction)).chain(fused) {
+            *executions.entry(pc).or_default() += 1;
+            let (reads, write) = register_accesses(instruction);
+            for reg in reads {
+                pending_loads[reg as usize] = None;
+            }
+            if let Some(rd) = write {
+                if let Some(load_pc) = pending_loads[rd as usize].take() {
+                    *unused_loads.entry(load_pc).or_default() += 1;
+                }
+                if is_load(instruction) {
+                    pending_loads[rd as usize] = Some(pc);
+                }
+            }
+        }
+    }
+
+    let program = &program_info.program;
+    let mut dead_code: Vec<DeadCode> = Vec::new();
+    let mut biased_branches = Vec::new();
+    let mut redundant_loads = Vec::new();
+    for entry in program {
+        let count = executions.get(&entry.pc).copied().unwrap_or_default();
+        if count == 0 {
+            match dead_code.last_mut() {
+                Some(dead) if dead.end == entry.pc => dead.end += WORD_SIZE as u32,
+                _ => dead_code.push(DeadCode {
+                    start: entry.pc,
+                    end: entry.pc + WORD_SIZE as u32,
+                }),
+            }
+            continue;
+        }
+        let instruction = decode_instruction(entry.instruction_word);
+        if instruction.ins_type == InstructionType::BType {
+            let direction = match taken.get(&entry.pc).copied().unwrap_or_default() {
+                0 => Some(BranchDirection::NeverTaken),
+                taken if taken == count => Some(BranchDirection::AlwaysTaken),
+                _ => None,
+            };
+            if let Some(direction) = direction {
+                biased_branches.push(BiasedBranch {
+                    pc: entry.pc,
+                    word: entry.instruction_word,
+                    executions: count,
+                    direction,
+                });
+            }
+        }
+        if let Some(&unused) = unused_loads.get(&entry.pc) {
+            redundant_loads.push(RedundantLoad {
+                pc: entry.pc,
+                word: entry.instruction_word,
+                executions: count,
+                unused,
+            });
+        }
+    }
+
+    // Entries of the program are laid out consecutively from the first one.
+    let base = program.first().map_or(0, |entry| entry.pc);
+    let words: Vec<u32> = program.iter().map(|entry| entry.instruction_word).collect();
+    let graph = ControlFlowGraph::new(base, &decode_instructions(&words).blocks);
+    let mut cycles = vec![0; graph.nodes.len()];
+    for step in trace.get_blocks_iter().flat_map(|block| &block.steps) {
+        if let Some(node) = graph.node_of(step.pc) {
+            cycles[node] += 1;
+        }
+    }
+    let mut hot_blocks: Vec<HotBlock> = graph
+        .nodes
+        .iter()
+        .zip(cycles)
+        .filter(|(_, cycles)| *cycles > 0)
+        .map(|(node, cycles)| HotBlock {
+            start: node.start,
+            end: node.end,
+            cycles,
+            share: cycles as f64 / num_steps as f64,
+        })
+        .collect();
+    hot_blocks.sort_by(|a, b| b.cycles.cmp(&a.cycles).then(a.start.cmp(&b.start)));
+    hot_blocks.truncate(HOT_BLOCKS);
+
+    OptimizationReport {
+        num_steps,
+        num_instructions: program.len(),
+        dead_code,
+        biased_branches,
+        hot_blocks,
+        redundant_loads,
+    }
+}
+
+impl fmt::Display for OptimizationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let dead: usize = self.dead_code.iter().map(DeadCode::num_instructions).sum();
+        writeln!(
+            f,
+            "OptimizationReport {{ {} steps, {} of {} instructions executed }}",
+            self.num_steps,
+            self.num_instructions - dead,
+            self.num_instructions
+        )?;
+        if !self.dead_code.is_empty() {
+            writeln!(f, "dead code:")?;
+            for dead in &self.dead_code {
+                writeln!(
+                    f,
+                    "  {:#010x}..{:#010x}  {} instructions",
+                    dead.start,
+                    dead.end,
+                    dead.num_instructions()
+                )?;
+            }
+        }
+        if !self.biased_branches.is_empty() {
+            writeln!(f, "biased branches:")?;
+            for branch in &self.biased_branches {
+                let direction = match branch.direction {
+                    BranchDirection::AlwaysTaken => "always taken",
+                    BranchDirection::NeverTaken => "never taken",
+                };
+                writeln!(
+                    f,
+                    "  {:#010x}  {direction:<12}  {:>8}x  {}",
+                    branch.pc,
+                    branch.executions,
+                    decode_instruction(branch.word)
+                )?;
+            }
+        }
+        if !self.hot_blocks.is_empty() {
+            writeln!(f, "hot blocks:")?;
+            for block in &self.hot_blocks {
+                writeln!(
+                    f,
+                    "  {:#010x}..{:#010x}  {:>8} cycles  {:>5.1}%",
+                    block.start,
+                    block.end,
+                    block.cycles,
+                    block.share * 100.0
+                )?;
+            }
+        }
+        if !self.redundant_loads.is_empty() {
+            writeln!(f, "redundant loads:")?;
+            for load in &self.redundant_loads {
+                writeln!(
+                    f,
+                    "  {:#010x}  unused {} of {}  {}",
+                    load.pc,
+                    load.unused,
+                    load.executions,
+                    decode_instruction(load.word)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{riscv::builder::ProgramBuilder, trace::k_trace_direct};
+
+    #[test]
+    fn test_optimization_report() {
+        let blocks = ProgramBuilder::new()
+            .li(1, 0x10000)
+            .li(2, 7)
+            .sw(1, 2, 0)
+            // Overwritten by the next instruction.
+            .lw(3, 1, 0)
+            .li(3, 1)
+            .lw(4, 1, 0)
+            .add(5, 4, 3)
+            .beq(3, 0, "dead")
+            .jal(0, "end")
+            .label("dead")
+            .addi(5, 5, 1)
+            .label("end")
+            .nop()
+            .build()
+            .unwrap();
+        let (view, trace) = k_trace_direct(&blocks, 1).unwrap();
+        let program_info = view.get_program_memory();
+        let entry_of = |opcode: BuiltinOpcode, op_a: u8| {
+            program_info
+                .program
+                .iter()
+                .find(|entry| {
+                    let instruction = decode_instruction(entry.instruction_word);
+                    instruction.opcode.builtin() == Some(opcode)
+                        && instruction.op_a == Register::from(op_a)
+                })
+                .unwrap()
+        };
+
+        let report = optimization_report(&trace, program_info);
+        assert_eq!(report.num_steps, trace.get_num_steps());
+
+        let dead_pc = entry_of(BuiltinOpcode::ADDI, 5).pc;
+        assert_eq!(
+            report.dead_code,
+            [DeadCode {
+                start: dead_pc,
+                end: dead_pc + WORD_SIZE as u32
+            }]
+        );
+
+        let branch_pc = entry_of(BuiltinOpcode::BEQ, 3).pc;
+        assert_eq!(report.biased_branches.len(), 1);
+        assert_eq!(report.biased_branches[0].pc, branch_pc);
+        assert_eq!(report.biased_branches[0].executions, 1);
+        assert_eq!(
+            report.biased_branches[0].direction,
+            BranchDirection::NeverTaken
+        );
+
+        let load = entry_of(BuiltinOpcode::LW, 3);
+        assert_eq!(
+            report.redundant_loads,
+            [RedundantLoad {
+                pc: load.pc,
+                word: load.instruction_word,
+                executions: 1,
+                unused: 1,
+            }]
+        );
+
+        let total: usize = report.hot_blocks.iter().map(|block| block.cycles).sum();
+        assert!(total <= report.num_steps);
+        assert!(report
+            .hot_blocks
+            .windows(2)
+            .all(|pair| pair[0].cycles >= pair[1].cycles));
+
+        let display = report.to_string();
+        assert!(display.contains("never taken"));
+        assert!(display.contains("unused 1 of 1"));
+    }
+}
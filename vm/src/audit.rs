@@ -0,0 +1,311 @@
+//! Determinism audit of guest executions.
+//!
+//! Proving relies on every run of a program on the same inputs, including the two emulator passes, executing the
+//! same steps. Host state reaching the guest, e.g. through a hint that reads the clock, breaks this silently: the
+//! runs produce different views and traces.
+//!
+//! [`run_and_hash`] digests the stream of executed steps, [`assert_deterministic`] runs a program twice and reports
+//! the first step at which the runs diverge. The runs are compared in blocks of [`DIGEST_BLOCK_STEPS`] steps, the
+//! memory of the audit doesn't grow with the length of the execution.
+//!
+//! ```rust
+//! use nexus_vm::{audit::assert_deterministic, elf::ElfFile, system::HintRegistry};
+//!
+//! let elf = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+//! assert_deterministic(&elf, &[], &[], &HintRegistry::default()).unwrap();
+//! ```
+
+use nexus_common::cpu::Registers;
+use thiserror::Error;
+use tiny_keccak::{Hasher, Keccak};
+
+use crate::{
+    cpu::RegisterFile,
+    elf::ElfFile,
+    emulator::{Emulator, HarvardEmulator},
+    error::{Result, VMError, VMErrorKind},
+    memory::MemoryRecord,
+    riscv::Register,
+    system::HintRegistry,
+    trace::{k_step, Step},
+};
+
+/// Reasons for [`assert_deterministic`] to reject a program.
+#[derive(Debug, Error)]
+pub enum DeterminismError {
+    /// The program may call a hint registered as non-deterministic.
+    #[error("hint {0} is registered as non-deterministic")]
+    NondeterministicHint(u32),
+
+    /// Two runs of the program executed a different step at index `step`, counting from zero.
+    #[error("executions diverge at step {step} (pc=0x{pc:08X})")]
+    Divergence { step: usize, pc: u32 },
+
+    /// The program failed to execute.
+    #[error(transparent)]
+    Execution(#[from] VMError),
+}
+
+/// Number of steps of a run digested before it is compared with the other run.
+pub const DIGEST_BLOCK_STEPS: usize = 1 << 12;
+
+/// A single run, digested one block of steps at a time.
+struct StepDigester {
+    emulator: HarvardEmulator,
+    // Chained hash of all steps executed so far.
+    digest: [u8; 32],
+    finished: bool,
+    // The error that stopped execution, if it wasn't the program exiting or running out of instructions.
+    error: Option<VMError>,
+}
+
+/// Runs the program on the given inputs and returns the digest of all executed steps: their pc, instruction,
+/// result, memory accesses and the registers after them.
+///
+/// Two runs have the same digest exactly when they execute the same steps.
+pub fn run_and_hash(
+    elf: &ElfFile,
+    public_input: &[u8],
+    private_input: &[u8],
+    hints: &HintRegistry,
+) -> Result<[u8; 32]> {
+    let mut run = StepDigester::new(elf, public_input, private_input, hints);
+    while !run.finished {
+        run.next_block();
+    }
+    match run.error {
+        Some(error) => Err(error),
+        None => Ok(run.digest),
+    }
+}
+
+/// Runs the program twice on the same inputs and checks that both runs execute the same steps.
+///
+/// Programs with access to hints registered as non-deterministic, see
+/// [`HintRegistry::register_with_determinism`], are refused without being run.
+pub fn assert_deterministic(
+    elf: &ElfFile,
+    public_input: &[u8],
+    private_input: &[u8],
+    hints: &HintRegistry,
+) -> std::result::Result<(), DeterminismError> {
+    if let Some(&id) = hints.nondeterministic_ids().first() {
+        return Err(DeterminismError::NondeterministicHint(id));
+    }
+
+    let mut first = StepDigester::new(elf, public_input, private_input, hints);
+    let mut second = StepDigester::new(elf, public_input, private_input, hints);
+
+    // The digests are chained, the runs agree up to the first differing one.
+    let mut block_start = 0;
+    while !(first.finished && second.finished) {
+        let first_block = first.next_block();
+        let second_block = second.next_block();
+        let common_len = first_block.len().min(second_block.len());
+        let diverging_step = (0..common_len)
+            .find(|&step| first_block[step].0 != second_block[step].0)
+            .or((first_block.len() != second_block.len()).then_some(common_len));
+        if let Some(step) = diverging_step {
+            let pc = first_block
+                .get(step)
+                .or(second_block.get(step))
+                .map(|&(_, pc)| pc)
+                .unwrap_or_default();
+            return Err(DeterminismError::Divergence {
+                step: block_start + step,
+                pc,
+            });
+        }
+        block_start += common_len;
+    }
+    match first.error {
+        Some(error) => Err(error.into()),
+        None => Ok(()),
+    }
+}
+
+impl StepDigester {
+    fn new(elf: &ElfFile, public_input: &[u8], private_input: &[u8], hints: &HintRegistry) -> Self {
+        let mut emulator = HarvardEmulator::from_elf(elf, public_input, private_input);
+        emulator.get_executor_mut().hints = hints.clone();
+        Self {
+            emulator,
+            digest: [0u8; 32],
+            finished: false,
+            error: None,
+        }
+    }
+
+    /// Executes up to [`DIGEST_BLOCK_STEPS`] steps and returns the chained digest and pc after each of them. Fewer
+    /// steps are returned only once the run is finished.
+    fn next_block(&mut self) -> Vec<([u8; 32], u32)> {
+        let mut block_digests = Vec::new();
+        while !self.finished && block_digests.len() < DIGEST_BLOCK_STEPS {
+            let (block, result) = k_step(&mut self.emulator, 1, false);
+            // Blocks of other errors only hold padding, the instruction wasn't executed.
+            let executed = matches!(
+                result,
+                Ok(())
+                    | Err(VMError {
+                        source: VMErrorKind::VMExited(_),
+                        ..
+                    })
+            );
+            if let Some(step) = block
+                .filter(|_| executed)
+                .and_then(|block| block.steps.into_iter().next())
+            {
+                self.digest = digest_step(
+                    &self.digest,
+                    &step,
+                    &self.emulator.get_executor().cpu.registers,
+                );
+                block_digests.push((self.digest, step.pc));
+            }
+            match result {
+                Ok(()) => {}
+                Err(VMError {
+                    source: VMErrorKind::VMExited(_) | VMErrorKind::VMOutOfInstructions,
+                    ..
+                }) => self.finished = true,
+                Err(error) => {
+                    self.error = Some(error);
+                    self.finished = true;
+                }
+            }
+        }
+        block_digests
+    }
+}
+
+/// Chains `step` and the registers after it to `previous`.
+fn digest_step(previous: &[u8; 32], step: &Step, registers: &RegisterFile) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    hasher.update(previous);
+    for word in [step.timestamp, step.pc, step.next_pc, step.raw_instruction] {
+        hasher.update(&word.to_le_bytes());
+    }
+    match step.result {
+        Some(value) => {
+            hasher.update(&[1]);
+            hasher.update(&value.to_le_bytes());
+        }
+        None => hasher.update(&[0]),
+    }
+
+    // Memory records are kept in a `BTreeSet`, both runs iterate them in the same order.
+    for record in &step.memory_records {
+        let kind = match record {
+            MemoryRecord::LoadRecord(..) => 0u32,
+            MemoryRecord::StoreRecord(..) => 1,
+        };
+        for word in [
+            kind,
+            record.get_address(),
+            record.get_size() as u32,
+            record.get_value(),
+            record.get_prev_value().unwrap_or_default(),
+        ] {
+            hasher.update(&word.to_le_bytes());
+        }
+    }
+
+    for reg in 0..32u8 {
+        hasher.update(&registers.read(Register::from(reg)).to_le_bytes());
+    }
+
+    let mut output = [0u8; 32];
+    hasher.finalize(&mut output);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    };
+
+    use super::*;
+    use crate::{
+        riscv::{BuiltinOpcode, Instruction, Opcode},
+        SyscallCode,
+    };
+    use nexus_common::constants::ELF_TEXT_START;
+
+    const CLOCK_HINT: u32 = 1;
+
+    /// Reads a word from the clock hint and exits.
+    fn clock_reader() -> ElfFile {
+        let op = |opcode, a, b, c| Instruction::new_ir(Opcode::from(opcode), a, b, c);
+        let instructions = [
+            op(BuiltinOpcode::ADDI, 10, 0, CLOCK_HINT),
+            op(BuiltinOpcode::ADDI, 12, 0, 0),
+            op(BuiltinOpcode::ADDI, 17, 0, SyscallCode::Hint as u32),
+            op(BuiltinOpcode::ECALL, 0, 0, 0),
+            op(BuiltinOpcode::ADDI, 17, 0, SyscallCode::ReadHint as u32),
+            op(BuiltinOpcode::ECALL, 0, 0, 0),
+            op(BuiltinOpcode::ADDI, 10, 0, 0),
+            op(BuiltinOpcode::ADDI, 17, 0, SyscallCode::Exit as u32),
+            op(BuiltinOpcode::ECALL, 0, 0, 0),
+        ];
+        ElfFile::new(
+            instructions.iter().map(Instruction::encode).collect(),
+            ELF_TEXT_START,
+            ELF_TEXT_START,
+            Default::default(),
+            Default::default(),
+            vec![],
+        )
+    }
+
+    /// A hint returning the number of times it was called, standing in for host state.
+    fn clock(deterministic: bool) -> HintRegistry {
+        let ticks = Arc::new(AtomicU32::new(0));
+        let mut hints = HintRegistry::default();
+        hints.register_with_determinism(CLOCK_HINT, deterministic, move |_| {
+            let tick = ticks.fetch_add(1, Ordering::Relaxed) + 1;
+            tick.to_le_bytes().to_vec()
+        });
+        hints
+    }
+
+    #[test]
+    fn test_deterministic_program() {
+        let mut hints = HintRegistry::default();
+        hints.register(CLOCK_HINT, |_| 7u32.to_le_bytes().to_vec());
+        assert!(assert_deterministic(&clock_reader(), &[], &[], &hints).is_ok());
+
+        let digest = run_and_hash(&clock_reader(), &[], &[], &hints).unwrap();
+        assert_eq!(
+            run_and_hash(&clock_reader(), &[], &[], &hints).unwrap(),
+            digest
+        );
+        assert_ne!(
+            run_and_hash(&clock_reader(), &[], &[], &clock(true)).unwrap(),
+            digest
+        );
+    }
+
+    #[test]
+    fn test_nondeterministic_hint_is_caught() {
+        // The hint returns the same number of bytes on both runs, the runs diverge once the guest reads them.
+        let err = assert_deterministic(&clock_reader(), &[], &[], &clock(true)).unwrap_err();
+        assert!(matches!(
+            err,
+            DeterminismError::Divergence {
+                step: 5,
+                pc
+            } if pc == ELF_TEXT_START + 5 * 4
+        ));
+    }
+
+    #[test]
+    fn test_nondeterministic_hint_is_refused() {
+        let err = assert_deterministic(&clock_reader(), &[], &[], &clock(false)).unwrap_err();
+        assert!(matches!(
+            err,
+            DeterminismError::NondeterministicHint(CLOCK_HINT)
+        ));
+    }
+}
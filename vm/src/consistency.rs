@@ -0,0 +1,34 @@
+//! Constants assumed by the emulator, checked against the ones shared with the prover, see
+//! [`nexus_common::consistency`].
+
+use std::mem::size_of;
+
+use nexus_common::consistency::{AssumedConstants, SHARED_CONSTANTS};
+
+use crate::{cpu::RegisterFile, WORD_SIZE};
+
+/// Global clock of the first executed instruction, memory records at clock zero capture the initial memory.
+pub(crate) const FIRST_CLK: usize = 1;
+
+const EMULATOR_CONSTANTS: AssumedConstants = AssumedConstants {
+    word_size: Some(WORD_SIZE),
+    num_registers: Some((size_of::<RegisterFile>() / size_of::<u32>()) as u32),
+    first_clk: Some(FIRST_CLK as u32),
+    // The memory layout takes its addresses from `nexus_common::constants`.
+    ..AssumedConstants::NONE
+};
+
+const _: () = assert!(
+    SHARED_CONSTANTS
+        .first_mismatch(&EMULATOR_CONSTANTS)
+        .is_none(),
+    "the emulator assumes constants that differ from nexus_common"
+);
+
+/// Panics, naming the constant, if the emulator assumes a constant that differs from the one shared with the
+/// prover.
+pub fn verify_shared_constants() {
+    if let Err(err) = SHARED_CONSTANTS.check(&EMULATOR_CONSTANTS, "emulator") {
+        panic!("{err}");
+    }
+}
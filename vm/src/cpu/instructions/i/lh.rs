@@ -31,6 +31,7 @@ mod tests {
     use crate::cpu::state::Cpu;
     use crate::memory::{VariableMemory, RW};
     use crate::riscv::{BuiltinOpcode, Instruction, Opcode, Register};
+    use nexus_common::error::{MemoryError, MemoryFaultKind};
 
     fn setup_memory() -> VariableMemory<RW> {
         let mut memory = VariableMemory::<RW>::default();
@@ -182,20 +183,37 @@ mod tests {
     }
 
     #[test]
-    fn test_lh_lhu_address_overflow() {
+    fn test_lh_lhu_address_wraps_around() {
         let mut cpu = Cpu::default();
-        let memory = setup_memory();
+        let mut memory = setup_memory();
+        memory.write(0x2, MemAccessSize::HalfWord, 0x8001).unwrap();
 
+        // u32::MAX + 3 wraps around to 0x2
         cpu.registers.write(Register::X1, u32::MAX);
 
-        let bare_instruction = Instruction::new_ir(Opcode::from(BuiltinOpcode::LH), 2, 1, 1);
+        let bare_instruction = Instruction::new_ir(Opcode::from(BuiltinOpcode::LH), 2, 1, 3);
         let mut instruction = LhInstruction::decode(&bare_instruction, &cpu.registers);
 
-        assert!(instruction.memory_read(&memory).is_err());
+        instruction.memory_read(&memory).unwrap();
+        assert_eq!(instruction.write_back(&mut cpu), Some(0xFFFF8001));
 
-        let bare_instruction = Instruction::new_ir(Opcode::from(BuiltinOpcode::LHU), 2, 1, 1);
+        let bare_instruction = Instruction::new_ir(Opcode::from(BuiltinOpcode::LHU), 2, 1, 3);
         let mut instruction = LhuInstruction::decode(&bare_instruction, &cpu.registers);
 
-        assert!(instruction.memory_read(&memory).is_err());
+        instruction.memory_read(&memory).unwrap();
+        assert_eq!(instruction.write_back(&mut cpu), Some(0x8001));
+
+        // u32::MAX + 2 wraps around to the unaligned 0x1
+        let bare_instruction = Instruction::new_ir(Opcode::from(BuiltinOpcode::LH), 2, 1, 2);
+        let mut instruction = LhInstruction::decode(&bare_instruction, &cpu.registers);
+
+        assert_eq!(
+            instruction.memory_read(&memory),
+            Err(MemoryError::AccessFault {
+                kind: MemoryFaultKind::Misaligned,
+                addr: 0x1,
+                size: MemAccessSize::HalfWord,
+            })
+        );
     }
 }
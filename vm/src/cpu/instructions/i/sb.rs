@@ -16,8 +16,6 @@ implement_store_instruction!(SbInstruction, MemAccessSize::Byte);
 
 #[cfg(test)]
 mod tests {
-    use nexus_common::error::MemoryError;
-
     use super::*;
     use crate::cpu::state::Cpu;
     use crate::memory::{LoadOp, VariableMemory, RW};
@@ -91,22 +89,33 @@ mod tests {
     }
 
     #[test]
-    fn test_sb_overflow() {
+    fn test_sb_address_wraps_around() {
         let mut cpu = Cpu::default();
         let mut memory = setup_memory();
 
-        cpu.registers.write(Register::X1, u32::MAX);
+        // 0xFFFFFFFC + 8 wraps around to 0x4
+        cpu.registers.write(Register::X1, 0xFFFF_FFFC);
         cpu.registers.write(Register::X2, 0xAA);
 
-        let bare_instruction = Instruction::new_ir(Opcode::from(BuiltinOpcode::SB), 1, 2, 1);
+        let bare_instruction = Instruction::new_ir(Opcode::from(BuiltinOpcode::SB), 1, 2, 8);
         let instruction = SbInstruction::decode(&bare_instruction, &cpu.registers);
 
-        let result = instruction.memory_write(&mut memory);
-        assert!(result.is_err());
-        assert!(matches!(
-            result.unwrap_err(),
-            MemoryError::AddressCalculationOverflow
-        ));
+        instruction.memory_write(&mut memory).unwrap();
+        assert_eq!(
+            memory.read(0x4, MemAccessSize::Byte).unwrap(),
+            LoadOp::Op(MemAccessSize::Byte, 0x4, 0xAA)
+        );
+
+        // 0xFFFFFFFC - 4 stays below u32::MAX
+        let bare_instruction =
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SB), 1, 2, 0xFFFFFFFC);
+        let instruction = SbInstruction::decode(&bare_instruction, &cpu.registers);
+
+        instruction.memory_write(&mut memory).unwrap();
+        assert_eq!(
+            memory.read(0xFFFF_FFF8, MemAccessSize::Byte).unwrap(),
+            LoadOp::Op(MemAccessSize::Byte, 0xFFFF_FFF8, 0xAA)
+        );
     }
 
     // TODO: depending on the memory model, we need to test out of bound memory access
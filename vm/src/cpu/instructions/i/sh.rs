@@ -16,7 +16,7 @@ implement_store_instruction!(ShInstruction, MemAccessSize::HalfWord);
 
 #[cfg(test)]
 mod tests {
-    use nexus_common::error::MemoryError;
+    use nexus_common::error::{MemoryError, MemoryFaultKind};
 
     use super::*;
     use crate::cpu::state::Cpu;
@@ -102,30 +102,44 @@ mod tests {
         let instruction = ShInstruction::decode(&bare_instruction, &cpu.registers);
 
         let result = instruction.memory_write(&mut memory);
-        assert!(result.is_err());
-        assert!(matches!(
-            result.unwrap_err(),
-            MemoryError::UnalignedMemoryWrite(0x1001)
-        ));
+        assert_eq!(
+            result,
+            Err(MemoryError::AccessFault {
+                kind: MemoryFaultKind::Misaligned,
+                addr: 0x1001,
+                size: MemAccessSize::HalfWord,
+            })
+        );
     }
 
     #[test]
-    fn test_sh_overflow() {
+    fn test_sh_address_wraps_around() {
         let mut cpu = Cpu::default();
         let mut memory = setup_memory();
 
-        cpu.registers.write(Register::X1, u32::MAX);
+        // 0xFFFFFFFC + 8 wraps around to 0x4
+        cpu.registers.write(Register::X1, 0xFFFF_FFFC);
         cpu.registers.write(Register::X2, 0xABCD);
 
-        let bare_instruction = Instruction::new_ir(Opcode::from(BuiltinOpcode::SH), 1, 2, 1);
+        let bare_instruction = Instruction::new_ir(Opcode::from(BuiltinOpcode::SH), 1, 2, 8);
         let instruction = ShInstruction::decode(&bare_instruction, &cpu.registers);
 
-        let result = instruction.memory_write(&mut memory);
-        assert!(result.is_err());
-        assert!(matches!(
-            result.unwrap_err(),
-            MemoryError::AddressCalculationOverflow
-        ));
+        instruction.memory_write(&mut memory).unwrap();
+        assert_eq!(
+            memory.read(0x4, MemAccessSize::HalfWord).unwrap(),
+            LoadOp::Op(MemAccessSize::HalfWord, 0x4, 0xABCD)
+        );
+
+        // 0xFFFFFFFC - 4 stays below u32::MAX
+        let bare_instruction =
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SH), 1, 2, 0xFFFFFFFC);
+        let instruction = ShInstruction::decode(&bare_instruction, &cpu.registers);
+
+        instruction.memory_write(&mut memory).unwrap();
+        assert_eq!(
+            memory.read(0xFFFF_FFF8, MemAccessSize::HalfWord).unwrap(),
+            LoadOp::Op(MemAccessSize::HalfWord, 0xFFFF_FFF8, 0xABCD)
+        );
     }
 
     // TODO: depending on the memory model, we need to test out of bound memory access
@@ -182,4 +182,26 @@ mod tests {
             0b1100_0000_0000_0000_0000_0000_0000_0000
         );
     }
+
+    #[test]
+    fn test_sra_uses_low_five_bits_of_rs2() {
+        let value = 0x8765_4321u32;
+        for amount in [32, 33, 63, 255] {
+            let mut cpu = Cpu::default();
+            cpu.registers.write(Register::X1, value);
+            cpu.registers.write(Register::X2, amount);
+
+            let bare_instruction = Instruction::new_ir(Opcode::from(BuiltinOpcode::SRA), 3, 1, 2);
+            let mut instruction = SraInstruction::decode(&bare_instruction, &cpu.registers);
+
+            instruction.execute();
+            let res = instruction.write_back(&mut cpu);
+
+            assert_eq!(
+                res,
+                Some((value as i32).wrapping_shr(amount) as u32),
+                "shift amount {amount}"
+            );
+        }
+    }
 }
@@ -136,4 +136,26 @@ mod tests {
         assert_eq!(res, Some(0x40000000));
         assert_eq!(cpu.registers.read(Register::X3), 0x40000000);
     }
+
+    #[test]
+    fn test_srl_uses_low_five_bits_of_rs2() {
+        let value = 0x8765_4321u32;
+        for amount in [32, 33, 63, 255] {
+            let mut cpu = Cpu::default();
+            cpu.registers.write(Register::X1, value);
+            cpu.registers.write(Register::X2, amount);
+
+            let bare_instruction = Instruction::new_ir(Opcode::from(BuiltinOpcode::SRL), 3, 1, 2);
+            let mut instruction = SrlInstruction::decode(&bare_instruction, &cpu.registers);
+
+            instruction.execute();
+            let res = instruction.write_back(&mut cpu);
+
+            assert_eq!(
+                res,
+                Some(value.wrapping_shr(amount)),
+                "shift amount {amount}"
+            );
+        }
+    }
 }
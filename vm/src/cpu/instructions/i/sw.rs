@@ -16,11 +16,11 @@ implement_store_instruction!(SwInstruction, MemAccessSize::Word);
 
 #[cfg(test)]
 mod tests {
-    use nexus_common::error::MemoryError;
+    use nexus_common::error::{MemoryError, MemoryFaultKind};
 
     use super::*;
     use crate::cpu::state::Cpu;
-    use crate::memory::{LoadOp, VariableMemory, RW};
+    use crate::memory::{FixedMemory, LoadOp, VariableMemory, RO, RW};
     use crate::riscv::{BuiltinOpcode, Instruction, Opcode, Register};
 
     fn setup_memory() -> VariableMemory<RW> {
@@ -81,30 +81,44 @@ mod tests {
         let instruction = SwInstruction::decode(&bare_instruction, &cpu.registers);
 
         let result = instruction.memory_write(&mut memory);
-        assert!(result.is_err());
-        assert!(matches!(
-            result.unwrap_err(),
-            MemoryError::UnalignedMemoryWrite(0x1001)
-        ));
+        assert_eq!(
+            result,
+            Err(MemoryError::AccessFault {
+                kind: MemoryFaultKind::Misaligned,
+                addr: 0x1001,
+                size: MemAccessSize::Word,
+            })
+        );
     }
 
     #[test]
-    fn test_sw_overflow() {
+    fn test_sw_address_wraps_around() {
         let mut cpu = Cpu::default();
         let mut memory = setup_memory();
 
-        cpu.registers.write(Register::X1, u32::MAX);
+        // 0xFFFFFFFC + 8 wraps around to 0x4
+        cpu.registers.write(Register::X1, 0xFFFF_FFFC);
         cpu.registers.write(Register::X2, 0xDEADBEEF);
 
-        let bare_instruction = Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 1, 2, 1);
+        let bare_instruction = Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 1, 2, 8);
         let instruction = SwInstruction::decode(&bare_instruction, &cpu.registers);
 
-        let result = instruction.memory_write(&mut memory);
-        assert!(result.is_err());
-        assert!(matches!(
-            result.unwrap_err(),
-            MemoryError::AddressCalculationOverflow
-        ));
+        instruction.memory_write(&mut memory).unwrap();
+        assert_eq!(
+            memory.read(0x4, MemAccessSize::Word).unwrap(),
+            LoadOp::Op(MemAccessSize::Word, 0x4, 0xDEADBEEF)
+        );
+
+        // 0xFFFFFFFC - 4 stays below u32::MAX
+        let bare_instruction =
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 1, 2, 0xFFFFFFFC);
+        let instruction = SwInstruction::decode(&bare_instruction, &cpu.registers);
+
+        instruction.memory_write(&mut memory).unwrap();
+        assert_eq!(
+            memory.read(0xFFFF_FFF8, MemAccessSize::Word).unwrap(),
+            LoadOp::Op(MemAccessSize::Word, 0xFFFF_FFF8, 0xDEADBEEF)
+        );
     }
 
     #[test]
@@ -136,8 +150,46 @@ mod tests {
 
         let instruction = SwInstruction::decode(&bare_instruction, &cpu.registers);
 
+        // 0x1 - 3 wraps around to the unaligned 0xFFFFFFFE
         let result = instruction.memory_write(&mut memory);
-        assert_eq!(result, Err(MemoryError::AddressCalculationUnderflow));
+        assert_eq!(
+            result,
+            Err(MemoryError::AccessFault {
+                kind: MemoryFaultKind::Misaligned,
+                addr: 0xFFFF_FFFE,
+                size: MemAccessSize::Word,
+            })
+        );
+    }
+
+    #[test]
+    fn test_sw_fault_kinds() {
+        let mut cpu = Cpu::default();
+        let mut memory = FixedMemory::<RO>::from_word_vec(0x1000, 8, vec![0, 0]);
+        let bare_instruction = Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 1, 2, 4);
+
+        cpu.registers.write(Register::X1, 0x1000);
+        let instruction = SwInstruction::decode(&bare_instruction, &cpu.registers);
+        assert_eq!(
+            instruction.memory_write(&mut memory),
+            Err(MemoryError::AccessFault {
+                kind: MemoryFaultKind::PermissionDenied,
+                addr: 0x1004,
+                size: MemAccessSize::Word,
+            })
+        );
+
+        let mut memory = FixedMemory::<RW>::new(0x1000, 8);
+        cpu.registers.write(Register::X1, 0x0FF8);
+        let instruction = SwInstruction::decode(&bare_instruction, &cpu.registers);
+        assert_eq!(
+            instruction.memory_write(&mut memory),
+            Err(MemoryError::AccessFault {
+                kind: MemoryFaultKind::Unmapped,
+                addr: 0x0FFC,
+                size: MemAccessSize::Word,
+            })
+        );
     }
 
     // TODO: depending on the memory model, we need to test out of bound memory access
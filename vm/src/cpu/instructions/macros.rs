@@ -59,16 +59,12 @@ macro_rules! implement_store_instruction {
                 &self,
                 memory: &mut impl MemoryProcessor,
             ) -> Result<StoreOps, nexus_common::error::MemoryError> {
-                let address = if (self.imm as i32) < 0 {
-                    self.rs1
-                        .checked_sub((self.imm as i32).unsigned_abs())
-                        .ok_or(nexus_common::error::MemoryError::AddressCalculationUnderflow)?
-                } else {
-                    self.rs1
-                        .checked_add(self.imm as u32)
-                        .ok_or(nexus_common::error::MemoryError::AddressCalculationOverflow)?
-                };
-                Ok(memory.write(address, $size, self.rs2)?.into())
+                // The immediate is sign-extended, the effective address wraps modulo 2^32 as in RV32I.
+                let address = self.rs1.wrapping_add(self.imm);
+                let op = memory
+                    .write(address, $size, self.rs2)
+                    .map_err(|e| e.into_access_fault(address, $size))?;
+                Ok(op.into())
             }
 
             fn execute(&mut self) {}
@@ -99,16 +95,11 @@ macro_rules! implement_load_instruction {
                 &mut self,
                 memory: &impl MemoryProcessor,
             ) -> Result<LoadOps, nexus_common::error::MemoryError> {
-                let address = if (self.imm as i32) < 0 {
-                    self.rs1
-                        .checked_sub((self.imm as i32).unsigned_abs())
-                        .ok_or(nexus_common::error::MemoryError::AddressCalculationUnderflow)?
-                } else {
-                    self.rs1
-                        .checked_add(self.imm as u32)
-                        .ok_or(nexus_common::error::MemoryError::AddressCalculationOverflow)?
-                };
-                let op = memory.read(address, $size)?;
+                // The immediate is sign-extended, the effective address wraps modulo 2^32 as in RV32I.
+                let address = self.rs1.wrapping_add(self.imm);
+                let op = memory
+                    .read(address, $size)
+                    .map_err(|e| e.into_access_fault(address, $size))?;
                 let LoadOp::Op(_, _, value) = op;
 
                 self.rd.1 = if $sign_extend {
@@ -0,0 +1,230 @@
+//! State changes of an execution restricted to an address range.
+//!
+//! [`Trace::state_effects`](crate::trace::Trace::state_effects) lists every byte stored to the range in execution
+//! order, for a consumer to replay the writes of the guest without executing it. Only the net change of every
+//! byte can be recomputed from public data: [`committed_state_effects`] derives it from the initial memory and the
+//! public output of a proof, and matches [`net_state_effects`] of the traced effects when the range lies within
+//! the public output.
+
+use std::{collections::BTreeMap, ops::Range};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    emulator::{MemoryInitializationEntry, PublicOutputEntry},
+    memory::MemoryRecord,
+};
+
+/// A byte of memory written by a store, see [`Trace::state_effects`](crate::trace::Trace::state_effects).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateEffect {
+    pub addr: u32,
+    pub old_value: u8,
+    pub new_value: u8,
+    /// Timestamp of the store.
+    pub clk: u32,
+}
+
+/// The change of a byte of memory over a whole execution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetStateEffect {
+    pub addr: u32,
+    pub old_value: u8,
+    pub new_value: u8,
+}
+
+/// Returns the bytes of a store record within `range`, in address order.
+pub(crate) fn record_effects(
+    record: &MemoryRecord,
+    range: &Range<u32>,
+) -> impl Iterator<Item = StateEffect> {
+    let store = match *record {
+        MemoryRecord::StoreRecord((size, address, value, prev_value), clk) => {
+            Some((size as u32, address, value, prev_value, clk))
+        }
+        MemoryRecord::LoadRecord(..) => None,
+    };
+    let range = range.clone();
+    store
+        .into_iter()
+        .flat_map(|(size, address, value, prev_value, clk)| {
+            (0..size).map(move |byte| StateEffect {
+                addr: address.wrapping_add(byte),
+                old_value: (prev_value >> (8 * byte)) as u8,
+                new_value: (value >> (8 * byte)) as u8,
+                clk,
+            })
+        })
+        .filter(move |effect| range.contains(&effect.addr))
+}
+
+/// Collapses effects in execution order into the change of every byte, in address order.
+///
+/// Bytes that end with the value they started with are left out.
+pub fn net_state_effects(effects: &[StateEffect]) -> Vec<NetStateEffect> {
+    let mut net: BTreeMap<u32, NetStateEffect> = BTreeMap::new();
+    for effect in effects {
+        net.entry(effect.addr)
+            .or_insert(NetStateEffect {
+                addr: effect.addr,
+                old_value: effect.old_value,
+                new_value: effect.new_value,
+            })
+            .new_value = effect.new_value;
+    }
+    net.into_values()
+        .filter(|effect| effect.old_value != effect.new_value)
+        .collect()
+}
+
+/// Recomputes the net effects on `range` from the public data a proof is verified against.
+///
+/// The initial value of a byte is taken from `init_memory` and its final value from `output_memory`, bytes missing
+/// from either are zero. The result is committed to by the proof, and equal to the net effects of the execution,
+/// only if `range` lies within the public output.
+pub fn committed_state_effects(
+    range: Range<u32>,
+    init_memory: &[MemoryInitializationEntry],
+    output_memory: &[PublicOutputEntry],
+) -> Vec<NetStateEffect> {
+    let mut net: BTreeMap<u32, NetStateEffect> = BTreeMap::new();
+    let new_effect = |addr| NetStateEffect {
+        addr,
+        old_value: 0,
+        new_value: 0,
+    };
+    for entry in init_memory.iter().filter(|e| range.contains(&e.address)) {
+        net.entry(entry.address)
+            .or_insert_with(|| new_effect(entry.address))
+            .old_value = entry.value;
+    }
+    for entry in output_memory.iter().filter(|e| range.contains(&e.address)) {
+        net.entry(entry.address)
+            .or_insert_with(|| new_effect(entry.address))
+            .new_value = entry.value;
+    }
+    net.into_values()
+        .filter(|effect| effect.old_value != effect.new_value)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        riscv::{builder::ProgramBuilder, BuiltinOpcode},
+        trace::{k_trace_direct, Trace},
+    };
+
+    const BASE: u32 = 0x10000;
+
+    #[test]
+    fn test_interleaved_stores() {
+        let blocks = ProgramBuilder::new()
+            .li(1, BASE)
+            .li(2, 0x11223344)
+            .li(3, 0xaa)
+            .sw(1, 2, 0)
+            .sb(1, 3, 1)
+            .sb(1, 3, 5)
+            .sw(1, 2, 4)
+            .sb(1, 0, 2)
+            .lw(4, 1, 0)
+            .build()
+            .unwrap();
+        let (_, trace) = k_trace_direct(&blocks, 1).unwrap();
+        let clks: Vec<u32> = trace
+            .get_blocks_iter()
+            .flat_map(|block| &block.steps)
+            .filter(|step| {
+                matches!(
+                    step.instruction.opcode.builtin(),
+                    Some(BuiltinOpcode::SW | BuiltinOpcode::SB)
+                )
+            })
+            .map(|step| step.timestamp)
+            .collect();
+        assert_eq!(clks.len(), 5);
+
+        // Bytes 1 to 5 after BASE, the first and last bytes of the words are partly outside.
+        let range = BASE + 1..BASE + 6;
+        let effect = |offset, old_value, new_value, clk| StateEffect {
+            addr: BASE + offset,
+            old_value,
+            new_value,
+            clk,
+        };
+        let effects = trace.state_effects(range.clone());
+        assert_eq!(
+            effects,
+            [
+                effect(1, 0x00, 0x33, clks[0]),
+                effect(2, 0x00, 0x22, clks[0]),
+                effect(3, 0x00, 0x11, clks[0]),
+                effect(1, 0x33, 0xaa, clks[1]),
+                effect(5, 0x00, 0xaa, clks[2]),
+                effect(4, 0x00, 0x44, clks[3]),
+                effect(5, 0xaa, 0x33, clks[3]),
+                effect(2, 0x22, 0x00, clks[4]),
+            ]
+        );
+
+        // The byte written back to zero has no net effect.
+        let net = net_state_effects(&effects);
+        let net_effect = |offset, new_value| NetStateEffect {
+            addr: BASE + offset,
+            old_value: 0,
+            new_value,
+        };
+        assert_eq!(
+            net,
+            [
+                net_effect(1, 0xaa),
+                net_effect(3, 0x11),
+                net_effect(4, 0x44),
+                net_effect(5, 0x33),
+            ]
+        );
+
+        // The same changes are recomputed from the final bytes, as if the range was public output.
+        let output: Vec<PublicOutputEntry> = [0xaa, 0x00, 0x11, 0x44, 0x33]
+            .into_iter()
+            .zip(range.clone())
+            .map(|(value, address)| PublicOutputEntry { address, value })
+            .collect();
+        assert_eq!(committed_state_effects(range, &[], &output), net);
+
+        let json = serde_json::to_string(&effects).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Vec<StateEffect>>(&json).unwrap(),
+            effects
+        );
+    }
+
+    #[test]
+    fn test_committed_effects_use_initial_memory() {
+        let init = [
+            MemoryInitializationEntry {
+                address: BASE,
+                value: 7,
+            },
+            MemoryInitializationEntry {
+                address: BASE + 1,
+                value: 8,
+            },
+        ];
+        let output = [PublicOutputEntry {
+            address: BASE,
+            value: 7,
+        }];
+        // The first byte is unchanged, the second one is missing from the output and ends as zero.
+        assert_eq!(
+            committed_state_effects(BASE..BASE + 4, &init, &output),
+            [NetStateEffect {
+                addr: BASE + 1,
+                old_value: 8,
+                new_value: 0,
+            }]
+        );
+    }
+}
@@ -142,6 +142,22 @@ pub enum ParserError {
     // Could not find a .text section in the ELF file
     #[error("No executable segment found")]
     NoExecutableSegment,
+
+    /// The symbol table has no symbol with the given name.
+    #[error("symbol \"{0}\" not found")]
+    SymbolNotFound(String),
+
+    /// The entry point is not the address of an instruction in the executable segment.
+    #[error("entry point 0x{0:08x} is not in the executable segment")]
+    EntryNotExecutable(u32),
+
+    /// The guest was built against another version of the ecall ABI.
+    #[error("guest built against ecall ABI version {found}, expected version {expected}")]
+    IncompatibleEcallAbi { found: u32, expected: u32 },
+
+    /// The ecall ABI version symbol doesn't point into the memory image.
+    #[error("ecall ABI version at 0x{0:08x} is not in the memory image")]
+    InvalidEcallAbiVersionAddress(u32),
 }
 
 impl PartialEq for ParserError {
@@ -19,9 +19,12 @@
 //!   - Program base address
 //!   - Read-only memory image (ROM)
 //!   - Read-write memory image (RAM)
+//!   - Zero regions (`.bss` and the like), which are not part of the RAM image
 //!
-//! - `ElfFile::from_bytes`: Allows creation of `ElfFile` from raw bytes
+//! - `ElfFile::from_bytes`: Allows creation of `ElfFile` from raw bytes, rejecting guests built against another
+//!   version of the ecall ABI (see [`nexus_common::ecall`])
 //! - `ElfFile::from_path`: Allows creation of `ElfFile` from a file path
+//! - `ElfFile::from_bytes_with_entry_symbol`: Starts execution at a named function instead of the ELF entry point
 //!
 //! # Usage
 //!
@@ -43,9 +46,15 @@
 //! This loader is designed for little-endian RISC-V 32-bit executables and implements
 //! a Harvard architecture model. Ensure your ELF files are compatible with these specifications.
 
-use crate::{elf::parser, error::VMError, memory::MemorySegmentImage};
+use crate::{
+    elf::parser,
+    error::VMError,
+    memory::{MemorySegmentImage, ZeroRegion},
+    WORD_SIZE,
+};
 
 use elf::{endian::LittleEndian, ElfBytes};
+use nexus_common::ecall::{ECALL_ABI_VERSION, ECALL_ABI_VERSION_SYMBOL};
 use std::fs::File;
 use std::path::Path;
 
@@ -69,6 +78,10 @@ pub struct ElfFile {
     /// Initial read write memory image containing global and initialized data.
     pub ram_image: MemorySegmentImage,
 
+    /// Zero-initialized read write memory, disjoint from `ram_image`.
+    #[serde(default)]
+    pub zero_regions: Vec<ZeroRegion>,
+
     /// Nexus-specific metadata embedded in the ELF file.
     pub nexus_metadata: Vec<u32>,
 }
@@ -88,6 +101,7 @@ impl ElfFile {
             base,
             rom_image,
             ram_image,
+            zero_regions: Vec::new(),
             nexus_metadata,
         }
     }
@@ -110,14 +124,66 @@ impl ElfFile {
 
         let parsed_elf_data: ParsedElfData = parser::parse_segments(&elf, data)?;
 
-        Ok(ElfFile {
+        let file = ElfFile {
             instructions: parsed_elf_data.instructions,
             entry,
             base: parsed_elf_data.base_address as u32,
             rom_image: parsed_elf_data.readonly_memory,
             ram_image: parsed_elf_data.writable_memory,
+            zero_regions: parsed_elf_data.zero_regions,
             nexus_metadata: parsed_elf_data.nexus_metadata,
-        })
+        };
+        // Guests without the symbol predate the versioning of the ABI, or don't use the runtime at all.
+        if let Ok(address) = parser::symbol_address(&elf, ECALL_ABI_VERSION_SYMBOL) {
+            file.check_ecall_abi_version(address)?;
+        }
+        Ok(file)
+    }
+
+    /// Checks the ecall ABI version the guest stores at `address` against [`ECALL_ABI_VERSION`].
+    fn check_ecall_abi_version(&self, address: u32) -> Result<(), VMError> {
+        let version = [&self.rom_image, &self.ram_image]
+            .into_iter()
+            .find_map(|image| image.get_word(address).ok().flatten())
+            .ok_or(ParserError::InvalidEcallAbiVersionAddress(address))?;
+        if version != ECALL_ABI_VERSION {
+            return Err(ParserError::IncompatibleEcallAbi {
+                found: version,
+                expected: ECALL_ABI_VERSION,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Start and end of the static read write memory, the RAM image together with the zero regions.
+    pub fn static_ram_range(&self) -> (u32, u32) {
+        let image_range =
+            (!self.ram_image.is_empty()).then(|| (self.ram_image.base(), self.ram_image.end()));
+        self.zero_regions
+            .iter()
+            .map(|region| (region.base, region.end()))
+            .chain(image_range)
+            .reduce(|(start, end), (base, region_end)| (start.min(base), end.max(region_end)))
+            .unwrap_or((self.ram_image.base(), self.ram_image.end()))
+    }
+
+    /// The RAM image extended with its zero regions, for emulators that allocate the whole static memory upfront.
+    pub fn static_ram_image(&self) -> MemorySegmentImage {
+        if self.zero_regions.is_empty() {
+            return self.ram_image.clone();
+        }
+        let (start, end) = self.static_ram_range();
+        let words = |from: u32, to: u32| vec![0u32; (to - from) as usize / WORD_SIZE];
+        let mut image = MemorySegmentImage::empty_at(start);
+        if self.ram_image.is_empty() {
+            image.extend_from_word_slice(words(start, end));
+        } else {
+            image.extend_from_word_slice(words(start, self.ram_image.base()));
+            image.extend_from_word_slice(&self.ram_image);
+            image.extend_from_word_slice(words(self.ram_image.end(), end));
+        }
+        image
     }
 
     pub fn from_path<P: AsRef<Path> + ?Sized>(path: &P) -> Result<Self, VMError> {
@@ -128,13 +194,47 @@ impl ElfFile {
             .collect();
         Self::from_bytes(data.as_slice())
     }
+
+    /// Parses the ELF file like [`Self::from_bytes`], with execution starting at the function `entry_symbol` instead
+    /// of the entry point of the ELF header.
+    ///
+    /// Fails if the symbol table has no such symbol, or if it doesn't point into the executable segment.
+    pub fn from_bytes_with_entry_symbol(data: &[u8], entry_symbol: &str) -> Result<Self, VMError> {
+        let elf =
+            ElfBytes::<LittleEndian>::minimal_parse(data).map_err(Into::<ParserError>::into)?;
+        let entry = parser::symbol_address(&elf, entry_symbol)?;
+
+        Self::from_bytes(data)?.with_entry(entry)
+    }
+
+    /// Looks up the address of the defined symbol `name` in the symbol table of the ELF file, e.g. the bounds of a
+    /// region the program writes its results to.
+    pub fn symbol_address(data: &[u8], name: &str) -> Result<u32, VMError> {
+        let elf =
+            ElfBytes::<LittleEndian>::minimal_parse(data).map_err(Into::<ParserError>::into)?;
+        Ok(parser::symbol_address(&elf, name)?)
+    }
+
+    /// Overrides the entry point of the program, which must be the address of an instruction.
+    pub fn with_entry(mut self, entry: u32) -> Result<Self, VMError> {
+        let text_end = self.base as u64 + (self.instructions.len() * WORD_SIZE) as u64;
+        if entry < self.base || entry as u64 >= text_end || entry % WORD_SIZE as u32 != 0 {
+            return Err(ParserError::EntryNotExecutable(entry).into());
+        }
+
+        self.entry = entry;
+        Ok(self)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use nexus_common::constants::ELF_TEXT_START;
 
-    use crate::{memory::MemorySegmentImage, read_testing_elf_from_path};
+    use crate::{
+        error::VMErrorKind, memory::MemorySegmentImage, read_testing_binary_from_path,
+        read_testing_elf_from_path,
+    };
 
     use super::*;
     use std::io::Write;
@@ -195,4 +295,71 @@ mod tests {
 
         assert_eq!(elf.instructions.len(), NUMBER_OF_INSTRUCTIONS);
     }
+
+    #[test]
+    fn test_ecall_abi_version() {
+        let mut rom_image = MemorySegmentImage::empty_at(0x1000);
+        rom_image.extend_from_word_slice([ECALL_ABI_VERSION, ECALL_ABI_VERSION + 1]);
+        let elf = ElfFile::new(
+            vec![],
+            ELF_TEXT_START,
+            ELF_TEXT_START,
+            rom_image,
+            MemorySegmentImage::default(),
+            vec![],
+        );
+
+        elf.check_ecall_abi_version(0x1000).unwrap();
+        assert!(matches!(
+            elf.check_ecall_abi_version(0x1004).unwrap_err().source,
+            VMErrorKind::ElfError(ParserError::IncompatibleEcallAbi { found, expected })
+                if found == ECALL_ABI_VERSION + 1 && expected == ECALL_ABI_VERSION
+        ));
+        assert!(matches!(
+            elf.check_ecall_abi_version(0x1008).unwrap_err().source,
+            VMErrorKind::ElfError(ParserError::InvalidEcallAbiVersionAddress(0x1008))
+        ));
+    }
+
+    #[test]
+    fn test_ecall_abi_version_of_guest() {
+        // Guests embedding version 1 and 2 of the ABI, built from `test/ecall_abi.s`.
+        let data = read_testing_binary_from_path!("/test/ecall_abi_v1.elf");
+        assert_eq!(ECALL_ABI_VERSION, 1);
+        let elf = ElfFile::from_bytes(&data).unwrap();
+        assert_eq!(elf.instructions.len(), 3);
+
+        let data = read_testing_binary_from_path!("/test/ecall_abi_v2.elf");
+        assert!(matches!(
+            ElfFile::from_bytes(&data).unwrap_err().source,
+            VMErrorKind::ElfError(ParserError::IncompatibleEcallAbi { found: 2, expected })
+                if expected == ECALL_ABI_VERSION
+        ));
+    }
+
+    #[test]
+    fn test_entry_symbol() {
+        let data = read_testing_binary_from_path!("/test/fib_10.elf");
+
+        let elf = ElfFile::from_bytes_with_entry_symbol(&data, "main").unwrap();
+        assert_eq!(elf.entry, 0x25c);
+        assert_eq!(elf.base, ELF_TEXT_START);
+
+        let err = ElfFile::from_bytes_with_entry_symbol(&data, "no_such_function").unwrap_err();
+        assert!(matches!(
+            err.source,
+            VMErrorKind::ElfError(ParserError::SymbolNotFound(name)) if name == "no_such_function"
+        ));
+
+        // `_heap` is placed after the data segment.
+        let err = ElfFile::from_bytes_with_entry_symbol(&data, "_heap").unwrap_err();
+        assert!(matches!(
+            err.source,
+            VMErrorKind::ElfError(ParserError::EntryNotExecutable(0x194c))
+        ));
+
+        let elf = ElfFile::from_bytes(&data).unwrap();
+        assert!(elf.clone().with_entry(ELF_TEXT_START + 2).is_err());
+        assert!(elf.with_entry(ELF_TEXT_START - 4).is_err());
+    }
 }
@@ -9,7 +9,8 @@
 //! - Validates ELF headers for RISC-V 32-bit executables
 //! - Parses segment information and extracts executable content
 //! - Supports Harvard architecture with separate instruction and data memories
-//! - Handles allowed sections: .text, .data, .sdata, .rodata, .init, .fini, .bss, .sbss, .got
+//! - Handles allowed sections: .text, .data, .sdata, .rodata, .init, .fini, .bss, .sbss, .tdata, .tbss, .got
+//! - Keeps the zero-initialized tail of data segments as compact zero regions
 //! - Supports custom metadata section: .note.nexus-precompiles
 //! - Parses precompile metadata from ELF symbols
 //!
@@ -20,6 +21,7 @@
 //! - `create_allowed_section_map`: Builds a map of allowed ELF sections and their address ranges
 //! - `parse_segment_content`: Processes segment content and populates instruction and memory structures
 //! - `parse_precompile_metadata`: Extracts and validates precompile metadata from ELF symbols
+//! - `symbol_address`: Looks up the address of a symbol, e.g. to start execution at a given function
 //!
 //! # Memory Types
 //!
@@ -27,6 +29,7 @@
 //! - Instruction memory (read-only)
 //! - Read-only data memory
 //! - Writable data memory
+//! - Zero regions, the part of data segments past their file contents (`.bss` and the like)
 //! - Metadata (for precompiles)
 //!
 //! # Note
@@ -47,7 +50,10 @@ use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use tracing::debug;
 
-use crate::{error::Result, memory::MemorySegmentImage};
+use crate::{
+    error::Result,
+    memory::{MemorySegmentImage, ZeroRegion},
+};
 
 use super::error::ParserError;
 
@@ -59,6 +65,7 @@ pub struct ParsedElfData {
     pub instructions: Instructions,
     pub readonly_memory: MemorySegmentImage,
     pub writable_memory: MemorySegmentImage,
+    pub zero_regions: Vec<ZeroRegion>,
     pub base_address: u32,
     pub nexus_metadata: Metadata,
 }
@@ -73,7 +80,7 @@ const MAXIMUM_MEMORY_SIZE: u32 = u32::MAX;
 /// When building the section map, only these sections and their variants are considered.
 /// Section names starting with any of these prefixes are included (e.g., .text1, .data2).
 /// All other sections are ignored during parsing.
-const ALLOWED_SECTIONS: [&str; 10] = [
+const ALLOWED_SECTIONS: [&str; 12] = [
     ".text",
     ".data",
     ".sdata",
@@ -82,6 +89,8 @@ const ALLOWED_SECTIONS: [&str; 10] = [
     ".fini",
     ".bss",
     ".sbss",
+    ".tdata",
+    ".tbss",
     ".got",
     ".note.nexus-precompiles",
 ];
@@ -137,7 +146,9 @@ pub fn validate_elf_header(header: &FileHeader<LittleEndian>) -> Result<()> {
 ///
 /// This function extracts and validates key information from a program header segment,
 /// including the virtual address, file size, memory size, and offset.
-fn parse_segment_info(segment: &ProgramHeader) -> Result<(u32, u32, u32)> {
+///
+/// Returns the virtual address, the offset, the file size and the memory size of the segment.
+fn parse_segment_info(segment: &ProgramHeader) -> Result<(u32, u32, u32, u32)> {
     // Convert virtual address to u32 and check for validity
     let virtual_address: u32 = segment
         .p_vaddr
@@ -169,7 +180,7 @@ fn parse_segment_info(segment: &ProgramHeader) -> Result<(u32, u32, u32)> {
 
     // Ensure file_size <= mem_size and the total size does not exceed the maximum memory size
     if (file_size <= mem_size) && (mem_size + offset < MAXIMUM_MEMORY_SIZE) {
-        Ok((virtual_address, offset, mem_size))
+        Ok((virtual_address, offset, file_size, mem_size))
     } else {
         Err(ParserError::SegmentSizeExceedsMemorySize.into())
     }
@@ -221,6 +232,10 @@ fn create_allowed_section_map<'a>(
 /// This function processes the content of an ELF segment, determining whether it contains
 /// executable code or data, and appropriately populates either the instructions vector
 /// or the memory image map.
+///
+/// Words of a data segment past its file contents are zero, they are not read from the file but added to
+/// `zero_spans` as a `(start, end)` span instead.
+#[allow(clippy::too_many_arguments)]
 fn parse_segment_content(
     segment: &ProgramHeader,
     section_map: &HashMap<&str, (u64, u64)>,
@@ -228,13 +243,32 @@ fn parse_segment_content(
     instructions: &mut Vec<u32>,
     readonly_memory_image: &mut BTreeMap<u32, u32>,
     memory_image: &mut BTreeMap<u32, u32>,
+    zero_spans: &mut Vec<(u32, u32)>,
     metadata: &mut Vec<u32>,
 ) -> Result<()> {
     let is_executable_segment = (segment.p_flags & abi::PF_X) != 0;
-    let (segment_virtual_address, segment_physical_address, segment_size) =
+    let (segment_virtual_address, segment_physical_address, file_size, segment_size) =
         parse_segment_info(segment)?;
 
-    for offset_in_segment in (0..segment_size).step_by(WORD_SIZE as _) {
+    // The zero-initialized tail starts at the first word not fully backed by the file.
+    let file_backed_size = if is_executable_segment || segment.p_type != abi::PT_LOAD {
+        segment_size
+    } else {
+        file_size
+            .next_multiple_of(WORD_SIZE as u32)
+            .min(segment_size)
+    };
+    if file_backed_size < segment_size {
+        let start = segment_virtual_address
+            .checked_add(file_backed_size)
+            .ok_or(ParserError::InvalidSegmentAddress)?;
+        let end = segment_virtual_address
+            .checked_add(segment_size.next_multiple_of(WORD_SIZE as u32))
+            .ok_or(ParserError::InvalidSegmentAddress)?;
+        zero_spans.push((start, end));
+    }
+
+    for offset_in_segment in (0..file_backed_size).step_by(WORD_SIZE as _) {
         // Calculate the memory address for this word
         let memory_address = segment_virtual_address
             .checked_add(offset_in_segment)
@@ -307,6 +341,29 @@ fn parse_segment_content(
     Ok(())
 }
 
+/// Looks up the address of the defined symbol `name` in the symbol table.
+pub fn symbol_address(elf: &ElfBytes<LittleEndian>, name: &str) -> Result<u32> {
+    let (symbol_table, symbol_string_table) = elf
+        .symbol_table()
+        .map_err(ParserError::ELFError)?
+        .ok_or(ParserError::NoSymbolTable)?;
+
+    let symbol = symbol_table
+        .iter()
+        .filter(|symbol| symbol.st_shndx != abi::SHN_UNDEF)
+        .find(|symbol| {
+            symbol_string_table
+                .get(symbol.st_name as usize)
+                .is_ok_and(|symbol_name| symbol_name == name)
+        })
+        .ok_or_else(|| ParserError::SymbolNotFound(name.to_string()))?;
+
+    Ok(symbol
+        .st_value
+        .try_into()
+        .map_err(|_| ParserError::InvalidVirtualAddress(symbol.st_value))?)
+}
+
 /// Represents a precompile description as found in the ELF file.
 #[derive(PartialEq, Eq)]
 pub struct PrecompileDescription<'a>(u16, &'a str);
@@ -476,6 +533,7 @@ pub fn parse_segments(elf: &ElfBytes<LittleEndian>, data: &[u8]) -> Result<Parse
     let mut instructions = Instructions::new();
     let mut writable_memory = RawMemoryImage::new();
     let mut readonly_memory = RawMemoryImage::new();
+    let mut zero_spans = Vec::new();
     let mut metadata = Metadata::new();
 
     // Base address is the lowest virtual address of a program's loadable segment
@@ -506,6 +564,7 @@ pub fn parse_segments(elf: &ElfBytes<LittleEndian>, data: &[u8]) -> Result<Parse
             &mut instructions,
             &mut readonly_memory,
             &mut writable_memory,
+            &mut zero_spans,
             &mut metadata,
         )?;
     }
@@ -518,10 +577,15 @@ pub fn parse_segments(elf: &ElfBytes<LittleEndian>, data: &[u8]) -> Result<Parse
         base_address as u32
     };
 
+    let writable_memory = MemorySegmentImage::try_from_contiguous_btree(&writable_memory)?;
+    // Zero spans between initialized words are filled into the writable image already.
+    let zero_regions = ZeroRegion::from_spans(zero_spans, &writable_memory);
+
     Ok(ParsedElfData {
         instructions,
         readonly_memory: MemorySegmentImage::try_from_contiguous_btree(&readonly_memory)?,
-        writable_memory: MemorySegmentImage::try_from_contiguous_btree(&writable_memory)?,
+        writable_memory,
+        zero_regions,
         base_address,
         nexus_metadata: metadata,
     })
@@ -529,12 +593,15 @@ pub fn parse_segments(elf: &ElfBytes<LittleEndian>, data: &[u8]) -> Result<Parse
 
 #[cfg(test)]
 mod tests {
-    use crate::read_testing_binary_from_path;
+    use crate::{
+        memory::{MemorySegmentImage, ZeroRegion},
+        read_testing_binary_from_path,
+    };
 
     use super::{parse_precompile_metadata, validate_elf_header};
 
     use elf::{endian::LittleEndian, ElfBytes};
-    use std::collections::HashMap;
+    use std::collections::{BTreeMap, HashMap};
 
     #[tracing_test::traced_test]
     #[test]
@@ -578,4 +645,44 @@ mod tests {
             HashMap::<u16, String>::default()
         );
     }
+
+    #[test]
+    fn test_zero_regions_skip_initialized_memory() {
+        let image = MemorySegmentImage::try_from_contiguous_btree(&BTreeMap::from([
+            (0x1000, 1),
+            (0x1008, 2),
+        ]))
+        .unwrap();
+        let spans = vec![
+            (0x2000, 0x2010),
+            // Overlaps the image, only the part past it is left.
+            (0x1004, 0x1100),
+            (0x1100, 0x1200),
+            (0x0ff0, 0x1000),
+        ];
+        assert_eq!(
+            ZeroRegion::from_spans(spans, &image),
+            [
+                ZeroRegion {
+                    base: 0x0ff0,
+                    len: 0x10
+                },
+                ZeroRegion {
+                    base: 0x100c,
+                    len: 0x1f4
+                },
+                ZeroRegion {
+                    base: 0x2000,
+                    len: 0x10
+                },
+            ]
+        );
+        assert_eq!(
+            ZeroRegion::from_spans(vec![(0x10, 0x20)], &MemorySegmentImage::default()),
+            [ZeroRegion {
+                base: 0x10,
+                len: 0x10
+            }]
+        );
+    }
 }
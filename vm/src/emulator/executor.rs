@@ -132,25 +132,33 @@
 //! basic block caching, custom instruction support, debug logging, and associated data handling.
 
 use super::{
-    layout::LinearMemoryLayout, memory_stats::*, registry::InstructionExecutorRegistry, *,
+    layout::LinearMemoryLayout,
+    memory_stats::*,
+    registry::{Dispatch, InstructionExecutorRegistry},
+    *,
 };
 use crate::{
-    cpu::{instructions::InstructionResult, Cpu},
+    consistency::{verify_shared_constants, FIRST_CLK},
+    cpu::{instructions::InstructionResult, Cpu, RegisterFile},
     elf::ElfFile,
-    error::{Result, VMErrorKind},
+    error::{Result, VMError, VMErrorKind},
     memory::{
-        FixedMemory, LoadOp, MemoryProcessor, MemoryRecords, MemorySegmentImage, Modes, StoreOp,
-        UnifiedMemory, VariableMemory, NA, RO, RW, WO,
+        timestamp_accesses, FixedMemory, LoadOp, MemoryProcessor, MemoryRecords,
+        MemorySegmentImage, Modes, StoreOp, UnifiedMemory, VariableMemory, ZeroRegion, NA,
+        PAGE_SIZE_BYTES, RO, RW, WO,
     },
-    riscv::{decode_until_end_of_a_block, BasicBlock, Instruction, Opcode, Register},
-    system::SyscallInstruction,
+    riscv::{
+        decode_until_end_of_a_block, BasicBlock, BuiltinOpcode, ControlFlowGraph, Instruction,
+        Opcode, Register,
+    },
+    system::{HintRegistry, SyscallInstruction},
 };
 
 use nexus_common::{
     constants::{
         ELF_TEXT_START, MAX_PUBLIC_INPUT_SIZE, MEMORY_TOP, PUBLIC_INPUT_ADDRESS_LOCATION, WORD_SIZE,
     },
-    cpu::{InstructionExecutor, Registers},
+    cpu::{InstructionExecutor, OpcodeCost, Registers},
     memory::MemAccessSize,
 };
 use num_traits::FromPrimitive;
@@ -158,8 +166,126 @@ use rangemap::RangeMap;
 use std::{
     cmp::max,
     collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    ops::Range,
     rc::Rc,
 };
+
+#[cfg(test)]
+thread_local! {
+    // Number of basic blocks decoded on the current thread, used to assert that decoding is not repeated.
+    static DECODED_BLOCKS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Decode a single basic block, keeping track of the number of decoded blocks in test builds.
+fn decode_block(u32_instructions: &[u32]) -> BasicBlock {
+    #[cfg(test)]
+    DECODED_BLOCKS.with(|count| count.set(count.get() + 1));
+
+    decode_until_end_of_a_block(u32_instructions)
+}
+
+/// The reason an execution stopped.
+///
+/// The cause is reported by the emulator to the host and isn't part of the public data of a proof, a verifier can't
+/// rely on it. Only the exit code, read from the exit code region of the public output, and the number of steps are
+/// bound by the proof. A proof of a run stopped by [`Self::SelfLoopHalt`], [`Self::CycleLimit`] or
+/// [`Self::StackOverflow`] attests the steps that were executed, not that the guest exited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerminationCause {
+    /// The guest called the exit syscall with the given exit code.
+    Exit(u32),
+    /// The guest reached a side-effect free loop at the given pc, see [`Emulator::set_halt_on_self_loop`].
+    SelfLoopHalt(u32),
+    /// Execution reached the cycle limit.
+    CycleLimit(usize),
+    /// The guest accessed the guard region below the stack, see [`Emulator::set_stack_guard`].
+    StackOverflow { sp: u32, addr: u32 },
+}
+
+/// Guard region below the stack, see [`Emulator::set_stack_guard`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StackGuard {
+    /// Largest allowed stack, in bytes below the top of the stack.
+    pub max_stack_size: u32,
+    /// Number of pages below the stack limit that loads and stores must not touch.
+    pub guard_pages: u32,
+}
+
+/// A return that didn't go back to the address pushed by the matching call, see [`Emulator::set_shadow_stack`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShadowStackMismatch {
+    /// The pc of the returning `jalr`.
+    pub pc: u32,
+    /// The return address pushed by the matching call.
+    pub expected: u32,
+    /// The address the return jumped to.
+    pub got: u32,
+    /// The depth of the shadow stack before the return popped it.
+    pub depth: usize,
+}
+
+/// Shadow stack of return addresses, see [`Emulator::set_shadow_stack`].
+#[derive(Debug, Clone, Default)]
+struct ShadowStack {
+    max_depth: usize,
+    return_addresses: VecDeque<u32>,
+    mismatches: Vec<ShadowStackMismatch>,
+}
+
+impl ShadowStack {
+    fn new(max_depth: usize) -> Self {
+        Self {
+            max_depth,
+            ..Self::default()
+        }
+    }
+
+    /// Link registers of the RISC-V calling convention, `ra` and the alternate link register `t0`.
+    fn is_link(register: Register) -> bool {
+        matches!(register, Register::X1 | Register::X5)
+    }
+
+    /// Push the return address of a linking jump, or pop and compare on a return. `target` is the pc after the jump.
+    fn observe(&mut self, pc: u32, instruction: &Instruction, target: u32) {
+        match instruction.opcode.builtin() {
+            Some(BuiltinOpcode::JALR)
+                if instruction.op_a == Register::X0 && Self::is_link(instruction.op_b) =>
+            {
+                let depth = self.return_addresses.len();
+                // Returns past the bottom of the stack, e.g. from the entrypoint or from frames dropped at the maximum
+                // depth, have nothing to compare against.
+                if let Some(expected) = self.return_addresses.pop_back() {
+                    if expected != target {
+                        self.mismatches.push(ShadowStackMismatch {
+                            pc,
+                            expected,
+                            got: target,
+                            depth,
+                        });
+                    }
+                }
+            }
+            Some(BuiltinOpcode::JAL | BuiltinOpcode::JALR) if Self::is_link(instruction.op_a) => {
+                if self.max_depth == 0 {
+                    return;
+                }
+                // The oldest frames are dropped first, so that the innermost calls are still checked.
+                if self.return_addresses.len() == self.max_depth {
+                    self.return_addresses.pop_front();
+                }
+                self.return_addresses
+                    .push_back(pc.wrapping_add(WORD_SIZE as u32));
+            }
+            _ => {}
+        }
+    }
+
+    fn reset(&mut self) {
+        self.return_addresses.clear();
+        self.mismatches.clear();
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct Executor {
     // The CPU
@@ -171,14 +297,29 @@ pub struct Executor {
     // The private input tape as a FIFO queue.
     pub private_input_tape: VecDeque<u8>,
 
+    // Host hint functions available to the guest through the hint syscall
+    pub hints: HintRegistry,
+
+    // Output of the last hint, read by the guest word by word
+    pub hint_tape: VecDeque<u8>,
+
     // The global clock counter
     pub global_clock: usize,
 
-    // Reference component of basic block cache to improve performance
-    basic_block_ref_cache: RangeMap<u32, u32>,
+    // Reference component of basic block cache to improve performance, shared with a `LoadedProgram` until a block
+    // is decoded lazily
+    basic_block_ref_cache: Rc<RangeMap<u32, u32>>,
+
+    // Basic block cache to improve performance, shared like the reference component
+    basic_block_cache: Rc<BTreeMap<u32, Rc<BasicBlockEntry>>>,
 
-    // Basic block cache to improve performance
-    basic_block_cache: BTreeMap<u32, Rc<BasicBlockEntry>>,
+    // Resolved executors of the program's instructions and their opcodes, indexed by
+    // `(pc - base_address) / WORD_SIZE`, sized once to the program's instruction count
+    dispatch_table: Vec<Option<(Opcode, Dispatch)>>,
+
+    // Resolve executors on every step, used to compare against the dispatch table
+    #[cfg(test)]
+    bypass_dispatch_table: bool,
 
     // The base address of the program
     base_address: u32,
@@ -186,27 +327,268 @@ pub struct Executor {
     // The entrypoint of the program
     entrypoint: u32,
 
+    // The arguments passed to the entrypoint in registers a0 to a7
+    initial_args: Vec<u32>,
+
     // The cycles tracker: (name, (cycle_count, occurrence))
     pub cycle_tracker: HashMap<String, (usize, usize)>,
 
     // Debug logs written by the guest program
     pub logs: Option<Vec<Vec<u8>>>,
 
+    // Bytes written by the guest panic handler, see `PanicInfo`
+    pub panic_output: Vec<u8>,
+
     // A map of memory addresses to the last timestamp when they were accessed
     pub access_timestamps: HashMap<u32, usize>,
+
+    // The maximum number of cycles to execute, unlimited if None
+    pub cycle_limit: Option<usize>,
+
+    // Length of the committed prefix of the public output, None if the guest never committed
+    // in which case the whole output segment is public
+    pub committed_output_len: Option<u32>,
+
+    // Whether to halt once the program reaches an instruction that loops to itself without side effects
+    pub halt_on_self_loop: bool,
+
+    // The pc of the side-effect free self-loop reached by the program, if any
+    self_loop_pc: Option<u32>,
+
+    // The reason execution stopped, None while the program is running
+    pub termination: Option<TerminationCause>,
+
+    // Guard region below the stack, if any
+    pub stack_guard: Option<StackGuard>,
+
+    // Addresses of the guard region, cached so that a memory access is checked with a single range compare
+    stack_guard_range: Range<u32>,
+
+    // Lowest non-zero stack pointer observed after an instruction, None until the stack pointer is set
+    deepest_sp: Option<u32>,
+
+    // Shadow stack of return addresses checking returns, None unless enabled
+    shadow_stack: Option<ShadowStack>,
+
+    // Address ranges whose final bytes are recorded in the view
+    pub captured_memory: Vec<Range<u32>>,
 }
 
 impl Executor {
     /// Adds a new opcode and its corresponding execution function to the emulator.
     fn add_opcode<IE: InstructionExecutor>(&mut self, op: &Opcode) -> Result<()> {
+        // Instructions resolved so far may now dispatch to the new executor.
+        self.dispatch_table.fill(None);
         self.instruction_executor.add_opcode::<IE>(op)
     }
 
+    /// Resolves how the instruction at the current pc is executed.
+    ///
+    /// Resolution happens once per instruction of the program, later executions are dispatched through the
+    /// dispatch table. The table entry is only used if its opcode matches the executed instruction, since
+    /// callers may execute arbitrary instructions at the current pc. Instructions outside the program, below the
+    /// base address or past its last instruction, are resolved on every execution so that a stray pc cannot grow
+    /// the table.
+    ///
+    /// NOPs are recognized before the table lookup, a cached NOP would otherwise be reused for any `addi`.
+    fn dispatch(&mut self, instruction: &Instruction) -> Result<Dispatch> {
+        if instruction.is_nop() {
+            return Ok(Dispatch::Nop);
+        }
+
+        #[cfg(test)]
+        if self.bypass_dispatch_table {
+            return self.instruction_executor.dispatch(instruction);
+        }
+
+        let Some(offset) = self.cpu.pc.value.checked_sub(self.base_address) else {
+            return self.instruction_executor.dispatch(instruction);
+        };
+        let index = offset as usize / WORD_SIZE;
+        if let Some(Some((opcode, dispatch))) = self.dispatch_table.get(index) {
+            if *opcode == instruction.opcode {
+                return Ok(*dispatch);
+            }
+        }
+
+        let dispatch = self.instruction_executor.dispatch(instruction)?;
+        if let Some(entry) = self.dispatch_table.get_mut(index) {
+            *entry = Some((instruction.opcode.clone(), dispatch));
+        }
+        Ok(dispatch)
+    }
+
     /// Set or overwrite private input into the private input tape
     fn set_private_input(&mut self, private_input: &[u8]) {
         self.private_input_tape = VecDeque::<u8>::from(private_input.to_vec());
     }
 
+    /// Bring the cpu and the clock back to the start of execution, see [`Emulator::warm_reset`].
+    ///
+    /// Registers are zeroed before the initial arguments are written again, the pc goes back to the entrypoint and
+    /// the global clock to 1. The consumed input tapes, cycle tracking, captured logs and the termination state of
+    /// the previous run are dropped.
+    fn warm_reset(&mut self) {
+        self.cpu = Cpu::default();
+        for (&register, &arg) in Register::ARG_REGISTERS.iter().zip(&self.initial_args) {
+            self.cpu.registers.write(register, arg);
+        }
+        self.cpu.pc.value = self.entrypoint;
+        self.global_clock = FIRST_CLK;
+
+        self.private_input_tape.clear();
+        self.hint_tape.clear();
+        self.cycle_tracker.clear();
+        self.access_timestamps.clear();
+        if let Some(logs) = &mut self.logs {
+            logs.clear();
+        }
+        self.panic_output.clear();
+        self.committed_output_len = None;
+        self.self_loop_pc = None;
+        self.termination = None;
+        self.deepest_sp = None;
+        if let Some(shadow_stack) = &mut self.shadow_stack {
+            shadow_stack.reset();
+        }
+    }
+
+    /// Place the guard region of `guard` below `stack_top`, or remove it.
+    fn set_stack_guard(&mut self, guard: Option<StackGuard>, stack_top: u32) {
+        self.stack_guard = guard;
+        self.stack_guard_range = match guard {
+            Some(StackGuard {
+                max_stack_size,
+                guard_pages,
+            }) => {
+                let limit = stack_top.saturating_sub(max_stack_size);
+                let guard_size = guard_pages.saturating_mul(PAGE_SIZE_BYTES as u32);
+                limit.saturating_sub(guard_size)..limit
+            }
+            None => 0..0,
+        };
+    }
+
+    /// Return an error if `instruction` is a load or a store into the guard region below the stack.
+    ///
+    /// Checked before the instruction executes, so that the guard region is never accessed.
+    fn check_stack_guard(&mut self, instruction: &Instruction) -> Result<()> {
+        if self.stack_guard_range.is_empty() {
+            return Ok(());
+        }
+        let base = match instruction.opcode.builtin() {
+            Some(
+                BuiltinOpcode::LB
+                | BuiltinOpcode::LH
+                | BuiltinOpcode::LW
+                | BuiltinOpcode::LBU
+                | BuiltinOpcode::LHU,
+            ) => instruction.op_b,
+            Some(BuiltinOpcode::SB | BuiltinOpcode::SH | BuiltinOpcode::SW) => instruction.op_a,
+            _ => return Ok(()),
+        };
+        // Same effective address as the load and store executors.
+        let addr = self.cpu.registers.read(base).wrapping_add(instruction.op_c);
+        if self.stack_guard_range.contains(&addr) {
+            let sp = self.cpu.registers.read(Register::X2);
+            self.termination = Some(TerminationCause::StackOverflow { sp, addr });
+            Err(VMErrorKind::StackOverflow { sp, addr })?
+        }
+        Ok(())
+    }
+
+    /// Record the stack pointer if it is the deepest one so far.
+    fn track_stack_pointer(&mut self) {
+        let sp = self.cpu.registers.read(Register::X2);
+        if sp != 0 && self.deepest_sp.is_none_or(|deepest| sp < deepest) {
+            self.deepest_sp = Some(sp);
+        }
+    }
+
+    /// Check the jump at `pc` against the shadow stack, if enabled, once the instruction has executed.
+    fn track_shadow_stack(&mut self, pc: u32, instruction: &Instruction) {
+        if let Some(shadow_stack) = &mut self.shadow_stack {
+            shadow_stack.observe(pc, instruction, self.cpu.pc.value);
+        }
+    }
+
+    /// Final bytes of the captured address ranges, skipping ranges that are not entirely readable.
+    fn capture_memory(&self, memory: &impl MemoryProcessor) -> Vec<(Range<u32>, Vec<u8>)> {
+        self.captured_memory
+            .iter()
+            .filter_map(|range| {
+                let len = range.end.checked_sub(range.start)? as usize;
+                let bytes = memory.read_bytes(range.start, len).ok()?;
+                Some((range.clone(), bytes))
+            })
+            .collect()
+    }
+
+    /// Mismatches recorded by the shadow stack, empty unless it is enabled.
+    fn shadow_stack_mismatches(&self) -> Vec<ShadowStackMismatch> {
+        self.shadow_stack
+            .as_ref()
+            .map(|shadow_stack| shadow_stack.mismatches.clone())
+            .unwrap_or_default()
+    }
+
+    /// Return an error if executing one more instruction would exceed the cycle limit.
+    fn check_cycle_limit(&mut self) -> Result<()> {
+        match self.cycle_limit {
+            // global_clock starts at 1, so it is one past the number of elapsed cycles
+            Some(limit) if self.global_clock > limit => {
+                self.termination = Some(TerminationCause::CycleLimit(limit));
+                Err(VMErrorKind::CycleLimitExceeded(limit))?
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Return an error if the program is stuck in a side-effect free self-loop and halting on it is enabled.
+    fn check_self_loop(&mut self) -> Result<()> {
+        match self.self_loop_pc {
+            Some(pc) if self.halt_on_self_loop => {
+                self.termination = Some(TerminationCause::SelfLoopHalt(pc));
+                Err(VMErrorKind::SelfLoopHalt(pc))?
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Record whether an instruction executed at `pc` is a fixed point of execution: it didn't move the pc,
+    /// didn't change any register and didn't write to memory, so executing it again repeats it forever.
+    fn track_self_loop(&mut self, pc: u32, registers: &RegisterFile, store_ops: &HashSet<StoreOp>) {
+        if self.halt_on_self_loop
+            && self.cpu.pc.value == pc
+            && self.cpu.registers == *registers
+            && store_ops.is_empty()
+        {
+            self.self_loop_pc = Some(pc);
+        }
+    }
+
+    /// Reject stores into the committed prefix of the public output, which starts at `output_start`.
+    fn check_committed_output_writes(
+        &self,
+        store_ops: &HashSet<StoreOp>,
+        output_start: u32,
+    ) -> Result<()> {
+        let Some(committed_len) = self.committed_output_len else {
+            return Ok(());
+        };
+        // Bounds are computed in u64, a store or committed prefix may end at the top of the address space.
+        let committed_end = output_start as u64 + committed_len as u64;
+
+        for op in store_ops {
+            let start = op.get_address();
+            let end = start as u64 + op.get_size() as u64;
+            if (start as u64) < committed_end && (output_start as u64) < end {
+                return Err(VMErrorKind::CommittedOutputOverwrite(start))?;
+            }
+        }
+        Ok(())
+    }
+
     /// Set whether to capture logs or print out.
     pub(crate) fn capture_logs(&mut self, capture: bool) {
         if capture && self.logs.is_none() {
@@ -238,13 +620,21 @@ pub trait Emulator {
     ) -> Result<(InstructionResult, (HashSet<LoadOp>, HashSet<StoreOp>))> {
         let mut syscall_instruction = SyscallInstruction::decode(bare_instruction, &executor.cpu)?;
         let load_ops = syscall_instruction.memory_read(memory)?;
-        syscall_instruction.execute(
+        let executed = syscall_instruction.execute(
             executor,
             memory,
             memory_layout,
             memory_stats,
             force_provable_transcript,
-        )?;
+        );
+        if let Err(VMError {
+            source: VMErrorKind::VMExited(exit_code),
+            ..
+        }) = executed
+        {
+            executor.termination = Some(TerminationCause::Exit(exit_code));
+        }
+        executed?;
         let result = syscall_instruction.get_result().map(|(_, value)| value);
         let store_ops = syscall_instruction.memory_write(memory)?;
         syscall_instruction.write_back(&mut executor.cpu);
@@ -256,10 +646,10 @@ pub trait Emulator {
 
     /// Executes a single RISC-V instruction.
     ///
-    /// 1. Retrieves the instruction executor function for the given opcode via HashMap.
+    /// 1. Resolves the instruction executor function, which is cached per instruction after its first execution.
     /// 2. Executes the instruction using the appropriate executor function.
     /// 3. Updates the program counter (PC) if the instruction is not a branch or jump.
-    /// 4. Increments the global clock.
+    /// 4. Advances the global clock by the cost of the instruction, see [`OpcodeCost`].
     fn execute_instruction(
         &mut self,
         bare_instruction: &Instruction,
@@ -333,6 +723,94 @@ pub trait Emulator {
         self.get_executor_mut().set_private_input(private_input)
     }
 
+    /// Limit the number of cycles to execute, execution stops with
+    /// [`VMErrorKind::CycleLimitExceeded`] once the limit is reached.
+    fn set_cycle_limit(&mut self, cycle_limit: Option<usize>) {
+        self.get_executor_mut().cycle_limit = cycle_limit;
+    }
+
+    /// Halt with [`VMErrorKind::SelfLoopHalt`] once the program executes an instruction that jumps to itself
+    /// without changing registers or memory, e.g. a `j .` used by some toolchains in place of the exit syscall.
+    ///
+    /// The looping instruction is executed (and traced) once, execution stops before repeating it.
+    fn set_halt_on_self_loop(&mut self, enabled: bool) {
+        self.get_executor_mut().halt_on_self_loop = enabled;
+    }
+
+    /// Top of the stack, the initial stack pointer of the guest.
+    fn stack_top(&self) -> u32;
+
+    /// Stop with [`VMErrorKind::StackOverflow`] before a load or a store touches the guard region, the
+    /// `guard.guard_pages` pages below the stack limit that lies `guard.max_stack_size` bytes under the top of the
+    /// stack. `None` removes the guard region.
+    ///
+    /// The faulting instruction is not executed, the execution up to it can still be traced and proved.
+    fn set_stack_guard(&mut self, guard: Option<StackGuard>) {
+        let stack_top = self.stack_top();
+        self.get_executor_mut().set_stack_guard(guard, stack_top);
+    }
+
+    /// Keep a shadow stack of return addresses, holding at most `max_depth` of them, or stop keeping it with `None`.
+    ///
+    /// Jumps that link, `jal` and `jalr` with `rd` being `ra` or `t0`, push the address following them. Returns,
+    /// `jalr` with `rd` being `x0` and `rs1` being `ra` or `t0`, pop the latest one and record a
+    /// [`ShadowStackMismatch`] in the view if they jump elsewhere, which points at a corrupted return address close to
+    /// where it is used rather than at the wild jump that follows. Execution continues after a mismatch, since code
+    /// like `longjmp` legitimately returns elsewhere. At the maximum depth the oldest return address is dropped.
+    ///
+    /// The shadow stack is only observed, it doesn't affect execution or the trace.
+    fn set_shadow_stack(&mut self, max_depth: Option<usize>) {
+        self.get_executor_mut().shadow_stack = max_depth.map(ShadowStack::new);
+    }
+
+    /// Record the bytes of every range in `ranges` in the view once execution ends, see
+    /// [`View::view_captured_memory`].
+    ///
+    /// Meant for programs that leave their results in memory instead of the public output, e.g. the signature
+    /// region of compliance tests. Ranges that are not entirely readable are left out of the view.
+    fn set_captured_memory(&mut self, ranges: Vec<Range<u32>>) {
+        self.get_executor_mut().captured_memory = ranges;
+    }
+
+    /// Pass `args` to the entrypoint in registers `a0` to `a7`, the remaining registers stay zero. Must be called
+    /// before execution starts.
+    ///
+    /// The arguments are recorded in the [`ProgramInfo`] of the view, so that the proof binds them together with the
+    /// entrypoint.
+    fn set_initial_args(&mut self, args: &[u32]) -> Result<()> {
+        if args.len() > ProgramInfo::MAX_INITIAL_ARGS {
+            return Err(VMErrorKind::TooManyInitialArgs(args.len()).into());
+        }
+
+        let executor = self.get_executor_mut();
+        for (&register, &arg) in Register::ARG_REGISTERS.iter().zip(args) {
+            executor.cpu.registers.write(register, arg);
+        }
+        executor.initial_args = args.to_vec();
+        Ok(())
+    }
+
+    /// Like [`Self::set_initial_args`], with each argument given by the ABI name of its register, e.g. `("a1", 5)`.
+    ///
+    /// Argument registers before the last one given and not listed are zero.
+    fn set_named_initial_args(&mut self, args: &[(&str, u32)]) -> Result<()> {
+        let mut positional = Vec::new();
+        for &(name, arg) in args {
+            let index = Register::from_abi_name(name)
+                .and_then(|register| {
+                    Register::ARG_REGISTERS
+                        .iter()
+                        .position(|&arg_register| arg_register == register)
+                })
+                .ok_or_else(|| VMErrorKind::InvalidArgumentRegister(name.to_string()))?;
+            if positional.len() <= index {
+                positional.resize(index + 1, 0);
+            }
+            positional[index] = arg;
+        }
+        self.set_initial_args(&positional)
+    }
+
     /// Update and return previous timestamps, but it currently works word-wise, so not used.
     #[allow(dead_code)]
     fn manage_timestamps(&mut self, size: &MemAccessSize, address: &u32) -> usize {
@@ -385,10 +863,209 @@ pub trait Emulator {
         prev
     }
 
+    /// Prepare the emulator for another run of the same program on top of the memory left by the previous one.
+    ///
+    /// The registers are zeroed, except for the initial arguments which are passed again, the pc goes back to the
+    /// entrypoint and the clock to its initial value. The private input and hint tapes are emptied, new input can
+    /// be set before the next run. The public output starts out empty.
+    ///
+    /// The read-write memory is kept as is: the [`View`] of the next run lists its non-zero bytes as the initial
+    /// read-write memory, so that a proof of the next run binds the state it starts from.
+    fn warm_reset(&mut self);
+
     /// Return a `View` capturing the end-state of the emulator.
     fn finalize(&self) -> View;
 }
 
+/// The basic blocks of a program, decoded by a linear sweep over its instructions starting at `base`.
+///
+/// Jumps into the middle of a block are handled by the emulator the same way as for lazily decoded blocks.
+#[derive(Clone, Default)]
+struct DecodedBlocks {
+    ref_cache: Rc<RangeMap<u32, u32>>,
+    cache: Rc<BTreeMap<u32, Rc<BasicBlockEntry>>>,
+}
+
+impl DecodedBlocks {
+    fn decode(base: u32, instructions: &[u32]) -> Self {
+        let mut ref_cache = RangeMap::new();
+        let mut cache = BTreeMap::new();
+
+        let mut offset = 0;
+        while offset < instructions.len() {
+            let block = decode_block(&instructions[offset..]);
+            let pc = base + (offset * WORD_SIZE) as u32;
+            offset += block.len();
+
+            let entry = Rc::new(BasicBlockEntry::new(pc, block));
+            ref_cache.insert(entry.start..entry.end, pc);
+            cache.insert(pc, entry);
+        }
+
+        Self {
+            ref_cache: Rc::new(ref_cache),
+            cache: Rc::new(cache),
+        }
+    }
+
+    /// Share the blocks with the executor of a new run, the executor copies them only if it decodes another block.
+    fn share_with(&self, executor: &mut Executor) {
+        executor.basic_block_ref_cache = Rc::clone(&self.ref_cache);
+        executor.basic_block_cache = Rc::clone(&self.cache);
+    }
+}
+
+/// The memory of the [`HarvardEmulator`] that doesn't depend on the input of a run.
+#[derive(Clone)]
+struct HarvardProgramMemory {
+    instruction_memory: FixedMemory<RO>,
+    data_memory: UnifiedMemory,
+    // The end of the static data, the heap starts after it
+    data_end: u32,
+}
+
+impl HarvardProgramMemory {
+    fn from_elf(elf: &ElfFile) -> Self {
+        // the stack and heap will also be stored in this variable memory segment, words of the zero regions read
+        // as zero until they are written, like any other word missing from it
+        let (_, mut data_end) = elf.static_ram_range();
+        let mut data_memory =
+            UnifiedMemory::from(VariableMemory::<RW>::from(elf.ram_image.clone()));
+
+        if !elf.rom_image.is_empty() {
+            // Linker places data after rodata, but need to guard against edge case of empty data.
+            // We also advance the `data_end` past the end of the last address in the data segment
+            // because that address is used to set the base address of the heap, which needs to be
+            // aligned to `WORD_SIZE` and not overlap the final word of the data segment.
+            data_end = match max(data_end, elf.rom_image.end()) {
+                0 => 0,
+                x => x
+                    .checked_add(WORD_SIZE as u32)
+                    .expect("Heap base should not overflow"),
+            };
+
+            let ro_data_memory = FixedMemory::<RO>::from_word_vec(
+                elf.rom_image.base(),
+                elf.rom_image.len_bytes(),
+                elf.rom_image.as_ref().to_vec(),
+            );
+
+            // this unwrap will never fail for a well-formed elf file, and we've already validated
+            data_memory.add_fixed_ro(ro_data_memory).unwrap();
+        }
+
+        // Zero out the public input and public output start locations since no offset is needed for harvard emulator.
+        data_memory
+            .add_fixed_ro(FixedMemory::<RO>::from_word_slice(0x80, 8, &[0, 0]))
+            .unwrap();
+
+        // The program is readable as data, as it is in the linear emulator where `rin` and `wou` are converted to
+        // `lw` and `sw` in the second pass.
+        if !elf.instructions.is_empty() {
+            let code_memory = FixedMemory::<RO>::from_word_vec(
+                elf.base,
+                elf.instructions.len() * WORD_SIZE,
+                convert_instructions(&elf.instructions),
+            );
+            data_memory.add_fixed_ro(code_memory).unwrap();
+        }
+
+        Self {
+            instruction_memory: FixedMemory::<RO>::from_word_vec(
+                elf.base,
+                elf.instructions.len() * WORD_SIZE,
+                elf.instructions.clone(),
+            ),
+            data_memory,
+            data_end,
+        }
+    }
+}
+
+/// Replace the custom instructions `rin` and `wou` with `lw` and `sw`, as done for the second pass.
+fn convert_instructions(instructions: &[u32]) -> Vec<u32> {
+    let registry = InstructionExecutorRegistry::default();
+    instructions
+        .iter()
+        .map(|instr| super::convert_instruction(&registry, instr))
+        .collect()
+}
+
+/// A program decoded once, to be shared by several independent runs of the [`HarvardEmulator`] or the
+/// [`LinearEmulator`].
+///
+/// The loaded program is immutable: it holds the parsed ELF, the corresponding [`ProgramInfo`], the memory of the
+/// program and all of the decoded basic blocks, for the instructions of the first pass and for the converted
+/// instructions of the second pass. A run started with [`HarvardEmulator::new_run`] or [`LinearEmulator::new_run`]
+/// shares all of it: the memory is copied a page at a time when the run first writes to it, and the blocks only if
+/// the run decodes a block of its own.
+#[derive(Clone)]
+pub struct LoadedProgram {
+    elf: ElfFile,
+    program_info: ProgramInfo,
+    harvard_memory: HarvardProgramMemory,
+    harvard_blocks: DecodedBlocks,
+    // The converted instructions, at `elf.base`
+    linear_code_memory: FixedMemory<RO>,
+    linear_blocks: DecodedBlocks,
+}
+
+impl LoadedProgram {
+    /// Load the memory of the program and decode every basic block of it.
+    pub fn from_elf(elf: &ElfFile) -> Self {
+        let linear_instructions = convert_instructions(&elf.instructions);
+        let linear_blocks = DecodedBlocks::decode(elf.base, &linear_instructions);
+
+        let program_info = ProgramInfo {
+            initial_pc: elf.entry,
+            program: elf
+                .instructions
+                .iter()
+                .enumerate()
+                .map(|(pc_offset, instruction)| ProgramMemoryEntry {
+                    pc: elf.base + (pc_offset * WORD_SIZE) as u32,
+                    instruction_word: *instruction,
+                })
+                .collect(),
+            initial_args: vec![],
+        };
+
+        Self {
+            elf: elf.clone(),
+            program_info,
+            harvard_memory: HarvardProgramMemory::from_elf(elf),
+            harvard_blocks: DecodedBlocks::decode(elf.base, &elf.instructions),
+            linear_code_memory: FixedMemory::<RO>::from_word_vec(
+                elf.base,
+                elf.instructions.len() * WORD_SIZE,
+                linear_instructions,
+            ),
+            linear_blocks,
+        }
+    }
+
+    /// Return the program memory of the loaded program.
+    pub fn program_info(&self) -> &ProgramInfo {
+        &self.program_info
+    }
+
+    /// Return the ELF file the program was loaded from.
+    pub fn elf(&self) -> &ElfFile {
+        &self.elf
+    }
+
+    /// Return the control-flow graph of the decoded basic blocks.
+    pub fn control_flow_graph(&self) -> ControlFlowGraph {
+        let blocks: Vec<BasicBlock> = self
+            .harvard_blocks
+            .cache
+            .values()
+            .map(|entry| entry.block.clone())
+            .collect();
+        ControlFlowGraph::new(self.elf.base, &blocks)
+    }
+}
+
 #[derive(Debug)]
 pub struct HarvardEmulator {
     // The core execution components
@@ -409,6 +1086,12 @@ pub struct HarvardEmulator {
     // Initial content of static ram image
     initial_ram_image: MemorySegmentImage,
 
+    // Zero-initialized static ram, not part of the static ram image
+    zero_regions: Vec<ZeroRegion>,
+
+    // Read-write memory kept by a warm reset, replaces the static ram image as the initial memory of the next run
+    retained_ram: Option<Vec<MemoryInitializationEntry>>,
+
     // A combined read-only (in part) and read-write (in part) memory image
     pub data_memory: UnifiedMemory,
 
@@ -426,6 +1109,8 @@ impl Default for HarvardEmulator {
             output_memory: VariableMemory::<WO>::default(),
             initial_rom_image: MemorySegmentImage::default(),
             initial_ram_image: MemorySegmentImage::default(),
+            zero_regions: Vec::new(),
+            retained_ram: None,
             data_memory: UnifiedMemory::default(),
             memory_stats: MemoryStats::default(),
         }
@@ -434,37 +1119,26 @@ impl Default for HarvardEmulator {
 
 impl HarvardEmulator {
     pub fn from_elf(elf: &ElfFile, public_input: &[u8], private_input: &[u8]) -> Self {
-        // the stack and heap will also be stored in this variable memory segment
-        let mut data_end = elf.ram_image.end();
-        let mut data_memory =
-            UnifiedMemory::from(VariableMemory::<RW>::from(elf.ram_image.clone()));
-
-        if !elf.rom_image.is_empty() {
-            // Linker places data after rodata, but need to guard against edge case of empty data.
-            // We also advance the `data_end` past the end of the last address in the data segment
-            // because that address is used to set the base address of the heap, which needs to be
-            // aligned to `WORD_SIZE` and not overlap the final word of the data segment.
-            data_end = match max(data_end, elf.rom_image.end()) {
-                0 => 0,
-                x => x
-                    .checked_add(WORD_SIZE as u32)
-                    .expect("Heap base should not overflow"),
-            };
-
-            let ro_data_memory = FixedMemory::<RO>::from_word_vec(
-                elf.rom_image.base(),
-                elf.rom_image.len_bytes(),
-                elf.rom_image.as_ref().to_vec(),
-            );
-
-            // this unwrap will never fail for a well-formed elf file, and we've already validated
-            data_memory.add_fixed_ro(ro_data_memory).unwrap();
-        }
+        Self::with_program_memory(
+            elf,
+            HarvardProgramMemory::from_elf(elf),
+            public_input,
+            private_input,
+        )
+    }
 
-        // Zero out the public input and public output start locations since no offset is needed for harvard emulator.
-        data_memory
-            .add_fixed_ro(FixedMemory::<RO>::from_word_slice(0x80, 8, &[0, 0]))
-            .unwrap();
+    fn with_program_memory(
+        elf: &ElfFile,
+        program_memory: HarvardProgramMemory,
+        public_input: &[u8],
+        private_input: &[u8],
+    ) -> Self {
+        verify_shared_constants();
+        let HarvardProgramMemory {
+            instruction_memory,
+            data_memory,
+            data_end,
+        } = program_memory;
 
         // Add the public input length to the beginning of the public input.
         let len_bytes = (public_input.len()) as u32;
@@ -475,18 +1149,17 @@ impl HarvardEmulator {
                 private_input_tape: VecDeque::<u8>::from(private_input.to_vec()),
                 base_address: elf.base,
                 entrypoint: elf.entry,
-                global_clock: 1, // global_clock = 0 captures initalization for memory records
+                global_clock: FIRST_CLK,
+                dispatch_table: vec![None; elf.instructions.len()],
                 ..Default::default()
             },
-            instruction_memory: FixedMemory::<RO>::from_word_vec(
-                elf.base,
-                elf.instructions.len() * WORD_SIZE,
-                elf.instructions.clone(),
-            ),
+            instruction_memory,
             input_memory: FixedMemory::<RO>::from_byte_slice(0, &public_input_with_len),
             output_memory: VariableMemory::<WO>::default(),
             initial_rom_image: elf.rom_image.clone(),
             initial_ram_image: elf.ram_image.clone(),
+            zero_regions: elf.zero_regions.clone(),
+            retained_ram: None,
             data_memory,
             memory_stats: MemoryStats::new(data_end, MEMORY_TOP),
         };
@@ -494,11 +1167,28 @@ impl HarvardEmulator {
         emulator
     }
 
+    /// Start a new run of an already loaded program.
+    ///
+    /// The memory and the decoded basic blocks are shared with the [`LoadedProgram`], so no instruction is decoded
+    /// again and a page of memory is only copied when the run first writes to it. Only the registers and the input
+    /// are allocated for the new run.
+    pub fn new_run(program: &LoadedProgram, public_input: &[u8], private_input: &[u8]) -> Self {
+        let mut emulator = Self::with_program_memory(
+            &program.elf,
+            program.harvard_memory.clone(),
+            public_input,
+            private_input,
+        );
+        program.harvard_blocks.share_with(&mut emulator.executor);
+        emulator
+    }
+
     /// Creates a HarvardEmulator from a basic block IR, for simple testing purposes.
     ///
     /// This function initializes a Harvard with a single basic block of instructions.
     /// It's primarily used for testing and simple emulation scenarios.
     pub fn from_basic_blocks(basic_blocks: &Vec<BasicBlock>) -> Self {
+        verify_shared_constants();
         let mut encoded_basic_blocks = Vec::new();
         for block in basic_blocks {
             encoded_basic_blocks.extend(block.encode());
@@ -508,7 +1198,8 @@ impl HarvardEmulator {
             executor: Executor {
                 base_address: ELF_TEXT_START,
                 entrypoint: ELF_TEXT_START,
-                global_clock: 1, // global_clock = 0 captures initalization for memory records
+                global_clock: FIRST_CLK,
+                dispatch_table: vec![None; encoded_basic_blocks.len()],
                 ..Default::default()
             },
             instruction_memory: FixedMemory::<RO>::from_word_vec(
@@ -527,84 +1218,75 @@ impl HarvardEmulator {
 impl Emulator for HarvardEmulator {
     /// Executes a single RISC-V instruction.
     ///
-    /// 1. Retrieves the instruction executor function for the given opcode via HashMap.
+    /// 1. Resolves the instruction executor function, which is cached per instruction after its first execution.
     /// 2. Executes the instruction using the appropriate executor function.
     /// 3. Updates the program counter (PC) if the instruction is not a branch or jump.
-    /// 4. Increments the global clock.
+    /// 4. Advances the global clock by the cost of the instruction, see [`OpcodeCost`].
     fn execute_instruction(
         &mut self,
         bare_instruction: &Instruction,
         force_provable_transcript: bool,
     ) -> Result<(InstructionResult, MemoryRecords)> {
-        let (res, (load_ops, store_ops)) = match (
-            self.executor
-                .instruction_executor
-                .get_for_read_input(&bare_instruction.opcode),
-            self.executor
-                .instruction_executor
-                .get_for_write_output(&bare_instruction.opcode),
-            self.executor
-                .instruction_executor
-                .custom_executor_from_opcode(&bare_instruction.opcode),
-            self.executor
-                .instruction_executor
-                .get(&bare_instruction.opcode),
-        ) {
-            _ if bare_instruction.is_system_instruction() => {
-                <HarvardEmulator as Emulator>::execute_syscall(
-                    &mut self.executor,
-                    &mut self.data_memory,
-                    None,
-                    Some(&mut self.memory_stats),
-                    bare_instruction,
-                    force_provable_transcript,
-                )?
-            }
-            (Some(read_input), ..) => read_input(
-                &mut self.executor.cpu,
-                &mut self.input_memory,
-                bare_instruction,
-            )?,
-            (_, Some(write_output), ..) => write_output(
-                &mut self.executor.cpu,
-                &mut self.output_memory,
+        self.executor.check_cycle_limit()?;
+        self.executor.check_self_loop()?;
+        self.executor.check_stack_guard(bare_instruction)?;
+        let (pc, registers) = (self.executor.cpu.pc.value, self.executor.cpu.registers);
+
+        let (res, (load_ops, store_ops)) = match self.executor.dispatch(bare_instruction)? {
+            Dispatch::Syscall => <HarvardEmulator as Emulator>::execute_syscall(
+                &mut self.executor,
+                &mut self.data_memory,
+                None,
+                Some(&mut self.memory_stats),
                 bare_instruction,
+                force_provable_transcript,
             )?,
-            (_, _, Some(custom_executor), ..) => custom_executor(
+            Dispatch::ReadInput => InstructionExecutorRegistry::read_input_executor()(
                 &mut self.executor.cpu,
-                &mut self.data_memory,
+                &mut self.input_memory,
                 bare_instruction,
             )?,
-            (.., Ok(executor)) => executor(
+            Dispatch::WriteOutput => {
+                let (res, (load_ops, store_ops)) =
+                    InstructionExecutorRegistry::write_output_executor()(
+                        &mut self.executor.cpu,
+                        &mut self.output_memory,
+                        bare_instruction,
+                    )?;
+                // The first word of the output memory is the exit code.
+                self.executor
+                    .check_committed_output_writes(&store_ops, WORD_SIZE as u32)?;
+                (res, (load_ops, store_ops))
+            }
+            // Writes to x0 are discarded, the result of `addi x0, x0, 0` is zero.
+            Dispatch::Nop => (Some(0), Default::default()),
+            Dispatch::Execute(executor) => executor(
                 &mut self.executor.cpu,
                 &mut self.data_memory,
                 bare_instruction,
-            )?,
-            (.., Err(e)) => return Err(e),
+            )
+            .map_err(|e| VMErrorKind::memory_error_at(e, pc))?,
         };
 
-        let mut memory_records = MemoryRecords::new();
-
-        load_ops.iter().for_each(|op| {
-            memory_records.insert(op.as_record(self.executor.global_clock));
-        });
-
-        store_ops.iter().for_each(|op| {
-            memory_records.insert(op.as_record(self.executor.global_clock));
-        });
+        // Multi-cycle instructions give each of their accesses its own sub-timestamp.
+        let cycles =
+            OpcodeCost::instruction_cycles(&bare_instruction.opcode, registers[Register::X17]);
+        let memory_records =
+            timestamp_accesses(&load_ops, &store_ops, self.executor.global_clock, cycles);
 
         self.memory_stats
             .update_stack_access(self.executor.cpu.registers.read(Register::X2));
+        self.executor.track_stack_pointer();
 
         if !bare_instruction.is_branch_or_jump_instruction() {
             self.executor.cpu.pc.step();
         }
+        self.executor.track_shadow_stack(pc, bare_instruction);
+        self.executor.track_self_loop(pc, &registers, &store_ops);
 
-        // The global clock will update according to the currency of ZK (constraint?)
-        // instead of pure RISC-V cycle count.
-        // Right now we don't have information how an instruction cost in ZK, so we just
-        // increment the global clock by 1.
-        self.executor.global_clock += 1;
+        // The global clock advances by the cost of the instruction in the shared cost model, so that
+        // timestamps agree with the prover.
+        self.executor.global_clock += cycles as usize;
 
         Ok((res, memory_records))
     }
@@ -623,20 +1305,15 @@ impl Emulator for HarvardEmulator {
             return Ok(self.executor.basic_block_cache.get(start).unwrap().clone());
         }
 
-        let block = decode_until_end_of_a_block(self.instruction_memory.segment_words(pc, None));
+        let block = decode_block(self.instruction_memory.segment_words(pc, None));
         if block.is_empty() {
             Err(VMErrorKind::VMOutOfInstructions)?
         }
 
         let entry = Rc::new(BasicBlockEntry::new(pc, block));
-        let _ = self
-            .executor
-            .basic_block_cache
-            .insert(pc, Rc::clone(&entry));
+        let _ = Rc::make_mut(&mut self.executor.basic_block_cache).insert(pc, Rc::clone(&entry));
 
-        self.executor
-            .basic_block_ref_cache
-            .insert(entry.start..entry.end, pc);
+        Rc::make_mut(&mut self.executor.basic_block_ref_cache).insert(entry.start..entry.end, pc);
 
         Ok(entry)
     }
@@ -649,6 +1326,31 @@ impl Emulator for HarvardEmulator {
         &mut self.executor
     }
 
+    fn stack_top(&self) -> u32 {
+        MEMORY_TOP
+    }
+
+    /// Warm runs are traced with [`k_trace_harvard`](crate::trace::k_trace_harvard).
+    fn warm_reset(&mut self) {
+        self.executor.warm_reset();
+        self.output_memory = VariableMemory::<WO>::default();
+        self.retained_ram = Some(
+            self.data_memory
+                .variable_words()
+                .flat_map(|(address, word)| {
+                    word.to_le_bytes()
+                        .into_iter()
+                        .enumerate()
+                        .map(move |(i, value)| MemoryInitializationEntry {
+                            address: address + i as u32,
+                            value,
+                        })
+                })
+                .filter(|entry| entry.value != 0)
+                .collect(),
+        );
+    }
+
     /// Return a `View` capturing the end-state of the emulator.
     fn finalize(&self) -> View {
         let mut exit_code: Vec<PublicOutputEntry> = Vec::new();
@@ -695,16 +1397,19 @@ impl Emulator for HarvardEmulator {
                 value: byte,
             })
             .collect();
-        let rw_initial_memory: Vec<MemoryInitializationEntry> = self
-            .initial_ram_image
-            .as_byte_slice()
-            .iter()
-            .enumerate()
-            .map(|(i, &byte)| MemoryInitializationEntry {
-                address: self.initial_ram_image.base() + i as u32,
-                value: byte,
-            })
-            .collect();
+        let rw_initial_memory: Vec<MemoryInitializationEntry> = match &self.retained_ram {
+            Some(retained) => retained.clone(),
+            None => self
+                .initial_ram_image
+                .as_byte_slice()
+                .iter()
+                .enumerate()
+                .map(|(i, &byte)| MemoryInitializationEntry {
+                    address: self.initial_ram_image.base() + i as u32,
+                    value: byte,
+                })
+                .collect(),
+        };
 
         let debug_logs: Vec<Vec<u8>> = if self.get_executor().logs.is_some() {
             self.get_executor().logs.clone().unwrap()
@@ -718,6 +1423,11 @@ impl Emulator for HarvardEmulator {
             .get_tracked_ram_size(input_size as u32, output_memory.len() as u32)
             as usize;
 
+        // Once the guest commits, only the committed prefix of the output is public.
+        if let Some(committed_len) = self.executor.committed_output_len {
+            output_memory.truncate(committed_len as usize);
+        }
+
         View {
             memory_layout: None,
             debug_logs,
@@ -733,6 +1443,7 @@ impl Emulator for HarvardEmulator {
                         instruction_word: *instruction,
                     })
                     .collect(),
+                initial_args: self.executor.initial_args.clone(),
             },
             ro_initial_memory,
             rw_initial_memory,
@@ -741,6 +1452,15 @@ impl Emulator for HarvardEmulator {
             exit_code,
             output_memory,
             associated_data: Vec::new(),
+            cycle_count: Some(self.executor.global_clock.saturating_sub(1)),
+            termination: self.executor.termination,
+            output_regions: Vec::new(),
+            panic_info: PanicInfo::parse(&self.executor.panic_output),
+            zero_regions: self.zero_regions.clone(),
+            deepest_sp: self.executor.deepest_sp,
+            shadow_stack_mismatches: self.executor.shadow_stack_mismatches(),
+            captured_memory: self.executor.capture_memory(&self.data_memory),
+            committed_output_len: self.executor.committed_output_len,
         }
     }
 }
@@ -768,6 +1488,12 @@ pub struct LinearEmulator {
     /// Initial snapshot of the static ram image
     initial_static_ram_image: MemorySegmentImage,
 
+    /// Zero-initialized static ram, allocated together with the static ram image
+    zero_regions: Vec<ZeroRegion>,
+
+    // Read-write memory kept by a warm reset, replaces the static ram image as the initial memory of the next run
+    retained_ram: Option<Vec<MemoryInitializationEntry>>,
+
     // The memory layout
     pub memory_layout: LinearMemoryLayout,
 
@@ -791,6 +1517,10 @@ impl LinearEmulator {
             .input_memory
             .segment_bytes(WORD_SIZE as u32, None); // exclude the first word which is the length
         let output_memory_byte_len = emulator_harvard.output_memory.bytes_spanned();
+        let committed_output_len = emulator_harvard
+            .executor
+            .committed_output_len
+            .unwrap_or_default();
 
         // Replace custom instructions `rin` and `wou` with `lw` and `sw`.
         let instructions = compiled_elf
@@ -807,33 +1537,42 @@ impl LinearEmulator {
         };
 
         // Create an optimized memory layout using memory statistics from the first pass.
+        let (static_ram_start, static_ram_end) = elf.static_ram_range();
         let memory_layout = emulator_harvard
             .memory_stats
             .create_optimized_layout(
-                (
-                    emulator_harvard.initial_ram_image.base(),
-                    emulator_harvard.initial_ram_image.end(),
-                ),
+                (static_ram_start, static_ram_end),
                 (elf.instructions.len() * WORD_SIZE
                     + WORD_SIZE // padding for linker script spacing
                     + elf.rom_image.len_bytes()
                     + WORD_SIZE // padding for linker script spacing
-                    + elf.ram_image.len_bytes()
+                    + (static_ram_end - static_ram_start) as usize
                     + WORD_SIZE) // padding for linker script spacing
                     .try_into()?,
                 ad.len().try_into()?,
                 public_input.len().try_into()?,
-                output_memory_byte_len - WORD_SIZE as u32, // Exclude the first word which is the exit code
+                // Exclude the first word which is the exit code, the output must also cover the committed output.
+                output_memory_byte_len
+                    .saturating_sub(WORD_SIZE as u32)
+                    .max(committed_output_len.next_multiple_of(WORD_SIZE as u32)),
             )
             .unwrap();
 
-        Ok(Self::from_elf(
-            memory_layout,
-            ad,
-            &elf,
-            public_input,
-            private_input,
-        ))
+        let mut emulator = Self::from_elf(memory_layout, ad, &elf, public_input, private_input);
+        emulator.executor.cycle_limit = emulator_harvard.executor.cycle_limit;
+        emulator.executor.halt_on_self_loop = emulator_harvard.executor.halt_on_self_loop;
+        emulator.executor.hints = emulator_harvard.executor.hints.clone();
+        emulator.set_stack_guard(emulator_harvard.executor.stack_guard);
+        emulator.set_shadow_stack(
+            emulator_harvard
+                .executor
+                .shadow_stack
+                .as_ref()
+                .map(|shadow_stack| shadow_stack.max_depth),
+        );
+        emulator.set_captured_memory(emulator_harvard.executor.captured_memory.clone());
+        emulator.set_initial_args(&emulator_harvard.executor.initial_args)?;
+        Ok(emulator)
     }
 
     /// Creates a Linear Emulator from an ELF file.
@@ -853,18 +1592,77 @@ impl LinearEmulator {
         public_input: &[u8],
         private_input: &[u8],
     ) -> Self {
+        let code_memory = FixedMemory::<RO>::from_word_vec(
+            memory_layout.program_start(),
+            elf.instructions.len() * WORD_SIZE,
+            elf.instructions.clone(),
+        );
+        Self::with_code_memory(
+            memory_layout,
+            ad,
+            elf,
+            code_memory,
+            public_input,
+            private_input,
+        )
+    }
+
+    /// Start a new run of an already loaded program, with the given memory layout.
+    ///
+    /// The instructions converted for the second pass and their decoded basic blocks are shared with the
+    /// [`LoadedProgram`], unless the layout places the program elsewhere than the ELF file does, in which case the
+    /// blocks are decoded lazily as for [`LinearEmulator::from_elf`].
+    pub fn new_run(
+        program: &LoadedProgram,
+        memory_layout: LinearMemoryLayout,
+        ad: &[u8],
+        public_input: &[u8],
+        private_input: &[u8],
+    ) -> Self {
+        let code_start = memory_layout.program_start();
+        let shared = code_start == program.linear_code_memory.base_address;
+        let code_memory = if shared {
+            program.linear_code_memory.clone()
+        } else {
+            FixedMemory::<RO>::from_word_slice(
+                code_start,
+                program.linear_code_memory.max_len,
+                program
+                    .linear_code_memory
+                    .segment_words(program.elf.base, None),
+            )
+        };
+
+        let mut emulator = Self::with_code_memory(
+            memory_layout,
+            ad,
+            &program.elf,
+            code_memory,
+            public_input,
+            private_input,
+        );
+        if shared {
+            program.linear_blocks.share_with(&mut emulator.executor);
+        }
+        emulator
+    }
+
+    fn with_code_memory(
+        memory_layout: LinearMemoryLayout,
+        ad: &[u8],
+        elf: &ElfFile,
+        code_memory: FixedMemory<RO>,
+        public_input: &[u8],
+        private_input: &[u8],
+    ) -> Self {
+        verify_shared_constants();
         let mut memory = UnifiedMemory::default();
 
         // nb: unwraps below will never fail for a well-formed elf file, and we've already validated
 
         // 1. Add instruction memory segment.
         let code_start = memory_layout.program_start();
-
-        let code_memory = FixedMemory::<RO>::from_word_vec(
-            code_start,
-            elf.instructions.len() * WORD_SIZE,
-            elf.instructions.clone(),
-        );
+        let instruction_count = code_memory.max_len / WORD_SIZE;
 
         let instruction_memory_index = memory.add_fixed_ro(code_memory).unwrap();
 
@@ -881,14 +1679,15 @@ impl LinearEmulator {
             Some(memory.add_fixed_ro(ro_data_memory).unwrap())
         };
 
-        // 3. Add the static ram image memory segment if it exists.
-        let _elf_ram_image_index = if elf.ram_image.is_empty() {
+        // 3. Add the static ram image memory segment if it exists, zero regions are allocated along with it.
+        let static_ram_image = elf.static_ram_image();
+        let _elf_ram_image_index = if static_ram_image.is_empty() {
             None
         } else {
             let data_memory = FixedMemory::<RW>::from_word_vec(
-                elf.ram_image.base(),
-                elf.ram_image.len_bytes(),
-                elf.ram_image.as_ref().to_vec(),
+                static_ram_image.base(),
+                static_ram_image.len_bytes(),
+                static_ram_image.into(),
             );
 
             Some(memory.add_fixed_rw(data_memory).unwrap())
@@ -978,7 +1777,8 @@ impl LinearEmulator {
                 private_input_tape: VecDeque::<u8>::from(private_input.to_vec()),
                 base_address: code_start,
                 entrypoint: code_start + (elf.entry - elf.base),
-                global_clock: 1, // global_clock = 0 captures initalization for memory records
+                global_clock: FIRST_CLK,
+                dispatch_table: vec![None; instruction_count],
                 ..Default::default()
             },
             instruction_index: instruction_memory_index,
@@ -987,6 +1787,7 @@ impl LinearEmulator {
             public_output_index: output_memory_index,
             static_rom_image_index: elf_rom_image_index,
             initial_static_ram_image,
+            zero_regions: elf.zero_regions.clone(),
             memory_layout,
             memory,
             ..Default::default()
@@ -999,73 +1800,66 @@ impl LinearEmulator {
 impl Emulator for LinearEmulator {
     /// Executes a single RISC-V instruction.
     ///
-    /// 1. Retrieves the instruction executor function for the given opcode via HashMap.
+    /// 1. Resolves the instruction executor function, which is cached per instruction after its first execution.
     /// 2. Executes the instruction using the appropriate executor function.
     /// 3. Updates the program counter (PC) if the instruction is not a branch or jump.
-    /// 4. Increments the global clock.
+    /// 4. Advances the global clock by the cost of the instruction, see [`OpcodeCost`].
     fn execute_instruction(
         &mut self,
         bare_instruction: &Instruction,
         _force_second_pass: bool, // Linear Emulator always does second pass
     ) -> Result<(InstructionResult, MemoryRecords)> {
-        let (res, (load_ops, store_ops)) = match (
-            self.executor
-                .instruction_executor
-                .get_for_read_input(&bare_instruction.opcode),
-            self.executor
-                .instruction_executor
-                .get_for_write_output(&bare_instruction.opcode),
-            self.executor
-                .instruction_executor
-                .custom_executor_from_opcode(&bare_instruction.opcode),
-            self.executor
-                .instruction_executor
-                .get(&bare_instruction.opcode),
-        ) {
-            _ if bare_instruction.is_system_instruction() => {
-                <HarvardEmulator as Emulator>::execute_syscall(
-                    &mut self.executor,
-                    &mut self.memory,
-                    Some(self.memory_layout),
-                    None, // Don't bother tracking heap accesses for linear emulator
-                    bare_instruction,
-                    true,
-                )?
-            }
-            (Some(read_input), ..) => {
-                read_input(&mut self.executor.cpu, &mut self.memory, bare_instruction)?
-            }
-            (_, Some(write_output), ..) => {
-                write_output(&mut self.executor.cpu, &mut self.memory, bare_instruction)?
-            }
-            (_, _, Some(custom_executor), ..) => {
-                custom_executor(&mut self.executor.cpu, &mut self.memory, bare_instruction)?
-            }
-            (.., Ok(executor)) => {
-                executor(&mut self.executor.cpu, &mut self.memory, bare_instruction)?
+        self.executor.check_cycle_limit()?;
+        self.executor.check_self_loop()?;
+        self.executor.check_stack_guard(bare_instruction)?;
+        let (pc, registers) = (self.executor.cpu.pc.value, self.executor.cpu.registers);
+
+        let (res, (load_ops, store_ops)) = match self.executor.dispatch(bare_instruction)? {
+            Dispatch::Syscall => <HarvardEmulator as Emulator>::execute_syscall(
+                &mut self.executor,
+                &mut self.memory,
+                Some(self.memory_layout),
+                None, // Don't bother tracking heap accesses for linear emulator
+                bare_instruction,
+                true,
+            )?,
+            Dispatch::ReadInput => InstructionExecutorRegistry::read_input_executor()(
+                &mut self.executor.cpu,
+                &mut self.memory,
+                bare_instruction,
+            )?,
+            Dispatch::WriteOutput => InstructionExecutorRegistry::write_output_executor()(
+                &mut self.executor.cpu,
+                &mut self.memory,
+                bare_instruction,
+            )?,
+            Dispatch::Nop => (Some(0), Default::default()),
+            Dispatch::Execute(executor) => {
+                executor(&mut self.executor.cpu, &mut self.memory, bare_instruction)
+                    .map_err(|e| VMErrorKind::memory_error_at(e, pc))?
             }
-            (.., Err(e)) => return Err(e),
         };
 
-        let mut memory_records = MemoryRecords::new();
+        self.executor
+            .check_committed_output_writes(&store_ops, self.memory_layout.public_output_start())?;
 
-        load_ops.iter().for_each(|op| {
-            memory_records.insert(op.as_record(self.executor.global_clock));
-        });
+        // Multi-cycle instructions give each of their accesses its own sub-timestamp.
+        let cycles =
+            OpcodeCost::instruction_cycles(&bare_instruction.opcode, registers[Register::X17]);
+        let memory_records =
+            timestamp_accesses(&load_ops, &store_ops, self.executor.global_clock, cycles);
 
-        store_ops.iter().for_each(|op| {
-            memory_records.insert(op.as_record(self.executor.global_clock));
-        });
+        self.executor.track_stack_pointer();
 
         if !bare_instruction.is_branch_or_jump_instruction() {
             self.executor.cpu.pc.step();
         }
+        self.executor.track_shadow_stack(pc, bare_instruction);
+        self.executor.track_self_loop(pc, &registers, &store_ops);
 
-        // The global clock will update according to the currency of ZK (constraint?)
-        // instead of pure RISC-V cycle count.
-        // Right now we don't have information how an instruction cost in ZK, so we just
-        // increment the global clock by 1.
-        self.executor.global_clock += 1;
+        // The global clock advances by the cost of the instruction in the shared cost model, so that
+        // timestamps agree with the prover.
+        self.executor.global_clock += cycles as usize;
 
         Ok((res, memory_records))
     }
@@ -1084,21 +1878,18 @@ impl Emulator for LinearEmulator {
             return Ok(self.executor.basic_block_cache.get(start).unwrap().clone());
         }
 
-        let block = decode_until_end_of_a_block(self.memory.segment_words(
-            self.instruction_index,
-            pc,
-            None,
-        )?);
+        let block = decode_block(
+            self.memory
+                .segment_words(self.instruction_index, pc, None)?,
+        );
         if block.is_empty() {
             Err(VMErrorKind::VMOutOfInstructions)?
         }
 
         let entry = Rc::new(BasicBlockEntry::new(pc, block));
-        let _ = self.executor.basic_block_cache.insert(pc, entry.clone());
+        let _ = Rc::make_mut(&mut self.executor.basic_block_cache).insert(pc, entry.clone());
 
-        self.executor
-            .basic_block_ref_cache
-            .insert(entry.start..entry.end, pc);
+        Rc::make_mut(&mut self.executor.basic_block_ref_cache).insert(entry.start..entry.end, pc);
 
         Ok(entry)
     }
@@ -1111,6 +1902,32 @@ impl Emulator for LinearEmulator {
         &mut self.executor
     }
 
+    fn stack_top(&self) -> u32 {
+        self.memory_layout.stack_top()
+    }
+
+    /// The output segment is zeroed, while the static ram, heap and stack segments keep their content.
+    fn warm_reset(&mut self) {
+        self.executor.warm_reset();
+        if let Some((_, idx)) = self.public_output_index {
+            let output = &self.memory.fwo_store[idx];
+            self.memory.fwo_store[idx] = FixedMemory::<WO>::from_word_vec(
+                output.base_address,
+                output.max_len,
+                vec![0; output.max_len / WORD_SIZE],
+            );
+        }
+        self.retained_ram = Some(
+            self.memory
+                .frw_store
+                .iter()
+                .flat_map(|memory| memory.addr_val_bytes_iter())
+                .filter(|&(_, value)| value != 0)
+                .map(|(address, value)| MemoryInitializationEntry::new(address, value))
+                .collect(),
+        );
+    }
+
     /// Return a `View` capturing the end-state of the emulator.
     fn finalize(&self) -> View {
         let mut exit_code: Vec<PublicOutputEntry> = Vec::new();
@@ -1145,6 +1962,11 @@ impl Emulator for LinearEmulator {
             }
         }
 
+        // Once the guest commits, only the committed prefix of the output is public.
+        if let Some(committed_len) = self.executor.committed_output_len {
+            output_memory.truncate(committed_len as usize);
+        }
+
         // Need to use dynamic dispatch due to Rust typing rules and not wanting to incur the cost
         // of doing a `collect()`.
         let input_memory = self
@@ -1225,17 +2047,20 @@ impl Emulator for LinearEmulator {
             },
         };
         let ram_initialization = &self.initial_static_ram_image;
-        let rw_initial_memory = ram_initialization
-            .as_byte_slice()
-            .iter()
-            .enumerate()
-            .map(|(offset, byte)| {
-                MemoryInitializationEntry::new(
-                    offset as u32 + self.initial_static_ram_image.base(),
-                    *byte,
-                )
-            })
-            .collect();
+        let rw_initial_memory: Vec<MemoryInitializationEntry> = match &self.retained_ram {
+            Some(retained) => retained.clone(),
+            None => ram_initialization
+                .as_byte_slice()
+                .iter()
+                .enumerate()
+                .map(|(offset, byte)| {
+                    MemoryInitializationEntry::new(
+                        offset as u32 + self.initial_static_ram_image.base(),
+                        *byte,
+                    )
+                })
+                .collect(),
+        };
 
         let debug_logs: Vec<Vec<u8>> = if self.get_executor().logs.is_some() {
             self.get_executor().logs.clone().unwrap()
@@ -1253,35 +2078,49 @@ impl Emulator for LinearEmulator {
             .unwrap_or_default()
             .to_vec();
 
-        let ro_initial_memory: Vec<_> = public_io_loc_iter.into_iter().chain(rom_iter).collect();
+        let program_memory = ProgramInfo {
+            initial_pc: self.executor.entrypoint,
+            program: self
+                .memory
+                .segment_words(
+                    self.instruction_index,
+                    self.memory_layout.program_start(),
+                    None,
+                )
+                .expect("Cannot find program memory in LinearEmulator")
+                .iter()
+                .enumerate()
+                .map(|(pc_offset, instruction)| ProgramMemoryEntry {
+                    pc: self.memory_layout.program_start() + (pc_offset * WORD_SIZE) as u32,
+                    instruction_word: *instruction,
+                })
+                .collect(),
+            initial_args: self.executor.initial_args.clone(),
+        };
+
+        // The program is read-only memory like the rodata, loads from it are checked against its bytes. Every
+        // program byte costs a row of RAM initialization in the proof, see `ProgramInfo::memory_entries`.
+        let program_bytes = program_memory.memory_entries();
+        let program_size = program_bytes.len();
+        let ro_initial_memory: Vec<_> = public_io_loc_iter
+            .into_iter()
+            .chain(program_bytes)
+            .chain(rom_iter)
+            .collect();
 
-        let tracked_ram_size = self
-            .memory_layout
-            .tracked_ram_size(self.initial_static_ram_image.len_bytes() + rom_count);
+        let zero_regions_size: usize = self
+            .zero_regions
+            .iter()
+            .map(|region| region.len as usize)
+            .sum();
+        let tracked_ram_size = self.memory_layout.tracked_ram_size(
+            rw_initial_memory.len() + zero_regions_size + rom_count + program_size,
+        );
 
         View {
             memory_layout: Some(self.memory_layout),
             debug_logs,
-            program_memory: ProgramInfo {
-                // todo: this likely isn't robust, we need to rely on elf.entry,
-                //       but it seems to be working with the current runtime
-                initial_pc: self.memory_layout.program_start(),
-                program: self
-                    .memory
-                    .segment_words(
-                        self.instruction_index,
-                        self.memory_layout.program_start(),
-                        None,
-                    )
-                    .expect("Cannot find program memory in LinearEmulator")
-                    .iter()
-                    .enumerate()
-                    .map(|(pc_offset, instruction)| ProgramMemoryEntry {
-                        pc: self.memory_layout.program_start() + (pc_offset * WORD_SIZE) as u32,
-                        instruction_word: *instruction,
-                    })
-                    .collect(),
-            },
+            program_memory,
             ro_initial_memory,
             rw_initial_memory,
             input_memory,
@@ -1289,6 +2128,15 @@ impl Emulator for LinearEmulator {
             exit_code,
             output_memory,
             associated_data,
+            cycle_count: Some(self.executor.global_clock.saturating_sub(1)),
+            termination: self.executor.termination,
+            output_regions: Vec::new(),
+            panic_info: PanicInfo::parse(&self.executor.panic_output),
+            zero_regions: self.zero_regions.clone(),
+            deepest_sp: self.executor.deepest_sp,
+            shadow_stack_mismatches: self.executor.shadow_stack_mismatches(),
+            captured_memory: self.executor.capture_memory(&self.memory),
+            committed_output_len: self.executor.committed_output_len,
         }
     }
 }
@@ -1298,6 +2146,8 @@ mod tests {
     use super::*;
     use crate::read_testing_elf_from_path;
     use crate::riscv::{BuiltinOpcode, Instruction, Opcode};
+    use nexus_common::constants::NOP_INSTRUCTION_WORD;
+    use nexus_common::error::{MemoryError, MemoryFaultKind};
     use serial_test::serial;
 
     fn setup_basic_block_ir() -> Vec<BasicBlock> {
@@ -1353,6 +2203,136 @@ mod tests {
         );
     }
 
+    #[test]
+    #[serial]
+    fn test_harvard_new_run_shares_loaded_program() {
+        let elf_file = read_testing_elf_from_path!("/test/fib_10.elf");
+
+        let program = LoadedProgram::from_elf(&elf_file);
+        let decoded_blocks = DECODED_BLOCKS.with(|count| count.get());
+
+        let mut first = HarvardEmulator::new_run(&program, &[1, 2, 3], &[]);
+        let mut second = HarvardEmulator::new_run(&program, &[4, 5], &[]);
+        for emulator in [&mut first, &mut second] {
+            assert_eq!(
+                emulator.execute(false).unwrap_err().source,
+                VMErrorKind::VMExited(0)
+            );
+        }
+        assert_eq!(DECODED_BLOCKS.with(|count| count.get()), decoded_blocks);
+
+        let (first, second) = (first.finalize(), second.finalize());
+        let mut reference = HarvardEmulator::from_elf(&elf_file, &[1, 2, 3], &[]);
+        reference.execute(false).unwrap_err();
+        let reference = reference.finalize();
+
+        assert_eq!(first.get_public_input(), reference.get_public_input());
+        assert_ne!(first.get_public_input(), second.get_public_input());
+        assert_eq!(first.exit_code_u32(), reference.exit_code_u32());
+        assert_eq!(second.exit_code_u32(), reference.exit_code_u32());
+        assert_eq!(
+            first.get_program_memory().program.len(),
+            program.program_info().program.len()
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_linear_new_run_shares_loaded_program() {
+        let elf_file = read_testing_elf_from_path!("/test/fib_10.elf");
+        let layout = LinearMemoryLayout::default();
+
+        let program = LoadedProgram::from_elf(&elf_file);
+        let decoded_blocks = DECODED_BLOCKS.with(|count| count.get());
+
+        // The second run starts after the first one wrote to the shared memory.
+        let mut views = Vec::new();
+        for public_input in [&[1, 2, 3][..], &[4, 5]] {
+            let mut emulator = LinearEmulator::new_run(&program, layout, &[], public_input, &[]);
+            assert_eq!(
+                emulator.execute(false).unwrap_err().source,
+                VMErrorKind::VMExited(0)
+            );
+            views.push(emulator.finalize());
+        }
+        assert_eq!(DECODED_BLOCKS.with(|count| count.get()), decoded_blocks);
+
+        let mut reference = LinearEmulator::from_elf(layout, &[], &elf_file, &[4, 5], &[]);
+        reference.execute(false).unwrap_err();
+        let reference = reference.finalize();
+
+        assert_eq!(views[1].get_public_input(), reference.get_public_input());
+        assert_ne!(views[0].get_public_input(), views[1].get_public_input());
+        assert_eq!(views[1].exit_code_u32(), reference.exit_code_u32());
+        assert_eq!(
+            views[1].get_ro_initial_memory(),
+            reference.get_ro_initial_memory()
+        );
+        assert_eq!(
+            views[1].get_rw_initial_memory(),
+            reference.get_rw_initial_memory()
+        );
+    }
+
+    fn assert_warm_reset_keeps_ram(emulator: &mut impl Emulator) {
+        assert_eq!(
+            emulator.execute(false).unwrap_err().source,
+            VMErrorKind::VMExited(0)
+        );
+
+        emulator.warm_reset();
+        let executor = emulator.get_executor();
+        assert_eq!(executor.global_clock, FIRST_CLK);
+        assert_eq!(executor.cpu.pc.value, executor.entrypoint);
+        assert_eq!(executor.termination, None);
+
+        let view = emulator.finalize();
+        assert!(!view.get_rw_initial_memory().is_empty());
+        assert!(view
+            .get_rw_initial_memory()
+            .iter()
+            .all(|entry| entry.value != 0));
+        assert!(view.get_exit_code().iter().all(|entry| entry.value == 0));
+
+        assert_eq!(
+            emulator.execute(false).unwrap_err().source,
+            VMErrorKind::VMExited(0)
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_warm_reset_keeps_ram() {
+        let elf_file = read_testing_elf_from_path!("/test/fib_10.elf");
+
+        assert_warm_reset_keeps_ram(&mut HarvardEmulator::from_elf(&elf_file, &[], &[]));
+        assert_warm_reset_keeps_ram(&mut LinearEmulator::from_elf(
+            LinearMemoryLayout::default(),
+            &[],
+            &elf_file,
+            &[],
+            &[],
+        ));
+    }
+
+    #[test]
+    fn test_loaded_program_control_flow_graph() {
+        let elf_file = read_testing_elf_from_path!("/test/fib_10.elf");
+        let graph = LoadedProgram::from_elf(&elf_file).control_flow_graph();
+
+        // Nodes cover the text segment without gaps.
+        assert_eq!(graph.nodes[0].start, elf_file.base);
+        assert!(graph
+            .nodes
+            .windows(2)
+            .all(|pair| pair[0].end == pair[1].start));
+        assert_eq!(
+            graph.nodes.last().unwrap().end,
+            elf_file.base + (elf_file.instructions.len() * WORD_SIZE) as u32
+        );
+        assert!(graph.node_of(elf_file.entry).is_some());
+    }
+
     #[test]
     fn test_harvard_fibonacci() {
         let basic_blocks = setup_basic_block_ir();
@@ -1448,6 +2428,162 @@ mod tests {
         assert_eq!(emulator.executor.private_input_tape, private_input_vec);
     }
 
+    /// Executes the program one instruction at a time, returning all results and memory records together with
+    /// the error that stopped execution.
+    fn execute_steps(
+        emulator: &mut impl Emulator,
+    ) -> (Vec<InstructionResult>, MemoryTranscript, VMErrorKind) {
+        let mut results = Vec::new();
+        let mut transcript = Vec::new();
+
+        loop {
+            let pc = emulator.get_executor().cpu.pc.value;
+            let entry = match emulator.fetch_block(pc) {
+                Ok(entry) => entry,
+                Err(e) => return (results, transcript, e.source),
+            };
+            let at = (pc - entry.start) as usize / WORD_SIZE;
+            for instruction in entry.block.0[at..].iter() {
+                match emulator.execute_instruction(instruction, false) {
+                    Ok((res, mem)) => {
+                        results.push(res);
+                        transcript.push(mem);
+                    }
+                    Err(e) => return (results, transcript, e.source),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_multi_cycle_syscall_sub_timestamps() {
+        let read_word = nexus_common::ecall::Ecall::ReadPrivateInputWord as u32;
+        let basic_blocks = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0x100),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, read_word),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 11, 0, 0x100),
+        ])];
+        let mut emulator = HarvardEmulator::from_basic_blocks(&basic_blocks);
+        emulator.set_private_input(&[1, 2, 3]);
+
+        let (_, transcript, error) = execute_steps(&mut emulator);
+        assert_eq!(error, VMErrorKind::VMOutOfInstructions);
+        assert_eq!(emulator.executor.cpu.registers[Register::X10], 3);
+        assert_eq!(emulator.executor.cpu.registers[Register::X11], 0x030201);
+
+        // The syscall executes at clock 3 and takes a cycle per byte it writes, each write at its own
+        // sub-timestamp, the next instruction executes once they have all elapsed.
+        let accesses = |records: &MemoryRecords| {
+            records
+                .iter()
+                .map(|record| (record.get_address(), record.get_timestamp()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            accesses(&transcript[2]),
+            [(0x100, 3), (0x101, 4), (0x102, 5), (0x103, 6)]
+        );
+        assert_eq!(accesses(&transcript[3]), [(0x100, 7)]);
+        assert_eq!(emulator.executor.global_clock, 8);
+    }
+
+    #[test]
+    #[serial]
+    fn test_dispatch_table_preserves_execution() {
+        let elf_file = read_testing_elf_from_path!("/test/fib_10.elf");
+
+        let mut reference = HarvardEmulator::from_elf(&elf_file, &[], &[]);
+        reference.executor.bypass_dispatch_table = true;
+        let expected = execute_steps(&mut reference);
+        assert_eq!(expected.2, VMErrorKind::VMExited(0));
+
+        let mut emulator = HarvardEmulator::from_elf(&elf_file, &[], &[]);
+        assert_eq!(execute_steps(&mut emulator), expected);
+        assert!(emulator.executor.dispatch_table.iter().any(Option::is_some));
+        assert_eq!(
+            emulator.executor.global_clock,
+            reference.executor.global_clock
+        );
+
+        let mut reference =
+            LinearEmulator::from_elf(LinearMemoryLayout::default(), &[], &elf_file, &[], &[]);
+        reference.executor.bypass_dispatch_table = true;
+        let expected = execute_steps(&mut reference);
+        assert_eq!(expected.2, VMErrorKind::VMExited(0));
+
+        let mut emulator =
+            LinearEmulator::from_elf(LinearMemoryLayout::default(), &[], &elf_file, &[], &[]);
+        assert_eq!(execute_steps(&mut emulator), expected);
+        assert!(emulator.executor.dispatch_table.iter().any(Option::is_some));
+        assert_eq!(
+            emulator.executor.global_clock,
+            reference.executor.global_clock
+        );
+    }
+
+    #[test]
+    fn test_dispatch_table_checks_opcode() {
+        let add = BasicBlockEntry::new(
+            0,
+            BasicBlock::new(vec![Instruction::new_ir(
+                Opcode::from(BuiltinOpcode::ADDI),
+                1,
+                0,
+                1,
+            )]),
+        );
+        let xori = BasicBlockEntry::new(
+            0,
+            BasicBlock::new(vec![Instruction::new_ir(
+                Opcode::from(BuiltinOpcode::XORI),
+                1,
+                0,
+                3,
+            )]),
+        );
+
+        let mut emulator = HarvardEmulator::default();
+        emulator.execute_basic_block(&add, false).unwrap();
+        assert_eq!(emulator.executor.cpu.registers[1.into()], 1);
+
+        // A different instruction at the same pc must not reuse the cached executor.
+        emulator.executor.cpu.pc.value = 0;
+        emulator.execute_basic_block(&xori, false).unwrap();
+        assert_eq!(emulator.executor.cpu.registers[1.into()], 3);
+
+        // Instructions outside the program are never cached, whatever the pc.
+        emulator.executor.cpu.pc.value = 0xFFFF_FFF0;
+        emulator.execute_basic_block(&add, false).unwrap();
+        assert!(emulator.executor.dispatch_table.is_empty());
+    }
+
+    #[test]
+    fn test_nop_fast_path() {
+        let nop = BasicBlockEntry::new(0, BasicBlock::new(vec![Instruction::nop()]));
+        let add = BasicBlockEntry::new(
+            0,
+            BasicBlock::new(vec![Instruction::new_ir(
+                Opcode::from(BuiltinOpcode::ADDI),
+                1,
+                0,
+                1,
+            )]),
+        );
+        assert_eq!(Instruction::nop().encode(), NOP_INSTRUCTION_WORD);
+
+        let mut emulator = HarvardEmulator::default();
+        let registers = emulator.executor.cpu.registers;
+        emulator.execute_basic_block(&nop, false).unwrap();
+        assert_eq!(emulator.executor.cpu.pc.value, 4);
+        assert_eq!(emulator.executor.cpu.registers, registers);
+
+        // The NOP must not be cached as the executor of every `addi` at this pc.
+        emulator.executor.cpu.pc.value = 0;
+        emulator.execute_basic_block(&add, false).unwrap();
+        assert_eq!(emulator.executor.cpu.registers[1.into()], 1);
+    }
+
     #[test]
     fn test_unimplemented_instruction() {
         let op = Opcode::new(0, None, None, "unsupported");
@@ -1471,4 +2607,80 @@ mod tests {
             VMErrorKind::UndefinedInstruction(op)
         );
     }
+
+    #[test]
+    fn test_named_initial_args() {
+        let basic_blocks = vec![BasicBlock::new(vec![Instruction::new_ir(
+            Opcode::from(BuiltinOpcode::ADD),
+            12,
+            10,
+            11,
+        )])];
+        let mut emulator = HarvardEmulator::from_basic_blocks(&basic_blocks);
+        emulator
+            .set_named_initial_args(&[("a1", 5), ("a3", 7)])
+            .unwrap();
+        assert_eq!(emulator.executor.initial_args, [0, 5, 0, 7]);
+        assert_eq!(emulator.executor.cpu.registers.read(Register::X11), 5);
+        assert_eq!(emulator.executor.cpu.registers.read(Register::X13), 7);
+
+        for name in ["t0", "x10", "a8"] {
+            assert_eq!(
+                emulator
+                    .set_named_initial_args(&[(name, 1)])
+                    .unwrap_err()
+                    .source,
+                VMErrorKind::InvalidArgumentRegister(name.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn test_memory_fault_reports_pc() {
+        let basic_blocks = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 0x101),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 2, 1, 0),
+        ])];
+        let mut emulator = HarvardEmulator::from_basic_blocks(&basic_blocks);
+
+        assert_eq!(
+            emulator.execute(false).unwrap_err().source,
+            VMErrorKind::MemoryFault {
+                pc: ELF_TEXT_START + WORD_SIZE as u32,
+                error: MemoryError::AccessFault {
+                    kind: MemoryFaultKind::Misaligned,
+                    addr: 0x101,
+                    size: MemAccessSize::Word,
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_committed_output_writes_at_top_of_address_space() {
+        const TOP_WORD: u32 = 0xFFFF_FFFC;
+        let store = |address| HashSet::from([StoreOp::Op(MemAccessSize::Word, address, 1, 0)]);
+        let mut executor = Executor {
+            committed_output_len: Some(8),
+            ..Default::default()
+        };
+
+        // The committed prefix ends below the store.
+        assert!(executor
+            .check_committed_output_writes(&store(TOP_WORD), TOP_WORD - 8)
+            .is_ok());
+
+        // The committed prefix reaches the end of the address space.
+        executor.committed_output_len = Some(12);
+        assert_eq!(
+            executor
+                .check_committed_output_writes(&store(TOP_WORD), TOP_WORD - 8)
+                .unwrap_err()
+                .source,
+            VMErrorKind::CommittedOutputOverwrite(TOP_WORD)
+        );
+        assert!(executor
+            .check_committed_output_writes(&store(TOP_WORD - 16), TOP_WORD - 8)
+            .is_ok());
+    }
 }
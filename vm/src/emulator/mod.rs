@@ -57,7 +57,10 @@ mod layout;
 pub(crate) mod memory_stats;
 mod registry;
 
-pub use executor::{Emulator, Executor, HarvardEmulator, LinearEmulator};
+pub use executor::{
+    Emulator, Executor, HarvardEmulator, LinearEmulator, LoadedProgram, ShadowStackMismatch,
+    StackGuard, TerminationCause,
+};
 pub use layout::LinearMemoryLayout;
 
 mod utils;
@@ -49,6 +49,8 @@
 //! - The `add_opcode` method allows adding custom instructions at runtime.
 //! - The `get` method retrieves the execution function for a given opcode.
 //! - Special methods `get_for_read_input` and `get_for_write_output` handle the custom I/O instructions.
+//! - The `dispatch` method resolves all of the above at once into a [`Dispatch`], which the emulator caches per
+//!   instruction.
 //!
 //! This registry is crucial for the emulator's operation, providing a flexible and
 //! efficient way to map opcodes to their execution functions, including support for
@@ -74,6 +76,21 @@ macro_rules! register_instruction_executor {
     };
 }
 
+/// How an instruction is executed, as resolved by [`InstructionExecutorRegistry::dispatch`].
+#[derive(Debug, Clone, Copy)]
+pub enum Dispatch {
+    /// A system call, handled by the emulator itself.
+    Syscall,
+    /// Read input (`rin`), executed as `lw` on the input memory.
+    ReadInput,
+    /// Write output (`wou`), executed as `sw` on the output memory.
+    WriteOutput,
+    /// The canonical NOP, `addi x0, x0, 0`, which only advances the pc.
+    Nop,
+    /// A builtin or custom instruction executed on the data memory.
+    Execute(InstructionExecutorFn<UnifiedMemory>),
+}
+
 #[derive(Debug)]
 pub struct InstructionExecutorRegistry {
     builtins: [Option<InstructionExecutorFn<UnifiedMemory>>; BuiltinOpcode::VARIANT_COUNT],
@@ -245,7 +262,7 @@ impl InstructionExecutorRegistry {
     }
 
     pub fn get(&self, op: &Opcode) -> Result<InstructionExecutorFn<UnifiedMemory>> {
-        if let Ok(opcode) = TryInto::<BuiltinOpcode>::try_into(op.clone()) {
+        if let Some(opcode) = op.builtin() {
             let idx = opcode as usize;
 
             // Safety: the length of `builtins` is statically guaranteed to be equal to the number
@@ -268,8 +285,7 @@ impl InstructionExecutorRegistry {
     ) -> Option<InstructionExecutorFn<M>> {
         // Opcode will be parsed dynamically so the name will be different.
         if self.is_read_input(op) {
-            // Interpret `rin` as `lw`.
-            return Some(instructions::LwInstruction::evaluator as InstructionExecutorFn<M>);
+            return Some(Self::read_input_executor());
         }
 
         None
@@ -281,13 +297,47 @@ impl InstructionExecutorRegistry {
     ) -> Option<InstructionExecutorFn<M>> {
         // Opcode will be parsed dynamically so the name will be different.
         if self.is_write_output(op) {
-            // Interpret `wou` as `sw`.
-            return Some(instructions::SwInstruction::evaluator as InstructionExecutorFn<M>);
+            return Some(Self::write_output_executor());
         }
 
         None
     }
 
+    /// Returns the executor of `rin`, which is interpreted as `lw`.
+    #[inline(always)]
+    pub fn read_input_executor<M: MemoryProcessor>() -> InstructionExecutorFn<M> {
+        instructions::LwInstruction::evaluator as InstructionExecutorFn<M>
+    }
+
+    /// Returns the executor of `wou`, which is interpreted as `sw`.
+    #[inline(always)]
+    pub fn write_output_executor<M: MemoryProcessor>() -> InstructionExecutorFn<M> {
+        instructions::SwInstruction::evaluator as InstructionExecutorFn<M>
+    }
+
+    /// Resolves how `instruction` is executed.
+    ///
+    /// System instructions take precedence, followed by read input, write output, custom executors and finally
+    /// the builtin and precompile executors returned by [`Self::get`]. Apart from the canonical NOP, which is
+    /// resolved to [`Dispatch::Nop`], the result only depends on the opcode and the registered executors, so it can
+    /// be cached as long as no opcode is added.
+    pub fn dispatch(&self, instruction: &Instruction) -> Result<Dispatch> {
+        let op = &instruction.opcode;
+        if instruction.is_nop() {
+            Ok(Dispatch::Nop)
+        } else if instruction.is_system_instruction() {
+            Ok(Dispatch::Syscall)
+        } else if self.is_read_input(op) {
+            Ok(Dispatch::ReadInput)
+        } else if self.is_write_output(op) {
+            Ok(Dispatch::WriteOutput)
+        } else if let Some(custom_executor) = self.custom_executor_from_opcode(op) {
+            Ok(Dispatch::Execute(custom_executor))
+        } else {
+            self.get(op).map(Dispatch::Execute)
+        }
+    }
+
     pub fn custom_executor_from_opcode<M: MemoryProcessor>(
         &self,
         op: &Opcode,
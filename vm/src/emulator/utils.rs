@@ -1,14 +1,20 @@
-use crate::elf::ElfFile;
-use crate::memory::MemorySegmentImage;
-use crate::riscv::{decode_instruction, BasicBlock};
+use std::ops::Range;
+
+use crate::elf::{ElfError, ElfFile};
+use crate::error::{Result, VMErrorKind};
+use crate::memory::{MemorySegmentImage, ZeroRegion};
+use crate::riscv::{decode_instruction, BasicBlock, Register};
 
 pub use super::executor::Emulator;
+use super::executor::{ShadowStackMismatch, TerminationCause};
 pub use super::layout::LinearMemoryLayout;
 use super::registry;
 
-use nexus_common::constants::WORD_SIZE;
+use nexus_common::constants::{MEMORY_TOP, NUM_REGISTERS, WORD_SIZE};
 use nexus_common::memory::MemoryRecords;
 use nexus_common::riscv::{opcode::BuiltinOpcode, Opcode};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 pub type MemoryTranscript = Vec<MemoryRecords>;
 
@@ -62,9 +68,16 @@ pub fn convert_instruction(registry: &registry::InstructionExecutorRegistry, ins
     }
 }
 
+/// Assemble the bytes of `entries` in address order, starting at `base`.
+///
+/// The buffer ends at the highest address with an entry, addresses in between without an entry read as zero.
 pub fn io_entries_into_vec<T: IOEntry>(base: u32, entries: &[T]) -> Vec<u8> {
-    let mut vec: Vec<u8> = Vec::new();
-    vec.resize(entries.len(), u8::default());
+    let len = entries
+        .iter()
+        .map(|entry| (entry.address() - base) as usize + 1)
+        .max()
+        .unwrap_or_default();
+    let mut vec: Vec<u8> = vec![u8::default(); len];
 
     entries.iter().for_each(|entry: &T| {
         let loc = (entry.address() - base) as usize;
@@ -93,9 +106,16 @@ pub fn slice_into_io_entries<T: IOEntry>(base: u32, values: &[u8]) -> Vec<T> {
         .collect()
 }
 
-pub fn elf_into_program_info(elf: &ElfFile, layout: &LinearMemoryLayout) -> ProgramInfo {
-    ProgramInfo {
-        initial_pc: layout.program_start(),
+/// Returns the program of `elf` placed at the start of the program segment of `layout`.
+///
+/// Fails if the entry point of `elf` is below its base address.
+pub fn elf_into_program_info(elf: &ElfFile, layout: &LinearMemoryLayout) -> Result<ProgramInfo> {
+    let entry_offset = elf
+        .entry
+        .checked_sub(elf.base)
+        .ok_or(ElfError::EntryNotExecutable(elf.entry))?;
+    Ok(ProgramInfo {
+        initial_pc: layout.program_start() + entry_offset,
         program: elf
             .instructions
             .iter()
@@ -105,7 +125,8 @@ pub fn elf_into_program_info(elf: &ElfFile, layout: &LinearMemoryLayout) -> Prog
                 instruction_word: *instruction,
             })
             .collect(),
-    }
+        initial_args: vec![],
+    })
 }
 
 // One entry per byte because RO memory can be accessed bytewise
@@ -123,8 +144,48 @@ impl MemoryInitializationEntry {
     }
 }
 
+/// Errors raised while validating a list of [`MemoryInitializationEntry`].
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryInitializationError {
+    /// More than one entry initializes the same byte.
+    #[error("duplicate memory initialization entry at address 0x{address:08X}")]
+    DuplicateAddress { address: u32 },
+}
+
+/// Returns the initial memory entries in canonical order, i.e. sorted by ascending address.
+///
+/// Both the prover and the verifier commit to initial memory in this order, so that the resulting
+/// commitment doesn't depend on how the caller concatenated ROM, RAM and public input entries.
+/// Entries sharing the same address are rejected, regardless of whether their values agree.
+pub fn canonical_init_memory(
+    entries: &[MemoryInitializationEntry],
+) -> Result<Vec<MemoryInitializationEntry>, MemoryInitializationError> {
+    let mut entries = entries.to_vec();
+    entries.sort_unstable_by_key(|entry| entry.address);
+
+    if let Some(window) = entries
+        .windows(2)
+        .find(|window| window[0].address == window[1].address)
+    {
+        return Err(MemoryInitializationError::DuplicateAddress {
+            address: window[0].address,
+        });
+    }
+    Ok(entries)
+}
+
+/// Returns the public output entries in canonical order, i.e. sorted by ascending address.
+///
+/// Output regions are disjoint address ranges, so entries of each [`OutputRegion`] are contiguous in this order,
+/// and regions follow each other by base address.
+pub fn canonical_public_output(entries: &[PublicOutputEntry]) -> Vec<PublicOutputEntry> {
+    let mut entries = entries.to_vec();
+    entries.sort_by_key(|entry| entry.address);
+    entries
+}
+
 // One entry per byte because WO memory can be accessed bytewise
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct PublicOutputEntry {
     pub address: u32,
     pub value: u8,
@@ -136,6 +197,68 @@ impl PublicOutputEntry {
     pub fn new(address: u32, value: u8) -> Self {
         Self { address, value }
     }
+
+    /// Split a raw byte buffer into one entry per byte, the first one at `base_addr`.
+    ///
+    /// This is the inverse of [`View::public_output_bytes`] for verifiers that only have the raw output.
+    pub fn from_bytes(base_addr: u32, bytes: &[u8]) -> Vec<Self> {
+        slice_into_io_entries(base_addr, bytes)
+    }
+}
+
+/// Name of the region covering the whole public output when no regions are declared.
+pub const DEFAULT_OUTPUT_REGION: &str = "output";
+
+/// A named range of the public output, see [`View::set_output_regions`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputRegion {
+    pub name: String,
+    /// Address of the first byte of the region.
+    pub base: u32,
+    pub len: u32,
+}
+
+impl OutputRegion {
+    pub fn new(name: impl Into<String>, base: u32, len: u32) -> Self {
+        Self {
+            name: name.into(),
+            base,
+            len,
+        }
+    }
+
+    /// Returns the addresses covered by the region.
+    pub fn addresses(&self) -> Range<u32> {
+        self.base..self.base.saturating_add(self.len)
+    }
+
+    /// Returns the `len` bytes of the region, addresses without an entry read as zero.
+    pub fn bytes(&self, entries: &[PublicOutputEntry]) -> Vec<u8> {
+        let mut bytes = vec![0u8; self.len as usize];
+        for entry in entries
+            .iter()
+            .filter(|entry| self.addresses().contains(&entry.address))
+        {
+            bytes[(entry.address - self.base) as usize] = entry.value;
+        }
+        bytes
+    }
+}
+
+/// Error returned when declaring output regions that don't partition the public output.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum OutputRegionError {
+    /// Two regions share a name.
+    #[error("duplicate output region {0:?}")]
+    DuplicateName(String),
+
+    /// Two regions share an address.
+    #[error("output regions {0:?} and {1:?} overlap")]
+    Overlap(String, String),
+
+    /// A region reaches outside of the public output segment.
+    #[error("output region {0:?} is outside of the public output")]
+    OutOfBounds(String),
 }
 
 // One entry per instruction because program memory is always accessed instruction-wise
@@ -150,14 +273,96 @@ pub struct ProgramInfo {
     // The program counter where the execution starts
     pub initial_pc: u32,
     pub program: Vec<ProgramMemoryEntry>,
+    // The arguments passed to the entry point in registers a0 to a7, the remaining registers start at zero
+    pub initial_args: Vec<u32>,
 }
 
 impl ProgramInfo {
+    /// The maximum number of arguments passed in registers to the entry point.
+    pub const MAX_INITIAL_ARGS: usize = Register::ARG_REGISTERS.len();
+
     pub fn dummy() -> Self {
         Self {
             initial_pc: 0,
             program: vec![],
+            initial_args: vec![],
+        }
+    }
+
+    /// Checks that the initial arguments fit in the argument registers, see [`Self::MAX_INITIAL_ARGS`].
+    ///
+    /// Program information built by the emulator always passes, program information received from elsewhere
+    /// must be checked before proving or verifying.
+    pub fn check_initial_args(&self) -> Result<(), VMErrorKind> {
+        if self.initial_args.len() > Self::MAX_INITIAL_ARGS {
+            return Err(VMErrorKind::TooManyInitialArgs(self.initial_args.len()));
         }
+        Ok(())
+    }
+
+    /// Returns the initial value of every register.
+    ///
+    /// Arguments past the last argument register are ignored, see [`Self::check_initial_args`].
+    pub fn initial_registers(&self) -> [u32; NUM_REGISTERS] {
+        let mut registers = [0; NUM_REGISTERS];
+        for (&register, &arg) in Register::ARG_REGISTERS.iter().zip(&self.initial_args) {
+            registers[register as usize] = arg;
+        }
+        registers
+    }
+
+    /// Returns the bytes of the program as initial memory entries, one per byte in address order.
+    ///
+    /// With a unified address space the program is also readable as data, e.g. jump tables or constants embedded
+    /// in `.text`, so its bytes belong to the initial memory under memory checking.
+    ///
+    /// This isn't free: the prover commits one row of RAM initialization per byte, four per instruction, whether
+    /// the guest loads from `.text` or not. A program of 2^16 instructions adds 2^18 rows to the RAM
+    /// initialization component, which may exceed the rows taken by the guest's own data.
+    pub fn memory_entries(&self) -> Vec<MemoryInitializationEntry> {
+        self.program
+            .iter()
+            .flat_map(|entry| {
+                (0..WORD_SIZE).map(move |idx| {
+                    MemoryInitializationEntry::new(
+                        entry.pc + idx as u32,
+                        entry.instruction_word.to_le_bytes()[idx],
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+impl ProgramInfo {
+    /// The number of instructions listed by the `Display` implementation, unless a precision is given, e.g.
+    /// `format!("{program:.100}")` lists up to 100 instructions.
+    pub const LISTING_LIMIT: usize = 32;
+}
+
+/// Disassembled listing of the program, one instruction per line, with an elision marker past the limit.
+impl std::fmt::Display for ProgramInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let limit = f.precision().unwrap_or(Self::LISTING_LIMIT);
+        writeln!(
+            f,
+            "ProgramInfo {{ entry: {:#010x}, {} instructions }}",
+            self.initial_pc,
+            self.program.len()
+        )?;
+        for entry in self.program.iter().take(limit) {
+            writeln!(
+                f,
+                "  {:#010x}  {:08x}  {}",
+                entry.pc,
+                entry.instruction_word,
+                decode_instruction(entry.instruction_word)
+            )?;
+        }
+        if self.program.len() > limit {
+            writeln!(f, "  ... {} more instructions", self.program.len() - limit)?;
+        }
+        Ok(())
     }
 }
 
@@ -215,10 +420,47 @@ pub trait InternalView {
     /// Return information about the exit code.
     fn get_exit_code(&self) -> &[PublicOutputEntry];
 
-    /// Add debug logs from another emulator.
+    /// Add debug logs and the panic information from another emulator.
     fn add_logs(&mut self, emulator: &impl Emulator);
 }
 
+/// Panic of the guest program, as reported by the panic handler of the runtime.
+///
+/// The handler writes the line, the file and the message of the panic to [`PANIC_FD`](crate::system::PANIC_FD),
+/// separated by newlines, before exiting with the panic exit code.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PanicInfo {
+    pub message: String,
+    pub file: String,
+    pub line: u32,
+}
+
+impl PanicInfo {
+    /// Parse the bytes written to the panic file descriptor, `None` if nothing or something malformed was written.
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        let payload = std::str::from_utf8(bytes).ok()?;
+        let mut parts = payload.splitn(3, '\n');
+        let line = parts.next()?.parse().ok()?;
+        let file = parts.next()?.to_string();
+        let message = parts.next()?.to_string();
+        Some(Self {
+            message,
+            file,
+            line,
+        })
+    }
+}
+
+impl std::fmt::Display for PanicInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "panicked at {}:{}: {}",
+            self.file, self.line, self.message
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct View {
     pub(crate) memory_layout: Option<LinearMemoryLayout>,
@@ -233,6 +475,24 @@ pub struct View {
     pub(crate) exit_code: Vec<PublicOutputEntry>,
     pub(crate) output_memory: Vec<PublicOutputEntry>,
     pub(crate) associated_data: Vec<u8>,
+    /// The number of clock cycles elapsed during execution, when known.
+    pub(crate) cycle_count: Option<usize>,
+    /// The reason execution stopped, when known.
+    pub(crate) termination: Option<TerminationCause>,
+    /// Named regions of the public output, empty if the output is a single region.
+    pub(crate) output_regions: Vec<OutputRegion>,
+    /// The panic reported by the guest, if it panicked.
+    pub(crate) panic_info: Option<PanicInfo>,
+    /// Zero-initialized static RAM, which is not listed in the initial read-write memory.
+    pub(crate) zero_regions: Vec<ZeroRegion>,
+    /// The lowest stack pointer observed during execution, when known.
+    pub(crate) deepest_sp: Option<u32>,
+    /// Returns that didn't match the shadow stack, when it is enabled.
+    pub(crate) shadow_stack_mismatches: Vec<ShadowStackMismatch>,
+    /// Final bytes of the address ranges captured during execution.
+    pub(crate) captured_memory: Vec<(Range<u32>, Vec<u8>)>,
+    /// Number of public output bytes committed by the guest, if it committed any.
+    pub(crate) committed_output_len: Option<u32>,
 }
 
 impl View {
@@ -261,7 +521,88 @@ impl View {
             exit_code: exit_code.to_owned(),
             output_memory: output_memory.to_owned(),
             associated_data: associated_data.to_owned(),
+            cycle_count: None,
+            termination: None,
+            output_regions: Vec::new(),
+            panic_info: None,
+            zero_regions: Vec::new(),
+            deepest_sp: None,
+            shadow_stack_mismatches: Vec::new(),
+            captured_memory: Vec::new(),
+            committed_output_len: None,
+        }
+    }
+
+    /// Splits the public output into named regions, which can be read and checked independently.
+    ///
+    /// Regions must have distinct names and must not overlap. When the memory layout is known, they must lie
+    /// within the public output segment. Output bytes outside of every region are still part of the output.
+    pub fn set_output_regions(
+        &mut self,
+        mut regions: Vec<OutputRegion>,
+    ) -> Result<(), OutputRegionError> {
+        for (i, region) in regions.iter().enumerate() {
+            if regions[..i].iter().any(|other| other.name == region.name) {
+                return Err(OutputRegionError::DuplicateName(region.name.clone()));
+            }
+            let end = region.base.checked_add(region.len);
+            let in_bounds = self.memory_layout.is_none_or(|layout| {
+                layout.public_output_start() <= region.base
+                    && end.is_some_and(|end| end <= layout.public_output_end())
+            });
+            if !in_bounds || end.is_none() {
+                return Err(OutputRegionError::OutOfBounds(region.name.clone()));
+            }
+        }
+        regions.sort_by_key(|region| region.base);
+        if let Some(window) = regions
+            .windows(2)
+            .find(|window| window[0].addresses().end > window[1].base)
+        {
+            return Err(OutputRegionError::Overlap(
+                window[0].name.clone(),
+                window[1].name.clone(),
+            ));
         }
+
+        self.output_regions = regions;
+        Ok(())
+    }
+
+    /// Return the regions of the public output, ordered by base address.
+    ///
+    /// Without declared regions, a single region named [`DEFAULT_OUTPUT_REGION`] covers the whole output, from the
+    /// start of the output segment up to the last byte written by the guest.
+    pub fn output_regions(&self) -> Vec<OutputRegion> {
+        if !self.output_regions.is_empty() {
+            return self.output_regions.clone();
+        }
+        let base = self
+            .memory_layout
+            .map_or(0, |layout| layout.public_output_start());
+        let end = self
+            .output_memory
+            .iter()
+            .map(|entry| entry.address + 1)
+            .max()
+            .unwrap_or_default()
+            .max(base);
+        vec![OutputRegion::new(DEFAULT_OUTPUT_REGION, base, end - base)]
+    }
+
+    /// Return the public output entries of the region called `name`, in address order.
+    pub fn get_public_output_region(&self, name: &str) -> Option<Vec<PublicOutputEntry>> {
+        let region = self
+            .output_regions()
+            .into_iter()
+            .find(|region| region.name == name)?;
+        let entries: Vec<_> = self
+            .output_memory
+            .iter()
+            .filter(|entry| region.addresses().contains(&entry.address))
+            .copied()
+            .collect();
+        Some(canonical_public_output(&entries))
     }
 
     /// Return the raw bytes of the public input, if any.
@@ -295,6 +636,104 @@ impl View {
             .map(|layout| io_entries_into_vec(layout.public_output_start(), &self.output_memory))
     }
 
+    /// Return the public output as bytes in address order, starting at the beginning of the output region.
+    ///
+    /// The buffer ends at the highest written address, output addresses in between that have no entry read as
+    /// zero. Without a memory layout, entry addresses are offsets into the output region.
+    pub fn public_output_bytes(&self) -> Vec<u8> {
+        let base = self
+            .memory_layout
+            .map_or(0, |layout| layout.public_output_start());
+        io_entries_into_vec(base, &self.output_memory)
+    }
+
+    /// Return the exit code, read as a little-endian word, if any.
+    pub fn exit_code_u32(&self) -> Option<u32> {
+        if self.exit_code.is_empty() {
+            return None;
+        }
+        let base = self.memory_layout.map_or(0, |layout| layout.exit_code());
+        let mut bytes = [0u8; WORD_SIZE];
+        bytes
+            .iter_mut()
+            .zip(io_entries_into_vec(base, &self.exit_code))
+            .for_each(|(byte, value)| *byte = value);
+        Some(u32::from_le_bytes(bytes))
+    }
+
+    /// Return the initial memory bytes (static ROM, static RAM and public input) in the given address range.
+    ///
+    /// Addresses in the range that aren't initialized read as zero.
+    pub fn initial_memory_bytes(&self, range: Range<u32>) -> Vec<u8> {
+        let mut bytes = vec![0u8; range.len()];
+        for entry in self
+            .ro_initial_memory
+            .iter()
+            .chain(&self.rw_initial_memory)
+            .chain(&self.input_memory)
+            .filter(|entry| range.contains(&entry.address))
+        {
+            bytes[(entry.address - range.start) as usize] = entry.value;
+        }
+        bytes
+    }
+
+    /// Return the zero-initialized regions of the static RAM, such as `.bss`.
+    ///
+    /// Their bytes are not part of the initial read-write memory. Like every address missing from the initial memory
+    /// they read as zero until they are written, the regions only tell which of those addresses belong to the program.
+    pub fn view_zero_regions(&self) -> &[ZeroRegion] {
+        &self.zero_regions
+    }
+
+    /// Return the lowest stack pointer observed during execution, if known.
+    pub fn view_deepest_sp(&self) -> Option<u32> {
+        self.deepest_sp
+    }
+
+    /// Return the returns that didn't go back to the address pushed by their call, in execution order, see
+    /// [`Emulator::set_shadow_stack`]. Empty unless the shadow stack was enabled.
+    pub fn view_shadow_stack_mismatches(&self) -> &[ShadowStackMismatch] {
+        &self.shadow_stack_mismatches
+    }
+
+    /// Return the bytes of `range` once execution ended, if the range was captured, see
+    /// [`Emulator::set_captured_memory`].
+    pub fn view_captured_memory(&self, range: &Range<u32>) -> Option<&[u8]> {
+        self.captured_memory
+            .iter()
+            .find(|(captured, _)| captured == range)
+            .map(|(_, bytes)| bytes.as_slice())
+    }
+
+    /// Return the largest number of bytes the stack grew below its top during execution, if known.
+    pub fn view_max_stack_depth(&self) -> Option<u32> {
+        let stack_top = self
+            .memory_layout
+            .map_or(MEMORY_TOP, |layout| layout.stack_top());
+        self.deepest_sp.map(|sp| stack_top.saturating_sub(sp))
+    }
+
+    /// Return the number of clock cycles elapsed during execution, if known.
+    ///
+    /// The halting instruction does not advance the clock, so for a program that exits this is one less
+    /// than the number of executed instructions.
+    pub fn view_cycle_count(&self) -> Option<usize> {
+        self.cycle_count
+    }
+
+    /// Return the reason execution stopped, if known. The cause isn't bound by proofs of the execution, see
+    /// [`TerminationCause`].
+    pub fn view_termination_cause(&self) -> Option<TerminationCause> {
+        self.termination
+    }
+
+    /// Return the number of public output bytes committed with the commit ecall, `None` if the guest never
+    /// committed. Once the guest commits, the public output is exactly the committed prefix.
+    pub fn view_committed_output_len(&self) -> Option<u32> {
+        self.committed_output_len
+    }
+
     /// Return the number of all addresses under RAM memory checking.
     pub fn view_tracked_ram_size(&self) -> usize {
         self.tracked_ram_size
@@ -314,11 +753,111 @@ impl View {
         Some(self.debug_logs.clone())
     }
 
+    /// Return the message, file and line of the guest panic, if the guest panicked.
+    pub fn panic_info(&self) -> Option<PanicInfo> {
+        self.panic_info.clone()
+    }
+
     /// Return the memory layout, if any.
     // TODO: Remove once we split Supply-Side and Demand-Side Interfaces
     pub fn view_memory_layout(&self) -> Option<&LinearMemoryLayout> {
         self.memory_layout.as_ref()
     }
+
+    /// Return the sizes of the program and memory regions of the view together with the exit code and a preview
+    /// of the public output.
+    pub fn summary(&self) -> ViewSummary {
+        let public_output = self.public_output_bytes();
+        ViewSummary {
+            program_len: self.program_memory.program.len(),
+            entry_point: self.program_memory.initial_pc,
+            ro_initial_memory_len: self.ro_initial_memory.len(),
+            rw_initial_memory_len: self.rw_initial_memory.len(),
+            public_input_len: self.input_memory.len(),
+            zero_initialized_len: self
+                .zero_regions
+                .iter()
+                .map(|region| region.len as usize)
+                .sum(),
+            associated_data_len: self.associated_data.len(),
+            exit_code: self.exit_code_u32(),
+            public_output_len: public_output.len(),
+            public_output_preview: public_output
+                .into_iter()
+                .take(ViewSummary::OUTPUT_PREVIEW_LEN)
+                .collect(),
+            cycle_count: self.cycle_count,
+        }
+    }
+}
+
+/// Human-readable summary of a [`View`], see [`View::summary`].
+///
+/// Memory region lengths are in bytes, the program length is in instructions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ViewSummary {
+    pub program_len: usize,
+    pub entry_point: u32,
+    pub ro_initial_memory_len: usize,
+    pub rw_initial_memory_len: usize,
+    pub public_input_len: usize,
+    pub zero_initialized_len: usize,
+    pub associated_data_len: usize,
+    pub exit_code: Option<u32>,
+    pub public_output_len: usize,
+    /// The first bytes of the public output, at most [`Self::OUTPUT_PREVIEW_LEN`].
+    pub public_output_preview: Vec<u8>,
+    pub cycle_count: Option<usize>,
+}
+
+impl ViewSummary {
+    /// The number of public output bytes kept in the preview.
+    pub const OUTPUT_PREVIEW_LEN: usize = 16;
+}
+
+impl std::fmt::Display for ViewSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "View {{")?;
+        writeln!(
+            f,
+            "  program: {} instructions, entry {:#010x}",
+            self.program_len, self.entry_point
+        )?;
+        writeln!(f, "  static rom: {} bytes", self.ro_initial_memory_len)?;
+        writeln!(
+            f,
+            "  static ram: {} bytes, {} zero-initialized",
+            self.rw_initial_memory_len, self.zero_initialized_len
+        )?;
+        writeln!(f, "  public input: {} bytes", self.public_input_len)?;
+        writeln!(f, "  associated data: {} bytes", self.associated_data_len)?;
+        match self.exit_code {
+            Some(code) => writeln!(f, "  exit code: {code}")?,
+            None => writeln!(f, "  exit code: none")?,
+        }
+        write!(f, "  public output: {} bytes", self.public_output_len)?;
+        if !self.public_output_preview.is_empty() {
+            write!(f, " [")?;
+            for byte in &self.public_output_preview {
+                write!(f, "{byte:02x}")?;
+            }
+            if self.public_output_len > self.public_output_preview.len() {
+                write!(f, "...")?;
+            }
+            write!(f, "]")?;
+        }
+        writeln!(f)?;
+        if let Some(cycles) = self.cycle_count {
+            writeln!(f, "  cycles: {cycles}")?;
+        }
+        write!(f, "}}")
+    }
+}
+
+impl std::fmt::Display for View {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.summary().fmt(f)
+    }
 }
 
 impl InternalView for View {
@@ -433,10 +972,245 @@ impl InternalView for View {
         &self.exit_code
     }
 
-    /// Add logs from another emulator.
+    /// Add logs and the panic information from another emulator.
     fn add_logs(&mut self, emulator: &impl Emulator) {
         if let Some(logs) = &emulator.get_executor().logs {
             self.debug_logs = logs.to_vec();
         }
+        self.panic_info = PanicInfo::parse(&emulator.get_executor().panic_output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view_with(
+        exit_code: Vec<PublicOutputEntry>,
+        output_memory: Vec<PublicOutputEntry>,
+        ro_initial_memory: Vec<MemoryInitializationEntry>,
+    ) -> View {
+        View::new(
+            &None,
+            &vec![],
+            &ProgramInfo::dummy(),
+            &ro_initial_memory,
+            &vec![],
+            &vec![],
+            0,
+            &exit_code,
+            &output_memory,
+            &vec![],
+        )
+    }
+
+    #[test]
+    fn test_public_output_bytes_fills_gaps() {
+        // Entries are out of order and offset 2 is never written.
+        let output_memory = vec![
+            PublicOutputEntry::new(3, 0xDD),
+            PublicOutputEntry::new(0, 0xAA),
+            PublicOutputEntry::new(1, 0xBB),
+        ];
+        let view = view_with(vec![], output_memory, vec![]);
+        assert_eq!(view.public_output_bytes(), [0xAA, 0xBB, 0x00, 0xDD]);
+        assert!(view_with(vec![], vec![], vec![])
+            .public_output_bytes()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_public_output_entries_round_trip() {
+        let bytes = [1, 2, 3, 4, 5];
+        let entries = PublicOutputEntry::from_bytes(0, &bytes);
+        assert_eq!(entries[4], PublicOutputEntry::new(4, 5));
+
+        let view = view_with(vec![], entries, vec![]);
+        assert_eq!(view.public_output_bytes(), bytes);
+    }
+
+    #[test]
+    fn test_output_regions() {
+        let mut view = view_with(
+            vec![],
+            PublicOutputEntry::from_bytes(0, &[1, 2, 3, 4, 5]),
+            vec![],
+        );
+        assert_eq!(
+            view.output_regions(),
+            [OutputRegion::new(DEFAULT_OUTPUT_REGION, 0, 5)]
+        );
+        assert_eq!(
+            view_with(vec![], vec![], vec![]).output_regions(),
+            [OutputRegion::new(DEFAULT_OUTPUT_REGION, 0, 0)]
+        );
+
+        assert_eq!(
+            view.set_output_regions(vec![
+                OutputRegion::new("log", 2, 3),
+                OutputRegion::new("root", 0, 3),
+            ]),
+            Err(OutputRegionError::Overlap("root".into(), "log".into()))
+        );
+        assert_eq!(
+            view.set_output_regions(vec![
+                OutputRegion::new("root", 0, 2),
+                OutputRegion::new("root", 2, 3),
+            ]),
+            Err(OutputRegionError::DuplicateName("root".into()))
+        );
+
+        view.set_output_regions(vec![
+            OutputRegion::new("log", 2, 3),
+            OutputRegion::new("root", 0, 2),
+        ])
+        .unwrap();
+        assert_eq!(view.output_regions()[0].name, "root");
+        let log = view.get_public_output_region("log").unwrap();
+        assert_eq!(
+            log.iter().map(|entry| entry.value).collect::<Vec<_>>(),
+            [3, 4, 5]
+        );
+        assert!(view
+            .get_public_output_region(DEFAULT_OUTPUT_REGION)
+            .is_none());
+    }
+
+    #[test]
+    fn test_exit_code_is_little_endian() {
+        let exit_code = PublicOutputEntry::from_bytes(0, &0x0102_0304u32.to_le_bytes());
+        assert_eq!(exit_code[0], PublicOutputEntry::new(0, 0x04));
+
+        let view = view_with(exit_code, vec![], vec![]);
+        assert_eq!(view.exit_code_u32(), Some(0x0102_0304));
+        assert_eq!(view_with(vec![], vec![], vec![]).exit_code_u32(), None);
+    }
+
+    #[test]
+    fn test_initial_memory_bytes() {
+        let ro_initial_memory = vec![
+            MemoryInitializationEntry::new(0x100, 1),
+            MemoryInitializationEntry::new(0x102, 3),
+            MemoryInitializationEntry::new(0x200, 9),
+        ];
+        let view = view_with(vec![], vec![], ro_initial_memory);
+        assert_eq!(view.initial_memory_bytes(0xFF..0x104), [0, 1, 0, 3, 0]);
+        assert!(view.initial_memory_bytes(0x104..0x104).is_empty());
+    }
+
+    #[test]
+    fn test_elf_into_program_info() {
+        let layout = LinearMemoryLayout::default();
+        let elf = |entry| {
+            ElfFile::new(
+                vec![0x0050_0093, 0x0000_0073],
+                entry,
+                0x1000,
+                MemorySegmentImage::default(),
+                MemorySegmentImage::default(),
+                vec![],
+            )
+        };
+        let program = elf_into_program_info(&elf(0x1004), &layout).unwrap();
+        assert_eq!(program.initial_pc, layout.program_start() + 4);
+        assert_eq!(program.program.len(), 2);
+
+        let err = elf_into_program_info(&elf(0xFFC), &layout).unwrap_err();
+        assert_eq!(
+            err.source,
+            VMErrorKind::ElfError(ElfError::EntryNotExecutable(0xFFC))
+        );
+    }
+
+    #[test]
+    fn test_initial_args_are_checked() {
+        let mut program = ProgramInfo::dummy();
+        program.initial_args = vec![1, 2, 3];
+        program.check_initial_args().unwrap();
+        let registers = program.initial_registers();
+        assert_eq!(
+            registers[Register::X10 as usize..Register::X14 as usize],
+            [1, 2, 3, 0]
+        );
+
+        program.initial_args = (0..=ProgramInfo::MAX_INITIAL_ARGS as u32).collect();
+        assert_eq!(
+            program.check_initial_args(),
+            Err(VMErrorKind::TooManyInitialArgs(
+                ProgramInfo::MAX_INITIAL_ARGS + 1
+            ))
+        );
+        // The extra argument is dropped instead of overwriting the register after a7.
+        assert_eq!(program.initial_registers()[Register::X18 as usize], 0);
+    }
+
+    #[test]
+    fn test_program_info_display() {
+        let program = ProgramInfo {
+            initial_pc: 0x1000,
+            program: [0x0050_0093, 0x0020_81b3, 0x0000_0073]
+                .into_iter()
+                .enumerate()
+                .map(|(idx, instruction_word)| ProgramMemoryEntry {
+                    pc: 0x1000 + (idx * WORD_SIZE) as u32,
+                    instruction_word,
+                })
+                .collect(),
+            initial_args: vec![],
+        };
+        assert_eq!(
+            program.to_string(),
+            "ProgramInfo { entry: 0x00001000, 3 instructions }\n\
+             \x20 0x00001000  00500093  li ra, 5\n\
+             \x20 0x00001004  002081b3  add gp, ra, sp\n\
+             \x20 0x00001008  00000073  ecall\n"
+        );
+        assert_eq!(
+            format!("{program:.1}"),
+            "ProgramInfo { entry: 0x00001000, 3 instructions }\n\
+             \x20 0x00001000  00500093  li ra, 5\n\
+             \x20 ... 2 more instructions\n"
+        );
+    }
+
+    #[test]
+    fn test_view_display() {
+        let exit_code = PublicOutputEntry::from_bytes(0, &3u32.to_le_bytes());
+        let output_memory = PublicOutputEntry::from_bytes(0, &(0..20).collect::<Vec<u8>>());
+        let ro_initial_memory = vec![MemoryInitializationEntry::new(0x100, 1)];
+        let mut view = view_with(exit_code, output_memory, ro_initial_memory);
+        view.cycle_count = Some(42);
+
+        let summary = view.summary();
+        assert_eq!(summary.public_output_len, 20);
+        assert_eq!(
+            summary.public_output_preview.len(),
+            ViewSummary::OUTPUT_PREVIEW_LEN
+        );
+        assert_eq!(
+            view.to_string(),
+            "View {\n\
+             \x20 program: 0 instructions, entry 0x00000000\n\
+             \x20 static rom: 1 bytes\n\
+             \x20 static ram: 0 bytes, 0 zero-initialized\n\
+             \x20 public input: 0 bytes\n\
+             \x20 associated data: 0 bytes\n\
+             \x20 exit code: 3\n\
+             \x20 public output: 20 bytes [000102030405060708090a0b0c0d0e0f...]\n\
+             \x20 cycles: 42\n\
+             }"
+        );
+        assert_eq!(
+            view_with(vec![], vec![], vec![]).summary().to_string(),
+            "View {\n\
+             \x20 program: 0 instructions, entry 0x00000000\n\
+             \x20 static rom: 0 bytes\n\
+             \x20 static ram: 0 bytes, 0 zero-initialized\n\
+             \x20 public input: 0 bytes\n\
+             \x20 associated data: 0 bytes\n\
+             \x20 exit code: none\n\
+             \x20 public output: 0 bytes\n\
+             }"
+        );
     }
 }
@@ -68,6 +68,13 @@ pub enum VMErrorKind {
     #[error("Wrapped MemoryError: {0}")]
     MemoryError(#[from] nexus_common::error::MemoryError),
 
+    // Load or store fault of the instruction at a specific PC
+    #[error("Memory fault at pc=0x{pc:08X}: {error}")]
+    MemoryFault {
+        pc: u32,
+        error: nexus_common::error::MemoryError,
+    },
+
     #[error("Wrapped OpcodeError: {0}")]
     OpcodeError(#[from] nexus_common::error::OpcodeError),
 
@@ -104,6 +111,55 @@ pub enum VMErrorKind {
     // Merging non-contiguous memory segments
     #[error("Non-contiguous memory")]
     NonContiguousMemory,
+
+    // VM has executed the maximum number of allowed cycles.
+    #[error("VM has exceeded the cycle limit of {0}")]
+    CycleLimitExceeded(usize),
+
+    // Load or store into the guard region below the stack.
+    #[error("Stack overflow: access to 0x{addr:08X} below the stack limit with sp=0x{sp:08X}")]
+    StackOverflow { sp: u32, addr: u32 },
+
+    // VM has reached an instruction looping to itself without side effects.
+    #[error("VM has halted in a self-loop at pc=0x{0:08X}")]
+    SelfLoopHalt(u32),
+
+    // Public output commit not extending the committed prefix of the output.
+    #[error(
+        "Public output commit at offset {0} does not extend the committed output of length {1}"
+    )]
+    NonContiguousOutputCommit(u32, u32),
+
+    // Public output commit past the end of the output segment.
+    #[error("Public output commit of {1} bytes at offset {0} exceeds the output segment")]
+    OutputCommitOutOfBounds(u32, u32),
+
+    // Write to a byte of public output that has already been committed.
+    #[error("Write to committed public output at address 0x{0:08X}")]
+    CommittedOutputOverwrite(u32),
+
+    // More arguments passed to the entry point than there are argument registers.
+    #[error("Cannot pass {0} arguments to the entry point, at most 8 fit in registers")]
+    TooManyInitialArgs(usize),
+
+    // Initial argument passed in a register that isn't an argument register.
+    #[error("Cannot pass an initial argument in {0}, expected one of a0 to a7")]
+    InvalidArgumentRegister(String),
+
+    // Hint syscall with an id that has no registered hint function.
+    #[error("No hint is registered under id {0}")]
+    UnknownHint(u32),
+}
+
+impl VMErrorKind {
+    /// Wraps an error of the instruction at `pc`, attaching the pc to access faults.
+    pub fn memory_error_at(error: nexus_common::error::MemoryError, pc: u32) -> Self {
+        if error.fault_kind().is_some() {
+            VMErrorKind::MemoryFault { pc, error }
+        } else {
+            VMErrorKind::MemoryError(error)
+        }
+    }
 }
 
 /// Result type for VM functions that can produce errors.
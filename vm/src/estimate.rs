@@ -0,0 +1,110 @@
+//! Estimation of the trace size of an execution without tracing it.
+//!
+//! [`estimate_trace_size`] runs the program once on the [`HarvardEmulator`], counting steps and touched memory
+//! words instead of recording them, and derives the log size of the main trace the prover would select, see
+//! [`main_log_size`].
+//!
+//! ```rust
+//! use nexus_vm::{elf::ElfFile, emulator::LoadedProgram, estimate_trace_size};
+//!
+//! let elf = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+//! let estimate = estimate_trace_size(&LoadedProgram::from_elf(&elf), &[], &[]).unwrap();
+//! assert!(estimate.num_steps > 0);
+//! ```
+
+use std::collections::HashSet;
+
+use nexus_common::trace_size::main_log_size;
+
+use crate::{
+    emulator::{Emulator, HarvardEmulator, LoadedProgram},
+    error::{Result, VMError, VMErrorKind},
+    WORD_SIZE,
+};
+
+/// Size of the trace of an execution, see [`estimate_trace_size`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceSizeEstimate {
+    /// Number of executed instructions, including the exit syscall.
+    pub num_steps: usize,
+    /// Number of distinct data memory words loaded from or stored to.
+    pub touched_words: usize,
+    /// Number of instructions of the program.
+    pub program_len: usize,
+    /// Log size of the main trace, the same as selected by the prover.
+    pub log_size: u32,
+}
+
+/// Runs the program on the given inputs and returns the size of its trace.
+///
+/// Steps aren't stored, the run only allocates the memory image of the program and the set of touched words.
+/// Executions that don't exit with the exit syscall are reported as errors, like by
+/// [`k_trace`](crate::trace::k_trace).
+pub fn estimate_trace_size(
+    program: &LoadedProgram,
+    public_input: &[u8],
+    private_input: &[u8],
+) -> Result<TraceSizeEstimate> {
+    let mut emulator = HarvardEmulator::new_run(program, public_input, private_input);
+    let mut num_steps = 0;
+    let mut touched_words = HashSet::new();
+    loop {
+        let pc = emulator.get_executor().cpu.pc.value;
+        let entry = emulator.fetch_block(pc)?;
+        let at = (pc - entry.start) as usize / WORD_SIZE;
+        for instruction in &entry.block.0[at..] {
+            match emulator.execute_instruction(instruction, false) {
+                Ok((_, memory_records)) => {
+                    num_steps += 1;
+                    touched_words.extend(
+                        memory_records
+                            .iter()
+                            .map(|record| record.get_address() & !(WORD_SIZE as u32 - 1)),
+                    );
+                }
+                // The exit syscall is traced.
+                Err(VMError {
+                    source: VMErrorKind::VMExited(_),
+                    ..
+                }) => {
+                    let program_len = program.program_info().program.len();
+                    return Ok(TraceSizeEstimate {
+                        num_steps: num_steps + 1,
+                        touched_words: touched_words.len(),
+                        program_len,
+                        log_size: main_log_size(num_steps + 1, program_len),
+                    });
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        emulator::InternalView,
+        read_testing_elf_from_path,
+        trace::{k_trace, Trace},
+    };
+
+    #[test]
+    fn test_estimate_matches_trace() {
+        let elf = read_testing_elf_from_path!("/test/fib_10.elf");
+        let estimate = estimate_trace_size(&LoadedProgram::from_elf(&elf), &[], &[]).unwrap();
+        let (view, trace) = k_trace(elf, &[], &[], &[], 1).unwrap();
+
+        assert_eq!(estimate.num_steps, trace.get_num_steps());
+        assert_eq!(
+            estimate.program_len,
+            view.get_program_memory().program.len()
+        );
+        assert_eq!(
+            estimate.log_size,
+            main_log_size(trace.get_num_steps(), estimate.program_len)
+        );
+        assert!(estimate.touched_words > 0);
+    }
+}
@@ -1,13 +1,23 @@
+pub mod abi;
+pub mod analysis;
+pub mod audit;
+pub mod consistency;
 pub mod cpu;
+pub mod effects;
 pub mod elf;
 pub mod emulator;
 pub mod error;
+pub mod estimate;
 pub mod memory;
+pub mod raw;
 pub mod riscv;
 pub mod system;
 pub mod trace;
 
+pub use crate::analysis::{analyze_program, ProgramAnalysis};
 pub use crate::elf::WORD_SIZE;
+pub use crate::estimate::{estimate_trace_size, TraceSizeEstimate};
+pub use crate::raw::{load_raw, RawProgramError};
 pub use crate::system::SyscallCode;
 
 #[cfg(test)]
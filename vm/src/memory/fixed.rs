@@ -43,6 +43,7 @@
 //! across different access modes and sizes.
 use core::slice;
 use std::collections::BTreeMap;
+use std::sync::Arc;
 
 use std::{fmt::Debug, marker::PhantomData};
 
@@ -56,7 +57,8 @@ use super::{LoadOp, MemAccessSize, MemoryProcessor, Mode, StoreOp, NA, RO, RW, W
 pub struct FixedMemory<M: Mode> {
     pub base_address: u32,
     pub max_len: usize,
-    vec: Vec<u32>,
+    // Shared between clones until one of them is written to, so that cloning a loaded memory image is cheap
+    vec: Arc<Vec<u32>>,
     __mode: PhantomData<M>,
 }
 
@@ -118,7 +120,7 @@ impl<M: Mode> FixedMemory<M> {
         FixedMemory::<M> {
             base_address,
             max_len,
-            vec: Vec::<u32>::new(),
+            vec: Arc::default(),
             __mode: PhantomData,
         }
     }
@@ -130,7 +132,7 @@ impl<M: Mode> FixedMemory<M> {
         FixedMemory::<M> {
             base_address,
             max_len,
-            vec,
+            vec: Arc::new(vec),
             __mode: PhantomData,
         }
     }
@@ -151,7 +153,7 @@ impl<M: Mode> FixedMemory<M> {
         FixedMemory::<M> {
             base_address,
             max_len: padded_len_bytes,
-            vec: words,
+            vec: Arc::new(words),
             __mode: PhantomData,
         }
     }
@@ -163,7 +165,7 @@ impl<M: Mode> FixedMemory<M> {
         FixedMemory::<M> {
             base_address,
             max_len,
-            vec,
+            vec: Arc::new(vec),
             __mode: PhantomData,
         }
     }
@@ -284,18 +286,20 @@ impl<M: Mode> FixedMemory<M> {
         let write_mask = !(mask << shift);
         let data = (value & mask) << shift;
 
-        let prev_value = if self.vec.len() <= word_index {
+        // Copy the words on the first write if they are still shared with another clone
+        let vec = Arc::make_mut(&mut self.vec);
+        let prev_value = if vec.len() <= word_index {
             // Resize the vector to the next word-aligned size
-            self.vec.resize(word_index + 1, 0);
+            vec.resize(word_index + 1, 0);
 
             0
         } else {
-            (self.vec[word_index] >> shift) & mask
+            (vec[word_index] >> shift) & mask
         };
 
         // Perform the write operation
-        self.vec[word_index] &= write_mask;
-        self.vec[word_index] |= data;
+        vec[word_index] &= write_mask;
+        vec[word_index] |= data;
 
         Ok(StoreOp::Op(
             size,
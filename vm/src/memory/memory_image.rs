@@ -14,6 +14,60 @@ pub struct MemorySegmentImage {
     base: u32,
 }
 
+/// A word-aligned region of memory that reads as zero until it is written, such as `.bss`.
+///
+/// Zero regions are kept apart from the initial memory image, so that a large `.bss` takes no space in the initial
+/// memory of the program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct ZeroRegion {
+    pub base: u32,
+    /// Length in bytes, a multiple of the word size.
+    pub len: u32,
+}
+
+impl ZeroRegion {
+    pub fn end(&self) -> u32 {
+        self.base + self.len
+    }
+
+    pub fn contains(&self, address: u32) -> bool {
+        (self.base..self.end()).contains(&address)
+    }
+
+    /// Merges overlapping and adjacent `(start, end)` spans into sorted regions, leaving out the part of them that
+    /// is covered by `image`.
+    pub(crate) fn from_spans(mut spans: Vec<(u32, u32)>, image: &MemorySegmentImage) -> Vec<Self> {
+        spans.sort_unstable();
+        let mut merged: Vec<(u32, u32)> = Vec::with_capacity(spans.len());
+        for (start, end) in spans {
+            match merged.last_mut() {
+                Some((_, last_end)) if start <= *last_end => *last_end = (*last_end).max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+
+        let covered = if image.is_empty() {
+            0..0
+        } else {
+            image.base()..image.end()
+        };
+        merged
+            .into_iter()
+            .flat_map(|(start, end)| {
+                [
+                    (start, end.min(covered.start)),
+                    (start.max(covered.end), end),
+                ]
+            })
+            .filter(|(start, end)| start < end)
+            .map(|(base, end)| Self {
+                base,
+                len: end - base,
+            })
+            .collect()
+    }
+}
+
 /// A memory image is a contiguous region of memory that is word-aligned. Each loadable segment
 /// produced by the ELF parser can be a `MemorySegmentImage`.
 ///
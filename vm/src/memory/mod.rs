@@ -5,13 +5,15 @@ mod paged_memory;
 mod unified;
 mod variable;
 
+pub use nexus_common::memory::traits::timestamp_accesses;
 pub use nexus_common::memory::traits::{
     LoadOp, LoadOps, MemAccessSize, MemoryProcessor, MemoryRecord, MemoryRecords, Mode, StoreOp,
     StoreOps, NA, RO, RW, WO,
 };
 
 pub use fixed::FixedMemory;
-pub use memory_image::MemorySegmentImage;
+pub use memory_image::{MemorySegmentImage, ZeroRegion};
+pub(crate) use page::PAGE_SIZE_BYTES;
 pub use paged_memory::PagedMemory;
 pub use unified::{Modes, UnifiedMemory};
 pub use variable::VariableMemory;
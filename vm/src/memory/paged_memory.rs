@@ -1,6 +1,10 @@
-use std::{cmp, collections::BTreeMap};
+use std::{cmp, collections::BTreeMap, sync::Arc};
 
-use nexus_common::{constants::WORD_SIZE, error::MemoryError, memory::alignment::Alignable};
+use nexus_common::{
+    constants::WORD_SIZE,
+    error::MemoryError,
+    memory::{alignment::Alignable, traits::byte_range_end},
+};
 use rangemap::RangeSet;
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
@@ -9,10 +13,18 @@ use crate::error::VMErrorKind;
 
 use super::page::{next_page_base, page_number, page_word_offset, Page, PAGE_SIZE_BYTES};
 
+/// Returns the range of bytes of the word at `word_address` that lie in `start..end`.
+fn word_byte_range(start: u32, end: u64, word_address: u64) -> (usize, usize) {
+    let from = u64::from(start).saturating_sub(word_address) as usize;
+    let to = cmp::min(end - word_address, WORD_SIZE as u64) as usize;
+    (from, to)
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct PagedMemory {
-    /// Maps page numbers to their backing store.
-    segments: FxHashMap<u32, Box<Page>>,
+    /// Maps page numbers to their backing store. Pages are shared between clones of the memory until one of
+    /// them writes to the page.
+    segments: FxHashMap<u32, Arc<Page>>,
 
     /// The set of all addresses that have been accessed.
     ranges: RangeSet<u32>,
@@ -88,10 +100,11 @@ impl PagedMemory {
 
         let mut old_val = None;
 
-        let page = self
-            .segments
-            .entry(page_number(address))
-            .or_insert_with(|| Box::new(Page::new()));
+        let page = Arc::make_mut(
+            self.segments
+                .entry(page_number(address))
+                .or_insert_with(|| Arc::new(Page::new())),
+        );
 
         if self.ranges.contains(&address) {
             old_val = Some(page.get_from_address(address));
@@ -121,10 +134,11 @@ impl PagedMemory {
         let mut current_index = 0;
 
         while current_index < values.len() {
-            let page = self
-                .segments
-                .entry(page_number(current_address))
-                .or_default();
+            let page = Arc::make_mut(
+                self.segments
+                    .entry(page_number(current_address))
+                    .or_default(),
+            );
 
             let chunk_end = cmp::min(next_page_base(current_address), end_address);
             let chunk_size_words = (chunk_end - current_address) as usize / WORD_SIZE;
@@ -149,6 +163,52 @@ impl PagedMemory {
         Ok(())
     }
 
+    /// Returns the `len` bytes starting at `address`, which needn't be aligned. Bytes of unset words read as zero.
+    pub fn read_bytes(&self, address: u32, len: usize) -> Result<Vec<u8>, MemoryError> {
+        let end = byte_range_end(address, len)?;
+        let mut bytes = Vec::with_capacity(len);
+        if len == 0 {
+            return Ok(bytes);
+        }
+
+        let mut word_address = u64::from(address & !(WORD_SIZE - 1) as u32);
+        while word_address < end {
+            let (from, to) = word_byte_range(address, end, word_address);
+            let word = self.get_word(word_address as u32)?.unwrap_or(0);
+            bytes.extend_from_slice(&word.to_le_bytes()[from..to]);
+            word_address += WORD_SIZE as u64;
+        }
+        Ok(bytes)
+    }
+
+    /// Writes `data` starting at `address`, which needn't be aligned. Bytes of partially written words that were
+    /// unset are set to zero.
+    pub fn write_bytes(&mut self, address: u32, data: &[u8]) -> Result<(), MemoryError> {
+        let end = byte_range_end(address, data.len())?;
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let mut word_address = u64::from(address & !(WORD_SIZE - 1) as u32);
+        let mut remaining = data;
+        while word_address < end {
+            let (from, to) = word_byte_range(address, end, word_address);
+            let mut word = if to - from == WORD_SIZE {
+                [0; WORD_SIZE]
+            } else {
+                self.get_word(word_address as u32)?
+                    .unwrap_or(0)
+                    .to_le_bytes()
+            };
+            let (chunk, rest) = remaining.split_at(to - from);
+            word[from..to].copy_from_slice(chunk);
+            self.set_word(word_address as u32, u32::from_le_bytes(word))?;
+            remaining = rest;
+            word_address += WORD_SIZE as u64;
+        }
+        Ok(())
+    }
+
     /// Create an iterator over a range of words in the memory image.
     pub fn range_words_iter(
         &self,
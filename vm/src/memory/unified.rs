@@ -233,6 +233,11 @@ impl UnifiedMemory {
         Ok(())
     }
 
+    /// Iterate over the words of the variable read-write memory with their addresses, empty if there is none.
+    pub fn variable_words(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.vrw.iter().flat_map(|vrw| vrw.addressed_iter())
+    }
+
     add_fixed!(add_fixed_rw, frw, frw_store, RW);
     add_fixed!(add_fixed_ro, fro, fro_store, RO);
     add_fixed!(add_fixed_wo, fwo, fwo_store, WO);
@@ -465,6 +470,31 @@ mod tests {
         memory
     }
 
+    #[test]
+    fn test_bytes_across_unmapped_boundary() {
+        let mut memory = UnifiedMemory::default();
+        memory
+            .add_fixed_rw(FixedMemory::<RW>::new(0x1000, 0x1000))
+            .unwrap();
+
+        // Bytes before the unmapped address are written, as with byte by byte writes.
+        assert_eq!(
+            memory.write_bytes(0x1ffe, &[1, 2, 3, 4]),
+            Err(MemoryError::InvalidMemoryAccess(
+                0x2000,
+                "writing address not in unified memory"
+            ))
+        );
+        assert_eq!(memory.read_bytes(0x1ffc, 4), Ok(vec![0, 0, 1, 2]));
+        assert_eq!(
+            memory.read_bytes(0x1ffd, 4),
+            Err(MemoryError::InvalidMemoryAccess(
+                0x2000,
+                "reading address not in unified memory"
+            ))
+        );
+    }
+
     #[test]
     fn test_fixed_rw_write_and_read_byte() {
         let mut memory = memory_setup();
@@ -245,6 +245,14 @@ impl MemoryProcessor for VariableMemory<RW> {
     fn read(&self, raw_address: u32, size: MemAccessSize) -> Result<LoadOp, MemoryError> {
         VariableMemory::execute_read(self, raw_address, size)
     }
+
+    fn read_bytes(&self, address: u32, size: usize) -> Result<Vec<u8>, MemoryError> {
+        self.store.read_bytes(address, size)
+    }
+
+    fn write_bytes(&mut self, address: u32, data: &[u8]) -> Result<(), MemoryError> {
+        self.store.write_bytes(address, data)
+    }
 }
 
 impl MemoryProcessor for VariableMemory<RO> {
@@ -281,6 +289,10 @@ impl MemoryProcessor for VariableMemory<RO> {
     fn read(&self, raw_address: u32, size: MemAccessSize) -> Result<LoadOp, MemoryError> {
         VariableMemory::execute_read(self, raw_address, size)
     }
+
+    fn read_bytes(&self, address: u32, size: usize) -> Result<Vec<u8>, MemoryError> {
+        self.store.read_bytes(address, size)
+    }
 }
 
 impl MemoryProcessor for VariableMemory<WO> {
@@ -317,6 +329,10 @@ impl MemoryProcessor for VariableMemory<WO> {
     fn read(&self, raw_address: u32, _size: MemAccessSize) -> Result<LoadOp, MemoryError> {
         Err(MemoryError::UnauthorizedRead(raw_address))
     }
+
+    fn write_bytes(&mut self, address: u32, data: &[u8]) -> Result<(), MemoryError> {
+        self.store.write_bytes(address, data)
+    }
 }
 
 #[cfg(test)]
@@ -571,6 +587,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_read_write_bytes_unaligned() {
+        let mut memory = VariableMemory::<RW>::default();
+        let read_each = |memory: &VariableMemory<RW>, address: u32, len: u32| -> Vec<u8> {
+            (address..address + len)
+                .map(|address| match memory.read(address, MemAccessSize::Byte) {
+                    Ok(LoadOp::Op(_, _, value)) => value as u8,
+                    Err(err) => panic!("{err}"),
+                })
+                .collect()
+        };
+
+        // Unaligned start and end, the untouched bytes of partial words read as zero.
+        memory.write_bytes(0x1001, &[1, 2, 3, 4, 5, 6]).unwrap();
+        assert_eq!(memory.get_word(0x1000), Ok(Some(0x03020100)));
+        assert_eq!(memory.get_word(0x1004), Ok(Some(0x00060504)));
+        for (address, len) in [(0x1003, 3), (0x1000, 8), (0x1002, 1), (0x0ffe, 12)] {
+            assert_eq!(
+                memory.read_bytes(address, len as usize).unwrap(),
+                read_each(&memory, address, len)
+            );
+        }
+
+        // Zero length accesses touch nothing.
+        let occupied = memory.occupied_bytes();
+        memory.write_bytes(0x2001, &[]).unwrap();
+        assert_eq!(memory.read_bytes(0x2003, 0), Ok(vec![]));
+        assert_eq!(memory.occupied_bytes(), occupied);
+
+        // The range must fit in the address space.
+        assert_eq!(
+            memory.read_bytes(u32::MAX - 1, 3),
+            Err(MemoryError::AddressCalculationOverflow)
+        );
+        assert_eq!(
+            memory.write_bytes(u32::MAX, &[1, 2]),
+            Err(MemoryError::AddressCalculationOverflow)
+        );
+        assert_eq!(memory.read_bytes(u32::MAX - 1, 2), Ok(vec![0, 0]));
+    }
+
+    #[test]
+    fn test_read_write_bytes_across_pages() {
+        let mut memory = VariableMemory::<RW>::default();
+        let boundary = crate::memory::PAGE_SIZE_BYTES as u32;
+
+        // The page after the boundary is unmapped, its bytes read as zero.
+        memory.write_bytes(boundary - 3, &[1, 2, 3]).unwrap();
+        assert_eq!(memory.get_word(boundary), Ok(None));
+        assert_eq!(
+            memory.read_bytes(boundary - 3, 6),
+            Ok(vec![1, 2, 3, 0, 0, 0])
+        );
+
+        // Writing maps it.
+        memory.write_bytes(boundary - 1, &[4, 5, 6]).unwrap();
+        assert_eq!(memory.get_word(boundary), Ok(Some(0x00000605)));
+        assert_eq!(
+            memory.read_bytes(boundary - 3, 6),
+            Ok(vec![1, 2, 4, 5, 6, 0])
+        );
+    }
+
     #[test]
     fn test_unpermitted_read() {
         let mut map: BTreeMap<u32, u32> = BTreeMap::new();
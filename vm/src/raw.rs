@@ -0,0 +1,242 @@
+//! Loading of programs given as raw instruction words.
+//!
+//! [`load_raw`] decodes machine words generated at runtime into a [`LoadedProgram`], without an ELF file. The
+//! program runs and is traced like any other loaded program:
+//!
+//! ```rust
+//! use nexus_vm::{emulator::HarvardEmulator, load_raw, trace::k_trace_harvard};
+//!
+//! // addi a0, zero, 0; addi a7, zero, 0x201 (exit); ecall
+//! let words = [0x00000513, 0x20100893, 0x00000073];
+//! let program = load_raw(&words, 0x1000, 0).unwrap();
+//! let mut emulator = HarvardEmulator::new_run(&program, &[], &[]);
+//! let (_view, trace) = k_trace_harvard(&mut emulator, 1).unwrap();
+//! assert_eq!(trace.blocks.len(), 3);
+//! ```
+
+use nexus_common::constants::{ELF_TEXT_START, MEMORY_TOP};
+use thiserror::Error;
+
+use crate::{
+    elf::ElfFile,
+    emulator::LoadedProgram,
+    memory::MemorySegmentImage,
+    riscv::{decode_instruction, BuiltinOpcode},
+    WORD_SIZE,
+};
+
+/// Errors of [`load_raw`], instructions are identified by their index in the words of the program.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum RawProgramError {
+    #[error("Program contains no instruction")]
+    Empty,
+
+    #[error("Program of {len} words at 0x{base:08X} doesn't fit in program memory")]
+    InvalidBaseAddress { base: u32, len: usize },
+
+    #[error("Entry offset 0x{0:X} is not the offset of an instruction")]
+    InvalidEntryOffset(u32),
+
+    #[error("Unsupported instruction 0x{word:08X} at index {index}")]
+    UnsupportedInstruction { index: usize, word: u32 },
+
+    #[error("{opcode} at index {index} targets 0x{target:08X}, outside of the program")]
+    TargetOutOfBounds {
+        index: usize,
+        opcode: BuiltinOpcode,
+        target: u32,
+    },
+}
+
+/// Decodes `words`, placed from `base_addr` on, into a program starting at `base_addr + entry_offset`.
+///
+/// Every word must decode to an instruction supported by the zkVM, and every branch and `jal` must target an
+/// instruction of the program. Targets of `jalr` depend on registers and are checked by the emulator when it
+/// runs. The program has no data memory besides the stack and the heap.
+pub fn load_raw(
+    words: &[u32],
+    base_addr: u32,
+    entry_offset: u32,
+) -> Result<LoadedProgram, RawProgramError> {
+    if words.is_empty() {
+        return Err(RawProgramError::Empty);
+    }
+    let invalid_base = RawProgramError::InvalidBaseAddress {
+        base: base_addr,
+        len: words.len(),
+    };
+    let len_bytes = u32::try_from(words.len() * WORD_SIZE).map_err(|_| invalid_base.clone())?;
+    let end = base_addr
+        .checked_add(len_bytes)
+        .filter(|&end| end <= MEMORY_TOP)
+        .ok_or_else(|| invalid_base.clone())?;
+    if base_addr < ELF_TEXT_START || base_addr % WORD_SIZE as u32 != 0 {
+        return Err(invalid_base);
+    }
+    if entry_offset >= len_bytes || entry_offset % WORD_SIZE as u32 != 0 {
+        return Err(RawProgramError::InvalidEntryOffset(entry_offset));
+    }
+
+    for (index, &word) in words.iter().enumerate() {
+        let instruction = decode_instruction(word);
+        let opcode = match instruction.opcode.builtin() {
+            Some(BuiltinOpcode::UNIMPL) => {
+                return Err(RawProgramError::UnsupportedInstruction { index, word })
+            }
+            Some(opcode) => opcode,
+            None => continue,
+        };
+        if matches!(
+            opcode,
+            BuiltinOpcode::BEQ
+                | BuiltinOpcode::BNE
+                | BuiltinOpcode::BLT
+                | BuiltinOpcode::BGE
+                | BuiltinOpcode::BLTU
+                | BuiltinOpcode::BGEU
+                | BuiltinOpcode::JAL
+        ) {
+            let pc = base_addr + (index * WORD_SIZE) as u32;
+            let target = pc.wrapping_add(instruction.op_c);
+            if !(base_addr..end).contains(&target) || target % WORD_SIZE as u32 != 0 {
+                return Err(RawProgramError::TargetOutOfBounds {
+                    index,
+                    opcode,
+                    target,
+                });
+            }
+        }
+    }
+
+    let elf = ElfFile::new(
+        words.to_vec(),
+        base_addr + entry_offset,
+        base_addr,
+        MemorySegmentImage::default(),
+        MemorySegmentImage::default(),
+        Vec::new(),
+    );
+    Ok(LoadedProgram::from_elf(&elf))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        emulator::HarvardEmulator,
+        riscv::{builder::ProgramBuilder, BasicBlock},
+        system::SyscallCode,
+        trace::k_trace_harvard,
+    };
+
+    fn encode(blocks: Vec<BasicBlock>) -> Vec<u32> {
+        blocks.iter().flat_map(BasicBlock::encode).collect()
+    }
+
+    #[test]
+    fn test_load_raw_runs() {
+        let words = encode(
+            ProgramBuilder::new()
+                .label("start")
+                .addi(5, 5, 1)
+                .addi(6, 0, 3)
+                .blt(5, 6, "start")
+                .add(10, 5, 0)
+                .addi(17, 0, SyscallCode::Exit as i32)
+                .ecall()
+                .build()
+                .unwrap(),
+        );
+        let program = load_raw(&words, 0x2000, 0).unwrap();
+        assert_eq!(program.program_info().initial_pc, 0x2000);
+        assert_eq!(program.program_info().program.len(), words.len());
+
+        let mut emulator = HarvardEmulator::new_run(&program, &[], &[]);
+        let (view, trace) = k_trace_harvard(&mut emulator, 1).unwrap();
+        assert_eq!(trace.blocks.len(), 3 * 3 + 3);
+        assert_eq!(trace.blocks[0].steps[0].pc, 0x2000);
+        assert_eq!(view.exit_code_u32(), Some(3));
+    }
+
+    #[test]
+    fn test_load_raw_entry_offset() {
+        let words = encode(
+            ProgramBuilder::new()
+                .unimpl()
+                .ecall_exit(0)
+                .build()
+                .unwrap(),
+        );
+        // The unsupported word is rejected even though it is never executed.
+        assert_eq!(
+            load_raw(&words, 0x2000, 4).unwrap_err(),
+            RawProgramError::UnsupportedInstruction {
+                index: 0,
+                word: words[0]
+            }
+        );
+        let program = load_raw(&words[1..], 0x2000, 4).unwrap();
+        assert_eq!(program.program_info().initial_pc, 0x2004);
+    }
+
+    #[test]
+    fn test_load_raw_rejects_invalid_programs() {
+        let exit = encode(ProgramBuilder::new().ecall_exit(0).build().unwrap());
+        assert_eq!(
+            load_raw(&[], 0x2000, 0).unwrap_err(),
+            RawProgramError::Empty
+        );
+        for base in [0x2002, 0, MEMORY_TOP - 4] {
+            assert_eq!(
+                load_raw(&exit, base, 0).unwrap_err(),
+                RawProgramError::InvalidBaseAddress {
+                    base,
+                    len: exit.len()
+                }
+            );
+        }
+        for entry_offset in [2, (exit.len() * WORD_SIZE) as u32] {
+            assert_eq!(
+                load_raw(&exit, 0x2000, entry_offset).unwrap_err(),
+                RawProgramError::InvalidEntryOffset(entry_offset)
+            );
+        }
+
+        // Branches one instruction past either end of the program.
+        let words = encode(
+            ProgramBuilder::new()
+                .label("before")
+                .nop()
+                .beq(0, 0, "before")
+                .ecall_exit(0)
+                .build()
+                .unwrap(),
+        );
+        assert_eq!(
+            load_raw(&words[1..], 0x2000, 0).unwrap_err(),
+            RawProgramError::TargetOutOfBounds {
+                index: 0,
+                opcode: BuiltinOpcode::BEQ,
+                target: 0x2000 - 4,
+            }
+        );
+        let words = encode(
+            ProgramBuilder::new()
+                .jal(1, "after")
+                .ecall_exit(0)
+                .label("after")
+                .nop()
+                .build()
+                .unwrap(),
+        );
+        let len = words.len() - 1;
+        assert_eq!(
+            load_raw(&words[..len], 0x2000, 0).unwrap_err(),
+            RawProgramError::TargetOutOfBounds {
+                index: 0,
+                opcode: BuiltinOpcode::JAL,
+                target: 0x2000 + (len * WORD_SIZE) as u32,
+            }
+        );
+    }
+}
@@ -0,0 +1,458 @@
+//! Programmatic construction of [`BasicBlock`] programs, mainly for tests.
+//!
+//! Control flow refers to labels instead of offsets, which are computed when the program is built. Instructions
+//! are split into basic blocks the way the decoder splits them, after every branch or jump, and additionally at
+//! every label, so that each branch target starts a block.
+//!
+//! ```rust
+//! use nexus_vm::riscv::builder::ProgramBuilder;
+//!
+//! // Counts x1 up to 10.
+//! let program = ProgramBuilder::new()
+//!     .addi(2, 0, 10)
+//!     .label("loop")
+//!     .addi(1, 1, 1)
+//!     .blt(1, 2, "loop")
+//!     .ecall_exit(0)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(program.len(), 3);
+//! ```
+//!
+//! Operands are given in the order of [`Instruction::new_ir`]. Register indices and immediates are validated when
+//! the program is built, the first invalid operand is reported.
+
+use std::collections::{HashMap, HashSet};
+
+use nexus_common::constants::WORD_SIZE;
+use thiserror::Error;
+
+use super::{BasicBlock, BuiltinOpcode, Instruction, Opcode};
+use crate::SyscallCode;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum BuildError {
+    #[error("label {0:?} is not defined")]
+    UndefinedLabel(String),
+
+    #[error("label {0:?} is defined more than once")]
+    DuplicateLabel(String),
+
+    #[error("x{0} is not a register")]
+    InvalidRegister(u8),
+
+    #[error("immediate {imm} is out of range for {opcode}")]
+    ImmediateOutOfRange { opcode: BuiltinOpcode, imm: i64 },
+}
+
+/// An instruction, or a control flow instruction waiting for the offset of its label.
+#[derive(Debug, Clone)]
+enum Item {
+    Instruction(Instruction),
+    Branch {
+        opcode: BuiltinOpcode,
+        rs1: u8,
+        rs2: u8,
+        label: String,
+    },
+    Jal {
+        rd: u8,
+        label: String,
+    },
+}
+
+/// Builds a program instruction by instruction, see the [module documentation](self).
+#[derive(Debug, Clone, Default)]
+pub struct ProgramBuilder {
+    items: Vec<Item>,
+    labels: HashMap<String, usize>,
+    error: Option<BuildError>,
+}
+
+macro_rules! r_type_instructions {
+    ($($name:ident => $opcode:ident),+ $(,)?) => {
+        $(
+            #[doc = concat!("Appends `", stringify!($name), " rd, rs1, rs2`.")]
+            pub fn $name(self, rd: u8, rs1: u8, rs2: u8) -> Self {
+                self.registers(&[rd, rs1, rs2])
+                    .emit(BuiltinOpcode::$opcode, rd, rs1, rs2 as u32)
+            }
+        )+
+    };
+}
+
+macro_rules! i_type_instructions {
+    ($($name:ident => $opcode:ident),+ $(,)?) => {
+        $(
+            #[doc = concat!("Appends `", stringify!($name), " rd, rs1, imm` with a 12-bit signed `imm`.")]
+            pub fn $name(self, rd: u8, rs1: u8, imm: i32) -> Self {
+                self.registers(&[rd, rs1])
+                    .immediate(BuiltinOpcode::$opcode, imm, -(1 << 11)..=(1 << 11) - 1)
+                    .emit(BuiltinOpcode::$opcode, rd, rs1, imm as u32)
+            }
+        )+
+    };
+}
+
+macro_rules! shift_instructions {
+    ($($name:ident => $opcode:ident),+ $(,)?) => {
+        $(
+            #[doc = concat!("Appends `", stringify!($name), " rd, rs1, shamt`.")]
+            pub fn $name(self, rd: u8, rs1: u8, shamt: u32) -> Self {
+                self.registers(&[rd, rs1])
+                    .immediate(BuiltinOpcode::$opcode, shamt as i32, 0..=31)
+                    .emit(BuiltinOpcode::$opcode, rd, rs1, shamt)
+            }
+        )+
+    };
+}
+
+macro_rules! store_instructions {
+    ($($name:ident => $opcode:ident),+ $(,)?) => {
+        $(
+            #[doc = concat!("Appends `", stringify!($name), "`, storing `rs2` at `rs1 + offset`.")]
+            pub fn $name(self, rs1: u8, rs2: u8, offset: i32) -> Self {
+                self.registers(&[rs1, rs2])
+                    .immediate(BuiltinOpcode::$opcode, offset, -(1 << 11)..=(1 << 11) - 1)
+                    .emit(BuiltinOpcode::$opcode, rs1, rs2, offset as u32)
+            }
+        )+
+    };
+}
+
+macro_rules! branch_instructions {
+    ($($name:ident => $opcode:ident),+ $(,)?) => {
+        $(
+            #[doc = concat!("Appends `", stringify!($name), " rs1, rs2, label`.")]
+            pub fn $name(mut self, rs1: u8, rs2: u8, label: &str) -> Self {
+                self = self.registers(&[rs1, rs2]);
+                self.items.push(Item::Branch {
+                    opcode: BuiltinOpcode::$opcode,
+                    rs1,
+                    rs2,
+                    label: label.to_string(),
+                });
+                self
+            }
+        )+
+    };
+}
+
+macro_rules! u_type_instructions {
+    ($($name:ident => $opcode:ident),+ $(,)?) => {
+        $(
+            #[doc = concat!("Appends `", stringify!($name), " rd, imm` with a 20-bit `imm`.")]
+            pub fn $name(self, rd: u8, imm: u32) -> Self {
+                self.registers(&[rd])
+                    .immediate(BuiltinOpcode::$opcode, imm as i64, 0..=(1 << 20) - 1)
+                    .emit(BuiltinOpcode::$opcode, rd, 0, imm)
+            }
+        )+
+    };
+}
+
+impl ProgramBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Labels the next instruction, which starts a new basic block.
+    pub fn label(mut self, name: &str) -> Self {
+        if self
+            .labels
+            .insert(name.to_string(), self.items.len())
+            .is_some()
+        {
+            self.fail(BuildError::DuplicateLabel(name.to_string()));
+        }
+        self
+    }
+
+    /// Appends `instruction` as is.
+    pub fn instruction(mut self, instruction: Instruction) -> Self {
+        self.items.push(Item::Instruction(instruction));
+        self
+    }
+
+    r_type_instructions! {
+        add => ADD, sub => SUB, sll => SLL, slt => SLT, sltu => SLTU,
+        xor => XOR, srl => SRL, sra => SRA, or => OR, and => AND,
+        mul => MUL, mulh => MULH, mulhsu => MULHSU, mulhu => MULHU,
+        div => DIV, divu => DIVU, rem => REM, remu => REMU,
+    }
+
+    i_type_instructions! {
+        addi => ADDI, slti => SLTI, sltiu => SLTIU, xori => XORI, ori => ORI, andi => ANDI,
+        lb => LB, lh => LH, lw => LW, lbu => LBU, lhu => LHU, jalr => JALR,
+    }
+
+    shift_instructions! { slli => SLLI, srli => SRLI, srai => SRAI }
+
+    store_instructions! { sb => SB, sh => SH, sw => SW }
+
+    branch_instructions! {
+        beq => BEQ, bne => BNE, blt => BLT, bge => BGE, bltu => BLTU, bgeu => BGEU,
+    }
+
+    u_type_instructions! { lui => LUI, auipc => AUIPC }
+
+    /// Appends `jal rd, label`.
+    pub fn jal(mut self, rd: u8, label: &str) -> Self {
+        self = self.registers(&[rd]);
+        self.items.push(Item::Jal {
+            rd,
+            label: label.to_string(),
+        });
+        self
+    }
+
+    /// Sets `rd` to `value`, with `addi` alone if it fits, otherwise with `lui` and `addi`.
+    pub fn li(self, rd: u8, value: u32) -> Self {
+        let signed = value as i32;
+        if (-(1 << 11)..(1 << 11)).contains(&signed) {
+            return self.addi(rd, 0, signed);
+        }
+        // addi sign-extends its immediate, the upper part compensates for it.
+        let upper = value.wrapping_add(0x800) >> 12;
+        let lower = ((value & 0xfff) as i32) << 20 >> 20;
+        self.lui(rd, upper).addi(rd, rd, lower)
+    }
+
+    pub fn nop(self) -> Self {
+        self.instruction(Instruction::nop())
+    }
+
+    pub fn unimpl(self) -> Self {
+        self.instruction(Instruction::unimpl())
+    }
+
+    pub fn ecall(self) -> Self {
+        self.emit(BuiltinOpcode::ECALL, 0, 0, 0)
+    }
+
+    /// Exits with `code` through the exit syscall, overwriting `a0` and `a7`.
+    pub fn ecall_exit(self, code: u32) -> Self {
+        self.li(10, code)
+            .addi(17, 0, SyscallCode::Exit as i32)
+            .ecall()
+    }
+
+    /// Resolves labels and splits the instructions into basic blocks.
+    pub fn build(self) -> Result<Vec<BasicBlock>, BuildError> {
+        let Self {
+            items,
+            labels,
+            error,
+        } = self;
+        if let Some(err) = error {
+            return Err(err);
+        }
+        let offset = |idx: usize, label: &str| -> Result<i64, BuildError> {
+            let target = labels
+                .get(label)
+                .ok_or_else(|| BuildError::UndefinedLabel(label.to_string()))?;
+            Ok((*target as i64 - idx as i64) * WORD_SIZE as i64)
+        };
+        let in_range = |opcode, offset: i64, bits: u32| {
+            let bound = 1i64 << (bits - 1);
+            if (-bound..bound).contains(&offset) {
+                Ok(offset as i32 as u32)
+            } else {
+                Err(BuildError::ImmediateOutOfRange {
+                    opcode,
+                    imm: offset,
+                })
+            }
+        };
+
+        let targets: HashSet<usize> = labels.values().copied().collect();
+        let mut blocks = Vec::new();
+        let mut block = Vec::new();
+        for (idx, item) in items.into_iter().enumerate() {
+            if targets.contains(&idx) && !block.is_empty() {
+                blocks.push(BasicBlock::new(std::mem::take(&mut block)));
+            }
+            let instruction = match item {
+                Item::Instruction(instruction) => instruction,
+                Item::Branch {
+                    opcode,
+                    rs1,
+                    rs2,
+                    label,
+                } => {
+                    let offset = in_range(opcode, offset(idx, &label)?, 13)?;
+                    Instruction::new_ir(Opcode::from(opcode), rs1, rs2, offset)
+                }
+                Item::Jal { rd, label } => {
+                    let offset = in_range(BuiltinOpcode::JAL, offset(idx, &label)?, 21)?;
+                    Instruction::new_ir(Opcode::from(BuiltinOpcode::JAL), rd, 0, offset)
+                }
+            };
+            let ends_block = instruction.is_branch_or_jump_instruction();
+            block.push(instruction);
+            if ends_block {
+                blocks.push(BasicBlock::new(std::mem::take(&mut block)));
+            }
+        }
+        if !block.is_empty() {
+            blocks.push(BasicBlock::new(block));
+        }
+        Ok(blocks)
+    }
+
+    /// Records the first error, which is returned by [`Self::build`].
+    fn fail(&mut self, err: BuildError) {
+        self.error.get_or_insert(err);
+    }
+
+    /// Appends an instruction whose operands passed validation, nothing is appended after an error, as
+    /// [`Instruction::new_ir`] asserts valid operands.
+    fn emit(mut self, opcode: BuiltinOpcode, op_a: u8, op_b: u8, op_c: u32) -> Self {
+        if self.error.is_none() {
+            self.items.push(Item::Instruction(Instruction::new_ir(
+                Opcode::from(opcode),
+                op_a,
+                op_b,
+                op_c,
+            )));
+        }
+        self
+    }
+
+    fn registers(mut self, registers: &[u8]) -> Self {
+        if let Some(&register) = registers.iter().find(|&&register| register > 31) {
+            self.fail(BuildError::InvalidRegister(register));
+        }
+        self
+    }
+
+    fn immediate(
+        mut self,
+        opcode: BuiltinOpcode,
+        imm: impl Into<i64>,
+        range: std::ops::RangeInclusive<i64>,
+    ) -> Self {
+        let imm = imm.into();
+        if !range.contains(&imm) {
+            self.fail(BuildError::ImmediateOutOfRange { opcode, imm });
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{emulator::TerminationCause, trace::k_trace_direct};
+
+    #[test]
+    fn test_backward_branch() {
+        let program = ProgramBuilder::new()
+            .addi(2, 0, 10)
+            .label("loop")
+            .addi(1, 1, 1)
+            .blt(1, 2, "loop")
+            .ecall_exit(0)
+            .build()
+            .unwrap();
+
+        assert_eq!(program.len(), 3);
+        assert_eq!(program[1].len(), 2);
+        assert_eq!(program[1][1].op_c as i32, -4);
+
+        let (view, trace) = k_trace_direct(&program, 1).unwrap();
+        assert_eq!(
+            view.view_termination_cause(),
+            Some(TerminationCause::Exit(0))
+        );
+        // addi, ten iterations of the loop, then the exit sequence.
+        assert_eq!(trace.blocks.len(), 1 + 2 * 10 + 3);
+    }
+
+    #[test]
+    fn test_forward_branch() {
+        let program = ProgramBuilder::new()
+            .addi(1, 0, 5)
+            .beq(1, 0, "fail")
+            .bne(1, 0, "done")
+            .label("fail")
+            .unimpl()
+            .label("done")
+            .ecall_exit(7)
+            .build()
+            .unwrap();
+
+        let starts: Vec<_> = program.iter().map(|block| block[0].clone()).collect();
+        assert_eq!(
+            starts,
+            [
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 5),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::BNE), 1, 0, 8),
+                Instruction::unimpl(),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 7),
+            ]
+        );
+        assert_eq!(program[0][1].op_c, 8);
+
+        let (view, _) = k_trace_direct(&program, 1).unwrap();
+        assert_eq!(
+            view.view_termination_cause(),
+            Some(TerminationCause::Exit(7))
+        );
+    }
+
+    #[test]
+    fn test_jal_and_li() {
+        let program = ProgramBuilder::new()
+            .jal(1, "function")
+            .ecall_exit(0x12345678)
+            .label("function")
+            .li(5, 0xfffff800)
+            .jalr(0, 1, 0)
+            .build()
+            .unwrap();
+
+        let (view, trace) = k_trace_direct(&program, 1).unwrap();
+        assert_eq!(
+            view.view_termination_cause(),
+            Some(TerminationCause::Exit(0x12345678))
+        );
+        let li_step = &trace.blocks[1].steps[0];
+        assert_eq!(li_step.result, Some(0xfffff800));
+    }
+
+    #[test]
+    fn test_undefined_label() {
+        let result = ProgramBuilder::new().beq(0, 0, "nowhere").build();
+        assert_eq!(
+            result,
+            Err(BuildError::UndefinedLabel("nowhere".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_invalid_operands() {
+        assert_eq!(
+            ProgramBuilder::new().add(1, 32, 2).build(),
+            Err(BuildError::InvalidRegister(32))
+        );
+        assert_eq!(
+            ProgramBuilder::new().addi(1, 0, 2048).build(),
+            Err(BuildError::ImmediateOutOfRange {
+                opcode: BuiltinOpcode::ADDI,
+                imm: 2048
+            })
+        );
+        assert_eq!(
+            ProgramBuilder::new().slli(1, 1, 32).build(),
+            Err(BuildError::ImmediateOutOfRange {
+                opcode: BuiltinOpcode::SLLI,
+                imm: 32
+            })
+        );
+        assert_eq!(
+            ProgramBuilder::new().label("a").nop().label("a").build(),
+            Err(BuildError::DuplicateLabel("a".to_string()))
+        );
+    }
+}
@@ -0,0 +1,450 @@
+//! Control-flow graph of a program, for visualizing the control flow the emulator sees.
+//!
+//! Nodes are runs of instructions entered only at their first instruction: basic blocks, split where a branch or
+//! jump lands inside of them. Edges follow the instruction ending each node, [`ControlFlowGraph::to_dot`] renders
+//! the graph with Graphviz.
+//!
+//! ```rust
+//! use nexus_vm::riscv::{builder::ProgramBuilder, cfg};
+//!
+//! let blocks = ProgramBuilder::new()
+//!     .li(1, 3)
+//!     .label("loop")
+//!     .addi(1, 1, -1)
+//!     .bne(1, 0, "loop")
+//!     .build()
+//!     .unwrap();
+//! let graph = cfg(&blocks);
+//! assert_eq!(graph.nodes.len(), 2);
+//! println!("{}", graph.to_dot());
+//! ```
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
+
+use nexus_common::{constants::ELF_TEXT_START, cpu::PC};
+
+use super::{BasicBlock, BuiltinOpcode, Instruction, Register};
+use crate::{trace::Trace, WORD_SIZE};
+
+/// A run of consecutive instructions, entered only at the first one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CfgNode {
+    /// Address of the first instruction.
+    pub start: u32,
+    /// Address past the last instruction.
+    pub end: u32,
+}
+
+impl CfgNode {
+    pub fn num_instructions(&self) -> usize {
+        (self.end - self.start) as usize / WORD_SIZE
+    }
+
+    /// Address of the instruction ending the node.
+    pub fn last_pc(&self) -> u32 {
+        self.end - WORD_SIZE as u32
+    }
+}
+
+/// The instruction an edge follows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    /// Execution continues with the next instruction, including after a branch that isn't taken and after the return
+    /// of a call.
+    Fallthrough,
+    /// A taken conditional branch.
+    Branch,
+    /// `JAL` without a link.
+    Jump,
+    /// `JAL` with a link.
+    Call,
+    /// `JALR`, whose target isn't known statically.
+    Indirect,
+}
+
+impl EdgeKind {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Fallthrough => "fallthrough",
+            Self::Branch => "branch",
+            Self::Jump => "jump",
+            Self::Call => "call",
+            Self::Indirect => "indirect",
+        }
+    }
+}
+
+/// Destination of an edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CfgTarget {
+    /// The node with the given index.
+    Node(usize),
+    /// The target of an indirect jump, or an address outside of the program.
+    Unknown,
+}
+
+/// An edge of the control-flow graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CfgEdge {
+    /// Index of the source node.
+    pub from: usize,
+    pub to: CfgTarget,
+    pub kind: EdgeKind,
+    /// Number of times the edge was taken, see [`ControlFlowGraph::annotate`].
+    pub count: Option<usize>,
+}
+
+/// Control-flow graph of a program, see the [module documentation](self).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ControlFlowGraph {
+    /// Nodes in address order.
+    pub nodes: Vec<CfgNode>,
+    /// Edges grouped by source node, the edge of the ending instruction before the fallthrough.
+    pub edges: Vec<CfgEdge>,
+}
+
+/// Returns the control-flow graph of basic blocks laid out from [`ELF_TEXT_START`], as built by
+/// [`ProgramBuilder`](super::builder::ProgramBuilder).
+pub fn cfg(blocks: &[BasicBlock]) -> ControlFlowGraph {
+    ControlFlowGraph::new(ELF_TEXT_START, blocks)
+}
+
+impl ControlFlowGraph {
+    /// Builds the graph of consecutive basic blocks, the first of which starts at `base`.
+    pub fn new(base: u32, blocks: &[BasicBlock]) -> Self {
+        let instructions: Vec<(u32, &Instruction)> = blocks
+            .iter()
+            .flat_map(|block| &block.0)
+            .enumerate()
+            .map(|(idx, instruction)| (base + (idx * WORD_SIZE) as u32, instruction))
+            .collect();
+        let program_end = base + (instructions.len() * WORD_SIZE) as u32;
+        let in_program =
+            |pc: u32| (base..program_end).contains(&pc) && (pc - base) as usize % WORD_SIZE == 0;
+
+        // Nodes start at every basic block and at every target of a branch or jump.
+        let mut leaders = BTreeSet::new();
+        let mut block_start = base;
+        for block in blocks.iter().filter(|block| !block.is_empty()) {
+            leaders.insert(block_start);
+            block_start += (block.len() * WORD_SIZE) as u32;
+        }
+        for &(pc, instruction) in &instructions {
+            if let Some((target, _)) = static_target(pc, instruction) {
+                if in_program(target) {
+                    leaders.insert(target);
+                }
+            }
+        }
+
+        let starts: Vec<u32> = leaders.into_iter().collect();
+        let nodes: Vec<CfgNode> = starts
+            .iter()
+            .enumerate()
+            .map(|(idx, &start)| CfgNode {
+                start,
+                end: starts.get(idx + 1).copied().unwrap_or(program_end),
+            })
+            .collect();
+        let node_at: BTreeMap<u32, usize> = starts
+            .iter()
+            .enumerate()
+            .map(|(idx, &start)| (start, idx))
+            .collect();
+        let target_of = |pc: u32| {
+            node_at
+                .get(&pc)
+                .map_or(CfgTarget::Unknown, |&idx| CfgTarget::Node(idx))
+        };
+
+        let mut edges = Vec::new();
+        for (from, node) in nodes.iter().enumerate() {
+            let pc = node.last_pc();
+            let instruction = instructions[((pc - base) as usize) / WORD_SIZE].1;
+            let mut edge = |to, kind| {
+                edges.push(CfgEdge {
+                    from,
+                    to,
+                    kind,
+                    count: None,
+                })
+            };
+            let falls_through = match instruction.opcode.builtin() {
+                Some(BuiltinOpcode::JALR) => {
+                    edge(CfgTarget::Unknown, EdgeKind::Indirect);
+                    instruction.op_a != Register::X0
+                }
+                Some(BuiltinOpcode::UNIMPL) => false,
+                _ => match static_target(pc, instruction) {
+                    Some((target, kind)) => {
+                        edge(target_of(target), kind);
+                        kind != EdgeKind::Jump
+                    }
+                    None => true,
+                },
+            };
+            if falls_through && node.end < program_end {
+                edge(target_of(node.end), EdgeKind::Fallthrough);
+            }
+        }
+
+        Self { nodes, edges }
+    }
+
+    /// Index of the node containing `pc`.
+    pub fn node_of(&self, pc: u32) -> Option<usize> {
+        let idx = self.nodes.partition_point(|node| node.start <= pc);
+        idx.checked_sub(1).filter(|&idx| pc < self.nodes[idx].end)
+    }
+
+    /// Counts how many times each edge was taken in `trace`.
+    ///
+    /// A transfer from the last instruction of a node goes along the edge to the node starting at the next pc, or
+    /// along the indirect edge of the node if there's no such edge. Transfers to addresses outside of the graph, e.g.
+    /// from a trace of another program, aren't counted.
+    pub fn annotate(&mut self, trace: &impl Trace) {
+        let mut counts = vec![0; self.edges.len()];
+        for step in trace.get_blocks_iter().flat_map(|block| &block.steps) {
+            // The fused `ADDI` is the last instruction executed by the step.
+            let pc = step.pc + (step.fused.is_some() as usize * WORD_SIZE) as u32;
+            let Some(from) = self
+                .node_of(pc)
+                .filter(|&idx| self.nodes[idx].last_pc() == pc)
+            else {
+                continue;
+            };
+            let to = self
+                .node_of(step.next_pc)
+                .filter(|&idx| self.nodes[idx].start == step.next_pc);
+            let outgoing = || {
+                self.edges
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, edge)| edge.from == from)
+            };
+            let edge = outgoing()
+                .find(|(_, edge)| to.is_some_and(|to| edge.to == CfgTarget::Node(to)))
+                .or_else(|| outgoing().find(|(_, edge)| edge.kind == EdgeKind::Indirect));
+            if let Some((idx, _)) = edge {
+                counts[idx] += 1;
+            }
+        }
+        for (edge, count) in self.edges.iter_mut().zip(counts) {
+            edge.count = Some(count);
+        }
+    }
+
+    /// Renders the graph in the Graphviz DOT language, with execution counts on the edges if annotated.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        writeln!(dot, "digraph cfg {{").unwrap();
+        writeln!(dot, "  node [shape=box, fontname=\"monospace\"];").unwrap();
+        for (idx, node) in self.nodes.iter().enumerate() {
+            writeln!(
+                dot,
+                "  n{idx} [label=\"{:#010x}..{:#010x}\\n{} instructions\"];",
+                node.start,
+                node.end,
+                node.num_instructions()
+            )
+            .unwrap();
+        }
+        if self.edges.iter().any(|edge| edge.to == CfgTarget::Unknown) {
+            writeln!(dot, "  unknown [label=\"?\", shape=ellipse, style=dashed];").unwrap();
+        }
+        for edge in &self.edges {
+            let to = match edge.to {
+                CfgTarget::Node(idx) => format!("n{idx}"),
+                CfgTarget::Unknown => "unknown".to_string(),
+            };
+            let label = match edge.count {
+                Some(count) => format!("{} ({count})", edge.kind.name()),
+                None => edge.kind.name().to_string(),
+            };
+            let style = if edge.kind == EdgeKind::Fallthrough {
+                ", style=dashed"
+            } else {
+                ""
+            };
+            writeln!(dot, "  n{} -> {to} [label=\"{label}\"{style}];", edge.from).unwrap();
+        }
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+}
+
+/// Target and kind of a branch or `JAL` at `pc`.
+fn static_target(pc: u32, instruction: &Instruction) -> Option<(u32, EdgeKind)> {
+    let mut target = PC { value: pc };
+    let kind = match instruction.opcode.builtin()? {
+        BuiltinOpcode::BEQ
+        | BuiltinOpcode::BNE
+        | BuiltinOpcode::BLT
+        | BuiltinOpcode::BGE
+        | BuiltinOpcode::BLTU
+        | BuiltinOpcode::BGEU => {
+            target.branch(instruction.op_c);
+            EdgeKind::Branch
+        }
+        BuiltinOpcode::JAL => {
+            target.jal(instruction.op_c);
+            if instruction.op_a == Register::X0 {
+                EdgeKind::Jump
+            } else {
+                EdgeKind::Call
+            }
+        }
+        _ => return None,
+    };
+    Some((target.value, kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        riscv::{builder::ProgramBuilder, Opcode},
+        trace::k_trace_direct,
+    };
+
+    /// A loop counting x1 up to 3, followed by an if/else on x1.
+    fn loop_and_branch() -> Vec<BasicBlock> {
+        ProgramBuilder::new()
+            .li(1, 0)
+            .li(2, 3)
+            .label("loop")
+            .addi(1, 1, 1)
+            .bne(1, 2, "loop")
+            .beq(1, 2, "then")
+            .addi(3, 0, 1)
+            .jal(0, "end")
+            .label("then")
+            .addi(3, 0, 2)
+            .label("end")
+            .ecall_exit(0)
+            .build()
+            .unwrap()
+    }
+
+    fn edges(graph: &ControlFlowGraph) -> Vec<(usize, CfgTarget, EdgeKind, Option<usize>)> {
+        graph
+            .edges
+            .iter()
+            .map(|edge| (edge.from, edge.to, edge.kind, edge.count))
+            .collect()
+    }
+
+    #[test]
+    fn test_loop_and_if_else() {
+        use CfgTarget::Node;
+        use EdgeKind::*;
+
+        let graph = cfg(&loop_and_branch());
+        let ranges: Vec<(u32, usize)> = graph
+            .nodes
+            .iter()
+            .map(|node| (node.start - ELF_TEXT_START, node.num_instructions()))
+            .collect();
+        assert_eq!(
+            ranges,
+            [
+                (0x00, 2),
+                (0x08, 2),
+                (0x10, 1),
+                (0x14, 2),
+                (0x1c, 1),
+                (0x20, 3)
+            ]
+        );
+        assert_eq!(
+            edges(&graph),
+            [
+                (0, Node(1), Fallthrough, None),
+                (1, Node(1), Branch, None),
+                (1, Node(2), Fallthrough, None),
+                (2, Node(4), Branch, None),
+                (2, Node(3), Fallthrough, None),
+                (3, Node(5), Jump, None),
+                (4, Node(5), Fallthrough, None),
+            ]
+        );
+        assert_eq!(graph.node_of(ELF_TEXT_START + 0x0c), Some(1));
+        assert_eq!(graph.node_of(ELF_TEXT_START + 0x2c), None);
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph cfg {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert!(dot.contains("  n0 [label=\"0x00001000..0x00001008\\n2 instructions\"];\n"));
+        assert!(dot.contains("  n1 -> n1 [label=\"branch\"];\n"));
+        assert!(dot.contains("  n4 -> n5 [label=\"fallthrough\", style=dashed];\n"));
+        assert!(!dot.contains("unknown"));
+        assert_eq!(dot.matches('{').count(), dot.matches('}').count());
+    }
+
+    #[test]
+    fn test_execution_counts() {
+        use CfgTarget::Node;
+        use EdgeKind::*;
+
+        let blocks = loop_and_branch();
+        let (_, trace) = k_trace_direct(&blocks, 1).unwrap();
+        let mut graph = cfg(&blocks);
+        graph.annotate(&trace);
+        assert_eq!(
+            edges(&graph),
+            [
+                (0, Node(1), Fallthrough, Some(1)),
+                (1, Node(1), Branch, Some(2)),
+                (1, Node(2), Fallthrough, Some(1)),
+                (2, Node(4), Branch, Some(1)),
+                (2, Node(3), Fallthrough, Some(0)),
+                (3, Node(5), Jump, Some(0)),
+                (4, Node(5), Fallthrough, Some(1)),
+            ]
+        );
+        assert!(graph
+            .to_dot()
+            .contains("  n1 -> n1 [label=\"branch (2)\"];\n"));
+    }
+
+    #[test]
+    fn test_jump_into_block_and_indirect() {
+        use CfgTarget::{Node, Unknown};
+        use EdgeKind::*;
+
+        // The call lands in the middle of the block, which the builder doesn't split.
+        let blocks = vec![
+            BasicBlock::new(vec![
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::JAL), 1, 0, 8),
+            ]),
+            BasicBlock::new(vec![
+                Instruction::nop(),
+                Instruction::nop(),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::JALR), 0, 1, 0),
+            ]),
+        ];
+        let graph = cfg(&blocks);
+        let starts: Vec<u32> = graph
+            .nodes
+            .iter()
+            .map(|node| node.start - ELF_TEXT_START)
+            .collect();
+        assert_eq!(starts, [0x00, 0x08, 0x0c]);
+        assert_eq!(
+            edges(&graph),
+            [
+                (0, Node(2), Call, None),
+                (0, Node(1), Fallthrough, None),
+                (1, Node(2), Fallthrough, None),
+                (2, Unknown, Indirect, None),
+            ]
+        );
+        assert!(graph
+            .to_dot()
+            .contains("  n2 -> unknown [label=\"indirect\"];\n"));
+    }
+}
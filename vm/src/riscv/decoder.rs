@@ -235,7 +235,66 @@ pub fn decode_until_end_of_a_block(u32_instructions: &[u32]) -> BasicBlock {
 mod tests {
     use super::*;
     use crate::elf::ElfFile;
-    use nexus_common::constants::WORD_SIZE;
+    use nexus_common::{
+        constants::WORD_SIZE,
+        riscv::opcode::{BuiltinOpcode, OperandKind, OPCODE_TABLE},
+    };
+
+    /// Encodes an instruction for every opcode of the table, and checks that the encoder, the decoder and the
+    /// disassembler all agree with the table.
+    #[test]
+    fn test_opcode_table_round_trip() {
+        for spec in OPCODE_TABLE {
+            // Fences and placeholders are decoded as unimplemented instructions.
+            if matches!(spec.opcode, BuiltinOpcode::FENCE | BuiltinOpcode::UNIMPL) {
+                continue;
+            }
+
+            let [op_a, op_b, op_c] = spec.operands;
+            let op_a = if op_a == OperandKind::Register { 5 } else { 0 };
+            let op_b = if op_b == OperandKind::Register { 6 } else { 0 };
+            let op_c = match op_c {
+                OperandKind::Register => 7,
+                OperandKind::Immediate => 12,
+                OperandKind::Unused => 0,
+            };
+            let instruction = Instruction::new_ir(Opcode::from(spec.opcode), op_a, op_b, op_c);
+            let word = instruction.encode();
+
+            assert_eq!(extract_opcode(word), spec.raw, "{}", spec.mnemonic);
+            if let Some(fn3) = spec.fn3 {
+                assert_eq!(extract_fn3(word), fn3, "{}", spec.mnemonic);
+            }
+            if let Some(fn7) = spec.fn7 {
+                assert_eq!(extract_fn7(word), fn7, "{}", spec.mnemonic);
+            }
+            if let Some(fn12) = spec.fn12 {
+                assert_eq!(extract_i_imm(word), fn12 as u32, "{}", spec.mnemonic);
+            }
+
+            let decoded = decode_instruction(word);
+            assert_eq!(decoded, instruction, "{}", spec.mnemonic);
+
+            let disassembled = decoded.to_string();
+            let mnemonic = disassembled.split_whitespace().next().unwrap();
+            assert_eq!(BuiltinOpcode::from_mnemonic(mnemonic), Some(spec.opcode));
+        }
+    }
+
+    /// RV32 shift immediates are 5 bits wide, setting the 6th bit makes the encoding invalid.
+    #[test]
+    fn test_shift_immediate_cannot_exceed_31() {
+        for opcode in [
+            BuiltinOpcode::SLLI,
+            BuiltinOpcode::SRLI,
+            BuiltinOpcode::SRAI,
+        ] {
+            let instruction = Instruction::new_ir(Opcode::from(opcode), 5, 6, 31);
+            let word = instruction.encode();
+            assert_eq!(decode_instruction(word), instruction);
+            assert_eq!(decode_instruction(word | (1 << 25)), Instruction::unimpl());
+        }
+    }
 
     /// Tests the decoding of instructions from an ELF file
     ///
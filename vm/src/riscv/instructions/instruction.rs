@@ -14,7 +14,7 @@ use crate::riscv::instructions::macros::{
     impl_r_type_instructions, impl_s_type_instructions, impl_systemcall_instructions,
     impl_u_type_instructions, unimplemented_instructions,
 };
-use nexus_common::riscv::instruction::{Instruction, InstructionType};
+use nexus_common::riscv::instruction::Instruction;
 use nexus_common::riscv::opcode::BuiltinOpcode;
 use nexus_common::riscv::register::Register;
 use nexus_common::riscv::Opcode;
@@ -105,23 +105,19 @@ impl InstructionProcessor for InstructionDecoder {
 
     // Implementations for J-type instructions
     fn process_jal(&mut self, dec_insn: JType) -> Self::InstructionResult {
+        let opcode = Opcode::from(BuiltinOpcode::JAL);
+        let ins_type = opcode.ins_type();
         Instruction::new(
-            Opcode::from(BuiltinOpcode::JAL),
+            opcode,
             Register::from(dec_insn.rd as u8),
             Register::X0,
             dec_insn.imm as _,
-            InstructionType::JType,
+            ins_type,
         )
     }
 
     fn process_jalr(&mut self, dec_insn: IType) -> Self::InstructionResult {
-        Instruction::new(
-            Opcode::from(BuiltinOpcode::JALR),
-            Register::from(dec_insn.rd as u8),
-            Register::from(dec_insn.rs1 as u8),
-            dec_insn.imm as _,
-            InstructionType::IType,
-        )
+        Instruction::from_i_type(Opcode::from(BuiltinOpcode::JALR), dec_insn)
     }
 
     unimplemented_instructions! {
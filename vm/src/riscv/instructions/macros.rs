@@ -22,13 +22,9 @@ macro_rules! impl_systemcall_instructions {
     ($($name:ident => $opcode:expr),+ $(,)?) => {
         $(
             fn $name(&mut self) -> Self::InstructionResult {
-                Instruction::new(
-                    $opcode,
-                    Register::X0,
-                    Register::X0,
-                    0,
-                    InstructionType::IType,
-                )
+                let opcode = $opcode;
+                let ins_type = opcode.ins_type();
+                Instruction::new(opcode, Register::X0, Register::X0, 0, ins_type)
             }
         )+
     };
@@ -68,12 +64,14 @@ macro_rules! impl_u_type_instructions {
     ($($name:ident => $opcode:expr),+ $(,)?) => {
         $(
             fn $name(&mut self, dec_insn: UType) -> Self::InstructionResult {
+                let opcode = $opcode;
+                let ins_type = opcode.ins_type();
                 Instruction::new(
-                    $opcode,
+                    opcode,
                     Register::from(dec_insn.rd as u8),
                     Register::X0,
                     ((dec_insn.imm as u32) >> 12) as _,
-                    InstructionType::UType,
+                    ins_type,
                 )
             }
         )+
@@ -1,6 +1,9 @@
+pub mod builder;
+pub(crate) mod cfg;
 pub(crate) mod decoder;
 pub(crate) mod instructions;
 
+pub use cfg::{cfg, CfgEdge, CfgNode, CfgTarget, ControlFlowGraph, EdgeKind};
 pub use decoder::{decode_instruction, decode_instructions, decode_until_end_of_a_block};
 pub use instructions::{
     BasicBlock, BasicBlockProgram, BuiltinOpcode, Instruction, InstructionType, Opcode,
@@ -0,0 +1,92 @@
+//! Host-provided hints.
+//!
+//! A hint is a host function that computes advice for the guest, e.g. the integer square root of a number. The
+//! guest requests it with the `Hint` syscall, passing the id of the hint together with an input buffer, and reads
+//! the output back word by word with the `ReadHint` syscall.
+//!
+//! Hint outputs are untrusted: they reach the guest as register values that the prover is free to choose, so the
+//! guest must check the advice with its own instructions before relying on it, e.g. by squaring the returned root.
+//! Hints are run in both emulator passes and must be deterministic. Hints that depend on host state, e.g. the time
+//! of day, are registered with [`HintRegistry::register_with_determinism`] so that
+//! [`assert_deterministic`](crate::audit::assert_deterministic) is able to refuse them.
+
+use std::{collections::HashMap, fmt, sync::Arc};
+
+/// A hint function, mapping input bytes provided by the guest to output bytes.
+///
+/// Hints are shared across threads together with the emulator, e.g. when traces are generated in parallel.
+pub type HintFn = Arc<dyn Fn(&[u8]) -> Vec<u8> + Send + Sync>;
+
+/// Hint functions available to the guest, keyed by their id.
+#[derive(Clone, Default)]
+pub struct HintRegistry {
+    // Hint functions together with whether they are deterministic.
+    hints: HashMap<u32, (HintFn, bool)>,
+}
+
+impl HintRegistry {
+    /// Registers the deterministic `hint` under `id`, replacing the hint previously registered under it.
+    pub fn register(
+        &mut self,
+        id: u32,
+        hint: impl Fn(&[u8]) -> Vec<u8> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.register_with_determinism(id, true, hint)
+    }
+
+    /// Registers `hint` under `id`, replacing the hint previously registered under it.
+    ///
+    /// `deterministic` tells whether the output of the hint only depends on its input.
+    pub fn register_with_determinism(
+        &mut self,
+        id: u32,
+        deterministic: bool,
+        hint: impl Fn(&[u8]) -> Vec<u8> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.hints.insert(id, (Arc::new(hint), deterministic));
+        self
+    }
+
+    /// Returns the hint registered under `id`.
+    pub fn get(&self, id: u32) -> Option<&HintFn> {
+        self.hints.get(&id).map(|(hint, _)| hint)
+    }
+
+    /// Returns the ids of the hints registered as non-deterministic, in ascending order.
+    pub fn nondeterministic_ids(&self) -> Vec<u32> {
+        let mut ids: Vec<_> = self
+            .hints
+            .iter()
+            .filter(|(_, (_, deterministic))| !deterministic)
+            .map(|(&id, _)| id)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+}
+
+impl fmt::Debug for HintRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut ids: Vec<_> = self.hints.keys().collect();
+        ids.sort_unstable();
+        f.debug_struct("HintRegistry").field("ids", &ids).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_is_shared_across_threads() {
+        let mut hints = HintRegistry::default();
+        hints.register(1, |input| input.iter().rev().copied().collect());
+
+        let output = std::thread::scope(|s| {
+            s.spawn(|| hints.get(1).map(|hint| hint(&[1, 2, 3])))
+                .join()
+                .unwrap()
+        });
+        assert_eq!(output, Some(vec![3, 2, 1]));
+    }
+}
@@ -1,3 +1,5 @@
+mod hint;
 mod syscall;
 
-pub use syscall::{SyscallCode, SyscallInstruction};
+pub use hint::{HintFn, HintRegistry};
+pub use syscall::{SyscallCode, SyscallInstruction, PANIC_FD};
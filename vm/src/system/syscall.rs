@@ -3,8 +3,8 @@
 //! This module provides the infrastructure for handling system calls in the RISC-V emulator.
 //! It defines:
 //!
-//! - `SyscallCode`: An enumeration of supported system call codes, including both standard
-//!   RISC-V syscalls and custom zkVM-specific syscalls.
+//! - `SyscallCode`: The system call codes of the guest ABI, including both standard RISC-V
+//!   syscalls and custom zkVM-specific syscalls, see [`nexus_common::ecall`].
 //!
 //! - `SyscallInstruction`: A struct representing a syscall instruction, including its code,
 //!   arguments, and result.
@@ -13,12 +13,16 @@
 //!
 //! 1. Decoding syscall instructions from CPU state.
 //! 2. Executing various syscalls, such as:
-//!    - Write: Output data to a file descriptor (currently only supports stdout).
+//!    - Write: Output data to a file descriptor, stdout or the panic payload [`PANIC_FD`].
 //!    - Exit: Terminate the program with a specified error code.
 //!    - CycleCount: Profile function execution time.
 //!    - ReadFromPrivateInput: Read data from a private input tape.
+//!    - ReadPrivateInputWord: Read a word of the private input tape into memory, one byte per cycle.
 //!    - OverwriteStackPointer: Modify the stack pointer based on memory layout.
 //!    - OverwriteHeapPointer: Modify the heap pointer based on memory layout.
+//!    - Commit: Append a part of the public output segment to the committed public output.
+//!    - Hint, ReadHint: Run a host hint function and read back its output, see [`HintRegistry`](super::HintRegistry).
+//!    - ReadCycleCounter: Return the clock of the current step, which the prover commits to.
 //! 3. Handling memory interactions for syscalls.
 //! 4. Writing back results to CPU registers.
 //!
@@ -28,74 +32,30 @@
 //! system.
 use std::collections::{hash_map, HashSet, VecDeque};
 
-use nexus_common::cpu::Registers;
+pub use nexus_common::ecall::Ecall as SyscallCode;
+use nexus_common::{constants::WORD_SIZE, cpu::Registers, error::MemoryError};
 
 use crate::{
     cpu::Cpu,
     emulator::{memory_stats::MemoryStats, Executor, LinearMemoryLayout},
     error::{Result, VMErrorKind},
-    memory::{LoadOp, MemoryProcessor, StoreOp},
+    memory::{LoadOp, MemAccessSize, MemoryProcessor, StoreOp},
     riscv::{BuiltinOpcode, Instruction, Register},
 };
 
-pub enum SyscallCode {
-    // Syscall code defines opcodes start from 0x200
-    Write = 0x200, // Is converted to NOP for tracing
-    Exit = 0x201,
-    // zkVM specific syscall opcodes start from 0x400
-    ReadFromPrivateInput = 0x400,
-    CycleCount = 0x401, // Is converted to NOP for tracing
-    OverwriteStackPointer = 0x402,
-    OverwriteHeapPointer = 0x403,
-    ReadFromAuxiliaryInput = 0x404,
-    MemoryAdvise = 0x405, // Is converted to NOP for tracing
-}
-
-impl SyscallCode {
-    fn try_from(value: u32, pc: u32) -> Result<Self> {
-        let code = match value {
-            0x200 => SyscallCode::Write,
-            0x201 => SyscallCode::Exit,
-            0x400 => SyscallCode::ReadFromPrivateInput,
-            0x401 => SyscallCode::CycleCount,
-            0x402 => SyscallCode::OverwriteStackPointer,
-            0x403 => SyscallCode::OverwriteHeapPointer,
-            //0x404 => SyscallCode::ReadFromAuxiliaryInput,
-            0x405 => SyscallCode::MemoryAdvise,
-            _ => return Err(VMErrorKind::UnimplementedSyscall(value, pc))?,
-        };
-        Ok(code)
-    }
-}
+/// File descriptor the runtime panic handler writes the panic payload to, see
+/// [`PanicInfo`](crate::emulator::PanicInfo).
+pub const PANIC_FD: u32 = 3;
 
-impl From<u32> for SyscallCode {
-    fn from(value: u32) -> Self {
-        match value {
-            0x200 => SyscallCode::Write,
-            0x201 => SyscallCode::Exit,
-            0x400 => SyscallCode::ReadFromPrivateInput,
-            0x401 => SyscallCode::CycleCount,
-            0x402 => SyscallCode::OverwriteStackPointer,
-            0x403 => SyscallCode::OverwriteHeapPointer,
-            0x404 => SyscallCode::ReadFromAuxiliaryInput,
-            0x405 => SyscallCode::MemoryAdvise,
-            _ => panic!("Invalid syscall code"),
-        }
-    }
-}
-
-impl From<SyscallCode> for u32 {
-    fn from(val: SyscallCode) -> Self {
-        match val {
-            SyscallCode::Write => 0x200,
-            SyscallCode::Exit => 0x201,
-            SyscallCode::ReadFromPrivateInput => 0x400,
-            SyscallCode::CycleCount => 0x401,
-            SyscallCode::OverwriteStackPointer => 0x402,
-            SyscallCode::OverwriteHeapPointer => 0x403,
-            SyscallCode::ReadFromAuxiliaryInput => 0x404,
-            SyscallCode::MemoryAdvise => 0x405,
+/// Decodes the number of the syscall in `a7`, for the ecall at `pc`.
+///
+/// [`SyscallCode::ReadFromAuxiliaryInput`] is reserved by the ABI but not implemented.
+fn decode_syscall_code(value: u32, pc: u32) -> Result<SyscallCode> {
+    match SyscallCode::try_from(value) {
+        Ok(SyscallCode::ReadFromAuxiliaryInput) | Err(_) => {
+            Err(VMErrorKind::UnimplementedSyscall(value, pc))?
         }
+        Ok(code) => Ok(code),
     }
 }
 
@@ -121,6 +81,11 @@ pub struct SyscallInstruction {
     /// These correspond to registers X10 through X16 ("a0" through "a6").
     /// The number and meaning of arguments depend on the specific system call.
     args: Vec<u32>,
+
+    /// Bytes the system call writes to memory, as (address, value) pairs.
+    ///
+    /// They are written by `memory_write`, each one a separate memory access.
+    writes: Vec<(u32, u8)>,
 }
 
 impl SyscallInstruction {
@@ -132,7 +97,7 @@ impl SyscallInstruction {
             ))?;
         }
         Ok(Self {
-            code: SyscallCode::try_from(cpu.registers[Register::X17], cpu.pc.value)?,
+            code: decode_syscall_code(cpu.registers[Register::X17], cpu.pc.value)?,
             result: Some((Register::X10, u32::MAX)),
             args: vec![
                 cpu.registers[Register::X10],
@@ -143,16 +108,18 @@ impl SyscallInstruction {
                 cpu.registers[Register::X15],
                 cpu.registers[Register::X16],
             ],
+            writes: Vec::new(),
         })
     }
 
     /// Executes the write syscall to output data to a file descriptor.
     ///
-    /// This function currently only supports writing to standard output (stdout).
-    /// It reads data from memory and prints it to the console.
+    /// This function supports writing to standard output (stdout), which it prints to the console, and to
+    /// [`PANIC_FD`], which it appends to `panic_output`.
     fn execute_write(
         &mut self,
         logs: &mut Option<Vec<Vec<u8>>>,
+        panic_output: &mut Vec<u8>,
         memory: &impl MemoryProcessor,
         fd: u32,
         buf_addr: u32,
@@ -168,6 +135,9 @@ impl SyscallInstruction {
                 print!("{}", String::from_utf8_lossy(&buffer));
             }
 
+            self.result = Some((Register::X10, count));
+        } else if fd == PANIC_FD {
+            panic_output.extend(memory.read_bytes(buf_addr, count as _)?);
             self.result = Some((Register::X10, count));
         } else {
             // Return -1
@@ -257,6 +227,30 @@ impl SyscallInstruction {
         Ok(())
     }
 
+    /// Executes the read private input word syscall, reading up to a word of the private input tape into the
+    /// word-aligned `buf`, zero-padded past the end of the tape. Returns the number of bytes read.
+    ///
+    /// The four bytes are written one at a time, the syscall takes a cycle per byte so that each write gets its
+    /// own sub-timestamp, see [`OpcodeCost`](nexus_common::cpu::OpcodeCost).
+    fn execute_read_private_input_word(
+        &mut self,
+        private_input_tape: &mut VecDeque<u8>,
+        buf: u32,
+    ) -> Result<()> {
+        if !MemAccessSize::Word.is_aligned(buf) {
+            return Err(MemoryError::UnalignedMemoryWrite(buf))?;
+        }
+        let len = private_input_tape.len().min(WORD_SIZE);
+        let mut word = [0u8; WORD_SIZE];
+        for (byte, value) in word.iter_mut().zip(private_input_tape.drain(..len)) {
+            *byte = value;
+        }
+
+        self.writes = (buf..).zip(word).collect();
+        self.result = Some((Register::X10, len as u32));
+        Ok(())
+    }
+
     fn execute_overwrite_stack_pointer(
         &mut self,
         memory_layout: Option<LinearMemoryLayout>,
@@ -293,6 +287,82 @@ impl SyscallInstruction {
         Ok(())
     }
 
+    /// Executes the commit syscall, appending `len` bytes of the public output segment starting at `offset`
+    /// to the committed public output.
+    ///
+    /// The committed public output is append-only, so `offset` must be equal to the number of bytes committed
+    /// so far. Once committed, the bytes cannot be overwritten by the guest.
+    fn execute_commit(
+        &mut self,
+        executor: &mut Executor,
+        memory_layout: Option<LinearMemoryLayout>,
+        offset: u32,
+        len: u32,
+    ) -> Result<()> {
+        let committed = executor.committed_output_len.unwrap_or_default();
+        if offset != committed {
+            return Err(VMErrorKind::NonContiguousOutputCommit(offset, committed))?;
+        }
+
+        let end = offset
+            .checked_add(len)
+            .ok_or(VMErrorKind::OutputCommitOutOfBounds(offset, len))?;
+        if let Some(layout) = memory_layout {
+            if end > layout.public_output_end() - layout.public_output_start() {
+                return Err(VMErrorKind::OutputCommitOutOfBounds(offset, len))?;
+            }
+        }
+
+        executor.committed_output_len = Some(end);
+        self.result = None;
+        Ok(())
+    }
+
+    /// Executes the hint syscall, running the hint registered under `hint_id` on `len` bytes of memory at `buf`.
+    ///
+    /// The output replaces the hint tape, its length is returned to the guest.
+    fn execute_hint(
+        &mut self,
+        executor: &mut Executor,
+        memory: &impl MemoryProcessor,
+        hint_id: u32,
+        buf: u32,
+        len: u32,
+    ) -> Result<()> {
+        let hint = executor
+            .hints
+            .get(hint_id)
+            .ok_or(VMErrorKind::UnknownHint(hint_id))?;
+        let input = memory.read_bytes(buf, len as _)?;
+        let output = hint(&input);
+
+        self.result = Some((Register::X10, output.len() as u32));
+        executor.hint_tape = output.into();
+        Ok(())
+    }
+
+    /// Executes the read hint syscall, returning the next word of the hint tape, zero-padded past its end.
+    fn execute_read_hint(&mut self, hint_tape: &mut VecDeque<u8>) -> Result<()> {
+        let mut word = [0u8; 4];
+        for (byte, value) in word
+            .iter_mut()
+            .zip(hint_tape.drain(..hint_tape.len().min(4)))
+        {
+            *byte = value;
+        }
+        self.result = Some((Register::X10, u32::from_le_bytes(word)));
+        Ok(())
+    }
+
+    /// Executes the read cycle counter syscall, returning the clock of its own step.
+    ///
+    /// The clock starts at one and advances by the cycles of every executed instruction, it is the clock the prover
+    /// commits in the row of the syscall.
+    fn execute_read_cycle_counter(&mut self, executor: &Executor) -> Result<()> {
+        self.result = Some((Register::X10, executor.global_clock as u32));
+        Ok(())
+    }
+
     // Reads from memory for syscall instruction.
     pub fn memory_read(&mut self, _memory: &impl MemoryProcessor) -> Result<HashSet<LoadOp>> {
         Ok(HashSet::<LoadOp>::new())
@@ -328,7 +398,14 @@ impl SyscallInstruction {
                 let fd = self.args[0];
                 let buf = self.args[1];
                 let count = self.args[2];
-                self.execute_write(&mut executor.logs, memory, fd, buf, count)
+                self.execute_write(
+                    &mut executor.logs,
+                    &mut executor.panic_output,
+                    memory,
+                    fd,
+                    buf,
+                    count,
+                )
             }
 
             SyscallCode::CycleCount => {
@@ -359,6 +436,11 @@ impl SyscallInstruction {
                 self.execute_read_from_private_input(&mut executor.private_input_tape)
             }
 
+            SyscallCode::ReadPrivateInputWord => {
+                let buf = self.args[0];
+                self.execute_read_private_input_word(&mut executor.private_input_tape, buf)
+            }
+
             SyscallCode::OverwriteStackPointer => {
                 self.execute_overwrite_stack_pointer(memory_layout)
             }
@@ -379,12 +461,39 @@ impl SyscallInstruction {
 
                 self.execute_allocate_heap(addr, len, memory_stats)
             }
+
+            SyscallCode::Commit => {
+                // Executed on both passes, both emulators have to agree on the committed output.
+                let offset = self.args[0];
+                let len = self.args[1];
+
+                self.execute_commit(executor, memory_layout, offset, len)
+            }
+
+            SyscallCode::Hint => {
+                // Executed on both passes, the guest observes the output length.
+                let hint_id = self.args[0];
+                let buf = self.args[1];
+                let len = self.args[2];
+
+                self.execute_hint(executor, memory, hint_id, buf, len)
+            }
+
+            SyscallCode::ReadHint => self.execute_read_hint(&mut executor.hint_tape),
+
+            // Executed on both passes, the guest observes the clock.
+            SyscallCode::ReadCycleCounter => self.execute_read_cycle_counter(executor),
         }
     }
 
     // Writes to memory for syscall instructions.
-    pub fn memory_write(&self, _memory: &mut impl MemoryProcessor) -> Result<HashSet<StoreOp>> {
-        Ok(HashSet::<StoreOp>::new())
+    pub fn memory_write(&self, memory: &mut impl MemoryProcessor) -> Result<HashSet<StoreOp>> {
+        let store_ops = self
+            .writes
+            .iter()
+            .map(|&(address, value)| memory.write(address, MemAccessSize::Byte, value as u32))
+            .collect::<std::result::Result<_, _>>()?;
+        Ok(store_ops)
     }
 
     // All the write back to registers is done in the write_back function
@@ -427,6 +536,7 @@ mod tests {
             code: SyscallCode::Write,
             result: Some((Register::X10, 0)),
             args: vec![fd, buf_addr, buf_len as _, 0, 0, 0, 0],
+            writes: Vec::new(),
         };
 
         emulator
@@ -434,7 +544,14 @@ mod tests {
             .write_bytes(buf_addr, buf)
             .expect("Failed to write to memory");
         syscall_instruction
-            .execute_write(&mut None, &emulator.data_memory, fd, buf_addr, buf_len as _)
+            .execute_write(
+                &mut None,
+                &mut Vec::new(),
+                &emulator.data_memory,
+                fd,
+                buf_addr,
+                buf_len as _,
+            )
             .expect("Failed to execute write syscall");
         syscall_instruction.write_back(&mut emulator.executor.cpu);
 
@@ -444,6 +561,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_execute_write_panic_fd() {
+        let payload = b"7\nsrc/main.rs\nvalue 42 is too large";
+        let mut emulator = setup_emulator();
+        emulator
+            .data_memory
+            .write_bytes(0, payload)
+            .expect("Failed to write to memory");
+
+        // The handler may write the payload in several parts.
+        let mut panic_output = Vec::new();
+        let len = payload.len() as u32;
+        for (buf_addr, count) in [(0, 2), (2, len - 2)] {
+            let mut syscall_instruction = SyscallInstruction {
+                code: SyscallCode::Write,
+                result: None,
+                args: vec![PANIC_FD, buf_addr, count, 0, 0, 0, 0],
+                writes: Vec::new(),
+            };
+            syscall_instruction
+                .execute_write(
+                    &mut None,
+                    &mut panic_output,
+                    &emulator.data_memory,
+                    PANIC_FD,
+                    buf_addr,
+                    count,
+                )
+                .expect("Failed to execute write syscall");
+            assert_eq!(syscall_instruction.result, Some((Register::X10, count)));
+        }
+
+        assert_eq!(
+            crate::emulator::PanicInfo::parse(&panic_output),
+            Some(crate::emulator::PanicInfo {
+                message: "value 42 is too large".to_string(),
+                file: "src/main.rs".to_string(),
+                line: 7,
+            })
+        );
+    }
+
     #[test]
     fn test_execute_write_invalid_fd() {
         let fd = 2; // Invalid fd
@@ -455,6 +614,7 @@ mod tests {
             code: SyscallCode::Write,
             result: Some((Register::X10, 0)),
             args: vec![fd, buf_addr, buf_len as _, 0, 0, 0, 0],
+            writes: Vec::new(),
         };
 
         emulator
@@ -462,7 +622,14 @@ mod tests {
             .write_bytes(buf_addr, buf)
             .expect("Failed to write to memory");
         syscall_instruction
-            .execute_write(&mut None, &emulator.data_memory, fd, buf_addr, buf_len as _)
+            .execute_write(
+                &mut None,
+                &mut Vec::new(),
+                &emulator.data_memory,
+                fd,
+                buf_addr,
+                buf_len as _,
+            )
             .expect("Failed to execute write syscall");
         syscall_instruction.write_back(&mut emulator.executor.cpu);
 
@@ -480,6 +647,7 @@ mod tests {
             code: SyscallCode::Exit,
             result: Some((Register::X10, 0)),
             args: vec![error_code, 0, 0, 0, 0, 0, 0],
+            writes: Vec::new(),
         };
 
         let result = syscall_instruction.execute_exit(error_code);
@@ -499,6 +667,7 @@ mod tests {
             code: SyscallCode::OverwriteStackPointer,
             result: Some((Register::X10, 0)),
             args: vec![0, 0, 0, 0, 0, 0, 0],
+            writes: Vec::new(),
         };
 
         let _ = syscall_instruction.execute_overwrite_stack_pointer(Some(memory_layout));
@@ -518,6 +687,7 @@ mod tests {
             code: SyscallCode::OverwriteStackPointer,
             result: Some((Register::X10, 0)),
             args: vec![0, 0, 0, 0, 0, 0, 0],
+            writes: Vec::new(),
         };
 
         let _ = syscall_instruction.execute_overwrite_heap_pointer(Some(memory_layout));
@@ -539,6 +709,7 @@ mod tests {
             code: SyscallCode::CycleCount,
             result: Some((Register::X10, 0)),
             args: vec![buf_addr, buf_len as _, 0, 0, 0, 0, 0],
+            writes: Vec::new(),
         };
 
         emulator
@@ -581,6 +752,46 @@ mod tests {
         assert_eq!(emulator.executor.cycle_tracker["fib"].1, 0);
     }
 
+    #[test]
+    fn test_execute_commit() {
+        let memory_layout = LinearMemoryLayout::default();
+        let mut emulator = setup_emulator();
+        let mut syscall_instruction = SyscallInstruction {
+            code: SyscallCode::Commit,
+            result: Some((Register::X10, 0)),
+            args: vec![0, 8, 0, 0, 0, 0, 0],
+            writes: Vec::new(),
+        };
+
+        syscall_instruction
+            .execute_commit(&mut emulator.executor, None, 0, 8)
+            .expect("Failed to execute commit syscall");
+        syscall_instruction
+            .execute_commit(&mut emulator.executor, None, 8, 4)
+            .expect("Failed to execute commit syscall");
+        assert_eq!(emulator.executor.committed_output_len, Some(12));
+        assert!(syscall_instruction.result.is_none());
+
+        // Commits must extend the committed output.
+        assert_eq!(
+            syscall_instruction
+                .execute_commit(&mut emulator.executor, None, 4, 4)
+                .unwrap_err()
+                .source,
+            VMErrorKind::NonContiguousOutputCommit(4, 12)
+        );
+
+        // Commits must stay within the output segment.
+        let output_len = memory_layout.public_output_end() - memory_layout.public_output_start();
+        assert_eq!(
+            syscall_instruction
+                .execute_commit(&mut emulator.executor, Some(memory_layout), 12, output_len)
+                .unwrap_err()
+                .source,
+            VMErrorKind::OutputCommitOutOfBounds(12, output_len)
+        );
+    }
+
     #[test]
     fn test_syscall_decode() {
         let mut cpu = Cpu::default();
@@ -599,6 +810,25 @@ mod tests {
         assert_eq!(syscall.args[2], 5);
     }
 
+    #[test]
+    fn test_syscall_decode_unknown() {
+        let mut cpu = Cpu::default();
+        cpu.pc.value = 0x1000;
+        let instruction = Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0);
+
+        // Numbers outside of the ABI, and the reserved auxiliary input call.
+        for code in [93, 0x40b, SyscallCode::ReadFromAuxiliaryInput as u32] {
+            cpu.registers.write(Register::X17, code);
+            assert_eq!(
+                SyscallInstruction::decode(&instruction, &cpu)
+                    .err()
+                    .unwrap()
+                    .source,
+                VMErrorKind::UnimplementedSyscall(code, 0x1000)
+            );
+        }
+    }
+
     #[test]
     fn test_execute_read_from_private_input() {
         let mut private_input_tape = VecDeque::from(vec![1, 2, 3]);
@@ -606,6 +836,7 @@ mod tests {
             code: SyscallCode::ReadFromPrivateInput,
             result: Some((Register::X10, 0)),
             args: vec![],
+            writes: Vec::new(),
         };
 
         // Test reading values
@@ -626,4 +857,93 @@ mod tests {
             .result
             .is_some_and(|(reg, value)| { reg == Register::X10 && value == u32::MAX }));
     }
+
+    #[test]
+    fn test_execute_read_private_input_word() {
+        let mut emulator = setup_emulator();
+        let mut private_input_tape = VecDeque::from(vec![1, 2, 3, 4, 5, 6]);
+        let mut syscall_instruction = SyscallInstruction {
+            code: SyscallCode::ReadPrivateInputWord,
+            result: None,
+            args: vec![],
+            writes: Vec::new(),
+        };
+
+        // A full word, then the rest of the tape zero-padded, then nothing.
+        for (expected_len, expected_word) in [(4, 0x04030201), (2, 0x0605), (0, 0)] {
+            syscall_instruction
+                .execute_read_private_input_word(&mut private_input_tape, 8)
+                .expect("Failed to execute read private input word");
+            assert_eq!(
+                syscall_instruction.result,
+                Some((Register::X10, expected_len))
+            );
+
+            // Every byte of the word is written, one access each.
+            let store_ops = syscall_instruction
+                .memory_write(&mut emulator.data_memory)
+                .unwrap();
+            assert_eq!(store_ops.len(), WORD_SIZE);
+            assert!(store_ops
+                .iter()
+                .all(|op| op.get_size() == MemAccessSize::Byte));
+            assert_eq!(
+                emulator
+                    .data_memory
+                    .read(8, MemAccessSize::Word)
+                    .unwrap()
+                    .get_value(),
+                expected_word
+            );
+        }
+
+        // The buffer must be word-aligned.
+        assert_eq!(
+            syscall_instruction
+                .execute_read_private_input_word(&mut private_input_tape, 6)
+                .unwrap_err()
+                .source,
+            VMErrorKind::from(MemoryError::UnalignedMemoryWrite(6))
+        );
+    }
+
+    #[test]
+    fn test_execute_hint() {
+        let mut emulator = setup_emulator();
+        emulator
+            .executor
+            .hints
+            .register(7, |input| input.iter().rev().copied().collect());
+        emulator
+            .data_memory
+            .write_bytes(0, &[1, 2, 3, 4, 5])
+            .expect("Failed to write to memory");
+
+        let mut syscall_instruction = SyscallInstruction {
+            code: SyscallCode::Hint,
+            result: None,
+            args: vec![7, 0, 5, 0, 0, 0, 0],
+            writes: Vec::new(),
+        };
+        syscall_instruction
+            .execute_hint(&mut emulator.executor, &emulator.data_memory, 7, 0, 5)
+            .expect("Failed to execute hint syscall");
+        assert_eq!(syscall_instruction.result, Some((Register::X10, 5)));
+
+        // The output is read word by word, zero-padded past its end.
+        for expected in [u32::from_le_bytes([5, 4, 3, 2]), 1, 0] {
+            syscall_instruction
+                .execute_read_hint(&mut emulator.executor.hint_tape)
+                .expect("Failed to execute read hint syscall");
+            assert_eq!(syscall_instruction.result, Some((Register::X10, expected)));
+        }
+
+        assert_eq!(
+            syscall_instruction
+                .execute_hint(&mut emulator.executor, &emulator.data_memory, 8, 0, 5)
+                .unwrap_err()
+                .source,
+            VMErrorKind::UnknownHint(8)
+        );
+    }
 }
@@ -1,17 +1,30 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+    mem::size_of,
+    ops::Range,
+};
+
+use nexus_common::cpu::{OpcodeCost, Registers};
 use serde::{Deserialize, Serialize};
 
 use crate::{
     cpu::{instructions::InstructionResult, RegisterFile},
+    effects::{self, StateEffect},
     elf::ElfFile,
-    emulator::{Emulator, HarvardEmulator, InternalView, LinearEmulator, LinearMemoryLayout, View},
+    emulator::{
+        Emulator, HarvardEmulator, InternalView, LinearEmulator, LinearMemoryLayout, StackGuard,
+        View,
+    },
     error::{Result, VMError, VMErrorKind},
-    memory::MemoryRecords,
-    riscv::{BasicBlock, Instruction},
+    memory::{MemoryRecord, MemoryRecords},
+    riscv::{decode_instructions, BasicBlock, BuiltinOpcode, Instruction, Register},
+    system::{HintRegistry, SyscallCode},
     WORD_SIZE,
 };
 
 /// A program step.
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Step {
     /// Timestamp of the step.
     pub timestamp: u32,
@@ -27,10 +40,22 @@ pub struct Step {
     pub result: InstructionResult,
     /// Memory records for instruction evaluation.
     pub memory_records: MemoryRecords,
+    /// `ADDI` executed as part of this step, see [`fuse_address_materialization`].
+    #[serde(default)]
+    pub fused: Option<FusedInstruction>,
+}
+
+/// Second instruction of a fused step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FusedInstruction {
+    /// Raw instruction.
+    pub raw_instruction: u32,
+    /// Encoded instruction.
+    pub instruction: Instruction,
 }
 
 /// A sequence of program steps.
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Block {
     /// Starting register file for this block.
     pub regs: RegisterFile,
@@ -38,6 +63,19 @@ pub struct Block {
     pub steps: Vec<Step>,
 }
 
+impl Block {
+    /// Estimate the memory, in bytes, used by this block and the steps it owns.
+    pub fn memory_footprint(&self) -> usize {
+        size_of::<Self>()
+            + self.steps.capacity() * size_of::<Step>()
+            + self
+                .steps
+                .iter()
+                .map(|step| step.memory_records.len() * size_of::<MemoryRecord>())
+                .sum::<usize>()
+    }
+}
+
 pub trait Trace {
     fn get_memory_layout(&self) -> &LinearMemoryLayout;
 
@@ -55,10 +93,109 @@ pub trait Trace {
     }
 
     fn as_blocks_slice(&self) -> &[Block];
+
+    /// Return the index of the basic block that step `row` of this (sub)trace belongs to, if recorded.
+    ///
+    /// Rows count the steps of all blocks in order, as they are laid out in the main trace of the prover.
+    /// The index is not committed to by the proof.
+    fn block_index_of(&self, _row: usize) -> Option<u16> {
+        None
+    }
+
+    /// Return the bytes stored to `range` by the steps of this (sub)trace, in execution order.
+    ///
+    /// Stores of several bytes are split into a byte each, in address order, bytes of a store outside of `range`
+    /// are left out. See [`effects`] for the net effects a verifier can recompute.
+    fn state_effects(&self, range: Range<u32>) -> Vec<StateEffect> {
+        self.get_blocks_iter()
+            .flat_map(|block| &block.steps)
+            .flat_map(|step| &step.memory_records)
+            .flat_map(|record| effects::record_effects(record, &range))
+            .collect()
+    }
+
+    /// Estimate the memory, in bytes, used by this trace, see [`Block::memory_footprint`].
+    fn memory_footprint(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self
+                .get_blocks_iter()
+                .map(Block::memory_footprint)
+                .sum::<usize>()
+    }
+
+    /// Return a compressed copy of this trace keeping the fields of the steps selected by `schema`, e.g. for
+    /// archival, see [`CompressedTrace`].
+    fn compact(&self, schema: CompactionSchema) -> CompressedTrace {
+        let mut compressed = CompressedTrace::with_schema(
+            *self.get_memory_layout(),
+            self.get_start(),
+            schema,
+            self.get_blocks_iter().cloned(),
+        );
+        if schema.registers {
+            let block_indices: Vec<_> = (0..compressed.get_num_steps())
+                .map(|row| self.block_index_of(row))
+                .collect();
+            if block_indices.iter().any(Option::is_some) {
+                compressed.block_indices = block_indices;
+            }
+        }
+        compressed
+    }
+
+    /// Return the number of steps, the range of their timestamps and how often each opcode was executed.
+    ///
+    /// The `ADDI` of a fused step is counted as well, see [`FusedInstruction`].
+    fn summary(&self) -> TraceSummary {
+        let mut summary = TraceSummary::default();
+        for step in self.get_blocks_iter().flat_map(|block| &block.steps) {
+            summary.num_steps += 1;
+            summary.clk_range = Some(match summary.clk_range {
+                Some((first, last)) => (first.min(step.timestamp), last.max(step.timestamp)),
+                None => (step.timestamp, step.timestamp),
+            });
+            let fused = step.fused.iter().map(|fused| &fused.instruction);
+            for instruction in std::iter::once(&step.instruction).chain(fused) {
+                *summary
+                    .opcode_histogram
+                    .entry(instruction.opcode.name().to_string())
+                    .or_default() += 1;
+            }
+        }
+        summary
+    }
+}
+
+/// Human-readable summary of a trace, see [`Trace::summary`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceSummary {
+    /// Number of steps.
+    pub num_steps: usize,
+    /// First and last timestamp of the steps, `None` for an empty trace.
+    pub clk_range: Option<(u32, u32)>,
+    /// Number of executed instructions per opcode mnemonic.
+    pub opcode_histogram: BTreeMap<String, usize>,
+}
+
+/// Lists the opcodes by descending count, ties by mnemonic.
+impl fmt::Display for TraceSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} steps", self.num_steps)?;
+        if let Some((first, last)) = self.clk_range {
+            write!(f, ", clk {first}..={last}")?;
+        }
+        writeln!(f)?;
+        let mut histogram: Vec<_> = self.opcode_histogram.iter().collect();
+        histogram.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+        for (name, count) in histogram {
+            writeln!(f, "  {name:<8} {count}")?;
+        }
+        Ok(())
+    }
 }
 
 /// Represents a program trace over uniform blocks.
-#[derive(Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct UniformTrace {
     /// Memory layout.
     pub memory_layout: LinearMemoryLayout,
@@ -68,6 +205,9 @@ pub struct UniformTrace {
     pub start: usize,
     /// The blocks contained in this trace.
     pub blocks: Vec<Block>,
+    /// Basic-block index of every step, see [`UniformTrace::record_block_indices`]. Empty unless recorded.
+    #[serde(default)]
+    pub block_indices: Vec<Option<u16>>,
 }
 
 impl Trace for UniformTrace {
@@ -90,16 +230,29 @@ impl Trace for UniformTrace {
     fn as_blocks_slice(&self) -> &[Block] {
         self.blocks.as_slice()
     }
+
+    fn block_index_of(&self, row: usize) -> Option<u16> {
+        self.block_indices.get(row).copied().flatten()
+    }
+}
+
+impl fmt::Display for UniformTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "UniformTrace (k = {}): {}", self.k, self.summary())
+    }
 }
 
 impl UniformTrace {
     /// Create a subtrace containing only block `n`.
     pub fn get(&self, n: usize) -> Option<Self> {
+        let block = self.block(n)?.clone();
+        let first_row = (n - self.start) * self.k;
         Some(UniformTrace {
             memory_layout: self.memory_layout,
             k: self.k,
             start: n,
-            blocks: vec![self.block(n)?.clone()],
+            blocks: vec![block],
+            block_indices: self.block_indices_of_rows(first_row, self.k),
         })
     }
 
@@ -115,10 +268,46 @@ impl UniformTrace {
                 k: self.k,
                 start,
                 blocks: bs.to_vec(),
+                block_indices: self.block_indices_of_rows(start * self.k, bs.len() * self.k),
             }
         })
     }
 
+    /// Record the basic block of every step: the index in `basic_blocks` of the block containing its pc, where the
+    /// first block starts at `base` and the others follow it. Steps outside of the blocks, such as padding past the
+    /// end of the program, get no index.
+    ///
+    /// Indices are kept for subtraces, see [`Trace::block_index_of`]. They are not updated by transformations of
+    /// the trace that change its steps, e.g. [`fuse_address_materialization`].
+    pub fn record_block_indices(&mut self, basic_blocks: &[BasicBlock], base: u32) {
+        let ends: Vec<u32> = basic_blocks
+            .iter()
+            .scan(base, |end, block| {
+                *end += (block.0.len() * WORD_SIZE) as u32;
+                Some(*end)
+            })
+            .collect();
+        self.block_indices = self
+            .blocks
+            .iter()
+            .flat_map(|block| &block.steps)
+            .map(|step| {
+                let idx = ends.partition_point(|&end| end <= step.pc);
+                if step.pc < base || idx == ends.len() {
+                    return None;
+                }
+                u16::try_from(idx).ok()
+            })
+            .collect();
+    }
+
+    fn block_indices_of_rows(&self, first_row: usize, num_rows: usize) -> Vec<Option<u16>> {
+        self.block_indices
+            .get(first_row..first_row + num_rows)
+            .map(<[_]>::to_vec)
+            .unwrap_or_default()
+    }
+
     /// Estimate the size, in bytes, of this trace.
     pub fn estimate_size(&self) -> usize {
         use std::mem::size_of_val as sizeof;
@@ -158,6 +347,12 @@ impl Trace for BBTrace {
     }
 }
 
+impl fmt::Display for BBTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BBTrace: {}", self.summary())
+    }
+}
+
 impl BBTrace {
     /// Create a subtrace containing only block `n`.
     pub fn get(&self, n: usize) -> Option<Self> {
@@ -179,6 +374,422 @@ impl BBTrace {
     }
 }
 
+/// Fields of the steps kept by [`Trace::compact`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactionSchema {
+    pub pc: bool,
+    pub timestamp: bool,
+    /// Instruction of every step, together with the `ADDI` of fused steps.
+    pub instruction: bool,
+    pub next_pc: bool,
+    /// Value written to the destination register.
+    pub result: bool,
+    pub memory_records: bool,
+    /// Register file at the start of every block.
+    pub registers: bool,
+}
+
+impl CompactionSchema {
+    /// Program counter, timestamp and instruction of every step.
+    pub const ANALYTICS: Self = Self {
+        pc: true,
+        timestamp: true,
+        instruction: true,
+        next_pc: false,
+        result: false,
+        memory_records: false,
+        registers: false,
+    };
+
+    /// Every field of the steps, including their register and memory effects.
+    pub const REPLAY: Self = Self {
+        next_pc: true,
+        result: true,
+        memory_records: true,
+        ..Self::ANALYTICS
+    };
+
+    /// Everything, the trace can be expanded and proved.
+    pub const FULL: Self = Self {
+        registers: true,
+        ..Self::REPLAY
+    };
+
+    /// Returns whether traces compacted with the schema can be expanded into a trace to prove.
+    pub fn is_provable(&self) -> bool {
+        *self == Self::FULL
+    }
+
+    fn keeps_steps(&self) -> bool {
+        Self {
+            registers: false,
+            ..*self
+        } == Self::REPLAY
+    }
+
+    // Registers after executing `step` from `regs`, zero if the schema doesn't keep registers.
+    fn regs_after(&self, regs: RegisterFile, step: &Step) -> RegisterFile {
+        if self.registers {
+            regs_after(regs, step)
+        } else {
+            RegisterFile::default()
+        }
+    }
+
+    // Clears the fields of `step` not kept by the schema.
+    fn strip(&self, step: &mut Step) {
+        if !self.pc {
+            step.pc = 0;
+        }
+        if !self.timestamp {
+            step.timestamp = 0;
+        }
+        if !self.instruction {
+            step.raw_instruction = 0;
+            step.instruction = Instruction::default();
+            step.fused = None;
+        }
+        if !self.next_pc {
+            step.next_pc = 0;
+        }
+        if !self.result {
+            step.result = None;
+        }
+        if !self.memory_records {
+            step.memory_records.clear();
+        }
+    }
+}
+
+/// A trace stored compactly, keeping the fields of the steps selected by a [`CompactionSchema`].
+///
+/// Loops produce long runs of steps that repeat an earlier execution of the same instruction. Such a step, with
+/// the same pc, next pc and memory records as the first execution at its pc, is stored as a reference to that
+/// execution together with its timestamp and result. Its registers aren't stored either when they follow from the
+/// previous step, i.e. they only differ from the previous registers by the value written back. Every other step
+/// is stored in full. Fields left out by the schema are cleared before comparing steps, so that smaller schemas
+/// repeat more often.
+///
+/// [`CompressedTrace::blocks`] expands the trace back into the original blocks. Traces compressed with
+/// [`CompactionSchema::FULL`] can be expanded by [`CompressedTrace::to_uniform`] into a [`UniformTrace`] that can be
+/// passed to the prover, and by [`CompressedTrace::split_by`] a few blocks at a time, into subtraces implementing
+/// [`Trace`], without holding the whole expanded trace in memory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompressedTrace {
+    /// Memory layout.
+    pub memory_layout: LinearMemoryLayout,
+    /// First block in this (sub)trace.
+    pub start: usize,
+    schema: CompactionSchema,
+    /// One entry per step.
+    entries: Vec<CompressedStep>,
+    /// Number of steps of every block, empty if every block holds a single step.
+    block_lens: Vec<usize>,
+    /// Block index of every step, see [`Trace::block_index_of`], empty if not recorded.
+    block_indices: Vec<Option<u16>>,
+}
+
+impl Default for CompressedTrace {
+    fn default() -> Self {
+        Self {
+            memory_layout: LinearMemoryLayout::default(),
+            start: 0,
+            schema: CompactionSchema::FULL,
+            entries: Vec::new(),
+            block_lens: Vec::new(),
+            block_indices: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum CompressedStep {
+    /// A step stored as is, in a block of its own holding the registers before the step.
+    Full(Box<Block>),
+    /// A step repeating the step of the full entry at index `source`.
+    Repeat {
+        source: u32,
+        timestamp: u32,
+        result: InstructionResult,
+    },
+}
+
+impl CompressedTrace {
+    /// Compress a trace, keeping every field.
+    pub fn new(trace: &UniformTrace) -> Self {
+        let mut compressed = Self::from_blocks(
+            trace.memory_layout,
+            trace.start,
+            trace.blocks.iter().cloned(),
+        );
+        compressed.block_indices = trace.block_indices.clone();
+        compressed
+    }
+
+    /// Compress blocks as they are produced, e.g. while the program is traced, so that the uncompressed trace is
+    /// never held in memory.
+    pub fn from_blocks(
+        memory_layout: LinearMemoryLayout,
+        start: usize,
+        blocks: impl IntoIterator<Item = Block>,
+    ) -> Self {
+        Self::with_schema(memory_layout, start, CompactionSchema::FULL, blocks)
+    }
+
+    /// Compress blocks, keeping the fields selected by `schema`. Empty blocks are left out.
+    pub fn with_schema(
+        memory_layout: LinearMemoryLayout,
+        start: usize,
+        schema: CompactionSchema,
+        blocks: impl IntoIterator<Item = Block>,
+    ) -> Self {
+        let mut source_by_pc: HashMap<u32, u32> = HashMap::new();
+        let mut entries = Vec::new();
+        let mut block_lens = Vec::new();
+        // Registers the expansion assigns to the next step.
+        let mut predicted_regs = None;
+
+        for block in blocks {
+            if block.steps.is_empty() {
+                continue;
+            }
+            block_lens.push(block.steps.len());
+            let block_regs = if schema.registers {
+                block.regs
+            } else {
+                RegisterFile::default()
+            };
+            for (idx, mut step) in block.steps.into_iter().enumerate() {
+                schema.strip(&mut step);
+                // Only the registers at the start of a block are kept, the ones of later steps aren't expanded.
+                let regs = match predicted_regs {
+                    Some(regs) if idx > 0 => regs,
+                    _ => block_regs,
+                };
+                let source = source_by_pc.get(&step.pc).copied().filter(|&source| {
+                    let CompressedStep::Full(source) = &entries[source as usize] else {
+                        unreachable!("repeated steps refer to full entries");
+                    };
+                    repeats(&step, &source.steps[0])
+                });
+
+                let regs_follow = predicted_regs == Some(regs);
+                predicted_regs = Some(schema.regs_after(regs, &step));
+                let entry = match source {
+                    Some(source) if regs_follow => CompressedStep::Repeat {
+                        source,
+                        timestamp: step.timestamp,
+                        result: step.result,
+                    },
+                    _ => {
+                        source_by_pc.entry(step.pc).or_insert(entries.len() as u32);
+                        CompressedStep::Full(Box::new(Block {
+                            regs,
+                            steps: vec![step],
+                        }))
+                    }
+                };
+                entries.push(entry);
+            }
+        }
+        entries.shrink_to_fit();
+        if block_lens.iter().all(|&len| len == 1) {
+            block_lens = Vec::new();
+        }
+        block_lens.shrink_to_fit();
+
+        Self {
+            memory_layout,
+            start,
+            schema,
+            entries,
+            block_lens,
+            block_indices: Vec::new(),
+        }
+    }
+
+    pub fn schema(&self) -> CompactionSchema {
+        self.schema
+    }
+
+    /// Returns whether the trace can be expanded into a trace to prove, see [`CompactionSchema::is_provable`].
+    pub fn is_provable(&self) -> bool {
+        self.schema.is_provable()
+    }
+
+    // Expand the entries, yielding every step together with the registers before it.
+    fn expand_steps(&self) -> impl Iterator<Item = (RegisterFile, Step)> + '_ {
+        let mut regs = RegisterFile::default();
+        self.entries.iter().map(move |entry| {
+            let (block_regs, step) = match entry {
+                CompressedStep::Full(block) => (block.regs, block.steps[0].clone()),
+                CompressedStep::Repeat {
+                    source,
+                    timestamp,
+                    result,
+                } => {
+                    let CompressedStep::Full(source) = &self.entries[*source as usize] else {
+                        unreachable!("repeated steps refer to full entries");
+                    };
+                    let step = Step {
+                        timestamp: *timestamp,
+                        result: *result,
+                        ..source.steps[0].clone()
+                    };
+                    (regs, step)
+                }
+            };
+            regs = self.schema.regs_after(block_regs, &step);
+            (block_regs, step)
+        })
+    }
+
+    /// Expand the trace, yielding the original blocks. Fields left out by the schema are zero.
+    pub fn blocks(&self) -> impl Iterator<Item = Block> + '_ {
+        let mut steps = self.expand_steps();
+        let mut block_lens = self.block_lens.iter();
+        std::iter::from_fn(move || {
+            let (regs, step) = steps.next()?;
+            let len = match block_lens.next() {
+                Some(&len) => len,
+                None => 1,
+            };
+            let mut block = Block {
+                regs,
+                steps: Vec::with_capacity(len),
+            };
+            block.steps.push(step);
+            block
+                .steps
+                .extend(steps.by_ref().take(len - 1).map(|(_, step)| step));
+            Some(block)
+        })
+    }
+
+    /// Expand the trace into a [`UniformTrace`], if compressed with [`CompactionSchema::FULL`].
+    ///
+    /// The steps per block of the expanded trace are the ones of its first block.
+    pub fn to_uniform(&self) -> Option<UniformTrace> {
+        if !self.is_provable() {
+            return None;
+        }
+        Some(UniformTrace {
+            memory_layout: self.memory_layout,
+            k: self.block_lens.first().copied().unwrap_or(1),
+            start: self.start,
+            blocks: self.blocks().collect(),
+            block_indices: self.block_indices.clone(),
+        })
+    }
+
+    /// Expand the trace into subtraces with `n` blocks each, one at a time. Note, the final subtrace may contain
+    /// fewer than `n` blocks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the trace wasn't compressed with [`CompactionSchema::FULL`].
+    pub fn split_by(&self, n: usize) -> impl Iterator<Item = UniformTrace> + '_ {
+        assert!(n > 0);
+        assert!(self.is_provable(), "only provable traces can be split");
+        let mut blocks = self.blocks();
+        let mut start = self.start;
+        let mut first_row = 0;
+        std::iter::from_fn(move || {
+            let blocks: Vec<Block> = blocks.by_ref().take(n).collect();
+            if blocks.is_empty() {
+                return None;
+            }
+            let num_rows: usize = blocks.iter().map(|block| block.steps.len()).sum();
+            let block_indices = self
+                .block_indices
+                .get(first_row..first_row + num_rows)
+                .map(<[_]>::to_vec)
+                .unwrap_or_default();
+            let subtrace = UniformTrace {
+                memory_layout: self.memory_layout,
+                k: self.block_lens.first().copied().unwrap_or(1),
+                start,
+                blocks,
+                block_indices,
+            };
+            start += subtrace.blocks.len();
+            first_row += num_rows;
+            Some(subtrace)
+        })
+    }
+
+    /// Return the number of steps in this trace.
+    pub fn get_num_steps(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Program counters of the steps, if kept by the schema.
+    pub fn pcs(&self) -> Option<Vec<u32>> {
+        self.schema
+            .pc
+            .then(|| self.expand_steps().map(|(_, step)| step.pc).collect())
+    }
+
+    /// Timestamps of the steps, if kept by the schema.
+    pub fn timestamps(&self) -> Option<Vec<u32>> {
+        self.schema.timestamp.then(|| {
+            self.expand_steps()
+                .map(|(_, step)| step.timestamp)
+                .collect()
+        })
+    }
+
+    /// Instructions of the steps, if kept by the schema. The `ADDI` of fused steps isn't included.
+    pub fn instructions(&self) -> Option<Vec<Instruction>> {
+        self.schema.instruction.then(|| {
+            self.expand_steps()
+                .map(|(_, step)| step.instruction)
+                .collect()
+        })
+    }
+
+    /// Reconstructs the steps, if the schema keeps all of their fields.
+    pub fn steps(&self) -> Option<Vec<Step>> {
+        self.schema
+            .keeps_steps()
+            .then(|| self.expand_steps().map(|(_, step)| step).collect())
+    }
+
+    /// Estimate the memory, in bytes, used by this trace.
+    pub fn memory_footprint(&self) -> usize {
+        size_of::<Self>()
+            + self.entries.capacity() * size_of::<CompressedStep>()
+            + self.block_lens.capacity() * size_of::<usize>()
+            + self.block_indices.capacity() * size_of::<Option<u16>>()
+            + self
+                .entries
+                .iter()
+                .map(|entry| match entry {
+                    CompressedStep::Full(block) => block.memory_footprint(),
+                    CompressedStep::Repeat { .. } => 0,
+                })
+                .sum::<usize>()
+    }
+}
+
+// Whether `step` is the same execution as `source` up to its timestamp and result.
+fn repeats(step: &Step, source: &Step) -> bool {
+    step.pc == source.pc
+        && step.next_pc == source.next_pc
+        && step.raw_instruction == source.raw_instruction
+        && step.instruction == source.instruction
+        && step.memory_records == source.memory_records
+        && step.fused == source.fused
+}
+
+// Registers after executing `step` from `regs`, assuming the step only wrote back its result.
+fn regs_after(mut regs: RegisterFile, step: &Step) -> RegisterFile {
+    if let Some(value) = step.result {
+        regs.write(step.instruction.op_a, value);
+    }
+    regs
+}
+
 // Generate a `Step` by evaluating the next instruction of `vm`.
 fn step(
     vm: &mut impl Emulator,
@@ -199,13 +810,14 @@ fn step(
         instruction: instruction.clone(),
         result,
         memory_records,
+        fused: None,
     };
 
     Ok(step)
 }
 
 // Generate a `Block` by evaluating `k` steps of `vm`.
-fn k_step(
+pub(crate) fn k_step(
     vm: &mut impl Emulator,
     k: usize,
     force_second_pass: bool,
@@ -221,31 +833,7 @@ fn k_step(
                 // When the block is not fully filled with 'k' instructions,
                 // we still return the block we have,
                 // along with padded UNIMPL instructions to complete the block.
-                // The padded instructions are not executed in the VM.
-                if k > 1 && block.steps.len() < k {
-                    let last_step = block.steps.last().unwrap();
-                    let unimpl_instruction = Instruction::unimpl();
-                    let mut padding_steps = Vec::new();
-
-                    for _ in block.steps.len()..k {
-                        // 1. Increment the global_clock for each padding step.
-                        vm.get_executor_mut().global_clock += 1;
-
-                        // 2. Repeat the last state, but with the global_clock incremented.
-                        padding_steps.push(Step {
-                            timestamp: vm.get_executor().global_clock as u32,
-                            pc: last_step.next_pc,
-                            next_pc: last_step.next_pc,
-                            raw_instruction: unimpl_instruction.encode(),
-                            instruction: unimpl_instruction.clone(),
-                            result: None,
-                            memory_records: MemoryRecords::default(),
-                        });
-                    }
-                    // 3. Complete the block with UNIMPL instructions
-                    block.steps.extend(padding_steps);
-                }
-
+                pad_block(vm, &mut block, k);
                 return (Some(block), Err(e));
             }
             Ok(basic_block_entry) => {
@@ -275,10 +863,26 @@ fn k_step(
                                 instruction: instruction.clone(),
                                 result: if force_second_pass { None } else { Some(n) },
                                 memory_records: MemoryRecords::default(),
+                                fused: None,
                             });
 
                             return (Some(block), Err(VMErrorKind::VMExited(n).into()));
                         }
+                        Err(
+                            e @ VMError {
+                                source:
+                                    VMErrorKind::CycleLimitExceeded(_)
+                                    | VMErrorKind::SelfLoopHalt(_)
+                                    | VMErrorKind::StackOverflow { .. },
+                                ..
+                            },
+                        ) => {
+                            // The instruction was not executed, the steps so far are kept.
+                            if !block.steps.is_empty() {
+                                pad_block(vm, &mut block, k);
+                            }
+                            return (Some(block), Err(e));
+                        }
                         Err(e) => return (None, Err(e)),
                     }
                 }
@@ -289,6 +893,35 @@ fn k_step(
     (Some(block), Ok(()))
 }
 
+// Pad a block that is not fully filled with `k` instructions with UNIMPL instructions.
+// The padded instructions are not executed in the VM.
+fn pad_block(vm: &mut impl Emulator, block: &mut Block, k: usize) {
+    if k > 1 && block.steps.len() < k {
+        let last_step = block.steps.last().unwrap();
+        let unimpl_instruction = Instruction::unimpl();
+        let mut padding_steps = Vec::new();
+
+        for _ in block.steps.len()..k {
+            // 1. Increment the global_clock for each padding step.
+            vm.get_executor_mut().global_clock += 1;
+
+            // 2. Repeat the last state, but with the global_clock incremented.
+            padding_steps.push(Step {
+                timestamp: vm.get_executor().global_clock as u32,
+                pc: last_step.next_pc,
+                next_pc: last_step.next_pc,
+                raw_instruction: unimpl_instruction.encode(),
+                instruction: unimpl_instruction.clone(),
+                result: None,
+                memory_records: MemoryRecords::default(),
+                fused: None,
+            });
+        }
+        // 3. Complete the block with UNIMPL instructions
+        block.steps.extend(padding_steps);
+    }
+}
+
 /// Trace a program over an ELF for a given `k`.
 ///
 /// This function generates a trace of the program execution using the provided ELF file.
@@ -305,14 +938,86 @@ pub fn k_trace(
     public_input: &[u8],
     private_input: &[u8],
     k: usize,
+) -> Result<(View, UniformTrace)> {
+    k_trace_with_cycle_limit(elf, ad, public_input, private_input, k, None)
+}
+
+/// Same as [`k_trace`], but execution stops once `cycle_limit` instructions have been executed.
+///
+/// A program reaching the cycle limit is traced up to the limit, its public output is the output it
+/// committed before that point.
+pub fn k_trace_with_cycle_limit(
+    elf: ElfFile,
+    ad: &[u8],
+    public_input: &[u8],
+    private_input: &[u8],
+    k: usize,
+    cycle_limit: Option<usize>,
+) -> Result<(View, UniformTrace)> {
+    let options = TraceOptions {
+        cycle_limit,
+        ..Default::default()
+    };
+    k_trace_with_options(elf, ad, public_input, private_input, k, options)
+}
+
+/// Options controlling how the program starts and when tracing stops in addition to the exit syscall.
+#[derive(Debug, Default, Clone)]
+pub struct TraceOptions {
+    /// Stop once this many instructions have been executed, see [`Emulator::set_cycle_limit`].
+    pub cycle_limit: Option<usize>,
+    /// Halt once the program reaches a side-effect free self-loop, see [`Emulator::set_halt_on_self_loop`].
+    pub halt_on_self_loop: bool,
+    /// Arguments passed to the entrypoint in registers, see [`Emulator::set_initial_args`].
+    pub initial_args: Vec<u32>,
+    /// Hint functions available to the guest through the hint syscall.
+    pub hints: HintRegistry,
+    /// Fuse address materialization pairs, see [`fuse_address_materialization`]. Requires `k = 1`.
+    pub fuse_address_materialization: bool,
+    /// Stop before the program accesses the guard region below the stack, see [`Emulator::set_stack_guard`].
+    pub stack_guard: Option<StackGuard>,
+    /// Keep a shadow stack of at most this many return addresses, see [`Emulator::set_shadow_stack`].
+    pub shadow_stack_depth: Option<usize>,
+    /// Record the final bytes of these address ranges in the view, see [`Emulator::set_captured_memory`].
+    pub captured_memory: Vec<std::ops::Range<u32>>,
+    /// Record the basic block of every step, see [`UniformTrace::record_block_indices`]. Blocks are those of
+    /// [`decode_instructions`] over the instructions of the ELF, indices refer to the trace returned, after fusion.
+    pub record_block_indices: bool,
+}
+
+/// Same as [`k_trace`], with additional options for starting and stopping execution.
+///
+/// To call a function other than the entrypoint of the ELF header, load the ELF with
+/// [`ElfFile::from_bytes_with_entry_symbol`] or override its entrypoint with [`ElfFile::with_entry`].
+pub fn k_trace_with_options(
+    elf: ElfFile,
+    ad: &[u8],
+    public_input: &[u8],
+    private_input: &[u8],
+    k: usize,
+    options: TraceOptions,
 ) -> Result<(View, UniformTrace)> {
     assert!(k > 0);
+    let program_blocks = options
+        .record_block_indices
+        .then(|| (decode_instructions(&elf.instructions).blocks, elf.base));
     let mut harvard = HarvardEmulator::from_elf(&elf, public_input, private_input);
     harvard.get_executor_mut().capture_logs(true);
+    harvard.set_cycle_limit(options.cycle_limit);
+    harvard.set_halt_on_self_loop(options.halt_on_self_loop);
+    harvard.set_stack_guard(options.stack_guard);
+    harvard.set_shadow_stack(options.shadow_stack_depth);
+    harvard.set_captured_memory(options.captured_memory);
+    harvard.set_initial_args(&options.initial_args)?;
+    harvard.get_executor_mut().hints = options.hints;
 
     match harvard.execute(false) {
         Err(VMError {
-            source: VMErrorKind::VMExited(_),
+            source:
+                VMErrorKind::VMExited(_)
+                | VMErrorKind::CycleLimitExceeded(_)
+                | VMErrorKind::SelfLoopHalt(_)
+                | VMErrorKind::StackOverflow { .. },
             ..
         }) => {
             // todo: consistency check i/o between harvard and linear?
@@ -323,6 +1028,7 @@ pub fn k_trace(
                 k,
                 start: 0,
                 blocks: Vec::new(),
+                block_indices: Vec::new(),
             };
 
             loop {
@@ -334,9 +1040,18 @@ pub fn k_trace(
                         }
 
                         match e.source {
-                            VMErrorKind::VMExited(_) => {
+                            VMErrorKind::VMExited(_)
+                            | VMErrorKind::CycleLimitExceeded(_)
+                            | VMErrorKind::SelfLoopHalt(_)
+                            | VMErrorKind::StackOverflow { .. } => {
                                 let mut view = linear.finalize();
                                 view.add_logs(&harvard);
+                                if options.fuse_address_materialization {
+                                    trace = fuse_address_materialization(&trace);
+                                }
+                                if let Some((basic_blocks, base)) = &program_blocks {
+                                    trace.record_block_indices(basic_blocks, *base);
+                                }
                                 return Ok((view, trace));
                             }
                             _ => return Err(e),
@@ -354,17 +1069,35 @@ pub fn k_trace(
 
 /// Similar to `k_trace`, but uses HarvardEmulator and supports Intermediate Representation (IR) as input instead of an ELF file.
 pub fn k_trace_direct(basic_blocks: &Vec<BasicBlock>, k: usize) -> Result<(View, UniformTrace)> {
+    k_trace_direct_with_hints(basic_blocks, k, HintRegistry::default())
+}
+
+/// Same as [`k_trace_direct`], with `hints` available to the program through the hint syscall.
+pub fn k_trace_direct_with_hints(
+    basic_blocks: &Vec<BasicBlock>,
+    k: usize,
+    hints: HintRegistry,
+) -> Result<(View, UniformTrace)> {
     let mut harvard = HarvardEmulator::from_basic_blocks(basic_blocks);
+    harvard.executor.hints = hints;
+    k_trace_harvard(&mut harvard, k)
+}
 
+/// Trace the execution of `harvard` from its current state until the program stops, see [`k_trace_direct`].
+///
+/// The emulator is left in its end state, so that it can be run again after a
+/// [`warm_reset`](Emulator::warm_reset).
+pub fn k_trace_harvard(harvard: &mut HarvardEmulator, k: usize) -> Result<(View, UniformTrace)> {
     let mut trace = UniformTrace {
         memory_layout: LinearMemoryLayout::default(), // dummy
         k,
         start: 0,
         blocks: Vec::new(),
+        block_indices: Vec::new(),
     };
 
     loop {
-        match k_step(&mut harvard, k, true) {
+        match k_step(harvard, k, true) {
             (Some(block), Ok(())) => trace.blocks.push(block),
             (Some(block), Err(e)) => {
                 if !block.steps.is_empty() {
@@ -372,9 +1105,11 @@ pub fn k_trace_direct(basic_blocks: &Vec<BasicBlock>, k: usize) -> Result<(View,
                 }
 
                 match e.source {
-                    VMErrorKind::VMExited(_) | VMErrorKind::VMOutOfInstructions => {
-                        return Ok((harvard.finalize(), trace))
-                    }
+                    VMErrorKind::VMExited(_)
+                    | VMErrorKind::VMOutOfInstructions
+                    | VMErrorKind::CycleLimitExceeded(_)
+                    | VMErrorKind::SelfLoopHalt(_)
+                    | VMErrorKind::StackOverflow { .. } => return Ok((harvard.finalize(), trace)),
                     _ => return Err(e),
                 }
             }
@@ -384,6 +1119,109 @@ pub fn k_trace_direct(basic_blocks: &Vec<BasicBlock>, k: usize) -> Result<(View,
     }
 }
 
+// Whether `second` is an `ADDI` completing the address materialized by `first`.
+fn materializes_address(first: &Step, second: &Step) -> bool {
+    let rd = first.instruction.op_a;
+    matches!(
+        first.instruction.opcode.builtin(),
+        Some(BuiltinOpcode::LUI | BuiltinOpcode::AUIPC)
+    ) && rd != Register::X0
+        && second.instruction.opcode.builtin() == Some(BuiltinOpcode::ADDI)
+        && second.instruction.op_a == rd
+        && second.instruction.op_b == rd
+        && first.next_pc == first.pc.wrapping_add(WORD_SIZE as u32)
+        && second.pc == first.next_pc
+}
+
+// `record` with its timestamp moved back by `shift`.
+fn shift_timestamp(record: &MemoryRecord, shift: u32) -> MemoryRecord {
+    match *record {
+        MemoryRecord::LoadRecord(access, timestamp) => {
+            MemoryRecord::LoadRecord(access, timestamp - shift)
+        }
+        MemoryRecord::StoreRecord(access, timestamp) => {
+            MemoryRecord::StoreRecord(access, timestamp - shift)
+        }
+    }
+}
+
+/// Fuse address materialization pairs of a trace with one step per block.
+///
+/// A `LUI` or `AUIPC` writing `rd`, followed by `ADDI rd, rd, imm`, becomes a single step with the result of the
+/// `ADDI` and a pc advancing by 8, the `ADDI` is kept in [`Step::fused`]. Pairs whose `ADDI` is the target of a
+/// jump or a taken branch anywhere in the trace are left apart, that `ADDI` also runs on its own.
+///
+/// Execution doesn't change, only its representation: the clock no longer ticks for the fused `ADDI`, timestamps
+/// of the following steps and of their memory records are moved back accordingly. Traces reading the cycle counter
+/// are returned unfused, since the guest has observed the clock of the unfused execution.
+///
+/// # Panics
+///
+/// Panics if `trace.k` is not 1.
+pub fn fuse_address_materialization(trace: &UniformTrace) -> UniformTrace {
+    assert_eq!(trace.k, 1, "Only k = 1 traces can be fused.");
+
+    if trace.blocks.iter().any(reads_cycle_counter) {
+        return trace.clone();
+    }
+
+    let jump_targets: HashSet<u32> = trace
+        .blocks
+        .iter()
+        .map(|block| &block.steps[0])
+        .filter(|step| step.next_pc != step.pc.wrapping_add(WORD_SIZE as u32))
+        .map(|step| step.next_pc)
+        .collect();
+
+    let mut blocks = Vec::with_capacity(trace.blocks.len());
+    let mut shift = 0;
+    let mut iter = trace.blocks.iter().peekable();
+    while let Some(block) = iter.next() {
+        let mut step = block.steps[0].clone();
+        let second = iter.next_if(|next| {
+            materializes_address(&step, &next.steps[0]) && !jump_targets.contains(&next.steps[0].pc)
+        });
+
+        step.timestamp -= shift;
+        step.memory_records = step
+            .memory_records
+            .iter()
+            .map(|record| shift_timestamp(record, shift))
+            .collect();
+        if let Some(second) = second {
+            let second = &second.steps[0];
+            step.next_pc = second.next_pc;
+            step.result = second.result;
+            step.fused = Some(FusedInstruction {
+                raw_instruction: second.raw_instruction,
+                instruction: second.instruction.clone(),
+            });
+            shift += OpcodeCost::cycles(&second.instruction.opcode);
+        }
+
+        blocks.push(Block {
+            regs: block.regs,
+            steps: vec![step],
+        });
+    }
+
+    UniformTrace {
+        memory_layout: trace.memory_layout,
+        k: 1,
+        start: trace.start,
+        blocks,
+        block_indices: Vec::new(),
+    }
+}
+
+// Whether the single step of `block` is a `ReadCycleCounter` syscall.
+fn reads_cycle_counter(block: &Block) -> bool {
+    matches!(
+        block.steps[0].instruction.opcode.builtin(),
+        Some(BuiltinOpcode::ECALL)
+    ) && block.regs[Register::X17] == SyscallCode::ReadCycleCounter as u32
+}
+
 /// Generate a `Block` by evaluating a basic block in the `vm`.
 fn bb_step(vm: &mut impl Emulator) -> (Option<Block>, Result<()>) {
     let mut block = Block {
@@ -415,6 +1253,7 @@ fn bb_step(vm: &mut impl Emulator) -> (Option<Block>, Result<()>) {
                             instruction: instruction.clone(),
                             result: Some(n),
                             memory_records: MemoryRecords::default(),
+                            fused: None,
                         });
 
                         return (Some(block), Err(VMErrorKind::VMExited(n).into()));
@@ -519,12 +1358,222 @@ pub fn bb_trace_direct(basic_blocks: &Vec<BasicBlock>) -> Result<(View, BBTrace)
 mod tests {
     use super::*;
     use crate::{
+        emulator::{ShadowStackMismatch, TerminationCause},
+        memory::MemoryProcessor,
         read_testing_elf_from_path,
         riscv::{BuiltinOpcode, Opcode, Register},
     };
-    use nexus_common::constants::ELF_TEXT_START;
+    use nexus_common::constants::{ELF_TEXT_START, MEMORY_TOP};
     use serial_test::serial;
 
+    /// A program that ends in `j .` instead of the exit syscall.
+    fn self_loop_elf() -> ElfFile {
+        let instructions = [
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 7),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::JAL), 0, 0, 0),
+        ];
+        ElfFile::new(
+            instructions.iter().map(Instruction::encode).collect(),
+            ELF_TEXT_START,
+            ELF_TEXT_START,
+            Default::default(),
+            Default::default(),
+            vec![],
+        )
+    }
+
+    #[test]
+    fn test_block_indices_across_branch() {
+        let basic_blocks = vec![
+            BasicBlock::new(vec![
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+                // Jump over the first instruction of the second block.
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::JAL), 0, 0, 8),
+            ]),
+            BasicBlock::new(vec![
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 2),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 3, 0, 3),
+            ]),
+        ];
+        let (_, mut trace) = k_trace_direct(&basic_blocks, 1).unwrap();
+        assert_eq!(trace.block_index_of(0), None);
+
+        trace.record_block_indices(&basic_blocks, ELF_TEXT_START);
+        let pcs: Vec<u32> = trace.blocks.iter().map(|block| block.steps[0].pc).collect();
+        assert_eq!(
+            pcs,
+            [ELF_TEXT_START, ELF_TEXT_START + 4, ELF_TEXT_START + 12]
+        );
+        let indices: Vec<_> = (0..4).map(|row| trace.block_index_of(row)).collect();
+        assert_eq!(indices, [Some(0), Some(0), Some(1), None]);
+
+        // Rows of subtraces are counted from their first block.
+        assert_eq!(trace.get(2).unwrap().block_index_of(0), Some(1));
+        let subtraces: Vec<_> = trace.split_by(2).collect();
+        assert_eq!(subtraces[1].block_index_of(0), Some(1));
+
+        let options = TraceOptions {
+            halt_on_self_loop: true,
+            record_block_indices: true,
+            ..Default::default()
+        };
+        let (_, trace) = k_trace_with_options(self_loop_elf(), &[], &[], &[], 1, options).unwrap();
+        assert!((0..trace.get_num_steps()).all(|row| trace.block_index_of(row) == Some(0)));
+    }
+
+    #[test]
+    fn test_shadow_stack_records_smashed_return_address() {
+        let overwrite_sp = u32::from(SyscallCode::OverwriteStackPointer);
+        let instructions = [
+            // la sp, __memory_top
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LUI), 2, 0, MEMORY_TOP >> 12),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, overwrite_sp),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+            // Call a function that returns normally, then one that overwrites its saved return address.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::JAL), 1, 0, 16),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::JAL), 1, 0, 16),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::JAL), 0, 0, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::JAL), 0, 0, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::JALR), 0, 1, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 2, 0xFF0), // -16
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 2, 1, 12),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 3, 1, 4),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 2, 3, 12),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 1, 2, 12),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 2, 16),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::JALR), 0, 1, 0),
+        ];
+        let elf = ElfFile::new(
+            instructions.iter().map(Instruction::encode).collect(),
+            ELF_TEXT_START,
+            ELF_TEXT_START,
+            Default::default(),
+            Default::default(),
+            vec![],
+        );
+        let options = TraceOptions {
+            halt_on_self_loop: true,
+            shadow_stack_depth: Some(16),
+            ..Default::default()
+        };
+        let (view, trace) =
+            k_trace_with_options(elf.clone(), &[], &[], &[], 1, options.clone()).unwrap();
+
+        // The smashed return lands on the second self-loop.
+        assert_eq!(
+            view.view_termination_cause(),
+            Some(TerminationCause::SelfLoopHalt(ELF_TEXT_START + 24))
+        );
+        assert_eq!(
+            view.view_shadow_stack_mismatches(),
+            [ShadowStackMismatch {
+                pc: ELF_TEXT_START + 56,
+                expected: ELF_TEXT_START + 20,
+                got: ELF_TEXT_START + 24,
+                depth: 1,
+            }]
+        );
+
+        // The shadow stack doesn't affect the trace.
+        let options = TraceOptions {
+            shadow_stack_depth: None,
+            ..options
+        };
+        let (view, unchecked_trace) = k_trace_with_options(elf, &[], &[], &[], 1, options).unwrap();
+        assert!(view.view_shadow_stack_mismatches().is_empty());
+        assert_eq!(unchecked_trace.blocks, trace.blocks);
+    }
+
+    #[test]
+    fn test_k_trace_halts_on_self_loop() {
+        let options = TraceOptions {
+            cycle_limit: Some(1000),
+            halt_on_self_loop: true,
+            ..Default::default()
+        };
+        let (view, trace) =
+            k_trace_with_options(self_loop_elf(), &[], &[], &[], 1, options).unwrap();
+
+        assert_eq!(
+            view.view_termination_cause(),
+            Some(TerminationCause::SelfLoopHalt(ELF_TEXT_START + 4))
+        );
+        // The loop is executed once as an ordinary jump.
+        assert_eq!(trace.get_num_steps(), 2);
+        let last_step = &trace.blocks.last().unwrap().steps[0];
+        assert_eq!(
+            last_step.instruction.opcode.builtin(),
+            Some(BuiltinOpcode::JAL)
+        );
+        assert_eq!(last_step.pc, ELF_TEXT_START + 4);
+        assert_eq!(last_step.next_pc, ELF_TEXT_START + 4);
+    }
+
+    #[test]
+    fn test_k_trace_self_loop_without_halting() {
+        let (view, trace) =
+            k_trace_with_cycle_limit(self_loop_elf(), &[], &[], &[], 1, Some(100)).unwrap();
+
+        assert_eq!(
+            view.view_termination_cause(),
+            Some(TerminationCause::CycleLimit(100))
+        );
+        assert_eq!(trace.get_num_steps(), 100);
+    }
+
+    #[test]
+    #[serial]
+    fn test_k_trace_within_stack_guard() {
+        let elf_file = read_testing_elf_from_path!("/test/fib_10.elf");
+        let options = TraceOptions {
+            stack_guard: Some(StackGuard {
+                max_stack_size: 1 << 16,
+                guard_pages: 4,
+            }),
+            ..Default::default()
+        };
+        let (view, _) = k_trace_with_options(elf_file, &[], &[], &[], 1, options).unwrap();
+
+        assert_eq!(
+            view.view_termination_cause(),
+            Some(TerminationCause::Exit(0))
+        );
+        let stack_top = view.view_memory_layout().unwrap().stack_top();
+        let deepest_sp = view.view_deepest_sp().unwrap();
+        assert!(deepest_sp < stack_top);
+        assert_eq!(view.view_max_stack_depth(), Some(stack_top - deepest_sp));
+        assert!(stack_top - deepest_sp < 1 << 16);
+    }
+
+    #[test]
+    #[serial]
+    fn test_k_trace_captures_memory() {
+        let elf_file = read_testing_elf_from_path!("/test/fib_10.elf");
+        let (start, end) = elf_file.static_ram_range();
+        let options = TraceOptions {
+            captured_memory: vec![start..end],
+            ..Default::default()
+        };
+        let (view, _) = k_trace_with_options(elf_file.clone(), &[], &[], &[], 1, options).unwrap();
+        let captured = view.view_captured_memory(&(start..end)).unwrap();
+        assert_eq!(captured.len(), (end - start) as usize);
+        assert!(view.view_captured_memory(&(start..end - 4)).is_none());
+
+        // Both emulators agree on the final static memory.
+        let mut harvard = HarvardEmulator::from_elf(&elf_file, &[], &[]);
+        assert!(matches!(
+            harvard.execute(false).unwrap_err().source,
+            VMErrorKind::VMExited(0)
+        ));
+        assert_eq!(
+            harvard
+                .data_memory
+                .read_bytes(start, (end - start) as usize)
+                .unwrap(),
+            captured
+        );
+    }
+
     #[test]
     #[serial]
     fn test_k1_trace_nexus_rt_binary() {
@@ -787,6 +1836,27 @@ mod tests {
         assert_eq!(last_step.result, None, "Unexpected Fibonacci result");
     }
 
+    #[test]
+    fn test_k1_trace_direct_timestamps_follow_opcode_cost() {
+        let basic_block = setup_basic_block_ir();
+        let (view, trace) = k_trace_direct(&basic_block, 1).expect("Failed to create trace");
+
+        let steps: Vec<&Step> = trace.get_blocks_iter().flat_map(|b| &b.steps).collect();
+        for pair in steps.windows(2) {
+            assert_eq!(
+                pair[1].timestamp - pair[0].timestamp,
+                OpcodeCost::cycles(&pair[0].instruction.opcode),
+                "unexpected clock delta after {}",
+                pair[0].instruction
+            );
+        }
+        let total_cycles: u32 = steps
+            .iter()
+            .map(|step| OpcodeCost::cycles(&step.instruction.opcode))
+            .sum();
+        assert_eq!(view.view_cycle_count(), Some(total_cycles as usize));
+    }
+
     #[test]
     fn test_k8_trace_direct_timestamp_tick_after_instruction_ended() {
         let basic_block = vec![BasicBlock::new(vec![
@@ -808,4 +1878,286 @@ mod tests {
             "Unexpected timestamp for the last step"
         );
     }
+
+    /// Counts x1 up to `iterations` with an `addi`/`bne` loop.
+    fn counting_loop(iterations: u32) -> Vec<BasicBlock> {
+        vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LUI), 2, 0, iterations >> 12),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 2, iterations & 0x7FF),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 1, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::BNE), 1, 2, 0x1FFC), // -4
+        ])]
+    }
+
+    #[test]
+    fn test_compressed_trace_expands_to_original() {
+        let (_, trace) = k_trace_direct(&counting_loop(1 << 10), 1).unwrap();
+        let compressed = CompressedTrace::new(&trace);
+        assert_eq!(compressed.get_num_steps(), trace.get_num_steps());
+        assert!(compressed.blocks().eq(trace.blocks.iter().cloned()));
+
+        // Memory accesses and calls aren't repeated, they are stored in full.
+        let elf_file = read_testing_elf_from_path!("/test/fib_10.elf");
+        let (_, trace) = k_trace(elf_file, &[], &[], &[], 1).unwrap();
+        let compressed = CompressedTrace::new(&trace);
+        let expanded = compressed.to_uniform().unwrap();
+        assert_eq!(expanded.blocks, trace.blocks);
+        assert_eq!(expanded.start, trace.start);
+
+        let subtraces: Vec<UniformTrace> = compressed.split_by(100).collect();
+        assert_eq!(subtraces.len(), trace.blocks.len().div_ceil(100));
+        for (subtrace, expected) in subtraces.iter().zip(trace.split_by(100)) {
+            assert_eq!(subtrace.start, expected.start);
+            assert_eq!(subtrace.blocks, expected.blocks);
+        }
+    }
+
+    /// Compresses the trace of `basic_blocks` while it is generated, returning it together with the memory footprint
+    /// the uncompressed trace would have.
+    fn compress_while_tracing(basic_blocks: &Vec<BasicBlock>) -> (CompressedTrace, usize) {
+        let mut harvard = HarvardEmulator::from_basic_blocks(basic_blocks);
+        let mut uncompressed = size_of::<UniformTrace>();
+        let mut done = false;
+        let blocks = std::iter::from_fn(|| {
+            if done {
+                return None;
+            }
+            let (block, result) = k_step(&mut harvard, 1, true);
+            done = result.is_err();
+            block.filter(|block| !block.steps.is_empty())
+        })
+        .inspect(|block| uncompressed += block.memory_footprint());
+        let compressed = CompressedTrace::from_blocks(LinearMemoryLayout::default(), 0, blocks);
+        (compressed, uncompressed)
+    }
+
+    fn fused_steps(trace: &UniformTrace) -> Vec<&Step> {
+        trace
+            .get_blocks_iter()
+            .flat_map(|block| &block.steps)
+            .filter(|step| step.fused.is_some())
+            .collect()
+    }
+
+    #[test]
+    fn test_fuse_address_materialization() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LUI), 5, 0, 0x12345),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 5, 5, 0x678),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::AUIPC), 6, 0, 0x1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 6, 6, 0xFFC), // -4
+            // Writes another register, not fused.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LUI), 7, 0, 0x1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 8, 7, 0x1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 0, 5, 0x10),
+        ])];
+        let (_, trace) = k_trace_direct(&basic_block, 1).unwrap();
+        assert!(fused_steps(&trace).is_empty());
+
+        let fused = fuse_address_materialization(&trace);
+        assert_eq!(fused.get_num_steps(), trace.get_num_steps() - 2);
+
+        let steps: Vec<&Step> = fused.get_blocks_iter().flat_map(|b| &b.steps).collect();
+        assert_eq!(steps[0].pc, 0);
+        assert_eq!(steps[0].next_pc, 8);
+        assert_eq!(steps[0].result, Some(0x12345678));
+        assert_eq!(
+            steps[0].fused.as_ref().map(|fused| fused.raw_instruction),
+            Some(basic_block[0].0[1].encode())
+        );
+        assert_eq!(steps[1].pc, 8);
+        assert_eq!(steps[1].next_pc, 16);
+        assert_eq!(steps[1].result, Some(8 + 0x1000 - 4));
+        assert!(steps[2].fused.is_none() && steps[3].fused.is_none());
+
+        // The clock doesn't tick for fused instructions, memory records follow the steps.
+        for (i, step) in steps.iter().enumerate() {
+            assert_eq!(step.timestamp, i as u32 + 1);
+        }
+        let store = steps.last().unwrap();
+        assert!(store
+            .memory_records
+            .iter()
+            .all(|record| record.get_timestamp() == store.timestamp));
+
+        // Registers at the start of each block are unchanged.
+        assert_eq!(fused.blocks[2].regs, trace.blocks[4].regs);
+    }
+
+    #[test]
+    fn test_fusion_inhibited_by_jump_target() {
+        // The second ADDI is the target of the BNE, it runs once without the LUI in front of it.
+        let program = |branch_offset| {
+            vec![BasicBlock::new(vec![
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 7, 0, 2),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::LUI), 5, 0, 0x1),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 5, 5, 0x1),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 6, 6, 0x1),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::BNE), 6, 7, branch_offset),
+            ])]
+        };
+
+        let (_, trace) = k_trace_direct(&program(0x1FF8), 1).unwrap(); // -8
+        let fused = fuse_address_materialization(&trace);
+        assert!(fused_steps(&fused).is_empty());
+        assert_eq!(fused.get_num_steps(), trace.get_num_steps());
+
+        let (_, trace) = k_trace_direct(&program(0x1FFC), 1).unwrap(); // -4
+        let fused = fuse_address_materialization(&trace);
+        assert_eq!(fused_steps(&fused).len(), 1);
+        assert_eq!(fused.get_num_steps(), trace.get_num_steps() - 1);
+    }
+
+    #[test]
+    fn test_cycle_counter_reads_step_clock() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LUI), 5, 0, 0x1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 5, 5, 0x1),
+            Instruction::new_ir(
+                Opcode::from(BuiltinOpcode::ADDI),
+                17,
+                0,
+                SyscallCode::ReadCycleCounter as u32,
+            ),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 6, 0, 0x1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+        ])];
+        let (_, trace) = k_trace_direct(&basic_block, 1).unwrap();
+
+        let reads: Vec<&Step> = trace
+            .get_blocks_iter()
+            .flat_map(|block| &block.steps)
+            .filter(|step| step.instruction.opcode.builtin() == Some(BuiltinOpcode::ECALL))
+            .collect();
+        assert_eq!(reads.len(), 2);
+        for step in reads {
+            assert_eq!(step.result, Some(step.timestamp));
+        }
+
+        // Fusing would move the clock the guest has already read.
+        let fused = fuse_address_materialization(&trace);
+        assert!(fused_steps(&fused).is_empty());
+        assert_eq!(fused.blocks, trace.blocks);
+    }
+
+    #[test]
+    #[serial]
+    fn test_fusion_reduces_steps_of_nexus_rt_binary() {
+        let elf_file = read_testing_elf_from_path!("/test/fib_10.elf");
+        let (view, trace) = k_trace(elf_file.clone(), &[], &[], &[], 1).unwrap();
+        assert!(fused_steps(&trace).is_empty());
+
+        let options = TraceOptions {
+            fuse_address_materialization: true,
+            ..Default::default()
+        };
+        let (fused_view, fused) =
+            k_trace_with_options(elf_file, &[], &[], &[], 1, options).unwrap();
+        let num_fused = fused_steps(&fused).len();
+        assert!(num_fused > 0);
+        assert_eq!(fused.get_num_steps(), trace.get_num_steps() - num_fused);
+        assert_eq!(fused_view.view_cycle_count(), view.view_cycle_count());
+
+        let last_regs = |trace: &UniformTrace| trace.blocks.last().unwrap().regs;
+        assert_eq!(last_regs(&fused), last_regs(&trace));
+    }
+
+    #[test]
+    fn test_trace_summary() {
+        let (_, trace) = k_trace_direct(&counting_loop(3), 1).unwrap();
+        let summary = trace.summary();
+        assert_eq!(summary.num_steps, trace.get_num_steps());
+        assert_eq!(summary.clk_range, Some((1, 8)));
+        assert_eq!(
+            trace.to_string(),
+            "UniformTrace (k = 1): 8 steps, clk 1..=8\n\
+             \x20 addi     4\n\
+             \x20 bne      3\n\
+             \x20 lui      1\n"
+        );
+
+        let (_, trace) = bb_trace_direct(&counting_loop(3)).unwrap();
+        assert_eq!(trace.summary(), summary);
+        assert!(trace
+            .to_string()
+            .starts_with("BBTrace: 8 steps, clk 1..=8\n"));
+        assert_eq!(
+            UniformTrace::default().to_string(),
+            "UniformTrace (k = 0): 0 steps\n"
+        );
+    }
+
+    #[test]
+    fn test_compressed_trace_footprint() {
+        let iterations = 1 << 20;
+        let (compressed, uncompressed) = compress_while_tracing(&counting_loop(iterations));
+        // Two instructions to set up the loop, then two per iteration.
+        assert_eq!(compressed.get_num_steps(), 2 * iterations as usize + 2);
+
+        let footprint = compressed.memory_footprint();
+        assert!(
+            uncompressed >= 5 * footprint,
+            "trace takes {uncompressed} bytes, compressed trace {footprint} bytes"
+        );
+
+        // The subtraces continue each other.
+        let mut timestamp = 0;
+        for subtrace in compressed.split_by(1 << 16) {
+            for step in subtrace.get_blocks_iter().flat_map(|block| &block.steps) {
+                assert_eq!(step.timestamp, timestamp + 1);
+                timestamp = step.timestamp;
+            }
+        }
+        assert_eq!(timestamp as usize, compressed.get_num_steps());
+    }
+
+    fn json_len(value: &impl Serialize) -> usize {
+        serde_json::to_vec(value).unwrap().len()
+    }
+
+    #[test]
+    fn test_compaction_schemas() {
+        // About 3k steps, in a loop of three instructions storing a different value every iteration.
+        let blocks = crate::riscv::builder::ProgramBuilder::new()
+            .li(1, 1000)
+            .li(2, 0x10000)
+            .label("loop")
+            .sw(2, 1, 0)
+            .addi(1, 1, -1)
+            .bne(1, 0, "loop")
+            .build()
+            .unwrap();
+        let (_, trace) = k_trace_direct(&blocks, 1).unwrap();
+        let full_size = json_len(&trace);
+        let original_steps: Vec<Step> = trace
+            .get_blocks_iter()
+            .flat_map(|block| block.steps.clone())
+            .collect();
+
+        let analytics = trace.compact(CompactionSchema::ANALYTICS);
+        assert!(!analytics.is_provable());
+        assert!(analytics.steps().is_none());
+        assert!(analytics.to_uniform().is_none());
+        assert_eq!(analytics.get_num_steps(), trace.get_num_steps());
+        let pcs: Vec<u32> = original_steps.iter().map(|step| step.pc).collect();
+        assert_eq!(analytics.pcs(), Some(pcs));
+        assert!(json_len(&analytics) * 5 < full_size);
+
+        let replay = trace.compact(CompactionSchema::REPLAY);
+        assert!(!replay.is_provable());
+        assert!(replay.to_uniform().is_none());
+        assert!(json_len(&replay) * 2 < full_size);
+        let deserialized: CompressedTrace =
+            serde_json::from_str(&serde_json::to_string(&replay).unwrap()).unwrap();
+        assert_eq!(deserialized.steps().unwrap(), original_steps);
+
+        // Blocks of several steps keep their registers and lengths.
+        let (_, trace) = k_trace_direct(&blocks, 4).unwrap();
+        let full = trace.compact(CompactionSchema::FULL);
+        assert!(full.is_provable());
+        let expanded = full.to_uniform().unwrap();
+        assert_eq!(expanded.k, trace.k);
+        assert_eq!(expanded.blocks, trace.blocks);
+    }
 }
@@ -0,0 +1,18 @@
+# Minimal guest carrying the ecall ABI version symbol the way the runtime embeds it, used by the ELF loader
+# tests. `ecall_abi_v1.elf` and `ecall_abi_v2.elf` are built with VERSION set to 1 and 2:
+#
+#   llvm-mc -triple=riscv32 -mattr=+m -filetype=obj --defsym VERSION=1 ecall_abi.s -o ecall_abi.o
+#   ld.lld -T ../../runtime/linker-scripts/default.x ecall_abi.o -o ecall_abi_v1.elf
+
+    .section .init, "ax"
+    .globl _start
+_start:
+    li a0, 0
+    li a7, 0x201
+    ecall
+
+    .section .rodata.nexus_ecall_abi, "a"
+    .globl __nexus_ecall_abi_version
+    .p2align 2
+__nexus_ecall_abi_version:
+    .word VERSION